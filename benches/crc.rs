@@ -65,6 +65,13 @@ fn bench_crc(c: &mut Criterion) {
         BatchSize::SmallInput
     ));
 
+    let mut xs = xorshift64(42).map(|x| x as u8);
+    group.bench_function("slice8_table_crc", |b| b.iter_batched_ref(
+        || (&mut xs).take(SIZE).collect::<Vec<u8>>(),
+        |data| crc::slice8_table_crc(data),
+        BatchSize::SmallInput
+    ));
+
     let mut xs = xorshift64(42).map(|x| x as u8);
     group.bench_function("barret_crc", |b| b.iter_batched_ref(
         || (&mut xs).take(SIZE).collect::<Vec<u8>>(),
@@ -58,7 +58,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes(SIZE as u64));
     group.bench_function("raid5_update", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT).unwrap()),
             (&mut xs).take(SIZE).collect::<Vec<u8>>(),
         )},
         |(i, data)| {
@@ -73,7 +73,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((1*SIZE) as u64));
     group.bench_function("raid5_repair", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+1).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT+1).unwrap()),
             iter::repeat_with(|| {
                 (&mut xs).take(SIZE).collect::<Vec<u8>>()
             })
@@ -111,11 +111,11 @@ fn bench_raid(c: &mut Criterion) {
         .collect::<Vec<_>>();
     let mut p = (&mut xs).take(SIZE).collect::<Vec<u8>>();
     let mut q = (&mut xs).take(SIZE).collect::<Vec<u8>>();
-    raid::raid6_format(&mut disks, &mut p, &mut q);
+    raid::raid6_format(&disks, &mut p, &mut q);
     group.throughput(Throughput::Bytes(SIZE as u64));
     group.bench_function("raid6_update", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT).unwrap()),
             (&mut xs).take(SIZE).collect::<Vec<u8>>(),
         )},
         |(i, data)| {
@@ -130,7 +130,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((1*SIZE) as u64));
     group.bench_function("raid6_repair_1", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+2).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT+2).unwrap()),
             iter::repeat_with(|| {
                     (&mut xs).take(SIZE).collect::<Vec<u8>>()
                 })
@@ -148,7 +148,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((2*SIZE) as u64));
     group.bench_function("raid6_repair_2", |b| b.iter_batched_ref(
         || {
-            let i = usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+2).unwrap());
+            let i = usize::from(xs.next().unwrap() % u8::try_from(COUNT+2).unwrap());
             (
                 i,
                 (i+1) % (COUNT+2),
@@ -193,11 +193,11 @@ fn bench_raid(c: &mut Criterion) {
     let mut p = (&mut xs).take(SIZE).collect::<Vec<u8>>();
     let mut q = (&mut xs).take(SIZE).collect::<Vec<u8>>();
     let mut r = (&mut xs).take(SIZE).collect::<Vec<u8>>();
-    raid::raid6_format(&mut disks, &mut p, &mut q);
+    raid::raid6_format(&disks, &mut p, &mut q);
     group.throughput(Throughput::Bytes(SIZE as u64));
     group.bench_function("raid7_update", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT).unwrap()),
             (&mut xs).take(SIZE).collect::<Vec<u8>>(),
         )},
         |(i, data)| {
@@ -212,7 +212,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((1*SIZE) as u64));
     group.bench_function("raid7_repair_1", |b| b.iter_batched_ref(
         || {(
-            usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+3).unwrap()),
+            usize::from(xs.next().unwrap() % u8::try_from(COUNT+3).unwrap()),
             iter::repeat_with(|| {
                     (&mut xs).take(SIZE).collect::<Vec<u8>>()
                 })
@@ -231,7 +231,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((2*SIZE) as u64));
     group.bench_function("raid7_repair_2", |b| b.iter_batched_ref(
         || {
-            let i = usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+3).unwrap());
+            let i = usize::from(xs.next().unwrap() % u8::try_from(COUNT+3).unwrap());
             (
                 i,
                 (i+1) % (COUNT+2),
@@ -254,7 +254,7 @@ fn bench_raid(c: &mut Criterion) {
     group.throughput(Throughput::Bytes((2*SIZE) as u64));
     group.bench_function("raid7_repair_3", |b| b.iter_batched_ref(
         || {
-            let i = usize::from((&mut xs).next().unwrap() % u8::try_from(COUNT+3).unwrap());
+            let i = usize::from(xs.next().unwrap() % u8::try_from(COUNT+3).unwrap());
             (
                 i,
                 (i+1) % (COUNT+3),
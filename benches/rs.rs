@@ -52,6 +52,46 @@ fn bench_rs(c: &mut Criterion) {
         BatchSize::SmallInput
     ));
 
+    // compare the library's free encode with its reusable Encoder, these
+    // should use the same split tables for GF(256)
+    #[cfg(all(feature="rs", feature="alloc"))]
+    {
+        use gf256::rs::rs255w223;
+
+        let mut xs = xorshift64(42);
+        group.bench_function("rs255w223_encode", |b| b.iter_batched_ref(
+            || (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>(),
+            |data| {
+                data.chunks(rs255w223::DATA_SIZE)
+                    .map(|chunk| {
+                        let mut chunk = Vec::from(chunk);
+                        chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                        rs255w223::encode(&mut chunk);
+                        chunk
+                    })
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::SmallInput
+        ));
+
+        let mut xs = xorshift64(42);
+        let encoder = rs255w223::Encoder::new();
+        group.bench_function("rs255w223_encoder_encode", |b| b.iter_batched_ref(
+            || (&mut xs).take(SIZE).map(|x| x as u8).collect::<Vec<u8>>(),
+            |data| {
+                data.chunks(rs255w223::DATA_SIZE)
+                    .map(|chunk| {
+                        let mut chunk = Vec::from(chunk);
+                        chunk.resize(chunk.len() + rs255w223::ECC_SIZE, 0);
+                        encoder.encode(&mut chunk);
+                        chunk
+                    })
+                    .collect::<Vec<_>>()
+            },
+            BatchSize::SmallInput
+        ));
+    }
+
     // correct w/ no errors
     let mut xs = xorshift64(42);
     group.bench_function("rs_correct_none", |b| b.iter_batched_ref(
@@ -102,7 +142,7 @@ fn bench_rs(c: &mut Criterion) {
         },
         |data| {
             for (chunk, erasures) in data.iter_mut() {
-                rs::rs_correct_erasures(chunk, &erasures).unwrap();
+                rs::rs_correct_erasures(chunk, erasures).unwrap();
             }
         },
         BatchSize::SmallInput
@@ -152,7 +192,7 @@ fn bench_rs(c: &mut Criterion) {
                     rs::rs_encode(&mut chunk);
                     let chunk_len = chunk.len();
 
-                    let erasure_count = ((&mut xs).next().unwrap() as usize)
+                    let erasure_count = (xs.next().unwrap() as usize)
                         % rs::ECC_SIZE;
 
                     let mut erasures = HashSet::new();
@@ -179,7 +219,7 @@ fn bench_rs(c: &mut Criterion) {
         },
         |data| {
             for (chunk, erasures) in data.iter_mut() {
-                rs::rs_correct(chunk, &erasures).unwrap();
+                rs::rs_correct(chunk, erasures).unwrap();
             }
         },
         BatchSize::SmallInput
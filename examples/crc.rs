@@ -42,9 +42,9 @@ pub fn naive_crc(data: &[u8]) -> u32 {
     for b in
         data.iter().copied()
             // pad with 32-bits
-            .chain(iter::repeat(0x00).take(4))
+            .chain(iter::repeat_n(0x00, 4))
             // invert the first 32-bits
-            .zip(iter::repeat(0xff).take(4).chain(iter::repeat(0x00)))
+            .zip(iter::repeat_n(0xff, 4).chain(iter::repeat(0x00)))
             .map(|(m, b)| m ^ b)
     {
         crc = (crc << 8) | p64::from(b.reverse_bits());
@@ -150,6 +150,55 @@ pub fn small_table_crc(data: &[u8]) -> u32 {
     crc ^ 0xffffffff
 }
 
+/// A table-based CRC implementation using 8 tables of precomputed
+/// remainders, operating on 8 bytes at a time
+///
+/// This is commonly called "slice-by-8". The k-th table contains the
+/// remainder of each byte followed by k bytes of zeros, so each byte can be
+/// looked up independently and the results xored together. This breaks the
+/// dependency between lookups, at the cost of a 8*4*256 = 8192 byte table.
+///
+pub fn slice8_table_crc(data: &[u8]) -> u32 {
+    const CRC_TABLE: [[u32; 256]; 8] = {
+        let mut table = [[0; 256]; 8];
+        let mut i = 0;
+        while i < 256 {
+            let mut x = (i as u32).reverse_bits();
+            let mut k = 0;
+            while k < 8 {
+                x = p64((x as u64) << 8).naive_rem(POLYNOMIAL).0 as u32;
+                table[k][i] = x.reverse_bits();
+                k += 1;
+            }
+            i += 1;
+        }
+        table
+    };
+
+    let mut crc = 0xffffffff;
+
+    // iterate over 8-byte slices
+    let mut slices = data.chunks_exact(8);
+    for slice in &mut slices {
+        let lo = u32::from_le_bytes(<[u8; 4]>::try_from(&slice[..4]).unwrap()) ^ crc;
+        let hi = u32::from_le_bytes(<[u8; 4]>::try_from(&slice[4..]).unwrap());
+        crc = CRC_TABLE[7][usize::from(lo as u8)]
+            ^ CRC_TABLE[6][usize::from((lo >>  8) as u8)]
+            ^ CRC_TABLE[5][usize::from((lo >> 16) as u8)]
+            ^ CRC_TABLE[4][usize::from((lo >> 24) as u8)]
+            ^ CRC_TABLE[3][usize::from(hi as u8)]
+            ^ CRC_TABLE[2][usize::from((hi >>  8) as u8)]
+            ^ CRC_TABLE[1][usize::from((hi >> 16) as u8)]
+            ^ CRC_TABLE[0][usize::from((hi >> 24) as u8)];
+    }
+
+    for b in slices.remainder() {
+        crc = (crc >> 8) ^ CRC_TABLE[0][usize::from((crc as u8) ^ b)];
+    }
+
+    crc ^ 0xffffffff
+}
+
 /// A hardware-accelerated CRC implementation using Barret reduction
 ///
 /// This leverages polynomial multiplication instructions (pclmulqdq,
@@ -365,6 +414,10 @@ fn main() {
     println!("{:<24} => 0x{:08x}", "small_table_crc", output);
     assert_eq!(output, expected);
 
+    let output = slice8_table_crc(input);
+    println!("{:<24} => 0x{:08x}", "slice8_table_crc", output);
+    assert_eq!(output, expected);
+
     let output = barret_crc(input);
     println!("{:<24} => 0x{:08x}", "barret_crc", output);
     assert_eq!(output, expected);
@@ -61,15 +61,9 @@ pub fn is_irreducible(p: p128) -> Option<p128> {
     // test division of all polynomials < sqrt(p), or a simpler
     // heuristic of < 2^(log2(p)/2)
     let npw2 = 128 - (u128::from(p)-1).leading_zeros();
-    let roughsqrt = 1u128 << ((npw2+1)/2);
+    let roughsqrt = 1u128 << npw2.div_ceil(2);
 
-    for x in (3..roughsqrt).step_by(2).map(p128) {
-        if p % x == p128(0) {
-            return Some(x);
-        }
-    }
-
-    None
+    (3..roughsqrt).step_by(2).map(p128).find(|&x| p % x == p128(0))
 }
 
 /// Find all irreducible polynomials of a given bit-width
@@ -143,7 +137,7 @@ pub fn is_generator(g: p128, p: p128) -> bool {
         let mut prime = 2;
         iter::from_fn(move || {
             while prime <= x {
-                if x % prime == 0 {
+                if x.is_multiple_of(prime) {
                     x /= prime;
                     return Some(prime);
                 }
@@ -816,7 +816,7 @@ fn main() {
     }
 
     fn grid<'a>(width: usize, bs: &'a [u8]) -> impl Iterator<Item=String> + 'a {
-        (0 .. (bs.len()+width-1)/width)
+        (0 .. bs.len().div_ceil(width))
             .step_by(2)
             .rev()
             .map(move |y| {
@@ -1135,7 +1135,7 @@ fn main() {
     }
     for x in 0..WIDTH {
         let v: u32 = (0..HEIGHT).map(|y| u32::from(buffer[x+y*WIDTH])).sum();
-        let v = (4*v+x_max-1) / x_max;
+        let v = (4*v).div_ceil(x_max);
         for i in 0..usize::try_from(v).unwrap() {
             x_dist[x+i*WIDTH] = 1;
         }
@@ -1148,7 +1148,7 @@ fn main() {
     }
     for y in 0..HEIGHT {
         let v: u32 = (0..WIDTH).map(|x| u32::from(buffer[x+y*WIDTH])).sum();
-        let v = (4*v+y_max-1) / y_max;
+        let v = (4*v).div_ceil(y_max);
         for i in 0..usize::try_from(v).unwrap() {
             y_dist[(3-i)+y*4] = 1;
         }
@@ -1169,7 +1169,7 @@ fn main() {
     let zeros: u32 = samples.iter().map(|x| x.count_zeros()).sum();
     let mut comp = DeflateEncoder::new(Vec::new(), Compression::best());
     let bytes = unsafe { slice::from_raw_parts(samples.as_ptr() as *const u8, 8*samples.len()) };
-    comp.write_all(&bytes).unwrap();
+    comp.write_all(bytes).unwrap();
     let comp = comp.finish().unwrap();
     println!("{}/{} ones ({:.2}%), {:.2}% compressability",
         ones,
@@ -17,7 +17,6 @@
 
 use std::convert::TryFrom;
 use std::fmt;
-use rand;
 use ::gf256::*;
 use ::gf256::crc::crc32c;
 
@@ -40,7 +39,7 @@ impl fmt::Display for RaidError {
 }
 
 
-//// RAID5 ////
+// RAID5 //
 
 /// Format blocks with RAID5, aka single block of parity
 pub fn raid5_format<B: AsRef<[u8]>>(blocks: &[B], p: &mut [u8]) {
@@ -78,9 +77,7 @@ pub fn raid5_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = d.as_mut();
 
-        for i in 0..len {
-            d[i] = p[i];
-        }
+        d[..len].copy_from_slice(&p[..len]);
 
         for b in before.iter_mut().chain(after.iter_mut()) {
             for i in 0..len {
@@ -145,7 +142,7 @@ pub fn raid5_update(_j: usize, old: &[u8], new: &[u8], p: &mut [u8]) {
 }
 
 
-//// RAID6 ////
+// RAID6 //
 
 /// Format blocks with RAID6, aka two blocks of parity
 pub fn raid6_format<B: AsRef<[u8]>>(blocks: &[B], p: &mut [u8], q: &mut [u8]) {
@@ -189,7 +186,7 @@ pub fn raid6_repair<B: AsMut<[u8]>>(
     // sort the data blocks without alloc, this is only so we can split
     // the mut blocks array safely
     let mut bad_blocks_array = [
-        bad_blocks.get(0).copied().unwrap_or(0),
+        bad_blocks.first().copied().unwrap_or(0),
         bad_blocks.get(1).copied().unwrap_or(0),
     ];
     let bad_blocks = &mut bad_blocks_array[..bad_blocks.len()];
@@ -203,9 +200,7 @@ pub fn raid6_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = gf256::slice_from_slice_mut(d.as_mut());
 
-        for i in 0..len {
-            d[i] = p[i];
-        }
+        d[..len].copy_from_slice(&p[..len]);
 
         for b in before.iter_mut().chain(after.iter_mut()) {
             for i in 0..len {
@@ -220,9 +215,7 @@ pub fn raid6_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = gf256::slice_from_slice_mut(d.as_mut());
 
-        for i in 0..len {
-            d[i] = q[i];
-        }
+        d[..len].copy_from_slice(&q[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(after.iter_mut()))
@@ -256,10 +249,8 @@ pub fn raid6_repair<B: AsMut<[u8]>>(
         // q - Σ di*g^i
         //   i!=x,y
         //
-        for i in 0..len {
-            dx[i] = p[i];
-            dy[i] = q[i];
-        }
+        dx[..len].copy_from_slice(&p[..len]);
+        dy[..len].copy_from_slice(&q[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(between.iter_mut()))
@@ -292,7 +283,7 @@ pub fn raid6_repair<B: AsMut<[u8]>>(
         }
     }
 
-    if bad_blocks.iter().any(|x| *x == blocks.len()) {
+    if bad_blocks.contains(&blocks.len()) {
         // regenerate p
         for i in 0..len {
             p[i] = gf256(0);
@@ -376,7 +367,7 @@ pub fn raid6_update(j: usize, old: &[u8], new: &[u8], p: &mut [u8], q: &mut [u8]
 }
 
 
-//// RAID7 ////
+// RAID7 //
 
 /// Format blocks with RAID7, aka three blocks of parity
 pub fn raid7_format<B: AsRef<[u8]>>(blocks: &[B], p: &mut [u8], q: &mut [u8], r: &mut [u8]) {
@@ -427,7 +418,7 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
     // sort the data blocks without alloc, this is only so we can split
     // the mut blocks array safely
     let mut bad_blocks_array = [
-        bad_blocks.get(0).copied().unwrap_or(0),
+        bad_blocks.first().copied().unwrap_or(0),
         bad_blocks.get(1).copied().unwrap_or(0),
         bad_blocks.get(2).copied().unwrap_or(0),
     ];
@@ -442,9 +433,7 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = gf256::slice_from_slice_mut(d.as_mut());
 
-        for i in 0..len {
-            d[i] = p[i];
-        }
+        d[..len].copy_from_slice(&p[..len]);
 
         for b in before.iter_mut().chain(after.iter_mut()) {
             for i in 0..len {
@@ -459,9 +448,7 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = gf256::slice_from_slice_mut(d.as_mut());
 
-        for i in 0..len {
-            d[i] = q[i];
-        }
+        d[..len].copy_from_slice(&q[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(after.iter_mut()))
@@ -484,9 +471,7 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         let (d, after) = after.split_first_mut().unwrap();
         let d = gf256::slice_from_slice_mut(d.as_mut());
 
-        for i in 0..len {
-            d[i] = r[i];
-        }
+        d[..len].copy_from_slice(&r[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(after.iter_mut()))
@@ -522,10 +507,8 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         // q - Σ di*g^i
         //   i!=x,y
         //
-        for i in 0..len {
-            dx[i] = p[i];
-            dy[i] = q[i];
-        }
+        dx[..len].copy_from_slice(&p[..len]);
+        dy[..len].copy_from_slice(&q[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(between.iter_mut()))
@@ -575,10 +558,8 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         // r - Σ di*h^i
         //   i!=x,y
         //
-        for i in 0..len {
-            dx[i] = q[i];
-            dy[i] = r[i];
-        }
+        dx[..len].copy_from_slice(&q[..len]);
+        dy[..len].copy_from_slice(&r[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(between.iter_mut()))
@@ -631,10 +612,8 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         // r - Σ di*h^i
         //   i!=x,y
         //
-        for i in 0..len {
-            dx[i] = p[i];
-            dy[i] = r[i];
-        }
+        dx[..len].copy_from_slice(&p[..len]);
+        dy[..len].copy_from_slice(&r[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(between.iter_mut()))
@@ -691,11 +670,9 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         // r - Σ di*h^i
         //  i!=x,y,z
         //
-        for i in 0..len {
-            dx[i] = p[i];
-            dy[i] = q[i];
-            dz[i] = r[i];
-        }
+        dx[..len].copy_from_slice(&p[..len]);
+        dy[..len].copy_from_slice(&q[..len]);
+        dz[..len].copy_from_slice(&r[..len]);
 
         for (j, b) in before.iter_mut().enumerate()
             .chain((bad_blocks[0]+1..).zip(between.iter_mut()))
@@ -739,7 +716,7 @@ pub fn raid7_repair<B: AsMut<[u8]>>(
         }
     }
 
-    if bad_blocks.iter().any(|x| *x == blocks.len()) {
+    if bad_blocks.contains(&blocks.len()) {
         // regenerate p
         for i in 0..len {
             p[i] = gf256(0);
@@ -898,7 +875,7 @@ pub fn main() {
 
     let old = blocks[2][3];
     blocks[2][3] = b'!';
-    raid5_update(2, &[old], &[b'!'], &mut parity[3..4]);
+    raid5_update(2, &[old], b"!", &mut parity[3..4]);
     println!("{:<7} => {}  {}",
         "update",
         blocks.iter()
@@ -986,7 +963,7 @@ pub fn main() {
 
     let old = blocks[2][3];
     blocks[2][3] = b'!';
-    raid6_update(2, &[old], &[b'!'], &mut parity1[3..4], &mut parity2[3..4]);
+    raid6_update(2, &[old], b"!", &mut parity1[3..4], &mut parity2[3..4]);
     println!("{:<7} => {}  {}",
         "update",
         blocks.iter()
@@ -1121,7 +1098,7 @@ pub fn main() {
 
     let old = blocks[2][3];
     blocks[2][3] = b'!';
-    raid7_update(2, &[old], &[b'!'], &mut parity1[3..4], &mut parity2[3..4], &mut parity3[3..4]);
+    raid7_update(2, &[old], b"!", &mut parity1[3..4], &mut parity2[3..4], &mut parity3[3..4]);
     println!("{:<7} => {}  {}",
         "update",
         blocks.iter()
@@ -1328,10 +1305,9 @@ pub fn main() {
     let mut blocks = (0..image.len()/block)
         .map(|i| {
             let mut data = (0 .. image.len()/width)
-                .map(|j| {
+                .flat_map(|j| {
                     image[i*columns+j*width..i*columns+j*width+columns].iter().copied()
                 })
-                .flatten()
                 .collect::<Vec<u8>>();
 
             // make space for CRCs, this is one option for determining block failures
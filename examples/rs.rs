@@ -20,7 +20,6 @@
 
 use std::convert::TryFrom;
 use std::fmt;
-use rand;
 use rand::Rng;
 use ::gf256::*;
 
@@ -339,7 +338,7 @@ fn rs_find_error_locations(codeword: &[gf256], Λ: &[gf256]) -> Vec<usize> {
     let mut error_locations = vec![];
     for j in 0..codeword.len() {
         let Xj = gf256::GENERATOR.pow(u8::try_from(codeword.len()-1-j).unwrap());
-        let zero = rs_poly_eval(&Λ, Xj.recip());
+        let zero = rs_poly_eval(Λ, Xj.recip());
         if zero == gf256(0) {
             // found an error location!
             error_locations.push(j);
@@ -391,9 +390,9 @@ fn rs_find_error_magnitudes(
     //
     let mut Ω = vec![gf256(0); S.len()+Λ.len()-1];
     let Ω_len = Ω.len();
-    Ω[Ω_len-S.len()..].copy_from_slice(&S);
+    Ω[Ω_len-S.len()..].copy_from_slice(S);
     Ω[Ω_len-S.len()..].reverse();
-    rs_poly_mul(&mut Ω, &Λ);
+    rs_poly_mul(&mut Ω, Λ);
     Ω.drain(..Ω.len()-S.len());
 
     // find the formal derivative of Λ
@@ -464,14 +463,14 @@ pub fn rs_correct_erasures(
     }
 
     // find erasure locator polynomial
-    let Λ = rs_find_erasure_locator(codeword, &erasures);
+    let Λ = rs_find_erasure_locator(codeword, erasures);
 
     // find erasure magnitudes using Forney's algorithm
     let erasure_magnitudes = rs_find_error_magnitudes(
         codeword,
         &S,
         &Λ,
-        &erasures,
+        erasures,
     );
 
     // correct the errors
@@ -554,7 +553,7 @@ pub fn rs_correct(
     }
 
     // find Forney syndromes, hiding known erasures from the syndromes
-    let forney_S = rs_find_forney_syndromes(codeword, &S, &erasures);
+    let forney_S = rs_find_forney_syndromes(codeword, &S, erasures);
 
     // find error locator polynomial
     let Λ = rs_find_error_locator(&forney_S);
@@ -568,7 +567,7 @@ pub fn rs_correct(
 
     // find all error locations
     let mut error_locations = rs_find_error_locations(codeword, &Λ);
-    error_locations.extend_from_slice(&erasures);
+    error_locations.extend_from_slice(erasures);
 
     // re-find error locator polynomial, this time including both 
     // errors and erasures
@@ -955,7 +954,7 @@ fn main() {
         }
 
         for i in 0 .. encoded_size/(block+ECC_SIZE) {
-            let mut slice = unstripe(&encoded, i);
+            let mut slice = unstripe(encoded, i);
             let slice_erasures = unstripe(&encoded_erasures, i);
             let erasures = slice_erasures.iter()
                 .enumerate()
@@ -9,7 +9,6 @@
 //!
 //! [shamir-mod]: https://docs.rs/gf256/latest/gf256/shamir
 
-use rand;
 use rand::Rng;
 use std::convert::TryFrom;
 use ::gf256::gf;
@@ -99,7 +98,7 @@ pub fn shamir_reconstruct<S: AsRef<[u8]>>(shares: &[S]) -> Vec<u8> {
     );
 
     let mut secret = vec![];
-    let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
+    let len = shares.first().map(|s| s.as_ref().len()).unwrap_or(0);
     if len == 0 {
         return secret;
     }
@@ -140,30 +139,30 @@ fn main() {
     println!("testing shamir({:?})", String::from_utf8_lossy(input));
 
     let shares = shamir_generate(input, 5, 4);
-    println!("{} => {}  {}", "generate share1", ascii(&shares[0]), hex(&shares[0]));
-    println!("{} => {}  {}", "generate share2", ascii(&shares[1]), hex(&shares[1]));
-    println!("{} => {}  {}", "generate share3", ascii(&shares[2]), hex(&shares[2]));
-    println!("{} => {}  {}", "generate share4", ascii(&shares[3]), hex(&shares[3]));
-    println!("{} => {}  {}", "generate share5", ascii(&shares[4]), hex(&shares[4]));
+    println!("generate share1 => {}  {}", ascii(&shares[0]), hex(&shares[0]));
+    println!("generate share2 => {}  {}", ascii(&shares[1]), hex(&shares[1]));
+    println!("generate share3 => {}  {}", ascii(&shares[2]), hex(&shares[2]));
+    println!("generate share4 => {}  {}", ascii(&shares[3]), hex(&shares[3]));
+    println!("generate share5 => {}  {}", ascii(&shares[4]), hex(&shares[4]));
 
     let output = shamir_reconstruct(&shares[..1]);
-    println!("{} => {}  {}", "reconstruct 1 shares", ascii(&output), hex(&output));
+    println!("reconstruct 1 shares => {}  {}", ascii(&output), hex(&output));
     assert_ne!(output, input);
 
     let output = shamir_reconstruct(&shares[..2]);
-    println!("{} => {}  {}", "reconstruct 2 shares", ascii(&output), hex(&output));
+    println!("reconstruct 2 shares => {}  {}", ascii(&output), hex(&output));
     assert_ne!(output, input);
 
     let output = shamir_reconstruct(&shares[..3]);
-    println!("{} => {}  {}", "reconstruct 3 shares", ascii(&output), hex(&output));
+    println!("reconstruct 3 shares => {}  {}", ascii(&output), hex(&output));
     assert_ne!(output, input);
 
     let output = shamir_reconstruct(&shares[..4]);
-    println!("{} => {}  {}", "reconstruct 4 shares", ascii(&output), hex(&output));
+    println!("reconstruct 4 shares => {}  {}", ascii(&output), hex(&output));
     assert_eq!(output, input);
 
     let output = shamir_reconstruct(&shares[..5]);
-    println!("{} => {}  {}", "reconstruct 5 shares", ascii(&output), hex(&output));
+    println!("reconstruct 5 shares => {}  {}", ascii(&output), hex(&output));
     assert_eq!(output, input);
 
     println!();
@@ -0,0 +1,216 @@
+//! BCH error-correction macro
+
+extern crate proc_macro;
+
+use darling;
+use darling::FromMeta;
+use syn;
+use syn::parse_macro_input;
+use proc_macro2::*;
+use std::collections::HashMap;
+use quote::quote;
+use std::iter::FromIterator;
+use std::convert::TryFrom;
+use crate::common::*;
+
+// template files are relative to the current file
+const BCH_TEMPLATE: &'static str = include_str!("../templates/bch.rs");
+
+// default primitive polynomials for each m, these are the same defaults
+// used by Linux's BCH library
+const DEFAULT_POLYNOMIALS: [u32; 17] = [
+    0, 0, 0x7, 0xb, 0x13, 0x25, 0x43, 0x83,
+    0x11d, 0x211, 0x409, 0x805, 0x1053, 0x201b, 0x402b, 0x8003,
+    0x1100b,
+];
+
+
+#[derive(Debug, FromMeta)]
+struct BchArgs {
+    m: usize,
+    t: usize,
+
+    #[darling(default)]
+    polynomial: Option<U128Wrapper>,
+}
+
+/// Find the generator polynomial of a binary BCH code, the least common
+/// multiple of the minimal polynomials of g^1..g^2t, as a list of
+/// coefficients, biggest-coefficient first
+fn generator_poly(m: usize, t: usize, polynomial: u32) -> Vec<bool> {
+    let n = (1usize << m) - 1;
+
+    // build exp table for GF(2^m)
+    let mut exp = vec![0u32; n];
+    let mut x = 1u32;
+    for i in 0..n {
+        exp[i] = x;
+        x <<= 1;
+        if x >> m != 0 {
+            x ^= polynomial;
+        }
+        assert!(x != 1 || i == n-1,
+            "bch polynomial must be primitive, {:#x} is not", polynomial);
+    }
+
+    let mul = |a: u32, b: u32| -> u32 {
+        let mut x = 0;
+        for i in 0..m {
+            if (b >> i) & 1 != 0 {
+                x ^= a << i;
+            }
+        }
+        for i in (m..2*m).rev() {
+            if (x >> i) & 1 != 0 {
+                x ^= polynomial << (i-m);
+            }
+        }
+        x
+    };
+
+    // multiply together the minimal polynomials of each cyclotomic coset,
+    // g(x) starts as 1, coefficients are in GF(2^m) here, smallest first
+    let mut g = vec![1u32];
+    let mut seen = vec![false; n];
+    for i in 1..=2*t {
+        let i = i % n;
+        if seen[i] {
+            continue;
+        }
+
+        // the cyclotomic coset of i, {i, 2i, 4i, ...}
+        let mut j = i;
+        loop {
+            seen[j] = true;
+
+            // g(x) = g(x)*(x - g^j)
+            let root = exp[j];
+            let mut product = vec![0u32; g.len()+1];
+            for (k, c) in g.iter().enumerate() {
+                product[k+1] ^= *c;
+                product[k] ^= mul(*c, root);
+            }
+            g = product;
+
+            j = (2*j) % n;
+            if j == i {
+                break;
+            }
+        }
+    }
+
+    // coefficients of minimal polynomials are all in GF(2)
+    assert!(g.iter().all(|c| *c <= 1));
+    g.iter().rev().map(|c| *c == 1).collect()
+}
+
+pub fn bch(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> proc_macro::TokenStream {
+    let __crate = crate_path();
+
+    // parse args
+    let raw_args = parse_macro_input!(args as AttributeArgsWrapper).0;
+    let args = match BchArgs::from_list(&raw_args) {
+        Ok(args) => args,
+        Err(err) => {
+            return err.write_errors().into();
+        }
+    };
+
+    // we need m <= 16 to keep our codeword lengths, and tables, reasonable
+    assert!(args.m >= 3 && args.m <= 16, "bch requires 3 <= m <= 16");
+    assert!(args.t >= 1, "bch requires t >= 1");
+
+    let polynomial = match args.polynomial {
+        Some(polynomial) => {
+            assert!(polynomial.0 >> args.m == 1,
+                "bch polynomial must have degree m");
+            u32::try_from(polynomial.0).unwrap()
+        }
+        None => DEFAULT_POLYNOMIALS[args.m],
+    };
+
+    let g = generator_poly(args.m, args.t, polynomial);
+    let ecc_bits = g.len() - 1;
+    assert!(ecc_bits < (1 << args.m) - 1,
+        "bch t={} is too large for m={}, no data bits remaining", args.t, args.m);
+
+    // pack the generator polynomial into big-endian bytes
+    let mut g_bytes = vec![0u8; (g.len()+7)/8];
+    let g_bytes_len = g_bytes.len();
+    for (i, c) in g.iter().rev().enumerate() {
+        if *c {
+            g_bytes[g_bytes_len-1-i/8] |= 1 << (i%8);
+        }
+    }
+    let g_bytes = g_bytes.iter().map(|b| Literal::u8_unsuffixed(*b));
+
+    // parse type
+    let ty = parse_macro_input!(input as syn::ItemMod);
+    let attrs = ty.attrs;
+    let vis = ty.vis;
+    let bch = ty.ident;
+
+    let __gf = Ident::new(&format!("__{}_gf", bch.to_string()), Span::call_site());
+    let __u  = Ident::new(&format!("__{}_u",  bch.to_string()), Span::call_site());
+    let u = Ident::new(if args.m <= 8 { "u8" } else { "u16" }, Span::call_site());
+    let polynomial_lit = Literal::u32_unsuffixed(polynomial);
+
+    // overrides in parent's namespace, we need our own GF(2^m) field
+    let overrides = quote! {
+        #[#__crate::gf::gf(polynomial=#polynomial_lit, generator=0x2, u=#u)]
+        type #__gf;
+        use #u as #__u;
+    };
+
+    // keyword replacements
+    let replacements = HashMap::from_iter([
+        ("__bch".to_owned(), TokenTree::Ident(bch.clone())),
+        ("__m".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(args.m)
+        )),
+        ("__t".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(args.t)
+        )),
+        ("__polynomial".to_owned(), TokenTree::Literal(
+            Literal::u32_unsuffixed(polynomial)
+        )),
+        ("__ecc_bits".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(ecc_bits)
+        )),
+        ("__generator_poly".to_owned(), TokenTree::Group(Group::new(Delimiter::Bracket, {
+            quote! { #(#g_bytes),* }
+        }))),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__gf".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            quote! { super::#__gf }
+        }))),
+        ("__u".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            quote! { super::#__u }
+        }))),
+        ("__crate".to_owned(), __crate.clone()),
+    ]);
+
+    // parse template
+    let template = match compile_template(BCH_TEMPLATE, &replacements) {
+        Ok(template) => template,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let output = quote! {
+        #(#attrs)* #vis mod #bch {
+            #template
+        }
+
+        // overrides in parent's namespace
+        #overrides
+    };
+
+    output.into()
+}
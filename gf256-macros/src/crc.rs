@@ -12,12 +12,48 @@ use quote::quote;
 use std::iter::FromIterator;
 use std::convert::TryFrom;
 use std::cmp::max;
+use std::cmp::min;
 use crate::common::*;
 
 // template files are relative to the current file
 const CRC_TEMPLATE: &'static str = include_str!("../templates/crc.rs");
 
 
+// polynomials with dedicated hardware instructions on some architectures
+const CRC32_POLYNOMIAL: u128 = 0x104c11db7;
+const CRC32C_POLYNOMIAL: u128 = 0x11edc6f41;
+
+/// Predicate for when hardware CRC instructions are available, if any
+/// exist for this polynomial
+fn hardware_predicate(polynomial: u128, reflected: bool) -> Option<TokenStream> {
+    // hardware instructions are only defined for bit-reflected CRCs
+    if !reflected {
+        return None;
+    }
+
+    match polynomial {
+        CRC32_POLYNOMIAL => Some(quote! {
+            all(
+                target_arch="aarch64",
+                target_feature="crc"
+            )
+        }),
+        CRC32C_POLYNOMIAL => Some(quote! {
+            any(
+                all(
+                    target_arch="x86_64",
+                    target_feature="sse4.2"
+                ),
+                all(
+                    target_arch="aarch64",
+                    target_feature="crc"
+                )
+            )
+        }),
+        _ => None,
+    }
+}
+
 #[derive(Debug, FromMeta)]
 struct CrcArgs {
     polynomial: U128Wrapper,
@@ -43,7 +79,17 @@ struct CrcArgs {
     #[darling(default)]
     small_table: bool,
     #[darling(default)]
+    slice8: bool,
+    #[darling(default)]
+    slice16: bool,
+    #[darling(default)]
     barret: bool,
+    #[darling(default)]
+    folding: bool,
+    #[darling(default)]
+    hardware: bool,
+    #[darling(default)]
+    hardware_detect: bool,
 }
 
 pub fn crc(
@@ -68,26 +114,84 @@ pub fn crc(
         (128-usize::try_from(polynomial.leading_zeros()).unwrap()) - 1
     };
 
+    // does our polynomial have dedicated hardware instructions?
+    let hardware_predicate = hardware_predicate(
+        args.polynomial.0,
+        args.reflected.unwrap_or(true)
+    );
+
+    // if no mode is explicitly requested and hardware instructions are
+    // available, prefer these over everything else
+    if !args.naive
+        && !args.table
+        && !args.small_table
+        && !args.slice8
+        && !args.slice16
+        && !args.barret
+        && !args.folding
+        && !args.hardware
+    {
+        if let Some(hardware) = hardware_predicate.as_ref() {
+            // otherwise use the same defaults we would normally use, but
+            // with std we can still check for hardware instructions at
+            // runtime
+            let detect = if cfg!(feature="std") && !args.hardware_detect {
+                quote! { hardware_detect, }
+            } else {
+                quote! {}
+            };
+            let xmul = xmul_predicate();
+            let table = if cfg!(feature="no-tables") {
+                quote! { barret }
+            } else if cfg!(feature="small-tables") {
+                quote! { small_table }
+            } else {
+                quote! { table }
+            };
+
+            let input = TokenStream::from(input);
+            let output = quote! {
+                #[cfg_attr(#hardware, #__crate::crc::crc(hardware, #(#raw_args),*))]
+                #[cfg_attr(all(not(#hardware), #xmul),      #__crate::crc::crc(barret, #detect #(#raw_args),*))]
+                #[cfg_attr(all(not(#hardware), not(#xmul)), #__crate::crc::crc(#table, #detect #(#raw_args),*))]
+                #input
+            };
+            return output.into();
+        }
+    }
+
     // decide between implementations
-    let (naive, table, small_table, barret) = match
-        (args.naive, args.table, args.small_table, args.barret)
+    let modes = [
+        args.naive,
+        args.table,
+        args.small_table,
+        args.slice8,
+        args.slice16,
+        args.barret,
+        args.folding,
+        args.hardware,
+    ];
+    let (naive, table, small_table, slice8, slice16, barret, folding, hardware) = match
+        modes.iter().filter(|mode| **mode).count()
     {
         // choose mode if one is explicitly requested
-        (true,  false, false, false) => (true,  false, false, false),
-        (false, true,  false, false) => (false, true,  false, false),
-        (false, false, true,  false) => (false, false, true,  false),
-        (false, false, false, true ) => (false, false, false, true ),
+        1 => (
+            args.naive,
+            args.table,
+            args.small_table,
+            args.slice8,
+            args.slice16,
+            args.barret,
+            args.folding,
+            args.hardware,
+        ),
 
         // if no-tables is enabled, stick to Barret reduction, it beats
         // a naive implementation even without hardware xmul
-        (false, false, false, false)
-            if cfg!(feature="no-tables")
-            => (false, false, false, true),
+        0 if cfg!(feature="no-tables") => (false, false, false, false, false, true, false, false),
 
         // if small-tables is enabled, we can use a smaller 16-element table
-        (false, false, false, false)
-            if cfg!(feature="small-tables")
-            => {
+        0 if cfg!(feature="small-tables") => {
             // if xmul is available, Barret reduction is the fastest option for
             // CRCs, otherwise a table-based approach wins
             let input = TokenStream::from(input);
@@ -100,7 +204,7 @@ pub fn crc(
             return output.into();
         }
 
-        (false, false, false, false) => {
+        0 => {
             // if xmul is available, Barret reduction is the fastest option for
             // CRCs, otherwise a table-based approach wins
             let input = TokenStream::from(input);
@@ -114,15 +218,63 @@ pub fn crc(
         },
 
         // multiple modes selected?
-        _ => panic!("invalid configuration of macro crc (naive, table, small_table, barret?)"),
+        _ => panic!("invalid configuration of macro crc (naive, table, small_table, slice8, slice16, barret, folding, hardware?)"),
     };
 
+    // folding operates on 64-bit halves of 128-bit blocks
+    if folding && width > 64 {
+        panic!("folding mode in macro crc only supports polynomials up to 64 bits");
+    }
+
+    // hardware mode only works for specific polynomials
+    if (hardware || args.hardware_detect) && hardware_predicate.is_none() {
+        panic!("no hardware instructions available for macro crc with polynomial 0x{:x}", args.polynomial.0);
+    }
+
+    // runtime detection requires std
+    if args.hardware_detect && !cfg!(feature="std") {
+        panic!("hardware_detect in macro crc requires feature std");
+    }
+
     // parse type
     let ty = parse_macro_input!(input as syn::ItemFn);
     let attrs = ty.attrs;
     let vis = ty.vis;
     let crc = ty.sig.ident;
 
+    // extra functions generated alongside our CRC function, these are named
+    // with the CRC function's name as a prefix
+    let mut extras = vec![
+        "combine",
+        "rolling_table",
+        "roll",
+        "patch",
+        "bits",
+        "correct",
+        "correct_burst",
+        "vectored",
+        "const",
+        "verify",
+        "byte_table",
+    ];
+    if cfg!(feature="rayon") {
+        extras.push("parallel");
+    }
+    let extras = extras.iter()
+        .map(|suffix| (
+            format!("__crc_{}", suffix),
+            Ident::new(&format!("{}_{}", crc.to_string(), suffix), Span::call_site())
+        ))
+        .collect::<Vec<_>>();
+    let extra_idents = extras.iter()
+        .map(|(_, ident)| ident)
+        .collect::<Vec<_>>();
+
+    // only forward non-doc attributes to our extra functions
+    let extra_attrs = attrs.iter()
+        .filter(|attr| !attr.path.is_ident("doc"))
+        .collect::<Vec<_>>();
+
     let __mod = Ident::new(&format!("__{}_gen", crc.to_string()), Span::call_site());
     let __u   = Ident::new(&format!("__{}_u",   crc.to_string()), Span::call_site());
     let __u2  = Ident::new(&format!("__{}_u2",  crc.to_string()), Span::call_site());
@@ -151,7 +303,9 @@ pub fn crc(
             })
         }
         None => {
-            let u2 = Ident::new(&format!("u{}", 2*max(width.next_power_of_two(), 8)), Span::call_site());
+            // there is no 2x-wide type for CRCs wider than 64 bits, in
+            // this case we fall back to bitwise implementations
+            let u2 = Ident::new(&format!("u{}", min(2*max(width.next_power_of_two(), 8), 128)), Span::call_site());
             overrides.push(quote! {
                 use #u2 as #__u2;
             })
@@ -177,7 +331,7 @@ pub fn crc(
             })
         }
         None => {
-            let p2 = Ident::new(&format!("p{}", 2*max(width.next_power_of_two(), 8)), Span::call_site());
+            let p2 = Ident::new(&format!("p{}", min(2*max(width.next_power_of_two(), 8), 128)), Span::call_site());
             overrides.push(quote! {
                 use #__crate::p::#p2 as #__p2;
             })
@@ -185,7 +339,7 @@ pub fn crc(
     }
 
     // keyword replacements
-    let replacements = HashMap::from_iter([
+    let mut replacements = HashMap::from_iter([
         ("__crc".to_owned(), TokenTree::Ident(crc.clone())),
         ("__polynomial".to_owned(), TokenTree::Literal(
             Literal::u128_unsuffixed(args.polynomial.0)
@@ -226,12 +380,67 @@ pub fn crc(
         ("__small_table".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", small_table), Span::call_site())
         )),
+        ("__slice8".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", slice8), Span::call_site())
+        )),
+        ("__slice16".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", slice16), Span::call_site())
+        )),
         ("__barret".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", barret), Span::call_site())
         )),
+        ("__folding".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", folding), Span::call_site())
+        )),
+        ("__hardware".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", hardware), Span::call_site())
+        )),
+        ("__hardware_predicate".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            match hardware_predicate.as_ref() {
+                Some(hardware_predicate) => hardware_predicate.clone(),
+                None => quote! { any() },
+            }
+        }))),
+        ("__hardware_error".to_owned(), TokenTree::Literal(
+            Literal::string(&format!(
+                "no hardware instructions available for macro crc with polynomial 0x{:x} on this target",
+                args.polynomial.0
+            ))
+        )),
+        ("__hardware_detect".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.hardware_detect), Span::call_site())
+        )),
+        ("__hardware_detect_fn".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            if args.polynomial.0 == CRC32_POLYNOMIAL {
+                quote! { #__crate::internal::hwcrc::crc32_detect }
+            } else {
+                quote! { #__crate::internal::hwcrc::crc32c_detect }
+            }
+        }))),
+        ("__hardware_u8".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            if args.polynomial.0 == CRC32_POLYNOMIAL {
+                quote! { #__crate::internal::hwcrc::crc32_u8 }
+            } else {
+                quote! { #__crate::internal::hwcrc::crc32c_u8 }
+            }
+        }))),
+        ("__hardware_u64".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            if args.polynomial.0 == CRC32_POLYNOMIAL {
+                quote! { #__crate::internal::hwcrc::crc32_u64 }
+            } else {
+                quote! { #__crate::internal::hwcrc::crc32c_u64 }
+            }
+        }))),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate),
     ]);
 
+    for (name, ident) in extras.iter() {
+        replacements.insert(name.clone(), TokenTree::Ident(ident.clone()));
+    }
+
     // parse template
     let template = match compile_template(CRC_TEMPLATE, &replacements) {
         Ok(template) => template,
@@ -240,8 +449,15 @@ pub fn crc(
         }
     };
 
+    let extra_uses = extra_idents.iter()
+        .map(|ident| quote! {
+            #(#extra_attrs)* #vis use #__mod::#ident;
+        })
+        .collect::<Vec<_>>();
+
     let output = quote! {
         #(#attrs)* #vis use #__mod::#crc;
+        #(#extra_uses)*
         mod #__mod {
             #template
         }
@@ -0,0 +1,124 @@
+//! Hamming/SECDED error-correction macro
+
+extern crate proc_macro;
+
+use darling;
+use darling::FromMeta;
+use syn;
+use syn::parse_macro_input;
+use proc_macro2::*;
+use std::collections::HashMap;
+use quote::quote;
+use std::iter::FromIterator;
+use crate::common::*;
+
+// template files are relative to the current file
+const HAMMING_TEMPLATE: &'static str = include_str!("../templates/hamming.rs");
+
+
+#[derive(Debug, FromMeta)]
+struct HammingArgs {
+    m: usize,
+
+    #[darling(default)]
+    extended: bool,
+    #[darling(default)]
+    u: Option<syn::Path>,
+}
+
+pub fn hamming(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> proc_macro::TokenStream {
+    let __crate = crate_path();
+
+    // parse args
+    let raw_args = parse_macro_input!(args as AttributeArgsWrapper).0;
+    let args = match HammingArgs::from_list(&raw_args) {
+        Ok(args) => args,
+        Err(err) => {
+            return err.write_errors().into();
+        }
+    };
+
+    // we need m <= 16 to keep our codeword lengths, and syndromes, reasonable
+    assert!(args.m >= 2 && args.m <= 16, "hamming requires 2 <= m <= 16");
+    let data_bits = (1usize << args.m) - 1 - args.m;
+    let ecc_bits = args.m + if args.extended { 1 } else { 0 };
+
+    // the smallest type that fits all of our error-correction bits
+    let c = Ident::new(
+        if ecc_bits <= 8 {
+            "u8"
+        } else if ecc_bits <= 16 {
+            "u16"
+        } else {
+            "u32"
+        },
+        Span::call_site()
+    );
+
+    // the word type defaults to the smallest type that fits all of our data
+    // bits, if there is one
+    let (words, u) = match args.u {
+        Some(u) => {
+            let width = guess_width(&u).unwrap_or(0);
+            assert!(width > 0 && width <= data_bits,
+                "hamming u type must have at most {} bits for m={}",
+                data_bits, args.m);
+            (true, u)
+        }
+        None => {
+            let width = data_bits.next_power_of_two().max(8);
+            if width <= 128 {
+                (true, syn::parse_str(&format!("u{}", width)).unwrap())
+            } else {
+                (false, syn::parse_str("u8").unwrap())
+            }
+        }
+    };
+
+    // parse type
+    let ty = parse_macro_input!(input as syn::ItemMod);
+    let attrs = ty.attrs;
+    let vis = ty.vis;
+    let hamming = ty.ident;
+
+    // keyword replacements
+    let replacements = HashMap::from_iter([
+        ("__hamming".to_owned(), TokenTree::Ident(hamming.clone())),
+        ("__m".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(args.m)
+        )),
+        ("__extended".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.extended), Span::call_site())
+        )),
+        ("__words".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", words), Span::call_site())
+        )),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__u".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
+            quote! { #u }
+        }))),
+        ("__c".to_owned(), TokenTree::Ident(c)),
+        ("__crate".to_owned(), __crate.clone()),
+    ]);
+
+    // parse template
+    let template = match compile_template(HAMMING_TEMPLATE, &replacements) {
+        Ok(template) => template,
+        Err(err) => {
+            return err.to_compile_error().into();
+        }
+    };
+
+    let output = quote! {
+        #(#attrs)* #vis mod #hamming {
+            #template
+        }
+    };
+
+    output.into()
+}
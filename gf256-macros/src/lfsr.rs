@@ -38,6 +38,12 @@ struct LfsrArgs {
     #[darling(default)]
     reflected: Option<bool>,
 
+    // register configurations
+    #[darling(default)]
+    galois: bool,
+    #[darling(default)]
+    fibonacci: bool,
+
     // div/rem modes
     #[darling(default)]
     naive: bool,
@@ -85,6 +91,14 @@ pub fn lfsr(
         (128-usize::try_from(polynomial.leading_zeros()).unwrap()) - 1
     };
 
+    // decide between register configurations
+    let fibonacci = match (args.galois, args.fibonacci) {
+        (false, false) => false,
+        (true,  false) => false,
+        (false, true ) => true,
+        _ => panic!("invalid configuration of macro lfsr (galois, fibonacci?)"),
+    };
+
     // decide between div/rem modes
     let (naive, table, small_table, barret, table_barret, small_table_barret) = match
         (args.naive, args.table, args.small_table, args.barret, args.table_barret, args.small_table_barret)
@@ -270,6 +284,9 @@ pub fn lfsr(
         ("__reflected".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", args.reflected.unwrap_or(false)), Span::call_site())
         )),
+        ("__fibonacci".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", fibonacci), Span::call_site())
+        )),
         ("__naive".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", naive), Span::call_site())
         )),
@@ -300,6 +317,12 @@ pub fn lfsr(
         ("__barret_skip".to_owned(), TokenTree::Ident(
             Ident::new(&format!("{}", barret_skip), Span::call_site())
         )),
+        ("__rand_core_09".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rand-core-09")), Span::call_site())
+        )),
+        ("__serde".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="serde")), Span::call_site())
+        )),
         ("__crate".to_owned(), __crate.clone()),
     ]);
 
@@ -9,6 +9,8 @@ mod gf;
 #[cfg(feature="shamir")] mod shamir;
 #[cfg(feature="raid")] mod raid;
 #[cfg(feature="rs")] mod rs;
+#[cfg(feature="bch")] mod bch;
+#[cfg(feature="hamming")] mod hamming;
 
 
 #[proc_macro_attribute]
@@ -71,3 +73,21 @@ pub fn rs(
 ) -> proc_macro::TokenStream {
     rs::rs(args, input)
 }
+
+#[cfg(feature="bch")]
+#[proc_macro_attribute]
+pub fn bch(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> proc_macro::TokenStream {
+    bch::bch(args, input)
+}
+
+#[cfg(feature="hamming")]
+#[proc_macro_attribute]
+pub fn hamming(
+    args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream
+) -> proc_macro::TokenStream {
+    hamming::hamming(args, input)
+}
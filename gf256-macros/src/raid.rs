@@ -40,9 +40,6 @@ pub fn raid(
         }
     };
 
-    // only up to 2 parity blocks are currently supported
-    assert!(args.parity <= 3);
-
     // parse type
     let ty = parse_macro_input!(input as syn::ItemMod);
     let attrs = ty.attrs;
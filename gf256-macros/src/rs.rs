@@ -25,6 +25,13 @@ struct RsArgs {
     gf: Option<syn::Path>,
     #[darling(default)]
     u: Option<syn::Path>,
+    #[darling(default)]
+    interleave: Option<usize>,
+
+    #[darling(default)]
+    berlekamp_massey: bool,
+    #[darling(default)]
+    euclidean: bool,
 }
 
 pub fn rs(
@@ -42,10 +49,30 @@ pub fn rs(
         }
     };
 
-    // gf256 is limited to 255 elements
-    assert!(args.block <= 255);
+    // note the block size is limited by the number of non-zero elements
+    // in our field, but we can't check this until the template is compiled
     assert!(args.data <= args.block);
 
+    // default to no interleaving
+    let interleave = args.interleave.unwrap_or(1);
+    assert!(interleave >= 1);
+
+    // choose our decoder, defaulting to Berlekamp-Massey
+    let (berlekamp_massey, euclidean) = match (args.berlekamp_massey, args.euclidean) {
+        (false, false) => (true, false),
+        (berlekamp_massey, false) => (berlekamp_massey, false),
+        (false, euclidean) => (false, euclidean),
+        // multiple decoders selected?
+        _ => panic!("invalid configuration of macro rs (berlekamp_massey, euclidean?)"),
+    };
+
+    // byte-oriented adapters, such as io adapters, are only available
+    // if our symbols are bytes
+    let byte_symbols = match args.u.as_ref() {
+        Some(u) => u.is_ident("u8"),
+        None => true,
+    };
+
     // parse type
     let ty = parse_macro_input!(input as syn::ItemMod);
     let attrs = ty.attrs;
@@ -101,6 +128,33 @@ pub fn rs(
         ("__ecc_size".to_owned(), TokenTree::Literal(
             Literal::usize_unsuffixed(args.block-args.data)
         )),
+        ("__interleave".to_owned(), TokenTree::Literal(
+            Literal::usize_unsuffixed(interleave)
+        )),
+        ("__berlekamp_massey".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", berlekamp_massey), Span::call_site())
+        )),
+        ("__euclidean".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", euclidean), Span::call_site())
+        )),
+        ("__no_tables".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="no-tables")), Span::call_site())
+        )),
+        ("__alloc".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="alloc")), Span::call_site())
+        )),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__async".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="async")), Span::call_site())
+        )),
+        ("__byte_symbols".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", byte_symbols), Span::call_site())
+        )),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
         ("__gf".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__gf }
         }))),
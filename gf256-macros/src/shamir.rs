@@ -24,6 +24,8 @@ struct ShamirArgs {
     u: Option<syn::Path>,
     #[darling(default)]
     rng: Option<ExprWrapper>,
+    #[darling(default)]
+    constant_time: bool,
 }
 
 pub fn shamir(
@@ -41,6 +43,13 @@ pub fn shamir(
         }
     };
 
+    // byte-oriented adapters, such as io adapters, are only available
+    // if our shares are bytes
+    let byte_symbols = match args.u.as_ref() {
+        Some(u) => u.is_ident("u8"),
+        None => true,
+    };
+
     // parse type
     let ty = parse_macro_input!(input as syn::ItemMod);
     let attrs = ty.attrs;
@@ -109,6 +118,24 @@ pub fn shamir(
     // keyword replacements
     let replacements = HashMap::from_iter([
         ("__shamir".to_owned(), TokenTree::Ident(shamir.clone())),
+        ("__std".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="std")), Span::call_site())
+        )),
+        ("__byte_symbols".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", byte_symbols), Span::call_site())
+        )),
+        ("__rayon".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="rayon")), Span::call_site())
+        )),
+        ("__seeded_rng".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="seeded-rng")), Span::call_site())
+        )),
+        ("__zeroize".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", cfg!(feature="zeroize")), Span::call_site())
+        )),
+        ("__constant_time".to_owned(), TokenTree::Ident(
+            Ident::new(&format!("{}", args.constant_time), Span::call_site())
+        )),
         ("__gf".to_owned(), TokenTree::Group(Group::new(Delimiter::None, {
             quote! { super::#__gf }
         }))),
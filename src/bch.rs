@@ -0,0 +1,328 @@
+//! ## BCH error-correction codes
+//!
+//! [BCH codes][bch-wiki] are a family of error-correction codes (ECC) capable
+//! of correcting multiple bit-level errors. Where [Reed-Solomon](../rs)
+//! corrects errors in whole bytes, binary BCH codes correct errors in
+//! individual bits, which makes them a better fit for channels where errors
+//! are scattered single-bit flips, such as NAND flash.
+//!
+//! ``` rust
+//! use gf256::bch::bch8191t8;
+//!
+//! // encode
+//! let mut buf = b"Hello World!".to_vec();
+//! buf.resize(buf.len()+13, 0u8);
+//! bch8191t8::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0] ^= 0x81;
+//! buf[5] ^= 0x10;
+//!
+//! // correct
+//! bch8191t8::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..12], b"Hello World!");
+//! # Ok::<(), bch8191t8::Error>(())
+//! ```
+//!
+//! Note this module requires feature `bch`.
+//!
+//! ## How do BCH codes work?
+//!
+//! Reed-Solomon is actually a special case of BCH codes, and the
+//! [Reed-Solomon module](../rs#how-does-reed-solomon-error-correction-work)
+//! goes over most of the machinery in detail. The main difference is that
+//! the codeword of a binary BCH code is a polynomial with coefficients in
+//! `GF(2)`, bits, even though its roots, and so our syndromes, are in the
+//! larger field `GF(2^m)`.
+//!
+//! Like Reed-Solomon, we want valid codewords to have roots at `g^1`, `g^2`,
+//! ..., `g^2t`. But a binary polynomial with a root at `g^i` must also have
+//! roots at `g^2i`, `g^4i`, `g^8i`, etc, the _cyclotomic coset_ of `i`. So
+//! the smallest binary polynomial with a root at `g^i` is its _minimal
+//! polynomial_, `Mi(x)`, the product of `(x - g^j)` for each `j` in this
+//! coset. The generator polynomial is then the least common multiple of
+//! these:
+//!
+//! ``` text
+//! G(x) = lcm(M1(x), M3(x), ..., M(2t-1)(x))
+//! ```
+//!
+//! Each minimal polynomial has degree at most `m`, so this needs at most
+//! `m*t` bits of error-correction to correct `t` bit errors, far fewer than
+//! the `2*t` bytes Reed-Solomon would need.
+//!
+//! Decoding follows Reed-Solomon closely:
+//!
+//! 1. Find the `2t` syndromes, `Si = C'(g^i)`. For binary codes
+//!    `S2i = Si^2`, so we only need to evaluate half of these.
+//!
+//! 2. Find the error locator polynomial, `Λ(x)`, with the Berlekamp-Massey
+//!    algorithm.
+//!
+//! 3. Find the roots of `Λ(x)` with a Chien search, which tell us the
+//!    locations of the errors.
+//!
+//! 4. Flip the bits at these locations. Since errors are single bits, we
+//!    don't need Forney's algorithm to find error magnitudes.
+//!
+//! ## Codeword layout
+//!
+//! A binary BCH code over `GF(2^m)` has codewords of up to `2^m-1` bits. We
+//! shorten these to byte-aligned codewords, with up to
+//! [`DATA_SIZE`](crate::bch::bch8191t8::DATA_SIZE) bytes of data followed by
+//! [`ECC_SIZE`](crate::bch::bch8191t8::ECC_SIZE) bytes of error-correction.
+//! Bits are stored msb first. If the error-correction isn't a multiple of 8
+//! bits, the unused bits at the end of the codeword are set to zero by
+//! [`encode`](crate::bch::bch8191t8::encode), and are ignored when decoding.
+//!
+//! The default code, [`bch8191t8`](crate::bch::bch8191t8), uses `GF(2^13)` and
+//! corrects up to 8 bit errors with 13 bytes of error-correction. This is a
+//! common configuration for 512-byte sectors of NAND flash:
+//!
+//! ``` rust
+//! use gf256::bch::bch8191t8;
+//!
+//! let mut sector = vec![0u8; 512+13];
+//! sector[..512].copy_from_slice(&[0x55; 512]);
+//! bch8191t8::encode(&mut sector);
+//!
+//! // flip some bits
+//! for i in (0..8).map(|i| i*500) {
+//!     sector[i/8] ^= 1 << (i%8);
+//! }
+//!
+//! assert_eq!(bch8191t8::correct_errors(&mut sector), Ok(8));
+//! assert_eq!(&sector[..512], &[0x55; 512]);
+//! ```
+//!
+//! [bch-wiki]: https://en.wikipedia.org/wiki/BCH_code
+
+
+/// A macro for generating custom BCH error-correction modules.
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::bch::bch;
+/// #[bch(m=13, t=8)]
+/// pub mod my_bch8191t8 {}
+///
+/// # fn main() -> Result<(), my_bch8191t8::Error> {
+/// // encode
+/// let mut buf = b"Hello World!".to_vec();
+/// buf.resize(buf.len()+13, 0u8);
+/// my_bch8191t8::encode(&mut buf);
+///
+/// // corrupt
+/// buf[0] ^= 0x81;
+///
+/// // correct
+/// my_bch8191t8::correct_errors(&mut buf)?;
+/// assert_eq!(&buf[0..12], b"Hello World!");
+/// # Ok::<(), my_bch8191t8::Error>(())
+/// # }
+/// ```
+///
+/// The `bch` macro accepts a number of configuration options:
+///
+/// - `m` - Width of the field, `GF(2^m)`, codewords are limited to `2^m-1`
+///   bits. Must be between 3 and 16.
+/// - `t` - Number of bit errors to correct.
+/// - `polynomial` - The primitive polynomial that defines `GF(2^m)`, defaults
+///   to the same polynomials used by Linux's BCH library. Different
+///   polynomials result in different, incompatible, codes.
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::bch::bch;
+/// #[bch(
+///     m=13,
+///     t=8,
+///     polynomial=0x201b,
+/// )]
+/// pub mod my_bch8191t8 {}
+///
+/// # fn main() -> Result<(), my_bch8191t8::Error> {
+/// // encode
+/// let mut buf = b"Hello World!".to_vec();
+/// buf.resize(buf.len()+13, 0u8);
+/// my_bch8191t8::encode(&mut buf);
+///
+/// // corrupt
+/// buf[0] ^= 0x81;
+///
+/// // correct
+/// my_bch8191t8::correct_errors(&mut buf)?;
+/// assert_eq!(&buf[0..12], b"Hello World!");
+/// # Ok::<(), my_bch8191t8::Error>(())
+/// # }
+/// ```
+///
+pub use gf256_macros::bch;
+
+
+// BCH error-correction functions, correcting up to 8 bit errors in
+// GF(2^13), common for 512-byte sectors of NAND flash
+//
+#[bch(m=13, t=8)]
+pub mod bch8191t8 {}
+
+// A smaller BCH code, correcting up to 4 bit errors in GF(2^8)
+//
+#[bch(m=8, t=4)]
+pub mod bch255t4 {}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+    use alloc::vec;
+
+    // classic textbook codes
+    #[bch(m=4, t=2)]
+    pub mod bch15t2 {}
+    #[bch(m=4, t=3)]
+    pub mod bch15t3 {}
+
+    #[test]
+    fn bch_generator_poly() {
+        // BCH(15,7), x^8+x^7+x^6+x^4+1
+        assert_eq!(bch15t2::ECC_BITS, 8);
+        assert_eq!(bch15t2::GENERATOR_POLY, [0x01, 0xd1]);
+        // BCH(15,5), x^10+x^8+x^5+x^4+x^2+x+1
+        assert_eq!(bch15t3::ECC_BITS, 10);
+        assert_eq!(bch15t3::GENERATOR_POLY, [0x05, 0x37]);
+
+        assert_eq!(bch255t4::ECC_BITS, 32);
+        assert_eq!(bch255t4::ECC_SIZE, 4);
+        assert_eq!(bch255t4::DATA_SIZE, 27);
+        assert_eq!(bch8191t8::ECC_BITS, 104);
+        assert_eq!(bch8191t8::ECC_SIZE, 13);
+        assert_eq!(bch8191t8::DATA_SIZE, 1010);
+    }
+
+    #[test]
+    fn bch15t2() {
+        // 7 data bits don't fit in a byte, so we can only use the code
+        // shortened to 0 bytes of data here, which is still useful for
+        // testing the error-correction bits
+        let mut codeword = vec![0u8; bch15t2::ECC_SIZE];
+        bch15t2::encode(&mut codeword);
+        assert_eq!(codeword, [0x00]);
+
+        for i in 0..8 {
+            for j in 0..8 {
+                let mut corrupted = codeword.clone();
+                corrupted[0] ^= 0x80 >> i;
+                corrupted[0] ^= 0x80 >> j;
+                let res = bch15t2::correct_errors(&mut corrupted);
+                assert_eq!(res.ok(), Some(if i == j { 0 } else { 2 }));
+                assert_eq!(corrupted, codeword);
+            }
+        }
+    }
+
+    #[test]
+    fn bch15t3() {
+        // 10 bits of error-correction leaves 6 unused bits
+        let mut codeword = vec![0xffu8; bch15t3::ECC_SIZE];
+        bch15t3::encode(&mut codeword);
+        assert_eq!(codeword, [0x00, 0x00]);
+
+        for i in 0..10 {
+            let mut corrupted = codeword.clone();
+            corrupted[i/8] ^= 0x80 >> (i%8);
+            assert_eq!(bch15t3::correct_errors(&mut corrupted), Ok(1));
+            assert_eq!(corrupted, codeword);
+        }
+
+        // unused bits are ignored
+        let mut corrupted = codeword.clone();
+        corrupted[1] ^= 0x3f;
+        assert!(bch15t3::is_correct(&corrupted));
+        assert_eq!(bch15t3::correct_errors(&mut corrupted), Ok(0));
+    }
+
+    #[test]
+    fn bch255t4() {
+        let data = (0..27).collect::<Vec<u8>>();
+        let mut codeword = data.clone();
+        codeword.resize(27+4, 0);
+        bch255t4::encode(&mut codeword);
+        assert!(bch255t4::is_correct(&codeword));
+
+        // correct any single error
+        for i in 0..8*codeword.len() {
+            let mut corrupted = codeword.clone();
+            corrupted[i/8] ^= 0x80 >> (i%8);
+            assert!(!bch255t4::is_correct(&corrupted));
+            assert_eq!(bch255t4::correct_errors(&mut corrupted), Ok(1));
+            assert_eq!(corrupted, codeword);
+        }
+
+        // correct up to t errors, anywhere
+        for _ in 0..1000 {
+            let mut corrupted = codeword.clone();
+            let mut errors = 0;
+            while errors < 4 {
+                let i = rand::random::<usize>() % (8*codeword.len());
+                if (corrupted[i/8] ^ codeword[i/8]) & (0x80 >> (i%8)) == 0 {
+                    corrupted[i/8] ^= 0x80 >> (i%8);
+                    errors += 1;
+                }
+            }
+            assert_eq!(bch255t4::correct_errors(&mut corrupted), Ok(4));
+            assert_eq!(corrupted, codeword);
+        }
+    }
+
+    #[test]
+    fn bch8191t8() {
+        // shortened codewords
+        for len in [0, 1, 12, 512, bch8191t8::DATA_SIZE] {
+            let data = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut codeword = data.clone();
+            codeword.resize(len+13, 0);
+            bch8191t8::encode(&mut codeword);
+            assert!(bch8191t8::is_correct(&codeword));
+
+            let mut parity = vec![0u8; 13];
+            bch8191t8::encode_parity(&data, &mut parity);
+            assert_eq!(parity, &codeword[len..]);
+
+            for errors in 0..=8 {
+                let mut corrupted = codeword.clone();
+                for e in 0..errors {
+                    let i = (e*7919 + len) % (8*codeword.len());
+                    corrupted[i/8] ^= 0x80 >> (i%8);
+                }
+                assert_eq!(bch8191t8::correct_errors(&mut corrupted), Ok(errors));
+                assert_eq!(corrupted, codeword);
+            }
+        }
+    }
+
+    #[test]
+    fn bch_too_many_errors() {
+        let mut codeword = vec![0u8; 27+4];
+        bch255t4::encode(&mut codeword);
+
+        // with more than t errors, decoding either fails or finds a
+        // different codeword, but never silently leaves us with a
+        // non-codeword
+        for _ in 0..1000 {
+            let mut corrupted = codeword.clone();
+            for _ in 0..5 {
+                let i = rand::random::<usize>() % (8*codeword.len());
+                corrupted[i/8] ^= 0x80 >> (i%8);
+            }
+            let prev = corrupted.clone();
+            match bch255t4::correct_errors(&mut corrupted) {
+                Ok(_) => assert!(bch255t4::is_correct(&corrupted)),
+                Err(bch255t4::Error::TooManyErrors) => assert_eq!(corrupted, prev),
+            }
+        }
+    }
+}
@@ -0,0 +1,589 @@
+//! ## Convolutional codes
+//!
+//! [Convolutional codes][conv-wiki] are error-correction codes (ECC) that,
+//! instead of operating on fixed blocks, slide a small shift register over
+//! the data, emitting a few parity bits for every bit of input. Decoded with
+//! the [Viterbi algorithm][viterbi-wiki], they are cheap, handle
+//! soft-decisions naturally, and are good at correcting scattered bit errors.
+//!
+//! This makes them a good _inner_ code, with [Reed-Solomon](../rs) as the
+//! _outer_ code cleaning up the bursts of errors Viterbi leaves behind when
+//! it fails. This was the classic concatenated code used by Voyager, CCSDS,
+//! and DVB-S, see [`rs::concat`](crate::rs::concat).
+//!
+//! ``` rust
+//! use gf256::conv::K7R12;
+//!
+//! // encode
+//! let data = b"Hello World!";
+//! let mut encoded = vec![0u8; K7R12.encoded_size(data.len())];
+//! K7R12.encode(data, &mut encoded);
+//!
+//! // corrupt
+//! encoded[0] ^= 0x81;
+//! encoded[10] ^= 0x10;
+//!
+//! // decode
+//! let mut decoded = [0u8; 12];
+//! assert_eq!(K7R12.decode(&encoded, &mut decoded), 3);
+//! assert_eq!(&decoded, data);
+//! ```
+//!
+//! Note this module requires feature `conv`, and `alloc`.
+//!
+//! ## How do convolutional codes work?
+//!
+//! A rate `1/n` convolutional code with constraint length `k` feeds each bit
+//! of data into a `k`-bit shift register. For each bit, we output `n` bits,
+//! each the parity of the shift register masked by one of `n` generator
+//! polynomials. Polynomials are usually written in octal, with the most
+//! significant bit tapping the newest bit of input:
+//!
+//! ``` text
+//!           .---.---.---.---.---.---.---.
+//! data ---->| 6 | 5 | 4 | 3 | 2 | 1 | 0 |
+//!           '---'---'---'---'---'---'---'
+//! 0o171 =     1   1   1   1   0   0   1
+//!             |   |   |   |           |
+//!             '---'---'---'-----------'---> xor ---> output
+//! ```
+//!
+//! After the data, we feed in `k-1` zeros to flush the shift register back to
+//! its initial state, so each byte of data costs `n` bytes of output, plus
+//! `n*(k-1)` bits of tail.
+//!
+//! The encoder is a state machine with `2^(k-1)` states, the previous `k-1`
+//! bits of input. Decoding is finding the path through this state machine,
+//! the trellis, most likely to have produced what we received. The Viterbi
+//! algorithm does this efficiently by only keeping the best path into each
+//! state at each step:
+//!
+//! 1. For each state, and each bit of input, compute the branch metric, how
+//!    well the expected output matches what we received.
+//!
+//! 2. Each state keeps the better of the two paths that lead into it, the
+//!    add-compare-select step, and we record which path we chose.
+//!
+//! 3. At the end, we start from the final state, all zeros thanks to the
+//!    tail, and trace back through our recorded choices to find the data.
+//!
+//! For hard-decision decoding, the branch metric is the Hamming distance
+//! between the expected output and received bits. For soft-decision
+//! decoding, we use log-likelihood ratios (LLRs), `ln(P(bit=0)/P(bit=1))`,
+//! which lets the decoder weigh confident bits over unconfident ones, worth
+//! roughly 2dB over hard-decisions.
+//!
+//! ## Standard codes
+//!
+//! - [`K3R12`](crate::conv::K3R12) - The textbook constraint length 3, rate 1/2
+//!   code, with polynomials `0o7` and `0o5`.
+//!
+//! - [`K7R12`](crate::conv::K7R12) - The constraint length 7, rate 1/2 code
+//!   with polynomials `0o171` and `0o133`, used by Voyager, DVB-S, 802.11,
+//!   and many others. Note 802.11 swaps the outputs, this isn't handled
+//!   here.
+//!
+//! - [`K7R12_CCSDS`](crate::conv::K7R12_CCSDS) - The same code as
+//!   [`K7R12`](crate::conv::K7R12), but with the second output inverted, as
+//!   used by CCSDS.
+//!
+//! - [`K7R13`](crate::conv::K7R13) - The constraint length 7, rate 1/3 code
+//!   with polynomials `0o133`, `0o171`, and `0o165`, used by LTE, though LTE
+//!   uses tail-biting instead of a zero tail.
+//!
+//! Custom codes can be created with [`Conv::new`](crate::conv::Conv::new),
+//! and outputs can be inverted with
+//! [`Conv::with_inverted`](crate::conv::Conv::with_inverted).
+//!
+//! [conv-wiki]: https://en.wikipedia.org/wiki/Convolutional_code
+//! [viterbi-wiki]: https://en.wikipedia.org/wiki/Viterbi_algorithm
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// The textbook constraint length 3, rate 1/2 convolutional code.
+pub const K3R12: Conv<2> = Conv::new(3, [0o7, 0o5]);
+
+/// The constraint length 7, rate 1/2 convolutional code used by Voyager,
+/// DVB-S, 802.11, etc.
+pub const K7R12: Conv<2> = Conv::new(7, [0o171, 0o133]);
+
+/// The constraint length 7, rate 1/2 convolutional code used by CCSDS, with
+/// the second output inverted.
+pub const K7R12_CCSDS: Conv<2> = K7R12.with_inverted([false, true]);
+
+/// The constraint length 7, rate 1/3 convolutional code used by LTE.
+pub const K7R13: Conv<3> = Conv::new(7, [0o133, 0o171, 0o165]);
+
+
+/// A rate 1/N convolutional code, with a zero tail.
+///
+/// ``` rust
+/// use gf256::conv::Conv;
+///
+/// // a custom constraint length 5, rate 1/2 code
+/// let conv = Conv::new(5, [0o23, 0o35]);
+///
+/// let mut encoded = vec![0u8; conv.encoded_size(12)];
+/// conv.encode(b"Hello World!", &mut encoded);
+///
+/// encoded[3] ^= 0x04;
+/// let mut decoded = [0u8; 12];
+/// assert_eq!(conv.decode(&encoded, &mut decoded), 1);
+/// assert_eq!(&decoded, b"Hello World!");
+/// ```
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Conv<const N: usize> {
+    k: usize,
+    polynomials: [u32; N],
+    // mask of inverted outputs, the first polynomial's output in the
+    // lowest bit
+    inverted: u32,
+}
+
+impl<const N: usize> Conv<N> {
+    /// Create a convolutional code with constraint length `k` and the given
+    /// generator polynomials, one for each output bit.
+    ///
+    /// The most significant bit of each polynomial, bit `k-1`, taps the
+    /// newest bit of input. Must have 2 <= k <= 16.
+    ///
+    pub const fn new(k: usize, polynomials: [u32; N]) -> Self {
+        assert!(k >= 2 && k <= 16, "conv requires 2 <= k <= 16");
+        assert!(N >= 1, "conv requires at least one polynomial");
+        let mut i = 0;
+        while i < N {
+            assert!(polynomials[i] >> k == 0,
+                "conv polynomials must fit in the constraint length");
+            i += 1;
+        }
+
+        Conv { k, polynomials, inverted: 0 }
+    }
+
+    /// Invert some of the outputs, one flag for each polynomial.
+    ///
+    /// Some standards, such as CCSDS, invert an output so long runs of
+    /// zeros in the data don't produce long runs of zeros on the wire.
+    ///
+    /// ``` rust
+    /// use gf256::conv::K3R12;
+    ///
+    /// // 1011 => 10 11 01 00, plus the tail
+    /// let mut encoded = [0u8; 3];
+    /// K3R12.with_inverted([false, true]).encode(&[0xb0], &mut encoded);
+    /// assert_eq!(encoded[0], 0b10110100);
+    /// ```
+    ///
+    pub const fn with_inverted(self, inverted: [bool; N]) -> Self {
+        let mut mask = 0;
+        let mut i = 0;
+        while i < N {
+            if inverted[i] {
+                mask |= 1 << i;
+            }
+            i += 1;
+        }
+
+        Conv { inverted: mask, ..self }
+    }
+
+    /// The constraint length, the size of the shift register in bits.
+    pub const fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The generator polynomials.
+    pub const fn polynomials(&self) -> [u32; N] {
+        self.polynomials
+    }
+
+    /// Which outputs are inverted, one flag for each polynomial.
+    pub const fn inverted(&self) -> [bool; N] {
+        let mut inverted = [false; N];
+        let mut i = 0;
+        while i < N {
+            inverted[i] = self.inverted & (1 << i) != 0;
+            i += 1;
+        }
+        inverted
+    }
+
+    /// Number of encoded bits for the given number of data bits, this
+    /// includes the tail.
+    pub const fn encoded_bits(&self, data_bits: usize) -> usize {
+        N*(data_bits + self.k - 1)
+    }
+
+    /// Size of the encoded output in bytes for the given size of data in
+    /// bytes, [`encoded_bits`](Self::encoded_bits) rounded up.
+    pub const fn encoded_size(&self, data_size: usize) -> usize {
+        self.encoded_bits(8*data_size).div_ceil(8)
+    }
+
+    /// Number of states in the trellis
+    const fn states(&self) -> usize {
+        1 << (self.k-1)
+    }
+
+    /// Output bits for a given state and input bit, the first polynomial's
+    /// output in the lowest bit
+    fn output(&self, state: usize, bit: usize) -> u32 {
+        let reg = ((bit << (self.k-1)) | state) as u32;
+        let mut out = 0;
+        for (j, p) in self.polynomials.iter().enumerate() {
+            out |= ((reg & p).count_ones() & 1) << j;
+        }
+        out ^ self.inverted
+    }
+
+    /// Encode data with a convolutional code.
+    ///
+    /// This writes [`encoded_bits`](Self::encoded_bits) bits to `encoded`,
+    /// msb first, with any unused bits at the end of the last byte set to
+    /// zero.
+    ///
+    /// ``` rust
+    /// use gf256::conv::K3R12;
+    ///
+    /// // 1011 => 11 10 00 01, plus the tail
+    /// let mut encoded = [0u8; 3];
+    /// K3R12.encode(&[0xb0], &mut encoded);
+    /// assert_eq!(encoded[0], 0b11100001);
+    /// ```
+    ///
+    pub fn encode(&self, data: &[u8], encoded: &mut [u8]) {
+        assert!(encoded.len() == self.encoded_size(data.len()));
+
+        encoded.fill(0);
+        let mut state = 0;
+        let mut i = 0;
+        let tail = core::iter::repeat_n(0, self.k-1);
+        for bit in bits(data).chain(tail) {
+            let out = self.output(state, bit);
+            for j in 0..N {
+                if out & (1 << j) != 0 {
+                    encoded[i/8] |= 0x80 >> (i%8);
+                }
+                i += 1;
+            }
+            state = ((bit << (self.k-1)) | state) >> 1;
+        }
+    }
+
+    /// Decode data with hard-decisions using the Viterbi algorithm.
+    ///
+    /// `encoded` must be the output of [`encode`](Self::encode) for
+    /// `data.len()` bytes of data. Returns the number of bit errors
+    /// corrected.
+    ///
+    /// Viterbi always finds the most likely data, so this can't fail, but
+    /// with too many errors the data may be wrong. Check the number of errors
+    /// corrected, or use an outer code, to catch this.
+    ///
+    /// ``` rust
+    /// use gf256::conv::K7R12;
+    ///
+    /// let mut encoded = vec![0u8; K7R12.encoded_size(12)];
+    /// K7R12.encode(b"Hello World!", &mut encoded);
+    ///
+    /// // flip 8 well-spaced bits
+    /// for i in 0..8 {
+    ///     encoded[3*i] ^= 1 << i;
+    /// }
+    ///
+    /// let mut decoded = [0u8; 12];
+    /// assert_eq!(K7R12.decode(&encoded, &mut decoded), 8);
+    /// assert_eq!(&decoded, b"Hello World!");
+    /// ```
+    ///
+    pub fn decode(&self, encoded: &[u8], data: &mut [u8]) -> usize {
+        assert!(encoded.len() == self.encoded_size(data.len()));
+
+        let llrs = (0..self.encoded_bits(8*data.len()))
+            .map(|i| if encoded[i/8] & (0x80 >> (i%8)) != 0 { -1.0 } else { 1.0 })
+            .collect::<Vec<f32>>();
+        self.decode_soft(&llrs, data)
+    }
+
+    /// Decode data with soft-decisions using the Viterbi algorithm.
+    ///
+    /// Each LLR is `ln(P(bit=0)/P(bit=1))`, so positive means 0, negative
+    /// means 1, and the magnitude is our confidence. Only the relative
+    /// scale of the LLRs matters. Zero can be used for erased or punctured
+    /// bits.
+    ///
+    /// `llrs` must contain [`encoded_bits`](Self::encoded_bits) LLRs for
+    /// `data.len()` bytes of data. Returns the number of bits that differ
+    /// from the hard decisions of the LLRs, not counting erased bits.
+    ///
+    /// ``` rust
+    /// use gf256::conv::K7R12;
+    ///
+    /// let mut encoded = vec![0u8; K7R12.encoded_size(12)];
+    /// K7R12.encode(b"Hello World!", &mut encoded);
+    ///
+    /// // a burst of unconfident bits are wrong
+    /// let mut llrs = (0..K7R12.encoded_bits(8*12))
+    ///     .map(|i| if encoded[i/8] & (0x80 >> (i%8)) == 0 { 1.0 } else { -1.0 })
+    ///     .collect::<Vec<f32>>();
+    /// for i in 20..26 {
+    ///     llrs[i] = -0.1*llrs[i];
+    /// }
+    ///
+    /// let mut decoded = [0u8; 12];
+    /// assert_eq!(K7R12.decode_soft(&llrs, &mut decoded), 6);
+    /// assert_eq!(&decoded, b"Hello World!");
+    /// ```
+    ///
+    pub fn decode_soft(&self, llrs: &[f32], data: &mut [u8]) -> usize {
+        let steps = 8*data.len() + self.k - 1;
+        assert!(llrs.len() == self.encoded_bits(8*data.len()));
+
+        // precompute expected outputs for each state and input bit
+        let states = self.states();
+        let outputs = (0..2*states)
+            .map(|x| self.output(x >> 1, x & 1))
+            .collect::<Vec<_>>();
+
+        // path metrics, higher is better, we always start in state 0
+        let mut metrics = vec![f32::NEG_INFINITY; states];
+        metrics[0] = 0.0;
+        let mut next = vec![f32::NEG_INFINITY; states];
+
+        // survivor decisions, 1 bit per state per step, recording which
+        // previous state we came from
+        let words = states.div_ceil(64);
+        let mut decisions = vec![0u64; steps*words];
+
+        for (t, llrs) in llrs.chunks(N).enumerate() {
+            // branch metric, correlate expected output with our LLRs
+            let branch = |out: u32| -> f32 {
+                llrs.iter()
+                    .enumerate()
+                    .map(|(j, llr)| if out & (1 << j) == 0 { *llr } else { -*llr })
+                    .sum()
+            };
+
+            // add-compare-select, each next state ns has two previous
+            // states, which differ only in the oldest bit
+            for (ns, m) in next.iter_mut().enumerate() {
+                let bit = ns >> (self.k-2);
+                let s0 = (ns << 1) & (states-1);
+                let s1 = s0 | 1;
+                let m0 = metrics[s0] + branch(outputs[(s0 << 1) | bit]);
+                let m1 = metrics[s1] + branch(outputs[(s1 << 1) | bit]);
+                if m1 > m0 {
+                    *m = m1;
+                    decisions[t*words + ns/64] |= 1 << (ns%64);
+                } else {
+                    *m = m0;
+                }
+            }
+
+            // normalize to avoid growing without bound
+            let max = next.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+            for (m, n) in metrics.iter_mut().zip(&next) {
+                *m = n - max;
+            }
+        }
+
+        // traceback from state 0, thanks to our tail
+        data.fill(0);
+        let mut state = 0;
+        for t in (0..steps).rev() {
+            let bit = state >> (self.k-2);
+            if t < 8*data.len() && bit != 0 {
+                data[t/8] |= 0x80 >> (t%8);
+            }
+            let d = (decisions[t*words + state/64] >> (state%64)) & 1;
+            state = ((state << 1) & (states-1)) | d as usize;
+        }
+
+        // count how many bits we corrected by re-encoding
+        let mut encoded = vec![0u8; self.encoded_size(data.len())];
+        self.encode(data, &mut encoded);
+        llrs.iter()
+            .enumerate()
+            .filter(|(i, llr)| {
+                **llr != 0.0
+                    && (encoded[i/8] & (0x80 >> (i%8)) != 0) != (**llr < 0.0)
+            })
+            .count()
+    }
+}
+
+/// Iterate over bits, msb first
+fn bits(data: &[u8]) -> impl Iterator<Item=usize> + '_ {
+    data.iter().flat_map(|b| (0..8).rev().map(move |i| usize::from((b >> i) & 1)))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Approximately normal noise, the sum of 12 uniform samples, which
+    /// avoids needing ln/sqrt without std
+    fn noise(sigma: f32) -> f32 {
+        sigma * ((0..12).map(|_| rand::random::<f32>()).sum::<f32>() - 6.0)
+    }
+
+    #[test]
+    fn conv_encode() {
+        // textbook example, 1011 => 11 10 00 01
+        let mut encoded = [0u8; 3];
+        K3R12.encode(&[0xb0], &mut encoded);
+        assert_eq!(K3R12.encoded_bits(8), 20);
+        assert_eq!(encoded[0], 0b11100001);
+
+        // impulse response is the interleaved polynomials
+        let mut encoded = vec![0u8; K7R12.encoded_size(1)];
+        K7R12.encode(&[0x80], &mut encoded);
+        // 171 = 1111001, 133 = 1011011 => 11 10 11 11 00 01 11
+        assert_eq!(&encoded[..2], &[0b11101111, 0b00011100]);
+
+        let mut encoded = vec![0u8; K7R13.encoded_size(1)];
+        K7R13.encode(&[0x80], &mut encoded);
+        // 133 = 1011011, 171 = 1111001, 165 = 1110101
+        // => 111 011 111 110 001 100 111
+        assert_eq!(&encoded[..3], &[0b11101111, 0b11100011, 0b00111000]);
+
+        // CCSDS inverts the second output, so zeros aren't encoded as zeros
+        let mut encoded = vec![0u8; K7R12_CCSDS.encoded_size(1)];
+        K7R12_CCSDS.encode(&[0x00], &mut encoded);
+        assert_eq!(&encoded[..2], &[0b01010101, 0b01010101]);
+        assert_eq!(K7R12_CCSDS.inverted(), [false, true]);
+    }
+
+    #[test]
+    fn conv_decode() {
+        for _ in 0..100 {
+            let data = (0..32).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+            let mut encoded = vec![0u8; K7R12.encoded_size(data.len())];
+            K7R12.encode(&data, &mut encoded);
+
+            let mut decoded = vec![0u8; data.len()];
+            assert_eq!(K7R12.decode(&encoded, &mut decoded), 0);
+            assert_eq!(decoded, data);
+
+            // errors spaced at least a few constraint lengths apart are
+            // always correctable
+            let bits = K7R12.encoded_bits(8*data.len());
+            let offset = rand::random::<usize>() % 40;
+            let mut corrupted = encoded.clone();
+            let mut errors = 0;
+            for i in (offset..bits).step_by(40) {
+                corrupted[i/8] ^= 0x80 >> (i%8);
+                errors += 1;
+            }
+            assert_eq!(K7R12.decode(&corrupted, &mut decoded), errors);
+            assert_eq!(decoded, data);
+
+            // K7R12 has a free distance of 10, so any 4 errors in a burst
+            // are also correctable
+            let start = rand::random::<usize>() % (bits-4);
+            let mut corrupted = encoded.clone();
+            for i in start..start+4 {
+                corrupted[i/8] ^= 0x80 >> (i%8);
+            }
+            assert_eq!(K7R12.decode(&corrupted, &mut decoded), 4);
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn conv_decode_inverted() {
+        let data = b"Hello World!";
+        let mut encoded = vec![0u8; K7R12_CCSDS.encoded_size(data.len())];
+        K7R12_CCSDS.encode(data, &mut encoded);
+
+        // only the inverted outputs differ
+        let mut uninverted = vec![0u8; K7R12.encoded_size(data.len())];
+        K7R12.encode(data, &mut uninverted);
+        for i in 0..K7R12.encoded_bits(8*data.len()) {
+            let x = encoded[i/8] ^ uninverted[i/8];
+            assert_eq!(x & (0x80 >> (i%8)) != 0, i % 2 == 1);
+        }
+
+        encoded[1] ^= 0x20;
+        encoded[7] ^= 0x01;
+        let mut decoded = [0u8; 12];
+        assert_eq!(K7R12_CCSDS.decode(&encoded, &mut decoded), 2);
+        assert_eq!(&decoded, data);
+    }
+
+    #[test]
+    fn conv_decode_k3() {
+        // K3R12 has a free distance of 5, so any 2 errors are correctable
+        let data = [0x12, 0x34, 0x56, 0x78];
+        let mut encoded = vec![0u8; K3R12.encoded_size(data.len())];
+        K3R12.encode(&data, &mut encoded);
+        let bits = K3R12.encoded_bits(8*data.len());
+        for i in 0..bits {
+            for j in i+1..bits {
+                let mut corrupted = encoded.clone();
+                corrupted[i/8] ^= 0x80 >> (i%8);
+                corrupted[j/8] ^= 0x80 >> (j%8);
+                let mut decoded = [0u8; 4];
+                assert_eq!(K3R12.decode(&corrupted, &mut decoded), 2);
+                assert_eq!(decoded, data);
+            }
+        }
+    }
+
+    #[test]
+    fn conv_decode_soft() {
+        for (sigma, errors) in [(0.5, 0), (0.63, 1)] {
+            // rate 1/2 at Eb/N0 = 6dB, and 4dB
+            let mut failures = 0;
+            for _ in 0..100 {
+                let data = (0..32).map(|_| rand::random::<u8>()).collect::<Vec<_>>();
+                let mut encoded = vec![0u8; K7R12.encoded_size(data.len())];
+                K7R12.encode(&data, &mut encoded);
+
+                let llrs = (0..K7R12.encoded_bits(8*data.len()))
+                    .map(|i| {
+                        let x = if encoded[i/8] & (0x80 >> (i%8)) == 0 { 1.0 } else { -1.0 };
+                        x + noise(sigma)
+                    })
+                    .collect::<Vec<f32>>();
+
+                let mut decoded = vec![0u8; data.len()];
+                K7R12.decode_soft(&llrs, &mut decoded);
+                if decoded != data {
+                    failures += 1;
+                }
+            }
+            assert!(failures <= errors, "sigma={} failed {} times", sigma, failures);
+        }
+    }
+
+    #[test]
+    fn conv_rate13() {
+        let data = b"Hello World!";
+        let mut encoded = vec![0u8; K7R13.encoded_size(data.len())];
+        K7R13.encode(data, &mut encoded);
+        assert_eq!(encoded.len(), 39);
+
+        // erased bits carry no information, but with rate 1/3 we can lose
+        // a whole output stream and still decode
+        let llrs = (0..K7R13.encoded_bits(8*data.len()))
+            .map(|i| {
+                if i % 3 == 2 {
+                    0.0
+                } else if encoded[i/8] & (0x80 >> (i%8)) == 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            })
+            .collect::<Vec<f32>>();
+        let mut decoded = [0u8; 12];
+        assert_eq!(K7R13.decode_soft(&llrs, &mut decoded), 0);
+        assert_eq!(&decoded, data);
+    }
+}
@@ -29,6 +29,7 @@
 //! word_less_naive_crc      => 0x1c291ca3
 //! table_crc                => 0x1c291ca3
 //! small_table_crc          => 0x1c291ca3
+//! slice8_table_crc         => 0x1c291ca3
 //! barret_crc               => 0x1c291ca3
 //! word_barret_crc          => 0x1c291ca3
 //! reversed_barret_crc      => 0x1c291ca3
@@ -207,14 +208,61 @@
 //! - In `small_table` mode, the same strategy as `table` mode is used, but with a 16
 //!   element  remainder table computer the remainder a nibble at a time.
 //!
+//! - In `slice8` and `slice16` modes, CRCs use 8 or 16 remainder tables to compute
+//!   the remainder 8 or 16 bytes at a time, commonly known as "slice-by-8" and
+//!   "slice-by-16".
+//!
+//!   The k-th table contains the remainder of each byte followed by k bytes of
+//!   zeros. Since polynomial remainder distributes over addition, we can look up
+//!   each byte in a slice independently and xor the results together. This breaks
+//!   the dependency between each byte lookup, at the cost of 8x or 16x the table
+//!   size.
+//!
+//!   These modes are not used by default, but may be significantly faster than
+//!   `table` mode on machines without hardware carry-less multiplication.
+//!
 //! - In `barret` mode, CRCs use [Barret-reduction][barret-reduction] to efficiently
 //!   compute the remainder using only multiplication by precomputed constants.
 //!
 //!   This mode is especially effective when hardware carry-less multiplication
 //!   instructions are available.
 //!
-//! If hardware carry-less multiplication is available, `barret` mode is the fastest
-//! option for CRCs, so CRC implementations will use `barret` by default.
+//! - In `folding` mode, CRCs "fold" the data forward 64 bytes at a time, using
+//!   carry-less multiplication by precomputed constants, `x^n mod p`, to shift
+//!   4 independent 128-bit blocks. Since multiplication distributes over
+//!   addition, we can leave the actual remainder until the very end.
+//!
+//!   This is the same technique used by the classic PCLMULQDQ CRC kernels, and
+//!   is very effective when hardware carry-less multiplication is available.
+//!   Any remaining data is handled with Barret reduction.
+//!
+//!   This mode is limited to CRCs with at most 64 bits.
+//!
+//! - In `hardware` mode, CRCs use dedicated CRC instructions. These are only
+//!   available for a couple of specific polynomials: CRC32C (`0x11edc6f41`) via
+//!   SSE4.2 on x86_64 and the CRC extension on aarch64, and CRC32 (`0x104c11db7`)
+//!   via the CRC extension on aarch64. Both must also be bit-reflected.
+//!
+//!   Like our carry-less multiplication, these instructions are detected at
+//!   compile-time via the target's features, so you may need to enable them
+//!   with `-Ctarget-feature=+sse4.2`, `-Ctarget-feature=+crc`, or
+//!   `-Ctarget-cpu=native`. [`HAS_HWCRC32C`](crate::crc::HAS_HWCRC32C) and
+//!   [`HAS_HWCRC32`](crate::crc::HAS_HWCRC32) indicate if these instructions
+//!   are available. Explicitly requesting `hardware` mode on a target without
+//!   these instructions is a compile error.
+//!
+//! If the CRC's polynomial has dedicated hardware instructions available, `hardware`
+//! mode is used, since it is by far the fastest option.
+//!
+//! If these instructions aren't enabled at compile-time, but the feature `std`
+//! is enabled, CRCs with these polynomials will still check for the instructions
+//! at runtime, via `is_x86_feature_detected!` and `is_aarch64_feature_detected!`,
+//! falling back to the default mode below if they are not found.
+//!
+//! Otherwise, if hardware carry-less multiplication is available, `barret` mode is
+//! the fastest option for CRCs, so CRC implementations will use `barret` by default.
+//! `folding` mode is never chosen by default, it must be explicitly requested with
+//! `#[crc(polynomial=..., folding)]`.
 //!
 //! If hardware carry-less multiplication is not available, `table` mode will be
 //! used, unless the feature `small-tables` is enabled, in which case `small_table`
@@ -299,7 +347,9 @@
 /// - `u` - The underlying unsigned type, defaults to the minimum sized
 ///   unsigned type that fits the CRC state space.
 /// - `u2` - An unsigned type with twice the width, used as an intermediary type
-///   for computations, defaults to the correct type based on `u`.
+///   for computations, defaults to the correct type based on `u`. CRCs wider
+///   than 64 bits have no such type, and fall back to bitwise computations
+///   where it would be needed.
 /// - `p` - The polynomial type used for computation, defaults to the
 ///   polynomial version of `u`.
 /// - `p2` - A polynomial type with twice the width, used as an intermediary type
@@ -312,8 +362,19 @@
 /// - `table` - Use precomputed CRC table. This is the default if hardware
 ///   polynomial multiplication is not available.
 /// - `small_table` - Use a small, 16-element CRC table.
+/// - `slice8` - Use 8 precomputed CRC tables to process 8 bytes at a time.
+/// - `slice16` - Use 16 precomputed CRC tables to process 16 bytes at a time.
 /// - `barret` - Use Barret-reduction with polynomial multiplication. This is
 ///   the default if hardware polynomial multiplication is available.
+/// - `folding` - Fold 64-byte chunks with polynomial multiplication. This is
+///   never chosen by default, and only supports CRCs up to 64 bits.
+/// - `hardware` - Use dedicated hardware CRC instructions. This is only
+///   available for bit-reflected CRC32C/CRC32 on some architectures, and is
+///   the default when available.
+/// - `hardware_detect` - Check for dedicated hardware CRC instructions at
+///   runtime, falling back to the selected mode if they are not found. This
+///   requires feature `std`, and is the default for bit-reflected
+///   CRC32C/CRC32 when `hardware` is not available at compile-time.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -329,7 +390,12 @@
 ///     // naive,
 ///     // table,
 ///     // small_table,
+///     // slice8,
+///     // slice16,
 ///     // barret,
+///     // folding,
+///     // hardware,
+///     // hardware_detect,
 /// )]
 /// pub fn my_crc32() {}
 ///
@@ -338,8 +404,246 @@
 /// # }
 /// ```
 ///
-
+/// Along with the CRC function itself, the `crc` macro generates a set of
+/// related functions, named after the CRC function:
+///
+/// - `<name>_combine` - Combine the CRCs of two pieces of data into the CRC
+///   of their concatenation, given only the length of the second piece of
+///   data.
+/// - `<name>_rolling_table` - Build a table for rolling a CRC over a
+///   fixed-size window.
+/// - `<name>_roll` - Roll a CRC over a fixed-size window, adding a new byte
+///   and removing the oldest byte.
+/// - `<name>_patch` - Update a CRC after some bytes in the data have changed,
+///   without recomputing the CRC over all of the data.
+/// - `<name>_bits` - Calculate the CRC for data that is not a whole number of
+///   bytes long.
+/// - `<name>_correct` - Correct a single-bit error in data, given the
+///   expected CRC.
+/// - `<name>_correct_burst` - Correct a short burst of bit-errors in data,
+///   given the expected CRC.
+/// - `<name>_verify` - Check data that ends with its own CRC.
+/// - `<name>_byte_table` - The byte-wise lookup table for the CRC, useful for
+///   sharing with other implementations, see [`export`](crate::export).
+/// - `<name>_const` - Calculate the CRC of data in a const context, useful
+///   for compile-time checksums.
+/// - `<name>_vectored` - Calculate the CRC of data split across multiple
+///   buffers, such as [`IoSlice`](https://doc.rust-lang.org/std/io/struct.IoSlice.html)s.
+/// - `<name>_parallel` - Calculate the CRC of data using multiple threads
+///   (requires feature `rayon`).
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::crc::crc;
+/// #[crc(polynomial=0x11edc6f41)]
+/// pub fn my_crc32() {}
+///
+/// # fn main() {
+/// let a = my_crc32(b"Hello ", 0);
+/// let b = my_crc32(b"World!", 0);
+/// assert_eq!(my_crc32_combine(a, b, 6), 0xfe6cf1dc);
+/// # }
+/// ```
+///
 pub use gf256_macros::crc;
+use core::fmt;
+
+/// CRC parameter recovery
+#[cfg(feature="alloc")]
+#[path="reveng.rs"]
+pub mod reveng;
+
+/// Hamming distance analysis of CRC polynomials
+#[cfg(feature="alloc")]
+#[path="hd.rs"]
+pub mod hd;
+
+/// A flag indicating if hardware CRC32 instructions are available
+pub use crate::internal::hwcrc::HAS_HWCRC32;
+
+/// A flag indicating if hardware CRC32C instructions are available
+pub use crate::internal::hwcrc::HAS_HWCRC32C;
+
+
+/// A reader that calculates the CRC of any data read through it.
+///
+/// This works with any CRC function, including CRC functions generated by
+/// the [`crc`] macro:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// # use std::io::Read;
+/// let mut reader = CrcReader::new(&b"Hello World!"[..], crc32c);
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf).unwrap();
+/// assert_eq!(reader.crc(), 0xfe6cf1dc);
+/// ```
+///
+/// Note this requires feature `std`.
+///
+#[cfg(feature="std")]
+pub struct CrcReader<R, U, F> {
+    inner: R,
+    crc: U,
+    f: F,
+}
+
+#[cfg(feature="std")]
+impl<R, U: Copy+Default, F: Fn(&[u8], U) -> U> CrcReader<R, U, F> {
+    /// Create a new reader with an initial CRC of zero
+    pub fn new(inner: R, f: F) -> Self {
+        Self::with_crc(inner, f, U::default())
+    }
+
+    /// Create a new reader, continuing from a previous CRC
+    pub fn with_crc(inner: R, f: F, crc: U) -> Self {
+        Self { inner, crc, f }
+    }
+
+    /// The CRC of all data read so far
+    pub fn crc(&self) -> U {
+        self.crc
+    }
+
+    /// Get a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying reader
+    ///
+    /// Note that data read directly from the underlying reader will not be
+    /// included in the CRC.
+    ///
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this reader, returning the underlying reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature="std")]
+impl<R: std::io::Read, U: Copy, F: Fn(&[u8], U) -> U> std::io::Read for CrcReader<R, U, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.crc = (self.f)(&buf[..n], self.crc);
+        Ok(n)
+    }
+}
+
+#[cfg(feature="std")]
+impl<R: fmt::Debug, U: fmt::Debug, F> fmt::Debug for CrcReader<R, U, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrcReader")
+            .field("inner", &self.inner)
+            .field("crc", &self.crc)
+            .finish()
+    }
+}
+
+/// A writer that calculates the CRC of any data written through it.
+///
+/// This works with any CRC function, including CRC functions generated by
+/// the [`crc`] macro:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// # use std::io::Write;
+/// let mut writer = CrcWriter::new(Vec::new(), crc32c);
+/// writer.write_all(b"Hello World!").unwrap();
+/// assert_eq!(writer.crc(), 0xfe6cf1dc);
+/// assert_eq!(writer.into_inner(), b"Hello World!");
+/// ```
+///
+/// Note this requires feature `std`.
+///
+#[cfg(feature="std")]
+pub struct CrcWriter<W, U, F> {
+    inner: W,
+    crc: U,
+    f: F,
+}
+
+#[cfg(feature="std")]
+impl<W, U: Copy+Default, F: Fn(&[u8], U) -> U> CrcWriter<W, U, F> {
+    /// Create a new writer with an initial CRC of zero
+    pub fn new(inner: W, f: F) -> Self {
+        Self::with_crc(inner, f, U::default())
+    }
+
+    /// Create a new writer, continuing from a previous CRC
+    pub fn with_crc(inner: W, f: F, crc: U) -> Self {
+        Self { inner, crc, f }
+    }
+
+    /// The CRC of all data written so far
+    pub fn crc(&self) -> U {
+        self.crc
+    }
+
+    /// Get a reference to the underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer
+    ///
+    /// Note that data written directly to the underlying writer will not be
+    /// included in the CRC.
+    ///
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap this writer, returning the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(feature="std")]
+impl<W: std::io::Write, U: Copy, F: Fn(&[u8], U) -> U> std::io::Write for CrcWriter<W, U, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.crc = (self.f)(&buf[..n], self.crc);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature="std")]
+impl<W: fmt::Debug, U: fmt::Debug, F> fmt::Debug for CrcWriter<W, U, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrcWriter")
+            .field("inner", &self.inner)
+            .field("crc", &self.crc)
+            .finish()
+    }
+}
+
+
+/// Error codes for CRC error-correction
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// CRC error-correction can fail if there is more than one bit-error,
+    /// or if the error is ambiguous
+    ///
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+        }
+    }
+}
 
 
 // CRC functions
@@ -382,6 +686,9 @@ mod test {
     use super::*;
     use crate::p::*;
 
+    extern crate alloc;
+    use alloc::vec::Vec;
+
     #[test]
     fn crc() {
         assert_eq!(crc8(b"Hello World!", 0),   0xb3);
@@ -410,6 +717,24 @@ mod test {
     #[crc(polynomial=0x11edc6f41, small_table)] fn crc32c_small_table() {}
     #[crc(polynomial=0x142f0e1eba9ea3693, small_table)] fn crc64_small_table() {}
 
+    #[crc(polynomial=0x107, slice8)] fn crc8_slice8() {}
+    #[crc(polynomial=0x11021, slice8)] fn crc16_slice8() {}
+    #[crc(polynomial=0x104c11db7, slice8)] fn crc32_slice8() {}
+    #[crc(polynomial=0x11edc6f41, slice8)] fn crc32c_slice8() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, slice8)] fn crc64_slice8() {}
+
+    #[crc(polynomial=0x107, slice16)] fn crc8_slice16() {}
+    #[crc(polynomial=0x11021, slice16)] fn crc16_slice16() {}
+    #[crc(polynomial=0x104c11db7, slice16)] fn crc32_slice16() {}
+    #[crc(polynomial=0x11edc6f41, slice16)] fn crc32c_slice16() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, slice16)] fn crc64_slice16() {}
+
+    #[crc(polynomial=0x107, folding)] fn crc8_folding() {}
+    #[crc(polynomial=0x11021, folding)] fn crc16_folding() {}
+    #[crc(polynomial=0x104c11db7, folding)] fn crc32_folding() {}
+    #[crc(polynomial=0x11edc6f41, folding)] fn crc32c_folding() {}
+    #[crc(polynomial=0x142f0e1eba9ea3693, folding)] fn crc64_folding() {}
+
     #[crc(polynomial=0x107, barret)] fn crc8_barret() {}
     #[crc(polynomial=0x11021, barret)] fn crc16_barret() {}
     #[crc(polynomial=0x104c11db7, barret)] fn crc32_barret() {}
@@ -443,6 +768,33 @@ mod test {
         assert_eq!(crc64_small_table(b"Hello World!", 0),  0x75045245c9ea6fe2);
     }
 
+    #[test]
+    fn crc_slice8() {
+        assert_eq!(crc8_slice8(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_slice8(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_slice8(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_slice8(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_slice8(b"Hello World!", 0),  0x75045245c9ea6fe2);
+    }
+
+    #[test]
+    fn crc_slice16() {
+        assert_eq!(crc8_slice16(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_slice16(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_slice16(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_slice16(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_slice16(b"Hello World!", 0),  0x75045245c9ea6fe2);
+    }
+
+    #[test]
+    fn crc_folding() {
+        assert_eq!(crc8_folding(b"Hello World!", 0),   0xb3);
+        assert_eq!(crc16_folding(b"Hello World!", 0),  0x0bbb);
+        assert_eq!(crc32_folding(b"Hello World!", 0),  0x1c291ca3);
+        assert_eq!(crc32c_folding(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc64_folding(b"Hello World!", 0),  0x75045245c9ea6fe2);
+    }
+
     #[test]
     fn crc_barret() {
         assert_eq!(crc8_barret(b"Hello World!", 0),   0xb3);
@@ -472,6 +824,18 @@ mod test {
         assert_eq!(crc32c_small_table(b"Hello World!!", 0), 0x1ec51c06);
         assert_eq!(crc64_small_table(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
 
+        assert_eq!(crc8_slice8(b"Hello World!!", 0),   0x2f);
+        assert_eq!(crc16_slice8(b"Hello World!!", 0),  0xcba0);
+        assert_eq!(crc32_slice8(b"Hello World!!", 0),  0xd1a8249d);
+        assert_eq!(crc32c_slice8(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc64_slice8(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
+
+        assert_eq!(crc8_slice16(b"Hello World!!", 0),   0x2f);
+        assert_eq!(crc16_slice16(b"Hello World!!", 0),  0xcba0);
+        assert_eq!(crc32_slice16(b"Hello World!!", 0),  0xd1a8249d);
+        assert_eq!(crc32c_slice16(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc64_slice16(b"Hello World!!", 0),  0xf5a8a397b60da2e1);
+
         assert_eq!(crc8_barret(b"Hello World!!", 0),   0x2f);
         assert_eq!(crc16_barret(b"Hello World!!", 0),  0xcba0);
         assert_eq!(crc32_barret(b"Hello World!!", 0),  0xd1a8249d);
@@ -499,6 +863,18 @@ mod test {
         assert_eq!(crc32c_small_table(b"World!", crc32c_small_table(b"Hello ", 0)), 0xfe6cf1dc);
         assert_eq!(crc64_small_table(b"World!", crc64_small_table(b"Hello ", 0)),   0x75045245c9ea6fe2);
 
+        assert_eq!(crc8_slice8(b"World!", crc8_slice8(b"Hello ", 0)),     0xb3);
+        assert_eq!(crc16_slice8(b"World!", crc16_slice8(b"Hello ", 0)),   0x0bbb);
+        assert_eq!(crc32_slice8(b"World!", crc32_slice8(b"Hello ", 0)),   0x1c291ca3);
+        assert_eq!(crc32c_slice8(b"World!", crc32c_slice8(b"Hello ", 0)), 0xfe6cf1dc);
+        assert_eq!(crc64_slice8(b"World!", crc64_slice8(b"Hello ", 0)),   0x75045245c9ea6fe2);
+
+        assert_eq!(crc8_slice16(b"World!", crc8_slice16(b"Hello ", 0)),     0xb3);
+        assert_eq!(crc16_slice16(b"World!", crc16_slice16(b"Hello ", 0)),   0x0bbb);
+        assert_eq!(crc32_slice16(b"World!", crc32_slice16(b"Hello ", 0)),   0x1c291ca3);
+        assert_eq!(crc32c_slice16(b"World!", crc32c_slice16(b"Hello ", 0)), 0xfe6cf1dc);
+        assert_eq!(crc64_slice16(b"World!", crc64_slice16(b"Hello ", 0)),   0x75045245c9ea6fe2);
+
         assert_eq!(crc8_barret(b"World!", crc8_barret(b"Hello ", 0)),     0xb3);
         assert_eq!(crc16_barret(b"World!", crc16_barret(b"Hello ", 0)),   0x0bbb);
         assert_eq!(crc32_barret(b"World!", crc32_barret(b"Hello ", 0)),   0x1c291ca3);
@@ -510,16 +886,25 @@ mod test {
     #[crc(polynomial=0x13, naive)] fn crc4_naive() {}
     #[crc(polynomial=0x13, table)] fn crc4_table() {}
     #[crc(polynomial=0x13, small_table)] fn crc4_small_table() {}
+    #[crc(polynomial=0x13, slice8)] fn crc4_slice8() {}
+    #[crc(polynomial=0x13, slice16)] fn crc4_slice16() {}
+    #[crc(polynomial=0x13, folding)] fn crc4_folding() {}
     #[crc(polynomial=0x13, barret)] fn crc4_barret() {}
 
     #[crc(polynomial=0x11e7, naive)] fn crc12_naive() {}
     #[crc(polynomial=0x11e7, table)] fn crc12_table() {}
     #[crc(polynomial=0x11e7, small_table)] fn crc12_small_table() {}
+    #[crc(polynomial=0x11e7, slice8)] fn crc12_slice8() {}
+    #[crc(polynomial=0x11e7, slice16)] fn crc12_slice16() {}
+    #[crc(polynomial=0x11e7, folding)] fn crc12_folding() {}
     #[crc(polynomial=0x11e7, barret)] fn crc12_barret() {}
 
     #[crc(polynomial=0x8002a9, naive)] fn crc23_naive() {}
     #[crc(polynomial=0x8002a9, table)] fn crc23_table() {}
     #[crc(polynomial=0x8002a9, small_table)] fn crc23_small_table() {}
+    #[crc(polynomial=0x8002a9, slice8)] fn crc23_slice8() {}
+    #[crc(polynomial=0x8002a9, slice16)] fn crc23_slice16() {}
+    #[crc(polynomial=0x8002a9, folding)] fn crc23_folding() {}
     #[crc(polynomial=0x8002a9, barret)] fn crc23_barret() {}
 
     #[test]
@@ -527,38 +912,101 @@ mod test {
         assert_eq!(crc4_naive(b"Hello World!", 0),       0x7);
         assert_eq!(crc4_table(b"Hello World!", 0),       0x7);
         assert_eq!(crc4_small_table(b"Hello World!", 0), 0x7);
+        assert_eq!(crc4_slice8(b"Hello World!", 0),      0x7);
+        assert_eq!(crc4_slice16(b"Hello World!", 0),     0x7);
         assert_eq!(crc4_barret(b"Hello World!", 0),      0x7);
 
         assert_eq!(crc12_naive(b"Hello World!", 0),       0x1d4);
         assert_eq!(crc12_table(b"Hello World!", 0),       0x1d4);
         assert_eq!(crc12_small_table(b"Hello World!", 0), 0x1d4);
+        assert_eq!(crc12_slice8(b"Hello World!", 0),      0x1d4);
+        assert_eq!(crc12_slice16(b"Hello World!", 0),     0x1d4);
         assert_eq!(crc12_barret(b"Hello World!", 0),      0x1d4);
 
         assert_eq!(crc23_naive(b"Hello World!", 0),       0x32da1c);
         assert_eq!(crc23_table(b"Hello World!", 0),       0x32da1c);
         assert_eq!(crc23_small_table(b"Hello World!", 0), 0x32da1c);
+        assert_eq!(crc23_slice8(b"Hello World!", 0),      0x32da1c);
+        assert_eq!(crc23_slice16(b"Hello World!", 0),     0x32da1c);
         assert_eq!(crc23_barret(b"Hello World!", 0),      0x32da1c);
 
         assert_eq!(crc4_naive(b"Hello World!!", 0),       0x1);
         assert_eq!(crc4_table(b"Hello World!!", 0),       0x1);
         assert_eq!(crc4_small_table(b"Hello World!!", 0), 0x1);
+        assert_eq!(crc4_slice8(b"Hello World!!", 0),      0x1);
+        assert_eq!(crc4_slice16(b"Hello World!!", 0),     0x1);
         assert_eq!(crc4_barret(b"Hello World!!", 0),      0x1);
 
         assert_eq!(crc12_naive(b"Hello World!!", 0),       0xb8d);
         assert_eq!(crc12_table(b"Hello World!!", 0),       0xb8d);
         assert_eq!(crc12_small_table(b"Hello World!!", 0), 0xb8d);
+        assert_eq!(crc12_slice8(b"Hello World!!", 0),      0xb8d);
+        assert_eq!(crc12_slice16(b"Hello World!!", 0),     0xb8d);
         assert_eq!(crc12_barret(b"Hello World!!", 0),      0xb8d);
 
         assert_eq!(crc23_naive(b"Hello World!!", 0),       0x11685a);
         assert_eq!(crc23_table(b"Hello World!!", 0),       0x11685a);
         assert_eq!(crc23_small_table(b"Hello World!!", 0), 0x11685a);
+        assert_eq!(crc23_slice8(b"Hello World!!", 0),      0x11685a);
+        assert_eq!(crc23_slice16(b"Hello World!!", 0),     0x11685a);
         assert_eq!(crc23_barret(b"Hello World!!", 0),      0x11685a);
     }
 
+    #[test]
+    fn crc_slice_lengths() {
+        // make sure we cover both full slices and remainders
+        let data = (0..100).map(|i| (i*37) as u8).collect::<Vec<u8>>();
+        for i in 0..data.len() {
+            let data = &data[..i];
+            assert_eq!(crc4_slice8(data, 0),   crc4_naive(data, 0));
+            assert_eq!(crc4_slice16(data, 0),  crc4_naive(data, 0));
+            assert_eq!(crc8_slice8(data, 0),   crc8_naive(data, 0));
+            assert_eq!(crc8_slice16(data, 0),  crc8_naive(data, 0));
+            assert_eq!(crc12_slice8(data, 0),  crc12_naive(data, 0));
+            assert_eq!(crc12_slice16(data, 0), crc12_naive(data, 0));
+            assert_eq!(crc16_slice8(data, 0),  crc16_naive(data, 0));
+            assert_eq!(crc16_slice16(data, 0), crc16_naive(data, 0));
+            assert_eq!(crc23_slice8(data, 0),  crc23_naive(data, 0));
+            assert_eq!(crc23_slice16(data, 0), crc23_naive(data, 0));
+            assert_eq!(crc32_slice8(data, 0),  crc32_naive(data, 0));
+            assert_eq!(crc32_slice16(data, 0), crc32_naive(data, 0));
+            assert_eq!(crc64_slice8(data, 0),  crc64_naive(data, 0));
+            assert_eq!(crc64_slice16(data, 0), crc64_naive(data, 0));
+            assert_eq!(crc32_slice8_unreflected(data, 0),  crc32_naive_unreflected(data, 0));
+            assert_eq!(crc32_slice16_unreflected(data, 0), crc32_naive_unreflected(data, 0));
+        }
+    }
+
+    #[test]
+    fn crc_folding_lengths() {
+        // make sure we cover multiple chunks and remainders
+        let data = (0..300).map(|i| (i*37) as u8).collect::<Vec<u8>>();
+        for i in 0..data.len() {
+            let data = &data[..i];
+            assert_eq!(crc4_folding(data, 0),  crc4_naive(data, 0));
+            assert_eq!(crc8_folding(data, 0),  crc8_naive(data, 0));
+            assert_eq!(crc12_folding(data, 0), crc12_naive(data, 0));
+            assert_eq!(crc16_folding(data, 0), crc16_naive(data, 0));
+            assert_eq!(crc23_folding(data, 0), crc23_naive(data, 0));
+            assert_eq!(crc32_folding(data, 0), crc32_naive(data, 0));
+            assert_eq!(crc32c_folding(data, 0), crc32c_naive(data, 0));
+            assert_eq!(crc64_folding(data, 0), crc64_naive(data, 0));
+            assert_eq!(crc32_folding_unreflected(data, 0), crc32_naive_unreflected(data, 0));
+            assert_eq!(crc32_folding_uninverted(data, 0),  crc32_naive_uninverted(data, 0));
+
+            // with a non-zero initial crc
+            assert_eq!(crc32_folding(data, 0x12345678), crc32_naive(data, 0x12345678));
+            assert_eq!(crc32_folding_unreflected(data, 0x12345678), crc32_naive_unreflected(data, 0x12345678));
+        }
+    }
+
     // bit reflected 
     #[crc(polynomial=0x104c11db7, naive, reflected=false)] fn crc32_naive_unreflected() {}
     #[crc(polynomial=0x104c11db7, table, reflected=false)] fn crc32_table_unreflected() {}
     #[crc(polynomial=0x104c11db7, small_table, reflected=false)] fn crc32_small_table_unreflected() {}
+    #[crc(polynomial=0x104c11db7, slice8, reflected=false)] fn crc32_slice8_unreflected() {}
+    #[crc(polynomial=0x104c11db7, slice16, reflected=false)] fn crc32_slice16_unreflected() {}
+    #[crc(polynomial=0x104c11db7, folding, reflected=false)] fn crc32_folding_unreflected() {}
     #[crc(polynomial=0x104c11db7, barret, reflected=false)] fn crc32_barret_unreflected() {}
 
     #[test]
@@ -566,6 +1014,9 @@ mod test {
         assert_eq!(crc32_naive_unreflected(b"Hello World!", 0),       0x6b1a7cae);
         assert_eq!(crc32_table_unreflected(b"Hello World!", 0),       0x6b1a7cae);
         assert_eq!(crc32_small_table_unreflected(b"Hello World!", 0), 0x6b1a7cae);
+        assert_eq!(crc32_slice8_unreflected(b"Hello World!", 0),      0x6b1a7cae);
+        assert_eq!(crc32_slice16_unreflected(b"Hello World!", 0),     0x6b1a7cae);
+        assert_eq!(crc32_folding_unreflected(b"Hello World!", 0),     0x6b1a7cae);
         assert_eq!(crc32_barret_unreflected(b"Hello World!", 0),      0x6b1a7cae);
     }
 
@@ -573,6 +1024,9 @@ mod test {
     #[crc(polynomial=0x104c11db7, naive, xor=0)] fn crc32_naive_uninverted() {}
     #[crc(polynomial=0x104c11db7, table, xor=0)] fn crc32_table_uninverted() {}
     #[crc(polynomial=0x104c11db7, small_table, xor=0)] fn crc32_small_table_uninverted() {}
+    #[crc(polynomial=0x104c11db7, slice8, xor=0)] fn crc32_slice8_uninverted() {}
+    #[crc(polynomial=0x104c11db7, slice16, xor=0)] fn crc32_slice16_uninverted() {}
+    #[crc(polynomial=0x104c11db7, folding, xor=0)] fn crc32_folding_uninverted() {}
     #[crc(polynomial=0x104c11db7, barret, xor=0)] fn crc32_barret_uninverted() {}
 
     #[test]
@@ -580,9 +1034,576 @@ mod test {
         assert_eq!(crc32_naive_uninverted(b"Hello World!", 0),       0x67fcdacc);
         assert_eq!(crc32_table_uninverted(b"Hello World!", 0),       0x67fcdacc);
         assert_eq!(crc32_small_table_uninverted(b"Hello World!", 0), 0x67fcdacc);
+        assert_eq!(crc32_slice8_uninverted(b"Hello World!", 0),      0x67fcdacc);
+        assert_eq!(crc32_slice16_uninverted(b"Hello World!", 0),     0x67fcdacc);
+        assert_eq!(crc32_folding_uninverted(b"Hello World!", 0),     0x67fcdacc);
         assert_eq!(crc32_barret_uninverted(b"Hello World!", 0),      0x67fcdacc);
     }
 
+    #[test]
+    fn crc_combine() {
+        assert_eq!(crc8_combine(crc8(b"Hello ", 0), crc8(b"World!", 0), 6),       0xb3);
+        assert_eq!(crc16_combine(crc16(b"Hello ", 0), crc16(b"World!", 0), 6),    0x0bbb);
+        assert_eq!(crc32_combine(crc32(b"Hello ", 0), crc32(b"World!", 0), 6),    0x1c291ca3);
+        assert_eq!(crc32c_combine(crc32c(b"Hello ", 0), crc32c(b"World!", 0), 6), 0xfe6cf1dc);
+        assert_eq!(crc64_combine(crc64(b"Hello ", 0), crc64(b"World!", 0), 6),    0x75045245c9ea6fe2);
+
+        // odd sizes/unreflected/uninverted
+        assert_eq!(crc4_naive_combine(crc4_naive(b"Hello ", 0), crc4_naive(b"World!!", 0), 7),       0x1);
+        assert_eq!(crc12_naive_combine(crc12_naive(b"Hello ", 0), crc12_naive(b"World!!", 0), 7),    0xb8d);
+        assert_eq!(crc23_naive_combine(crc23_naive(b"Hello ", 0), crc23_naive(b"World!!", 0), 7),    0x11685a);
+        assert_eq!(crc32_naive_unreflected_combine(
+            crc32_naive_unreflected(b"Hello ", 0),
+            crc32_naive_unreflected(b"World!", 0), 6),
+            0x6b1a7cae);
+        assert_eq!(crc32_naive_uninverted_combine(
+            crc32_naive_uninverted(b"Hello ", 0),
+            crc32_naive_uninverted(b"World!", 0), 6),
+            0x67fcdacc);
+
+        // empty data
+        assert_eq!(crc32c_combine(0xfe6cf1dc, crc32c(b"", 0), 0), 0xfe6cf1dc);
+        assert_eq!(crc32c_combine(crc32c(b"", 0), 0xfe6cf1dc, 12), 0xfe6cf1dc);
+
+        // larger data
+        let data = (0..10000).map(|i| i as u8).collect::<Vec<u8>>();
+        for split in [0, 1, 7, 255, 4096, 9999, 10000] {
+            assert_eq!(
+                crc32_combine(
+                    crc32(&data[..split], 0),
+                    crc32(&data[split..], 0),
+                    data.len()-split
+                ),
+                crc32(&data, 0)
+            );
+        }
+    }
+
+    // hardware instructions, if available
+    #[cfg(all(target_arch="aarch64", target_feature="crc"))]
+    #[crc(polynomial=0x104c11db7, hardware)] fn crc32_hardware() {}
+    #[cfg(any(
+        all(target_arch="x86_64", target_feature="sse4.2"),
+        all(target_arch="aarch64", target_feature="crc")
+    ))]
+    #[crc(polynomial=0x11edc6f41, hardware)] fn crc32c_hardware() {}
+    #[cfg(any(
+        all(target_arch="x86_64", target_feature="sse4.2"),
+        all(target_arch="aarch64", target_feature="crc")
+    ))]
+    #[crc(polynomial=0x11edc6f41, hardware, xor=0)] fn crc32c_hardware_uninverted() {}
+    #[crc(polynomial=0x11edc6f41, naive, xor=0)] fn crc32c_naive_uninverted() {}
+    #[cfg(feature="std")]
+    #[crc(polynomial=0x11edc6f41, table, hardware_detect)] fn crc32c_hardware_detect() {}
+    #[cfg(feature="std")]
+    #[crc(polynomial=0x11edc6f41, table, hardware_detect, xor=0)] fn crc32c_hardware_detect_uninverted() {}
+    #[cfg(all(feature="std", target_arch="aarch64"))]
+    #[crc(polynomial=0x104c11db7, table, hardware_detect)] fn crc32_hardware_detect() {}
+
+    #[cfg(all(target_arch="aarch64", target_feature="crc"))]
+    #[test]
+    fn crc_hardware_crc32() {
+        assert_eq!(crc32_hardware(b"Hello World!", 0), 0x1c291ca3);
+        assert_eq!(crc32_hardware(b"Hello World!!", 0), 0xd1a8249d);
+        assert_eq!(crc32_hardware(b"World!", crc32_hardware(b"Hello ", 0)), 0x1c291ca3);
+    }
+
+    #[cfg(any(
+        all(target_arch="x86_64", target_feature="sse4.2"),
+        all(target_arch="aarch64", target_feature="crc")
+    ))]
+    #[test]
+    fn crc_hardware_crc32c() {
+        assert_eq!(crc32c_hardware(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc32c_hardware(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc32c_hardware(b"World!", crc32c_hardware(b"Hello ", 0)), 0xfe6cf1dc);
+
+        let data = (0..100).map(|i| (i*37) as u8).collect::<Vec<u8>>();
+        for i in 0..data.len() {
+            assert_eq!(crc32c_hardware(&data[..i], 0), crc32c_naive(&data[..i], 0));
+            assert_eq!(
+                crc32c_hardware_uninverted(&data[..i], 0),
+                crc32c_naive_uninverted(&data[..i], 0)
+            );
+        }
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_hardware_detect() {
+        assert_eq!(crc32c_hardware_detect(b"Hello World!", 0), 0xfe6cf1dc);
+        assert_eq!(crc32c_hardware_detect(b"Hello World!!", 0), 0x1ec51c06);
+        assert_eq!(crc32c_hardware_detect(b"World!", crc32c_hardware_detect(b"Hello ", 0)), 0xfe6cf1dc);
+
+        let data = (0..100).map(|i| (i*37) as u8).collect::<Vec<u8>>();
+        for i in 0..data.len() {
+            assert_eq!(crc32c_hardware_detect(&data[..i], 0), crc32c_naive(&data[..i], 0));
+            assert_eq!(
+                crc32c_hardware_detect_uninverted(&data[..i], 0),
+                crc32c_naive_uninverted(&data[..i], 0)
+            );
+        }
+
+        #[cfg(target_arch="aarch64")]
+        for i in 0..data.len() {
+            assert_eq!(crc32_hardware_detect(&data[..i], 0), crc32(&data[..i], 0));
+        }
+    }
+
+    #[test]
+    fn crc_rolling() {
+        let data = (0..1000).map(|i| (i*37 + i/7) as u8).collect::<Vec<u8>>();
+        for window in [1, 4, 16, 100] {
+            let table8 = crc8_rolling_table(window);
+            let table12 = crc12_naive_rolling_table(window);
+            let table32 = crc32_rolling_table(window);
+            let table32_unreflected = crc32_naive_unreflected_rolling_table(window);
+            let table64 = crc64_rolling_table(window);
+
+            let mut crc8_ = crc8(&data[..window], 0);
+            let mut crc12 = crc12_naive(&data[..window], 0);
+            let mut crc32_ = crc32(&data[..window], 0);
+            let mut crc32_unreflected = crc32_naive_unreflected(&data[..window], 0);
+            let mut crc64_ = crc64(&data[..window], 0);
+            for i in window..data.len() {
+                crc8_ = crc8_roll(crc8_, &table8, data[i-window], data[i]);
+                crc12 = crc12_naive_roll(crc12, &table12, data[i-window], data[i]);
+                crc32_ = crc32_roll(crc32_, &table32, data[i-window], data[i]);
+                crc32_unreflected = crc32_naive_unreflected_roll(
+                    crc32_unreflected, &table32_unreflected, data[i-window], data[i]
+                );
+                crc64_ = crc64_roll(crc64_, &table64, data[i-window], data[i]);
+
+                let window = &data[i+1-window..i+1];
+                assert_eq!(crc8_, crc8(window, 0));
+                assert_eq!(crc12, crc12_naive(window, 0));
+                assert_eq!(crc32_, crc32(window, 0));
+                assert_eq!(crc32_unreflected, crc32_naive_unreflected(window, 0));
+                assert_eq!(crc64_, crc64(window, 0));
+            }
+        }
+    }
+
+    #[test]
+    fn crc_patch() {
+        let mut data = (0..1000).map(|i| (i*37 + i/7) as u8).collect::<Vec<u8>>();
+        let mut crc8_ = crc8(&data, 0);
+        let mut crc12 = crc12_naive(&data, 0);
+        let mut crc32_ = crc32(&data, 0);
+        let mut crc32_unreflected = crc32_naive_unreflected(&data, 0);
+        let mut crc64_ = crc64(&data, 0);
+        for (offset, len) in [(0, 1), (0, 1000), (999, 1), (100, 200), (500, 0), (3, 70)] {
+            let old = data[offset..offset+len].to_vec();
+            let new = (0..len).map(|i| (i*13) as u8).collect::<Vec<u8>>();
+            data[offset..offset+len].copy_from_slice(&new);
+
+            crc8_ = crc8_patch(crc8_, offset, &old, &new, data.len());
+            crc12 = crc12_naive_patch(crc12, offset, &old, &new, data.len());
+            crc32_ = crc32_patch(crc32_, offset, &old, &new, data.len());
+            crc32_unreflected = crc32_naive_unreflected_patch(crc32_unreflected, offset, &old, &new, data.len());
+            crc64_ = crc64_patch(crc64_, offset, &old, &new, data.len());
+
+            assert_eq!(crc8_, crc8(&data, 0));
+            assert_eq!(crc12, crc12_naive(&data, 0));
+            assert_eq!(crc32_, crc32(&data, 0));
+            assert_eq!(crc32_unreflected, crc32_naive_unreflected(&data, 0));
+            assert_eq!(crc64_, crc64(&data, 0));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn crc_patch_overflow() {
+        crc32_patch(0, usize::MAX, b"a", b"b", 10);
+    }
+
+    // CAN's 15-bit CRC
+    #[crc(polynomial=0xc599, reflected=false, xor=0)] fn crc15_can() {}
+    #[crc(polynomial=0x104c11db7, xor=0)] fn crc32_uninverted() {}
+
+    #[test]
+    fn crc_bits() {
+        // bit-at-a-time reference implementation
+        fn reference(
+            bits: impl Iterator<Item=bool>,
+            polynomial: u128,
+            width: usize
+        ) -> u128 {
+            let mut crc = 0;
+            for bit in bits.chain(core::iter::repeat_n(false, width)) {
+                crc = (crc << 1) | u128::from(bit);
+                if crc & (1 << width) != 0 {
+                    crc ^= polynomial;
+                }
+            }
+            crc
+        }
+
+        let data = b"Hello World!";
+        for bits in 0..8*data.len() {
+            // whole bytes should match our byte-level CRCs
+            if bits % 8 == 0 {
+                assert_eq!(crc8_bits(data, bits, 0),   crc8(&data[..bits/8], 0));
+                assert_eq!(crc12_naive_bits(data, bits, 0), crc12_naive(&data[..bits/8], 0));
+                assert_eq!(crc32_bits(data, bits, 0),  crc32(&data[..bits/8], 0));
+                assert_eq!(crc32_naive_unreflected_bits(data, bits, 0), crc32_naive_unreflected(&data[..bits/8], 0));
+                assert_eq!(crc64_bits(data, bits, 0),  crc64(&data[..bits/8], 0));
+            }
+
+            let msb_bits = (0..bits).map(|i| (data[i/8] >> (7-i%8)) & 1 != 0);
+            let lsb_bits = (0..bits).map(|i| (data[i/8] >> (i%8)) & 1 != 0);
+            assert_eq!(
+                u128::from(crc15_can_bits(data, bits, 0)),
+                reference(msb_bits, 0xc599, 15)
+            );
+            assert_eq!(
+                crc32_uninverted_bits(data, bits, 0),
+                (reference(lsb_bits, 0x104c11db7, 32) as u32).reverse_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn crc_correct() {
+        let data = b"Hello World!".to_vec();
+        let crc8_ = crc8(&data, 0);
+        let crc16_ = crc16(&data, 0);
+        let crc32_ = crc32(&data, 0);
+        let crc32c_ = crc32c(&data, 0);
+        let crc64_ = crc64(&data, 0);
+        let crc32_unreflected_ = crc32_naive_unreflected(&data, 0);
+
+        // no errors
+        let mut copy = data.clone();
+        assert_eq!(crc32_correct(&mut copy, crc32_), Ok(0));
+        assert_eq!(copy, data);
+
+        // every possible single-bit error in the data
+        for i in 0..8*data.len() {
+            let mut corrupted = data.clone();
+            corrupted[i/8] ^= 1 << (i%8);
+
+            let mut copy = corrupted.clone();
+            assert_eq!(crc8_correct(&mut copy, crc8_), Ok(1));
+            assert_eq!(copy, data);
+            let mut copy = corrupted.clone();
+            assert_eq!(crc16_correct(&mut copy, crc16_), Ok(1));
+            assert_eq!(copy, data);
+            let mut copy = corrupted.clone();
+            assert_eq!(crc32_correct(&mut copy, crc32_), Ok(1));
+            assert_eq!(copy, data);
+            let mut copy = corrupted.clone();
+            assert_eq!(crc32c_correct(&mut copy, crc32c_), Ok(1));
+            assert_eq!(copy, data);
+            let mut copy = corrupted.clone();
+            assert_eq!(crc64_correct(&mut copy, crc64_), Ok(1));
+            assert_eq!(copy, data);
+            let mut copy = corrupted.clone();
+            assert_eq!(crc32_naive_unreflected_correct(&mut copy, crc32_unreflected_), Ok(1));
+            assert_eq!(copy, data);
+        }
+
+        // every possible single-bit error in the CRC
+        for i in 0..32 {
+            let mut copy = data.clone();
+            assert_eq!(crc32_correct(&mut copy, crc32_ ^ (1 << i)), Ok(1));
+            assert_eq!(copy, data);
+        }
+
+        // too many errors
+        let mut copy = data.clone();
+        copy[0] ^= 0x01;
+        copy[5] ^= 0x10;
+        assert_eq!(crc32_correct(&mut copy, crc32_), Err(Error::TooManyErrors));
+    }
+
+    #[test]
+    fn crc_correct_burst() {
+        let data = b"Hello World!".to_vec();
+        let crc32_ = crc32(&data, 0);
+        let crc64_ = crc64(&data, 0);
+        let crc32_unreflected_ = crc32_naive_unreflected(&data, 0);
+
+        // no errors
+        let mut copy = data.clone();
+        assert_eq!(crc32_correct_burst(&mut copy, crc32_, 8), Ok(0));
+        assert_eq!(copy, data);
+
+        // flip bits in the order they are transmitted, reflected CRCs
+        // transmit lsb first
+        fn flip(data: &mut [u8], t: usize, reflected: bool) {
+            if reflected {
+                data[t/8] ^= 1 << (t%8);
+            } else {
+                data[t/8] ^= 0x80 >> (t%8);
+            }
+        }
+
+        // bursts of up to 8 bits at every position in the data
+        for burst in [0b1u32, 0b11, 0b101, 0b1001, 0b11011, 0b110101, 0b1000001, 0b11111111] {
+            let len = 32 - usize::try_from(burst.leading_zeros()).unwrap();
+            for t in 0..8*data.len()-len+1 {
+                for reflected in [true, false] {
+                    let mut corrupted = data.clone();
+                    for k in 0..len {
+                        if burst & (1 << k) != 0 {
+                            flip(&mut corrupted, t+k, reflected);
+                        }
+                    }
+
+                    let mut copy = corrupted.clone();
+                    if reflected {
+                        assert_eq!(crc32_correct_burst(&mut copy, crc32_, 8), Ok(usize::try_from(burst.count_ones()).unwrap()));
+                        assert_eq!(copy, data);
+                        let mut copy = corrupted.clone();
+                        assert_eq!(crc64_correct_burst(&mut copy, crc64_, 8), Ok(usize::try_from(burst.count_ones()).unwrap()));
+                        assert_eq!(copy, data);
+                    } else {
+                        assert_eq!(crc32_naive_unreflected_correct_burst(&mut copy, crc32_unreflected_, 8), Ok(usize::try_from(burst.count_ones()).unwrap()));
+                        assert_eq!(copy, data);
+                    }
+                }
+            }
+        }
+
+        // bursts in the CRC, and spanning the data and the CRC
+        for i in 0..32-2 {
+            let mut copy = data.clone();
+            assert_eq!(crc32_correct_burst(&mut copy, crc32_ ^ (0b111 << i), 8), Ok(3));
+            assert_eq!(copy, data);
+        }
+        let mut corrupted = data.clone();
+        corrupted[data.len()-1] ^= 0x80;
+        let mut copy = corrupted.clone();
+        assert_eq!(crc32_correct_burst(&mut copy, crc32_ ^ 0x1, 8), Ok(2));
+        assert_eq!(copy, data);
+
+        // a burst longer than we're looking for is too many errors
+        let mut copy = data.clone();
+        copy[3] ^= 0x81;
+        copy[4] ^= 0x01;
+        assert_eq!(crc32_correct_burst(&mut copy, crc32_, 8), Err(Error::TooManyErrors));
+    }
+
+    #[cfg(feature="rayon")]
+    #[test]
+    fn crc_parallel() {
+        let data = (0..1024*1024).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        for len in [0, 1, 64*1024-1, 64*1024, 64*1024+1, 1024*1024] {
+            assert_eq!(crc8_parallel(&data[..len], 0),   crc8(&data[..len], 0));
+            assert_eq!(crc16_parallel(&data[..len], 0),  crc16(&data[..len], 0));
+            assert_eq!(crc32_parallel(&data[..len], 0),  crc32(&data[..len], 0));
+            assert_eq!(crc32c_parallel(&data[..len], 0), crc32c(&data[..len], 0));
+            assert_eq!(crc64_parallel(&data[..len], 0),  crc64(&data[..len], 0));
+            assert_eq!(crc32_naive_unreflected_parallel(&data[..len], 0), crc32_naive_unreflected(&data[..len], 0));
+
+            // continuing a previous CRC
+            assert_eq!(
+                crc32c_parallel(&data[..len], crc32c(b"Hello World!", 0)),
+                crc32c(&data[..len], crc32c(b"Hello World!", 0))
+            );
+        }
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_io() {
+        use std::io::{self, Read, Write};
+
+        let data = (0..100000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+
+        // copy through both a reader and writer
+        let mut reader = CrcReader::new(&data[..], crc32c);
+        let mut writer = CrcWriter::new(Vec::new(), crc32c);
+        io::copy(&mut reader, &mut writer).unwrap();
+        assert_eq!(reader.crc(), crc32c(&data, 0));
+        assert_eq!(writer.crc(), crc32c(&data, 0));
+        assert_eq!(writer.into_inner(), data);
+
+        // small reads
+        let mut reader = CrcReader::new(&data[..], crc64);
+        let mut buf = [0; 7];
+        while reader.read(&mut buf).unwrap() != 0 {}
+        assert_eq!(reader.crc(), crc64(&data, 0));
+
+        // small writes, continuing a previous CRC
+        let mut writer = CrcWriter::with_crc(Vec::new(), crc8, crc8(b"Hello World!", 0));
+        for chunk in data.chunks(7) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(writer.crc(), crc8(&data, crc8(b"Hello World!", 0)));
+    }
+
+    #[test]
+    fn crc_vectored() {
+        let data = (0..1000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let bufs = [&data[..0], &data[..1], &data[1..100], &data[100..100], &data[100..]];
+        assert_eq!(crc8_vectored(&bufs, 0),   crc8(&data, 0));
+        assert_eq!(crc16_vectored(&bufs, 0),  crc16(&data, 0));
+        assert_eq!(crc32_vectored(&bufs, 0),  crc32(&data, 0));
+        assert_eq!(crc32c_vectored(&bufs, 0), crc32c(&data, 0));
+        assert_eq!(crc64_vectored(&bufs, 0),  crc64(&data, 0));
+        assert_eq!(crc32_naive_unreflected_vectored(&bufs, 0), crc32_naive_unreflected(&data, 0));
+
+        let empty: [&[u8]; 0] = [];
+        assert_eq!(crc32c_vectored(&empty, 0), 0);
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn crc_vectored_io_slices() {
+        use std::io::IoSlice;
+
+        let data = (0..1000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let bufs = [IoSlice::new(&data[..10]), IoSlice::new(&data[10..])];
+        assert_eq!(crc32c_vectored(&bufs, 0), crc32c(&data, 0));
+    }
+
+    #[test]
+    fn crc_const() {
+        const CRC8: u8 = crc8_const(b"Hello World!", 0);
+        const CRC16: u16 = crc16_const(b"Hello World!", 0);
+        const CRC32: u32 = crc32_const(b"Hello World!", 0);
+        const CRC32C: u32 = crc32c_const(b"Hello World!", 0);
+        const CRC64: u64 = crc64_const(b"Hello World!", 0);
+        assert_eq!(CRC8,   0xb3);
+        assert_eq!(CRC16,  0x0bbb);
+        assert_eq!(CRC32,  0x1c291ca3);
+        assert_eq!(CRC32C, 0xfe6cf1dc);
+        assert_eq!(CRC64,  0x75045245c9ea6fe2);
+
+        let data = (0..1000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        for len in [0, 1, 2, 7, 8, 9, 100, 1000] {
+            assert_eq!(crc4_naive_const(&data[..len], 0),  crc4_naive(&data[..len], 0));
+            assert_eq!(crc12_naive_const(&data[..len], 0), crc12_naive(&data[..len], 0));
+            assert_eq!(crc23_naive_const(&data[..len], 0), crc23_naive(&data[..len], 0));
+            assert_eq!(crc32_naive_unreflected_const(&data[..len], 0), crc32_naive_unreflected(&data[..len], 0));
+            assert_eq!(crc32_naive_uninverted_const(&data[..len], 0), crc32_naive_uninverted(&data[..len], 0));
+            assert_eq!(crc15_can_const(&data[..len], 0), crc15_can(&data[..len], 0));
+
+            // continuing a previous CRC
+            assert_eq!(
+                crc32c_const(&data[..len], 0x12345678),
+                crc32c(&data[..len], 0x12345678)
+            );
+        }
+    }
+
+    #[test]
+    fn crc_verify() {
+        let data = (0..100).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        for len in [0, 1, 7, 8, 9, 100] {
+            // bit-reflected CRCs are appended little-endian
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&crc32c(&buf, 0).to_le_bytes());
+            assert!(crc32c_verify(&buf));
+            buf[0] ^= 0x01;
+            assert!(!crc32c_verify(&buf));
+
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&crc64(&buf, 0).to_le_bytes());
+            assert!(crc64_verify(&buf));
+            let last = buf.len()-1;
+            buf[last] ^= 0x80;
+            assert!(!crc64_verify(&buf));
+
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&crc12_naive(&buf, 0).to_le_bytes());
+            assert!(crc12_naive_verify(&buf));
+            buf[1] ^= 0x08;
+            assert!(!crc12_naive_verify(&buf));
+
+            // non-reflected CRCs are appended big-endian, aligned to the
+            // most-significant bit
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&crc32_naive_unreflected(&buf, 0).to_be_bytes());
+            assert!(crc32_naive_unreflected_verify(&buf));
+            buf[0] ^= 0x01;
+            assert!(!crc32_naive_unreflected_verify(&buf));
+
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&(crc15_can(&buf, 0) << 1).to_be_bytes());
+            assert!(crc15_can_verify(&buf));
+            buf[1] ^= 0x02;
+            assert!(!crc15_can_verify(&buf));
+
+            let mut buf = data[..len].to_vec();
+            buf.extend_from_slice(&crc82_darc(&buf, 0).to_le_bytes()[..11]);
+            assert!(crc82_darc_verify(&buf));
+            buf[0] ^= 0x01;
+            assert!(!crc82_darc_verify(&buf));
+        }
+
+        // too short
+        assert!(!crc32c_verify(&[0; 3]));
+    }
+
+    // CRCs wider than 64 bits
+    #[crc(polynomial=0x4308c0111011401440411, xor=0)] fn crc82_darc() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, naive)] fn crc82_darc_naive() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, table)] fn crc82_darc_table() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, small_table)] fn crc82_darc_small_table() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, slice8)] fn crc82_darc_slice8() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, slice16)] fn crc82_darc_slice16() {}
+    #[crc(polynomial=0x4308c0111011401440411, xor=0, barret)] fn crc82_darc_barret() {}
+
+    #[crc(polynomial=0x4308c0111011401440411, naive, reflected=false)] fn crc82_naive_unreflected() {}
+    #[crc(polynomial=0x4308c0111011401440411, table, reflected=false)] fn crc82_table_unreflected() {}
+    #[crc(polynomial=0x4308c0111011401440411, small_table, reflected=false)] fn crc82_small_table_unreflected() {}
+    #[crc(polynomial=0x4308c0111011401440411, slice8, reflected=false)] fn crc82_slice8_unreflected() {}
+    #[crc(polynomial=0x4308c0111011401440411, slice16, reflected=false)] fn crc82_slice16_unreflected() {}
+    #[crc(polynomial=0x4308c0111011401440411, barret, reflected=false)] fn crc82_barret_unreflected() {}
+
+    #[test]
+    fn crc_wide() {
+        // CRC-82/DARC check value
+        assert_eq!(crc82_darc(b"123456789", 0),              0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_naive(b"123456789", 0),        0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_table(b"123456789", 0),        0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_small_table(b"123456789", 0),  0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_slice8(b"123456789", 0),       0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_slice16(b"123456789", 0),      0x09ea83f625023801fd612);
+        assert_eq!(crc82_darc_barret(b"123456789", 0),       0x09ea83f625023801fd612);
+
+        let data = (0..1000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        for len in 0..100 {
+            let expected = crc82_darc_naive(&data[..len], 0);
+            assert_eq!(crc82_darc(&data[..len], 0),             expected);
+            assert_eq!(crc82_darc_table(&data[..len], 0),       expected);
+            assert_eq!(crc82_darc_small_table(&data[..len], 0), expected);
+            assert_eq!(crc82_darc_slice8(&data[..len], 0),      expected);
+            assert_eq!(crc82_darc_slice16(&data[..len], 0),     expected);
+            assert_eq!(crc82_darc_barret(&data[..len], 0),      expected);
+
+            let expected = crc82_naive_unreflected(&data[..len], 0);
+            assert_eq!(crc82_table_unreflected(&data[..len], 0),       expected);
+            assert_eq!(crc82_small_table_unreflected(&data[..len], 0), expected);
+            assert_eq!(crc82_slice8_unreflected(&data[..len], 0),      expected);
+            assert_eq!(crc82_slice16_unreflected(&data[..len], 0),     expected);
+            assert_eq!(crc82_barret_unreflected(&data[..len], 0),      expected);
+        }
+
+        // extra functions should also work
+        let a = crc82_darc(&data[..400], 0);
+        let b = crc82_darc(&data[400..], 0);
+        assert_eq!(crc82_darc_combine(a, b, 600), crc82_darc(&data, 0));
+        let a = crc82_naive_unreflected(&data[..400], 0);
+        let b = crc82_naive_unreflected(&data[400..], 0);
+        assert_eq!(crc82_naive_unreflected_combine(a, b, 600), crc82_naive_unreflected(&data, 0));
+
+        assert_eq!(crc82_darc_bits(&data, 8*100, 0), crc82_darc(&data[..100], 0));
+        assert_eq!(crc82_darc_const(&data, 0), crc82_darc(&data, 0));
+        assert_eq!(crc82_naive_unreflected_const(&data, 0), crc82_naive_unreflected(&data, 0));
+
+        // note CRC-82/DARC is designed for 272-bit blocks
+        let crc = crc82_darc(&data[..20], 0);
+        let mut corrupted = data[..20].to_vec();
+        corrupted[12] ^= 0x10;
+        assert_eq!(crc82_darc_correct(&mut corrupted, crc), Ok(1));
+        assert_eq!(&corrupted, &data[..20]);
+    }
+
     // all CRC params
     #[crc(
         polynomial=0x104c11db7,
@@ -0,0 +1,206 @@
+//! ## Exporting tables to C
+//!
+//! Many of gf256's types and functions are built on precomputed tables, such
+//! as the log/antilog tables of Galois-field types in `table` mode, and the
+//! byte-wise tables of CRCs. Sometimes it's useful to share these with code
+//! written in other languages, such as firmware written in C, to make sure
+//! both stay bit-compatible.
+//!
+//! [`write_c_array`](crate::export::write_c_array) writes any array of unsigned
+//! integers as a C array, in a stable format:
+//!
+//! ``` rust
+//! use gf256::export::write_c_array;
+//!
+//! let mut header = String::new();
+//! write_c_array(&mut header, "table", &[0x00u8, 0x07, 0x0e, 0x09]).unwrap();
+//! assert_eq!(header, "\
+//!     static const uint8_t table[4] = {\n\
+//!     \x20   0x00, 0x07, 0x0e, 0x09,\n\
+//!     };\n");
+//! ```
+//!
+//! This includes the lookup tables of our CRC functions:
+//!
+//! ``` rust
+//! # #[cfg(feature="crc")] {
+//! use gf256::crc::crc32c_byte_table;
+//! use gf256::export::write_c_array;
+//!
+//! let mut header = String::new();
+//! write_c_array(&mut header, "crc32c_table", &crc32c_byte_table()).unwrap();
+//! assert!(header.starts_with("\
+//!     static const uint32_t crc32c_table[256] = {\n\
+//!     \x20   0x00000000, 0xf26b8303, 0xe13b70f7, 0x1350f3f4,"));
+//! # }
+//! ```
+//!
+//! And the log/antilog tables of Galois-field types in `table` mode:
+//!
+//! ``` rust
+//! # #[cfg(not(any(feature="no-tables", feature="small-tables")))] {
+//! use ::gf256::gf::gf256;
+//! use ::gf256::export::write_c_array;
+//!
+//! let mut header = String::new();
+//! write_c_array(&mut header, "gf256_log_table", &gf256::LOG_TABLE).unwrap();
+//! write_c_array(&mut header, "gf256_exp_table", &gf256::EXP_TABLE).unwrap();
+//! # }
+//! ```
+
+use core::fmt;
+
+
+/// Unsigned integer types that have a C equivalent
+///
+/// Note this is intentionally not implemented for `u128`. C has no standard
+/// 128-bit integer type, and even where `unsigned __int128` is available as
+/// an extension, there is no way to write a 128-bit integer literal, so
+/// 128-bit tables can't be written as a C array initializer:
+///
+/// ``` rust,compile_fail
+/// # use gf256::export::write_c_array;
+/// let mut header = String::new();
+/// write_c_array(&mut header, "table", &[0u128, 1, 2]).unwrap();
+/// ```
+///
+/// If you need 128-bit values in C, split them into pairs of `u64`s first,
+/// in whatever order your C code expects.
+///
+pub trait CType: Copy {
+    /// The name of the equivalent C type, from stdint.h
+    const C_TYPE: &'static str;
+
+    /// Convert to a u64 for formatting
+    fn to_u64(self) -> u64;
+}
+
+impl CType for u8 {
+    const C_TYPE: &'static str = "uint8_t";
+    fn to_u64(self) -> u64 { u64::from(self) }
+}
+
+impl CType for u16 {
+    const C_TYPE: &'static str = "uint16_t";
+    fn to_u64(self) -> u64 { u64::from(self) }
+}
+
+impl CType for u32 {
+    const C_TYPE: &'static str = "uint32_t";
+    fn to_u64(self) -> u64 { u64::from(self) }
+}
+
+impl CType for u64 {
+    const C_TYPE: &'static str = "uint64_t";
+    fn to_u64(self) -> u64 { self }
+}
+
+
+/// Write an array as a C array definition.
+///
+/// Values are written in hex, padded to the width of the type, with
+/// roughly 64 columns of values per line. 64-bit values are given a `ULL`
+/// suffix so they are valid in C89.
+///
+/// ``` rust
+/// # use gf256::export::write_c_array;
+/// let mut header = String::new();
+/// write_c_array(&mut header, "table", &[0x0000u16, 0x1021, 0x2042]).unwrap();
+/// assert_eq!(header, "\
+///     static const uint16_t table[3] = {\n\
+///     \x20   0x0000, 0x1021, 0x2042,\n\
+///     };\n");
+/// ```
+///
+/// See the [module-level documentation](self) for more info.
+///
+pub fn write_c_array<W: fmt::Write, T: CType>(
+    w: &mut W,
+    name: &str,
+    array: &[T]
+) -> fmt::Result {
+    let digits = 2*core::mem::size_of::<T>();
+    let suffix = if digits > 8 { "ULL" } else { "" };
+    let per_line = 64 / (digits+2);
+
+    writeln!(w, "static const {} {}[{}] = {{", T::C_TYPE, name, array.len())?;
+    for line in array.chunks(per_line) {
+        write!(w, "   ")?;
+        for x in line {
+            write!(w, " 0x{:0digits$x}{},", x.to_u64(), suffix, digits=digits)?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w, "}};")
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::string::String;
+
+    #[test]
+    fn export_c_array() {
+        let mut header = String::new();
+        write_c_array(&mut header, "xs", &(0..20u8).collect::<alloc::vec::Vec<_>>()).unwrap();
+        assert_eq!(header, "\
+            static const uint8_t xs[20] = {\n    \
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,\n    \
+                0x10, 0x11, 0x12, 0x13,\n\
+            };\n");
+
+        let mut header = String::new();
+        write_c_array(&mut header, "xs", &[1u64, 2, 3, 4, 5]).unwrap();
+        assert_eq!(header, "\
+            static const uint64_t xs[5] = {\n    \
+                0x0000000000000001ULL, 0x0000000000000002ULL, 0x0000000000000003ULL,\n    \
+                0x0000000000000004ULL, 0x0000000000000005ULL,\n\
+            };\n");
+
+        let mut header = String::new();
+        write_c_array::<_, u32>(&mut header, "xs", &[]).unwrap();
+        assert_eq!(header, "static const uint32_t xs[0] = {\n};\n");
+    }
+
+    #[cfg(feature="crc")]
+    #[test]
+    fn export_crc_tables() {
+        use crate::crc::*;
+
+        // these should match the tables used by common C implementations
+        let table = crc32_byte_table();
+        assert_eq!(&table[..4], &[0x00000000, 0x77073096, 0xee0e612c, 0x990951ba]);
+        let table = crc32c_byte_table();
+        assert_eq!(&table[..4], &[0x00000000, 0xf26b8303, 0xe13b70f7, 0x1350f3f4]);
+        let table = crc16_byte_table();
+        assert_eq!(&table[..4], &[0x0000, 0x1189, 0x2312, 0x329b]);
+
+        let mut header = String::new();
+        write_c_array(&mut header, "crc32_table", &crc32_byte_table()).unwrap();
+        assert!(header.starts_with("\
+            static const uint32_t crc32_table[256] = {\n    \
+                0x00000000, 0x77073096, 0xee0e612c, 0x990951ba, 0x076dc419, 0x706af48f,\n"));
+        assert!(header.ends_with("0x5a05df1b, 0x2d02ef8d,\n};\n"));
+    }
+
+    #[cfg(not(any(feature="no-tables", feature="small-tables")))]
+    #[test]
+    fn export_gf_tables() {
+        use crate::gf::*;
+
+        assert_eq!(gf256::EXP_TABLE[1], 2);
+        assert_eq!(gf256::LOG_TABLE[2], 1);
+        for i in 0..255 {
+            assert_eq!(gf256::LOG_TABLE[usize::from(gf256::EXP_TABLE[i])], i as u8);
+        }
+
+        let mut header = String::new();
+        write_c_array(&mut header, "gf256_exp_table", &gf256::EXP_TABLE).unwrap();
+        assert!(header.starts_with("\
+            static const uint8_t gf256_exp_table[256] = {\n    \
+                0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1d, 0x3a, 0x74, 0xe8, 0xcd, 0x87, 0x13, 0x26,\n"));
+    }
+}
@@ -0,0 +1,629 @@
+//! ## GHASH
+//!
+//! [GHASH][ghash-wiki] is the universal hash at the core of [AES-GCM][gcm-wiki]
+//! and AES-GMAC. It's a polynomial
+//! evaluated over `GF(2^128)`, with a secret hash key `H` as the point of
+//! evaluation. Each 16-byte block of input is added into an accumulator,
+//! which is then multiplied by `H`:
+//!
+//! ``` text
+//! Y0 = 0
+//! Yi = (Yi-1 + Xi) * H
+//! ```
+//!
+//! In AES-GCM, `H` is the encryption of an all-zero block, and the input is
+//! the additional authenticated data, then the ciphertext, each zero-padded to
+//! a multiple of 16 bytes, followed by a block containing the bit-lengths of
+//! both. [`Ghash::gcm`](crate::ghash::Ghash::gcm) computes exactly this:
+//!
+//! ``` rust
+//! use gf256::ghash::Ghash;
+//!
+//! // NIST's GCM test case 2, H = AES-128(0, 0)
+//! let h = [
+//!     0x66, 0xe9, 0x4b, 0xd4, 0xef, 0x8a, 0x2c, 0x3b,
+//!     0x88, 0x4c, 0xfa, 0x59, 0xca, 0x34, 0x2b, 0x2e,
+//! ];
+//! let ciphertext = [
+//!     0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92,
+//!     0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe, 0x78,
+//! ];
+//!
+//! assert_eq!(Ghash::gcm(&h, b"", &ciphertext), [
+//!     0xf3, 0x8c, 0xbb, 0x1a, 0xd6, 0x92, 0x23, 0xdc,
+//!     0xc3, 0x45, 0x7a, 0xe5, 0xb6, 0xb0, 0xf8, 0x85,
+//! ]);
+//! ```
+//!
+//! Or, for streaming, or other framings, [`Ghash`](crate::ghash::Ghash) can be
+//! fed data incrementally:
+//!
+//! ``` rust
+//! # use gf256::ghash::Ghash;
+//! # let h = [
+//! #     0x66, 0xe9, 0x4b, 0xd4, 0xef, 0x8a, 0x2c, 0x3b,
+//! #     0x88, 0x4c, 0xfa, 0x59, 0xca, 0x34, 0x2b, 0x2e,
+//! # ];
+//! # let ciphertext = [
+//! #     0x03, 0x88, 0xda, 0xce, 0x60, 0xb6, 0xa3, 0x92,
+//! #     0xf3, 0x28, 0xc2, 0xb9, 0x71, 0xb2, 0xfe, 0x78,
+//! # ];
+//! let mut ghash = Ghash::new(&h);
+//! ghash.update(&ciphertext[..5]);
+//! ghash.update(&ciphertext[5..]);
+//! ghash.pad();
+//! ghash.update(&[0,0,0,0,0,0,0,0, 0,0,0,0,0,0,0,0x80]);
+//! assert_eq!(ghash.finalize(), Ghash::gcm(&h, b"", &ciphertext));
+//! ```
+//!
+//! Note this module requires feature `ghash`.
+//!
+//! Also note that GHASH is only a building block. It is not a MAC on its own,
+//! and `H` must be kept secret, as knowing `H` makes forging tags trivial.
+//!
+//! ## Bit-reflection
+//!
+//! GHASH uses the field `GF(2^128)` defined by the irreducible polynomial
+//! `x^128 + x^7 + x^2 + x + 1`, but with one twist: blocks are bit-reflected,
+//! so the most-significant bit of the first byte is the coefficient of `x^0`,
+//! and the least-significant bit of the last byte is the coefficient of
+//! `x^127`.
+//!
+//! Internally we reverse each block with [`u128::reverse_bits`] on the way in
+//! and out, which lets us use the normal polynomial representation, and
+//! normal [`p128`](crate::p128) multiplication. Reversing bits is cheap
+//! compared to a 128-bit multiplication, and only needs to happen once per
+//! block.
+//!
+//! Note that `gf256` doesn't provide a `GF(2^128)` type through the
+//! [`gf`](mod@crate::gf) macro, since the polynomial doesn't fit in a `u128`,
+//! so GHASH carries its own multiplication here.
+//!
+//! ## Implementations
+//!
+//! Like the other Galois-field types, GHASH comes with several implementations,
+//! with different trade-offs:
+//!
+//! - [`GhashNaive`](crate::ghash::GhashNaive) - A bitwise implementation, no
+//!   tables, no hardware instructions.
+//!
+//! - [`GhashSmallTable`](crate::ghash::GhashSmallTable) - Shoup's 4-bit tables,
+//!   a 16-element table of multiples of `H` (256 bytes), and a constant
+//!   16-element reduction table.
+//!
+//! - [`GhashTable`](crate::ghash::GhashTable) - Shoup's 8-bit tables, a
+//!   256-element table of multiples of `H` (4 KiB), and a constant 256-element
+//!   reduction table.
+//!
+//! - [`GhashXmul`](crate::ghash::GhashXmul) - Carry-less multiplication with
+//!   [`p128::widening_mul`](crate::p128::widening_mul), which uses `pclmulqdq`
+//!   on x86_64 and `pmull` on aarch64, followed by a two-step reduction.
+//!
+//! [`Ghash`](crate::ghash::Ghash) is an alias to the best implementation
+//! available, following the same rules as the [`gf`](mod@crate::gf) types:
+//! [`GhashXmul`](crate::ghash::GhashXmul) if hardware carry-less multiplication
+//! is available, otherwise [`GhashTable`](crate::ghash::GhashTable), or
+//! [`GhashSmallTable`](crate::ghash::GhashSmallTable) if the feature
+//! `small-tables` is enabled. With `no-tables`,
+//! [`GhashXmul`](crate::ghash::GhashXmul) is used, falling back to a naive
+//! widening multiplication.
+//!
+//! All implementations compute the same values, so one can be used to check
+//! another, for example to verify the results of a hardware offload engine.
+//!
+//! Note that the table-based implementations index tables with secret data, and
+//! may be susceptible to cache-timing attacks. If this is a concern, use
+//! [`GhashXmul`](crate::ghash::GhashXmul) on hardware with carry-less
+//! multiplication, or [`GhashNaive`](crate::ghash::GhashNaive).
+//!
+//! [ghash-wiki]: https://en.wikipedia.org/wiki/Galois/Counter_Mode#Mathematical_basis
+//! [gcm-wiki]: https://en.wikipedia.org/wiki/Galois/Counter_Mode
+
+
+use cfg_if::cfg_if;
+use crate::p::p128;
+
+
+/// Size of a GHASH block, and of the resulting hash, in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// Low terms of GHASH's polynomial, x^128 + x^7 + x^2 + x + 1
+const POLYNOMIAL: u128 = 0x87;
+
+/// Reduction table for the 4-bit tables, x^128*i mod p(x) for i < 2^4
+const SMALL_REM_TABLE: [u16; 16] = rem_table();
+
+/// Reduction table for the 8-bit tables, x^128*i mod p(x) for i < 2^8
+const REM_TABLE: [u16; 256] = rem_table();
+
+const fn rem_table<const N: usize>() -> [u16; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    while i < N {
+        // i has < 8 bits, so i*x^128 mod p(x) is just i*0x87, which
+        // fits in 15 bits
+        table[i] = p128(i as u128).naive_wrapping_mul(p128(POLYNOMIAL)).0 as u16;
+        i += 1;
+    }
+    table
+}
+
+/// Multiply by x, mod p(x)
+#[inline]
+const fn mulx(x: u128) -> u128 {
+    (x << 1) ^ ((x >> 127).wrapping_neg() & POLYNOMIAL)
+}
+
+/// Convert a bit-reflected block into a normal polynomial
+#[inline]
+fn load(block: &[u8; BLOCK_SIZE]) -> u128 {
+    u128::from_be_bytes(*block).reverse_bits()
+}
+
+/// Convert a normal polynomial into a bit-reflected block
+#[inline]
+fn store(x: u128) -> [u8; BLOCK_SIZE] {
+    x.reverse_bits().to_be_bytes()
+}
+
+
+// the state machinery is identical for all implementations, only key
+// expansion and multiplication by H differ
+macro_rules! ghash {
+    (
+        $(#[$attr:meta])*
+        $ghash:ident {
+            key: $key:ty,
+            expand: $expand:path,
+            mul: $mul:path,
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $ghash {
+            key: $key,
+            y: u128,
+            buffer: [u8; BLOCK_SIZE],
+            buffered: usize,
+        }
+
+        impl $ghash {
+            /// Create a new GHASH state with the hash key `H`.
+            ///
+            /// In AES-GCM, `H` is the encryption of an all-zero block.
+            ///
+            pub fn new(h: &[u8; BLOCK_SIZE]) -> Self {
+                Self {
+                    key: $expand(load(h)),
+                    y: 0,
+                    buffer: [0; BLOCK_SIZE],
+                    buffered: 0,
+                }
+            }
+
+            /// Reset to the initial state, keeping the hash key.
+            pub fn reset(&mut self) {
+                self.y = 0;
+                self.buffer = [0; BLOCK_SIZE];
+                self.buffered = 0;
+            }
+
+            /// Multiply a block by `H` in GHASH's bit-reflected `GF(2^128)`.
+            ///
+            /// This is the core operation of GHASH, exposed mostly for
+            /// testing.
+            ///
+            pub fn mul_h(&self, x: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+                store($mul(&self.key, load(x)))
+            }
+
+            #[inline]
+            fn absorb(&mut self, block: &[u8; BLOCK_SIZE]) {
+                self.y = $mul(&self.key, self.y ^ load(block));
+            }
+
+            /// Feed data into GHASH.
+            ///
+            /// Data does not need to be aligned to blocks, partial blocks
+            /// are buffered until either more data or [`pad`](Self::pad)
+            /// completes them.
+            ///
+            pub fn update(&mut self, mut data: &[u8]) {
+                // finish any buffered block
+                if self.buffered > 0 {
+                    let n = (BLOCK_SIZE-self.buffered).min(data.len());
+                    self.buffer[self.buffered..self.buffered+n]
+                        .copy_from_slice(&data[..n]);
+                    self.buffered += n;
+                    data = &data[n..];
+
+                    if self.buffered < BLOCK_SIZE {
+                        return;
+                    }
+
+                    let block = self.buffer;
+                    self.absorb(&block);
+                    self.buffered = 0;
+                }
+
+                // full blocks
+                let mut blocks = data.chunks_exact(BLOCK_SIZE);
+                for block in &mut blocks {
+                    self.absorb(block.try_into().unwrap());
+                }
+
+                // buffer any leftovers
+                let rem = blocks.remainder();
+                self.buffer[..rem.len()].copy_from_slice(rem);
+                self.buffered = rem.len();
+            }
+
+            /// Zero-pad any buffered partial block, as GCM does between
+            /// the additional authenticated data and the ciphertext.
+            ///
+            /// This does nothing if we're already aligned to a block.
+            ///
+            pub fn pad(&mut self) {
+                if self.buffered > 0 {
+                    self.buffer[self.buffered..].fill(0);
+                    let block = self.buffer;
+                    self.absorb(&block);
+                    self.buffered = 0;
+                }
+            }
+
+            /// Finish GHASH, returning the hash.
+            ///
+            /// Any buffered partial block is zero-padded.
+            ///
+            pub fn finalize(mut self) -> [u8; BLOCK_SIZE] {
+                self.pad();
+                store(self.y)
+            }
+
+            /// Compute GHASH as used in AES-GCM, `GHASH(H, A, C)`.
+            ///
+            /// This hashes the additional authenticated data `aad`, the
+            /// `ciphertext`, each zero-padded to a block, followed by a
+            /// block containing the bit-lengths of both as big-endian
+            /// 64-bit integers.
+            ///
+            /// To get the final tag, AES-GCM xors this with the
+            /// encryption of the initial counter block.
+            ///
+            pub fn gcm(
+                h: &[u8; BLOCK_SIZE],
+                aad: &[u8],
+                ciphertext: &[u8]
+            ) -> [u8; BLOCK_SIZE] {
+                let mut ghash = Self::new(h);
+                ghash.update(aad);
+                ghash.pad();
+                ghash.update(ciphertext);
+                ghash.pad();
+
+                let mut lengths = [0; BLOCK_SIZE];
+                lengths[..8].copy_from_slice(&(8*aad.len() as u64).to_be_bytes());
+                lengths[8..].copy_from_slice(&(8*ciphertext.len() as u64).to_be_bytes());
+                ghash.update(&lengths);
+                ghash.finalize()
+            }
+        }
+    };
+}
+
+
+// naive implementation
+fn naive_expand(h: u128) -> u128 {
+    h
+}
+
+fn naive_mul(h: &u128, x: u128) -> u128 {
+    // Horner's method, one bit at a time starting from x^127, using
+    // masks instead of branches
+    let mut y = 0;
+    let mut i = 128;
+    while i > 0 {
+        i -= 1;
+        y = mulx(y);
+        y ^= ((x >> i) & 1).wrapping_neg() & *h;
+    }
+    y
+}
+
+ghash! {
+    /// GHASH using a naive bitwise implementation.
+    ///
+    /// See the [module-level documentation](crate::ghash) for more info.
+    ///
+    GhashNaive {
+        key: u128,
+        expand: naive_expand,
+        mul: naive_mul,
+    }
+}
+
+
+// 4-bit table implementation
+fn small_table_expand(h: u128) -> [u128; 16] {
+    // table[i] = i*h for i < 2^4
+    let mut table = [0; 16];
+    table[1] = h;
+    let mut i = 2;
+    while i < 16 {
+        table[i] = mulx(table[i/2]);
+        let mut j = 1;
+        while j < i {
+            table[i+j] = table[i] ^ table[j];
+            j += 1;
+        }
+        i *= 2;
+    }
+    table
+}
+
+fn small_table_mul(table: &[u128; 16], x: u128) -> u128 {
+    // Horner's method, 4 bits at a time, with the bits shifted out of
+    // y reduced via our remainder table
+    let mut y = 0;
+    let mut i = 128;
+    while i > 0 {
+        i -= 4;
+        let hi = (y >> 124) as usize;
+        y = (y << 4) ^ u128::from(SMALL_REM_TABLE[hi]);
+        y ^= table[((x >> i) & 0xf) as usize];
+    }
+    y
+}
+
+ghash! {
+    /// GHASH using Shoup's 4-bit tables.
+    ///
+    /// This uses a 16-element table of multiples of `H`, 256 bytes.
+    ///
+    /// See the [module-level documentation](crate::ghash) for more info.
+    ///
+    GhashSmallTable {
+        key: [u128; 16],
+        expand: small_table_expand,
+        mul: small_table_mul,
+    }
+}
+
+
+// 8-bit table implementation
+fn table_expand(h: u128) -> [u128; 256] {
+    // table[i] = i*h for i < 2^8
+    let mut table = [0; 256];
+    table[1] = h;
+    let mut i = 2;
+    while i < 256 {
+        table[i] = mulx(table[i/2]);
+        let mut j = 1;
+        while j < i {
+            table[i+j] = table[i] ^ table[j];
+            j += 1;
+        }
+        i *= 2;
+    }
+    table
+}
+
+fn table_mul(table: &[u128; 256], x: u128) -> u128 {
+    // Horner's method, 8 bits at a time, with the bits shifted out of
+    // y reduced via our remainder table
+    let mut y = 0;
+    let mut i = 128;
+    while i > 0 {
+        i -= 8;
+        let hi = (y >> 120) as usize;
+        y = (y << 8) ^ u128::from(REM_TABLE[hi]);
+        y ^= table[((x >> i) & 0xff) as usize];
+    }
+    y
+}
+
+ghash! {
+    /// GHASH using Shoup's 8-bit tables.
+    ///
+    /// This uses a 256-element table of multiples of `H`, 4 KiB, stored
+    /// inline.
+    ///
+    /// See the [module-level documentation](crate::ghash) for more info.
+    ///
+    GhashTable {
+        key: [u128; 256],
+        expand: table_expand,
+        mul: table_mul,
+    }
+}
+
+
+// carry-less multiplication implementation
+fn xmul_expand(h: u128) -> u128 {
+    h
+}
+
+fn xmul_mul(h: &u128, x: u128) -> u128 {
+    // 256-bit product, then fold the high half down twice, since
+    // x^128 = x^7 + x^2 + x + 1, the second fold has < 8 bits and
+    // can't overflow
+    let (lo, hi) = p128(x).widening_mul(p128(*h));
+    let (lo2, hi2) = hi.widening_mul(p128(POLYNOMIAL));
+    (lo + lo2 + hi2.wrapping_mul(p128(POLYNOMIAL))).0
+}
+
+ghash! {
+    /// GHASH using carry-less multiplication.
+    ///
+    /// This uses hardware carry-less multiplication instructions when
+    /// available (`pclmulqdq` on x86_64, `pmull` on aarch64), otherwise
+    /// falls back to a naive widening multiplication.
+    ///
+    /// See the [module-level documentation](crate::ghash) for more info.
+    ///
+    GhashXmul {
+        key: u128,
+        expand: xmul_expand,
+        mul: xmul_mul,
+    }
+}
+
+
+cfg_if! {
+    if #[cfg(any(
+        all(
+            not(feature="no-xmul"),
+            target_arch="x86_64",
+            target_feature="pclmulqdq"
+        ),
+        all(
+            not(feature="no-xmul"),
+            target_arch="aarch64",
+            target_feature="neon"
+        ),
+        feature="no-tables"
+    ))] {
+        /// GHASH using the best implementation available.
+        ///
+        /// See the [module-level documentation](crate::ghash) for more info.
+        ///
+        pub type Ghash = GhashXmul;
+    } else if #[cfg(feature="small-tables")] {
+        /// GHASH using the best implementation available.
+        ///
+        /// See the [module-level documentation](crate::ghash) for more info.
+        ///
+        pub type Ghash = GhashSmallTable;
+    } else {
+        /// GHASH using the best implementation available.
+        ///
+        /// See the [module-level documentation](crate::ghash) for more info.
+        ///
+        pub type Ghash = GhashTable;
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    fn hex<const N: usize>(s: &str) -> [u8; N] {
+        let mut buf = [0; N];
+        for i in 0..N {
+            buf[i] = u8::from_str_radix(&s[2*i..2*i+2], 16).unwrap();
+        }
+        buf
+    }
+
+    fn hex_vec(s: &str) -> Vec<u8> {
+        (0..s.len()/2)
+            .map(|i| u8::from_str_radix(&s[2*i..2*i+2], 16).unwrap())
+            .collect()
+    }
+
+    // NIST's GCM test cases 2 and 4
+    const H2: &str = "66e94bd4ef8a2c3b884cfa59ca342b2e";
+    const C2: &str = "0388dace60b6a392f328c2b971b2fe78";
+    const G2: &str = "f38cbb1ad69223dcc3457ae5b6b0f885";
+
+    const H4: &str = "b83b533708bf535d0aa6e52980d53b78";
+    const A4: &str = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+    const C4: &str = "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091";
+    const G4: &str = "698e57f70e6ecc7fd9463b7260a9ae5f";
+
+    #[test]
+    fn ghash_gcm() {
+        let h2 = hex::<16>(H2);
+        let h4 = hex::<16>(H4);
+        let c2 = hex_vec(C2);
+        let a4 = hex_vec(A4);
+        let c4 = hex_vec(C4);
+
+        assert_eq!(GhashNaive::gcm(&h2, b"", &c2), hex::<16>(G2));
+        assert_eq!(GhashSmallTable::gcm(&h2, b"", &c2), hex::<16>(G2));
+        assert_eq!(GhashTable::gcm(&h2, b"", &c2), hex::<16>(G2));
+        assert_eq!(GhashXmul::gcm(&h2, b"", &c2), hex::<16>(G2));
+        assert_eq!(Ghash::gcm(&h2, b"", &c2), hex::<16>(G2));
+
+        assert_eq!(GhashNaive::gcm(&h4, &a4, &c4), hex::<16>(G4));
+        assert_eq!(GhashSmallTable::gcm(&h4, &a4, &c4), hex::<16>(G4));
+        assert_eq!(GhashTable::gcm(&h4, &a4, &c4), hex::<16>(G4));
+        assert_eq!(GhashXmul::gcm(&h4, &a4, &c4), hex::<16>(G4));
+        assert_eq!(Ghash::gcm(&h4, &a4, &c4), hex::<16>(G4));
+    }
+
+    #[test]
+    fn ghash_mul() {
+        // 1 is the multiplicative identity, which in GHASH's bit order is
+        // the msb of the first byte
+        let one = hex::<16>("80000000000000000000000000000000");
+        let h = hex::<16>(H4);
+        assert_eq!(GhashNaive::new(&h).mul_h(&one), h);
+        assert_eq!(GhashSmallTable::new(&h).mul_h(&one), h);
+        assert_eq!(GhashTable::new(&h).mul_h(&one), h);
+        assert_eq!(GhashXmul::new(&h).mul_h(&one), h);
+
+        // x^127*x = x^128 = x^7 + x^2 + x + 1
+        let x127 = hex::<16>("00000000000000000000000000000001");
+        let x = hex::<16>("40000000000000000000000000000000");
+        let p = hex::<16>("e1000000000000000000000000000000");
+        assert_eq!(GhashNaive::new(&x).mul_h(&x127), p);
+        assert_eq!(GhashSmallTable::new(&x).mul_h(&x127), p);
+        assert_eq!(GhashTable::new(&x).mul_h(&x127), p);
+        assert_eq!(GhashXmul::new(&x).mul_h(&x127), p);
+
+        // all implementations should agree
+        let mut a = h;
+        let mut b = hex::<16>(G4);
+        for _ in 0..1000 {
+            let naive = GhashNaive::new(&a).mul_h(&b);
+            assert_eq!(GhashSmallTable::new(&a).mul_h(&b), naive);
+            assert_eq!(GhashTable::new(&a).mul_h(&b), naive);
+            assert_eq!(GhashXmul::new(&a).mul_h(&b), naive);
+            a = b;
+            b = naive;
+        }
+    }
+
+    #[test]
+    fn ghash_streaming() {
+        let h = hex::<16>(H4);
+        let data = hex_vec(C4);
+        let expected = {
+            let mut ghash = Ghash::new(&h);
+            ghash.update(&data);
+            ghash.finalize()
+        };
+
+        // any split of the data should give the same hash
+        for i in 0..data.len() {
+            for j in i..data.len() {
+                let mut ghash = Ghash::new(&h);
+                ghash.update(&data[..i]);
+                ghash.update(&data[i..j]);
+                ghash.update(&data[j..]);
+                assert_eq!(ghash.finalize(), expected);
+            }
+        }
+
+        // pad should only matter if we're not aligned
+        let mut ghash = Ghash::new(&h);
+        ghash.update(&data[..32]);
+        ghash.pad();
+        ghash.pad();
+        ghash.update(&data[32..]);
+        assert_eq!(ghash.finalize(), expected);
+
+        // reset should get us back to the initial state
+        let mut ghash = Ghash::new(&h);
+        ghash.update(&data[..7]);
+        ghash.reset();
+        ghash.update(&data);
+        assert_eq!(ghash.finalize(), expected);
+    }
+}
@@ -0,0 +1,394 @@
+//! ## Hamming/SECDED error-correction codes
+//!
+//! [Hamming codes][hamming-wiki] are the simplest family of error-correction
+//! codes (ECC), capable of correcting a single bit error with only a
+//! logarithmic number of parity bits. Extended Hamming codes add one more
+//! parity bit, which lets them also detect any two bit errors. This is
+//! commonly called single-error-correction/double-error-detection, or SECDED,
+//! and is what protects each word of ECC RAM.
+//!
+//! ``` rust
+//! use gf256::hamming::secded72w64;
+//!
+//! // encode
+//! let mut data = 0x0123456789abcdefu64;
+//! let mut ecc = secded72w64::encode_word(data);
+//!
+//! // corrupt
+//! data ^= 1 << 17;
+//!
+//! // correct
+//! secded72w64::correct_word(&mut data, &mut ecc)?;
+//! assert_eq!(data, 0x0123456789abcdef);
+//! # Ok::<(), secded72w64::Error>(())
+//! ```
+//!
+//! Note this module requires feature `hamming`.
+//!
+//! ## How do Hamming codes work?
+//!
+//! A Hamming code with `m` parity bits assigns each bit in the codeword a
+//! unique, non-zero, `m`-bit column. The parity bits get the columns with only
+//! one bit set, `1`, `2`, `4`, etc, and the data bits get the remaining
+//! columns, `3`, `5`, `6`, `7`, `9`, etc. So a codeword can contain up to
+//! `2^m-1` bits, `2^m-m-1` of which are data.
+//!
+//! When encoding, we choose the parity bits so that the xor of the columns of
+//! all set bits is zero. Conveniently, this is just the xor of the columns of
+//! all set data bits.
+//!
+//! When decoding, we find the xor of the columns of all set bits again, the
+//! _syndrome_. If there are no errors, this is zero. If there is a single bit
+//! error, this is exactly the column of the erroneous bit, and we can fix it
+//! by flipping the bit.
+//!
+//! If there are two bit errors, the syndrome is the xor of two columns, which
+//! looks just like some other single bit error. To tell these apart, extended
+//! Hamming codes add an overall parity bit over the whole codeword:
+//!
+//! | syndrome | parity | meaning                             |
+//! |----------|--------|-------------------------------------|
+//! | zero     | even   | no errors                           |
+//! | non-zero | odd    | one error, at the syndrome's column |
+//! | zero     | odd    | one error, in the overall parity bit |
+//! | non-zero | even   | two errors, which can't be corrected |
+//!
+//! ## Words and blocks
+//!
+//! Each Hamming module provides two interfaces:
+//!
+//! 1. A word interface,
+//!    [`encode_word`](crate::hamming::secded72w64::encode_word) and
+//!    [`correct_word`](crate::hamming::secded72w64::correct_word), which
+//!    protects the low [`WORD_BITS`](crate::hamming::secded72w64::WORD_BITS)
+//!    bits of an integer, storing the error-correction bits in a separate
+//!    integer. This is well suited for ECC-RAM-style protection of small
+//!    records.
+//!
+//!    This interface is only available if the data bits of the code fit in
+//!    an integer, up to `m=7`.
+//!
+//! 2. A block interface, [`encode`](crate::hamming::secded8192w8178::encode)
+//!    and [`correct_errors`](crate::hamming::secded8192w8178::correct_errors),
+//!    which, like [BCH](../bch), protects up to
+//!    [`DATA_SIZE`](crate::hamming::secded8192w8178::DATA_SIZE) bytes of data
+//!    followed by [`ECC_SIZE`](crate::hamming::secded8192w8178::ECC_SIZE) bytes
+//!    of error-correction. Data bits are stored msb first, and the
+//!    error-correction bits are stored big-endian, with any unused bits at the
+//!    start set to zero.
+//!
+//! Both interfaces support shortened codewords, where the missing data bits are
+//! treated as zero. Errors that point into the missing data bits are reported
+//! as [`TooManyErrors`](crate::hamming::secded72w64::Error::TooManyErrors), so
+//! shortened codes can detect some double bit errors even without the overall
+//! parity bit.
+//!
+//! ``` rust
+//! use gf256::hamming::secded8192w8178;
+//!
+//! let mut sector = vec![0u8; 512+2];
+//! sector[..512].copy_from_slice(&[0x55; 512]);
+//! secded8192w8178::encode(&mut sector);
+//!
+//! // flip a bit
+//! sector[100] ^= 0x08;
+//!
+//! assert_eq!(secded8192w8178::correct_errors(&mut sector), Ok(1));
+//! assert_eq!(&sector[..512], &[0x55; 512]);
+//! ```
+//!
+//! Keep in mind Hamming codes are only guaranteed to correct one bit error
+//! per codeword. For channels with more errors, consider [BCH](../bch) or
+//! [Reed-Solomon](../rs).
+//!
+//! [hamming-wiki]: https://en.wikipedia.org/wiki/Hamming_code
+
+
+/// A macro for generating custom Hamming/SECDED error-correction modules.
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::hamming::hamming;
+/// #[hamming(m=7, extended=true, u=u64)]
+/// pub mod my_secded72w64 {}
+///
+/// # fn main() -> Result<(), my_secded72w64::Error> {
+/// // encode
+/// let mut data = 0x0123456789abcdefu64;
+/// let mut ecc = my_secded72w64::encode_word(data);
+///
+/// // corrupt
+/// data ^= 1 << 17;
+///
+/// // correct
+/// my_secded72w64::correct_word(&mut data, &mut ecc)?;
+/// assert_eq!(data, 0x0123456789abcdef);
+/// # Ok::<(), my_secded72w64::Error>(())
+/// # }
+/// ```
+///
+/// The `hamming` macro accepts a number of configuration options:
+///
+/// - `m` - Number of parity bits, codewords are limited to `2^m-1` bits.
+///   Must be between 2 and 16.
+/// - `extended` - Add an overall parity bit, making this a SECDED code that
+///   can also detect any two bit errors, defaults to false.
+/// - `u` - The integer type used by the word interface. This must have at
+///   most `2^m-m-1` bits, and defaults to the smallest integer type that fits
+///   all `2^m-m-1` data bits. If there is no such type, the word interface
+///   is not available.
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::hamming::hamming;
+/// #[hamming(m=6, extended=true, u=u32)]
+/// pub mod my_secded39w32 {}
+///
+/// # fn main() -> Result<(), my_secded39w32::Error> {
+/// // encode
+/// let mut data = 0x01234567u32;
+/// let mut ecc = my_secded39w32::encode_word(data);
+///
+/// // corrupt
+/// data ^= 1 << 17;
+///
+/// // correct
+/// my_secded39w32::correct_word(&mut data, &mut ecc)?;
+/// assert_eq!(data, 0x01234567);
+/// # Ok::<(), my_secded39w32::Error>(())
+/// # }
+/// ```
+///
+pub use gf256_macros::hamming;
+
+
+// The classic Hamming(7,4) code, correcting one bit error in 4 bits of data
+//
+#[hamming(m=3)]
+pub mod hamming7w4 {}
+
+// SECDED over 32-bit words, common in embedded SRAM
+//
+#[hamming(m=6, extended=true, u=u32)]
+pub mod secded39w32 {}
+
+// SECDED over 64-bit words, the standard (72,64) code used by ECC RAM
+//
+#[hamming(m=7, extended=true, u=u64)]
+pub mod secded72w64 {}
+
+// SECDED over blocks of up to 1022 bytes with 2 bytes of error-correction
+//
+#[hamming(m=13, extended=true)]
+pub mod secded8192w8178 {}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    #[hamming(m=2)]
+    pub mod hamming3w1 {}
+    #[hamming(m=4, extended=true)]
+    pub mod secded16w11 {}
+
+    #[test]
+    fn hamming_constants() {
+        assert_eq!(hamming7w4::ECC_BITS, 3);
+        assert_eq!(hamming7w4::WORD_BITS, 4);
+        assert_eq!(hamming7w4::DATA_SIZE, 0);
+        assert_eq!(secded16w11::ECC_BITS, 5);
+        assert_eq!(secded16w11::WORD_BITS, 11);
+        assert_eq!(secded39w32::ECC_BITS, 7);
+        assert_eq!(secded39w32::WORD_BITS, 32);
+        assert_eq!(secded72w64::ECC_BITS, 8);
+        assert_eq!(secded72w64::WORD_BITS, 64);
+        assert_eq!(secded72w64::DATA_SIZE, 15);
+        assert_eq!(secded8192w8178::ECC_BITS, 14);
+        assert_eq!(secded8192w8178::ECC_SIZE, 2);
+        assert_eq!(secded8192w8178::DATA_SIZE, 1022);
+    }
+
+    #[test]
+    fn hamming3w1() {
+        // Hamming(3,1) is just a repetition code
+        assert_eq!(hamming3w1::encode_word(0), 0b00);
+        assert_eq!(hamming3w1::encode_word(1), 0b11);
+        for data in 0..2u8 {
+            for i in 0..3 {
+                let mut x = data;
+                let mut ecc = hamming3w1::encode_word(data);
+                if i < 1 { x ^= 1 << i } else { ecc ^= 1 << (i-1) }
+                assert_eq!(hamming3w1::correct_word(&mut x, &mut ecc), Ok(1));
+                assert_eq!(x, data);
+                assert_eq!(ecc, hamming3w1::encode_word(data));
+            }
+        }
+    }
+
+    #[test]
+    fn hamming7w4() {
+        // Hamming(7,4) has minimum distance 3, so any single bit error is
+        // correctable, and all 16 codewords are distinct
+        let mut codewords = Vec::new();
+        for data in 0..16u8 {
+            let ecc = hamming7w4::encode_word(data);
+            assert!(ecc < 8);
+            assert!(hamming7w4::is_word_correct(data, ecc));
+            codewords.push((data, ecc));
+
+            for i in 0..7 {
+                let mut x = data;
+                let mut e = ecc;
+                if i < 4 { x ^= 1 << i } else { e ^= 1 << (i-4) }
+                assert!(!hamming7w4::is_word_correct(x, e));
+                assert_eq!(hamming7w4::correct_word(&mut x, &mut e), Ok(1));
+                assert_eq!((x, e), (data, ecc));
+            }
+        }
+
+        for (i, (a, x)) in codewords.iter().enumerate() {
+            for (b, y) in &codewords[i+1..] {
+                assert!((a ^ b).count_ones() + (x ^ y).count_ones() >= 3);
+            }
+        }
+
+        // upper bits are ignored
+        assert_eq!(hamming7w4::encode_word(0xf5), hamming7w4::encode_word(0x05));
+        let mut x = 0xf5;
+        let mut e = hamming7w4::encode_word(0x05);
+        assert_eq!(hamming7w4::correct_word(&mut x, &mut e), Ok(0));
+        assert_eq!(x, 0xf5);
+    }
+
+    #[test]
+    fn secded72w64() {
+        for _ in 0..100 {
+            let data = rand::random::<u64>();
+            let ecc = secded72w64::encode_word(data);
+
+            // correct any single error
+            for i in 0..72 {
+                let mut x = data;
+                let mut e = ecc;
+                if i < 64 { x ^= 1 << i } else { e ^= 1 << (i-64) }
+                assert_eq!(secded72w64::correct_word(&mut x, &mut e), Ok(1));
+                assert_eq!((x, e), (data, ecc));
+            }
+
+            // detect any double error
+            for i in 0..72 {
+                for j in i+1..72 {
+                    let mut x = data;
+                    let mut e = ecc;
+                    if i < 64 { x ^= 1 << i } else { e ^= 1 << (i-64) }
+                    if j < 64 { x ^= 1 << j } else { e ^= 1 << (j-64) }
+                    let (prev_x, prev_e) = (x, e);
+                    assert_eq!(
+                        secded72w64::correct_word(&mut x, &mut e),
+                        Err(secded72w64::Error::TooManyErrors)
+                    );
+                    assert_eq!((x, e), (prev_x, prev_e));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn secded39w32() {
+        // shortened from 57 to 32 data bits
+        for _ in 0..100 {
+            let data = rand::random::<u32>();
+            let ecc = secded39w32::encode_word(data);
+            assert!(ecc < 0x80);
+
+            for i in 0..39 {
+                let mut x = data;
+                let mut e = ecc;
+                if i < 32 { x ^= 1 << i } else { e ^= 1 << (i-32) }
+                let (prev_x, prev_e) = (x, e);
+                assert_eq!(secded39w32::correct_word(&mut x, &mut e), Ok(1));
+                assert_eq!((x, e), (data, ecc));
+
+                for j in i+1..39 {
+                    let mut x = prev_x;
+                    let mut e = prev_e;
+                    if j < 32 { x ^= 1 << j } else { e ^= 1 << (j-32) }
+                    assert!(secded39w32::correct_word(&mut x, &mut e).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn secded16w11() {
+        // 11 data bits round down to 1 byte of data, with 5 bits of
+        // error-correction in 1 byte
+        assert_eq!(secded16w11::DATA_SIZE, 1);
+        for data in 0..=255u8 {
+            let mut codeword = [data, 0xff];
+            secded16w11::encode(&mut codeword);
+            assert!(codeword[1] < 0x20);
+            assert!(secded16w11::is_correct(&codeword));
+
+            for i in 0..13 {
+                let mut corrupted = codeword;
+                if i < 8 { corrupted[0] ^= 0x80 >> i } else { corrupted[1] ^= 1 << (i-8) }
+                assert_eq!(secded16w11::correct_errors(&mut corrupted), Ok(1));
+                assert_eq!(corrupted, codeword);
+            }
+
+            // unused bits are ignored
+            let mut corrupted = codeword;
+            corrupted[1] ^= 0xe0;
+            assert!(secded16w11::is_correct(&corrupted));
+        }
+    }
+
+    #[test]
+    fn secded8192w8178() {
+        // shortened codewords
+        for len in [0, 1, 12, 512, secded8192w8178::DATA_SIZE] {
+            let data = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut codeword = data.clone();
+            codeword.resize(len+2, 0);
+            secded8192w8178::encode(&mut codeword);
+            assert!(secded8192w8178::is_correct(&codeword));
+
+            let mut parity = [0u8; 2];
+            secded8192w8178::encode_parity(&data, &mut parity);
+            assert_eq!(parity, &codeword[len..]);
+
+            // correct any single error, skipping the two unused bits
+            for i in (0..8*codeword.len()).filter(|i| *i < 8*len || *i >= 8*len+2) {
+                let mut corrupted = codeword.clone();
+                corrupted[i/8] ^= 0x80 >> (i%8);
+                assert_eq!(secded8192w8178::correct_errors(&mut corrupted), Ok(1));
+                assert_eq!(corrupted, codeword);
+            }
+
+            // detect double errors
+            for _ in 0..1000 {
+                let i = rand::random::<usize>() % (8*len+14);
+                let j = rand::random::<usize>() % (8*len+14);
+                if i == j {
+                    continue;
+                }
+                let mut corrupted = codeword.clone();
+                for k in [i, j] {
+                    let k = if k < 8*len { k } else { k+2 };
+                    corrupted[k/8] ^= 0x80 >> (k%8);
+                }
+                let prev = corrupted.clone();
+                assert_eq!(
+                    secded8192w8178::correct_errors(&mut corrupted),
+                    Err(secded8192w8178::Error::TooManyErrors)
+                );
+                assert_eq!(corrupted, prev);
+            }
+        }
+    }
+}
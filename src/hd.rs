@@ -0,0 +1,170 @@
+//! ## Hamming distance analysis
+//!
+//! The [Hamming distance (HD)][hamming-distance] of a CRC is the minimum
+//! number of bit-errors that can go undetected, so a CRC with HD=n is
+//! guaranteed to detect any n-1 bit-errors. This depends on both the CRC's
+//! polynomial and the length of the message, and usually drops as messages
+//! get longer.
+//!
+//! [`hamming_distance`](crate::crc::hd::hamming_distance) finds the HD of a CRC
+//! polynomial at a given message length, so you can check a polynomial against
+//! your own requirements:
+//!
+//! ``` rust
+//! use gf256::crc::hd::hamming_distance;
+//!
+//! // CRC32C, HD=5,6 up to 5243+32 bits, HD=7,8 up to 177+32 bits
+//! assert_eq!(hamming_distance(0x11edc6f41, 177, 6), None);
+//! assert_eq!(hamming_distance(0x11edc6f41, 178, 6), Some(6));
+//! ```
+//!
+//! An undetected error is any error that is itself a multiple of the
+//! polynomial, so the HD is the minimum weight of these multiples. To find
+//! these, we look for sets of bits whose remainders, `x^i mod p`, xor to
+//! zero.
+//!
+//! Since the polynomial is not divisible by `x`, we can shift any multiple
+//! down so its lowest bit is `x^0`, and we can split the remaining bits into
+//! two halves, looking for collisions between halves with a sorted list.
+//! Even so, this gets expensive quickly for larger Hamming distances and
+//! longer messages, so this takes a maximum Hamming distance to search for.
+//!
+//! See [Philip Koopman's work on CRC polynomials][koopman] for more info,
+//! and the [best known polynomials][crc-polynomials] for many lengths.
+//!
+//! Note this module requires feature `alloc`.
+//!
+//! [hamming-distance]: https://en.wikipedia.org/wiki/Hamming_distance
+//! [koopman]: http://users.ece.cmu.edu/~koopman/roses/dsn04/koopman04_crc_poly_embedded.pdf
+//! [crc-polynomials]: https://users.ece.cmu.edu/~koopman/crc
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// Find the Hamming distance of a CRC polynomial for messages with the
+/// given number of bits, not including the CRC itself.
+///
+/// Returns [`None`] if the Hamming distance is greater than `max`, or if the
+/// polynomial is divisible by `x`, since the search below relies on the
+/// polynomial's constant term being set.
+///
+/// ``` rust
+/// # use gf256::crc::hd::hamming_distance;
+/// // CRC8, HD=3,4 up to 119+8 bits
+/// assert_eq!(hamming_distance(0x107, 119, 8), Some(4));
+/// assert_eq!(hamming_distance(0x107, 120, 8), Some(2));
+/// ```
+///
+/// Note this needs roughly O(n^ceil((max-1)/2)) operations and
+/// O(n^floor((max-1)/2)) memory, where n is the number of bits, so large
+/// Hamming distances with long messages may take a while.
+///
+/// See the [module-level documentation](self) for more info.
+///
+pub fn hamming_distance(polynomial: u128, bits: usize, max: usize) -> Option<usize> {
+    assert!(polynomial > 1);
+    // we assume the constant term is set below
+    if polynomial & 1 == 0 {
+        return None;
+    }
+
+    let width = 127 - usize::try_from(polynomial.leading_zeros()).unwrap();
+
+    // find x^i mod p for every bit in our codewords, these are the
+    // remainders of single-bit errors
+    let mut remainders = Vec::with_capacity(bits + width);
+    let mut x = 1u128;
+    for _ in 0..bits + width {
+        remainders.push(x);
+        x <<= 1;
+        if x & (1 << width) != 0 {
+            x ^= polynomial;
+        }
+    }
+
+    // find the smallest number of bits whose remainders sum to zero, we
+    // can assume the first bit is x^0
+    (2..=max).find(|&n| {
+        // split the remaining bits into two halves
+        let left = (n-1) / 2;
+        let right = (n-1) - left;
+
+        let mut sums = Vec::new();
+        combinations(&remainders[1..], left, 0, &mut |sum| sums.push(sum));
+        sums.sort_unstable();
+        sums.dedup();
+
+        // note that if the halves share bits, these cancel out and we find
+        // a smaller multiple, but we would have already found these
+        let mut found = false;
+        combinations(&remainders[1..], right, remainders[0], &mut |sum| {
+            found = found || sums.binary_search(&sum).is_ok();
+        });
+        found
+    })
+}
+
+/// Call f with the sum of every n-element combination of xs
+fn combinations(xs: &[u128], n: usize, sum: u128, f: &mut impl FnMut(u128)) {
+    if n == 0 {
+        f(sum);
+        return;
+    }
+
+    for i in 0..xs.len() {
+        combinations(&xs[i+1..], n-1, sum ^ xs[i], f);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hamming_distance_crc8() {
+        // HD=3,4, up to 119+8 bits
+        assert_eq!(hamming_distance(0x107, 8, 8), Some(4));
+        assert_eq!(hamming_distance(0x107, 119, 8), Some(4));
+        assert_eq!(hamming_distance(0x107, 120, 8), Some(2));
+        assert_eq!(hamming_distance(0x107, 1000, 8), Some(2));
+    }
+
+    #[test]
+    fn hamming_distance_divisible_by_x() {
+        assert_eq!(hamming_distance(0x106, 8, 8), None);
+        assert_eq!(hamming_distance(0x20e, 8, 8), None);
+        assert_eq!(hamming_distance(0x2, 8, 8), None);
+    }
+
+    #[test]
+    fn hamming_distance_crc16() {
+        // HD=3,4, up to 32751+16 bits
+        assert_eq!(hamming_distance(0x11021, 256, 4), Some(4));
+        assert_eq!(hamming_distance(0x11021, 256, 3), None);
+    }
+
+    #[test]
+    fn hamming_distance_crc32() {
+        // HD=3, up to 4294967263+32 bits
+        // HD=4, up to 91607+32 bits
+        // HD=5, up to 2974+32 bits
+        // HD=6, up to 268+32 bits
+        // HD=7, up to 171+32 bits
+        // HD=8, up to 91+32 bits
+        assert_eq!(hamming_distance(0x104c11db7, 171, 6), None);
+        assert_eq!(hamming_distance(0x104c11db7, 172, 6), Some(6));
+        assert_eq!(hamming_distance(0x104c11db7, 268, 6), Some(6));
+        assert_eq!(hamming_distance(0x104c11db7, 269, 6), Some(5));
+    }
+
+    #[test]
+    fn hamming_distance_crc32c() {
+        // HD=3,4, up to 2147483615+32 bits
+        // HD=5,6, up to 5243+32 bits
+        // HD=7,8, up to 177+32 bits
+        assert_eq!(hamming_distance(0x11edc6f41, 177, 6), None);
+        assert_eq!(hamming_distance(0x11edc6f41, 178, 6), Some(6));
+    }
+}
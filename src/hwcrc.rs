@@ -0,0 +1,254 @@
+//! Hardware CRC implementations if available
+//!
+//! Some architectures provide dedicated instructions for specific CRC
+//! polynomials. x86_64 provides CRC32C via SSE4.2, and aarch64 provides
+//! both CRC32 and CRC32C via its optional CRC extension.
+//!
+//! These functions are intended to only be used by gf256's proc_macros,
+//! these functions may or may not be available depending on target_features,
+//! and may change behavior, so they shouldn't be used directly.
+//!
+//! Note that these operate on the raw, bit-reflected CRC state, without
+//! any initial/final xor.
+//!
+
+use cfg_if::cfg_if;
+
+
+/// A flag indicating if hardware CRC32C instructions are available.
+///
+/// If this is true, the default implementation of any CRC with the
+/// CRC32C polynomial (0x11edc6f41) will use these instructions. Otherwise,
+/// with feature `std`, these instructions may still be detected at runtime.
+///
+pub const HAS_HWCRC32C: bool = {
+    cfg_if! {
+        if #[cfg(any(
+            all(
+                target_arch="x86_64",
+                target_feature="sse4.2"
+            ),
+            all(
+                target_arch="aarch64",
+                target_feature="crc"
+            )
+        ))] {
+            true
+        } else {
+            false
+        }
+    }
+};
+
+/// A flag indicating if hardware CRC32 instructions are available.
+///
+/// If this is true, the default implementation of any CRC with the
+/// CRC32 polynomial (0x104c11db7) will use these instructions. Otherwise,
+/// with feature `std`, these instructions may still be detected at runtime.
+///
+pub const HAS_HWCRC32: bool = {
+    cfg_if! {
+        if #[cfg(all(
+            target_arch="aarch64",
+            target_feature="crc"
+        ))] {
+            true
+        } else {
+            false
+        }
+    }
+};
+
+
+/// Update a CRC32C with a byte, if hardware instructions are available
+#[cfg(any(
+    all(
+        target_arch="x86_64",
+        target_feature="sse4.2"
+    ),
+    all(
+        target_arch="aarch64",
+        target_feature="crc"
+    )
+))]
+#[inline]
+pub fn crc32c_u8(crc: u32, b: u8) -> u32 {
+    cfg_if! {
+        if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="sse4.2"
+        ))] {
+            // x86_64 provides CRC32C via the crc32 instruction
+            use core::arch::x86_64::*;
+            unsafe { _mm_crc32_u8(crc, b) }
+        } else if #[cfg(all(
+            target_arch="aarch64",
+            target_feature="crc"
+        ))] {
+            // aarch64 provides CRC32C via the crc32cb instruction
+            use core::arch::aarch64::*;
+            unsafe { __crc32cb(crc, b) }
+        }
+    }
+}
+
+/// Update a CRC32C with a little-endian 64-bit word, if hardware
+/// instructions are available
+#[cfg(any(
+    all(
+        target_arch="x86_64",
+        target_feature="sse4.2"
+    ),
+    all(
+        target_arch="aarch64",
+        target_feature="crc"
+    )
+))]
+#[inline]
+pub fn crc32c_u64(crc: u32, x: u64) -> u32 {
+    cfg_if! {
+        if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="sse4.2"
+        ))] {
+            // x86_64 provides CRC32C via the crc32 instruction
+            use core::arch::x86_64::*;
+            unsafe { _mm_crc32_u64(u64::from(crc), x) as u32 }
+        } else if #[cfg(all(
+            target_arch="aarch64",
+            target_feature="crc"
+        ))] {
+            // aarch64 provides CRC32C via the crc32cx instruction
+            use core::arch::aarch64::*;
+            unsafe { __crc32cd(crc, x) }
+        }
+    }
+}
+
+/// Update a CRC32 with a byte, if hardware instructions are available
+#[cfg(all(
+    target_arch="aarch64",
+    target_feature="crc"
+))]
+#[inline]
+pub fn crc32_u8(crc: u32, b: u8) -> u32 {
+    // aarch64 provides CRC32 via the crc32b instruction
+    use core::arch::aarch64::*;
+    unsafe { __crc32b(crc, b) }
+}
+
+/// Update a CRC32 with a little-endian 64-bit word, if hardware
+/// instructions are available
+#[cfg(all(
+    target_arch="aarch64",
+    target_feature="crc"
+))]
+#[inline]
+pub fn crc32_u64(crc: u32, x: u64) -> u32 {
+    // aarch64 provides CRC32 via the crc32x instruction
+    use core::arch::aarch64::*;
+    unsafe { __crc32d(crc, x) }
+}
+
+
+/// Update a CRC32C with a slice of bytes, if hardware instructions are
+/// detected at runtime, otherwise returns None
+#[cfg(feature="std")]
+#[inline]
+pub fn crc32c_detect(crc: u32, data: &[u8]) -> Option<u32> {
+    cfg_if! {
+        if #[cfg(target_arch="x86_64")] {
+            if super::std::arch::is_x86_feature_detected!("sse4.2") {
+                return Some(unsafe { crc32c_sse42(crc, data) });
+            }
+        } else if #[cfg(target_arch="aarch64")] {
+            if super::std::arch::is_aarch64_feature_detected!("crc") {
+                return Some(unsafe { crc32c_armcrc(crc, data) });
+            }
+        }
+    }
+
+    let _ = (crc, data);
+    None
+}
+
+/// Update a CRC32 with a slice of bytes, if hardware instructions are
+/// detected at runtime, otherwise returns None
+#[cfg(feature="std")]
+#[inline]
+pub fn crc32_detect(crc: u32, data: &[u8]) -> Option<u32> {
+    cfg_if! {
+        if #[cfg(target_arch="aarch64")] {
+            if super::std::arch::is_aarch64_feature_detected!("crc") {
+                return Some(unsafe { crc32_armcrc(crc, data) });
+            }
+        }
+    }
+
+    let _ = (crc, data);
+    None
+}
+
+#[cfg(all(feature="std", target_arch="x86_64"))]
+#[target_feature(enable="sse4.2")]
+unsafe fn crc32c_sse42(crc: u32, data: &[u8]) -> u32 {
+    use core::arch::x86_64::*;
+    let mut crc = u64::from(crc);
+
+    // iterate over words
+    let mut words = data.chunks_exact(8);
+    for word in &mut words {
+        let word = <[u8; 8]>::try_from(word).unwrap();
+        crc = _mm_crc32_u64(crc, u64::from_le_bytes(word));
+    }
+
+    // handle remainder
+    let mut crc = crc as u32;
+    for b in words.remainder() {
+        crc = _mm_crc32_u8(crc, *b);
+    }
+
+    crc
+}
+
+#[cfg(all(feature="std", target_arch="aarch64"))]
+#[target_feature(enable="crc")]
+unsafe fn crc32c_armcrc(crc: u32, data: &[u8]) -> u32 {
+    use core::arch::aarch64::*;
+    let mut crc = crc;
+
+    // iterate over words
+    let mut words = data.chunks_exact(8);
+    for word in &mut words {
+        let word = <[u8; 8]>::try_from(word).unwrap();
+        crc = __crc32cd(crc, u64::from_le_bytes(word));
+    }
+
+    // handle remainder
+    for b in words.remainder() {
+        crc = __crc32cb(crc, *b);
+    }
+
+    crc
+}
+
+#[cfg(all(feature="std", target_arch="aarch64"))]
+#[target_feature(enable="crc")]
+unsafe fn crc32_armcrc(crc: u32, data: &[u8]) -> u32 {
+    use core::arch::aarch64::*;
+    let mut crc = crc;
+
+    // iterate over words
+    let mut words = data.chunks_exact(8);
+    for word in &mut words {
+        let word = <[u8; 8]>::try_from(word).unwrap();
+        crc = __crc32d(crc, u64::from_le_bytes(word));
+    }
+
+    // handle remainder
+    for b in words.remainder() {
+        crc = __crc32b(crc, *b);
+    }
+
+    crc
+}
@@ -0,0 +1,850 @@
+//! ## LDPC error-correction codes
+//!
+//! [Low-density parity-check codes][ldpc-wiki] (LDPC) are a family of
+//! error-correction codes (ECC) defined by a sparse parity-check matrix.
+//! Combined with iterative belief-propagation decoding, they come remarkably
+//! close to the theoretical limits of noisy channels, and are used in most
+//! modern channel codes, WiFi, 5G, DVB-S2, etc.
+//!
+//! Unlike [Reed-Solomon](../rs) or [BCH](../bch), LDPC decoders are best at
+//! _soft-decision_ decoding, where the input is not just bits, but how
+//! confident we are in each bit, expressed as log-likelihood ratios (LLRs).
+//!
+//! ``` rust
+//! use gf256::ldpc::{Ldpc, Algorithm};
+//!
+//! let ldpc = Ldpc::ieee80211n_648r12();
+//!
+//! // encode
+//! let data = [0x55u8; 324/8+1];
+//! let mut codeword = vec![0u8; ldpc.codeword_size()];
+//! ldpc.encode(&data, &mut codeword);
+//!
+//! // transmit over a noisy channel, positive LLRs mean 0, negative LLRs
+//! // mean 1, and the magnitude is our confidence
+//! let mut llrs = (0..648)
+//!     .map(|i| if codeword[i/8] & (0x80 >> (i%8)) == 0 { 2.0 } else { -2.0 })
+//!     .collect::<Vec<f32>>();
+//! for i in (0..648).step_by(37) {
+//!     llrs[i] = -0.5*llrs[i];
+//! }
+//!
+//! // decode
+//! let mut decoded = vec![0u8; ldpc.codeword_size()];
+//! ldpc.decode(&llrs, &mut decoded, Algorithm::MinSum, 50)?;
+//! assert_eq!(decoded, codeword);
+//! # Ok::<(), gf256::ldpc::Error>(())
+//! ```
+//!
+//! Note this module requires feature `ldpc`, and `alloc`.
+//!
+//! ## How do LDPC codes work?
+//!
+//! An LDPC code is defined by a parity-check matrix, `H`, where each row is a
+//! parity check over a small number of bits. Valid codewords are exactly the
+//! bit vectors that satisfy all of these parity checks, `H*c = 0`.
+//!
+//! We can view this as a bipartite graph, the Tanner graph, with a variable
+//! node for each bit, a check node for each row, and an edge wherever `H` has
+//! a one. Decoding passes messages along these edges:
+//!
+//! 1. Each variable node tells each of its check nodes what it believes its
+//!    bit to be, based on the channel and what its _other_ check nodes told
+//!    it.
+//!
+//! 2. Each check node tells each of its variable nodes what it believes its
+//!    bit must be for the parity check to be satisfied, based on what its
+//!    _other_ variable nodes told it.
+//!
+//! 3. Repeat until the hard decisions satisfy all parity checks, or we give
+//!    up.
+//!
+//! With exact probabilities this is the sum-product algorithm, also called
+//! belief-propagation:
+//!
+//! ``` text
+//! r = 2*atanh(Π tanh(q/2))
+//! ```
+//!
+//! The min-sum algorithm approximates this with the sign and minimum of
+//! the incoming messages, which is much cheaper and doesn't care about
+//! the scale of the LLRs:
+//!
+//! ``` text
+//! r = Π sign(q) * min |q|
+//! ```
+//!
+//! Min-sum tends to overestimate its confidence, normalized min-sum scales
+//! its messages by a constant factor to compensate.
+//!
+//! ## Encoding
+//!
+//! Encoding is less straightforward, since we only have the parity-check
+//! matrix. When an [`Ldpc`](crate::ldpc::Ldpc) code is created, we reduce `H`
+//! to row-echelon form, choosing pivots from the last columns first. The pivot
+//! columns hold the parity bits, and the remaining columns hold the data bits,
+//! which are copied into the codeword as-is. Each parity bit can then be found
+//! by back-substitution, starting from the last row.
+//!
+//! For most codes, including the standard matrices provided here, this puts the
+//! data bits at the start of the codeword, but this isn't guaranteed. Use
+//! [`Ldpc::extract`](crate::ldpc::Ldpc::extract) to get the data bits back out
+//! of a codeword.
+//!
+//! Codewords, and data, are stored as bits, msb first, with any unused bits
+//! at the end of the last byte set to zero.
+//!
+//! ## Standard matrices
+//!
+//! - [`Ldpc::ieee80211n_648r12`](crate::ldpc::Ldpc::ieee80211n_648r12) - The
+//!   rate 1/2, 648-bit code from IEEE 802.11n, a quasi-cyclic code with 27x27
+//!   circulants.
+//!
+//! - [`Ldpc::tanner155`](crate::ldpc::Ldpc::tanner155) - The (155,64) Tanner
+//!   code, a quasi-cyclic code with 31x31 circulants, a minimum distance of 20,
+//!   and girth of 8.
+//!
+//! Custom codes can be created from a sparse parity-check matrix with
+//! [`Ldpc::new`](crate::ldpc::Ldpc::new), or from the base matrix of a
+//! quasi-cyclic code with [`Ldpc::from_qc`](crate::ldpc::Ldpc::from_qc).
+//!
+//! [ldpc-wiki]: https://en.wikipedia.org/wiki/Low-density_parity-check_code
+
+use core::fmt;
+use core::ops::Range;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Default number of iterations used by [`Ldpc::correct_errors`].
+pub const DEFAULT_ITERATIONS: usize = 50;
+
+/// Scaling factor used by [`Algorithm::NormalizedMinSum`].
+pub const MIN_SUM_SCALE: f32 = 0.75;
+
+// IEEE 802.11n, n=648, rate 1/2, base matrix with z=27
+const IEEE80211N_648R12: [[i16; 24]; 12] = [
+    [ 0, -1, -1, -1,  0,  0, -1, -1,  0, -1, -1,  0,  1,  0, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1],
+    [22,  0, -1, -1, 17, -1,  0,  0, 12, -1, -1, -1, -1,  0,  0, -1, -1, -1, -1, -1, -1, -1, -1, -1],
+    [ 6, -1,  0, -1, 10, -1, -1, -1, 24, -1,  0, -1, -1, -1,  0,  0, -1, -1, -1, -1, -1, -1, -1, -1],
+    [ 2, -1, -1,  0, 20, -1, -1, -1, 25,  0, -1, -1, -1, -1, -1,  0,  0, -1, -1, -1, -1, -1, -1, -1],
+    [23, -1, -1, -1,  3, -1, -1, -1,  0, -1,  9, 11, -1, -1, -1, -1,  0,  0, -1, -1, -1, -1, -1, -1],
+    [24, -1, 23,  1, 17, -1,  3, -1, 10, -1, -1, -1, -1, -1, -1, -1, -1,  0,  0, -1, -1, -1, -1, -1],
+    [25, -1, -1, -1,  8, -1, -1, -1,  7, 18, -1, -1,  0, -1, -1, -1, -1, -1,  0,  0, -1, -1, -1, -1],
+    [13, 24, -1, -1,  0, -1,  8, -1,  6, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  0, -1, -1, -1],
+    [ 7, 20, -1, 16, 22, 10, -1, -1, 23, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  0, -1, -1],
+    [11, -1, -1, -1, 19, -1, -1, -1, 13, -1,  3, 17, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  0, -1],
+    [25, -1,  8, -1, 23, 18, -1, 14,  9, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  0],
+    [ 3, -1, -1, -1, 16, -1, -1,  2, 25,  5, -1, -1,  1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0],
+];
+
+// Tanner (155,64) code, base matrix with z=31, block (j, i) is shifted by
+// 5^j * 2^i mod 31
+const TANNER155: [[i16; 5]; 3] = [
+    [ 1,  2,  4,  8, 16],
+    [ 5, 10, 20,  9, 18],
+    [25, 19,  7, 14, 28],
+];
+
+
+/// Decoding algorithms for [`Ldpc::decode`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Algorithm {
+    /// Min-sum decoding, an approximation of belief-propagation using only
+    /// comparisons and additions.
+    MinSum,
+
+    /// Normalized min-sum decoding, min-sum with check-node messages scaled
+    /// by [`MIN_SUM_SCALE`], which is usually closer to belief-propagation.
+    NormalizedMinSum,
+
+    /// Sum-product decoding, also called belief-propagation.
+    ///
+    /// Note this requires feature `std` for `tanh`/`atanh`.
+    ///
+    #[cfg(feature="std")]
+    SumProduct,
+}
+
+/// Error codes for LDPC
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Decoding failed to find a valid codeword within the given number of
+    /// iterations, usually because there are too many errors.
+    ///
+    /// Note that with too many errors, LDPC may also converge to the wrong
+    /// codeword, this is only detected sometimes.
+    ///
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl std::error::Error for Error {}
+
+
+/// An LDPC code, defined by a sparse parity-check matrix.
+///
+/// ``` rust
+/// use gf256::ldpc::Ldpc;
+///
+/// let ldpc = Ldpc::tanner155();
+/// assert_eq!(ldpc.n(), 155);
+/// assert_eq!(ldpc.k(), 64);
+///
+/// // encode
+/// let data = b"Hello!!!";
+/// let mut codeword = vec![0u8; ldpc.codeword_size()];
+/// ldpc.encode(data, &mut codeword);
+///
+/// // corrupt
+/// codeword[0] ^= 0x81;
+/// codeword[5] ^= 0x10;
+///
+/// // correct
+/// assert_eq!(ldpc.correct_errors(&mut codeword)?, 3);
+/// let mut decoded = [0u8; 8];
+/// ldpc.extract(&codeword, &mut decoded);
+/// assert_eq!(&decoded, data);
+/// # Ok::<(), gf256::ldpc::Error>(())
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Ldpc {
+    n: usize,
+    k: usize,
+
+    // Tanner graph, edges are numbered row by row, so each row (check node)
+    // owns a contiguous range of edges
+    rows: Vec<Range<usize>>,
+    edge_columns: Vec<usize>,
+    column_edges: Vec<Vec<usize>>,
+
+    // systematic encoder, H in row-echelon form as bitsets, the pivot column
+    // of each row, and the remaining data columns
+    echelon: Vec<Vec<u64>>,
+    pivots: Vec<usize>,
+    data_columns: Vec<usize>,
+}
+
+impl Ldpc {
+    /// Create an LDPC code from a sparse parity-check matrix with `n`
+    /// columns, given as the columns of the ones in each row.
+    ///
+    /// The parity-check matrix doesn't need to be full rank, redundant rows
+    /// are still used for decoding.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::Ldpc;
+    ///
+    /// // the (7,4) Hamming code
+    /// let ldpc = Ldpc::new(7, &[
+    ///     [0, 1, 3, 4],
+    ///     [0, 2, 3, 5],
+    ///     [1, 2, 3, 6],
+    /// ]);
+    /// assert_eq!(ldpc.k(), 4);
+    /// ```
+    ///
+    pub fn new<R: AsRef<[usize]>>(n: usize, h: &[R]) -> Self {
+        // build the Tanner graph
+        let mut rows = Vec::with_capacity(h.len());
+        let mut edge_columns = Vec::new();
+        let mut column_edges = vec![Vec::new(); n];
+        for row in h {
+            let start = edge_columns.len();
+            for &c in row.as_ref() {
+                assert!(c < n, "ldpc column {} out of range for n={}", c, n);
+                column_edges[c].push(edge_columns.len());
+                edge_columns.push(c);
+            }
+            rows.push(start..edge_columns.len());
+        }
+
+        // reduce to row-echelon form, choosing pivots from the last column
+        // first so parity bits end up at the end of the codeword if possible
+        let words = n.div_ceil(64);
+        let mut matrix = h.iter()
+            .map(|row| {
+                let mut bits = vec![0u64; words];
+                for &c in row.as_ref() {
+                    bits[c/64] ^= 1 << (c%64);
+                }
+                bits
+            })
+            .collect::<Vec<_>>();
+
+        let mut echelon = Vec::new();
+        let mut pivots = Vec::new();
+        for c in (0..n).rev() {
+            let r = match matrix.iter()
+                .position(|row| row[c/64] & (1 << (c%64)) != 0)
+            {
+                Some(r) => r,
+                None => continue,
+            };
+
+            let pivot = matrix.swap_remove(r);
+            for row in matrix.iter_mut() {
+                if row[c/64] & (1 << (c%64)) != 0 {
+                    for (x, y) in row.iter_mut().zip(&pivot) {
+                        *x ^= y;
+                    }
+                }
+            }
+            echelon.push(pivot);
+            pivots.push(c);
+        }
+
+        let data_columns = (0..n)
+            .filter(|c| !pivots.contains(c))
+            .collect::<Vec<_>>();
+
+        Ldpc {
+            n,
+            k: data_columns.len(),
+            rows,
+            edge_columns,
+            column_edges,
+            echelon,
+            pivots,
+            data_columns,
+        }
+    }
+
+    /// Create a quasi-cyclic LDPC code from a base matrix of `z`x`z`
+    /// circulants.
+    ///
+    /// Each entry of the base matrix is either `-1`, for a block of zeros, or
+    /// a shift `s`, for the identity matrix cyclically shifted right by `s`.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::Ldpc;
+    ///
+    /// let ldpc = Ldpc::from_qc(&[
+    ///     [0,  1, -1],
+    ///     [2, -1,  0],
+    /// ], 4);
+    /// assert_eq!(ldpc.n(), 12);
+    /// assert_eq!(ldpc.k(), 4);
+    /// ```
+    ///
+    pub fn from_qc<R: AsRef<[i16]>>(base: &[R], z: usize) -> Self {
+        let cols = base.first().map(|row| row.as_ref().len()).unwrap_or(0);
+        let mut h = Vec::with_capacity(base.len()*z);
+        for row in base {
+            let row = row.as_ref();
+            assert!(row.len() == cols, "ldpc base matrix must be rectangular");
+            for i in 0..z {
+                h.push(row.iter()
+                    .enumerate()
+                    .filter(|(_, s)| **s >= 0)
+                    .map(|(j, s)| j*z + (i + *s as usize) % z)
+                    .collect::<Vec<_>>());
+            }
+        }
+
+        Self::new(cols*z, &h)
+    }
+
+    /// The rate 1/2, 648-bit LDPC code from IEEE 802.11n.
+    ///
+    /// This encodes 324 bits of data into 648-bit codewords.
+    ///
+    pub fn ieee80211n_648r12() -> Self {
+        Self::from_qc(&IEEE80211N_648R12, 27)
+    }
+
+    /// The (155,64) Tanner code.
+    ///
+    /// This encodes 64 bits of data into 155-bit codewords, with a minimum
+    /// distance of 20.
+    ///
+    pub fn tanner155() -> Self {
+        Self::from_qc(&TANNER155, 31)
+    }
+
+    /// Number of bits in each codeword.
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Number of data bits in each codeword.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Size of the data in bytes, [`k`](Self::k) rounded up.
+    pub fn data_size(&self) -> usize {
+        self.k.div_ceil(8)
+    }
+
+    /// Size of each codeword in bytes, [`n`](Self::n) rounded up.
+    pub fn codeword_size(&self) -> usize {
+        self.n.div_ceil(8)
+    }
+
+    /// The columns of the ones in each row of the parity-check matrix.
+    pub fn parity_checks(&self) -> impl ExactSizeIterator<Item=&[usize]> + '_ {
+        self.rows.iter().map(move |r| &self.edge_columns[r.clone()])
+    }
+
+    /// The positions of the data bits in each codeword.
+    pub fn data_columns(&self) -> &[usize] {
+        &self.data_columns
+    }
+
+    /// Encode [`k`](Self::k) bits of data into a codeword of [`n`](Self::n)
+    /// bits.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::Ldpc;
+    ///
+    /// let ldpc = Ldpc::tanner155();
+    /// let mut codeword = vec![0u8; ldpc.codeword_size()];
+    /// ldpc.encode(b"Hello!!!", &mut codeword);
+    /// assert!(ldpc.is_correct(&codeword));
+    /// ```
+    ///
+    pub fn encode(&self, data: &[u8], codeword: &mut [u8]) {
+        assert!(data.len() == self.data_size());
+        assert!(codeword.len() == self.codeword_size());
+
+        // copy data bits
+        codeword.fill(0);
+        for (i, &c) in self.data_columns.iter().enumerate() {
+            if get_bit(data, i) {
+                flip_bit(codeword, c);
+            }
+        }
+
+        // find parity bits via back-substitution, each row only depends on
+        // data bits and the parity bits of later rows
+        for (row, &p) in self.echelon.iter().zip(&self.pivots).rev() {
+            let mut parity = false;
+            for (i, word) in row.iter().enumerate() {
+                let mut x = *word;
+                while x != 0 {
+                    let c = 64*i + x.trailing_zeros() as usize;
+                    parity ^= c != p && get_bit(codeword, c);
+                    x &= x - 1;
+                }
+            }
+
+            if parity {
+                flip_bit(codeword, p);
+            }
+        }
+    }
+
+    /// Extract the [`k`](Self::k) bits of data from a codeword.
+    ///
+    /// Any unused bits at the end of `data` are set to zero.
+    ///
+    pub fn extract(&self, codeword: &[u8], data: &mut [u8]) {
+        assert!(codeword.len() == self.codeword_size());
+        assert!(data.len() == self.data_size());
+
+        data.fill(0);
+        for (i, &c) in self.data_columns.iter().enumerate() {
+            if get_bit(codeword, c) {
+                flip_bit(data, i);
+            }
+        }
+    }
+
+    /// Determine if codeword is correct and satisfies all parity checks.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::Ldpc;
+    ///
+    /// let ldpc = Ldpc::tanner155();
+    /// let mut codeword = vec![0u8; ldpc.codeword_size()];
+    /// ldpc.encode(b"Hello!!!", &mut codeword);
+    /// assert!(ldpc.is_correct(&codeword));
+    ///
+    /// codeword[3] ^= 0x04;
+    /// assert!(!ldpc.is_correct(&codeword));
+    /// ```
+    ///
+    pub fn is_correct(&self, codeword: &[u8]) -> bool {
+        assert!(codeword.len() == self.codeword_size());
+
+        self.rows.iter().all(|r| {
+            self.edge_columns[r.clone()].iter()
+                .filter(|&&c| get_bit(codeword, c))
+                .count() % 2 == 0
+        })
+    }
+
+    /// Decode a codeword from soft-decision log-likelihood ratios (LLRs).
+    ///
+    /// Each LLR is `ln(P(bit=0)/P(bit=1))`, so positive means 0, negative
+    /// means 1, and the magnitude is our confidence. Decoding runs for at
+    /// most `iterations` iterations of the given [`Algorithm`].
+    ///
+    /// Returns the number of bits that differ from the hard decisions of
+    /// the LLRs, or [`Error::TooManyErrors`] if decoding fails to converge,
+    /// in which case `codeword` is left unmodified.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::{Ldpc, Algorithm};
+    ///
+    /// let ldpc = Ldpc::tanner155();
+    /// let mut codeword = vec![0u8; ldpc.codeword_size()];
+    /// ldpc.encode(b"Hello!!!", &mut codeword);
+    ///
+    /// // a few unconfident bits are wrong
+    /// let mut llrs = (0..155)
+    ///     .map(|i| if codeword[i/8] & (0x80 >> (i%8)) == 0 { 1.0 } else { -1.0 })
+    ///     .collect::<Vec<f32>>();
+    /// llrs[3] = -0.1*llrs[3];
+    /// llrs[50] = -0.1*llrs[50];
+    /// llrs[100] = -0.1*llrs[100];
+    /// llrs[150] = -0.1*llrs[150];
+    ///
+    /// let mut decoded = vec![0u8; ldpc.codeword_size()];
+    /// assert_eq!(ldpc.decode(&llrs, &mut decoded, Algorithm::NormalizedMinSum, 20), Ok(4));
+    /// assert_eq!(decoded, codeword);
+    /// ```
+    ///
+    pub fn decode(
+        &self,
+        llrs: &[f32],
+        codeword: &mut [u8],
+        algorithm: Algorithm,
+        iterations: usize
+    ) -> Result<usize, Error> {
+        assert!(llrs.len() == self.n);
+        assert!(codeword.len() == self.codeword_size());
+
+        // hard decisions
+        let mut bits = llrs.iter().map(|llr| *llr < 0.0).collect::<Vec<_>>();
+
+        // variable-to-check messages, initially just the channel LLRs
+        let mut q = self.edge_columns.iter()
+            .map(|&c| llrs[c])
+            .collect::<Vec<_>>();
+        // check-to-variable messages
+        let mut r = vec![0.0f32; self.edge_columns.len()];
+
+        let mut i = 0;
+        while !self.is_satisfied(&bits) {
+            if i >= iterations {
+                return Err(Error::TooManyErrors);
+            }
+            i += 1;
+
+            // check node update
+            for row in &self.rows {
+                let q = &q[row.clone()];
+                let r = &mut r[row.clone()];
+                match algorithm {
+                    Algorithm::MinSum => min_sum(q, r, 1.0),
+                    Algorithm::NormalizedMinSum => min_sum(q, r, MIN_SUM_SCALE),
+                    #[cfg(feature="std")]
+                    Algorithm::SumProduct => sum_product(q, r),
+                }
+            }
+
+            // variable node update, and new hard decisions
+            for (c, edges) in self.column_edges.iter().enumerate() {
+                let total = llrs[c] + edges.iter().map(|&e| r[e]).sum::<f32>();
+                bits[c] = total < 0.0;
+                for &e in edges {
+                    q[e] = total - r[e];
+                }
+            }
+        }
+
+        // write out our codeword
+        codeword.fill(0);
+        let mut count = 0;
+        for (c, &bit) in bits.iter().enumerate() {
+            if bit {
+                flip_bit(codeword, c);
+            }
+            if bit != (llrs[c] < 0.0) {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Correct bit errors in a codeword with hard-decision decoding.
+    ///
+    /// This runs [`Algorithm::MinSum`] for up to [`DEFAULT_ITERATIONS`]
+    /// iterations. Returns the number of bit errors corrected, or
+    /// [`Error::TooManyErrors`] if the codeword can not be corrected, in which
+    /// case the codeword is left unmodified.
+    ///
+    /// ``` rust
+    /// use gf256::ldpc::Ldpc;
+    ///
+    /// let ldpc = Ldpc::ieee80211n_648r12();
+    /// let mut codeword = vec![0u8; ldpc.codeword_size()];
+    /// ldpc.encode(&[0x55; 41], &mut codeword);
+    ///
+    /// // flip 8 bits
+    /// for i in 0..8 {
+    ///     codeword[i*10] ^= 1 << i;
+    /// }
+    ///
+    /// assert_eq!(ldpc.correct_errors(&mut codeword), Ok(8));
+    /// assert!(ldpc.is_correct(&codeword));
+    /// ```
+    ///
+    pub fn correct_errors(&self, codeword: &mut [u8]) -> Result<usize, Error> {
+        assert!(codeword.len() == self.codeword_size());
+
+        let llrs = (0..self.n)
+            .map(|c| if get_bit(codeword, c) { -1.0 } else { 1.0 })
+            .collect::<Vec<_>>();
+        self.decode(&llrs, codeword, Algorithm::MinSum, DEFAULT_ITERATIONS)
+    }
+
+    /// Check if hard decisions satisfy all parity checks
+    fn is_satisfied(&self, bits: &[bool]) -> bool {
+        self.rows.iter().all(|r| {
+            self.edge_columns[r.clone()].iter()
+                .filter(|&&c| bits[c])
+                .count() % 2 == 0
+        })
+    }
+}
+
+
+// bit helpers, bits are stored msb first
+
+fn get_bit(buf: &[u8], i: usize) -> bool {
+    buf[i/8] & (0x80 >> (i%8)) != 0
+}
+
+fn flip_bit(buf: &mut [u8], i: usize) {
+    buf[i/8] ^= 0x80 >> (i%8);
+}
+
+
+// check node updates
+
+/// Min-sum check node update, each outgoing message is the product of the
+/// signs, and minimum magnitude, of the other incoming messages
+fn min_sum(q: &[f32], r: &mut [f32], scale: f32) {
+    // we only need the two smallest magnitudes, the smallest for every
+    // message except the one that is the smallest
+    let mut sign = 1.0f32;
+    let mut min1 = f32::INFINITY;
+    let mut min2 = f32::INFINITY;
+    let mut min1_i = 0;
+    for (i, x) in q.iter().enumerate() {
+        if *x < 0.0 {
+            sign = -sign;
+        }
+        let m = x.abs();
+        if m < min1 {
+            min2 = min1;
+            min1 = m;
+            min1_i = i;
+        } else if m < min2 {
+            min2 = m;
+        }
+    }
+
+    for (i, (x, y)) in q.iter().zip(r.iter_mut()).enumerate() {
+        let m = if i == min1_i { min2 } else { min1 };
+        let s = if *x < 0.0 { -sign } else { sign };
+        *y = s * scale * m;
+    }
+}
+
+/// Sum-product check node update, each outgoing message is
+/// 2*atanh(Π tanh(q/2)) of the other incoming messages
+#[cfg(feature="std")]
+fn sum_product(q: &[f32], r: &mut [f32]) {
+    // clamp to avoid infinities when we're very confident
+    const LIMIT: f32 = 1.0 - 1.0e-6;
+
+    for (i, y) in r.iter_mut().enumerate() {
+        let mut p = 1.0f32;
+        for (j, x) in q.iter().enumerate() {
+            if j != i {
+                p *= (x / 2.0).tanh();
+            }
+        }
+        *y = 2.0 * p.clamp(-LIMIT, LIMIT).atanh();
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Approximately normal noise, the sum of 12 uniform samples, which
+    /// avoids needing ln/sqrt without std
+    fn noise(sigma: f32) -> f32 {
+        sigma * ((0..12).map(|_| rand::random::<f32>()).sum::<f32>() - 6.0)
+    }
+
+    fn llrs(ldpc: &Ldpc, codeword: &[u8], sigma: f32) -> Vec<f32> {
+        // BPSK, 0 => +1, 1 => -1, LLR = 2y/sigma^2
+        (0..ldpc.n())
+            .map(|c| {
+                let x = if get_bit(codeword, c) { -1.0 } else { 1.0 };
+                2.0*(x + noise(sigma)) / (sigma*sigma)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ldpc_hamming() {
+        let ldpc = Ldpc::new(7, &[
+            [0, 1, 3, 4],
+            [0, 2, 3, 5],
+            [1, 2, 3, 6],
+        ]);
+        assert_eq!(ldpc.n(), 7);
+        assert_eq!(ldpc.k(), 4);
+        assert_eq!(ldpc.data_columns(), &[0, 1, 2, 3]);
+
+        for data in 0..16u8 {
+            let mut codeword = [0u8; 1];
+            ldpc.encode(&[data << 4], &mut codeword);
+            assert!(ldpc.is_correct(&codeword));
+            assert_eq!(codeword[0] & 1, 0);
+
+            let mut extracted = [0u8; 1];
+            ldpc.extract(&codeword, &mut extracted);
+            assert_eq!(extracted, [data << 4]);
+        }
+    }
+
+    #[test]
+    fn ldpc_standard_matrices() {
+        let ldpc = Ldpc::ieee80211n_648r12();
+        assert_eq!(ldpc.n(), 648);
+        assert_eq!(ldpc.k(), 324);
+        assert_eq!(ldpc.parity_checks().len(), 324);
+        // full rank parity part, so data comes first
+        assert_eq!(ldpc.data_columns(), &(0..324).collect::<Vec<_>>()[..]);
+
+        // Tanner's code has 2 redundant parity checks
+        let ldpc = Ldpc::tanner155();
+        assert_eq!(ldpc.n(), 155);
+        assert_eq!(ldpc.k(), 64);
+        assert_eq!(ldpc.parity_checks().len(), 93);
+        assert!(ldpc.parity_checks().all(|row| row.len() == 5));
+    }
+
+    #[test]
+    fn ldpc_encode() {
+        for ldpc in [Ldpc::ieee80211n_648r12(), Ldpc::tanner155()] {
+            for _ in 0..100 {
+                let mut data = (0..ldpc.data_size())
+                    .map(|_| rand::random::<u8>())
+                    .collect::<Vec<_>>();
+                if ldpc.k() % 8 != 0 {
+                    *data.last_mut().unwrap() &= 0xff << (8 - ldpc.k()%8);
+                }
+
+                let mut codeword = vec![0u8; ldpc.codeword_size()];
+                ldpc.encode(&data, &mut codeword);
+                assert!(ldpc.is_correct(&codeword));
+
+                let mut extracted = vec![0u8; ldpc.data_size()];
+                ldpc.extract(&codeword, &mut extracted);
+                assert_eq!(extracted, data);
+            }
+        }
+    }
+
+    #[test]
+    fn ldpc_correct_errors() {
+        for (ldpc, errors) in [
+            (Ldpc::ieee80211n_648r12(), 10),
+            (Ldpc::tanner155(), 3),
+        ] {
+            for _ in 0..100 {
+                let data = (0..ldpc.data_size())
+                    .map(|_| rand::random::<u8>())
+                    .collect::<Vec<_>>();
+                let mut codeword = vec![0u8; ldpc.codeword_size()];
+                ldpc.encode(&data, &mut codeword);
+
+                let mut corrupted = codeword.clone();
+                let mut count = 0;
+                while count < errors {
+                    let c = rand::random::<usize>() % ldpc.n();
+                    if get_bit(&corrupted, c) == get_bit(&codeword, c) {
+                        flip_bit(&mut corrupted, c);
+                        count += 1;
+                    }
+                }
+
+                assert_eq!(ldpc.correct_errors(&mut corrupted), Ok(errors));
+                assert_eq!(corrupted, codeword);
+            }
+        }
+    }
+
+    #[test]
+    fn ldpc_too_many_errors() {
+        let ldpc = Ldpc::ieee80211n_648r12();
+        let mut codeword = vec![0u8; ldpc.codeword_size()];
+        ldpc.encode(&vec![0u8; ldpc.data_size()], &mut codeword);
+
+        // random garbage should never decode, and should be left unmodified
+        for _ in 0..10 {
+            let mut corrupted = (0..ldpc.codeword_size())
+                .map(|_| rand::random::<u8>())
+                .collect::<Vec<_>>();
+            *corrupted.last_mut().unwrap() = 0;
+            let prev = corrupted.clone();
+            match ldpc.correct_errors(&mut corrupted) {
+                Ok(_) => assert!(ldpc.is_correct(&corrupted)),
+                Err(Error::TooManyErrors) => assert_eq!(corrupted, prev),
+            }
+        }
+    }
+
+    #[test]
+    fn ldpc_soft_decode() {
+        let ldpc = Ldpc::ieee80211n_648r12();
+        for algorithm in [
+            Algorithm::MinSum,
+            Algorithm::NormalizedMinSum,
+            #[cfg(feature="std")]
+            Algorithm::SumProduct,
+        ] {
+            // at Eb/N0 ~= 4dB, rate 1/2, decoding should essentially never
+            // fail
+            let sigma = 0.63;
+            let mut failures = 0;
+            for _ in 0..100 {
+                let data = (0..ldpc.data_size())
+                    .map(|_| rand::random::<u8>())
+                    .collect::<Vec<_>>();
+                let mut codeword = vec![0u8; ldpc.codeword_size()];
+                ldpc.encode(&data, &mut codeword);
+
+                let llrs = llrs(&ldpc, &codeword, sigma);
+                let mut decoded = vec![0u8; ldpc.codeword_size()];
+                match ldpc.decode(&llrs, &mut decoded, algorithm, 50) {
+                    Ok(_) => assert_eq!(decoded, codeword),
+                    Err(_) => failures += 1,
+                }
+            }
+            assert!(failures <= 1, "{:?} failed {} times", algorithm, failures);
+        }
+    }
+}
@@ -250,6 +250,19 @@
 //! more information about this in [`gf`'s module-level documentation
 //! ](../gf#finding-irreducible-polynomials-and-generators).
 //!
+//! If you're bringing your own polynomial, [`is_maximal`](crate::lfsr::is_maximal)
+//! can check that it generates a maximal-length sequence, and
+//! [`period`](crate::lfsr::period) reports how long the sequence actually is if
+//! it doesn't:
+//!
+//! ``` rust
+//! use gf256::lfsr::{is_maximal, period};
+//!
+//! assert!(is_maximal(0x11d));
+//! assert!(!is_maximal(0x11b));
+//! assert_eq!(period(0x11b), Some(51));
+//! ```
+//!
 //! Knowing that LFSRs are equivalent to polynomial multiplication and division,
 //! we can also step through multiple bits at a time by multiplying by a
 //! power-of-two:
@@ -399,6 +412,103 @@
 //! # }
 //! ```
 //!
+//! `skip` is limited to the width of the LFSR, but since the LFSR repeats
+//! every `NONZEROS` bits, `jump` can reduce any `u128` distance to a skip. This
+//! makes it cheap to split one LFSR into reproducible, non-overlapping
+//! substreams, for example one for each thread:
+//!
+//! ``` rust
+//! # use ::gf256::lfsr::Lfsr64;
+//! let mut lfsrs = (0..4u128)
+//!     .map(|i| {
+//!         let mut lfsr = Lfsr64::new(1);
+//!         lfsr.jump(i << 80);
+//!         lfsr
+//!     })
+//!     .collect::<Vec<_>>();
+//! assert_ne!(lfsrs[0].next(64), lfsrs[1].next(64));
+//! ```
+//!
+//! If you don't know how much data each substream will need, `leapfrog`
+//! instead interleaves the substreams, substream i takes every k-th byte
+//! starting at byte i, see [`LeapFrog`](crate::lfsr::LeapFrog).
+//!
+//! An LFSR can also be checkpointed with `state` and resumed exactly with
+//! `set_state`, or, with feature `serde`, serialized as its state.
+//!
+//! ## Synthesis
+//!
+//! Going the other direction, [`synthesize`](crate::lfsr::synthesize) uses the
+//! Berlekamp-Massey algorithm to find the shortest LFSR that generates a given
+//! sequence of bits. This only needs 2n bits to recover an LFSR of degree n,
+//! which is why LFSRs should never be used where unpredictability matters:
+//!
+//! ``` rust
+//! # #[cfg(feature="alloc")] {
+//! use gf256::lfsr::{Lfsr32, synthesize};
+//!
+//! let mut lfsr = Lfsr32::new(0x12345678);
+//! let bits = lfsr.bits().take(64).collect::<Vec<_>>();
+//!
+//! let synthesis = synthesize(bits);
+//! assert_eq!(synthesis.linear_complexity(), 32);
+//! assert_eq!(synthesis.polynomial(), Some(0x1000000af));
+//! # }
+//! ```
+//!
+//! Note [`synthesize`](crate::lfsr::synthesize) requires feature `alloc`.
+//!
+//! ## Scrambling
+//!
+//! LFSRs are also used to scramble data on serial links, breaking up long runs
+//! of identical bits. The LFSR structs provide both additive scramblers,
+//! [`scramble`](crate::lfsr::Lfsr16::scramble), which xor the data with the
+//! LFSR's output and need the descrambler to stay in sync, and multiplicative
+//! scramblers,
+//! [`scramble_multiplicative`](crate::lfsr::Lfsr16::scramble_multiplicative),
+//! which feed the scrambled bits back into the register so the descrambler
+//! can synchronize on its own, even after bit slips, at the cost of
+//! multiplying bit errors, see
+//! [`ERROR_MULTIPLICATION`](crate::lfsr::Lfsr16::ERROR_MULTIPLICATION):
+//!
+//! ``` rust
+//! use gf256::lfsr::Lfsr16;
+//!
+//! let mut buf = *b"Hello World!";
+//! let mut state = 0;
+//! Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+//!
+//! let mut state = 0;
+//! Lfsr16::descramble_multiplicative(&mut state, &mut buf);
+//! assert_eq!(&buf, b"Hello World!");
+//! ```
+//!
+//! The [`scramblers`](crate::lfsr::scramblers) module provides presets for a
+//! number of standard scramblers.
+//!
+//! Outside of serial links, the same xor is useful for whitening data, such as
+//! data written to flash, with [`whiten`](crate::lfsr::Lfsr16::whiten). Keep in
+//! mind none of this is encryption, LFSRs are trivially predictable.
+//!
+//! ## Signature analysis
+//!
+//! Instead of generating data, an LFSR can also consume data, compressing a
+//! stream of data into a `width`-bit signature. This is how
+//! built-in self-test (BIST) hardware checks circuit responses, with
+//! [`misr`](crate::lfsr::Lfsr16::misr) clocking in a whole word each step,
+//! and [`sisr`](crate::lfsr::Lfsr16::sisr) a single bit each step:
+//!
+//! ``` rust
+//! use gf256::lfsr::Lfsr32;
+//!
+//! let mut golden = 0;
+//! Lfsr32::misr(&mut golden, &[0x01234567, 0x89abcdef, 0xfedcba98]);
+//!
+//! let mut signature = 0;
+//! Lfsr32::misr(&mut signature, &[0x01234567, 0x89abcdef, 0xfedcba99]);
+//! assert_ne!(signature, golden);
+//! ```
+//!
 //! ## Optimizations
 //!
 //! Since LFSRs are equivalent to Galois-fields, they share a lot of the same
@@ -449,6 +559,12 @@
 //! for other pseudo-random number generators with the additional ability to seek and
 //! rewind, allowing perfect replayability.
 //!
+//! With feature `rand-core-09`, the LFSR structs also implement the
+//! `RngCore` and `SeedableRng` traits from version 0.9 of
+//! [`rand_core`](https://docs.rs/rand_core/0.9), which rand 0.9 is built on.
+//! Both versions are implemented at the same time, so dependencies on either
+//! version of rand can share the same LFSR structs.
+//!
 //! Note! If you're just looking for a pseudo-random number generator, the
 //! randomness generated by these LFSRs is equivalent to the same-sized, naive
 //! [Xorshift generators][xorshift], with the same limitations and cycle-length.
@@ -503,6 +619,14 @@
 ///   for computations, defaults to the correct type based on `p`.
 /// - `reflected` - Indicate if the LFSR should have its bits reversed,
 ///   defaults to false.
+/// - `galois` - Use a Galois configuration, where the seed is the register
+///   of a Galois LFSR. This is the default.
+/// - `fibonacci` - Use a Fibonacci configuration, where the seed is the
+///   register of a Fibonacci LFSR, which is also the next `width` bits of
+///   output. Each step shifts out the msb and shifts in the xor of the bits
+///   tapped by the polynomial. Both configurations generate the same
+///   sequences, but the same seed starts at a different point in the
+///   sequence.
 /// - `naive` - Use a naive bitwise implementation.
 /// - `table` - Use precomputed quotient and remainder tables. This is the default.
 /// - `small_table` - Use small, 16-element division and remainder tables.
@@ -530,6 +654,8 @@
 ///     p=p16,
 ///     p2=p32,
 ///     reflected=false,
+///     // galois,
+///     // fibonacci,
 ///     // naive,
 ///     // table,
 ///     // small_table,
@@ -570,6 +696,37 @@ pub struct Lfsr32 {}
 #[lfsr(polynomial=0x1000000000000001b)]
 pub struct Lfsr64 {}
 
+// LFSR synthesis
+//
+#[cfg(feature="alloc")]
+#[path="lfsr_synthesis.rs"]
+mod synthesis;
+#[cfg(feature="alloc")]
+pub use synthesis::{synthesize, Synthesis, SynthesisIter};
+
+// Gold codes
+//
+#[path="lfsr_gold.rs"]
+mod gold;
+pub use gold::{Gold, GoldCode, correlate};
+
+// Period and maximal-length checks
+//
+#[path="lfsr_period.rs"]
+mod period;
+pub use period::{period, is_maximal};
+
+// Leap-frogged substreams
+//
+#[path="lfsr_leapfrog.rs"]
+mod leapfrog;
+pub use leapfrog::LeapFrog;
+
+// Standard scrambler presets
+//
+#[path="lfsr_scramblers.rs"]
+pub mod scramblers;
+
 
 #[cfg(test)]
 mod test {
@@ -666,6 +823,131 @@ mod test {
         assert_eq!(buf, &[0x000000001c6db6c7,0x0000000001514515,0x00000000001ab1ab,0x0000000000011011,0x0000000000001db7,0x0000000000000145,0x000000000000001b,0x0000000000000001]);
     }
 
+    #[test]
+    fn lfsr_jump() {
+        let mut lfsr8 = Lfsr8::new(1);
+        lfsr8.jump(8*8 + 1000*u128::from(Lfsr8::NONZEROS));
+        let buf = iter::repeat_with(|| lfsr8.prev(8)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x5b,0x41,0x6e,0x92,0x81,0x4b,0x1c,0x01]);
+
+        let mut lfsr16 = Lfsr16::new(1);
+        lfsr16.jump(16*8 + 1000*u128::from(Lfsr16::NONZEROS));
+        let buf = iter::repeat_with(|| lfsr16.prev(16)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0xfcb8,0x94e7,0xb877,0x13d3,0xbdad,0x0451,0x002d,0x0001]);
+
+        let mut lfsr32 = Lfsr32::new(1);
+        lfsr32.jump(32*8 + 1000*u128::from(Lfsr32::NONZEROS));
+        let buf = iter::repeat_with(|| lfsr32.prev(32)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x7d21bf13,0x11579360,0xfafa511e,0x1010111b,0x00295f23,0x00004455,0x000000af,0x00000001]);
+
+        let mut lfsr64 = Lfsr64::new(1);
+        lfsr64.jump(64*8 + 1000*u128::from(Lfsr64::NONZEROS));
+        let buf = iter::repeat_with(|| lfsr64.prev(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000000001c6db6c7,0x0000000001514515,0x00000000001ab1ab,0x0000000000011011,0x0000000000001db7,0x0000000000000145,0x000000000000001b,0x0000000000000001]);
+
+        // jumps compose
+        let mut a = Lfsr32::new(0x12345678);
+        let mut b = Lfsr32::new(0x12345678);
+        a.jump(u128::MAX);
+        for _ in 0..4 {
+            b.jump(u128::MAX/4);
+        }
+        b.jump(3);
+        assert_eq!(a.next(32), b.next(32));
+
+        // and reverse
+        let mut lfsr64 = Lfsr64::new(1);
+        lfsr64.jump(u128::MAX - 64*8);
+        lfsr64.jump_backwards(u128::MAX);
+        lfsr64.jump(64*16);
+        let buf = iter::repeat_with(|| lfsr64.prev(64)).take(8).collect::<Vec<_>>();
+        assert_eq!(buf, &[0x000000001c6db6c7,0x0000000001514515,0x00000000001ab1ab,0x0000000000011011,0x0000000000001db7,0x0000000000000145,0x000000000000001b,0x0000000000000001]);
+    }
+
+    #[test]
+    fn lfsr_bits() {
+        let mut lfsr8 = Lfsr8::new(1);
+        let mut bits = Lfsr8::new(1);
+        assert!((0..100).all(|_| {
+            let x = lfsr8.next(8);
+            bits.bits().take(8).fold(0, |x, b| (x << 1) | u8::from(b)) == x
+        }));
+        assert!((0..100).all(|_| {
+            let x = lfsr8.prev(8);
+            bits.bits_backwards().take(8).fold(0, |x, b| (x >> 1) | (u8::from(b) << 7)) == x
+        }));
+
+        let mut lfsr64 = Lfsr64::new(0x123456789abcdef0);
+        let mut bits = Lfsr64::new(0x123456789abcdef0);
+        assert!((0..100).all(|_| {
+            let x = lfsr64.next(64);
+            bits.bits().take(64).fold(0, |x, b| (x << 1) | u64::from(b)) == x
+        }));
+
+        // reflected LFSRs output the lsb first
+        let mut lfsr64 = Lfsr64TableReflected::new(0x123456789abcdef0);
+        let mut bits = Lfsr64TableReflected::new(0x123456789abcdef0);
+        assert!((0..100).all(|_| {
+            let x = lfsr64.next(64);
+            bits.bits().take(64).fold(0, |x, b| (x >> 1) | (u64::from(b) << 63)) == x
+        }));
+
+        // and small LFSRs
+        let mut lfsr4 = Lfsr4Table::new(0x3);
+        let mut bits = Lfsr4Table::new(0x3);
+        assert!((0..100).all(|_| {
+            let x = lfsr4.next(4);
+            bits.bits().take(4).fold(0, |x, b| (x << 1) | u8::from(b)) == x
+        }));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn lfsr_synthesize() {
+        // recover each default LFSR from 2n bits of output
+        let mut lfsr8 = Lfsr8::new(0x12);
+        let synthesis = synthesize(iter::repeat_with(|| lfsr8.next(1) == 1).take(2*8));
+        assert_eq!(synthesis.linear_complexity(), 8);
+        assert_eq!(synthesis.polynomial(), Some(Lfsr8::POLYNOMIAL.0 as u128));
+
+        let mut lfsr16 = Lfsr16::new(0x1234);
+        let synthesis = synthesize(iter::repeat_with(|| lfsr16.next(1) == 1).take(2*16));
+        assert_eq!(synthesis.linear_complexity(), 16);
+        assert_eq!(synthesis.polynomial(), Some(Lfsr16::POLYNOMIAL.0 as u128));
+
+        let mut lfsr32 = Lfsr32::new(0x12345678);
+        let synthesis = synthesize(iter::repeat_with(|| lfsr32.next(1) == 1).take(2*32));
+        assert_eq!(synthesis.linear_complexity(), 32);
+        assert_eq!(synthesis.polynomial(), Some(Lfsr32::POLYNOMIAL.0 as u128));
+
+        let mut lfsr64 = Lfsr64::new(0x123456789abcdef0);
+        let synthesis = synthesize(iter::repeat_with(|| lfsr64.next(1) == 1).take(2*64));
+        assert_eq!(synthesis.linear_complexity(), 64);
+        assert_eq!(synthesis.polynomial(), Some(Lfsr64::POLYNOMIAL.0));
+
+        // and continue the sequence
+        let mut lfsr64 = Lfsr64::new(0x123456789abcdef0);
+        assert!(synthesis.iter().take(10000).all(|b| b == (lfsr64.next(1) == 1)));
+
+        // random sequences have linear complexity close to n/2, and the
+        // synthesized LFSR always reproduces them
+        let mut rng = rand::thread_rng();
+        for n in [0, 1, 2, 3, 10, 100, 1000] {
+            let bits = iter::repeat_with(|| rng.gen::<bool>()).take(n).collect::<Vec<_>>();
+            let synthesis = synthesize(bits.iter().copied());
+            assert!(synthesis.linear_complexity() <= n);
+            assert!(synthesis.iter().take(n).eq(bits.iter().copied()));
+        }
+
+        // edge cases
+        let synthesis = synthesize(iter::repeat_n(false, 100));
+        assert_eq!(synthesis.linear_complexity(), 0);
+        assert_eq!(synthesis.polynomial(), Some(1));
+        let synthesis = synthesize(iter::repeat_n(false, 199).chain(iter::once(true)));
+        assert_eq!(synthesis.linear_complexity(), 200);
+        assert_eq!(synthesis.polynomial(), None);
+    }
+
     // explicit modes
     #[lfsr(polynomial=0x11d, naive, naive_skip)]               pub struct Lfsr8Naive {}
     #[lfsr(polynomial=0x11d, table, table_skip)]               pub struct Lfsr8Table {}
@@ -1297,6 +1579,507 @@ mod test {
         assert_eq!(buf, &[0xe36db63800000000,0xa8a28a8000000000,0xd58d580000000000,0x8808800000000000,0xedb8000000000000,0xa280000000000000,0xd800000000000000,0x8000000000000000]);
     }
 
+    // Fibonacci LFSRs
+    #[lfsr(polynomial=0x1002d, naive, naive_skip, fibonacci)]               pub struct Lfsr16NaiveFibonacci {}
+    #[lfsr(polynomial=0x1002d, table, table_skip, fibonacci)]               pub struct Lfsr16TableFibonacci {}
+    #[lfsr(polynomial=0x1002d, small_table, small_table_skip, fibonacci)]   pub struct Lfsr16SmallTableFibonacci {}
+    #[lfsr(polynomial=0x1002d, barret, barret_skip, fibonacci)]             pub struct Lfsr16BarretFibonacci {}
+    #[lfsr(polynomial=0x1002d, galois)]                                     pub struct Lfsr16Galois {}
+    #[lfsr(polynomial=0x1000000000000001b, fibonacci, reflected=true)]      pub struct Lfsr64FibonacciReflected {}
+
+    #[test]
+    fn lfsr_fibonacci() {
+        // a bit-by-bit Fibonacci LFSR, shifting out the msb and shifting in
+        // the xor of the tapped bits
+        fn fibonacci16(r: &mut u16) -> u16 {
+            let taps = (0x1002du32 as u16).reverse_bits();
+            let msb = *r >> 15;
+            *r = (*r << 1) | ((*r & taps).count_ones() as u16 & 1);
+            msb
+        }
+
+        for seed in [0x0001, 0x1234, 0x8000, 0xffff] {
+            let mut r = seed;
+            let expected = iter::repeat_with(|| fibonacci16(&mut r)).take(1000).collect::<Vec<_>>();
+
+            let mut lfsr16 = Lfsr16NaiveFibonacci::new(seed);
+            assert_eq!(lfsr16.clone().next(16), seed);
+            assert_eq!(iter::repeat_with(|| lfsr16.next(1)).take(1000).collect::<Vec<_>>(), expected);
+            let mut lfsr16 = Lfsr16TableFibonacci::new(seed);
+            assert_eq!(lfsr16.clone().next(16), seed);
+            assert_eq!(iter::repeat_with(|| lfsr16.next(1)).take(1000).collect::<Vec<_>>(), expected);
+            let mut lfsr16 = Lfsr16SmallTableFibonacci::new(seed);
+            assert_eq!(lfsr16.clone().next(16), seed);
+            assert_eq!(iter::repeat_with(|| lfsr16.next(1)).take(1000).collect::<Vec<_>>(), expected);
+            let mut lfsr16 = Lfsr16BarretFibonacci::new(seed);
+            assert_eq!(lfsr16.clone().next(16), seed);
+            assert_eq!(iter::repeat_with(|| lfsr16.next(1)).take(1000).collect::<Vec<_>>(), expected);
+
+            // prev/skip still work
+            let mut lfsr16 = Lfsr16BarretFibonacci::new(seed);
+            lfsr16.skip(1000);
+            assert_eq!(iter::repeat_with(|| lfsr16.prev(1)).take(1000).collect::<Vec<_>>(),
+                expected.iter().rev().copied().collect::<Vec<_>>());
+        }
+
+        // Galois is the default
+        let mut a = Lfsr16Galois::new(0x1234);
+        let mut b = Lfsr16::new(0x1234);
+        assert_eq!(a.next(16), b.next(16));
+
+        // and Fibonacci generates the same sequence, just from a different
+        // point in the sequence
+        let mut a = Lfsr16::new(1);
+        a.skip(1000);
+        let x = a.clone().next(16);
+        let mut b = Lfsr16BarretFibonacci::new(x);
+        assert_eq!(a.next(16), b.next(16));
+        assert_eq!(a.next(16), b.next(16));
+
+        // reflected Fibonacci LFSRs output the seed lsb first
+        let mut lfsr64 = Lfsr64FibonacciReflected::new(0x123456789abcdef0);
+        assert_eq!(lfsr64.next(64), 0x123456789abcdef0);
+        let mut lfsr64 = Lfsr64FibonacciReflected::new(0x123456789abcdef0);
+        assert_eq!(lfsr64.next(1), 0);
+        assert_eq!(lfsr64.next(4), 0b1000);
+    }
+
+    #[test]
+    fn lfsr_whiten() {
+        let data = iter::repeat_with(|| rand::thread_rng().gen::<u8>()).take(1000).collect::<Vec<_>>();
+
+        // whitening is xor with the LFSR's output, even across odd lengths
+        macro_rules! check {
+            ($lfsr:ident, $seed:expr) => {{
+                let mut buf = data.clone();
+                let mut lfsr = $lfsr::new($seed);
+                for range in [0..1, 1..10, 10..333, 333..1000] {
+                    lfsr.whiten(&mut buf[range]);
+                }
+                let mut rng_bytes = vec![0u8; 1000];
+                $lfsr::new($seed).fill(&mut rng_bytes[..]);
+                assert!(buf.iter().zip(&data).zip(&rng_bytes).all(|((x, y), z)| x ^ y == *z));
+                $lfsr::new($seed).whiten(&mut buf);
+                assert_eq!(buf, data);
+            }}
+        }
+
+        check!(Lfsr8, 0x12);
+        check!(Lfsr16, 0x1234);
+        check!(Lfsr32, 0x12345678);
+        check!(Lfsr64, 0x123456789abcdef0);
+        check!(Lfsr4Naive, 0x3);
+        check!(Lfsr12Table, 0x123);
+        check!(Lfsr23Barret, 0x12345);
+        check!(Lfsr64TableReflected, 0x123456789abcdef0);
+        check!(Lfsr64FibonacciReflected, 0x123456789abcdef0);
+    }
+
+    #[test]
+    fn lfsr_scramble() {
+        let data = iter::repeat_with(|| rand::thread_rng().gen::<u8>()).take(1000).collect::<Vec<_>>();
+
+        // additive scramblers are just xor with the LFSR's output
+        let mut buf = data.clone();
+        Lfsr16::new(0x1234).scramble(&mut buf);
+        let mut rng_bytes = vec![0u8; 1000];
+        Lfsr16::new(0x1234).fill(&mut rng_bytes[..]);
+        assert!(buf.iter().zip(&data).zip(&rng_bytes).all(|((x, y), z)| x ^ y == *z));
+        Lfsr16::new(0x1234).descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        let mut buf = data.clone();
+        Lfsr4Naive::new(0x3).scramble(&mut buf);
+        assert_ne!(buf, data);
+        Lfsr4Naive::new(0x3).descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        let mut buf = data.clone();
+        Lfsr64TableReflected::new(0x123456789abcdef0).scramble(&mut buf);
+        assert_ne!(buf, data);
+        Lfsr64TableReflected::new(0x123456789abcdef0).descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        // multiplicative scramblers round-trip
+        let mut buf = data.clone();
+        let mut state = 0x1234;
+        Lfsr16::scramble_multiplicative(&mut state, &mut buf[..500]);
+        Lfsr16::scramble_multiplicative(&mut state, &mut buf[500..]);
+        assert_ne!(buf, data);
+        let mut state = 0x1234;
+        Lfsr16::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+
+        let mut buf = data.clone();
+        let mut state = 0x5;
+        Lfsr4Naive::scramble_multiplicative(&mut state, &mut buf);
+        assert_ne!(buf, data);
+        let mut state = 0x5;
+        Lfsr4Naive::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+
+        let mut buf = data.clone();
+        let mut state = 0x123456789abcdef0;
+        Lfsr64TableReflected::scramble_multiplicative(&mut state, &mut buf);
+        assert_ne!(buf, data);
+        let mut state = 0x123456789abcdef0;
+        Lfsr64TableReflected::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+
+        // and self-synchronize
+        let mut buf = data.clone();
+        let mut state = 0x1234;
+        Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+        let mut state = 0xffff;
+        Lfsr16::descramble_multiplicative(&mut state, &mut buf[100..]);
+        assert_eq!(buf[102..], data[102..]);
+
+        let mut buf = data.clone();
+        let mut state = 0x123456789abcdef0;
+        Lfsr64TableReflected::scramble_multiplicative(&mut state, &mut buf);
+        let mut state = 0;
+        Lfsr64TableReflected::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf[8..], data[8..]);
+
+        // scrambling zeros continues the Fibonacci LFSR from its state
+        let mut buf = vec![0u8; 100];
+        let mut state = 0x1234;
+        Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+        let mut lfsr16 = Lfsr16BarretFibonacci::new(0x1234);
+        lfsr16.skip(16);
+        let mut rng_bytes = vec![0u8; 100];
+        lfsr16.fill(&mut rng_bytes[..]);
+        assert_eq!(buf, rng_bytes);
+        assert_eq!(state, u16::from_be_bytes([buf[98], buf[99]]));
+    }
+
+    #[test]
+    fn lfsr_scramble_slips() {
+        let data = iter::repeat_with(|| rand::thread_rng().gen::<u8>()).take(1000).collect::<Vec<_>>();
+
+        // convert between bytes and bits, msb first or lsb first
+        fn to_bits(bytes: &[u8], reflected: bool) -> Vec<u8> {
+            bytes.iter()
+                .flat_map(|b| (0..8).map(move |i| {
+                    if reflected { (b >> i) & 1 } else { (b >> (7-i)) & 1 }
+                }))
+                .collect()
+        }
+        fn from_bits(bits: &[u8], reflected: bool) -> Vec<u8> {
+            bits.chunks(8)
+                .map(|c| c.iter().enumerate().fold(0, |b, (i, x)| {
+                    if reflected { b | (x << i) } else { b | (x << (7-i)) }
+                }))
+                .collect()
+        }
+
+        // dropped and inserted bits resynchronize after width bits
+        for (slip, insert) in [(1234, false), (4321, true), (7, false)] {
+            let mut buf = data.clone();
+            let mut state = 0x1234;
+            Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+            let mut bits = to_bits(&buf, false);
+            if insert { bits.insert(slip, 1); } else { bits.remove(slip); }
+            bits.resize(8*1000, 0);
+            let mut buf = from_bits(&bits, false);
+            let mut state = 0x1234;
+            Lfsr16::descramble_multiplicative(&mut state, &mut buf);
+            let bits = to_bits(&buf, false);
+            let expected = to_bits(&data, false);
+            assert_eq!(bits[..slip], expected[..slip]);
+            if insert {
+                assert_eq!(bits[slip+1+16..], expected[slip+16..8*1000-1]);
+            } else {
+                assert_eq!(bits[slip+16..8*1000-1], expected[slip+1+16..]);
+            }
+
+            let mut buf = data.clone();
+            let mut state = 0x123456789abcdef0;
+            Lfsr64TableReflected::scramble_multiplicative(&mut state, &mut buf);
+            let mut bits = to_bits(&buf, true);
+            if insert { bits.insert(slip, 1); } else { bits.remove(slip); }
+            bits.resize(8*1000, 0);
+            let mut buf = from_bits(&bits, true);
+            let mut state = 0x123456789abcdef0;
+            Lfsr64TableReflected::descramble_multiplicative(&mut state, &mut buf);
+            let bits = to_bits(&buf, true);
+            let expected = to_bits(&data, true);
+            assert_eq!(bits[..slip], expected[..slip]);
+            if insert {
+                assert_eq!(bits[slip+1+64..], expected[slip+64..8*1000-1]);
+            } else {
+                assert_eq!(bits[slip+64..8*1000-1], expected[slip+1+64..]);
+            }
+        }
+
+        // bit errors are multiplied by the number of terms in the polynomial
+        assert_eq!(Lfsr16::ERROR_MULTIPLICATION, 5);
+        assert_eq!(Lfsr64::ERROR_MULTIPLICATION, 5);
+        assert_eq!(scramblers::Ieee8023::ERROR_MULTIPLICATION, 3);
+        assert_eq!(scramblers::V34Call::ERROR_MULTIPLICATION, 3);
+        for i in [0, 100, 8*1000-1] {
+            let mut buf = data.clone();
+            let mut state = 0;
+            scramblers::Ieee8023::scramble_multiplicative(&mut state, &mut buf);
+            buf[i/8] ^= 1 << (i%8);
+            let mut state = 0;
+            scramblers::Ieee8023::descramble_multiplicative(&mut state, &mut buf);
+            let errors = buf.iter().zip(&data).map(|(x, y)| (x ^ y).count_ones()).sum::<u32>();
+            // errors past the end of the data are lost
+            let expected = [0, 39, 58].iter().filter(|d| i+**d < 8*1000).count() as u32;
+            assert_eq!(errors, expected);
+        }
+    }
+
+    #[test]
+    fn lfsr_scramblers() {
+        use super::scramblers::*;
+        let data = iter::repeat_with(|| rand::thread_rng().gen::<u8>()).take(1000).collect::<Vec<_>>();
+
+        // DVB-S, straight from the specification's shift register
+        let mut r = [1u8,0,0,1,0,1,0,1,0,0,0,0,0,0,0];
+        let expected = data.iter()
+            .map(|x| {
+                let mut y = 0;
+                for i in (0..8).rev() {
+                    let b = r[13] ^ r[14];
+                    r.rotate_right(1);
+                    r[0] = b;
+                    y |= b << i;
+                }
+                x ^ y
+            })
+            .collect::<Vec<_>>();
+        let mut buf = data.clone();
+        DvbS::init().scramble(&mut buf);
+        assert_eq!(buf, expected);
+        DvbS::init().descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        // PCIe, straight from the specification's shift register
+        let mut r = 0xffffu16;
+        let expected = data.iter()
+            .map(|x| {
+                let mut y = 0;
+                for i in 0..8 {
+                    let b = (r >> 15) as u8;
+                    r = (r << 1) ^ if b != 0 { 0x0039 } else { 0 };
+                    y |= b << i;
+                }
+                x ^ y
+            })
+            .collect::<Vec<_>>();
+        let mut buf = data.clone();
+        Usb3::init().scramble(&mut buf);
+        assert_eq!(buf, expected);
+        Pcie::init().descramble(&mut buf);
+        assert_eq!(buf, data);
+
+        // self-synchronizing scramblers, straight from their specifications
+        fn self_sync(data: &[u8], taps: &[usize]) -> Vec<u8> {
+            let mut s = vec![0u8; 64];
+            let mut n = 64;
+            data.iter()
+                .map(|x| {
+                    let mut y = 0;
+                    for i in 0..8 {
+                        let mut b = (x >> i) & 1;
+                        for t in taps {
+                            b ^= s[n-t];
+                        }
+                        s.push(b);
+                        n += 1;
+                        y |= b << i;
+                    }
+                    y
+                })
+                .collect()
+        }
+
+        let expected = self_sync(&data, &[39, 58]);
+        let mut buf = data.clone();
+        let mut state = 0;
+        Ieee8023::scramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, expected);
+        let mut state = 0;
+        Ieee8023::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+
+        let expected = self_sync(&data, &[18, 23]);
+        let mut buf = data.clone();
+        let mut state = 0;
+        V34Call::scramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, expected);
+        let mut state = 0;
+        V34Call::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+
+        let expected = self_sync(&data, &[5, 23]);
+        let mut buf = data.clone();
+        let mut state = 0;
+        V34Answer::scramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, expected);
+        let mut state = 0;
+        V34Answer::descramble_multiplicative(&mut state, &mut buf);
+        assert_eq!(buf, data);
+    }
+
+    #[lfsr(polynomial=0x25, fibonacci)] pub struct Lfsr5Fibonacci {}
+
+    #[test]
+    fn lfsr_gold() {
+        // the m-sequences match Fibonacci LFSRs
+        let gold = Gold::new(0x25, 0x3d).with_seeds(0x12, 0x0d);
+        let mut lfsr5 = Lfsr5Fibonacci::new(0x12);
+        assert!(gold.code(0).eq(iter::repeat_with(|| lfsr5.next(1) == 1).take(31)));
+
+        // cross-correlation between every pair of codes is three-valued
+        for (gold, t) in [
+            (Gold::new(0x25, 0x3d), 9),
+            (Gold::new(0x43, 0x67).with_seeds(0x2a, 0x3f), 17),
+        ] {
+            let codes = gold.codes()
+                .map(|code| code.collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            assert_eq!(codes.len(), gold.family_size());
+            assert!(codes.iter().all(|code| code.len() == gold.code_len()));
+
+            for a in 0..codes.len() {
+                assert_eq!(correlate(&codes[a], &codes[a], 0), gold.code_len() as isize);
+                for b in a+1..codes.len() {
+                    for shift in 0..gold.code_len() {
+                        let c = correlate(&codes[a], &codes[b], shift);
+                        assert!(c == -1 || c == -t || c == t-2, "{} {} {} {}", a, b, shift, c);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lfsr_misr() {
+        // the signature is the inputs modulo the polynomial
+        let inputs = (0..100u64).map(|i| i.wrapping_mul(0x9e3779b97f4a7c15)).collect::<Vec<_>>();
+        let mut expected = p128(0);
+        for input in &inputs {
+            expected = (expected*p128(2) + p128::from(*input)) % Lfsr64::POLYNOMIAL;
+        }
+        let mut signature = 0;
+        Lfsr64::misr(&mut signature, &inputs);
+        assert_eq!(signature, u64::try_from(expected.0).unwrap());
+        let mut signature = 0;
+        Lfsr64Naive::misr(&mut signature, &inputs[..50]);
+        Lfsr64Naive::misr(&mut signature, &inputs[50..]);
+        assert_eq!(signature, u64::try_from(expected.0).unwrap());
+
+        // signatures are linear
+        let mut errors = vec![0u64; 100];
+        errors[17] = 0x100;
+        errors[83] = 0x8000000000000001;
+        let faulty = inputs.iter().zip(&errors).map(|(x, e)| x ^ e).collect::<Vec<_>>();
+        let (mut a, mut b, mut c) = (0, 0, 0);
+        Lfsr64::misr(&mut a, &inputs);
+        Lfsr64::misr(&mut b, &faulty);
+        Lfsr64::misr(&mut c, &errors);
+        assert_eq!(a ^ b, c);
+        assert_ne!(a, b);
+
+        // an SISR is an MISR with single-bit inputs
+        let data = b"Hello World!";
+        let bits = data.iter()
+            .flat_map(|b| (0..8).rev().map(move |i| u16::from((b >> i) & 1)))
+            .collect::<Vec<_>>();
+        let mut a = 0;
+        Lfsr16::misr(&mut a, &bits);
+        let mut b = 0;
+        Lfsr16::sisr(&mut b, data);
+        assert_eq!(a, b);
+
+        // and reflected LFSRs reverse the bit order
+        let reversed = data.iter().map(|b| b.reverse_bits()).collect::<Vec<_>>();
+        let mut a = 0;
+        Lfsr64::sisr(&mut a, &reversed);
+        let mut b = 0;
+        Lfsr64TableReflected::sisr(&mut b, data);
+        assert_eq!(a.reverse_bits(), b);
+    }
+
+    #[test]
+    fn lfsr_leapfrog() {
+        use rand::RngCore;
+
+        // interleaving the substreams recreates the original stream, this
+        // includes LFSRs narrower than a byte and reflected LFSRs
+        fn interleave<L: RngCore>(mut substreams: Vec<LeapFrog<L>>, len: usize) -> Vec<u8> {
+            let k = substreams.len();
+            let mut buf = vec![0; len];
+            for i in 0..len {
+                substreams[i % k].fill_bytes(&mut buf[i..i+1]);
+            }
+            buf
+        }
+
+        for k in 1..=5 {
+            let mut expected = vec![0; 100];
+            Lfsr4Naive::new(1).fill_bytes(&mut expected);
+            assert_eq!(interleave(Lfsr4Naive::new(1).leapfrog(k).collect(), 100), expected);
+
+            let mut expected = vec![0; 100];
+            Lfsr23Barret::new(0x12345).fill_bytes(&mut expected);
+            assert_eq!(interleave(Lfsr23Barret::new(0x12345).leapfrog(k).collect(), 100), expected);
+
+            let mut expected = vec![0; 100];
+            Lfsr64TableReflected::new(1).fill_bytes(&mut expected);
+            assert_eq!(interleave(Lfsr64TableReflected::new(1).leapfrog(k).collect(), 100), expected);
+        }
+
+        // substreams don't overlap
+        let substreams = Lfsr16::new(1).leapfrog(4)
+            .map(|mut substream| substream.next_u64())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(substreams.len(), 4);
+    }
+
+    #[test]
+    fn lfsr_period() {
+        // the default polynomials are all maximal
+        assert!(is_maximal(Lfsr8::POLYNOMIAL.0 as u128));
+        assert!(is_maximal(Lfsr16::POLYNOMIAL.0 as u128));
+        assert!(is_maximal(Lfsr32::POLYNOMIAL.0 as u128));
+        assert!(is_maximal(Lfsr64::POLYNOMIAL.0));
+        assert!(is_maximal(0x1000000000000001b));
+
+        // compare against brute force for every small polynomial
+        for p in (3u128..0x200).step_by(2) {
+            let n = 128 - p.leading_zeros() - 1;
+            let mut x = 1u128;
+            let mut e = 0;
+            loop {
+                x <<= 1;
+                if x >> n != 0 {
+                    x ^= p;
+                }
+                e += 1;
+                if x == 1 {
+                    break;
+                }
+            }
+            assert_eq!(period(p), Some(e), "{:#x}", p);
+            assert_eq!(is_maximal(p), e == (1 << n) - 1, "{:#x}", p);
+        }
+
+        // repeated and reducible factors
+        assert_eq!(period(0x11), Some(4));
+        assert_eq!(period(0x1f), Some(5));
+        assert_eq!(period(0x15), Some(6));
+        assert_eq!(period(0x1_0000_0001), Some(32));
+
+        // not invertible
+        assert_eq!(period(0x1), None);
+        assert_eq!(period(0x12), None);
+        assert!(!is_maximal(0x12));
+    }
+
     // all LFSR params
     #[lfsr(
         polynomial=0x1000000000000001b,
@@ -1325,7 +2108,7 @@ mod test {
     fn lfsr_rng_consistency() {
         // normal order
         let mut lfsr = Lfsr8::new(1);
-        let next_bytes = iter::repeat_with(|| lfsr.next(8) as u8).take(100).collect::<Vec<_>>();
+        let next_bytes = iter::repeat_with(|| lfsr.next(8)).take(100).collect::<Vec<_>>();
         let mut rng_bytes = vec![0u8; 100];
         let mut lfsr = Lfsr8::new(1);
         lfsr.fill(&mut rng_bytes[..]);
@@ -1382,6 +2165,85 @@ mod test {
         assert_eq!(&next_bytes, &rng_bytes);
     }
 
+    #[cfg(feature="rand-core-09")]
+    #[test]
+    fn lfsr_rand_core_09() {
+        use rand_core_09::RngCore as RngCore09;
+        use rand_core_09::SeedableRng as SeedableRng09;
+
+        // rand_core 0.9 generates the same data as rand 0.8
+        let mut a = <Lfsr16 as SeedableRng09>::from_seed([0x34, 0x12]);
+        let mut b = <Lfsr16 as rand::SeedableRng>::from_seed([0x34, 0x12]);
+        assert_eq!(RngCore09::next_u32(&mut a), rand::RngCore::next_u32(&mut b));
+        assert_eq!(RngCore09::next_u64(&mut a), rand::RngCore::next_u64(&mut b));
+        let mut a_bytes = vec![0u8; 100];
+        let mut b_bytes = vec![0u8; 100];
+        RngCore09::fill_bytes(&mut a, &mut a_bytes);
+        rand::RngCore::fill_bytes(&mut b, &mut b_bytes);
+        assert_eq!(a_bytes, b_bytes);
+
+        let mut a = <Lfsr64TableReflected as SeedableRng09>::seed_from_u64(42);
+        let mut b = <Lfsr64TableReflected as SeedableRng09>::seed_from_u64(42);
+        assert_eq!(RngCore09::next_u64(&mut a), RngCore09::next_u64(&mut b));
+
+        let mut a = <Lfsr8 as SeedableRng09>::from_rng(&mut Lfsr64::new(1));
+        let mut b = <Lfsr8 as rand::SeedableRng>::from_rng(Lfsr64::new(1)).unwrap();
+        assert_eq!(RngCore09::next_u32(&mut a), rand::RngCore::next_u32(&mut b));
+    }
+
+    #[test]
+    fn lfsr_state() {
+        // checkpoint and resume
+        let mut lfsr = Lfsr64::new(0x123456789abcdef0);
+        lfsr.skip(1000);
+        let state = lfsr.state();
+        let expected = iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>();
+        let mut lfsr = Lfsr64::new(state);
+        assert_eq!(iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>(), expected);
+        lfsr.set_state(state);
+        assert_eq!(iter::repeat_with(|| lfsr.next(64)).take(100).collect::<Vec<_>>(), expected);
+
+        // state is in the same form as the seed
+        assert_eq!(Lfsr8::new(0x12).state(), 0x12);
+        assert_eq!(Lfsr8::new(0).state(), 0x01);
+        assert_eq!(Lfsr4Table::new(0x3).state(), 0x3);
+        assert_eq!(Lfsr64TableReflected::new(0x123456789abcdef0).state(), 0x123456789abcdef0);
+        assert_eq!(Lfsr16BarretFibonacci::new(0x1234).state(), 0x1234);
+        assert_eq!(Lfsr64FibonacciReflected::new(0x123456789abcdef0).state(), 0x123456789abcdef0);
+
+        let mut lfsr = Lfsr64TableReflected::new(0x123456789abcdef0);
+        lfsr.skip(1000);
+        let mut resumed = Lfsr64TableReflected::new(lfsr.state());
+        assert_eq!(lfsr.next(64), resumed.next(64));
+
+        let mut lfsr = Lfsr64FibonacciReflected::new(0x123456789abcdef0);
+        lfsr.skip(1000);
+        let mut resumed = Lfsr64FibonacciReflected::new(lfsr.state());
+        assert_eq!(lfsr.next(64), resumed.next(64));
+    }
+
+    #[cfg(feature="serde")]
+    #[test]
+    fn lfsr_serde() {
+        let mut lfsr = Lfsr16::new(0x1234);
+        lfsr.skip(1000);
+        let json = serde_json::to_string(&lfsr).unwrap();
+        assert_eq!(json, alloc::format!("{}", lfsr.state()));
+        let mut resumed = serde_json::from_str::<Lfsr16>(&json).unwrap();
+        assert_eq!(lfsr.next(16), resumed.next(16));
+
+        let mut lfsr = Lfsr64FibonacciReflected::new(0x123456789abcdef0);
+        lfsr.skip(1000);
+        let cbor = serde_cbor::to_vec(&lfsr).unwrap();
+        let mut resumed = serde_cbor::from_slice::<Lfsr64FibonacciReflected>(&cbor).unwrap();
+        assert_eq!(lfsr.next(64), resumed.next(64));
+
+        // zero and out-of-range states are rejected
+        assert!(serde_json::from_str::<Lfsr16>("0").is_err());
+        assert!(serde_json::from_str::<Lfsr4Table>("16").is_err());
+        assert!(serde_json::from_str::<Lfsr4Table>("15").is_ok());
+    }
+
     #[test]
     fn lfsr_uniqueness() {
         let mut lfsr = Lfsr8::new(1);
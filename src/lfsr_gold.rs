@@ -0,0 +1,204 @@
+//! Gold codes
+//!
+//! [Gold codes][gold-codes] are families of sequences with bounded
+//! cross-correlation, used to let many transmitters share a channel, most
+//! famously in GPS and CDMA.
+//!
+//! A Gold code family is built from a preferred pair of m-sequences, u and v,
+//! generated by two LFSRs of the same degree n. The family contains u, v, and
+//! u xored with each of the 2^n-1 cyclic shifts of v, for a total of 2^n+1
+//! codes, each 2^n-1 bits long. The cross-correlation between any two codes
+//! in the family takes only three values, `-1`, `-t`, and `t-2`, where
+//! `t = 2^((n+2)/2)+1`, rounding down.
+//!
+//! [gold-codes]: https://en.wikipedia.org/wiki/Gold_code
+//!
+
+use core::iter::FusedIterator;
+
+
+/// A family of Gold codes generated by a preferred pair of LFSRs.
+///
+/// The LFSRs use the Fibonacci configuration, so each seed is the first n
+/// bits of its m-sequence, see the `fibonacci` option of the
+/// [`lfsr`](super::lfsr) macro.
+///
+/// For example, the GPS C/A codes are Gold codes from the LFSRs
+/// `1 + x^3 + x^10` and `1 + x^2 + x^3 + x^6 + x^8 + x^9 + x^10`, both seeded
+/// with all ones. The code for PRN 1 delays the second m-sequence by 5 chips:
+///
+/// ``` rust
+/// use gf256::lfsr::Gold;
+///
+/// let gps = Gold::new(0x481, 0x597)
+///     .with_seeds(0x3ff, 0x3ff);
+/// assert_eq!(gps.code_len(), 1023);
+/// assert_eq!(gps.family_size(), 1025);
+///
+/// // the first 10 chips of PRN 1 are 1440 in octal
+/// let prn1 = gps.code(2 + 1023-5).take(10)
+///     .fold(0, |x, b| (x << 1) | u16::from(b));
+/// assert_eq!(prn1, 0o1440);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Gold {
+    width: usize,
+    a_taps: u128,
+    b_taps: u128,
+    a_seed: u128,
+    b_seed: u128,
+}
+
+impl Gold {
+    /// Create a Gold code family from a preferred pair of polynomials.
+    ///
+    /// Polynomials are in the same form as the `polynomial` option of the
+    /// [`lfsr`](super::lfsr) macro, and must have the same degree. Note
+    /// this doesn't check that the polynomials are a preferred pair, if
+    /// they aren't, the cross-correlation between codes isn't bounded.
+    ///
+    /// Both LFSRs are seeded with `1` by default.
+    ///
+    pub fn new(a: u128, b: u128) -> Self {
+        let width = (128 - a.leading_zeros() as usize).saturating_sub(1);
+        assert!(width > 0 && width < 8*core::mem::size_of::<usize>());
+        assert!((128 - b.leading_zeros() as usize).saturating_sub(1) == width,
+            "Gold code polynomials must have the same degree");
+
+        // the Fibonacci taps are the polynomial, without x^n, bit-reversed
+        let mask = (1u128 << width) - 1;
+        Self {
+            width,
+            a_taps: (a & mask).reverse_bits() >> (128-width),
+            b_taps: (b & mask).reverse_bits() >> (128-width),
+            a_seed: 1,
+            b_seed: 1,
+        }
+    }
+
+    /// Set the seeds of both LFSRs.
+    ///
+    /// The seeds can't be `0`, so if `0` is provided, the seed `1` is used
+    /// instead.
+    ///
+    pub fn with_seeds(self, a_seed: u128, b_seed: u128) -> Self {
+        let mask = (1u128 << self.width) - 1;
+        Self {
+            a_seed: if a_seed & mask == 0 { 1 } else { a_seed & mask },
+            b_seed: if b_seed & mask == 0 { 1 } else { b_seed & mask },
+            ..self
+        }
+    }
+
+    /// Degree of the LFSRs, n.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Length of each code in bits, 2^n-1.
+    pub fn code_len(&self) -> usize {
+        (1 << self.width) - 1
+    }
+
+    /// Number of codes in the family, 2^n+1.
+    pub fn family_size(&self) -> usize {
+        (1 << self.width) + 1
+    }
+
+    /// Get the k-th code in the family.
+    ///
+    /// Code 0 is u, code 1 is v, and code k+2 is u xored with v shifted
+    /// forward by k bits.
+    ///
+    pub fn code(&self, k: usize) -> GoldCode {
+        assert!(k < self.family_size());
+        let mut code = GoldCode {
+            width: self.width,
+            a_taps: self.a_taps,
+            b_taps: self.b_taps,
+            a: if k != 1 { self.a_seed } else { 0 },
+            b: if k != 0 { self.b_seed } else { 0 },
+            remaining: self.code_len(),
+        };
+
+        for _ in 0..k.saturating_sub(2) {
+            GoldCode::step(self.width, self.b_taps, &mut code.b);
+        }
+
+        code
+    }
+
+    /// Iterate over every code in the family.
+    pub fn codes(&self) -> impl Iterator<Item=GoldCode> + '_ {
+        (0..self.family_size()).map(move |k| self.code(k))
+    }
+}
+
+/// An iterator over the bits of a Gold code, see [`Gold::code`].
+#[derive(Debug, Clone)]
+pub struct GoldCode {
+    width: usize,
+    a_taps: u128,
+    b_taps: u128,
+    a: u128,
+    b: u128,
+    remaining: usize,
+}
+
+impl GoldCode {
+    /// Step a Fibonacci LFSR, returning the bit shifted out
+    fn step(width: usize, taps: u128, r: &mut u128) -> bool {
+        let msb = (*r >> (width-1)) & 1;
+        let mask = (1u128 << width) - 1;
+        *r = ((*r << 1) | u128::from((*r & taps).count_ones() & 1)) & mask;
+        msb != 0
+    }
+}
+
+impl Iterator for GoldCode {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let a = Self::step(self.width, self.a_taps, &mut self.a);
+        let b = Self::step(self.width, self.b_taps, &mut self.b);
+        Some(a ^ b)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for GoldCode {}
+impl FusedIterator for GoldCode {}
+
+/// Find the periodic cross-correlation between two codes, with `b` shifted
+/// forward by `shift` bits.
+///
+/// Bits are mapped to `+1` and `-1`, so this is the number of matching bits
+/// minus the number of differing bits. The autocorrelation of a code with
+/// itself, with no shift, is its length.
+///
+/// ``` rust
+/// use gf256::lfsr::{Gold, correlate};
+///
+/// let gold = Gold::new(0x25, 0x3d);
+/// let a = gold.code(2).collect::<Vec<_>>();
+/// let b = gold.code(3).collect::<Vec<_>>();
+/// assert_eq!(correlate(&a, &a, 0), 31);
+/// assert!((0..31).all(|i| [-1, -9, 7].contains(&correlate(&a, &b, i))));
+/// ```
+///
+pub fn correlate(a: &[bool], b: &[bool], shift: usize) -> isize {
+    assert!(a.len() == b.len());
+    let n = a.len();
+    a.iter().enumerate()
+        .map(|(i, x)| if *x == b[(i+shift) % n] { 1 } else { -1 })
+        .sum()
+}
@@ -0,0 +1,137 @@
+//! Leap-frogged LFSR substreams
+//!
+//! Leap-frogging splits one random stream into k interleaved substreams,
+//! substream i takes bytes i, i+k, i+2k, ... of the original stream. Unlike
+//! splitting the stream into blocks, this doesn't require knowing in advance
+//! how much data each substream will consume, and the combined output of all
+//! substreams is exactly the original stream.
+//!
+//! LFSRs are well suited for this since jumping ahead is cheap, see
+//! [`jump`](super::Lfsr64::jump).
+//!
+
+use rand::RngCore;
+
+
+/// One of k interleaved substreams of an LFSR, created by the `leapfrog`
+/// method of the LFSR structs.
+///
+/// Each byte of output advances the underlying LFSR by one byte, and then
+/// jumps over the bytes belonging to the other k-1 substreams.
+///
+/// ``` rust
+/// use gf256::lfsr::Lfsr64;
+/// use rand::RngCore;
+///
+/// let mut substreams = Lfsr64::new(1).leapfrog(3).collect::<Vec<_>>();
+///
+/// // interleaving the substreams recreates the original stream
+/// let mut buf = [0u8; 12];
+/// for (i, b) in buf.iter_mut().enumerate() {
+///     let mut byte = [0u8; 1];
+///     substreams[i % 3].fill_bytes(&mut byte);
+///     *b = byte[0];
+/// }
+///
+/// let mut expected = [0u8; 12];
+/// Lfsr64::new(1).fill_bytes(&mut expected);
+/// assert_eq!(buf, expected);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct LeapFrog<L> {
+    lfsr: L,
+    jump: fn(&mut L, u128),
+    stride: u128,
+}
+
+impl<L: Clone> LeapFrog<L> {
+    /// Split an LFSR into k interleaved substreams.
+    ///
+    /// `jump` must advance the LFSR by the given number of bits, this is
+    /// usually the LFSR's [`jump`](super::Lfsr64::jump) method. Prefer the
+    /// `leapfrog` method of the LFSR structs, which provides this for you.
+    ///
+    pub fn split(
+        lfsr: &L,
+        jump: fn(&mut L, u128),
+        k: usize
+    ) -> impl ExactSizeIterator<Item=Self> {
+        assert!(k > 0, "leapfrog requires at least one substream");
+        let lfsr = lfsr.clone();
+        let stride = 8*(k as u128 - 1);
+        (0..k).map(move |i| {
+            let mut lfsr = lfsr.clone();
+            jump(&mut lfsr, 8*(i as u128));
+            LeapFrog {
+                lfsr,
+                jump,
+                stride,
+            }
+        })
+    }
+
+    /// The underlying LFSR, positioned at the next byte of this substream.
+    pub fn lfsr(&self) -> &L {
+        &self.lfsr
+    }
+}
+
+impl<L: RngCore> LeapFrog<L> {
+    #[inline]
+    fn next_byte(&mut self) -> u8 {
+        let mut byte = [0u8; 1];
+        self.lfsr.fill_bytes(&mut byte);
+        if self.stride > 0 {
+            (self.jump)(&mut self.lfsr, self.stride);
+        }
+        byte[0]
+    }
+}
+
+impl<L: RngCore> RngCore for LeapFrog<L> {
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest {
+            *b = self.next_byte();
+        }
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0; 4];
+        self.fill_bytes(&mut buf);
+        u32::from_le_bytes(buf)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0; 8];
+        self.fill_bytes(&mut buf);
+        u64::from_le_bytes(buf)
+    }
+}
+
+#[cfg(feature="rand-core-09")]
+impl<L: RngCore> rand_core_09::RngCore for LeapFrog<L> {
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RngCore::fill_bytes(self, dest)
+    }
+
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        RngCore::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        RngCore::next_u64(self)
+    }
+}
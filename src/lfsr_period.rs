@@ -0,0 +1,423 @@
+//! LFSR period and maximal-length checks
+//!
+//! An LFSR defined by a polynomial of degree n only generates a
+//! maximal-length sequence, visiting all 2^n-1 non-zero states, if the
+//! polynomial is primitive. Otherwise the sequence repeats early, and
+//! different seeds may even end up in different cycles.
+//!
+//! The period of an LFSR is the multiplicative order of x modulo its
+//! polynomial, the smallest e such that `x^e = 1 (mod p)`. We can find this
+//! without stepping through the sequence by factoring the polynomial:
+//!
+//! 1. Split the polynomial into square-free parts, `p = Π s_k^k`.
+//!
+//! 2. Split each square-free part into the products of its irreducible
+//!    factors of each degree d with distinct-degree factorization.
+//!
+//! 3. The order of x modulo a product of irreducible polynomials of degree d
+//!    divides 2^d-1, so we can find it by dividing out the prime factors of
+//!    2^d-1, the same trick used to test for generators in
+//!    [`examples/find-p.rs`][find-p].
+//!
+//! 4. Repeated factors multiply the order by the smallest power of 2 >= k,
+//!    and the period is the least common multiple of all of these orders.
+//!
+//! [find-p]: https://github.com/geky/gf256/blob/master/examples/find-p.rs
+//!
+
+use crate::p::p128;
+use core::ops::Deref;
+
+
+/// Find the period of an LFSR defined by the given polynomial.
+///
+/// The polynomial is in the same form as the `polynomial` option of the
+/// [`lfsr`](super::lfsr) macro. This is the number of steps before the LFSR
+/// seeded with `1` returns to its initial state, and the longest period of
+/// any seed. The period of other seeds always divides this.
+///
+/// Returns `None` if the polynomial is divisible by x, or has degree 0, in
+/// which case the LFSR may never return to its initial state.
+///
+/// ``` rust
+/// use gf256::lfsr::period;
+///
+/// // x^4+x+1 is primitive
+/// assert_eq!(period(0x13), Some(15));
+/// // x^4+x^3+x^2+x+1 is irreducible, but not primitive
+/// assert_eq!(period(0x1f), Some(5));
+/// // x^4+1 = (x+1)^4
+/// assert_eq!(period(0x11), Some(4));
+/// ```
+///
+pub fn period(polynomial: u128) -> Option<u128> {
+    let p = p128(polynomial);
+    if deg(p) == 0 || polynomial & 1 == 0 {
+        return None;
+    }
+
+    let mut period = 1;
+    for &(s, k) in square_free_factors(p).iter() {
+        // repeated factors multiply the order by the smallest power of 2 >= k
+        let t = (k as u128).next_power_of_two();
+        for &(g, d) in distinct_degree_factors(s).iter() {
+            period = lcm(period, t * order_of_x(g, d));
+        }
+    }
+
+    Some(period)
+}
+
+/// Check if the LFSR defined by the given polynomial generates a
+/// maximal-length sequence, with a period of 2^n-1.
+///
+/// This is true only if the polynomial is primitive. The polynomial is in the
+/// same form as the `polynomial` option of the [`lfsr`](super::lfsr) macro.
+///
+/// ``` rust
+/// use gf256::lfsr::{is_maximal, Lfsr64};
+///
+/// assert!(is_maximal(Lfsr64::POLYNOMIAL.0));
+/// assert!(is_maximal(0x13));
+/// assert!(!is_maximal(0x1f));
+/// ```
+///
+pub fn is_maximal(polynomial: u128) -> bool {
+    let n = deg(p128(polynomial));
+    n > 0 && period(polynomial) == Some(u128::MAX >> (128-n))
+}
+
+
+// A fixed-capacity list of factors
+//
+// The number of factors of a 128-bit polynomial or integer, counted with
+// multiplicity, is bounded by its width, so we can avoid alloc
+//
+#[derive(Clone)]
+struct Factors<T> {
+    buf: [T; 128],
+    len: usize,
+}
+
+impl<T: Copy> Factors<T> {
+    fn new(zero: T) -> Self {
+        Self { buf: [zero; 128], len: 0 }
+    }
+
+    fn push(&mut self, x: T) {
+        self.buf[self.len] = x;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.buf[self.len])
+    }
+}
+
+impl<T> Deref for Factors<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.buf[..self.len]
+    }
+}
+
+
+// polynomial helpers
+
+fn deg(p: p128) -> u32 {
+    (128 - p.leading_zeros()).saturating_sub(1)
+}
+
+fn gcd(mut a: p128, mut b: p128) -> p128 {
+    while b != p128(0) {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Multiply a and b modulo p, using shifts so we don't need a 256-bit
+/// intermediate
+fn mul_mod(a: p128, b: p128, p: p128) -> p128 {
+    let n = deg(p);
+    let mut x = p128(0);
+    for i in (0..128-b.leading_zeros()).rev() {
+        // x = x*2 mod p, where 2 is the polynomial x
+        x = x << 1;
+        if (x >> n) & p128(1) != p128(0) {
+            x ^= p;
+        }
+        if (b >> i) & p128(1) != p128(0) {
+            x ^= a;
+        }
+    }
+    x
+}
+
+/// Find x^e modulo p, via exponentiation by squaring
+fn pow_mod_x(mut e: u128, p: p128) -> p128 {
+    let mut x = p128(1) % p;
+    let mut a = p128(2) % p;
+    while e > 0 {
+        if e & 1 != 0 {
+            x = mul_mod(x, a, p);
+        }
+        a = mul_mod(a, a, p);
+        e >>= 1;
+    }
+    x
+}
+
+/// Split a polynomial into square-free parts, returning each part with its
+/// multiplicity
+fn square_free_factors(p: p128) -> Factors<(p128, usize)> {
+    let mut factors = Factors::new((p128(0), 0));
+
+    // the formal derivative over GF(2) keeps only the odd terms
+    let dp = (p >> 1) & p128(0x55555555555555555555555555555555);
+
+    let mut c = gcd(p, dp);
+    let mut w = p / c;
+    let mut k = 1;
+    while w != p128(1) {
+        let y = gcd(w, c);
+        if w / y != p128(1) {
+            factors.push((w / y, k));
+        }
+        w = y;
+        c = c / y;
+        k += 1;
+    }
+
+    // anything left is a perfect square, since its derivative is zero
+    if c != p128(1) {
+        let mut root = p128(0);
+        for i in 0..64 {
+            root |= ((c >> (2*i)) & p128(1)) << i;
+        }
+        for &(s, k) in square_free_factors(root).iter() {
+            factors.push((s, 2*k));
+        }
+    }
+
+    factors
+}
+
+/// Split a square-free polynomial into the products of its irreducible
+/// factors of each degree d
+fn distinct_degree_factors(mut p: p128) -> Factors<(p128, u32)> {
+    let mut factors = Factors::new((p128(0), 0));
+
+    // h = x^(2^d) mod p
+    let mut h = p128(2) % p;
+    let mut d = 1;
+    while deg(p) >= 2*d {
+        h = mul_mod(h, h, p);
+        let g = gcd(p, h ^ p128(2));
+        if g != p128(1) {
+            factors.push((g, d));
+            p = p / g;
+            h = h % p;
+        }
+        d += 1;
+    }
+
+    if deg(p) > 0 {
+        factors.push((p, deg(p)));
+    }
+
+    factors
+}
+
+/// Find the order of x modulo a product of irreducible polynomials of
+/// degree d, this always divides 2^d-1
+fn order_of_x(g: p128, d: u32) -> u128 {
+    let mut order = u128::MAX >> (128-d);
+    for &q in prime_factors(order).iter() {
+        while order.is_multiple_of(q) && pow_mod_x(order / q, g) == p128(1) {
+            order /= q;
+        }
+    }
+    order
+}
+
+
+// integer helpers
+
+fn int_gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+fn lcm(a: u128, b: u128) -> u128 {
+    a / int_gcd(a, b) * b
+}
+
+/// Add a and b modulo m, without overflowing
+fn int_add_mod(a: u128, b: u128, m: u128) -> u128 {
+    let (x, overflow) = a.overflowing_add(b);
+    if overflow || x >= m { x.wrapping_sub(m) } else { x }
+}
+
+/// Multiply a and b modulo m, without overflowing
+fn int_mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u128::from(u64::MAX) {
+        return (a % m) * (b % m) % m;
+    }
+
+    let mut x = 0;
+    let a = a % m;
+    for i in (0..128-b.leading_zeros()).rev() {
+        x = int_add_mod(x, x, m);
+        if (b >> i) & 1 != 0 {
+            x = int_add_mod(x, a, m);
+        }
+    }
+    x
+}
+
+fn int_pow_mod(mut a: u128, mut e: u128, m: u128) -> u128 {
+    let mut x = 1 % m;
+    while e > 0 {
+        if e & 1 != 0 {
+            x = int_mul_mod(x, a, m);
+        }
+        a = int_mul_mod(a, a, m);
+        e >>= 1;
+    }
+    x
+}
+
+/// Miller-Rabin primality test
+///
+/// These bases are deterministic below 2^64, and above that the odds of a
+/// false positive are negligible
+fn is_prime(n: u128) -> bool {
+    const BASES: [u128; 20] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71
+    ];
+
+    if n < 2 {
+        return false;
+    }
+    for b in BASES {
+        if n.is_multiple_of(b) {
+            return n == b;
+        }
+    }
+
+    let s = (n-1).trailing_zeros();
+    let d = (n-1) >> s;
+    'bases: for b in BASES {
+        let mut x = int_pow_mod(b, d, n);
+        if x == 1 || x == n-1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = int_mul_mod(x, x, n);
+            if x == n-1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+/// Find a non-trivial factor of a composite number with Pollard's rho,
+/// using Brent's cycle detection
+fn find_factor(n: u128) -> u128 {
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+
+    for c in 1.. {
+        let f = |x: u128| int_add_mod(int_mul_mod(x, x, n), c, n);
+        let mut y = 2;
+        let mut x;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+
+            let mut k = 0;
+            while k < r && g == 1 {
+                // batch gcds by accumulating the product of differences
+                let ys = y;
+                let mut q = 1;
+                for _ in 0..core::cmp::min(128, r-k) {
+                    y = f(y);
+                    q = int_mul_mod(q, x.abs_diff(y), n);
+                }
+                g = int_gcd(q, n);
+
+                // backtrack if we overshot
+                if g == n {
+                    y = ys;
+                    loop {
+                        y = f(y);
+                        g = int_gcd(x.abs_diff(y), n);
+                        if g != 1 {
+                            break;
+                        }
+                    }
+                }
+                k += 128;
+            }
+            r *= 2;
+        }
+
+        if g != n {
+            return g;
+        }
+    }
+
+    unreachable!()
+}
+
+/// Find the distinct prime factors of n
+fn prime_factors(mut n: u128) -> Factors<u128> {
+    let mut primes = Factors::new(0);
+
+    // trial division takes care of small factors quickly
+    for q in (2..1024).filter(|q| (2..*q).take_while(|d| d*d <= *q).all(|d| q % d != 0)) {
+        if n.is_multiple_of(q) {
+            primes.push(q);
+            while n.is_multiple_of(q) {
+                n /= q;
+            }
+        }
+    }
+
+    // then Pollard's rho for the rest
+    let mut stack = Factors::new(0);
+    if n > 1 {
+        stack.push(n);
+    }
+    while let Some(n) = stack.pop() {
+        if is_prime(n) {
+            if !primes.contains(&n) {
+                primes.push(n);
+            }
+        } else {
+            let f = find_factor(n);
+            stack.push(f);
+            stack.push(n / f);
+        }
+    }
+
+    primes
+}
@@ -0,0 +1,115 @@
+//! Standard scrambler presets
+//!
+//! Scramblers are usually specified by their tap positions and the initial
+//! contents of a hardware shift register, which take some care to translate
+//! into [`lfsr`] macro parameters. This module provides
+//! preconfigured LFSRs for a few widely-used scramblers:
+//!
+//! | Scrambler                         | Type           | Polynomial            | Bit order |
+//! |-----------------------------------|----------------|-----------------------|-----------|
+//! | [`DvbS`], DVB-S/DVB-S2            | additive       | `1 + x^14 + x^15`     | msb first |
+//! | [`Pcie`], PCIe 1.x/2.x, USB 3.0   | additive       | `x^16+x^5+x^4+x^3+1`  | lsb first |
+//! | [`Ieee8023`], 10GBASE-R 64b/66b   | multiplicative | `1 + x^39 + x^58`     | lsb first |
+//! | [`V34Call`], V.34 call mode       | multiplicative | `1 + x^-18 + x^-23`   | lsb first |
+//! | [`V34Answer`], V.34 answer mode   | multiplicative | `1 + x^-5 + x^-23`    | lsb first |
+//!
+//! Note the `polynomial` of some of these LFSRs is the reciprocal of the
+//! polynomial in the specification. Fibonacci and self-synchronizing
+//! scramblers are usually specified by their feedback taps, while the `lfsr`
+//! macro expects the polynomial that defines the field, whose roots generate
+//! the sequence.
+//!
+//! Additive scramblers start from their specified initial state:
+//!
+//! ``` rust
+//! use gf256::lfsr::scramblers::DvbS;
+//!
+//! let mut buf = [0u8; 4];
+//! DvbS::init().scramble(&mut buf);
+//! assert_eq!(buf, [0x03, 0xf6, 0x08, 0x34]);
+//! ```
+//!
+//! Multiplicative scramblers synchronize on their own, so any initial state
+//! works:
+//!
+//! ``` rust
+//! use gf256::lfsr::scramblers::Ieee8023;
+//!
+//! let mut buf = *b"Hello World!";
+//! let mut state = 0;
+//! Ieee8023::scramble_multiplicative(&mut state, &mut buf);
+//!
+//! let mut state = 0;
+//! Ieee8023::descramble_multiplicative(&mut state, &mut buf);
+//! assert_eq!(&buf, b"Hello World!");
+//! ```
+//!
+//! The price of self-synchronization is error multiplication, each bit error
+//! in the channel turns into one bit error for each term of the polynomial,
+//! see `ERROR_MULTIPLICATION`. Both the IEEE 802.3 and V.34 scramblers triple
+//! bit errors.
+//!
+//! Framing, such as when the scrambler is reset or which symbols are left
+//! unscrambled, varies by protocol and is left to the caller.
+//!
+
+use super::lfsr;
+
+
+/// The DVB-S/DVB-S2 energy dispersal scrambler, `1 + x^14 + x^15`.
+///
+/// This is reset with [`init`](Self::init) every 8 transport packets.
+///
+#[lfsr(polynomial=0x8003, fibonacci)]
+pub struct DvbS {}
+
+impl DvbS {
+    /// The initial state, this is the register `100101010000000` in the
+    /// specification, which generates these next 15 bits.
+    pub const INIT: u16 = 0x01fb;
+
+    /// Create the scrambler in its initial state.
+    pub const fn init() -> Self {
+        Self::new(Self::INIT)
+    }
+}
+
+/// The PCIe 1.x/2.x and USB 3.0 scrambler, `x^16+x^5+x^4+x^3+1`.
+///
+/// In PCIe this is reset on every COM symbol, and doesn't advance on SKP
+/// symbols.
+///
+#[lfsr(polynomial=0x10039, reflected=true)]
+pub struct Pcie {}
+
+impl Pcie {
+    /// The initial state, `0xffff`.
+    pub const INIT: u16 = 0xffff;
+
+    /// Create the scrambler in its initial state.
+    pub const fn init() -> Self {
+        Self::new(Self::INIT)
+    }
+}
+
+/// The USB 3.0 scrambler, which is the same as the PCIe scrambler.
+pub type Usb3 = Pcie;
+
+/// The IEEE 802.3 64b/66b self-synchronizing scrambler, `1 + x^39 + x^58`,
+/// used by 10GBASE-R and faster Ethernet.
+///
+/// The scrambler is applied to the 64-bit payload of each block, but not to
+/// the 2-bit sync header.
+///
+#[lfsr(polynomial=0x400000000080001, reflected=true)]
+pub struct Ieee8023 {}
+
+/// The V.34 self-synchronizing scrambler for the call modem,
+/// `1 + x^-18 + x^-23`.
+#[lfsr(polynomial=0x800021, reflected=true)]
+pub struct V34Call {}
+
+/// The V.34 self-synchronizing scrambler for the answer modem,
+/// `1 + x^-5 + x^-23`.
+#[lfsr(polynomial=0x840001, reflected=true)]
+pub struct V34Answer {}
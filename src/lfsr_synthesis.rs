@@ -0,0 +1,204 @@
+//! LFSR synthesis with the Berlekamp-Massey algorithm
+//!
+//! Given a sequence of bits, the [Berlekamp-Massey algorithm][berlekamp-massey]
+//! finds the shortest LFSR that generates the sequence. The length of this
+//! LFSR is the sequence's _linear complexity_, a common measure of how
+//! predictable a generator is. Any LFSR of degree n can be recovered from only
+//! 2n bits of its output.
+//!
+//! Berlekamp-Massey is the same algorithm the [rs](crate::rs) module uses to
+//! find the error locator polynomial, here over `GF(2)`.
+//!
+//! [berlekamp-massey]: https://en.wikipedia.org/wiki/Berlekamp%E2%80%93Massey_algorithm
+//!
+
+extern crate alloc;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+
+/// The shortest LFSR that generates a sequence of bits, found by
+/// [`synthesize`].
+///
+/// This is a Fibonacci LFSR, each new bit is the xor of the previous bits
+/// selected by the connection polynomial, starting from the first
+/// [`linear_complexity`](Self::linear_complexity) bits of the sequence.
+///
+/// ``` rust
+/// use gf256::lfsr::{Lfsr16, synthesize};
+///
+/// // 32 bits of output is enough to recover a 16-bit LFSR
+/// let mut lfsr = Lfsr16::new(1);
+/// let bits = lfsr.bits().take(32).collect::<Vec<_>>();
+///
+/// let synthesis = synthesize(bits);
+/// assert_eq!(synthesis.linear_complexity(), 16);
+/// assert_eq!(synthesis.polynomial(), Some(0x1002d));
+///
+/// // the synthesized LFSR continues the sequence
+/// let mut lfsr = Lfsr16::new(1);
+/// assert!(synthesis.iter().take(1000).eq(lfsr.bits().take(1000)));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Synthesis {
+    connection: Vec<bool>,
+    state: Vec<bool>,
+}
+
+impl Synthesis {
+    /// The linear complexity of the sequence, this is the length of the
+    /// shortest LFSR that generates it.
+    pub fn linear_complexity(&self) -> usize {
+        self.state.len()
+    }
+
+    /// The connection polynomial, `C(x) = 1 + c1*x + ... + cL*x^L`, where
+    /// `connection()[i]` is the coefficient of `x^i`.
+    ///
+    /// Each bit of the sequence is `s[n] = c1*s[n-1] + ... + cL*s[n-L]`.
+    ///
+    pub fn connection(&self) -> &[bool] {
+        &self.connection
+    }
+
+    /// The characteristic polynomial, `x^L*C(1/x)`, in the same form as the
+    /// `polynomial` option of the [`lfsr`](super::lfsr) macro.
+    ///
+    /// Returns `None` if the polynomial doesn't fit in a `u128`.
+    ///
+    pub fn polynomial(&self) -> Option<u128> {
+        if self.connection.len() > 128 {
+            return None;
+        }
+
+        Some(self.connection.iter()
+            .fold(0, |p, c| (p << 1) | u128::from(*c)))
+    }
+
+    /// The initial state of the LFSR, this is the first
+    /// [`linear_complexity`](Self::linear_complexity) bits of the sequence.
+    pub fn state(&self) -> &[bool] {
+        &self.state
+    }
+
+    /// Generate the sequence from the initial state.
+    ///
+    /// Note if the sequence has no linear complexity, all bits are zero.
+    ///
+    pub fn iter(&self) -> SynthesisIter<'_> {
+        SynthesisIter {
+            connection: &self.connection,
+            state: self.state.clone(),
+            i: 0,
+        }
+    }
+}
+
+/// An iterator over the bits generated by a [`Synthesis`]
+#[derive(Debug, Clone)]
+pub struct SynthesisIter<'a> {
+    connection: &'a [bool],
+    // ring buffer of the last L bits, i is the oldest
+    state: Vec<bool>,
+    i: usize,
+}
+
+impl Iterator for SynthesisIter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let l = self.state.len();
+        if l == 0 {
+            return Some(false);
+        }
+
+        // s[n] = c1*s[n-1] + ... + cL*s[n-L]
+        let mut s = false;
+        for (j, c) in self.connection[1..].iter().enumerate() {
+            s ^= *c && self.state[(self.i + l-1-j) % l];
+        }
+
+        let b = self.state[self.i];
+        self.state[self.i] = s;
+        self.i = (self.i + 1) % l;
+        Some(b)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl FusedIterator for SynthesisIter<'_> {}
+
+/// Find the shortest LFSR that generates a sequence of bits, using the
+/// Berlekamp-Massey algorithm.
+///
+/// This runs in `O(n^2)` for a sequence of n bits. If the sequence was
+/// generated by an LFSR of degree L, at least 2L bits are needed to find it.
+///
+/// Note this requires feature `alloc`.
+///
+/// ``` rust
+/// use gf256::lfsr::synthesize;
+///
+/// // a sequence with no short recurrence has high linear complexity
+/// let synthesis = synthesize([false, false, false, false, false, false, false, true]);
+/// assert_eq!(synthesis.linear_complexity(), 8);
+///
+/// let synthesis = synthesize([true, false, true, false, true, false, true, false]);
+/// assert_eq!(synthesis.linear_complexity(), 2);
+/// assert_eq!(synthesis.polynomial(), Some(0b101));
+/// ```
+///
+pub fn synthesize<I: IntoIterator<Item=bool>>(bits: I) -> Synthesis {
+    let s = bits.into_iter().collect::<Vec<_>>();
+
+    // the current estimate for the connection polynomial, C(x), and the
+    // connection polynomial before the last length change, B(x)
+    let mut c = alloc::vec![false; s.len()+1];
+    let mut b = alloc::vec![false; s.len()+1];
+    c[0] = true;
+    b[0] = true;
+
+    // the current estimate for the linear complexity
+    let mut l = 0;
+    // steps since the last length change
+    let mut m = 1;
+
+    for n in 0..s.len() {
+        // discrepancy between the sequence and the current estimate
+        let mut d = s[n];
+        for i in 1..l+1 {
+            d ^= c[i] && s[n-i];
+        }
+
+        if !d {
+            m += 1;
+        } else if 2*l <= n {
+            // C(x) = C(x) + x^m*B(x), with a new length
+            let t = c.clone();
+            for i in 0..s.len()+1-m {
+                c[i+m] ^= b[i];
+            }
+            l = n+1-l;
+            b = t;
+            m = 1;
+        } else {
+            // C(x) = C(x) + x^m*B(x)
+            for i in 0..s.len()+1-m {
+                c[i+m] ^= b[i];
+            }
+            m += 1;
+        }
+    }
+
+    c.truncate(l+1);
+    let mut s = s;
+    s.truncate(l);
+    Synthesis {
+        connection: c,
+        state: s,
+    }
+}
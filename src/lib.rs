@@ -1,4 +1,16 @@
-#![doc=include_str!("../README.md")]
+// The README's examples depend on most of our features, so only include
+// it when these are enabled (this is the case on docs.rs)
+#![cfg_attr(
+    all(
+        feature="thread-rng",
+        feature="lfsr",
+        feature="crc",
+        feature="shamir",
+        feature="raid",
+        feature="rs"
+    ),
+    doc=include_str!("../README.md")
+)]
 
 
 // We don't really need std
@@ -7,6 +19,9 @@
 // Other assertions
 #![deny(missing_debug_implementations)]
 
+#[cfg(feature="std")]
+extern crate std;
+
 
 /// Extra traits
 pub mod traits;
@@ -19,6 +34,9 @@ pub use p::*;
 pub mod gf;
 pub use gf::*;
 
+/// Exporting tables to other languages
+pub mod export;
+
 /// LFSR structs
 #[cfg(feature="lfsr")]
 pub mod lfsr;
@@ -39,6 +57,30 @@ pub mod raid;
 #[cfg(feature="rs")]
 pub mod rs;
 
+/// BCH error-correction
+#[cfg(feature="bch")]
+pub mod bch;
+
+/// Hamming/SECDED error-correction
+#[cfg(feature="hamming")]
+pub mod hamming;
+
+/// LDPC error-correction
+#[cfg(feature="ldpc")]
+pub mod ldpc;
+
+/// Convolutional codes and Viterbi decoding
+#[cfg(feature="conv")]
+pub mod conv;
+
+/// LT fountain codes
+#[cfg(feature="lt")]
+pub mod lt;
+
+/// GHASH universal hash
+#[cfg(feature="ghash")]
+pub mod ghash;
+
 
 /// Re-exports for proc_macros
 ///
@@ -46,12 +88,32 @@ pub mod rs;
 #[path="."]
 pub mod internal {
     pub mod xmul;
+    pub mod shuffle;
+    pub mod zeroize;
+    #[cfg(feature="crc")]
+    pub mod hwcrc;
     pub use cfg_if;
     #[cfg(any(feature="lfsr", feature="shamir"))]
     pub use rand;
+    #[cfg(feature="seeded-rng")]
+    pub use rand_chacha;
+    #[cfg(feature="rand-core-09")]
+    pub use rand_core_09;
+    #[cfg(feature="serde")]
+    pub use serde;
+    #[cfg(feature="rayon")]
+    pub use rayon;
+    #[cfg(feature="async")]
+    pub use tokio;
+    #[cfg(feature="std")]
+    pub extern crate std;
 }
 
 /// A flag indicating if hardware carry-less multiplication
 /// instructions are available
 pub use internal::xmul::HAS_XMUL;
 
+/// A flag indicating if hardware byte-shuffle instructions are
+/// available for GF(2^8) slice multiplication
+pub use internal::shuffle::HAS_SHUFFLE;
+
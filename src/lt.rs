@@ -0,0 +1,750 @@
+//! ## LT fountain codes
+//!
+//! [Luby transform codes][lt-wiki] (LT) are _fountain codes_, rateless
+//! erasure codes that can generate an endless stream of encoded symbols
+//! from `k` blocks of data. A receiver can recover the data from any
+//! slightly more than `k` of these symbols, regardless of which symbols were
+//! lost. This makes them a good fit for distributing bulk data over lossy,
+//! one-way channels, where the receiver can't ask for retransmissions.
+//!
+//! ``` rust
+//! use gf256::lt::{Lt, Field};
+//!
+//! let data = b"Hello World! Hello World! Hello World! Hello World!";
+//! let lt = Lt::new(17, 3, Field::Gf2);
+//!
+//! // encode, and lose every third symbol
+//! let mut decoder = lt.decoder();
+//! for (id, symbol) in lt.encoder(data).filter(|(id, _)| id % 3 != 0) {
+//!     if decoder.push(id, &symbol) {
+//!         break;
+//!     }
+//! }
+//!
+//! assert_eq!(decoder.data(), Some(&data[..]));
+//! ```
+//!
+//! Note this module requires feature `lt`, and `alloc`.
+//!
+//! ## How do LT codes work?
+//!
+//! Each encoded symbol is the sum of a random subset of the `k` source
+//! blocks. The number of blocks in the subset, the symbol's _degree_, is
+//! drawn from a carefully chosen degree distribution, and the blocks are
+//! then chosen uniformly at random.
+//!
+//! The randomness is seeded by the symbol's id, so the receiver can recreate
+//! each symbol's subset from only its id, without any other side-channel.
+//!
+//! Decoding is mostly done with a _peeling decoder_:
+//!
+//! 1. Any symbol with degree 1 is a copy of a source block, so we've
+//!    recovered that block.
+//!
+//! 2. Subtract every recovered block from the other symbols that contain
+//!    it, reducing their degree. Some of these will now have degree 1.
+//!
+//! 3. Repeat until we've recovered every block, or we run out of symbols
+//!    with degree 1 and need to wait for more.
+//!
+//! For this to work, we need a steady supply of degree 1 symbols, but also
+//! enough high-degree symbols that every block is covered. The _robust
+//! soliton distribution_ balances these, letting the peeling decoder
+//! finish with high probability after receiving `k + O(√k ln²(k/δ))`
+//! symbols:
+//!
+//! ``` text
+//! ρ(1) = 1/k
+//! ρ(d) = 1/(d(d-1))                  for d = 2..k
+//!
+//! R = c ln(k/δ) √k
+//!
+//! τ(d) = R/(dk)                      for d = 1..k/R-1
+//! τ(d) = R ln(R/δ)/k                 for d = k/R
+//! τ(d) = 0                           otherwise
+//!
+//! μ(d) = (ρ(d) + τ(d)) / Σ(ρ + τ)
+//! ```
+//!
+//! ## GF(2) vs GF(256)
+//!
+//! With [`Field::Gf2`](crate::lt::Field::Gf2), symbols are the xor of their
+//! source blocks, which is cheap, and is the classic LT code.
+//!
+//! With [`Field::Gf256`](crate::lt::Field::Gf256), each source block in a
+//! symbol is also multiplied by a random non-zero coefficient in `GF(256)`.
+//! This is more expensive to encode and decode, and doesn't help the peeling
+//! decoder at all.
+//!
+//! But when peeling stalls after receiving enough symbols, the decoder falls
+//! back to Gaussian elimination over the remaining symbols. In `GF(2)`,
+//! these are linearly dependent fairly often, about 30% of the time even
+//! with random symbols, and each extra symbol only halves this. In
+//! `GF(256)`, this is closer to 0.4%, so decoding usually finishes within
+//! a symbol or two of `k`.
+//!
+//! [lt-wiki]: https://en.wikipedia.org/wiki/Luby_transform_code
+
+use crate::gf::gf256;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Default `c` parameter of the robust soliton distribution.
+pub const DEFAULT_C: f64 = 0.1;
+
+/// Default `δ` parameter of the robust soliton distribution.
+pub const DEFAULT_DELTA: f64 = 0.05;
+
+
+/// Field used for combining source blocks into encoded symbols.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Field {
+    /// Symbols are the xor of their source blocks.
+    Gf2,
+
+    /// Symbols are a random linear combination of their source blocks in
+    /// `GF(256)`.
+    Gf256,
+}
+
+
+/// An LT fountain code over `k` source blocks of `symbol_size` bytes each.
+///
+/// ``` rust
+/// use gf256::lt::{Lt, Field};
+///
+/// let lt = Lt::new(4, 3, Field::Gf256).with_seed(42);
+/// let data = b"Hello World!";
+///
+/// let mut symbol = [0u8; 3];
+/// let mut decoder = lt.decoder();
+/// let mut id = 0;
+/// while !decoder.is_complete() {
+///     lt.encode(data, id, &mut symbol);
+///     decoder.push(id, &symbol);
+///     id += 1;
+/// }
+///
+/// assert_eq!(decoder.data(), Some(&data[..]));
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Lt {
+    k: usize,
+    symbol_size: usize,
+    field: Field,
+    seed: u64,
+    // cumulative degree distribution, cdf[d-1] = P(degree <= d)
+    cdf: Vec<f64>,
+}
+
+impl Lt {
+    /// Create an LT code over `k` source blocks of `symbol_size` bytes
+    /// each, using the robust soliton distribution with [`DEFAULT_C`] and
+    /// [`DEFAULT_DELTA`].
+    pub fn new(k: usize, symbol_size: usize, field: Field) -> Self {
+        assert!(k > 0, "lt requires at least one source block");
+        Lt {
+            k,
+            symbol_size,
+            field,
+            seed: 0,
+            cdf: robust_soliton(k, DEFAULT_C, DEFAULT_DELTA),
+        }
+    }
+
+    /// Use a different seed for generating symbols.
+    ///
+    /// The encoder and decoder must use the same seed.
+    ///
+    pub fn with_seed(self, seed: u64) -> Self {
+        Lt { seed, ..self }
+    }
+
+    /// Use different parameters for the robust soliton distribution.
+    ///
+    /// Larger `c` and smaller `δ` produce more degree 1 symbols, and a larger
+    /// spike of high-degree symbols, trading overhead on average for a
+    /// lower chance of decoding taking many more symbols than expected.
+    ///
+    pub fn with_robust_soliton(self, c: f64, delta: f64) -> Self {
+        assert!(c > 0.0 && delta > 0.0 && delta < 1.0,
+            "lt requires c > 0 and 0 < δ < 1");
+        Lt { cdf: robust_soliton(self.k, c, delta), ..self }
+    }
+
+    /// Number of source blocks.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Size of each source block, and encoded symbol, in bytes.
+    pub fn symbol_size(&self) -> usize {
+        self.symbol_size
+    }
+
+    /// Size of the data in bytes, `k*symbol_size`.
+    pub fn data_size(&self) -> usize {
+        self.k * self.symbol_size
+    }
+
+    /// The field used for combining source blocks.
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// The seed used for generating symbols.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Find the source blocks, and their coefficients, that make up the
+    /// symbol with the given id.
+    ///
+    /// For [`Field::Gf2`], all coefficients are 1.
+    ///
+    pub fn neighbors(&self, id: u32) -> Vec<(usize, u8)> {
+        let mut rng = SplitMix64::new(
+            self.seed ^ u64::from(id).wrapping_mul(0x9e3779b97f4a7c15)
+        );
+
+        // sample our degree
+        let u = rng.next_f64();
+        let d = self.cdf.iter()
+            .position(|p| u < *p)
+            .unwrap_or(self.k-1) + 1;
+
+        // choose d distinct source blocks with Floyd's algorithm
+        let mut neighbors = Vec::with_capacity(d);
+        for j in self.k-d..self.k {
+            let i = rng.below(j+1);
+            let i = if neighbors.iter().any(|(x, _)| *x == i) { j } else { i };
+            let c = match self.field {
+                Field::Gf2 => 1,
+                Field::Gf256 => 1 + rng.below(255) as u8,
+            };
+            neighbors.push((i, c));
+        }
+
+        neighbors
+    }
+
+    /// Encode the symbol with the given id.
+    ///
+    /// `data` must be exactly [`data_size`](Self::data_size) bytes, pad the
+    /// data if necessary.
+    ///
+    pub fn encode(&self, data: &[u8], id: u32, symbol: &mut [u8]) {
+        assert!(data.len() == self.data_size());
+        assert!(symbol.len() == self.symbol_size);
+
+        symbol.fill(0);
+        for (i, c) in self.neighbors(id) {
+            mul_add(symbol, &data[i*self.symbol_size..(i+1)*self.symbol_size], c);
+        }
+    }
+
+    /// An endless stream of encoded symbols, with their ids.
+    ///
+    /// ``` rust
+    /// use gf256::lt::{Lt, Field};
+    ///
+    /// let lt = Lt::new(4, 3, Field::Gf2);
+    /// let symbols = lt.encoder(b"Hello World!").take(8).collect::<Vec<_>>();
+    /// assert_eq!(symbols.len(), 8);
+    /// assert_eq!(symbols[7].0, 7);
+    /// ```
+    ///
+    pub fn encoder<'a>(
+        &'a self,
+        data: &'a [u8]
+    ) -> impl Iterator<Item=(u32, Vec<u8>)> + 'a {
+        assert!(data.len() == self.data_size());
+        (0..=u32::MAX).map(move |id| {
+            let mut symbol = vec![0u8; self.symbol_size];
+            self.encode(data, id, &mut symbol);
+            (id, symbol)
+        })
+    }
+
+    /// Create a decoder for this code.
+    pub fn decoder(&self) -> LtDecoder {
+        LtDecoder::new(self.clone())
+    }
+}
+
+
+/// An encoded symbol we haven't been able to use yet
+#[derive(Debug, Clone)]
+struct Pending {
+    // unknown source blocks, and their coefficients
+    neighbors: Vec<(usize, u8)>,
+    value: Vec<u8>,
+}
+
+/// A peeling decoder for an [`Lt`] code.
+///
+/// Push encoded symbols, in any order, until [`is_complete`](Self::is_complete)
+/// returns true.
+///
+#[derive(Debug, Clone)]
+pub struct LtDecoder {
+    lt: Lt,
+    data: Vec<u8>,
+    known: Vec<bool>,
+    decoded: usize,
+    received: usize,
+
+    // symbols with 2 or more unknown source blocks, and the symbols that
+    // reference each source block
+    pending: Vec<Pending>,
+    active: usize,
+    waiting: Vec<Vec<usize>>,
+}
+
+impl LtDecoder {
+    /// Create a decoder for the given code.
+    pub fn new(lt: Lt) -> Self {
+        LtDecoder {
+            data: vec![0; lt.data_size()],
+            known: vec![false; lt.k],
+            decoded: 0,
+            received: 0,
+            pending: Vec::new(),
+            active: 0,
+            waiting: vec![Vec::new(); lt.k],
+            lt,
+        }
+    }
+
+    /// The code being decoded.
+    pub fn lt(&self) -> &Lt {
+        &self.lt
+    }
+
+    /// Number of source blocks recovered so far.
+    pub fn decoded(&self) -> usize {
+        self.decoded
+    }
+
+    /// Number of symbols received so far.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Returns true if all source blocks have been recovered.
+    pub fn is_complete(&self) -> bool {
+        self.decoded == self.lt.k
+    }
+
+    /// The recovered data, if all source blocks have been recovered.
+    pub fn data(&self) -> Option<&[u8]> {
+        if self.is_complete() {
+            Some(&self.data)
+        } else {
+            None
+        }
+    }
+
+    /// Push an encoded symbol with the given id, returning true if all source
+    /// blocks have been recovered.
+    ///
+    /// Duplicate and unneeded symbols are ignored.
+    ///
+    pub fn push(&mut self, id: u32, symbol: &[u8]) -> bool {
+        assert!(symbol.len() == self.lt.symbol_size);
+        self.received += 1;
+        if self.is_complete() {
+            return true;
+        }
+
+        // subtract any source blocks we already know
+        let s = self.lt.symbol_size;
+        let mut value = symbol.to_vec();
+        let mut neighbors = Vec::new();
+        for (i, c) in self.lt.neighbors(id) {
+            if self.known[i] {
+                mul_add(&mut value, &self.data[i*s..(i+1)*s], c);
+            } else {
+                neighbors.push((i, c));
+            }
+        }
+
+        match neighbors.len() {
+            // nothing new
+            0 => {}
+            // we found a source block, peel it from other symbols
+            1 => self.peel(neighbors[0].0, neighbors[0].1, value),
+            // wait for more source blocks
+            _ => {
+                for (i, _) in &neighbors {
+                    self.waiting[*i].push(self.pending.len());
+                }
+                self.pending.push(Pending { neighbors, value });
+                self.active += 1;
+            }
+        }
+
+        // peeling stalled with enough symbols? try Gaussian elimination
+        if !self.is_complete() && self.active >= self.lt.k - self.decoded {
+            self.eliminate();
+        }
+
+        self.is_complete()
+    }
+
+    /// Recover source block i from a symbol c*block = value, and peel it from
+    /// any pending symbols, recursively
+    fn peel(&mut self, i: usize, c: u8, value: Vec<u8>) {
+        let s = self.lt.symbol_size;
+        let mut stack = vec![(i, c, value)];
+        while let Some((i, c, mut value)) = stack.pop() {
+            if self.known[i] {
+                continue;
+            }
+
+            scale(&mut value, gf256::new(c).recip().get());
+            self.data[i*s..(i+1)*s].copy_from_slice(&value);
+            self.known[i] = true;
+            self.decoded += 1;
+
+            for p in core::mem::take(&mut self.waiting[i]) {
+                let pending = &mut self.pending[p];
+                let j = match pending.neighbors.iter().position(|(x, _)| *x == i) {
+                    Some(j) => j,
+                    None => continue,
+                };
+
+                let (_, c) = pending.neighbors.swap_remove(j);
+                mul_add(&mut pending.value, &self.data[i*s..(i+1)*s], c);
+                if pending.neighbors.len() == 1 {
+                    let (j, c) = pending.neighbors.pop().unwrap();
+                    stack.push((j, c, core::mem::take(&mut pending.value)));
+                    self.active -= 1;
+                }
+            }
+        }
+    }
+
+    /// Try to recover all remaining source blocks with Gaussian elimination
+    /// over the pending symbols, this leaves the decoder unmodified if the
+    /// pending symbols aren't full rank
+    fn eliminate(&mut self) {
+        let s = self.lt.symbol_size;
+        let unknown = (0..self.lt.k)
+            .filter(|i| !self.known[*i])
+            .collect::<Vec<_>>();
+        let mut columns = vec![usize::MAX; self.lt.k];
+        for (j, i) in unknown.iter().enumerate() {
+            columns[*i] = j;
+        }
+
+        // build a dense system from our pending symbols
+        let mut rows = self.pending.iter()
+            .filter(|p| !p.neighbors.is_empty())
+            .map(|p| {
+                let mut row = vec![0u8; unknown.len()];
+                for (i, c) in &p.neighbors {
+                    row[columns[*i]] = *c;
+                }
+                (row, p.value.clone())
+            })
+            .collect::<Vec<_>>();
+
+        // Gauss-Jordan elimination
+        for j in 0..unknown.len() {
+            let r = match (j..rows.len()).find(|r| rows[*r].0[j] != 0) {
+                Some(r) => r,
+                None => return,
+            };
+            rows.swap(j, r);
+
+            let inv = gf256::new(rows[j].0[j]).recip().get();
+            scale(&mut rows[j].0, inv);
+            scale(&mut rows[j].1, inv);
+
+            let pivot = rows[j].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                let c = row.0[j];
+                if r != j && c != 0 {
+                    mul_add(&mut row.0, &pivot.0, c);
+                    mul_add(&mut row.1, &pivot.1, c);
+                }
+            }
+        }
+
+        // full rank, we've solved every remaining block
+        for (j, i) in unknown.iter().enumerate() {
+            self.data[i*s..(i+1)*s].copy_from_slice(&rows[j].1);
+            self.known[*i] = true;
+        }
+        self.decoded = self.lt.k;
+        self.pending.clear();
+        self.active = 0;
+        for w in self.waiting.iter_mut() {
+            w.clear();
+        }
+    }
+}
+
+// field helpers, addition is xor in both GF(2) and GF(256)
+
+/// f += c*g
+fn mul_add(f: &mut [u8], g: &[u8], c: u8) {
+    if c == 1 {
+        for (x, y) in f.iter_mut().zip(g) {
+            *x ^= y;
+        }
+    } else {
+        let c = gf256::new(c);
+        for (x, y) in f.iter_mut().zip(g) {
+            *x ^= (c * gf256::new(*y)).get();
+        }
+    }
+}
+
+/// f *= c
+fn scale(f: &mut [u8], c: u8) {
+    if c != 1 {
+        let c = gf256::new(c);
+        for x in f.iter_mut() {
+            *x = (c * gf256::new(*x)).get();
+        }
+    }
+}
+
+
+/// Find the cumulative robust soliton distribution
+fn robust_soliton(k: usize, c: f64, delta: f64) -> Vec<f64> {
+    let kf = k as f64;
+    let r = c * ln(kf/delta) * sqrt(kf);
+    let spike = ((kf/r + 0.5) as usize).clamp(1, k);
+
+    let mut mu = vec![0.0; k];
+    for d in 1..=k {
+        let df = d as f64;
+        let rho = if d == 1 { 1.0/kf } else { 1.0/(df*(df-1.0)) };
+        let tau = if d < spike {
+            r/(df*kf)
+        } else if d == spike {
+            (r*ln(r/delta)/kf).max(0.0)
+        } else {
+            0.0
+        };
+        mu[d-1] = rho + tau;
+    }
+
+    // normalize and accumulate
+    let beta = mu.iter().sum::<f64>();
+    let mut sum = 0.0;
+    for p in mu.iter_mut() {
+        sum += *p / beta;
+        *p = sum;
+    }
+    mu
+}
+
+// we don't have std's float functions in no_std, but we only need these
+// when creating the degree distribution, so simple implementations are fine
+
+/// Natural logarithm, via x = m*2^e and ln(m) = 2*atanh((m-1)/(m+1))
+fn ln(x: f64) -> f64 {
+    assert!(x > 0.0);
+    let bits = x.to_bits();
+    let e = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let m = f64::from_bits((bits & 0x000fffffffffffff) | 0x3ff0000000000000);
+
+    let t = (m-1.0)/(m+1.0);
+    let t2 = t*t;
+    let mut term = t;
+    let mut sum = 0.0;
+    let mut n = 1.0;
+    while term.abs() > 1e-17 {
+        sum += term/n;
+        term *= t2;
+        n += 2.0;
+    }
+
+    (e as f64)*core::f64::consts::LN_2 + 2.0*sum
+}
+
+/// Square root, via Newton's method
+fn sqrt(x: f64) -> f64 {
+    if x == 0.0 {
+        return 0.0;
+    }
+
+    // halving the exponent gives a good initial guess
+    let mut y = f64::from_bits((x.to_bits() >> 1) + (0x3ff0000000000000 >> 1));
+    for _ in 0..8 {
+        y = 0.5*(y + x/y);
+    }
+    y
+}
+
+
+/// A small, deterministic PRNG for generating symbols, we need the encoder
+/// and decoder to agree on every symbol's neighbors
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        SplitMix64(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [0, n)
+    fn below(&mut self, n: usize) -> usize {
+        ((u128::from(self.next_u64()) * n as u128) >> 64) as usize
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lt_float_helpers() {
+        for x in [1e-3, 0.05, 0.5, 1.0, 2.0, 10.0, 1000.0, 1e9] {
+            // exp(ln(x)) = x, using the series for exp
+            let e = ln(x);
+            let mut term = 1.0;
+            let mut exp = 0.0;
+            for n in 1..200 {
+                exp += term;
+                term *= e / n as f64;
+            }
+            assert!((exp - x).abs() <= 1e-9*x, "ln({}) = {}", x, e);
+
+            let y = sqrt(x);
+            assert!((y*y - x).abs() <= 1e-12*x, "sqrt({}) = {}", x, y);
+        }
+    }
+
+    #[test]
+    fn lt_robust_soliton() {
+        for k in [1, 2, 10, 100, 1000] {
+            let cdf = robust_soliton(k, DEFAULT_C, DEFAULT_DELTA);
+            assert_eq!(cdf.len(), k);
+            assert!((cdf[k-1] - 1.0).abs() < 1e-9);
+            assert!(cdf.windows(2).all(|w| w[0] <= w[1]));
+        }
+
+        // degree 2 should be the most common degree
+        let cdf = robust_soliton(1000, DEFAULT_C, DEFAULT_DELTA);
+        let p2 = cdf[1] - cdf[0];
+        assert!((2..1000).all(|d| cdf[d] - cdf[d-1] <= p2));
+    }
+
+    #[test]
+    fn lt_neighbors() {
+        for field in [Field::Gf2, Field::Gf256] {
+            let lt = Lt::new(100, 1, field);
+            for id in 0..1000 {
+                let neighbors = lt.neighbors(id);
+                assert!(!neighbors.is_empty());
+                assert!(neighbors.iter().all(|(i, c)| *i < 100 && *c != 0));
+                for (j, (i, _)) in neighbors.iter().enumerate() {
+                    assert!(neighbors[..j].iter().all(|(x, _)| x != i));
+                }
+                if field == Field::Gf2 {
+                    assert!(neighbors.iter().all(|(_, c)| *c == 1));
+                }
+
+                // deterministic
+                assert_eq!(lt.neighbors(id), neighbors);
+            }
+
+            // different seeds give different symbols
+            let other = lt.clone().with_seed(1);
+            assert!((0..100).any(|id| other.neighbors(id) != lt.neighbors(id)));
+        }
+    }
+
+    fn symbols_needed(lt: &Lt, loss: u32) -> usize {
+        let data = (0..lt.data_size())
+            .map(|_| rand::random::<u8>())
+            .collect::<Vec<_>>();
+
+        let mut decoder = lt.decoder();
+        for (id, symbol) in lt.encoder(&data) {
+            if rand::random::<u32>() % 100 < loss {
+                continue;
+            }
+            if decoder.push(id, &symbol) {
+                break;
+            }
+        }
+
+        assert_eq!(decoder.data(), Some(&data[..]));
+        decoder.received()
+    }
+
+    #[test]
+    fn lt_decode() {
+        for k in [1, 2, 10, 100] {
+            for field in [Field::Gf2, Field::Gf256] {
+                let lt = Lt::new(k, 16, field).with_seed(rand::random());
+                for loss in [0, 50] {
+                    for _ in 0..10 {
+                        let received = symbols_needed(&lt, loss);
+                        assert!(received >= k);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lt_overhead() {
+        // thanks to Gaussian elimination, both fields should finish close
+        // to k on average, GF(256) especially
+        let k = 100;
+        let mut gf2_total = 0;
+        let mut gf256_total = 0;
+        for _ in 0..20 {
+            let seed = rand::random();
+            gf2_total += symbols_needed(&Lt::new(k, 4, Field::Gf2).with_seed(seed), 0);
+            gf256_total += symbols_needed(&Lt::new(k, 4, Field::Gf256).with_seed(seed), 0);
+        }
+
+        assert!(gf2_total <= 20*k*6/5, "gf2 needed {} symbols on average", gf2_total/20);
+        assert!(gf256_total <= 20*k*11/10, "gf256 needed {} symbols on average", gf256_total/20);
+    }
+
+    #[test]
+    fn lt_duplicates() {
+        let lt = Lt::new(10, 4, Field::Gf2);
+        let data = (0..40).collect::<Vec<u8>>();
+
+        // duplicate symbols don't break anything
+        let mut decoder = lt.decoder();
+        for (id, symbol) in lt.encoder(&data) {
+            decoder.push(id, &symbol);
+            if decoder.push(id, &symbol) {
+                break;
+            }
+        }
+        assert_eq!(decoder.data(), Some(&data[..]));
+
+        // symbols after completion are ignored
+        assert!(decoder.push(0, &[0; 4]));
+        assert_eq!(decoder.data(), Some(&data[..]));
+    }
+}
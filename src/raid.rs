@@ -959,24 +959,173 @@
 //! RAID 6 and RAID 7, aka double-parity and triple-parity, rely on the uniqueness
 //! of powers of a generator in the field. Because of this, these schemes are
 //! limited to the number of non-zero elements in the field. In the case of `GF(256)`,
-//! this limits RAID 6 and RAID 7 to 255 blocks.
+//! this limits RAID 6 and RAID 7 to 255 blocks. With more than 3 parity blocks,
+//! the parity blocks need unique powers too, so the total number of blocks,
+//! data and parity, is limited to 255 blocks.
+//!
+//! If you need more blocks, you can use a larger field. With `GF(2^16)`, RAID 6
+//! and RAID 7 can support up to 65535 blocks, which may be useful for wide
+//! erasure-coded stripes:
+//!
+//! ``` rust,ignore
+//! # use ::gf256::*;
+//! # use ::gf256::raid::raid;
+//! #[raid(gf=gf2p16, u=u16, parity=2)]
+//! pub mod gf2p16_raid6 {}
+//!
+//! # fn main() {
+//! let mut blocks = (0..1000).map(|i| vec![i as u16; 4]).collect::<Vec<_>>();
+//! let mut p = vec![0u16; 4];
+//! let mut q = vec![0u16; 4];
+//! gf2p16_raid6::format(&blocks, &mut p, &mut q);
+//!
+//! blocks[300].fill(0);
+//! blocks[700].fill(0);
+//! gf2p16_raid6::repair(&mut blocks, &mut p, &mut q, &[300, 700]).unwrap();
+//! assert_eq!(blocks[300], vec![300u16; 4]);
+//! # }
+//! ```
+//!
+//! Note that `u` must be the same size as the field, so `u16` for `GF(2^16)`.
 //!
 //! Each scheme can repair any block up to the number of parity blocks, however
 //! they don't actually provide the detection of block failures. One way to do this
 //! is attach a CRC or other checksum to each block.
 //!
+//! The exception is a single silently corrupted block. With at least two parity
+//! blocks, RAID 6 and RAID 7 can use the ratio between the errors in p and q to
+//! find which block is corrupted. [`raid6::find_and_repair`][find-and-repair]
+//! uses this to repair a corrupted block without knowing where it is.
+//!
 //! ## RAID8? >3 parity blocks?
 //!
-//! As it is, the current scheme only supports up to 3 parity blocks. But it is
-//! actually possible to use a different scheme that works beyond 3 parity blocks.
+//! Unfortunately, this scheme only works up to 3 parity blocks. Beyond 3 parity
+//! blocks, some combinations of bad data blocks and bad parity blocks can lead
+//! to a system of equations that isn't linearly independent.
 //!
 //! As outlined in James S. Plank’s paper, [Note: Correction to the 1997 Tutorial
-//! on Reed-Solomon Coding][plank], you can construct a modified [Vandermonde matrix
-//! ][vandermonde-matrix] that allows you to solve the linear system of equations for
-//! any number of parity blocks.
+//! on Reed-Solomon Coding][plank], the trick is to use a [Vandermonde matrix
+//! ][vandermonde-matrix] over _all_ of the blocks, parity blocks included. So
+//! with more than 3 parity blocks, each block is assigned a unique power of
+//! the generator, its "locator" `xi`, with the parity blocks coming first, and
+//! the parity blocks are chosen so that:
+//!
+//! ``` text
+//! Σ di*xi^r = 0, for r = 0..parity
+//! ```
+//!
+//! Any `parity` columns of a Vandermonde matrix are linearly independent, so we
+//! can repair any `parity` bad blocks, data or parity, by solving for the bad
+//! blocks using the remaining blocks. Formatting is just repairing all of the
+//! parity blocks.
+//!
+//! Note this is a different scheme than the one used for up to 3 parity blocks,
+//! so the two are not compatible. And since we pass all parity blocks in a
+//! single slice, the API is slightly different:
+//!
+//! ``` rust,ignore
+//! # use ::gf256::raid::raid;
+//! #[raid(parity=4)]
+//! pub mod raid8 {}
+//!
+//! # fn main() {
+//! // format
+//! let mut buf = b"Hello World!".to_vec();
+//! let mut parity = vec![vec![0u8; 4]; 4];
+//! let slices = buf.chunks(4).collect::<Vec<_>>();
+//! raid8::format(&slices, &mut parity);
+//!
+//! // corrupt
+//! buf.fill(b'x');
+//! parity[0].fill(b'x');
+//!
+//! // repair
+//! let mut slices = buf.chunks_mut(4).collect::<Vec<_>>();
+//! raid8::repair(&mut slices, &mut parity, &[0, 1, 2, 3])?;
+//! assert_eq!(&buf, b"Hello World!");
+//! # Ok::<(), raid8::Error>(())
+//! # }
+//! ```
+//!
+//! ## Runtime-configurable arrays
+//!
+//! The [`raid`](macro@crate::raid::raid) macro fixes the number of parity blocks at compile
+//! time. If the shape of the array is only known at runtime, [`Raid`](crate::raid::Raid) provides
+//! the same schemes over `GF(256)` with both the number of data blocks and the
+//! number of parity blocks chosen at runtime:
+//!
+//! ``` rust
+//! # #[cfg(feature="alloc")] {
+//! use gf256::raid::Raid;
+//!
+//! let mut raid = Raid::new(2, 2)?;
+//! let mut blocks = vec![b"Hell".to_vec(), b"o Wo".to_vec()];
+//! let mut parity = vec![vec![0u8; 4]; 2];
+//! raid.format(&blocks, &mut parity);
+//!
+//! // grow the array
+//! raid.push(b"rld!", &mut parity)?;
+//! blocks.push(b"rld!".to_vec());
+//!
+//! // repair
+//! blocks[0].fill(b'x');
+//! blocks[2].fill(b'x');
+//! raid.repair(&mut blocks, &mut parity, &[0, 2])?;
+//! assert_eq!(blocks.concat(), b"Hello World!");
+//! # }
+//! # Ok::<(), gf256::raid::Error>(())
+//! ```
+//!
+//! ## XOR-only double parity
+//!
+//! q in [`raid6`](crate::raid::raid6) needs a finite-field multiplication for
+//! every byte, which can be expensive on weak CPUs without hardware support.
+//! As an alternative for 2 parity blocks, [`rdp`](crate::raid::rdp) implements
+//! row-diagonal parity, which gets two independent parity blocks from xor
+//! alone, at the cost of requiring block lengths to be a multiple of
+//! [`rdp::rows`](crate::raid::rdp::rows).
+//!
+//! ## Local reconstruction codes
 //!
-//! The downside Plank's approach is that you need to store an array of unique constants
-//! for each block of data, for each parity block.
+//! Repairing even a single bad block with RAID-parity requires reading every
+//! other block in the stripe. [`Lrc`](crate::raid::Lrc) implements local
+//! reconstruction codes, as used in large object stores, which split the
+//! data blocks into small groups, each with a local xor parity block, plus
+//! global parity blocks over all of the data blocks. A single bad block can
+//! be repaired by reading only its group, while the global parity blocks
+//! still cover rarer multiple failures.
+//!
+//! ## Allocation
+//!
+//! The RAID-parity functions generated by the [`raid`](macro@crate::raid::raid)
+//! macro, such as [`raid6`](crate::raid::raid6), and the xor-only
+//! [`rdp`](crate::raid::rdp) operate entirely on caller-provided buffers and
+//! never allocate, so they can be used in `no_std` storage firmware without
+//! an allocator. Only the runtime-configurable [`Raid`](crate::raid::Raid) and
+//! [`Lrc`](crate::raid::Lrc) require `alloc`, and are only available with the
+//! feature `alloc`.
+//!
+//! ## Rebuilding from block devices
+//!
+//! Real arrays rarely fit in memory. Each generated module also provides a
+//! `rebuild` function, which takes a set of devices implementing
+//! [`BlockDevice`](crate::raid::BlockDevice) and streams each stripe through
+//! `repair`, one block from each device at a time, writing the repaired
+//! blocks back to the failed devices. The stripe is read into caller-provided
+//! buffers, one block for each device. See
+//! [`raid6::rebuild`](crate::raid::raid6::rebuild) for an example.
+//!
+//! Similarly, `scrub` walks the devices checking that each stripe's parity is
+//! consistent with its data, reporting the suspected bad block where it can
+//! be determined. [`raid6::scrub_range`](crate::raid::raid6::scrub_range)
+//! allows scrubbing incrementally in a background task.
+//!
+//! For a single stripe in memory, [`raid6::verify`](crate::raid::raid6::verify)
+//! reports which parity blocks disagree with the data blocks, and the range
+//! of symbols involved in each, for targeted re-reads and logging.
+//!
+//! With feature `std`, `IoDevice` adapts anything that implements `Read`,
+//! `Write`, and `Seek`, such as a file, into a block device.
 //!
 //!
 //! [raid-wiki]: https://en.wikipedia.org/wiki/Standard_RAID_levels
@@ -988,6 +1137,7 @@
 //! [leventhal-blog]: http://dtrace.org/blogs/ahl/2009/07/21/triple-parity-raid-z
 //! [plank]: http://web.eecs.utk.edu/~jplank/plank/papers/CS-03-504.pdf
 //! [raid-example]: https://github.com/geky/gf256/blob/master/examples/raid.rs
+//! [find-and-repair]: crate::raid::raid6::find_and_repair
 
 
 /// A macro for generating custom RAID-parity modules.
@@ -1022,7 +1172,8 @@
 /// - `parity` - The number of parity blocks to use for redundancy.
 /// - `gf` - The finite-field we are implemented over, defaults to
 ///   [`gf256`](crate::gf256).
-/// - `u` - The unsigned type to operate on, defaults to [`u8`].
+/// - `u` - The unsigned type to operate on, defaults to [`u8`]. This must be
+///   the same size as `gf`, for example `u16` for [`gf2p16`](crate::gf2p16).
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -1071,6 +1222,71 @@ pub mod raid6 {}
 pub mod raid7 {}
 
 
+// Error codes shared by the runtime-configurable RAID-parity, stripe
+// layouts, and xor-only double parity
+//
+use core::fmt;
+
+/// Error codes for runtime-configurable RAID-parity, stripe layouts, and
+/// xor-only double parity
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The provided parameters don't describe a valid RAID array, the
+    /// number of blocks is limited by the number of non-zero elements in
+    /// `GF(256)`
+    InvalidParameters,
+
+    /// RAID-parity can fail to decode if there are more bad-blocks
+    /// than there are parity blocks
+    ///
+    TooManyBadBlocks,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameters => write!(f, "Invalid RAID parameters"),
+            Error::TooManyBadBlocks => write!(f, "Too many bad-blocks to repair"),
+        }
+    }
+}
+
+
+// Runtime-configurable RAID-parity
+//
+#[cfg(feature="alloc")]
+#[path="raid_runtime.rs"]
+mod runtime;
+#[cfg(feature="alloc")]
+pub use runtime::Raid;
+
+// XOR-only double parity
+//
+#[path="raid_rdp.rs"]
+pub mod rdp;
+
+// Local reconstruction codes
+//
+#[cfg(feature="alloc")]
+#[path="raid_lrc.rs"]
+mod lrc;
+#[cfg(feature="alloc")]
+pub use lrc::Lrc;
+
+// Stripe layouts with rotating parity
+//
+#[path="raid_layout.rs"]
+pub mod layout;
+
+// Block devices for streaming rebuilds
+//
+#[path="raid_device.rs"]
+mod device;
+pub use device::{BlockDevice, RebuildError, Inconsistency};
+#[cfg(feature="std")]
+pub use device::IoDevice;
+
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1078,6 +1294,8 @@ mod test {
 
     extern crate alloc;
     use alloc::vec::Vec;
+    use alloc::vec;
+    use alloc::string::ToString;
 
     #[test]
     fn raid5() {
@@ -1089,10 +1307,10 @@ mod test {
         let mut p = (40..50).collect::<Vec<u8>>();
 
         // format
-        raid5::format(&mut blocks, &mut p);
+        raid5::format(&blocks, &mut p);
 
         // update
-        raid5::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p);
+        raid5::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u8>>());
@@ -1118,10 +1336,10 @@ mod test {
         let mut p = (10..20).collect::<Vec<u8>>();
 
         // format
-        raid5::format(&mut blocks, &mut p);
+        raid5::format(&blocks, &mut p);
 
         // mount and update
-        raid5::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p);
+        raid5::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         for i in 0..255 {
             assert_eq!(&blocks[i], &((i+1)*10..(i+2)*10).map(|x| x as u8).collect::<Vec<u8>>());
@@ -1150,10 +1368,10 @@ mod test {
         let mut q = (50..60).collect::<Vec<u8>>();
 
         // format
-        raid6::format(&mut blocks, &mut p, &mut q);
+        raid6::format(&blocks, &mut p, &mut q);
 
         // update
-        raid6::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q);
+        raid6::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u8>>());
@@ -1187,6 +1405,16 @@ mod test {
         }
     }
 
+    #[test]
+    #[should_panic]
+    fn raid6_repair_mismatched() {
+        // long enough to use the byte-shuffle kernels
+        let mut blocks = vec![vec![1u8; 1024], vec![2u8; 1024], vec![3u8; 16]];
+        let mut p = vec![0u8; 1024];
+        let mut q = vec![0u8; 1024];
+        let _ = raid6::repair(&mut blocks, &mut p, &mut q, &[0]);
+    }
+
     #[test]
     fn raid6_large() {
         let mut blocks = Vec::new();
@@ -1197,10 +1425,10 @@ mod test {
         let mut q = (10..20).collect::<Vec<u8>>();
 
         // format
-        raid6::format(&mut blocks, &mut p, &mut q);
+        raid6::format(&blocks, &mut p, &mut q);
 
         // mount and update
-        raid6::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q);
+        raid6::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         for i in 0..255 {
             assert_eq!(&blocks[i], &((i+1)*10..(i+2)*10).map(|x| x as u8).collect::<Vec<u8>>());
@@ -1231,10 +1459,10 @@ mod test {
         let mut r = (60..70).collect::<Vec<u8>>();
 
         // format
-        raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         // update
-        raid7::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
+        raid7::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u8>>());
@@ -1299,10 +1527,10 @@ mod test {
         let mut r = (10..20).collect::<Vec<u8>>();
 
         // format
-        raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         // mount and update
-        raid7::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
+        raid7::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         for i in 0..255 {
             assert_eq!(&blocks[i], &((i+1)*10..(i+2)*10).map(|x| x as u8).collect::<Vec<u8>>());
@@ -1322,6 +1550,516 @@ mod test {
         }
     }
 
+    #[test]
+    fn raid7_long() {
+        // long blocks, which may take a different path through our slice
+        // kernels
+        let block = |i: usize| (0..1000).map(|x| (x*7 + i*13) as u8).collect::<Vec<u8>>();
+        let mut blocks = (0..5).map(block).collect::<Vec<_>>();
+        let mut p = [0u8; 1000];
+        let mut q = [0u8; 1000];
+        let mut r = [0u8; 1000];
+
+        // format
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        for x in 0..1000 {
+            let g = |j: usize| gf256::GENERATOR.pow(j as u8);
+            assert_eq!(gf256(p[x]), (0..5).map(|j| gf256(blocks[j][x])).sum());
+            assert_eq!(gf256(q[x]), (0..5).map(|j| gf256(blocks[j][x])*g(j)).sum());
+            assert_eq!(gf256(r[x]), (0..5).map(|j| gf256(blocks[j][x])*g(j)*g(j)).sum());
+        }
+
+        // remove and add
+        raid7::remove(2, &blocks[2], &mut p, &mut q, &mut r);
+        raid7::add(2, &blocks[2], &mut p, &mut q, &mut r);
+
+        for i in 0..5+3 {
+            for j in i+1..5+3 {
+                for k in j+1..5+3 {
+                    // clobber
+                    for x in [i, j, k] {
+                        if x < blocks.len() { blocks[x].fill(b'x'); }
+                    }
+                    // repair
+                    raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &[i, j, k]).unwrap();
+                    for x in 0..5 {
+                        assert_eq!(blocks[x], block(x));
+                    }
+                }
+            }
+        }
+    }
+
+    // silent corruption
+    #[test]
+    fn raid6_find_and_repair() {
+        let data = (0..10)
+            .map(|i| (0..100).map(|j| (i*100+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut blocks = data.clone();
+        let mut p = vec![0u8; 100];
+        let mut q = vec![0u8; 100];
+        raid6::format(&blocks, &mut p, &mut q);
+        let (p0, q0) = (p.clone(), q.clone());
+        assert_eq!(raid6::find_and_repair(&mut blocks, &mut p, &mut q), Ok(None));
+
+        for i in 0..blocks.len()+2 {
+            // silently corrupt a few bytes
+            for j in [3, 50, 51, 99] {
+                match i {
+                    i if i < 10 => blocks[i][j] ^= j as u8,
+                    10 => p[j] ^= j as u8,
+                    _ => q[j] ^= j as u8,
+                }
+            }
+            // find and repair
+            assert_eq!(raid6::find_and_repair(&mut blocks, &mut p, &mut q), Ok(Some(i)));
+            assert_eq!(blocks, data);
+            assert_eq!(p, p0);
+            assert_eq!(q, q0);
+        }
+
+        // two bad blocks can't be located
+        blocks[1][10] ^= 1;
+        blocks[2][20] ^= 1;
+        let bad = blocks.clone();
+        assert_eq!(raid6::find_and_repair(&mut blocks, &mut p, &mut q), Err(raid6::Error::TooManyBadBlocks));
+        assert_eq!(blocks, bad);
+    }
+
+    #[test]
+    fn raid7_find_and_repair() {
+        let data = (0..10)
+            .map(|i| (0..100).map(|j| (i*100+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut blocks = data.clone();
+        let mut p = vec![0u8; 100];
+        let mut q = vec![0u8; 100];
+        let mut r = vec![0u8; 100];
+        raid7::format(&blocks, &mut p, &mut q, &mut r);
+        let (p0, q0, r0) = (p.clone(), q.clone(), r.clone());
+        assert_eq!(raid7::find_and_repair(&mut blocks, &mut p, &mut q, &mut r), Ok(None));
+
+        for i in 0..blocks.len()+3 {
+            // silently corrupt a few bytes
+            for j in [0, 42, 98] {
+                match i {
+                    i if i < 10 => blocks[i][j] ^= 0xff,
+                    10 => p[j] ^= 0xff,
+                    11 => q[j] ^= 0xff,
+                    _ => r[j] ^= 0xff,
+                }
+            }
+            // find and repair
+            assert_eq!(raid7::find_and_repair(&mut blocks, &mut p, &mut q, &mut r), Ok(Some(i)));
+            assert_eq!(blocks, data);
+            assert_eq!(p, p0);
+            assert_eq!(q, q0);
+            assert_eq!(r, r0);
+        }
+
+        // p and q both bad can't be explained by one bad block
+        p[0] ^= 1;
+        q[0] ^= 1;
+        assert_eq!(raid7::find_and_repair(&mut blocks, &mut p, &mut q, &mut r), Err(raid7::Error::TooManyBadBlocks));
+    }
+
+    // more than 255 blocks
+    #[raid(gf=gf2p16, u=u16, parity=2)]
+    pub mod gf2p16_raid6 {}
+
+    #[raid(gf=gf2p16, u=u16, parity=3)]
+    pub mod gf2p16_raid7 {}
+
+    #[raid(gf=gf2p16, u=u16, parity=4)]
+    pub mod gf2p16_raid8 {}
+
+    #[test]
+    fn gf2p16_raid_wide() {
+        let data = (0..1000)
+            .map(|i| (0..10).map(|j| (i*10+j) as u16).collect::<Vec<u16>>())
+            .collect::<Vec<_>>();
+        let mut blocks = data.clone();
+
+        // raid6
+        let mut p = vec![0u16; 10];
+        let mut q = vec![0u16; 10];
+        gf2p16_raid6::format(&blocks, &mut p, &mut q);
+        for bad_blocks in [[0, 999], [300, 700], [256, 1001], [998, 1000]] {
+            for &i in &bad_blocks {
+                if i < 1000 { blocks[i].fill(0xffff); }
+            }
+            gf2p16_raid6::repair(&mut blocks, &mut p, &mut q, &bad_blocks).unwrap();
+            assert_eq!(blocks, data);
+        }
+
+        // raid7
+        let mut r = vec![0u16; 10];
+        gf2p16_raid7::format(&blocks, &mut p, &mut q, &mut r);
+        for bad_blocks in [[0, 500, 999], [255, 256, 257], [998, 1000, 1002]] {
+            for &i in &bad_blocks {
+                if i < 1000 { blocks[i].fill(0xffff); }
+            }
+            gf2p16_raid7::repair(&mut blocks, &mut p, &mut q, &mut r, &bad_blocks).unwrap();
+            assert_eq!(blocks, data);
+        }
+
+        // raid8
+        let mut parity = vec![vec![0u16; 10]; 4];
+        gf2p16_raid8::format(&blocks, &mut parity);
+        let formatted = parity.clone();
+        for bad_blocks in [[0, 1, 998, 999], [255, 256, 1000, 1003], [500, 1001, 1002, 1003]] {
+            for &i in &bad_blocks {
+                if i < 1000 { blocks[i].fill(0xffff); } else { parity[i-1000].fill(0xffff); }
+            }
+            gf2p16_raid8::repair(&mut blocks, &mut parity, &bad_blocks).unwrap();
+            assert_eq!(blocks, data);
+            assert_eq!(parity, formatted);
+        }
+    }
+
+    // runtime-configurable RAID-parity
+    #[cfg(feature="alloc")]
+    #[test]
+    fn raid_runtime() {
+        let data = (0..10)
+            .map(|i| (0..100).map(|j| (i*100+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+
+        // should match raid5/raid6/raid7/raid8
+        let mut expected = vec![vec![0u8; 100]; 4];
+        for parity in 1..=4 {
+            let raid = Raid::new(10, parity).unwrap();
+            let mut parities = vec![vec![0u8; 100]; parity];
+            raid.format(&data, &mut parities);
+            match parity {
+                1 => raid5::format(&data, &mut expected[0]),
+                2 => {
+                    let (p, q) = expected.split_at_mut(1);
+                    raid6::format(&data, &mut p[0], &mut q[0]);
+                }
+                3 => {
+                    let (p, qr) = expected.split_at_mut(1);
+                    let (q, r) = qr.split_at_mut(1);
+                    raid7::format(&data, &mut p[0], &mut q[0], &mut r[0]);
+                }
+                _ => raid8::format(&data, &mut expected),
+            }
+            assert_eq!(parities, &expected[..parity]);
+        }
+
+        for parity in 0..=6 {
+            let raid = Raid::new(10, parity).unwrap();
+            let mut blocks = data.clone();
+            let mut parities = vec![vec![0u8; 100]; parity];
+            raid.format(&blocks, &mut parities);
+            let formatted = parities.clone();
+
+            // repair any combination of up to parity bad blocks
+            for bad in 0u32..(1 << (10+parity)) {
+                if bad.count_ones() as usize > parity.min(3) {
+                    continue;
+                }
+                let bad_blocks = (0..10+parity).filter(|i| bad & (1 << i) != 0).collect::<Vec<usize>>();
+                for &i in &bad_blocks {
+                    if i < 10 { blocks[i].fill(b'x'); } else { parities[i-10].fill(b'x'); }
+                }
+                raid.repair(&mut blocks, &mut parities, &bad_blocks).unwrap();
+                assert_eq!(blocks, data);
+                assert_eq!(parities, formatted);
+            }
+
+            // too many bad blocks
+            assert_eq!(
+                raid.repair(&mut blocks, &mut parities, &(0..parity+1).collect::<Vec<_>>()),
+                Err(Error::TooManyBadBlocks)
+            );
+        }
+
+        // grow and shrink
+        let mut raid = Raid::new(0, 5).unwrap();
+        let mut parities = vec![vec![0u8; 100]; 5];
+        for b in &data {
+            raid.push(b, &mut parities).unwrap();
+        }
+        let mut expected = vec![vec![0u8; 100]; 5];
+        raid.format(&data, &mut expected);
+        assert_eq!(raid.blocks(), 10);
+        assert_eq!(parities, expected);
+
+        let mut blocks = data.clone();
+        let new = vec![0x55u8; 100];
+        raid.update(3, &blocks[3], &new, &mut parities);
+        blocks[3] = new;
+        raid.pop(&blocks[9], &mut parities);
+        blocks.pop();
+        let raid_ = Raid::new(9, 5).unwrap();
+        raid_.format(&blocks, &mut expected);
+        assert_eq!(raid.blocks(), 9);
+        assert_eq!(parities, expected);
+
+        // limits
+        assert!(Raid::new(1000, 1).is_ok());
+        assert_eq!(Raid::new(256, 2).err(), Some(Error::InvalidParameters));
+        assert_eq!(Raid::new(252, 4).err(), Some(Error::InvalidParameters));
+        assert!(Raid::new(0, 255).is_ok());
+        assert_eq!(Raid::new(1, 255).err(), Some(Error::InvalidParameters));
+        assert_eq!(Raid::new(0, 256).err(), Some(Error::InvalidParameters));
+        let mut raid = Raid::new(251, 4).unwrap();
+        assert_eq!(raid.push(&[0; 100], &mut parities[..4]), Err(Error::InvalidParameters));
+    }
+
+    // xor-only double parity
+    #[test]
+    fn raid_rdp() {
+        assert_eq!(rdp::rows(1), 1);
+        assert_eq!(rdp::rows(2), 2);
+        assert_eq!(rdp::rows(4), 4);
+        assert_eq!(rdp::rows(5), 6);
+        assert_eq!(rdp::rows(10), 10);
+
+        for n in 1..=12 {
+            let len = 3*rdp::rows(n);
+            let data = (0..n)
+                .map(|j| (0..len).map(|k| (j*len + k) as u8).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let mut p = vec![0u8; len];
+            let mut q = vec![0u8; len];
+            rdp::format(&data, &mut p, &mut q);
+
+            // any 2 bad blocks can be repaired
+            for i in 0..n+2 {
+                for j in i..n+2 {
+                    let mut blocks = data.clone();
+                    let mut p_ = p.clone();
+                    let mut q_ = q.clone();
+                    for k in [i, j] {
+                        if k < n {
+                            blocks[k].fill(b'x');
+                        } else if k == n {
+                            p_.fill(b'x');
+                        } else {
+                            q_.fill(b'x');
+                        }
+                    }
+
+                    rdp::repair(&mut blocks, &mut p_, &mut q_, &[i, j]).unwrap();
+                    assert_eq!(blocks, data);
+                    assert_eq!(p_, p);
+                    assert_eq!(q_, q);
+                }
+            }
+
+            // but not 3
+            if n >= 3 {
+                assert_eq!(
+                    rdp::repair(&mut data.clone(), &mut p.clone(), &mut q.clone(), &[0, 1, 2]),
+                    Err(Error::TooManyBadBlocks)
+                );
+            }
+
+            // update
+            for j in 0..n {
+                let new = (0..len).map(|k| (k*7) as u8).collect::<Vec<_>>();
+                let mut p_ = p.clone();
+                let mut q_ = q.clone();
+                rdp::update(n, j, &data[j], &new, &mut p_, &mut q_);
+
+                let mut blocks = data.clone();
+                blocks[j] = new;
+                let mut p2 = vec![0u8; len];
+                let mut q2 = vec![0u8; len];
+                rdp::format(&blocks, &mut p2, &mut q2);
+                assert_eq!(p_, p2);
+                assert_eq!(q_, q2);
+            }
+        }
+    }
+
+    // local reconstruction codes
+    #[cfg(feature="alloc")]
+    #[test]
+    fn raid_lrc() {
+        // uneven groups
+        let lrc = Lrc::new(7, 3, 2).unwrap();
+        assert_eq!(lrc.group(0), 0..3);
+        assert_eq!(lrc.group(1), 3..5);
+        assert_eq!(lrc.group(2), 5..7);
+        for j in 0..7 {
+            assert!(lrc.group(lrc.group_of(j)).contains(&j));
+        }
+        assert_eq!(Lrc::new(3, 4, 2).err(), Some(Error::InvalidParameters));
+        assert_eq!(Lrc::new(3, 0, 2).err(), Some(Error::InvalidParameters));
+        assert_eq!(Lrc::new(256, 16, 2).err(), Some(Error::InvalidParameters));
+
+        let data = (0..7)
+            .map(|j| (0..20).map(|k| (j*20 + k) as u8).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut local = vec![vec![0u8; 20]; 3];
+        let mut global = vec![vec![0u8; 20]; 2];
+        lrc.format(&data, &mut local, &mut global);
+        let expected = (data.clone(), local.clone(), global.clone());
+
+        // repair within a group
+        for i in 0..3 {
+            let group = lrc.group(i);
+            for j in 0..group.len()+1 {
+                let mut blocks = data[group.clone()].to_vec();
+                let mut l = local[i].clone();
+                if j < blocks.len() { blocks[j].fill(b'x'); } else { l.fill(b'x'); }
+                lrc.repair_group(&mut blocks, &mut l, j);
+                assert_eq!(&blocks, &data[group.clone()]);
+                assert_eq!(&l, &local[i]);
+            }
+        }
+
+        // any 2 bad blocks can be repaired, and some 3 bad blocks
+        let n = 7+3+2;
+        let mut repaired3 = 0;
+        for a in 0..n {
+            for b in a+1..n {
+                for c in b..n {
+                    let bad = if b == c { vec![a, b] } else { vec![a, b, c] };
+                    let (mut blocks, mut local, mut global) = expected.clone();
+                    for &j in &bad {
+                        if j < 7 {
+                            blocks[j].fill(b'x');
+                        } else if j < 10 {
+                            local[j-7].fill(b'x');
+                        } else {
+                            global[j-10].fill(b'x');
+                        }
+                    }
+
+                    match lrc.repair(&mut blocks, &mut local, &mut global, &bad) {
+                        Ok(()) => {
+                            assert_eq!((blocks, local, global), expected);
+                            if bad.len() == 3 {
+                                repaired3 += 1;
+                            }
+                        }
+                        Err(Error::TooManyBadBlocks) => assert_eq!(bad.len(), 3),
+                        Err(e) => panic!("{:?}", e),
+                    }
+                }
+            }
+        }
+        // one bad block in each group is always repairable
+        assert!(repaired3 > 0);
+        let (mut blocks, mut local, mut global) = expected.clone();
+        blocks[0].fill(b'x');
+        blocks[3].fill(b'x');
+        blocks[5].fill(b'x');
+        global[1].fill(b'x');
+        lrc.repair(&mut blocks, &mut local, &mut global, &[0, 3, 5, 11]).unwrap();
+        assert_eq!((blocks, local, global), expected.clone());
+
+        // 3 bad blocks in one group needs both global parity blocks
+        let (mut blocks, mut local, mut global) = expected.clone();
+        assert_eq!(
+            lrc.repair(&mut blocks, &mut local, &mut global, &[0, 1, 2, 10]),
+            Err(Error::TooManyBadBlocks)
+        );
+        assert_eq!((blocks, local, global), expected.clone());
+
+        // update
+        let (mut blocks, mut local, mut global) = expected.clone();
+        let new = vec![0x55u8; 20];
+        lrc.update(4, &blocks[4].clone(), &new, &mut local, &mut global);
+        blocks[4] = new;
+        let mut local2 = vec![vec![0u8; 20]; 3];
+        let mut global2 = vec![vec![0u8; 20]; 2];
+        lrc.format(&blocks, &mut local2, &mut global2);
+        assert_eq!(local, local2);
+        assert_eq!(global, global2);
+    }
+
+    // stripe layouts
+    #[test]
+    fn raid_layout() {
+        use layout::*;
+
+        // left-symmetric, compare against md
+        let l = Layout::new(4, 1, 16, Rotation::LeftSymmetric).unwrap();
+        let disks = (0..4)
+            .map(|stripe| (0..4).map(|disk| l.block(stripe, disk)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(disks, [[0, 1, 2, 3], [1, 2, 3, 0], [2, 3, 0, 1], [3, 0, 1, 2]]);
+        let l = Layout::new(4, 1, 16, Rotation::LeftAsymmetric).unwrap();
+        let disks = (0..4)
+            .map(|stripe| (0..4).map(|disk| l.block(stripe, disk)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(disks, [[0, 1, 2, 3], [0, 1, 3, 2], [0, 3, 1, 2], [3, 0, 1, 2]]);
+
+        // all layouts should be bijections
+        for rotation in [
+            Rotation::None,
+            Rotation::LeftAsymmetric,
+            Rotation::LeftSymmetric,
+            Rotation::RightAsymmetric,
+            Rotation::RightSymmetric,
+        ] {
+            for parity in 0..4 {
+                let l = Layout::new(6, parity, 16, rotation).unwrap();
+                for stripe in 0..12 {
+                    let mut seen = [false; 6];
+                    for j in 0..6 {
+                        let disk = l.disk(stripe, j);
+                        assert!(!seen[disk]);
+                        seen[disk] = true;
+                        assert_eq!(l.block(stripe, disk), j);
+                    }
+                }
+
+                for addr in (0..l.stripe_size()*12).step_by(7) {
+                    let loc = l.map(addr);
+                    assert!(l.block(loc.stripe, loc.disk) < l.data_disks());
+                    assert_eq!(l.unmap(loc), Some(addr));
+                }
+                for i in 0..parity {
+                    let loc = Location { stripe: 5, disk: l.parity_disk(5, i), offset: 5*16 };
+                    assert_eq!(l.unmap(loc), None);
+                }
+            }
+        }
+
+        assert_eq!(Layout::new(2, 2, 16, Rotation::None), Err(Error::InvalidParameters));
+        assert_eq!(Layout::new(3, 2, 0, Rotation::None), Err(Error::InvalidParameters));
+
+        // write data through a layout, and repair a failed disk
+        let l = Layout::new(5, 2, 4, Rotation::LeftSymmetric).unwrap();
+        let data = (0..l.capacity(40)).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let mut disks = vec![vec![0u8; 40]; 5];
+        for (addr, x) in data.iter().enumerate() {
+            let loc = l.map(addr as u64);
+            disks[loc.disk][loc.offset as usize] = *x;
+        }
+        for stripe in 0..10 {
+            let r = stripe as usize*4..stripe as usize*4+4;
+            let blocks = (0..3).map(|j| disks[l.data_disk(stripe, j)][r.clone()].to_vec()).collect::<Vec<_>>();
+            let mut p = vec![0u8; 4];
+            let mut q = vec![0u8; 4];
+            raid6::format(&blocks, &mut p, &mut q);
+            disks[l.parity_disk(stripe, 0)][r.clone()].copy_from_slice(&p);
+            disks[l.parity_disk(stripe, 1)][r.clone()].copy_from_slice(&q);
+        }
+
+        let expected = disks.clone();
+        disks[1].fill(0);
+        disks[3].fill(0);
+        for stripe in 0..10 {
+            let r = stripe as usize*4..stripe as usize*4+4;
+            let mut blocks = (0..5).map(|j| disks[l.disk(stripe, j)][r.clone()].to_vec()).collect::<Vec<_>>();
+            let (datas, parity) = blocks.split_at_mut(3);
+            let (p, q) = parity.split_at_mut(1);
+            raid6::repair(datas, &mut p[0], &mut q[0], &[l.block(stripe, 1), l.block(stripe, 3)]).unwrap();
+            for (j, b) in blocks.iter().enumerate() {
+                disks[l.disk(stripe, j)][r.clone()].copy_from_slice(b);
+            }
+        }
+        assert_eq!(disks, expected);
+    }
+
     // why do we have this option?
     #[raid(parity=0)]
     pub mod raid0 {}
@@ -1335,10 +2073,10 @@ mod test {
         ];
 
         // format
-        raid0::format(&mut blocks);
+        raid0::format(&blocks);
 
         // update
-        raid0::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>());
+        raid0::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>());
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u8>>());
@@ -1361,10 +2099,10 @@ mod test {
         let mut r = (60..70).collect::<Vec<u64>>();
 
         // format
-        gf2p64_raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        gf2p64_raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         // update
-        gf2p64_raid7::update(0, &mut blocks[0], &(10..20).collect::<Vec<u64>>(), &mut p, &mut q, &mut r);
+        gf2p64_raid7::update(0, &blocks[0], &(10..20).collect::<Vec<u64>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u64>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u64>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u64>>());
@@ -1441,10 +2179,10 @@ mod test {
         let mut r = (60..70).map(|x| x%16).collect::<Vec<u8>>();
 
         // format
-        gf16_raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        gf16_raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         // update
-        gf16_raid7::update(0, &mut blocks[0], &(10..20).map(|x| x%16).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
+        gf16_raid7::update(0, &blocks[0], &(10..20).map(|x| x%16).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).map(|x| x%16).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).map(|x| x%16).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).map(|x| x%16).collect::<Vec<u8>>());
@@ -1510,10 +2248,10 @@ mod test {
         let mut r = (60..70).collect::<Vec<u32>>();
 
         // format
-        gf2p23_raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        gf2p23_raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         // update
-        gf2p23_raid7::update(0, &mut blocks[0], &(10..20).collect::<Vec<u32>>(), &mut p, &mut q, &mut r);
+        gf2p23_raid7::update(0, &blocks[0], &(10..20).collect::<Vec<u32>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u32>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u32>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u32>>());
@@ -1584,7 +2322,7 @@ mod test {
         let mut r = (10..20).collect::<Vec<u8>>();
 
         // format
-        gf16_raid7::format(&mut blocks, &mut p, &mut q, &mut r);
+        gf16_raid7::format(&blocks, &mut p, &mut q, &mut r);
 
         for i in 0..blocks.len() {
             for j in 0..blocks.len() {
@@ -1609,6 +2347,110 @@ mod test {
         }
     }
 
+    // more than 3 parity blocks
+    #[raid(parity=4)]
+    pub mod raid8 {}
+
+    #[raid(gf=gf2p64, u=u64, parity=6)]
+    pub mod gf2p64_raid10 {}
+
+    #[test]
+    fn raid8() {
+        let data = (0..5)
+            .map(|i| (0..100).map(|j| (i*100+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut blocks = data.clone();
+        let mut parity = vec![vec![b'x'; 100]; 4];
+
+        // format
+        raid8::format(&blocks, &mut parity);
+        let formatted = parity.clone();
+
+        // repair any combination of up to 4 bad blocks
+        for bad in 0u32..(1 << 9) {
+            if bad.count_ones() > 4 {
+                continue;
+            }
+            let bad_blocks = (0..9).filter(|i| bad & (1 << i) != 0).collect::<Vec<usize>>();
+
+            // clobber
+            for &i in &bad_blocks {
+                if i < 5 { blocks[i].fill(b'x'); } else { parity[i-5].fill(b'x'); }
+            }
+            // repair
+            raid8::repair(&mut blocks, &mut parity, &bad_blocks).unwrap();
+            assert_eq!(blocks, data);
+            assert_eq!(parity, formatted);
+        }
+
+        // too many bad blocks
+        assert_eq!(
+            raid8::repair(&mut blocks, &mut parity, &[0, 1, 2, 3, 4]),
+            Err(raid8::Error::TooManyBadBlocks)
+        );
+
+        // update
+        let new = (0..100).map(|j| (j*3) as u8).collect::<Vec<u8>>();
+        raid8::update(2, &blocks[2], &new, &mut parity);
+        blocks[2].copy_from_slice(&new);
+        let mut formatted = vec![vec![0u8; 100]; 4];
+        raid8::format(&blocks, &mut formatted);
+        assert_eq!(parity, formatted);
+
+        // update a range
+        let mut ranges = parity.iter_mut().map(|p| &mut p[10..20]).collect::<Vec<_>>();
+        raid8::update(4, &blocks[4][10..20], b"xxxxxxxxxx", &mut ranges);
+        blocks[4][10..20].copy_from_slice(b"xxxxxxxxxx");
+        raid8::format(&blocks, &mut formatted);
+        assert_eq!(parity, formatted);
+
+        // remove/add
+        raid8::remove(4, &blocks[4], &mut parity);
+        raid8::format(&blocks[..4], &mut formatted);
+        assert_eq!(parity, formatted);
+        raid8::add(4, &blocks[4], &mut parity);
+        raid8::format(&blocks, &mut formatted);
+        assert_eq!(parity, formatted);
+    }
+
+    #[test]
+    fn gf2p64_raid10() {
+        let data = (0..10)
+            .map(|i| (0..10).map(|j| (i*10+j) as u64 * 0x0123456789abcdef).collect::<Vec<u64>>())
+            .collect::<Vec<_>>();
+        let mut blocks = data.clone();
+        let mut parity = vec![vec![0u64; 10]; 6];
+
+        // format
+        gf2p64_raid10::format(&blocks, &mut parity);
+        let formatted = parity.clone();
+
+        for bad_blocks in [
+            &[0, 1, 2, 3, 4, 5][..],
+            &[10, 11, 12, 13, 14, 15],
+            &[0, 2, 4, 11, 13, 15],
+            &[9, 3, 14, 6],
+            &[15],
+        ] {
+            // clobber
+            for &i in bad_blocks {
+                if i < 10 { blocks[i].fill(0); } else { parity[i-10].fill(0); }
+            }
+            // repair
+            gf2p64_raid10::repair(&mut blocks, &mut parity, bad_blocks).unwrap();
+            assert_eq!(blocks, data);
+            assert_eq!(parity, formatted);
+        }
+
+        // update
+        let new = (0..10).map(|j| j as u64).collect::<Vec<u64>>();
+        gf2p64_raid10::update(9, &blocks[9], &new, &mut parity);
+        blocks[9].copy_from_slice(&new);
+        gf2p64_raid10::repair(&mut blocks, &mut parity, &[0, 1, 9, 10, 12, 15]).unwrap();
+        assert_eq!(&blocks[..9], &data[..9]);
+        assert_eq!(blocks[9], new);
+    }
+
     // all RAID-parity params
     #[raid(gf=gf256, u=u8, parity=3)]
     pub mod raid7_all_params {}
@@ -1625,10 +2467,10 @@ mod test {
         let mut r = (60..70).collect::<Vec<u8>>();
 
         // format
-        raid7_all_params::format(&mut blocks, &mut p, &mut q, &mut r);
+        raid7_all_params::format(&blocks, &mut p, &mut q, &mut r);
 
         // update
-        raid7_all_params::update(0, &mut blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
+        raid7_all_params::update(0, &blocks[0], &(10..20).collect::<Vec<u8>>(), &mut p, &mut q, &mut r);
         blocks[0].copy_from_slice(&(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[0], &(10..20).collect::<Vec<u8>>());
         assert_eq!(&blocks[1], &(20..30).collect::<Vec<u8>>());
@@ -1681,4 +2523,371 @@ mod test {
             }
         }
     }
+
+    // streaming rebuilds
+    struct MemDevice<U> {
+        blocks: Vec<Vec<U>>,
+        broken: bool,
+    }
+
+    impl<U: Copy + Default> MemDevice<U> {
+        fn new(block_size: usize, block_count: usize) -> Self {
+            MemDevice {
+                blocks: vec![vec![U::default(); block_size]; block_count],
+                broken: false,
+            }
+        }
+    }
+
+    impl<U: Copy> BlockDevice<U> for MemDevice<U> {
+        type Error = &'static str;
+
+        fn block_size(&self) -> usize {
+            self.blocks[0].len()
+        }
+
+        fn block_count(&self) -> u64 {
+            self.blocks.len() as u64
+        }
+
+        fn read_block(&mut self, block: u64, buf: &mut [U]) -> Result<(), Self::Error> {
+            if self.broken {
+                return Err("broken");
+            }
+            buf.copy_from_slice(&self.blocks[block as usize]);
+            Ok(())
+        }
+
+        fn write_block(&mut self, block: u64, buf: &[U]) -> Result<(), Self::Error> {
+            self.blocks[block as usize].copy_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn raid_rebuild() {
+        // 3 data devices + up to 4 parity devices, 5 blocks of 10 bytes
+        let mut devices = (0..7)
+            .map(|_| MemDevice::<u8>::new(10, 5))
+            .collect::<Vec<_>>();
+        for (i, d) in devices[..3].iter_mut().enumerate() {
+            for (b, block) in d.blocks.iter_mut().enumerate() {
+                for (k, x) in block.iter_mut().enumerate() {
+                    *x = (100*i + 10*b + k) as u8;
+                }
+            }
+        }
+        let expected = devices[..3].iter()
+            .map(|d| d.blocks.clone())
+            .collect::<Vec<_>>();
+
+        // format each stripe with raid8
+        for b in 0..5 {
+            let datas = devices[..3].iter().map(|d| d.blocks[b].clone()).collect::<Vec<_>>();
+            let mut parity = vec![vec![0u8; 10]; 4];
+            raid8::format(&datas, &mut parity);
+            for (d, p) in devices[3..].iter_mut().zip(parity) {
+                d.blocks[b] = p;
+            }
+        }
+        let formatted = devices.iter().map(|d| d.blocks.clone()).collect::<Vec<_>>();
+
+        let mut buffers = vec![vec![0u8; 10]; 7];
+        for failed in [&[0][..], &[1, 4], &[0, 2, 6], &[0, 1, 2, 5]] {
+            for &i in failed {
+                devices[i] = MemDevice::new(10, 5);
+            }
+            raid8::rebuild(&mut devices, failed, &mut buffers).unwrap();
+            for (d, f) in devices.iter().zip(&formatted) {
+                assert_eq!(&d.blocks, f);
+            }
+        }
+
+        // raid6 over the first 5 devices, treating devices 3-4 as p and q
+        let mut devices = devices.into_iter().take(5).collect::<Vec<_>>();
+        for b in 0..5 {
+            let datas = devices[..3].iter().map(|d| d.blocks[b].clone()).collect::<Vec<_>>();
+            let (p, q) = devices[3..].split_at_mut(1);
+            raid6::format(&datas, &mut p[0].blocks[b], &mut q[0].blocks[b]);
+        }
+        devices[1] = MemDevice::new(10, 5);
+        devices[4] = MemDevice::new(10, 5);
+        let mut buffers = vec![vec![0u8; 10]; 5];
+        raid6::rebuild(&mut devices, &[1, 4], &mut buffers).unwrap();
+        for (d, e) in devices.iter().zip(&expected) {
+            assert_eq!(&d.blocks, e);
+        }
+
+        // errors
+        assert_eq!(
+            raid6::rebuild(&mut devices, &[0, 1, 2], &mut buffers),
+            Err(RebuildError::TooManyBadBlocks)
+        );
+        devices[2].broken = true;
+        assert_eq!(
+            raid6::rebuild(&mut devices, &[0], &mut buffers),
+            Err(RebuildError::Device(2, "broken"))
+        );
+        // broken devices can be rebuilt since they are never read
+        raid6::rebuild(&mut devices, &[2], &mut buffers).unwrap();
+        for (d, e) in devices.iter().zip(&expected) {
+            assert_eq!(&d.blocks, e);
+        }
+    }
+
+    #[test]
+    fn gf2p64_raid_rebuild() {
+        let mut devices = (0..8)
+            .map(|_| MemDevice::<u64>::new(4, 3))
+            .collect::<Vec<_>>();
+        for (i, d) in devices[..2].iter_mut().enumerate() {
+            for (b, block) in d.blocks.iter_mut().enumerate() {
+                for (k, x) in block.iter_mut().enumerate() {
+                    *x = 0x0123456789abcdef * (100*i + 10*b + k + 1) as u64;
+                }
+            }
+        }
+        for b in 0..3 {
+            let datas = devices[..2].iter().map(|d| d.blocks[b].clone()).collect::<Vec<_>>();
+            let mut parity = vec![vec![0u64; 4]; 6];
+            gf2p64_raid10::format(&datas, &mut parity);
+            for (d, p) in devices[2..].iter_mut().zip(parity) {
+                d.blocks[b] = p;
+            }
+        }
+        let formatted = devices.iter().map(|d| d.blocks.clone()).collect::<Vec<_>>();
+
+        let failed = [0, 1, 3, 4, 6, 7];
+        for &i in &failed {
+            devices[i] = MemDevice::new(4, 3);
+        }
+        let mut buffers = [[0u64; 4]; 8];
+        gf2p64_raid10::rebuild(&mut devices, &failed, &mut buffers).unwrap();
+        for (d, f) in devices.iter().zip(&formatted) {
+            assert_eq!(&d.blocks, f);
+        }
+    }
+
+    #[test]
+    fn raid_scrub() {
+        // 3 data devices + up to 4 parity devices, 8 blocks of 10 bytes
+        fn devices(parity: usize) -> Vec<MemDevice<u8>> {
+            let mut devices = (0..3+parity)
+                .map(|_| MemDevice::<u8>::new(10, 8))
+                .collect::<Vec<_>>();
+            for (i, d) in devices[..3].iter_mut().enumerate() {
+                for (b, block) in d.blocks.iter_mut().enumerate() {
+                    for (k, x) in block.iter_mut().enumerate() {
+                        *x = (100*i + 10*b + k) as u8;
+                    }
+                }
+            }
+
+            for b in 0..8 {
+                let datas = devices[..3].iter().map(|d| d.blocks[b].clone()).collect::<Vec<_>>();
+                let mut parity = vec![vec![0u8; 10]; parity];
+                match parity.as_mut_slice() {
+                    [p] => raid5::format(&datas, p),
+                    [p, q] => raid6::format(&datas, p, q),
+                    [p, q, r] => raid7::format(&datas, p, q, r),
+                    parity => raid8::format(&datas, parity),
+                }
+                for (d, p) in devices[3..].iter_mut().zip(parity) {
+                    d.blocks[b] = p;
+                }
+            }
+            devices
+        }
+
+        fn scrub_all(
+            scrub: impl Iterator<Item=Result<Inconsistency, RebuildError<&'static str>>>
+        ) -> Vec<Inconsistency> {
+            scrub.collect::<Result<Vec<_>, _>>().unwrap()
+        }
+
+        // consistent
+        assert_eq!(scrub_all(raid5::scrub(&mut devices(1), &mut vec![vec![0u8; 10]; 4])), vec![]);
+        assert_eq!(scrub_all(raid6::scrub(&mut devices(2), &mut vec![vec![0u8; 10]; 5])), vec![]);
+        assert_eq!(scrub_all(raid7::scrub(&mut devices(3), &mut vec![vec![0u8; 10]; 6])), vec![]);
+        assert_eq!(scrub_all(raid8::scrub(&mut devices(4), &mut vec![vec![0u8; 10]; 7])), vec![]);
+
+        // single bad blocks, raid5 and raid8 can only detect them
+        let mut ds = devices(1);
+        ds[1].blocks[2][3] ^= 1;
+        ds[3].blocks[5][0] ^= 1;
+        assert_eq!(scrub_all(raid5::scrub(&mut ds, &mut vec![vec![0u8; 10]; 4])), vec![
+            Inconsistency { stripe: 2, bad_block: None },
+            Inconsistency { stripe: 5, bad_block: None },
+        ]);
+
+        let mut ds = devices(4);
+        ds[6].blocks[7][9] ^= 1;
+        assert_eq!(scrub_all(raid8::scrub(&mut ds, &mut vec![vec![0u8; 10]; 7])), vec![
+            Inconsistency { stripe: 7, bad_block: None },
+        ]);
+
+        // raid6 and raid7 can locate them
+        let mut ds = devices(2);
+        ds[0].blocks[1].fill(b'x');
+        ds[2].blocks[3][4] ^= 0x80;
+        ds[4].blocks[4][1] ^= 0x80;
+        ds[0].blocks[6][0] ^= 1;
+        ds[1].blocks[6][1] ^= 1;
+        assert_eq!(scrub_all(raid6::scrub(&mut ds, &mut vec![vec![0u8; 10]; 5])), vec![
+            Inconsistency { stripe: 1, bad_block: Some(0) },
+            Inconsistency { stripe: 3, bad_block: Some(2) },
+            Inconsistency { stripe: 4, bad_block: Some(4) },
+            // two bad blocks, can't tell which
+            Inconsistency { stripe: 6, bad_block: None },
+        ]);
+
+        let mut ds = devices(3);
+        ds[1].blocks[0][9] ^= 0x55;
+        ds[5].blocks[2][2] ^= 0x55;
+        assert_eq!(scrub_all(raid7::scrub(&mut ds, &mut vec![vec![0u8; 10]; 6])), vec![
+            Inconsistency { stripe: 0, bad_block: Some(1) },
+            Inconsistency { stripe: 2, bad_block: Some(5) },
+        ]);
+
+        // scrub incrementally
+        let mut ds = devices(2);
+        ds[2].blocks[1][0] ^= 1;
+        ds[1].blocks[5][0] ^= 1;
+        let mut buffers = vec![vec![0u8; 10]; 5];
+        let mut found = vec![];
+        let mut position = 0;
+        while position < 8 {
+            let mut scrub = raid6::scrub_range(&mut ds, &mut buffers, position..(position+3).min(8));
+            found.extend(scrub_all(&mut scrub));
+            assert_eq!(scrub.position(), (position+3).min(8));
+            position = scrub.position();
+        }
+        assert_eq!(found, vec![
+            Inconsistency { stripe: 1, bad_block: Some(2) },
+            Inconsistency { stripe: 5, bad_block: Some(1) },
+        ]);
+
+        // device errors don't stop scrubbing
+        let mut ds = devices(2);
+        ds[3].broken = true;
+        let mut scrub = raid6::scrub(&mut ds, &mut buffers);
+        assert_eq!(scrub.next(), Some(Err(RebuildError::Device(3, "broken"))));
+        assert_eq!(scrub.position(), 1);
+        assert_eq!(scrub.count(), 7);
+    }
+
+    #[test]
+    fn raid_update_at() {
+        let mut data = (0..5)
+            .map(|j| (0..40).map(|k| (j*40 + k) as u8).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut p5 = vec![0u8; 40];
+        let (mut p7, mut q7, mut r7) = (vec![0u8; 40], vec![0u8; 40], vec![0u8; 40]);
+        let mut p8 = vec![vec![0u8; 40]; 4];
+        raid5::format(&data, &mut p5);
+        raid7::format(&data, &mut p7, &mut q7, &mut r7);
+        raid8::format(&data, &mut p8);
+
+        for (j, offset, new) in [
+            (0, 0, &b"xxxx"[..]),
+            (1, 10, b"\x32\x33x\x35"),
+            (4, 39, b"?"),
+            (2, 20, b"\x64\x65"),
+            (3, 0, &[0x55; 40]),
+        ] {
+            let old = data[j][offset..offset+new.len()].to_vec();
+            let range = raid5::dirty_range(offset, &old, new);
+            assert!(range.start >= offset && range.end <= offset+new.len());
+            if !range.is_empty() {
+                // the range is trimmed to the changed symbols
+                assert_ne!(old[range.start-offset], new[range.start-offset]);
+                assert_ne!(old[range.end-1-offset], new[range.end-1-offset]);
+            }
+
+            // parity outside of the range must not change
+            let (p5_, p7_, q7_, r7_, p8_) = (p5.clone(), p7.clone(), q7.clone(), r7.clone(), p8.clone());
+            assert_eq!(raid5::update_at(j, offset, &old, new, &mut p5), range);
+            assert_eq!(raid7::update_at(j, offset, &old, new, &mut p7, &mut q7, &mut r7), range);
+            assert_eq!(raid8::update_at(j, offset, &old, new, &mut p8), range);
+            for (a, b) in [(&p5, &p5_), (&p7, &p7_), (&q7, &q7_), (&r7, &r7_)]
+                .into_iter()
+                .chain(p8.iter().zip(&p8_))
+            {
+                assert_eq!(a[..range.start], b[..range.start]);
+                assert_eq!(a[range.end..], b[range.end..]);
+            }
+
+            data[j][offset..offset+new.len()].copy_from_slice(new);
+            let mut f5 = vec![0u8; 40];
+            let (mut f7p, mut f7q, mut f7r) = (vec![0u8; 40], vec![0u8; 40], vec![0u8; 40]);
+            let mut f8 = vec![vec![0u8; 40]; 4];
+            raid5::format(&data, &mut f5);
+            raid7::format(&data, &mut f7p, &mut f7q, &mut f7r);
+            raid8::format(&data, &mut f8);
+            assert_eq!((&p5, &p7, &q7, &r7, &p8), (&f5, &f7p, &f7q, &f7r, &f8));
+        }
+
+        // nothing changed
+        assert_eq!(raid5::update_at(0, 7, &data[0][7..9], &data[0][7..9], &mut p5), 7..7);
+    }
+
+    #[test]
+    fn raid_verify() {
+        let data = (0..5)
+            .map(|j| (0..40).map(|k| (j*40 + k) as u8).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut p5 = vec![0u8; 40];
+        let (mut p7, mut q7, mut r7) = (vec![0u8; 40], vec![0u8; 40], vec![0u8; 40]);
+        let mut p8 = vec![vec![0u8; 40]; 4];
+        raid5::format(&data, &mut p5);
+        raid7::format(&data, &mut p7, &mut q7, &mut r7);
+        raid8::format(&data, &mut p8);
+        assert_eq!(raid5::verify(&data, &p5), Ok(()));
+        assert_eq!(raid7::verify(&data, &p7, &q7, &r7), Ok(()));
+        assert_eq!(raid8::verify(&data, &p8), Ok(()));
+
+        // bad data disagrees with every parity block
+        let mut bad = data.clone();
+        bad[2][5] ^= 1;
+        bad[2][9] ^= 2;
+        let m = raid5::verify(&bad, &p5).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![0]);
+        assert_eq!(m.range(0), Some(5..10));
+        assert_eq!(m.to_string(), "Parity mismatch in p at 5..10");
+        let m = raid7::verify(&bad, &p7, &q7, &r7).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(m.to_string(), "Parity mismatch in p at 5..10, q at 5..10, r at 5..10");
+        let m = raid8::verify(&bad, &p8).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert!((0..4).all(|i| m.range(i) == Some(5..10)));
+
+        // bad parity only disagrees with itself
+        let mut r7_ = r7.clone();
+        r7_[0] ^= 1;
+        r7_[39] ^= 1;
+        let m = raid7::verify(&data, &p7, &q7, &r7_).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![2]);
+        assert_eq!((m.range(0), m.range(1), m.range(2)), (None, None, Some(0..40)));
+
+        let mut p8_ = p8.clone();
+        p8_[1][20] ^= 0x80;
+        p8_[3][3] ^= 0x80;
+        let m = raid8::verify(&data, &p8_).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(m.range(1), Some(20..21));
+        assert_eq!(m.range(3), Some(3..4));
+        assert_eq!(m.to_string(), "Parity mismatch in parity 1 at 20..21, parity 3 at 3..4");
+
+        // wider fields
+        let data = (0..3)
+            .map(|j| (0..8).map(|k| 0x0123456789abcdef*(j*8 + k + 1) as u64).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut p10 = vec![vec![0u64; 8]; 6];
+        gf2p64_raid10::format(&data, &mut p10);
+        assert_eq!(gf2p64_raid10::verify(&data, &p10), Ok(()));
+        p10[5][7] ^= 1;
+        let m = gf2p64_raid10::verify(&data, &p10).unwrap_err();
+        assert_eq!(m.parity().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(m.range(5), Some(7..8));
+    }
 }
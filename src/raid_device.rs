@@ -0,0 +1,182 @@
+//! Block devices for rebuilding RAID arrays from real storage
+//!
+//! The RAID-parity functions operate on blocks in memory, but real arrays
+//! are usually much larger than memory. [`BlockDevice`] describes a device
+//! that can read and write fixed-size blocks, so the `rebuild` function
+//! generated by the [`raid`](super::raid) macro can stream each stripe
+//! through the parity math, one block from each device at a time.
+//!
+//! The generated `scrub` function walks the same devices, checking that each
+//! stripe's parity is consistent with its data, and reports each
+//! [`Inconsistency`] it finds.
+//!
+//! With feature `std`, `IoDevice` implements [`BlockDevice`] for anything
+//! that implements `Read`, `Write`, and `Seek`, such as a `File`.
+//!
+
+use core::fmt;
+
+
+/// A device that can read and write fixed-size blocks.
+///
+/// `U` is the unsigned type of each symbol in a block, which should match
+/// the `u` option of the [`raid`](super::raid) macro.
+///
+pub trait BlockDevice<U = u8> {
+    /// Error type returned by the device.
+    type Error;
+
+    /// Size of each block, in symbols.
+    fn block_size(&self) -> usize;
+
+    /// Number of blocks on the device.
+    fn block_count(&self) -> u64;
+
+    /// Read block `block` into `buf`, which is exactly
+    /// [`block_size`](Self::block_size) symbols.
+    fn read_block(&mut self, block: u64, buf: &mut [U]) -> Result<(), Self::Error>;
+
+    /// Write `buf`, which is exactly [`block_size`](Self::block_size)
+    /// symbols, to block `block`.
+    fn write_block(&mut self, block: u64, buf: &[U]) -> Result<(), Self::Error>;
+}
+
+impl<U, D: BlockDevice<U> + ?Sized> BlockDevice<U> for &mut D {
+    type Error = D::Error;
+
+    fn block_size(&self) -> usize {
+        (**self).block_size()
+    }
+
+    fn block_count(&self) -> u64 {
+        (**self).block_count()
+    }
+
+    fn read_block(&mut self, block: u64, buf: &mut [U]) -> Result<(), Self::Error> {
+        (**self).read_block(block, buf)
+    }
+
+    fn write_block(&mut self, block: u64, buf: &[U]) -> Result<(), Self::Error> {
+        (**self).write_block(block, buf)
+    }
+}
+
+
+/// Error codes for rebuilding RAID arrays
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RebuildError<E> {
+    /// RAID-parity can fail to rebuild if there are more failed devices
+    /// than there are parity devices
+    TooManyBadBlocks,
+
+    /// A device returned an error, the index of the device is included
+    Device(usize, E),
+}
+
+impl<E: fmt::Display> fmt::Display for RebuildError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RebuildError::TooManyBadBlocks => write!(f, "Too many bad-blocks to repair"),
+            RebuildError::Device(i, e) => write!(f, "Device {} failed: {}", i, e),
+        }
+    }
+}
+
+
+/// An inconsistent stripe found while scrubbing a RAID array
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Inconsistency {
+    /// Index of the inconsistent stripe, this is the block index on each
+    /// device.
+    pub stripe: u64,
+
+    /// Index of the device suspected to hold the bad block, if it can be
+    /// determined. This requires 2 or 3 parity blocks, and only a single
+    /// bad block in the stripe.
+    pub bad_block: Option<usize>,
+}
+
+
+/// A [`BlockDevice`] over anything that can read, write, and seek.
+///
+/// ``` rust
+/// use gf256::raid::{BlockDevice, IoDevice};
+/// use std::io::Cursor;
+///
+/// let mut device = IoDevice::new(Cursor::new(vec![0u8; 4096]), 512)?;
+/// assert_eq!(device.block_count(), 8);
+///
+/// device.write_block(3, &[0x55; 512])?;
+/// let mut buf = [0u8; 512];
+/// device.read_block(3, &mut buf)?;
+/// assert_eq!(buf, [0x55; 512]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// Note this requires feature `std`.
+///
+#[cfg(feature="std")]
+#[derive(Debug)]
+pub struct IoDevice<F> {
+    inner: F,
+    block_size: usize,
+    block_count: u64,
+}
+
+#[cfg(feature="std")]
+impl<F: std::io::Read + std::io::Write + std::io::Seek> IoDevice<F> {
+    /// Create a new block device with the given block size, in bytes.
+    ///
+    /// The number of blocks is found by seeking to the end of `inner`, any
+    /// partial block at the end is unused.
+    ///
+    pub fn new(mut inner: F, block_size: usize) -> std::io::Result<Self> {
+        assert!(block_size > 0);
+        let size = inner.seek(std::io::SeekFrom::End(0))?;
+        Ok(Self {
+            inner,
+            block_size,
+            block_count: size / block_size as u64,
+        })
+    }
+
+    /// Get a reference to the underlying device.
+    pub fn get_ref(&self) -> &F {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying device.
+    pub fn get_mut(&mut self) -> &mut F {
+        &mut self.inner
+    }
+
+    /// Unwrap the underlying device.
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+#[cfg(feature="std")]
+impl<F: std::io::Read + std::io::Write + std::io::Seek> BlockDevice for IoDevice<F> {
+    type Error = std::io::Error;
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.block_count
+    }
+
+    fn read_block(&mut self, block: u64, buf: &mut [u8]) -> std::io::Result<()> {
+        assert!(buf.len() == self.block_size);
+        self.inner.seek(std::io::SeekFrom::Start(block*self.block_size as u64))?;
+        self.inner.read_exact(buf)
+    }
+
+    fn write_block(&mut self, block: u64, buf: &[u8]) -> std::io::Result<()> {
+        assert!(buf.len() == self.block_size);
+        self.inner.seek(std::io::SeekFrom::Start(block*self.block_size as u64))?;
+        self.inner.write_all(buf)
+    }
+}
@@ -0,0 +1,254 @@
+//! Stripe layouts with rotating parity
+//!
+//! The RAID-parity functions only do the math for a single stripe, one
+//! block from each disk. A real array also needs to decide where each
+//! logical address lives, and which disks hold parity in each stripe.
+//!
+//! If parity always lives on the same disks, like RAID 4, every write needs
+//! to update the parity disks, and they become a bottleneck. So RAID 5 and
+//! RAID 6 rotate parity across the disks, one stripe at a time. [`Layout`]
+//! implements the common rotations, using the same names as Linux's md
+//! driver:
+//!
+//! ``` text
+//! left-symmetric, 4 disks, 1 parity:
+//!
+//!          disk0 disk1 disk2 disk3
+//! stripe0    d0    d1    d2    p
+//! stripe1    d4    d5    p     d3
+//! stripe2    d8    p     d6    d7
+//! stripe3    p     d9    d10   d11
+//! ```
+//!
+//! With more than one parity block, parity blocks occupy consecutive disks,
+//! so with 2 parity blocks, q follows p like in md's RAID 6 layouts.
+//!
+//! ``` rust
+//! use gf256::raid::layout::{Layout, Rotation, Location};
+//!
+//! // 4 disks, 1 parity, 4 KiB chunks
+//! let layout = Layout::new(4, 1, 4096, Rotation::LeftSymmetric)?;
+//! assert_eq!(layout.stripe_size(), 3*4096);
+//!
+//! // find where logical address 0x4000 lives
+//! assert_eq!(layout.map(0x4000), Location {
+//!     stripe: 1,
+//!     disk: 0,
+//!     offset: 0x1000,
+//! });
+//!
+//! // and where stripe 1's parity lives
+//! assert_eq!(layout.parity_disk(1, 0), 2);
+//! # Ok::<(), gf256::raid::Error>(())
+//! ```
+//!
+//! Each disk in a stripe also has an index into the blocks passed to the
+//! RAID-parity functions, data blocks first and then parity blocks, which
+//! [`Layout::block`] and [`Layout::disk`] convert between. So a failed
+//! disk can be mapped directly to the `bad_blocks` passed to `repair`.
+//!
+
+use super::Error;
+
+
+/// How parity rotates across disks
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Rotation {
+    /// No rotation, parity always lives on the last disks, like RAID 4.
+    None,
+
+    /// Parity starts on the last disk and moves left, data fills the
+    /// remaining disks in order.
+    LeftAsymmetric,
+
+    /// Parity starts on the last disk and moves left, data starts after
+    /// the parity and wraps around. This is the default in Linux's md.
+    LeftSymmetric,
+
+    /// Parity starts on the first disk and moves right, data fills the
+    /// remaining disks in order.
+    RightAsymmetric,
+
+    /// Parity starts on the first disk and moves right, data starts after
+    /// the parity and wraps around.
+    RightSymmetric,
+}
+
+/// Where a logical address lives in an array
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Location {
+    /// Stripe containing the address.
+    pub stripe: u64,
+    /// Disk containing the address.
+    pub disk: usize,
+    /// Byte offset on the disk.
+    pub offset: u64,
+}
+
+/// A stripe layout, mapping logical addresses to disks with rotating
+/// parity.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Layout {
+    disks: usize,
+    parity: usize,
+    chunk_size: usize,
+    rotation: Rotation,
+}
+
+impl Layout {
+    /// Create a new stripe layout.
+    ///
+    /// - `disks` - The total number of disks, data and parity.
+    /// - `parity` - The number of parity blocks in each stripe.
+    /// - `chunk_size` - The size of each block in a stripe, in bytes.
+    /// - `rotation` - How parity rotates across disks.
+    ///
+    /// Returns [`Error::InvalidParameters`] if there are no data disks, or
+    /// `chunk_size` is zero.
+    ///
+    pub fn new(
+        disks: usize,
+        parity: usize,
+        chunk_size: usize,
+        rotation: Rotation,
+    ) -> Result<Self, Error> {
+        if disks <= parity || chunk_size == 0 {
+            return Err(Error::InvalidParameters);
+        }
+
+        Ok(Self {
+            disks,
+            parity,
+            chunk_size,
+            rotation,
+        })
+    }
+
+    /// Total number of disks, data and parity.
+    pub fn disks(&self) -> usize {
+        self.disks
+    }
+
+    /// Number of parity blocks in each stripe.
+    pub fn parity(&self) -> usize {
+        self.parity
+    }
+
+    /// Number of data blocks in each stripe.
+    pub fn data_disks(&self) -> usize {
+        self.disks - self.parity
+    }
+
+    /// Size of each block in a stripe, in bytes.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// How parity rotates across disks.
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Size of the data in each stripe, in bytes.
+    pub fn stripe_size(&self) -> u64 {
+        (self.data_disks() * self.chunk_size) as u64
+    }
+
+    /// Usable capacity of the array, given the size of each disk, in bytes.
+    ///
+    /// Any partial chunk at the end of the disks is unused.
+    ///
+    pub fn capacity(&self, disk_size: u64) -> u64 {
+        (disk_size / self.chunk_size as u64) * self.stripe_size()
+    }
+
+    /// Find the disk holding the first parity block in a stripe
+    fn parity_start(&self, stripe: u64) -> usize {
+        let n = self.disks as u64;
+        match self.rotation {
+            Rotation::None => self.data_disks(),
+            Rotation::LeftAsymmetric | Rotation::LeftSymmetric => {
+                (n-1 - stripe % n) as usize
+            }
+            Rotation::RightAsymmetric | Rotation::RightSymmetric => {
+                (stripe % n) as usize
+            }
+        }
+    }
+
+    /// Disk holding parity block `i` in a stripe.
+    pub fn parity_disk(&self, stripe: u64, i: usize) -> usize {
+        assert!(i < self.parity);
+        (self.parity_start(stripe) + i) % self.disks
+    }
+
+    /// Disk holding data block `j` in a stripe.
+    pub fn data_disk(&self, stripe: u64, j: usize) -> usize {
+        assert!(j < self.data_disks());
+        let start = self.parity_start(stripe);
+        match self.rotation {
+            Rotation::LeftSymmetric | Rotation::RightSymmetric => {
+                (start + self.parity + j) % self.disks
+            }
+            Rotation::None
+                | Rotation::LeftAsymmetric
+                | Rotation::RightAsymmetric => {
+                // fill the disks in order, skipping parity, parity may
+                // wrap around to the first disks
+                let wrapped = (start + self.parity).saturating_sub(self.disks);
+                let j = j + wrapped;
+                if j < start { j } else { j + self.parity - wrapped }
+            }
+        }
+    }
+
+    /// Disk holding block `j` in a stripe, where data blocks come first,
+    /// followed by parity blocks, the same as the blocks passed to the
+    /// RAID-parity functions.
+    pub fn disk(&self, stripe: u64, j: usize) -> usize {
+        if j < self.data_disks() {
+            self.data_disk(stripe, j)
+        } else {
+            self.parity_disk(stripe, j - self.data_disks())
+        }
+    }
+
+    /// Index of the block a disk holds in a stripe, where data blocks come
+    /// first, followed by parity blocks, the same as the blocks passed to
+    /// the RAID-parity functions.
+    pub fn block(&self, stripe: u64, disk: usize) -> usize {
+        assert!(disk < self.disks);
+        // rotation is cheap to compute, so just search
+        (0..self.disks)
+            .find(|j| self.disk(stripe, *j) == disk)
+            .unwrap()
+    }
+
+    /// Find where a logical address lives.
+    pub fn map(&self, addr: u64) -> Location {
+        let chunk_size = self.chunk_size as u64;
+        let chunk = addr / chunk_size;
+        let stripe = chunk / self.data_disks() as u64;
+        let j = (chunk % self.data_disks() as u64) as usize;
+        Location {
+            stripe,
+            disk: self.data_disk(stripe, j),
+            offset: stripe*chunk_size + addr % chunk_size,
+        }
+    }
+
+    /// Find the logical address stored at a location, or `None` if the
+    /// location holds parity.
+    pub fn unmap(&self, location: Location) -> Option<u64> {
+        let chunk_size = self.chunk_size as u64;
+        let j = self.block(location.stripe, location.disk);
+        if j >= self.data_disks() {
+            return None;
+        }
+
+        Some(
+            (location.stripe*self.data_disks() as u64 + j as u64) * chunk_size
+                + location.offset % chunk_size
+        )
+    }
+}
@@ -0,0 +1,367 @@
+//! Local reconstruction codes
+//!
+//! With RAID-parity, repairing even a single bad block requires reading
+//! every other block in the stripe. In large object stores, where a stripe
+//! may be spread across dozens of machines, this makes the common case,
+//! a single failure, expensive.
+//!
+//! Local reconstruction codes (LRC), as used in Azure's storage, split the
+//! data blocks into small groups, each with its own local parity block,
+//! which is just the xor of the group's data blocks. A single bad block can
+//! be repaired by reading only its group. Global parity blocks, computed
+//! over all of the data blocks, provide the redundancy for rarer multiple
+//! failures.
+//!
+//! ``` text
+//! 6 data blocks, 2 groups, 2 global parity blocks:
+//!
+//!   group0     group1
+//! d0 d1 d2   d3 d4 d5
+//!    l0         l1        g0 g1
+//! ```
+//!
+//! [`Lrc`] uses the q and r parity of [`raid7`](super::raid7) as its
+//! global parity blocks, `g0 = Σ di*g^i`, `g1 = Σ di*g^2i`, etc. The xor
+//! parity is left out since it's already the sum of the local parity
+//! blocks. So any `global` bad blocks can always be repaired, and up to one
+//! more in each group if its local parity block is intact.
+//!
+
+use super::runtime::{slice_mul_add, invert};
+use super::Error;
+use crate::gf::gf256;
+use core::ops::Range;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// A local reconstruction code over `GF(256)`, with data blocks split into
+/// groups with local parity, plus global parity blocks.
+///
+/// Blocks are indexed with the data blocks first, followed by the local
+/// parity blocks, and then the global parity blocks.
+///
+/// Note this requires feature `alloc`.
+///
+/// ``` rust
+/// use gf256::raid::Lrc;
+///
+/// // 6 data blocks, 2 groups, 2 global parity blocks
+/// let lrc = Lrc::new(6, 2, 2)?;
+///
+/// // format
+/// let mut buf = b"Hello World!".to_vec();
+/// let mut local = vec![vec![0u8; 2]; lrc.groups()];
+/// let mut global = vec![vec![0u8; 2]; lrc.global()];
+/// let slices = buf.chunks(2).collect::<Vec<_>>();
+/// lrc.format(&slices, &mut local, &mut global);
+///
+/// // a single bad block only needs its group to repair
+/// buf[8..10].fill(b'x');
+/// let group = lrc.group(lrc.group_of(4));
+/// let mut slices = buf[2*group.start..2*group.end].chunks_mut(2).collect::<Vec<_>>();
+/// lrc.repair_group(&mut slices, &mut local[1], 4-group.start);
+/// assert_eq!(&buf, b"Hello World!");
+///
+/// // more bad blocks need global parity
+/// buf[0..4].fill(b'x');
+/// buf[10..12].fill(b'x');
+/// let mut slices = buf.chunks_mut(2).collect::<Vec<_>>();
+/// lrc.repair(&mut slices, &mut local, &mut global, &[0, 1, 5])?;
+/// assert_eq!(&buf, b"Hello World!");
+/// # Ok::<(), gf256::raid::Error>(())
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Lrc {
+    blocks: usize,
+    groups: usize,
+    global: usize,
+}
+
+impl Lrc {
+    /// Create a new local reconstruction code.
+    ///
+    /// - `blocks` - The number of data blocks.
+    /// - `groups` - The number of groups, each with one local parity block.
+    ///   Data blocks are split as evenly as possible between groups.
+    /// - `global` - The number of global parity blocks.
+    ///
+    /// Returns [`Error::InvalidParameters`] if there are no groups, more
+    /// groups than data blocks, or more than 255 data blocks.
+    ///
+    pub fn new(blocks: usize, groups: usize, global: usize) -> Result<Self, Error> {
+        if groups == 0 || groups > blocks || blocks > 255 {
+            return Err(Error::InvalidParameters);
+        }
+
+        Ok(Self {
+            blocks,
+            groups,
+            global,
+        })
+    }
+
+    /// Number of data blocks.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Number of groups, and local parity blocks.
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+
+    /// Number of global parity blocks.
+    pub fn global(&self) -> usize {
+        self.global
+    }
+
+    /// Range of data blocks in group `i`.
+    pub fn group(&self, i: usize) -> Range<usize> {
+        assert!(i < self.groups);
+        // the first blocks % groups groups get an extra block
+        let size = self.blocks / self.groups;
+        let extra = self.blocks % self.groups;
+        let start = i*size + i.min(extra);
+        start .. start + size + usize::from(i < extra)
+    }
+
+    /// Group containing data block `j`.
+    pub fn group_of(&self, j: usize) -> usize {
+        assert!(j < self.blocks);
+        let size = self.blocks / self.groups;
+        let extra = self.blocks % self.groups;
+        if j < extra*(size+1) {
+            j / (size+1)
+        } else {
+            extra + (j - extra*(size+1)) / size
+        }
+    }
+
+    /// Coefficient of data block `j` in global parity block `i`
+    fn coeff(&self, i: usize, j: usize) -> gf256 {
+        // gi = Σ dj*g^(j*(i+1))
+        gf256::GENERATOR.pow(j as u8).pow((i+1) as u8)
+    }
+
+    /// Coefficient of data block `j` in parity block `i`, where local
+    /// parity blocks come first
+    fn parity_coeff(&self, i: usize, j: usize) -> gf256 {
+        if i < self.groups {
+            gf256(u8::from(self.group_of(j) == i))
+        } else {
+            self.coeff(i-self.groups, j)
+        }
+    }
+
+    /// Format blocks with local and global parity.
+    ///
+    /// This writes the parity data to the provided parity blocks based on
+    /// the provided data blocks.
+    ///
+    pub fn format<B: AsRef<[u8]>, P: AsMut<[u8]>, Q: AsMut<[u8]>>(
+        &self,
+        blocks: &[B],
+        local: &mut [P],
+        global: &mut [Q],
+    ) {
+        assert!(blocks.len() == self.blocks);
+        assert!(local.len() == self.groups);
+        assert!(global.len() == self.global);
+
+        for (i, l) in local.iter_mut().enumerate() {
+            let l = l.as_mut();
+            l.fill(0);
+            for b in &blocks[self.group(i)] {
+                slice_mul_add(l, b.as_ref(), gf256(1));
+            }
+        }
+
+        for (i, g) in global.iter_mut().enumerate() {
+            let g = g.as_mut();
+            g.fill(0);
+            for (j, b) in blocks.iter().enumerate() {
+                slice_mul_add(g, b.as_ref(), self.coeff(i, j));
+            }
+        }
+    }
+
+    /// Repair a single bad block using only its group.
+    ///
+    /// `blocks` must contain only the data blocks in the group, see
+    /// [`group`](Self::group), and `local` the group's local parity block.
+    /// `bad_block` is the index of the bad block in the group, or
+    /// `blocks.len()` if the local parity block is bad.
+    ///
+    pub fn repair_group<B: AsMut<[u8]>>(
+        &self,
+        blocks: &mut [B],
+        local: &mut [u8],
+        bad_block: usize,
+    ) {
+        assert!(bad_block <= blocks.len());
+
+        // the bad block is the xor of the rest of the group
+        if bad_block == blocks.len() {
+            local.fill(0);
+            for b in blocks.iter_mut() {
+                slice_mul_add(local, b.as_mut(), gf256(1));
+            }
+        } else {
+            let mut x = local.to_vec();
+            for (j, b) in blocks.iter_mut().enumerate() {
+                if j != bad_block {
+                    slice_mul_add(&mut x, b.as_mut(), gf256(1));
+                }
+            }
+            blocks[bad_block].as_mut().copy_from_slice(&x);
+        }
+    }
+
+    /// Repair bad blocks, using local parity where possible and global
+    /// parity for the rest.
+    ///
+    /// `bad_blocks` must be an array of indices indicating which blocks are
+    /// bad, with data blocks first, then local parity blocks, and then
+    /// global parity blocks. Returns [`Error::TooManyBadBlocks`] if the bad
+    /// blocks can't be repaired, in which case the blocks are left
+    /// unmodified.
+    ///
+    pub fn repair<B: AsMut<[u8]>, P: AsMut<[u8]>, Q: AsMut<[u8]>>(
+        &self,
+        blocks: &mut [B],
+        local: &mut [P],
+        global: &mut [Q],
+        bad_blocks: &[usize],
+    ) -> Result<(), Error> {
+        assert!(blocks.len() == self.blocks);
+        assert!(local.len() == self.groups);
+        assert!(global.len() == self.global);
+        assert!(bad_blocks.iter().all(|j| *j < self.blocks+self.groups+self.global));
+
+        let mut bad_blocks = bad_blocks.to_vec();
+        bad_blocks.sort_unstable();
+        bad_blocks.dedup();
+        let bad_datas = &bad_blocks[..bad_blocks.partition_point(|j| *j < self.blocks)];
+        let bad_parity = bad_blocks[bad_datas.len()..].iter()
+            .map(|j| j - self.blocks)
+            .collect::<Vec<_>>();
+
+        if !bad_datas.is_empty() {
+            // pick linearly independent parity blocks to solve for the bad
+            // data blocks, preferring local parity since it involves fewer
+            // blocks, we keep a reduced basis to check independence
+            let mut rows = Vec::new();
+            let mut basis: Vec<(usize, Vec<gf256>)> = Vec::new();
+            for i in 0..self.groups+self.global {
+                if rows.len() == bad_datas.len() {
+                    break;
+                }
+                if bad_parity.contains(&i) {
+                    continue;
+                }
+
+                let mut v = bad_datas.iter()
+                    .map(|&j| self.parity_coeff(i, j))
+                    .collect::<Vec<_>>();
+                for (pivot, b) in &basis {
+                    let f = v[*pivot];
+                    for (x, y) in v.iter_mut().zip(b) {
+                        *x -= f * *y;
+                    }
+                }
+
+                if let Some(pivot) = v.iter().position(|x| *x != gf256(0)) {
+                    let s = v[pivot].recip();
+                    for x in v.iter_mut() {
+                        *x *= s;
+                    }
+                    basis.push((pivot, v));
+                    rows.push(i);
+                }
+            }
+
+            if rows.len() < bad_datas.len() {
+                // can't repair
+                return Err(Error::TooManyBadBlocks);
+            }
+
+            // find parity - Σ di*cij for each good data block
+            let mut syndromes = rows.iter()
+                .map(|&i| {
+                    if i < self.groups {
+                        local[i].as_mut().to_vec()
+                    } else {
+                        global[i-self.groups].as_mut().to_vec()
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (j, b) in blocks.iter_mut().enumerate() {
+                if bad_datas.contains(&j) {
+                    continue;
+                }
+
+                for (s, &i) in syndromes.iter_mut().zip(&rows) {
+                    slice_mul_add(s, b.as_mut(), self.parity_coeff(i, j));
+                }
+            }
+
+            // solve for the bad data blocks
+            let m = rows.iter()
+                .map(|&i| bad_datas.iter().map(|&j| self.parity_coeff(i, j)).collect())
+                .collect();
+            let m_inv = invert(m).ok_or(Error::TooManyBadBlocks)?;
+            for (&j, m_inv) in bad_datas.iter().zip(&m_inv) {
+                let d = blocks[j].as_mut();
+                d.fill(0);
+                for (s, c) in syndromes.iter().zip(m_inv) {
+                    slice_mul_add(d, s, *c);
+                }
+            }
+        }
+
+        // regenerate bad parity blocks
+        for &i in &bad_parity {
+            let p = if i < self.groups {
+                local[i].as_mut()
+            } else {
+                global[i-self.groups].as_mut()
+            };
+            p.fill(0);
+            for (j, b) in blocks.iter_mut().enumerate() {
+                slice_mul_add(p, b.as_mut(), self.parity_coeff(i, j));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update a data block.
+    ///
+    /// This applies only the difference between the old and new block to
+    /// the block's local parity block and each global parity block.
+    ///
+    pub fn update<P: AsMut<[u8]>, Q: AsMut<[u8]>>(
+        &self,
+        j: usize,
+        old: &[u8],
+        new: &[u8],
+        local: &mut [P],
+        global: &mut [Q],
+    ) {
+        assert!(j < self.blocks);
+        assert!(local.len() == self.groups);
+        assert!(global.len() == self.global);
+
+        let l = local[self.group_of(j)].as_mut();
+        slice_mul_add(l, old, gf256(1));
+        slice_mul_add(l, new, gf256(1));
+        for (i, g) in global.iter_mut().enumerate() {
+            let g = g.as_mut();
+            slice_mul_add(g, old, self.coeff(i, j));
+            slice_mul_add(g, new, self.coeff(i, j));
+        }
+    }
+}
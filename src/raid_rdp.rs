@@ -0,0 +1,388 @@
+//! XOR-only double parity, using row-diagonal parity (RDP)
+//!
+//! [`raid6`](super::raid6) needs a multiplication in `GF(256)` for every byte
+//! of q. This is cheap with the right instructions, but on weak CPUs
+//! without carry-less multiplication or byte shuffles it can dominate the
+//! cost of writing to an array.
+//!
+//! Row-diagonal parity, described by Corbett et al. in
+//! [Row-Diagonal Parity for Double Disk Failure Correction][rdp-paper],
+//! instead gets two independent parity blocks from xor alone. Each block
+//! is split into `prime-1` rows, where `prime` is a prime greater than the
+//! number of data blocks. p is the xor of each row, same as RAID 5, and q is
+//! the xor of each diagonal, including p:
+//!
+//! ``` text
+//! 4 data blocks, prime=5, diagonal of each row:
+//!
+//!        d0 d1 d2 d3 p    q
+//! row0   0  1  2  3  4    0
+//! row1   1  2  3  4  0    1
+//! row2   2  3  4  0  1    2
+//! row3   3  4  0  1  2    3
+//! ```
+//!
+//! Diagonal `prime-1` (4 above) is never stored, and every block misses
+//! exactly one diagonal. So after losing any two blocks, there is always a
+//! diagonal with only one unknown symbol, which lets us recover a symbol of
+//! one block, which completes a row, which completes another diagonal, and
+//! so on until both blocks are repaired.
+//!
+//! The trade-off is layout, block lengths must be a multiple of
+//! [`rows`], which depends on the number of data blocks. Fewer data blocks
+//! than `prime-1` are treated as if the missing blocks were all zeros.
+//!
+//! ``` rust
+//! use gf256::raid::rdp;
+//!
+//! // 3 data blocks need 4 rows
+//! assert_eq!(rdp::rows(3), 4);
+//!
+//! // format
+//! let mut buf = b"Hello World!".to_vec();
+//! let mut p = vec![0u8; 4];
+//! let mut q = vec![0u8; 4];
+//! let slices = buf.chunks(4).collect::<Vec<_>>();
+//! rdp::format(&slices, &mut p, &mut q);
+//!
+//! // corrupt
+//! buf[0..8].fill(b'x');
+//!
+//! // repair
+//! let mut slices = buf.chunks_mut(4).collect::<Vec<_>>();
+//! rdp::repair(&mut slices, &mut p, &mut q, &[0, 1])?;
+//! assert_eq!(&buf, b"Hello World!");
+//! # Ok::<(), gf256::raid::Error>(())
+//! ```
+//!
+//! Note RDP's parity is not compatible with [`raid6`](super::raid6).
+//!
+//! [rdp-paper]: https://www.usenix.org/legacy/events/fast04/tech/corbett/corbett.pdf
+//!
+
+use super::Error;
+use core::ops::Range;
+
+
+/// Add a slice to another slice, f ^= g
+fn slice_xor(f: &mut [u8], g: &[u8]) {
+    for (x, y) in f.iter_mut().zip(g) {
+        *x ^= *y;
+    }
+}
+
+/// Find the smallest prime greater than the number of data blocks
+fn prime(blocks: usize) -> usize {
+    (blocks+1..)
+        .find(|n| *n >= 2 && (2..).take_while(|d| d*d <= *n).all(|d| n % d != 0))
+        .unwrap()
+}
+
+/// Number of rows each block is split into.
+///
+/// This is `prime-1`, where `prime` is the smallest prime greater than the
+/// number of data blocks. The length of every block must be a multiple of
+/// this.
+///
+pub fn rows(blocks: usize) -> usize {
+    prime(blocks) - 1
+}
+
+/// Blocks in a stripe viewed as an RDP array of `prime` columns, data blocks
+/// followed by zero padding, followed by p
+struct Stripe<'a, B> {
+    prime: usize,
+    size: usize,
+    blocks: &'a mut [B],
+    p: &'a mut [u8],
+}
+
+impl<'a, B: AsMut<[u8]>> Stripe<'a, B> {
+    fn new(blocks: &'a mut [B], p: &'a mut [u8]) -> Self {
+        let prime = prime(blocks.len());
+        assert!(p.len().is_multiple_of(prime-1));
+        assert!(blocks.iter_mut().all(|b| b.as_mut().len() == p.len()));
+        let size = p.len() / (prime-1);
+
+        Self {
+            prime,
+            size,
+            blocks,
+            p,
+        }
+    }
+
+    /// Column i, or None if it's zero padding
+    fn column(&mut self, i: usize) -> Option<&mut [u8]> {
+        if i == self.prime-1 {
+            Some(self.p)
+        } else {
+            self.blocks.get_mut(i).map(|b| b.as_mut())
+        }
+    }
+
+    /// Columns i and j, where i is not padding and i != j
+    fn columns(&mut self, i: usize, j: usize) -> (&mut [u8], Option<&mut [u8]>) {
+        assert!(i != j);
+        let n = self.blocks.len();
+        if i == self.prime-1 {
+            (self.p, self.blocks.get_mut(j).map(|b| b.as_mut()))
+        } else if j == self.prime-1 {
+            (self.blocks[i].as_mut(), Some(self.p))
+        } else if j >= n {
+            (self.blocks[i].as_mut(), None)
+        } else if i < j {
+            let (lo, hi) = self.blocks.split_at_mut(j);
+            (lo[i].as_mut(), Some(hi[0].as_mut()))
+        } else {
+            let (lo, hi) = self.blocks.split_at_mut(i);
+            (hi[0].as_mut(), Some(lo[j].as_mut()))
+        }
+    }
+
+    /// Symbols in row r
+    fn row(&self, r: usize) -> Range<usize> {
+        r*self.size..(r+1)*self.size
+    }
+
+    /// Row containing column i's symbol on diagonal d, if any
+    fn diagonal_row(&self, i: usize, d: usize) -> Option<usize> {
+        let r = (d + self.prime - i) % self.prime;
+        if r < self.prime-1 { Some(r) } else { None }
+    }
+
+    /// Solve for column x's symbol in row r from its row
+    fn solve_row(&mut self, x: usize, r: usize) {
+        let row = self.row(r);
+        self.column(x).unwrap()[row.clone()].fill(0);
+        for i in (0..self.prime).filter(|i| *i != x) {
+            if let (c, Some(ci)) = self.columns(x, i) {
+                slice_xor(&mut c[row.clone()], &ci[row.clone()]);
+            }
+        }
+    }
+
+    /// Solve for column x's symbol on diagonal d from its diagonal
+    fn solve_diagonal(&mut self, x: usize, d: usize, q: &[u8]) {
+        let row = self.row(self.diagonal_row(x, d).unwrap());
+        let rowd = self.row(d);
+        self.column(x).unwrap()[row.clone()].copy_from_slice(&q[rowd]);
+        for i in (0..self.prime).filter(|i| *i != x) {
+            if let Some(ri) = self.diagonal_row(i, d) {
+                let rowi = self.row(ri);
+                if let (c, Some(ci)) = self.columns(x, i) {
+                    slice_xor(&mut c[row.clone()], &ci[rowi]);
+                }
+            }
+        }
+    }
+}
+
+
+/// Format blocks with RDP double parity.
+///
+/// This writes the parity data to p and q based on the provided data
+/// blocks. The length of every block must be a multiple of [`rows`].
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// let mut data = b"Hello World!".to_vec();
+/// let datas = data.chunks(4).collect::<Vec<_>>();
+/// let mut p = vec![0u8; 4];
+/// let mut q = vec![0u8; 4];
+/// rdp::format(&datas, &mut p, &mut q);
+///
+/// assert_eq!(&p, b"\x55\x29\x5f\x22");
+/// assert_eq!(&q, b"\x40\x55\x1c\x57");
+/// ```
+///
+pub fn format<B: AsRef<[u8]>>(
+    blocks: &[B],
+    p: &mut [u8],
+    q: &mut [u8],
+) {
+    let prime = prime(blocks.len());
+    let len = p.len();
+    assert!(len.is_multiple_of(prime-1));
+    assert!(q.len() == len);
+    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+    let size = len / (prime-1);
+
+    // p is the xor of each row
+    p.fill(0);
+    for b in blocks {
+        slice_xor(p, b.as_ref());
+    }
+
+    // q is the xor of each diagonal, including p, diagonal prime-1 is
+    // never stored
+    q.fill(0);
+    let columns = blocks.iter().map(|b| b.as_ref())
+        .enumerate()
+        .chain([(prime-1, &*p)]);
+    for (i, c) in columns {
+        for r in 0..prime-1 {
+            let d = (r + i) % prime;
+            if d < prime-1 {
+                slice_xor(
+                    &mut q[d*size..(d+1)*size],
+                    &c[r*size..(r+1)*size]
+                );
+            }
+        }
+    }
+}
+
+/// Repair up to two bad blocks.
+///
+/// This can include the parity blocks themselves, p is indexed after the
+/// data blocks, and q after p. `bad_blocks` must be an array of indices
+/// indicating which blocks are bad.
+///
+/// Returns [`Error::TooManyBadBlocks`] if there are more than two bad
+/// blocks, in which case the blocks are left unmodified.
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// let mut data = b"xxxxo Wo????".to_vec();
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// let mut p = b"\x55\x29\x5f\x22".to_vec();
+/// let mut q = b"\x40\x55\x1c\x57".to_vec();
+///
+/// // repair
+/// rdp::repair(&mut datas, &mut p, &mut q, &[0, 2])?;
+/// assert_eq!(&data, b"Hello World!");
+/// # Ok::<(), gf256::raid::Error>(())
+/// ```
+///
+pub fn repair<B: AsMut<[u8]>>(
+    blocks: &mut [B],
+    p: &mut [u8],
+    q: &mut [u8],
+    bad_blocks: &[usize],
+) -> Result<(), Error> {
+    let n = blocks.len();
+    assert!(bad_blocks.iter().all(|j| *j < n+2));
+    assert!(q.len() == p.len());
+
+    let mut bad = [usize::MAX; 2];
+    let mut count = 0;
+    for &j in bad_blocks {
+        if !bad[..count].contains(&j) {
+            if count == 2 {
+                // can't repair
+                return Err(Error::TooManyBadBlocks);
+            }
+            bad[count] = j;
+            count += 1;
+        }
+    }
+    let bad = &bad[..count];
+
+    let mut stripe = Stripe::new(blocks, p);
+    let prime = stripe.prime;
+
+    // map p to its column
+    let mut bad_columns = bad.iter()
+        .filter(|j| **j != n+1)
+        .map(|j| if *j == n { prime-1 } else { *j });
+
+    match (bad_columns.next(), bad_columns.next()) {
+        (None, _) => {}
+        (Some(x), None) => {
+            // one bad column, repair each row
+            for r in 0..prime-1 {
+                stripe.solve_row(x, r);
+            }
+        }
+        (Some(x), Some(y)) => {
+            // two bad columns, each column misses one diagonal, so we can
+            // start with the diagonal missing one column to solve for the
+            // other, which completes a row, which completes another
+            // diagonal, and so on until we reach the missing diagonal
+            // prime-1. Starting from both columns covers every symbol
+            for (a, b) in [(x, y), (y, x)] {
+                let mut d = (b + prime-1) % prime;
+                while d != prime-1 {
+                    let Some(r) = stripe.diagonal_row(a, d) else {
+                        break;
+                    };
+                    stripe.solve_diagonal(a, d, q);
+                    stripe.solve_row(b, r);
+                    d = (r + b) % prime;
+                }
+            }
+        }
+    }
+
+    if bad.contains(&(n+1)) {
+        // regenerate q
+        q.fill(0);
+        for i in 0..prime {
+            for r in 0..prime-1 {
+                let d = (r + i) % prime;
+                let (rowd, row) = (stripe.row(d), stripe.row(r));
+                if let (true, Some(c)) = (d < prime-1, stripe.column(i)) {
+                    slice_xor(&mut q[rowd], &c[row]);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Update a block in an RDP array.
+///
+/// This applies only the difference between the old and new block to p and
+/// q, so small writes don't need to read the rest of the array. Since the
+/// layout depends on the number of data blocks, this must also be provided.
+///
+/// ``` rust
+/// # use ::gf256::raid::rdp;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// let mut p = b"\x55\x29\x5f\x22".to_vec();
+/// let mut q = b"\x40\x55\x1c\x57".to_vec();
+///
+/// // update
+/// let new_data = b"Jell";
+/// rdp::update(3, 0, datas[0], new_data, &mut p, &mut q);
+/// datas[0].copy_from_slice(new_data);
+///
+/// assert_eq!(&datas[0], b"Jell");
+/// assert_eq!(&p, b"\x57\x29\x5f\x22");
+/// assert_eq!(&q, b"\x42\x55\x1c\x57");
+/// ```
+///
+pub fn update(
+    blocks: usize,
+    j: usize,
+    old: &[u8],
+    new: &[u8],
+    p: &mut [u8],
+    q: &mut [u8],
+) {
+    assert!(j < blocks);
+    let prime = prime(blocks);
+    let len = p.len();
+    assert!(len.is_multiple_of(prime-1));
+    assert!(old.len() == len);
+    assert!(new.len() == len);
+    assert!(q.len() == len);
+    let size = len / (prime-1);
+
+    for r in 0..prime-1 {
+        let rows = r*size..(r+1)*size;
+        // the change appears in row r of both column j and p
+        for d in [(r + j) % prime, (r + prime-1) % prime] {
+            if d < prime-1 {
+                let q = &mut q[d*size..(d+1)*size];
+                slice_xor(q, &old[rows.clone()]);
+                slice_xor(q, &new[rows.clone()]);
+            }
+        }
+        slice_xor(&mut p[rows.clone()], &old[rows.clone()]);
+        slice_xor(&mut p[rows.clone()], &new[rows]);
+    }
+}
@@ -0,0 +1,358 @@
+//! Runtime-configurable RAID-parity
+//!
+//! The [`raid`](super::raid) macro generates RAID-parity functions with the
+//! number of parity blocks fixed at compile time. But real arrays grow and
+//! shrink, and the level of redundancy may be read from a superblock or
+//! chosen by an operator.
+//!
+//! [`Raid`] implements the same RAID-parity schemes over `GF(256)`, with
+//! both the number of data blocks and the number of parity blocks chosen at
+//! runtime. With up to 3 parity blocks, [`Raid`] is compatible with
+//! [`raid5`](super::raid5), [`raid6`](super::raid6), and
+//! [`raid7`](super::raid7), and with more than 3 parity blocks, [`Raid`] is
+//! compatible with the [`raid`](super::raid) macro's Vandermonde scheme.
+//!
+
+use super::Error;
+use crate::gf::gf256;
+use crate::internal::shuffle;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Multiply a slice by a scalar and add it to another slice, f += c*g
+pub(super) fn slice_mul_add(f: &mut [u8], g: &[u8], c: gf256) {
+    assert!(f.len() == g.len());
+    if c == gf256(0) {
+        return;
+    } else if c == gf256(1) {
+        for (x, y) in f.iter_mut().zip(g) {
+            *x ^= *y;
+        }
+        return;
+    }
+
+    if shuffle::has_shuffle() && f.len() >= shuffle::SHUFFLE_THRESHOLD {
+        let (lo, hi) = shuffle::split_tables(|x| (c*gf256(x)).get());
+        shuffle::mul_add(f, g, &lo, &hi);
+        return;
+    }
+
+    for (x, y) in f.iter_mut().zip(g) {
+        *x = (gf256(*x) + c*gf256(*y)).get();
+    }
+}
+
+/// Invert a square matrix with Gauss-Jordan elimination
+pub(super) fn invert(mut a: Vec<Vec<gf256>>) -> Option<Vec<Vec<gf256>>> {
+    let n = a.len();
+    let mut inv = (0..n)
+        .map(|r| (0..n).map(|c| gf256(u8::from(r == c))).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    for c in 0..n {
+        let pivot = (c..n).find(|r| a[*r][c] != gf256(0))?;
+        a.swap(c, pivot);
+        inv.swap(c, pivot);
+
+        let s = a[c][c].recip();
+        for k in 0..n {
+            a[c][k] *= s;
+            inv[c][k] *= s;
+        }
+
+        for r in 0..n {
+            let f = a[r][c];
+            if r != c && f != gf256(0) {
+                for k in 0..n {
+                    let (ack, invck) = (a[c][k], inv[c][k]);
+                    a[r][k] -= f*ack;
+                    inv[r][k] -= f*invck;
+                }
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+
+/// A RAID array over `GF(256)`, with the number of data blocks and parity
+/// blocks chosen at runtime.
+///
+/// Note this requires feature `alloc`.
+///
+/// ``` rust
+/// use gf256::raid::Raid;
+///
+/// // the same scheme as raid7
+/// let raid = Raid::new(3, 3)?;
+///
+/// // format
+/// let mut buf = b"Hello World!".to_vec();
+/// let mut parity = vec![vec![0u8; 4]; raid.parity()];
+/// let slices = buf.chunks(4).collect::<Vec<_>>();
+/// raid.format(&slices, &mut parity);
+///
+/// // corrupt
+/// buf[0..8].fill(b'x');
+///
+/// // repair
+/// let mut slices = buf.chunks_mut(4).collect::<Vec<_>>();
+/// raid.repair(&mut slices, &mut parity, &[0, 1])?;
+/// assert_eq!(&buf, b"Hello World!");
+/// # Ok::<(), gf256::raid::Error>(())
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Raid {
+    blocks: usize,
+    parity: usize,
+
+    // inverse of the Vandermonde matrix of the parity blocks' locators,
+    // only used with more than 3 parity blocks
+    parity_inv: Vec<Vec<gf256>>,
+}
+
+impl Raid {
+    /// Create a new RAID array.
+    ///
+    /// - `blocks` - The number of data blocks.
+    /// - `parity` - The number of parity blocks.
+    ///
+    /// Returns [`Error::InvalidParameters`] if there are too many blocks.
+    /// With one parity block the number of data blocks is unlimited, with
+    /// 2-3 parity blocks the number of data blocks is limited to 255, and
+    /// with more than 3 parity blocks the total number of blocks, data and
+    /// parity, is limited to 255.
+    ///
+    pub fn new(blocks: usize, parity: usize) -> Result<Self, Error> {
+        if Self::max_blocks(parity).is_some_and(|max| blocks > max)
+            || (parity > 3 && blocks.saturating_add(parity) > 255)
+        {
+            return Err(Error::InvalidParameters);
+        }
+
+        let mut parity_inv = vec![];
+        if parity > 3 {
+            let xs = (0..parity)
+                .map(|i| gf256::GENERATOR.pow(i as u8))
+                .collect::<Vec<_>>();
+            let v = (0..parity)
+                .map(|r| xs.iter().map(|x| x.pow(r as u8)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            parity_inv = invert(v).unwrap();
+        }
+
+        Ok(Self {
+            blocks,
+            parity,
+            parity_inv,
+        })
+    }
+
+    /// Maximum number of data blocks for a number of parity blocks, or
+    /// `None` if unlimited.
+    pub fn max_blocks(parity: usize) -> Option<usize> {
+        match parity {
+            0 | 1 => None,
+            2 | 3 => Some(255),
+            _ => Some(255usize.saturating_sub(parity)),
+        }
+    }
+
+    /// Number of data blocks.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Number of parity blocks.
+    pub fn parity(&self) -> usize {
+        self.parity
+    }
+
+    /// Coefficient of data block `j` in parity block `i`
+    fn coeff(&self, i: usize, j: usize) -> gf256 {
+        if i == 0 && self.parity <= 3 {
+            gf256(1)
+        } else if self.parity <= 3 {
+            // p = Σ di, q = Σ di*g^i, r = Σ di*g^2i
+            gf256::GENERATOR.pow(j as u8).pow(i as u8)
+        } else {
+            // with more than 3 parity blocks, parity blocks are a linear
+            // function of the data blocks' locators
+            let x = gf256::GENERATOR.pow((j+self.parity) as u8);
+            let mut c = gf256(0);
+            let mut xr = gf256(1);
+            for a in &self.parity_inv[i] {
+                c += *a*xr;
+                xr *= x;
+            }
+            c
+        }
+    }
+
+    /// Format blocks as a RAID array.
+    ///
+    /// This writes the parity data to the provided parity blocks based on
+    /// the provided data blocks.
+    ///
+    pub fn format<B: AsRef<[u8]>, P: AsMut<[u8]>>(
+        &self,
+        blocks: &[B],
+        parity: &mut [P]
+    ) {
+        assert!(blocks.len() == self.blocks);
+        assert!(parity.len() == self.parity);
+
+        for (i, p) in parity.iter_mut().enumerate() {
+            let p = p.as_mut();
+            p.fill(0);
+            for (j, b) in blocks.iter().enumerate() {
+                slice_mul_add(p, b.as_ref(), self.coeff(i, j));
+            }
+        }
+    }
+
+    /// Repair up to [`parity`](Self::parity) bad blocks.
+    ///
+    /// This can include the parity blocks themselves, which are indexed
+    /// after the data blocks. `bad_blocks` must be an array of indices
+    /// indicating which blocks are bad.
+    ///
+    pub fn repair<B: AsMut<[u8]>, P: AsMut<[u8]>>(
+        &self,
+        blocks: &mut [B],
+        parity: &mut [P],
+        bad_blocks: &[usize]
+    ) -> Result<(), Error> {
+        assert!(blocks.len() == self.blocks);
+        assert!(parity.len() == self.parity);
+        assert!(bad_blocks.iter().all(|j| *j < self.blocks+self.parity));
+
+        let mut bad_blocks = bad_blocks.to_vec();
+        bad_blocks.sort_unstable();
+        bad_blocks.dedup();
+        if bad_blocks.len() > self.parity {
+            // can't repair
+            return Err(Error::TooManyBadBlocks);
+        }
+
+        let (bad_datas, bad_parity) = bad_blocks.split_at(
+            bad_blocks.partition_point(|j| *j < self.blocks)
+        );
+
+        if !bad_datas.is_empty() {
+            // use the first good parity blocks to solve for the bad data
+            // blocks
+            let rows = (0..self.parity)
+                .filter(|i| !bad_parity.contains(&(self.blocks+i)))
+                .take(bad_datas.len())
+                .collect::<Vec<_>>();
+
+            // find parity - Σ di*cij for each good data block
+            let mut syndromes = rows.iter()
+                .map(|&i| parity[i].as_mut().to_vec())
+                .collect::<Vec<_>>();
+            for (j, b) in blocks.iter_mut().enumerate() {
+                if bad_datas.contains(&j) {
+                    continue;
+                }
+
+                for (s, &i) in syndromes.iter_mut().zip(&rows) {
+                    slice_mul_add(s, b.as_mut(), self.coeff(i, j));
+                }
+            }
+
+            // solve for the bad data blocks
+            let m = rows.iter()
+                .map(|&i| bad_datas.iter().map(|&j| self.coeff(i, j)).collect())
+                .collect();
+            let m_inv = invert(m).ok_or(Error::TooManyBadBlocks)?;
+            for (&j, m_inv) in bad_datas.iter().zip(&m_inv) {
+                let d = blocks[j].as_mut();
+                d.fill(0);
+                for (s, c) in syndromes.iter().zip(m_inv) {
+                    slice_mul_add(d, s, *c);
+                }
+            }
+        }
+
+        // regenerate bad parity blocks
+        for &j in bad_parity {
+            let i = j - self.blocks;
+            let p = parity[i].as_mut();
+            p.fill(0);
+            for (j, b) in blocks.iter_mut().enumerate() {
+                slice_mul_add(p, b.as_mut(), self.coeff(i, j));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Update a block in a RAID array.
+    ///
+    /// This applies only the difference between the old and new block to
+    /// each parity block, so small writes don't need to read the rest of
+    /// the array.
+    ///
+    pub fn update<P: AsMut<[u8]>>(
+        &self,
+        j: usize,
+        old: &[u8],
+        new: &[u8],
+        parity: &mut [P]
+    ) {
+        assert!(j < self.blocks);
+        assert!(parity.len() == self.parity);
+
+        for (i, p) in parity.iter_mut().enumerate() {
+            let p = p.as_mut();
+            slice_mul_add(p, old, self.coeff(i, j));
+            slice_mul_add(p, new, self.coeff(i, j));
+        }
+    }
+
+    /// Grow the RAID array by adding a new data block to the end of the
+    /// array.
+    ///
+    /// Returns [`Error::InvalidParameters`] if the array can't grow any
+    /// further. On error the array is left unmodified.
+    ///
+    pub fn push<P: AsMut<[u8]>>(
+        &mut self,
+        new: &[u8],
+        parity: &mut [P]
+    ) -> Result<(), Error> {
+        assert!(parity.len() == self.parity);
+        if Self::max_blocks(self.parity).is_some_and(|max| self.blocks >= max) {
+            return Err(Error::InvalidParameters);
+        }
+
+        let j = self.blocks;
+        for (i, p) in parity.iter_mut().enumerate() {
+            slice_mul_add(p.as_mut(), new, self.coeff(i, j));
+        }
+        self.blocks += 1;
+        Ok(())
+    }
+
+    /// Shrink the RAID array by removing the last data block.
+    pub fn pop<P: AsMut<[u8]>>(
+        &mut self,
+        old: &[u8],
+        parity: &mut [P]
+    ) {
+        assert!(self.blocks > 0);
+        assert!(parity.len() == self.parity);
+
+        let j = self.blocks - 1;
+        for (i, p) in parity.iter_mut().enumerate() {
+            slice_mul_add(p.as_mut(), old, self.coeff(i, j));
+        }
+        self.blocks -= 1;
+    }
+}
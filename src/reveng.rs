@@ -0,0 +1,629 @@
+//! ## CRC parameter recovery
+//!
+//! Given a handful of messages and their CRCs, [`reveng`] attempts to recover
+//! the parameters of the CRC that generated them, in the style of
+//! [CRC RevEng][reveng]:
+//!
+//! ``` rust
+//! use gf256::crc::crc32c;
+//! use gf256::crc::reveng::reveng;
+//!
+//! let samples = [
+//!     (&b"Hello World!"[..], u128::from(crc32c(b"Hello World!", 0))),
+//!     (&b"Hello Earth!"[..], u128::from(crc32c(b"Hello Earth!", 0))),
+//!     (&b"Hello Mars!"[..],  u128::from(crc32c(b"Hello Mars!", 0))),
+//!     (&b"Hello Moon!"[..],  u128::from(crc32c(b"Hello Moon!", 0))),
+//! ];
+//!
+//! let params = reveng(32, &samples).unwrap();
+//! assert_eq!(params.polynomial, 0x11edc6f41);
+//! assert_eq!(params.reflected, true);
+//! assert_eq!(params.init, 0xffffffff);
+//! assert_eq!(params.xorout, 0xffffffff);
+//! ```
+//!
+//! Note this module requires feature `alloc`.
+//!
+//! This relies on the linearity of CRCs. The CRCs of two messages with the
+//! same length differ by the remainder of the messages' difference, so the
+//! polynomial must divide the difference of the messages, padded with their
+//! CRCs. Taking the GCD over a couple of these differences quickly narrows
+//! down the polynomial.
+//!
+//! Once we know the polynomial, the contributions of the initial register
+//! and final xor to each CRC are linear, so we can find these by solving
+//! a system of equations over GF(2).
+//!
+//! Note that we need at least two pairs of messages with the same length
+//! to find the polynomial, and messages with different lengths to tell the
+//! initial register apart from the final xor. Some CRCs have multiple sets
+//! of parameters that produce the same CRCs for all messages, in which case
+//! [`reveng`] returns one of these.
+//!
+//! [reveng]: https://reveng.sourceforge.io
+
+extern crate alloc;
+use alloc::vec::Vec;
+use core::mem::swap;
+
+
+/// Parameters of a CRC, in the style of Ross Williams' parameterized model
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CrcParams {
+    /// Width of the CRC in bits
+    pub width: usize,
+    /// The CRC's polynomial, including the highest bit, as expected by
+    /// the [`crc`](super::crc) macro
+    pub polynomial: u128,
+    /// Whether the CRC's bits are reflected
+    pub reflected: bool,
+    /// The initial value of the CRC's register, before any reflection
+    pub init: u128,
+    /// A value to xor the CRC with after any reflection
+    pub xorout: u128,
+}
+
+impl CrcParams {
+    /// Calculate the CRC of some data using these parameters.
+    ///
+    /// This is a naive bitwise implementation, mostly useful for checking
+    /// parameters.
+    ///
+    /// ``` rust
+    /// # use gf256::crc::reveng::CrcParams;
+    /// // CRC-32/MPEG-2
+    /// let params = CrcParams {
+    ///     width: 32,
+    ///     polynomial: 0x104c11db7,
+    ///     reflected: false,
+    ///     init: 0xffffffff,
+    ///     xorout: 0,
+    /// };
+    /// assert_eq!(params.crc(b"123456789"), 0x0376e6e7);
+    /// ```
+    ///
+    pub fn crc(&self, data: &[u8]) -> u128 {
+        let mut crc = self.init;
+        for b in data {
+            crc = lfsr_byte(
+                self.width,
+                self.polynomial,
+                crc,
+                if self.reflected { b.reverse_bits() } else { *b }
+            );
+        }
+
+        if self.reflected {
+            crc = reflect(self.width, crc);
+        }
+        crc ^ self.xorout
+    }
+
+    /// The initial CRC to pass to a CRC function generated with the
+    /// [`crc`](super::crc) macro, with `xor` set to [`xorout`](Self::xorout).
+    ///
+    /// ``` rust,ignore
+    /// # use ::gf256::*;
+    /// # use ::gf256::crc::crc;
+    /// # use ::gf256::crc::reveng::CrcParams;
+    /// // CRC-32/MPEG-2
+    /// #[crc(polynomial=0x104c11db7, reflected=false, xor=0)]
+    /// fn crc32_mpeg2() {}
+    ///
+    /// # fn main() {
+    /// let params = CrcParams {
+    ///     width: 32,
+    ///     polynomial: 0x104c11db7,
+    ///     reflected: false,
+    ///     init: 0xffffffff,
+    ///     xorout: 0,
+    /// };
+    /// let init = params.initial_crc() as u32;
+    /// assert_eq!(crc32_mpeg2(b"123456789", init), 0x0376e6e7);
+    /// # }
+    /// ```
+    ///
+    pub fn initial_crc(&self) -> u128 {
+        if self.reflected {
+            reflect(self.width, self.init) ^ self.xorout
+        } else {
+            self.init ^ self.xorout
+        }
+    }
+}
+
+
+/// Attempt to recover the parameters of a CRC from a set of messages and
+/// their CRCs.
+///
+/// This needs the width of the CRC, up to 64 bits, and returns [`None`] if
+/// the parameters can not be determined from the given samples, in which
+/// case more samples may help.
+///
+/// ``` rust
+/// # use gf256::crc::reveng::{reveng, CrcParams};
+/// // CRC-32/MPEG-2
+/// let params = CrcParams {
+///     width: 32,
+///     polynomial: 0x104c11db7,
+///     reflected: false,
+///     init: 0xffffffff,
+///     xorout: 0,
+/// };
+///
+/// let samples = [
+///     (&b"Hello World!"[..], params.crc(b"Hello World!")),
+///     (&b"Hello Earth!"[..], params.crc(b"Hello Earth!")),
+///     (&b"Hello Mars!"[..],  params.crc(b"Hello Mars!")),
+///     (&b"Hello Moon!"[..],  params.crc(b"Hello Moon!")),
+/// ];
+/// assert_eq!(reveng(32, &samples), Some(params));
+/// ```
+///
+/// See the [module-level documentation](self) for more info.
+///
+pub fn reveng(width: usize, samples: &[(&[u8], u128)]) -> Option<CrcParams> {
+    assert!((1..=64).contains(&width));
+
+    // try bit-reflected CRCs first, these are more common
+    [true, false].iter()
+        .find_map(|&reflected| reveng_reflected(width, reflected, samples))
+}
+
+/// The maximum number of extra degrees we're willing to search through when
+/// our samples share common factors
+const MAX_EXTRA_DEGREE: usize = 16;
+
+/// The maximum number of free unknowns we're willing to search through when
+/// there are multiple equivalent solutions
+const MAX_NULLSPACE: usize = 8;
+
+fn reveng_reflected(
+    width: usize,
+    reflected: bool,
+    samples: &[(&[u8], u128)],
+) -> Option<CrcParams> {
+    let mask = (1u128 << width) - 1;
+
+    // normalize our samples so we can pretend the CRC is not reflected,
+    // this reflects the final xor, but we can reflect it back later
+    let normalized = samples.iter()
+        .map(|&(data, crc)| {
+            let data = data.iter()
+                .map(|b| if reflected { b.reverse_bits() } else { *b })
+                .collect::<Vec<u8>>();
+            let crc = crc & mask;
+            (data, if reflected { reflect(width, crc) } else { crc })
+        })
+        .collect::<Vec<_>>();
+
+    // find the GCD of the differences between messages with the same length,
+    // padded with the difference of their CRCs, the polynomial must divide
+    // all of these
+    let mut gcd = Vec::new();
+    for (i, (a, a_crc)) in normalized.iter().enumerate() {
+        // we only need to compare against the first message with the
+        // same length
+        let b = normalized[..i].iter()
+            .find(|(b, _)| b.len() == a.len());
+        let (b, b_crc) = match b {
+            Some(b) => b,
+            None => continue,
+        };
+
+        let mut diff = poly_from_bytes(
+            a.iter().zip(b).map(|(a, b)| a ^ b),
+            width
+        );
+        poly_xor(&mut diff, &[((a_crc ^ b_crc) & mask) as u64], 0);
+        gcd = poly_gcd(gcd, diff);
+    }
+
+    // our GCD must be at least the width of the CRC, if it's smaller no
+    // CRC of this width exists
+    let degree = poly_degree(&gcd)?;
+    if degree < width {
+        return None;
+    }
+
+    // if it's larger, our samples may just share some common factors, if
+    // there aren't too many we can search for a factor with the right width
+    if degree - width > MAX_EXTRA_DEGREE {
+        return None;
+    }
+
+    for factor in (1u64 << (degree-width))..(1u64 << (degree-width+1)) {
+        let (polynomial, rem) = poly_divrem(gcd.clone(), &[factor]);
+        if poly_degree(&rem).is_some() {
+            continue;
+        }
+        let polynomial = u128::from(polynomial[0])
+            | (u128::from(polynomial.get(1).copied().unwrap_or(0)) << 64);
+
+        if let Some(params) = reveng_polynomial(
+            width,
+            reflected,
+            polynomial,
+            samples,
+            &normalized
+        ) {
+            return Some(params);
+        }
+    }
+
+    None
+}
+
+fn reveng_polynomial(
+    width: usize,
+    reflected: bool,
+    polynomial: u128,
+    samples: &[(&[u8], u128)],
+    normalized: &[(Vec<u8>, u128)],
+) -> Option<CrcParams> {
+    let mask = (1u128 << width) - 1;
+
+    // now find the initial register and final xor, each sample gives us
+    // width equations of the form:
+    //
+    //   crc = init*x^(8*len) + data*x^width + xorout (mod polynomial)
+    //
+    // unknowns are stored in a bit-matrix, with init in the lower bits,
+    // and xorout in the upper bits
+    //
+    let mut rows = Vec::new();
+    for (data, crc) in normalized.iter() {
+        // find the contribution of our data
+        let mut rem = 0;
+        for b in data {
+            rem = lfsr_byte(width, polynomial, rem, *b);
+        }
+
+        // find x^(8*len) mod polynomial, and the contribution of each bit
+        // of the initial register
+        let mut x = 1;
+        for _ in 0..data.len() {
+            x = lfsr_byte(width, polynomial, x, 0);
+        }
+
+        let mut cols = [0u128; 64];
+        for j in 0..width {
+            cols[j] = x;
+            x = lfsr_shift(width, polynomial, x);
+        }
+
+        for i in 0..width {
+            let mut row = 1u128 << (width+i);
+            for j in 0..width {
+                row |= ((cols[j] >> i) & 1) << j;
+            }
+            rows.push((row, ((crc ^ rem) >> i) & 1 != 0));
+        }
+    }
+
+    // some CRCs have multiple equivalent solutions, if so prefer the more
+    // common conventions of init/xorout being all zeros or all ones
+    let (solution, nullspace) = solve(&mut rows, 2*width)?;
+    let preference = |solution: u128| {
+        let init = solution & mask;
+        let xorout = solution >> width;
+        match (init == 0 || init == mask, init == xorout) {
+            (true, true) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (false, false) => 3,
+        }
+    };
+
+    let mut best = solution;
+    if nullspace.len() <= MAX_NULLSPACE {
+        for i in 0..1u32 << nullspace.len() {
+            let mut solution = solution;
+            for (j, basis) in nullspace.iter().enumerate() {
+                if i & (1 << j) != 0 {
+                    solution ^= basis;
+                }
+            }
+
+            if preference(solution) < preference(best) {
+                best = solution;
+            }
+        }
+    }
+
+    let init = best & mask;
+    let xorout = best >> width;
+
+    let params = CrcParams {
+        width,
+        polynomial,
+        reflected,
+        init,
+        xorout: if reflected { reflect(width, xorout) } else { xorout },
+    };
+
+    // double check that our parameters actually work
+    if !samples.iter().all(|&(data, crc)| params.crc(data) == crc & mask) {
+        return None;
+    }
+    Some(params)
+}
+
+
+/// Reflect the lower width bits
+fn reflect(width: usize, x: u128) -> u128 {
+    x.reverse_bits() >> (128-width)
+}
+
+/// Shift a non-reflected CRC register by one bit of zeros
+fn lfsr_shift(width: usize, polynomial: u128, crc: u128) -> u128 {
+    let crc = crc << 1;
+    if crc & (1 << width) != 0 {
+        crc ^ polynomial
+    } else {
+        crc
+    }
+}
+
+/// Shift a byte into a non-reflected CRC register, most-significant bit first
+fn lfsr_byte(width: usize, polynomial: u128, crc: u128, b: u8) -> u128 {
+    let mut crc = crc;
+    for i in (0..8).rev() {
+        crc ^= u128::from((b >> i) & 1) << (width-1);
+        crc = lfsr_shift(width, polynomial, crc);
+    }
+    crc
+}
+
+/// Solve a system of equations over GF(2) with Gaussian elimination,
+/// returning a bit-mask of unknowns with any free unknowns set to zero,
+/// and a basis for the nullspace
+fn solve(rows: &mut [(u128, bool)], unknowns: usize) -> Option<(u128, Vec<u128>)> {
+    let mut pivots = Vec::new();
+    let mut r = 0;
+    for c in 0..unknowns {
+        // find a row with this unknown
+        let p = match (r..rows.len()).find(|&i| rows[i].0 & (1 << c) != 0) {
+            Some(p) => p,
+            None => continue,
+        };
+        rows.swap(r, p);
+
+        // eliminate from all other rows
+        let (row, rhs) = rows[r];
+        for i in 0..rows.len() {
+            if i != r && rows[i].0 & (1 << c) != 0 {
+                rows[i].0 ^= row;
+                rows[i].1 ^= rhs;
+            }
+        }
+
+        pivots.push(c);
+        r += 1;
+    }
+
+    // any remaining rows must be consistent
+    if rows[r..].iter().any(|&(_, rhs)| rhs) {
+        return None;
+    }
+
+    let mut solution = 0;
+    for (i, &c) in pivots.iter().enumerate() {
+        if rows[i].1 {
+            solution |= 1 << c;
+        }
+    }
+
+    // each free unknown contributes a vector to the nullspace
+    let mut nullspace = Vec::new();
+    for f in (0..unknowns).filter(|f| !pivots.contains(f)) {
+        let mut basis = 1 << f;
+        for (i, &c) in pivots.iter().enumerate() {
+            if rows[i].0 & (1 << f) != 0 {
+                basis |= 1 << c;
+            }
+        }
+        nullspace.push(basis);
+    }
+
+    Some((solution, nullspace))
+}
+
+
+// Some helpers for arbitrarily large polynomials, stored as little-endian
+// 64-bit words
+
+/// Create a polynomial from bytes, most-significant bit first, multiplied
+/// by x^shift
+fn poly_from_bytes(bytes: impl DoubleEndedIterator<Item=u8>, shift: usize) -> Vec<u64> {
+    let mut a = Vec::new();
+    for (i, b) in bytes.rev().enumerate() {
+        poly_xor(&mut a, &[u64::from(b)], 8*i + shift);
+    }
+    a
+}
+
+/// Find the degree of a polynomial, None if zero
+fn poly_degree(a: &[u64]) -> Option<usize> {
+    a.iter().rposition(|&w| w != 0)
+        .map(|i| 64*i + 63 - usize::try_from(a[i].leading_zeros()).unwrap())
+}
+
+/// a += b*x^shift
+fn poly_xor(a: &mut Vec<u64>, b: &[u64], shift: usize) {
+    let words = shift / 64;
+    let bits = shift % 64;
+    if a.len() < b.len() + words + 1 {
+        a.resize(b.len() + words + 1, 0);
+    }
+
+    for (i, &w) in b.iter().enumerate() {
+        a[i+words] ^= w << bits;
+        if bits != 0 {
+            a[i+words+1] ^= w >> (64-bits);
+        }
+    }
+}
+
+/// (a / b, a % b)
+fn poly_divrem(mut a: Vec<u64>, b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let b_degree = poly_degree(b).unwrap();
+    let mut q = Vec::new();
+    while let Some(a_degree) = poly_degree(&a) {
+        if a_degree < b_degree {
+            break;
+        }
+        poly_xor(&mut a, b, a_degree - b_degree);
+        poly_xor(&mut q, &[1], a_degree - b_degree);
+    }
+    (q, a)
+}
+
+/// gcd(a, b), note gcd(0, b) = b
+fn poly_gcd(mut a: Vec<u64>, mut b: Vec<u64>) -> Vec<u64> {
+    while poly_degree(&b).is_some() {
+        a = poly_divrem(a, &b).1;
+        swap(&mut a, &mut b);
+    }
+    a
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crc::*;
+
+    fn samples<'a>(messages: &[&'a [u8]], f: impl Fn(&[u8]) -> u128) -> Vec<(&'a [u8], u128)> {
+        messages.iter()
+            .map(|m| (*m, f(m)))
+            .collect()
+    }
+
+    const MESSAGES: [&[u8]; 9] = [
+        b"Hello World!",
+        b"Hello Earth!",
+        b"Hello Venus!",
+        b"Hello Pluto!",
+        b"Hello Mars!",
+        b"Hello Moon!",
+        b"Hello Sun!",
+        b"123456789",
+        b"",
+    ];
+
+    #[test]
+    fn reveng_gf256() {
+        // recover our own CRCs
+        let params = reveng(8, &samples(&MESSAGES, |m| u128::from(crc8(m, 0)))).unwrap();
+        assert_eq!(params.polynomial, 0x107);
+        for m in MESSAGES {
+            assert_eq!(params.crc(m), u128::from(crc8(m, 0)));
+        }
+
+        let params = reveng(16, &samples(&MESSAGES, |m| u128::from(crc16(m, 0)))).unwrap();
+        assert_eq!(params.polynomial, 0x11021);
+        for m in MESSAGES {
+            assert_eq!(params.crc(m), u128::from(crc16(m, 0)));
+        }
+
+        let params = reveng(32, &samples(&MESSAGES, |m| u128::from(crc32(m, 0)))).unwrap();
+        assert_eq!(params, CrcParams {
+            width: 32,
+            polynomial: 0x104c11db7,
+            reflected: true,
+            init: 0xffffffff,
+            xorout: 0xffffffff,
+        });
+
+        let params = reveng(32, &samples(&MESSAGES, |m| u128::from(crc32c(m, 0)))).unwrap();
+        assert_eq!(params, CrcParams {
+            width: 32,
+            polynomial: 0x11edc6f41,
+            reflected: true,
+            init: 0xffffffff,
+            xorout: 0xffffffff,
+        });
+
+        let params = reveng(64, &samples(&MESSAGES, |m| u128::from(crc64(m, 0)))).unwrap();
+        assert_eq!(params, CrcParams {
+            width: 64,
+            polynomial: 0x142f0e1eba9ea3693,
+            reflected: true,
+            init: 0xffffffffffffffff,
+            xorout: 0xffffffffffffffff,
+        });
+    }
+
+    #[test]
+    fn reveng_catalog() {
+        // some CRCs with a mix of parameters, check values from the
+        // CRC RevEng catalogue
+        let catalog = [
+            // CRC-16/ARC
+            (CrcParams { width: 16, polynomial: 0x18005, reflected: true, init: 0x0000, xorout: 0x0000 }, 0xbb3d),
+            // CRC-16/GENIBUS
+            (CrcParams { width: 16, polynomial: 0x11021, reflected: false, init: 0xffff, xorout: 0xffff }, 0xd64e),
+            // CRC-16/RIELLO
+            (CrcParams { width: 16, polynomial: 0x11021, reflected: true, init: 0xb2aa, xorout: 0x0000 }, 0x63d0),
+            // CRC-32/MPEG-2
+            (CrcParams { width: 32, polynomial: 0x104c11db7, reflected: false, init: 0xffffffff, xorout: 0x00000000 }, 0x0376e6e7),
+            // CRC-32/BZIP2
+            (CrcParams { width: 32, polynomial: 0x104c11db7, reflected: false, init: 0xffffffff, xorout: 0xffffffff }, 0xfc891918),
+            // CRC-15/CAN
+            (CrcParams { width: 15, polynomial: 0xc599, reflected: false, init: 0x0000, xorout: 0x0000 }, 0x059e),
+            // CRC-5/USB
+            (CrcParams { width: 5, polynomial: 0x25, reflected: true, init: 0x1f, xorout: 0x1f }, 0x19),
+        ];
+
+        for (params, check) in catalog {
+            assert_eq!(params.crc(b"123456789"), check);
+
+            let recovered = reveng(params.width, &samples(&MESSAGES, |m| params.crc(m))).unwrap();
+            assert_eq!(recovered.polynomial, params.polynomial);
+            assert_eq!(recovered.reflected, params.reflected);
+            // some parameters may be equivalent
+            for m in MESSAGES {
+                assert_eq!(recovered.crc(m), params.crc(m));
+            }
+            assert_eq!(recovered.crc(b"a longer message to be sure"), params.crc(b"a longer message to be sure"));
+        }
+    }
+
+    #[crc(polynomial=0x11021, reflected=false, xor=0xffff)]
+    fn crc16_genibus() {}
+
+    #[crc(polynomial=0x11021, reflected=true, xor=0)]
+    fn crc16_riello() {}
+
+    #[test]
+    fn reveng_initial_crc() {
+        let params = CrcParams { width: 16, polynomial: 0x11021, reflected: false, init: 0xffff, xorout: 0xffff };
+        let init = params.initial_crc() as u16;
+        for m in MESSAGES {
+            assert_eq!(u128::from(crc16_genibus(m, init)), params.crc(m));
+        }
+
+        let params = CrcParams { width: 16, polynomial: 0x11021, reflected: true, init: 0xb2aa, xorout: 0x0000 };
+        let init = params.initial_crc() as u16;
+        for m in MESSAGES {
+            assert_eq!(u128::from(crc16_riello(m, init)), params.crc(m));
+        }
+    }
+
+    #[test]
+    fn reveng_not_enough_samples() {
+        // no messages with the same length
+        let messages = [MESSAGES[2], MESSAGES[4], MESSAGES[6], MESSAGES[7], MESSAGES[8]];
+        let s = samples(&messages, |m| u128::from(crc32c(m, 0)));
+        assert_eq!(reveng(32, &s), None);
+
+        // only one pair of messages with the same length
+        let messages = [MESSAGES[0], MESSAGES[2], MESSAGES[4], MESSAGES[6], MESSAGES[7]];
+        let s = samples(&messages, |m| u128::from(crc32c(m, 0)));
+        assert_eq!(reveng(32, &s), None);
+
+        // wrong width
+        assert_eq!(reveng(16, &samples(&MESSAGES, |m| u128::from(crc32c(m, 0)))), None);
+    }
+}
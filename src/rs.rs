@@ -29,6 +29,87 @@
 //!
 //! Note this module requires feature `rs`.
 //!
+//! If you just want to protect a buffer of any length, without worrying about
+//! block sizes, [`encode_message`](crate::rs::encode_message) and
+//! [`correct_message`](crate::rs::correct_message) split the message into
+//! [`rs255w223`](crate::rs::rs255w223) codewords and reassemble it for you:
+//!
+//! ``` rust
+//! # #[cfg(feature="alloc")]
+//! # fn main() -> Result<(), gf256::rs::Error> {
+//! use gf256::rs;
+//!
+//! let message = b"Hello World! ".repeat(100);
+//! let mut encoded = rs::encode_message(&message);
+//!
+//! // corrupt
+//! for i in (0..encoded.len()).step_by(20) {
+//!     encoded[i] ^= 0xff;
+//! }
+//!
+//! // correct
+//! rs::correct_message(&mut encoded)?;
+//! assert_eq!(encoded, message);
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature="alloc"))] fn main() {}
+//! ```
+//!
+//! Note [`encode_message`](crate::rs::encode_message) and
+//! [`correct_message`](crate::rs::correct_message) require feature `alloc`.
+//!
+//! Encoding and decoding, [`encode`](crate::rs::rs255w223::encode),
+//! [`is_correct`](crate::rs::rs255w223::is_correct), and the `correct`
+//! functions, do not allocate. All intermediate polynomials are bounded by
+//! `ECC_SIZE`, which is known at compile time, so these use fixed-size stack
+//! buffers.
+//!
+//! For blocks larger than 255 bytes,
+//! [`rs65535w65503`](crate::rs::rs65535w65503) provides Reed-Solomon over
+//! `GF(2^16)`, operating on 16-bit symbols:
+//!
+//! ``` rust
+//! use gf256::rs::rs65535w65503;
+//!
+//! // encode
+//! let mut buf = (0..1000u16).collect::<Vec<u16>>();
+//! buf.resize(buf.len()+32, 0u16);
+//! rs65535w65503::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0..16].fill(0xffff);
+//!
+//! // correct
+//! rs65535w65503::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..1000], &(0..1000u16).collect::<Vec<u16>>());
+//! # Ok::<(), rs65535w65503::Error>(())
+//! ```
+//!
+//! And for even larger blocks, such as for long-term archival or distributed
+//! storage, [`rs4294967295w4294967263`](crate::rs::rs4294967295w4294967263)
+//! provides Reed-Solomon over `GF(2^32)`, operating on 32-bit symbols.
+//! Log/antilog tables are infeasible for a field this size, so multiplication
+//! uses Barret reduction, with hardware carry-less multiplication where
+//! available. Codewords can be any length up to `2^32-1` symbols, shorter
+//! codewords are treated as if they were padded with leading zeros:
+//!
+//! ``` rust
+//! use gf256::rs::rs4294967295w4294967263;
+//!
+//! // encode
+//! let mut buf = (0..1000u32).collect::<Vec<u32>>();
+//! buf.resize(buf.len()+32, 0u32);
+//! rs4294967295w4294967263::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0..16].fill(0xffffffff);
+//!
+//! // correct
+//! rs4294967295w4294967263::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..1000], &(0..1000u32).collect::<Vec<u32>>());
+//! # Ok::<(), rs4294967295w4294967263::Error>(())
+//! ```
+//!
 //! A fully featured implementation of Reed-Solomon error-correction can be found in
 //! [`examples/rs.rs`][rs-example]:
 //!
@@ -1284,6 +1365,7 @@
 //! [crc-hd]: https://users.ece.cmu.edu/~koopman/crc
 //! [systematic]: https://en.wikipedia.org/wiki/Systematic_code
 //! [bch]: https://en.wikipedia.org/wiki/BCH_code
+//! [berlekamp-massey]: https://en.wikipedia.org/wiki/Berlekamp%E2%80%93Massey_algorithm
 //! [forney]: https://en.wikipedia.org/wiki/Forney_algorithm
 //! [formal-derivative]: https://en.wikipedia.org/wiki/Formal_derivative
 //! [voyager]: https://en.wikipedia.org/wiki/Voyager_program
@@ -1324,6 +1406,13 @@
 /// - `gf` - The finite-field we are implemented over, defaults to
 ///   [`gf256`](crate::gf256).
 /// - `u` - The unsigned type to operate on, defaults to [`u8`].
+/// - `interleave` - Number of codewords to interleave in the `_interleaved`
+///   functions, defaults to 1. Interleaving spreads burst errors across
+///   multiple codewords, allowing longer bursts to be corrected.
+/// - `berlekamp_massey`/`euclidean` - The algorithm used to find the error
+///   locator polynomial, defaults to `berlekamp_massey`. The Euclidean
+///   (Sugiyama) algorithm is simpler but usually a bit slower, having both
+///   is also useful for cross-validating the two.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -1333,6 +1422,8 @@
 ///     data=223,
 ///     gf=gf256,
 ///     u=u8,
+///     interleave=1,
+///     berlekamp_massey,
 /// )]
 /// pub mod my_rs255w223 {}
 ///
@@ -1354,12 +1445,142 @@
 ///
 pub use gf256_macros::rs;
 
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use alloc::vec::Vec;
+
 
 // Reed-Solomon error-correction functions
 //
 #[rs(block=255, data=223)]
 pub mod rs255w223 {}
 
+// Reed-Solomon error-correction functions over GF(2^16), for blocks
+// larger than 255 bytes
+//
+#[rs(gf=crate::gf::gf2p16, u=u16, block=65535, data=65503)]
+pub mod rs65535w65503 {}
+
+// Reed-Solomon error-correction functions over GF(2^32), for very large
+// blocks, note this relies on Barret reduction since tables are infeasible
+//
+#[rs(gf=crate::gf::gf2p32, u=u32, block=4294967295, data=4294967263)]
+pub mod rs4294967295w4294967263 {}
+
+/// Encode a message of any length, splitting it into [`rs255w223`]
+/// codewords.
+///
+/// Every 223 bytes of the message get 32 bytes of error-correction, which
+/// can correct up to 16 errors, with the last codeword shortened to fit.
+/// The result can be corrected with [`correct_message`]. This is the same
+/// as [`rs255w223::encode_stream`].
+///
+/// Note this requires feature `alloc`.
+///
+/// ``` rust
+/// use gf256::rs;
+///
+/// let encoded = rs::encode_message(b"Hello World!");
+/// assert_eq!(encoded.len(), 12+32);
+/// ```
+///
+#[cfg(feature="alloc")]
+pub fn encode_message(message: &[u8]) -> Vec<u8> {
+    rs255w223::encode_stream(message)
+}
+
+/// Correct a message created by [`encode_message`], in-place, removing the
+/// error-correction and leaving only the original message.
+///
+/// Returns the number of errors corrected, or [`Error::TooManyErrors`] if
+/// any codeword can not be corrected, in which case the message is left
+/// unmodified.
+///
+/// Note this requires feature `alloc`.
+///
+/// ``` rust
+/// use gf256::rs;
+///
+/// let mut encoded = rs::encode_message(b"Hello World!");
+/// encoded[0..6].fill(b'x');
+/// assert_eq!(rs::correct_message(&mut encoded), Ok(6));
+/// assert_eq!(&encoded, b"Hello World!");
+/// # Ok::<(), rs::Error>(())
+/// ```
+///
+#[cfg(feature="alloc")]
+pub fn correct_message(encoded: &mut Vec<u8>) -> Result<usize, Error> {
+    let mut message = Vec::with_capacity(encoded.len());
+    let mut codeword = Vec::with_capacity(rs255w223::BLOCK_SIZE);
+    let mut count = 0;
+    for chunk in encoded.chunks(rs255w223::BLOCK_SIZE) {
+        // a truncated codeword can't be from encode_message
+        if chunk.len() <= rs255w223::ECC_SIZE {
+            return Err(Error::TooManyErrors);
+        }
+
+        codeword.clear();
+        codeword.extend_from_slice(chunk);
+        count += rs255w223::correct_errors(&mut codeword)
+            .map_err(|_| Error::TooManyErrors)?;
+        message.extend_from_slice(&codeword[..codeword.len()-rs255w223::ECC_SIZE]);
+    }
+
+    *encoded = message;
+    Ok(count)
+}
+
+// Runtime-configurable Reed-Solomon
+//
+#[cfg(feature="alloc")]
+#[path="rs_runtime.rs"]
+mod runtime;
+#[cfg(feature="alloc")]
+pub use runtime::{Rs, Error};
+
+// CCSDS Reed-Solomon, with its dual-basis representation
+//
+#[cfg(feature="alloc")]
+#[path="rs_ccsds.rs"]
+pub mod ccsds;
+
+// PAR2-compatible Reed-Solomon over GF(2^16)
+//
+#[cfg(feature="alloc")]
+#[path="rs_par2.rs"]
+pub mod par2;
+
+// QR-code Reed-Solomon presets
+//
+#[cfg(feature="alloc")]
+#[path="rs_qr.rs"]
+pub mod qr;
+
+// Packet-level forward error correction, in the style of RFC 6865
+//
+#[cfg(feature="alloc")]
+#[path="rs_packet.rs"]
+pub mod packet;
+
+// File-level k-of-n shares, in the style of zfec
+//
+#[cfg(all(feature="alloc", feature="crc"))]
+#[path="rs_share.rs"]
+pub mod share;
+
+// Reed-Solomon with a CRC to reject miscorrections
+//
+#[cfg(all(feature="alloc", feature="crc"))]
+#[path="rs_checked.rs"]
+pub mod checked;
+
+// Concatenated Reed-Solomon + convolutional coding, in the style of CCSDS
+//
+#[cfg(feature="conv")]
+#[path="rs_concat.rs"]
+pub mod concat;
+
 
 #[cfg(test)]
 mod test {
@@ -1368,6 +1589,8 @@ mod test {
 
     extern crate alloc;
     use alloc::vec::Vec;
+    #[cfg(feature="alloc")]
+    use alloc::vec;
 
     // a smaller Reed-Solomon code
     #[rs(block=26, data=16)]
@@ -1396,6 +1619,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn rs26w16_errata() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+
+        // correct any mix of 2*errors + erasures <= k
+        for erasures in 0..=(26-16) {
+            for errors in 0..=(26-16-erasures)/2 {
+                let mut corrupted = data.clone();
+                corrupted[0..erasures].fill(b'x');
+                corrupted[20..20+errors].fill(b'x');
+                let res = rs26w16::correct(&mut corrupted, &(0..erasures).collect::<Vec<_>>());
+                assert_eq!(res.ok(), Some(erasures+errors));
+                assert_eq!(corrupted, data);
+            }
+        }
+    }
+
     #[test]
     fn rs26w16_any() {
         let mut data = (0..26).collect::<Vec<u8>>();
@@ -1499,6 +1740,1239 @@ mod test {
         }
     }
 
+    #[test]
+    fn rs255w223_encode_parity() {
+        for len in [0, 1, 12, 100, 223] {
+            let data = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut parity = [0u8; 32];
+            rs255w223::encode_parity(&data, &mut parity);
+
+            // should match in-place encoding
+            let mut codeword = data.clone();
+            codeword.resize(len+32, 0);
+            rs255w223::encode(&mut codeword);
+            assert_eq!(&codeword[..len], &data[..]);
+            assert_eq!(&codeword[len..], &parity[..]);
+            assert!(rs255w223::is_correct(&codeword));
+        }
+    }
+
+    #[test]
+    fn rs255w223_syndromes() {
+        let mut data = (0..255).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        rs255w223::encode(&mut data);
+        assert!(rs255w223::syndromes(&data).iter().all(|s| *s == gf256(0)));
+
+        // a single error at position j shows up as Si = e*g^(i*(len-1-j))
+        for j in [0, 100, 254] {
+            let mut corrupted = data.clone();
+            corrupted[j] ^= 0x55;
+            let syndromes = rs255w223::syndromes(&corrupted);
+            for (i, s) in syndromes.iter().enumerate() {
+                assert_eq!(*s, gf256(0x55) * gf256::GENERATOR.pow(((i*(254-j)) % 255) as u8));
+            }
+            assert!(!rs255w223::is_correct(&corrupted));
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w223_encoder_decoder() {
+        let encoder = rs255w223::Encoder::new();
+        let decoder = rs255w223::Decoder::new();
+
+        for len in [32, 33, 100, 255] {
+            let mut data = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            let mut expected = data.clone();
+            rs255w223::encode(&mut expected);
+            encoder.encode(&mut data);
+            assert_eq!(data, expected);
+            assert!(decoder.is_correct(&data));
+            assert_eq!(decoder.syndromes(&data), rs255w223::syndromes(&data));
+
+            // correct up to k known erasures
+            let erasures = (0..32).map(|i| i*(len/32)).collect::<Vec<_>>();
+            let mut corrupted = data.clone();
+            for &j in &erasures {
+                corrupted[j] ^= 0xff;
+            }
+            assert_eq!(decoder.correct_erasures(&mut corrupted, &erasures), Ok(32));
+            assert_eq!(corrupted, data);
+
+            // correct up to k/2 unknown errors
+            let mut corrupted = data.clone();
+            for &j in erasures.iter().step_by(2) {
+                corrupted[j] ^= 0xff;
+            }
+            assert!(!decoder.is_correct(&corrupted));
+            assert_eq!(decoder.correct_errors(&mut corrupted), Ok(16));
+            assert_eq!(corrupted, data);
+
+            // correct a mixture of errors and erasures
+            let mut corrupted = data.clone();
+            for &j in &erasures[..20] {
+                corrupted[j] ^= 0xff;
+            }
+            assert_eq!(decoder.correct(&mut corrupted, &erasures[..14]), Ok(20));
+            assert_eq!(corrupted, data);
+
+            // too many errors
+            let mut corrupted = data.clone();
+            corrupted[..17].fill(b'x');
+            let mut expected = corrupted.clone();
+            assert_eq!(
+                decoder.correct_errors(&mut corrupted),
+                rs255w223::correct_errors(&mut expected)
+            );
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w223_report() {
+        let mut data = (0..255).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        rs255w223::encode(&mut data);
+        let decoder = rs255w223::Decoder::new();
+
+        // no errors
+        let mut corrupted = data.clone();
+        let report = rs255w223::correct_report(&mut corrupted, &[]);
+        assert_eq!(report, rs255w223::Report {
+            errors: 0,
+            erasures: 0,
+            syndromes_before: 0,
+            syndromes_after: 0,
+        });
+        assert_eq!(report.result(), Ok(0));
+
+        // a mix of errors and erasures
+        for (errors, erasures) in [(16, 0), (0, 32), (10, 12), (3, 1)] {
+            let mut corrupted = data.clone();
+            for i in 0..errors+erasures {
+                corrupted[i*7] ^= 0xff;
+            }
+            let erasure_list = (errors..errors+erasures).map(|i| i*7).collect::<Vec<_>>();
+            let mut corrupted_ = corrupted.clone();
+            let report = rs255w223::correct_report(&mut corrupted, &erasure_list);
+            assert!(report.is_ok());
+            assert_eq!((report.errors, report.erasures), (errors, erasures));
+            assert_eq!(report.used_erasures(), erasures > 0);
+            assert!(report.syndromes_before > 0);
+            assert_eq!(report.syndromes_after, 0);
+            assert_eq!(corrupted, data);
+            assert_eq!(decoder.correct_report(&mut corrupted_, &erasure_list), report);
+            assert_eq!(corrupted_, data);
+        }
+
+        // too many errors
+        let mut corrupted = data.clone();
+        corrupted[0..17].fill(b'x');
+        let mut corrupted_ = corrupted.clone();
+        let report = rs255w223::correct_report(&mut corrupted, &[]);
+        assert!(!report.is_ok());
+        assert_eq!(report.corrected(), 0);
+        assert!(report.syndromes_after > 0);
+        assert_eq!(report.result(), rs255w223::correct(&mut corrupted_, &[]));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w223_punctured() {
+        let data = (0..223).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let mut full = data.clone();
+        full.resize(255, 0);
+        rs255w223::encode(&mut full);
+
+        for punctured in [vec![], vec![0], vec![31, 5, 17], (0..32).step_by(2).collect(), (0..32).collect()] {
+            let kept = (0..32).filter(|p| !punctured.contains(p)).collect::<Vec<_>>();
+            let mut codeword = data.clone();
+            codeword.resize(223+kept.len(), 0);
+            rs255w223::encode_punctured(&mut codeword, &punctured);
+            assert_eq!(&codeword[..223], &data[..]);
+            for (i, p) in kept.iter().enumerate() {
+                assert_eq!(codeword[223+i], full[223+p]);
+            }
+
+            let mut corrupted = codeword.clone();
+            assert_eq!(rs255w223::correct_punctured(&mut corrupted, &punctured, &[]), Ok(0));
+            assert_eq!(corrupted, codeword);
+
+            // correct up to (k-p) known erasures, including in the parity
+            let erasures = (0..kept.len()).map(|i| codeword.len()-1-i*7).collect::<Vec<_>>();
+            let mut corrupted = codeword.clone();
+            for &j in &erasures {
+                corrupted[j] ^= 0xff;
+            }
+            assert_eq!(rs255w223::correct_punctured(&mut corrupted, &punctured, &erasures), Ok(kept.len()));
+            assert_eq!(corrupted, codeword);
+
+            // correct up to (k-p)/2 unknown errors
+            let mut corrupted = codeword.clone();
+            for j in (0..kept.len()/2).map(|i| i*13) {
+                corrupted[j] ^= 0xff;
+            }
+            assert_eq!(rs255w223::correct_punctured(&mut corrupted, &punctured, &[]), Ok(kept.len()/2));
+            assert_eq!(corrupted, codeword);
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_message() {
+        for len in [0, 1, 223, 224, 1000] {
+            let message = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut encoded = encode_message(&message);
+            assert_eq!(encoded, rs255w223::encode_stream(&message));
+
+            // correct up to k/2 errors in each codeword
+            let original = encoded.clone();
+            for codeword in encoded.chunks_mut(255) {
+                for x in &mut codeword[..16] {
+                    *x ^= 0xff;
+                }
+            }
+            let errors = encoded.iter().zip(&original).filter(|(a, b)| a != b).count();
+            let mut corrected = encoded.clone();
+            assert_eq!(correct_message(&mut corrected), Ok(errors));
+            assert_eq!(corrected, message);
+
+            // but not more, leaving the message unmodified
+            if len > 0 {
+                encoded[0..17].fill(b'y');
+                let before = encoded.clone();
+                assert_eq!(correct_message(&mut encoded), Err(Error::TooManyErrors));
+                assert_eq!(encoded, before);
+            }
+        }
+
+        // truncated messages can't be corrected
+        let mut encoded = encode_message(b"Hello World!");
+        encoded.truncate(32);
+        assert_eq!(correct_message(&mut encoded), Err(Error::TooManyErrors));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w223_stream() {
+        for len in [0usize, 1, 100, 223, 224, 446, 1000] {
+            let message = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+            let mut stream = rs255w223::encode_stream(&message);
+            assert_eq!(stream.len(), len + len.div_ceil(223)*32);
+            assert_eq!(rs255w223::decode_stream(&stream).as_ref(), Ok(&message));
+
+            // correct up to k/2 errors in each codeword
+            for codeword in stream.chunks_mut(255) {
+                let errors = (codeword.len()-32).min(16);
+                codeword[..errors].fill(b'x');
+            }
+            assert_eq!(rs255w223::decode_stream(&stream).as_ref(), Ok(&message));
+
+            // but not more
+            if len > 16 {
+                stream[..17].fill(b'x');
+                assert_eq!(rs255w223::decode_stream(&stream), Err(rs255w223::Error::TooManyErrors));
+            }
+        }
+
+        // truncated streams are an error
+        let stream = rs255w223::encode_stream(&[1u8; 300]);
+        assert_eq!(rs255w223::decode_stream(&stream[..255+32]), Err(rs255w223::Error::TooManyErrors));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w223_soft() {
+        let mut data = (0..255).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        rs255w223::encode(&mut data);
+
+        // with no reliability information we can correct up to k/2 errors
+        let flat = [1.0f32; 255];
+        let mut corrupted = data.clone();
+        for i in 0..16 { corrupted[i*15] ^= 0xff; }
+        assert_eq!(rs255w223::correct_soft(&mut corrupted, &flat), Ok(16));
+        assert_eq!(corrupted, data);
+
+        // but with reliability information we can correct up to k errors
+        // if they are in unreliable symbols
+        for (errors, reliability) in [(17, 0.25), (24, 0.25), (32, 0.0)] {
+            let mut corrupted = data.clone();
+            let mut reliabilities = [1.0f32; 255];
+            for i in 0..errors {
+                corrupted[i*7] ^= 0xff;
+                reliabilities[i*7] = reliability;
+            }
+            let res = rs255w223::correct_soft(&mut corrupted, &reliabilities);
+            assert_eq!(res, Ok(errors));
+            assert_eq!(corrupted, data);
+        }
+
+        // a mix of unreliable erasures and reliable errors
+        let mut corrupted = data.clone();
+        let mut reliabilities = [1.0f32; 255];
+        for i in 0..20 {
+            corrupted[i] ^= 0xff;
+            reliabilities[i] = 0.05;
+        }
+        for i in 0..3 {
+            corrupted[100+i*10] ^= 0xff;
+        }
+        // and some unreliable but correct symbols
+        for i in 0..6 {
+            reliabilities[200+i] = 0.2;
+        }
+        let res = rs255w223::correct_soft(&mut corrupted, &reliabilities);
+        assert!(res.is_ok());
+        assert_eq!(corrupted, data);
+
+        // too many errors, note erasing ECC_SIZE symbols always "succeeds",
+        // so this relies on rejecting the result
+        for errors in [17, 40] {
+            let mut corrupted = data.clone();
+            for i in 0..errors { corrupted[i*6] ^= 0xff; }
+            let res = rs255w223::correct_soft(&mut corrupted, &flat);
+            assert_eq!(res, Err(rs255w223::Error::TooManyErrors));
+        }
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn rs255w223_io() {
+        use std::io::{Read, Write};
+
+        for len in [0, 1, 100, 223, 224, 1000] {
+            let message = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+
+            // write in odd-sized pieces
+            let mut writer = rs255w223::RsWriter::new(Vec::new());
+            for chunk in message.chunks(7) {
+                writer.write_all(chunk).unwrap();
+            }
+            writer.flush().unwrap();
+            let mut stream = writer.finish().unwrap();
+            assert_eq!(stream, rs255w223::encode_stream(&message));
+
+            // dropping the writer also writes the last codeword
+            let mut dropped = Vec::new();
+            {
+                let mut writer = rs255w223::RsWriter::new(&mut dropped);
+                writer.write_all(&message).unwrap();
+            }
+            assert_eq!(dropped, stream);
+
+            // corrupt up to k/2 errors in each codeword
+            for codeword in stream.chunks_mut(255) {
+                let errors = (codeword.len()-32).min(16);
+                for x in &mut codeword[..errors] {
+                    *x ^= 0xff;
+                }
+            }
+
+            // read in odd-sized pieces
+            let mut reader = rs255w223::RsReader::new(&stream[..]);
+            let mut decoded = Vec::new();
+            let mut buf = [0u8; 13];
+            loop {
+                let n = reader.read(&mut buf).unwrap();
+                if n == 0 {
+                    break;
+                }
+                decoded.extend_from_slice(&buf[..n]);
+            }
+            assert_eq!(decoded, message);
+            assert_eq!(reader.corrected(), stream.chunks(255).map(|c| (c.len()-32).min(16)).sum::<usize>());
+
+            // too many errors is an error
+            if len > 16 {
+                stream[..17].fill(b'x');
+                let mut reader = rs255w223::RsReader::new(&stream[..]);
+                let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+                assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            }
+        }
+    }
+
+    #[cfg(feature="async")]
+    #[test]
+    fn rs255w223_async() {
+        use std::io;
+        use std::pin::Pin;
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake, Waker};
+        use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+        // a reader/writer that is pending before each chunk
+        struct Chunks {
+            data: Vec<u8>,
+            n: usize,
+            pending: bool,
+            err: Option<usize>,
+        }
+
+        impl Chunks {
+            fn new(data: &[u8], n: usize) -> Self {
+                Chunks { data: data.to_vec(), n, pending: false, err: None }
+            }
+
+            fn poll(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<usize>> {
+                self.pending = !self.pending;
+                if self.pending {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                match self.err {
+                    Some(0) => Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "oops"))),
+                    Some(ref mut i) => { *i -= 1; Poll::Ready(Ok(self.n)) }
+                    None => Poll::Ready(Ok(self.n)),
+                }
+            }
+        }
+
+        impl AsyncRead for Chunks {
+            fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+                let this = self.get_mut();
+                let n = match this.poll(cx) {
+                    Poll::Ready(Ok(n)) => n.min(buf.remaining()).min(this.data.len()),
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                };
+                buf.put_slice(&this.data[..n]);
+                this.data.drain(..n);
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        impl AsyncWrite for Chunks {
+            fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+                let this = self.get_mut();
+                let n = match this.poll(cx) {
+                    Poll::Ready(Ok(n)) => n.min(buf.len()),
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                };
+                this.data.extend_from_slice(&buf[..n]);
+                Poll::Ready(Ok(n))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn block_on<T>(mut f: impl FnMut(&mut Context<'_>) -> Poll<T>) -> T {
+            struct Noop;
+            impl Wake for Noop {
+                fn wake(self: Arc<Self>) {}
+            }
+            let waker = Waker::from(Arc::new(Noop));
+            let mut cx = Context::from_waker(&waker);
+
+            loop {
+                if let Poll::Ready(x) = f(&mut cx) {
+                    return x;
+                }
+            }
+        }
+
+        fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, mut data: &[u8], n: usize) -> io::Result<()> {
+            while !data.is_empty() {
+                let m = data.len().min(n);
+                let m = block_on(|cx| Pin::new(&mut *writer).poll_write(cx, &data[..m]))?;
+                data = &data[m..];
+            }
+            block_on(|cx| Pin::new(&mut *writer).poll_shutdown(cx))
+        }
+
+        fn read_to_end<R: AsyncRead + Unpin>(reader: &mut R, n: usize) -> io::Result<Vec<u8>> {
+            let mut data = Vec::new();
+            loop {
+                let mut buf = vec![0; n];
+                let mut buf = ReadBuf::new(&mut buf);
+                block_on(|cx| Pin::new(&mut *reader).poll_read(cx, &mut buf))?;
+                if buf.filled().is_empty() {
+                    return Ok(data);
+                }
+                data.extend_from_slice(buf.filled());
+            }
+        }
+
+        for len in [0, 1, 100, 223, 224, 1000] {
+            let message = (0..len).map(|i| i as u8).collect::<Vec<u8>>();
+
+            // encode in odd-sized pieces
+            let mut writer = rs255w223::AsyncRsWriter::new(Chunks::new(&[], 11));
+            write_all(&mut writer, &message, 7).unwrap();
+            let mut stream = writer.into_inner().data;
+            assert_eq!(stream, rs255w223::encode_stream(&message));
+
+            // corrupt up to k/2 errors in each codeword
+            for codeword in stream.chunks_mut(255) {
+                let errors = (codeword.len()-32).min(16);
+                for x in &mut codeword[..errors] {
+                    *x ^= 0xff;
+                }
+            }
+
+            // decode in odd-sized pieces
+            let mut reader = rs255w223::AsyncRsReader::new(Chunks::new(&stream, 13));
+            let decoded = read_to_end(&mut reader, 17).unwrap();
+            assert_eq!(decoded, message);
+            assert_eq!(reader.corrected(), stream.chunks(255).map(|c| (c.len()-32).min(16)).sum::<usize>());
+
+            // too many errors is an error
+            if len > 16 {
+                stream[..17].fill(b'x');
+                let mut reader = rs255w223::AsyncRsReader::new(Chunks::new(&stream, 13));
+                let err = read_to_end(&mut reader, 17).unwrap_err();
+                assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            }
+        }
+
+        // errors from the underlying reader/writer are passed through
+        let mut inner = Chunks::new(&[], 4);
+        inner.err = Some(1);
+        let mut writer = rs255w223::AsyncRsWriter::new(inner);
+        let err = write_all(&mut writer, b"Hello World!", 4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+
+        let mut inner = Chunks::new(&rs255w223::encode_stream(b"Hello World!"), 4);
+        inner.err = Some(1);
+        let mut reader = rs255w223::AsyncRsReader::new(inner);
+        let err = read_to_end(&mut reader, 17).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    }
+
+    // try a GF(2^16) RS scheme, both full and shortened
+    #[test]
+    fn rs65535w65503() {
+        for len in [65535, 1032] {
+            let mut data = (0..len).map(|i| (i*7) as u16).collect::<Vec<u16>>();
+            rs65535w65503::encode(&mut data);
+            assert!(rs65535w65503::is_correct(&data));
+            let expected = data.clone();
+
+            // correct up to k known erasures
+            let mut corrupted = data.clone();
+            corrupted[100..132].fill(0xffff);
+            let res = rs65535w65503::correct_erasures(&mut corrupted, &(100..132).collect::<Vec<_>>());
+            assert_eq!(res.ok(), Some(32));
+            assert_eq!(corrupted, expected);
+
+            // correct up to k/2 unknown errors, spread out
+            let mut corrupted = data.clone();
+            for i in 0..16 {
+                corrupted[i*(len/16)] ^= 0x1234;
+            }
+            let res = rs65535w65503::correct_errors(&mut corrupted);
+            assert_eq!(res.ok(), Some(16));
+            assert_eq!(corrupted, expected);
+
+            // but not more
+            corrupted[0..17].fill(0xffff);
+            assert!(rs65535w65503::correct_errors(&mut corrupted).is_err());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs4294967295w4294967263() {
+        let len = 1032;
+        let mut data = (0..len).map(|i| (i*0x10001) as u32).collect::<Vec<u32>>();
+        rs4294967295w4294967263::encode(&mut data);
+        assert!(rs4294967295w4294967263::is_correct(&data));
+        let expected = data.clone();
+
+        // correct up to k known erasures
+        let mut corrupted = data.clone();
+        corrupted[100..132].fill(0xffffffff);
+        let res = rs4294967295w4294967263::correct_erasures(&mut corrupted, &(100..132).collect::<Vec<_>>());
+        assert_eq!(res.ok(), Some(32));
+        assert_eq!(corrupted, expected);
+
+        // correct up to k/2 unknown errors, spread out
+        let mut corrupted = data.clone();
+        for i in 0..16 {
+            corrupted[i*(len/16)] ^= 0x12345678;
+        }
+        let res = rs4294967295w4294967263::correct_errors(&mut corrupted);
+        assert_eq!(res.ok(), Some(16));
+        assert_eq!(corrupted, expected);
+
+        // but not more
+        corrupted[0..17].fill(0xffffffff);
+        assert!(rs4294967295w4294967263::correct_errors(&mut corrupted).is_err());
+
+        // codewords longer than our decoder tables need to compute some
+        // powers as needed
+        let len = rs4294967295w4294967263::DECODER_TABLE_SIZE + 100;
+        let mut data = (0..len).map(|i| (i*0x10001) as u32).collect::<Vec<u32>>();
+        rs4294967295w4294967263::Encoder::new().encode(&mut data);
+        let expected = data.clone();
+        for i in 0..16 {
+            data[i*(len/16)] ^= 0x12345678;
+        }
+        let decoder = rs4294967295w4294967263::Decoder::new();
+        assert_eq!(decoder.correct_errors(&mut data), Ok(16));
+        assert_eq!(data, expected);
+    }
+
+    #[cfg(feature="rayon")]
+    #[test]
+    fn rs255w223_blocks() {
+        let mut codewords = (0..100)
+            .map(|i| {
+                let mut codeword = (0..255).map(|j| (i*j) as u8).collect::<Vec<u8>>();
+                codeword.truncate(100 + i);
+                codeword
+            })
+            .collect::<Vec<_>>();
+        rs255w223::encode_blocks(&mut codewords);
+
+        // should match a serial loop
+        for (i, codeword) in codewords.iter().enumerate() {
+            let mut expected = (0..255).map(|j| (i*j) as u8).collect::<Vec<u8>>();
+            expected.truncate(100 + i);
+            rs255w223::encode(&mut expected);
+            assert_eq!(codeword, &expected);
+        }
+        let expected = codewords.clone();
+
+        // correct up to k/2 errors per codeword
+        for (i, codeword) in codewords.iter_mut().enumerate() {
+            codeword[0..i%17].iter_mut().for_each(|x| *x ^= 0x55);
+        }
+        // but not more
+        codewords[50][0..17].iter_mut().for_each(|x| *x ^= 0xaa);
+
+        let results = rs255w223::correct_blocks(&mut codewords);
+        for (i, res) in results.iter().enumerate() {
+            if i == 50 {
+                assert_eq!(*res, Err(rs255w223::Error::TooManyErrors));
+            } else {
+                assert_eq!(*res, Ok(i%17));
+                assert_eq!(codewords[i], expected[i]);
+            }
+        }
+    }
+
+    // list decoding
+    #[rs(block=32, data=8)]
+    mod rs32w8 {}
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs32w8_list() {
+        assert_eq!(rs32w8::correct_list_radius(32, 1), 14);
+        assert_eq!(rs32w8::correct_list_radius(32, 2), 15);
+        assert_eq!(rs32w8::correct_list_radius(28, 1), 16);
+
+        for len in [32, 28] {
+            let mut codeword = (0..len).map(|i| (i*i*7) as u8).collect::<Vec<u8>>();
+            rs32w8::encode(&mut codeword);
+
+            for m in [1, 2] {
+                let radius = rs32w8::correct_list_radius(len, m);
+                for errors in [0, 1, 12, radius] {
+                    let mut corrupted = codeword.clone();
+                    for i in 0..errors {
+                        corrupted[(i*7) % len] ^= 0x55 + i as u8;
+                    }
+
+                    let codewords = rs32w8::correct_list(&corrupted, m);
+                    assert!(codewords.contains(&codeword));
+                    // all codewords should be valid and within our radius
+                    for c in &codewords {
+                        assert!(rs32w8::is_correct(c));
+                        assert!(c.iter().zip(&corrupted).filter(|(a, b)| a != b).count() <= radius);
+                    }
+                }
+            }
+        }
+
+        // note this should be beyond what unique decoding can correct
+        let mut codeword = (0..32).collect::<Vec<u8>>();
+        rs32w8::encode(&mut codeword);
+        let mut corrupted = codeword.clone();
+        corrupted[0..15].fill(b'x');
+        assert!(rs32w8::correct_errors(&mut corrupted.clone()).is_err());
+        assert!(rs32w8::correct_list(&corrupted, 2).contains(&codeword));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs26w16_list() {
+        // high-rate codes should still find what unique decoding finds
+        let mut codeword = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut codeword);
+        for errors in 0..=5 {
+            let mut corrupted = codeword.clone();
+            corrupted[0..errors].fill(b'x');
+            assert_eq!(rs26w16::correct_list(&corrupted, 1)[0], codeword);
+        }
+    }
+
+    // Reed-Solomon using the Euclidean algorithm
+    #[rs(block=26, data=16, euclidean)]
+    pub mod rs26w16_euclidean {}
+
+    #[rs(block=255, data=223, euclidean)]
+    pub mod rs255w223_euclidean {}
+
+    #[test]
+    fn rs_euclidean() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16_euclidean::encode(&mut data);
+
+        // correct up to k/2 unknown errors, with any mix of erasures
+        for erasures in 0..=(26-16) {
+            for errors in 0..=(26-16-erasures)/2 {
+                let mut corrupted = data.clone();
+                corrupted[0..erasures].fill(b'x');
+                corrupted[20..20+errors].fill(b'x');
+                let res = rs26w16_euclidean::correct(&mut corrupted, &(0..erasures).collect::<Vec<_>>());
+                assert_eq!(res.ok(), Some(erasures+errors));
+                assert_eq!(corrupted, data);
+            }
+        }
+
+        // cross-validate against Berlekamp-Massey
+        let mut data = (0..255).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        rs255w223::encode(&mut data);
+        let mut x = 1u32;
+        for errors in 0..=24 {
+            let mut corrupted = data.clone();
+            for _ in 0..errors {
+                // simple xorshift for pseudo-random error locations
+                x ^= x << 13; x ^= x >> 17; x ^= x << 5;
+                corrupted[(x % 255) as usize] ^= (x >> 8) as u8 | 1;
+            }
+
+            let mut bm = corrupted.clone();
+            let mut euclidean = corrupted.clone();
+            let bm_res = rs255w223::correct_errors(&mut bm);
+            let euclidean_res = rs255w223_euclidean::correct_errors(&mut euclidean);
+            assert_eq!(bm_res.is_ok(), euclidean_res.is_ok());
+            if bm_res.is_ok() {
+                assert_eq!(bm_res.ok(), euclidean_res.ok());
+                assert_eq!(bm, euclidean);
+            }
+            if errors <= 16 {
+                assert_eq!(bm, data);
+            }
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_berlekamp_welch() {
+        let mut data = (0..26).collect::<Vec<u8>>();
+        rs26w16::encode(&mut data);
+
+        // correct up to k/2 unknown errors
+        for i in 0..=(26-16)/2 {
+            let mut corrupted = data.clone();
+            corrupted[0..i].fill(b'x');
+            assert_eq!(rs26w16::correct_errors_berlekamp_welch(&mut corrupted), Ok(i));
+            assert_eq!(corrupted, data);
+        }
+
+        // cross-validate against Berlekamp-Massey, including shortened
+        // codewords
+        for len in [255, 100, 32] {
+            let mut data = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            rs255w223::encode(&mut data);
+            let mut x = 1u32;
+            for errors in 0..=24 {
+                let mut corrupted = data.clone();
+                for _ in 0..errors {
+                    x ^= x << 13; x ^= x >> 17; x ^= x << 5;
+                    corrupted[(x as usize) % len] ^= (x >> 8) as u8 | 1;
+                }
+
+                let mut bm = corrupted.clone();
+                let mut bw = corrupted.clone();
+                let bm_res = rs255w223::correct_errors(&mut bm);
+                let bw_res = rs255w223::correct_errors_berlekamp_welch(&mut bw);
+                assert_eq!(bm_res.is_ok(), bw_res.is_ok());
+                if bm_res.is_ok() {
+                    assert_eq!(bm_res, bw_res);
+                    assert_eq!(bm, bw);
+                }
+                if errors <= 16 {
+                    assert_eq!(bw, data);
+                }
+            }
+        }
+    }
+
+    // runtime-configurable RS
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_runtime() {
+        // should match rs255w223
+        let rs = Rs::new(0x11d, 255, 223, 0, 0x02).unwrap();
+        assert_eq!(rs.generator_poly(), &rs255w223::GENERATOR_POLY.iter().map(|x| x.get()).collect::<Vec<u8>>()[..]);
+        for len in [32, 40, 255] {
+            let mut data = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            let mut expected = data.clone();
+            rs.encode(&mut data);
+            rs255w223::encode(&mut expected);
+            assert_eq!(data, expected);
+        }
+
+        // try a couple of different parameters, including CCSDS's, note
+        // CCSDS also uses a dual-basis representation, see rs_ccsds
+        for (polynomial, block, data, fcr, primitive) in [
+            (0x11d, 255, 223, 0, 0x02),
+            (0x11d, 26, 16, 1, 0x02),
+            (0x187, 255, 223, 112, 0xad),
+            (0x12b, 40, 30, 120, 0x02),
+            (0x11d, 20, 20, 0, 0x02),
+        ] {
+            let rs = Rs::new(polynomial, block, data, fcr, primitive).unwrap();
+            let ecc = block - data;
+
+            for len in [block, ecc+10] {
+                let mut codeword = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+                rs.encode(&mut codeword);
+                assert!(rs.is_correct(&codeword));
+                let expected = codeword.clone();
+
+                // correct up to k known erasures
+                for i in 0..=ecc {
+                    let mut corrupted = expected.clone();
+                    corrupted[0..i].fill(b'x');
+                    let res = rs.correct_erasures(&mut corrupted, &(0..i).collect::<Vec<_>>());
+                    assert_eq!(res.ok(), Some(i));
+                    assert_eq!(corrupted, expected);
+                }
+
+                // correct up to k/2 unknown errors
+                for i in 0..=ecc/2 {
+                    let mut corrupted = expected.clone();
+                    for j in 0..i {
+                        corrupted[j*len/(ecc/2+1)] ^= 0x55;
+                    }
+                    let res = rs.correct_errors(&mut corrupted);
+                    assert_eq!(res.ok(), Some(i));
+                    assert_eq!(corrupted, expected);
+                }
+
+                // correct a mix of both
+                for erasures in 0..=ecc {
+                    let errors = (ecc-erasures)/2;
+                    let mut corrupted = expected.clone();
+                    corrupted[0..erasures].fill(b'x');
+                    corrupted[len-errors..].iter_mut().for_each(|x| *x ^= 0x55);
+                    let res = rs.correct(&mut corrupted, &(0..erasures).collect::<Vec<_>>());
+                    assert_eq!(res.ok(), Some(erasures+errors));
+                    assert_eq!(corrupted, expected);
+                }
+            }
+        }
+
+        // invalid parameters
+        assert_eq!(Rs::new(0x11d, 256, 223, 0, 0x02).err(), Some(Error::InvalidParameters));
+        assert_eq!(Rs::new(0x11d, 255, 256, 0, 0x02).err(), Some(Error::InvalidParameters));
+        assert_eq!(Rs::new(0x1d, 255, 223, 0, 0x02).err(), Some(Error::InvalidParameters));
+        // reducible polynomial
+        assert_eq!(Rs::new(0x101, 255, 223, 0, 0x02).err(), Some(Error::InvalidParameters));
+        // irreducible but not primitive polynomial, x is not primitive
+        assert_eq!(Rs::new(0x11b, 255, 223, 0, 0x02).err(), Some(Error::InvalidParameters));
+        assert!(Rs::new(0x11b, 255, 223, 0, 0x03).is_ok());
+        // zero is never primitive
+        assert_eq!(Rs::new(0x11d, 255, 223, 0, 0x00).err(), Some(Error::InvalidParameters));
+    }
+
+    // PAR2-compatible RS
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_par2() {
+        // PAR2's constants
+        assert_eq!(
+            (0..10).map(|i| par2::input_constant(i).get()).collect::<Vec<_>>(),
+            &[2, 4, 16, 128, 256, 2048, 8192, 16384, 4107, 32856]
+        );
+        assert_eq!(par2::gf2p16(0x8000) * par2::gf2p16(2), par2::gf2p16(0x100b));
+
+        // recovery slice 0 is just the xor of all input slices
+        let inputs = (0..10)
+            .map(|i| (0..64).map(|j| (i*64+j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let mut recovery0 = vec![0u8; 64];
+        par2::encode(&inputs, 0, &mut recovery0);
+        for j in 0..64 {
+            assert_eq!(recovery0[j], inputs.iter().fold(0, |x, b| x ^ b[j]));
+        }
+
+        // repair up to as many input slices as we have recovery slices
+        let recovery = (0..5u16)
+            .map(|e| {
+                let mut r = vec![0u8; 64];
+                par2::encode(&inputs, e, &mut r);
+                (e, r)
+            })
+            .collect::<Vec<_>>();
+        for missing in [&[][..], &[3], &[0, 9], &[1, 2, 3], &[0, 2, 4, 6, 8], &[5, 6, 7, 8, 9]] {
+            let mut damaged = inputs.clone();
+            for &i in missing {
+                damaged[i].fill(b'x');
+            }
+            assert_eq!(par2::repair(&mut damaged, missing, &recovery), Ok(()));
+            assert_eq!(damaged, inputs);
+        }
+
+        // using any recovery exponents
+        let mut damaged = inputs.clone();
+        damaged[2].fill(b'x');
+        damaged[7].fill(b'x');
+        assert_eq!(par2::repair(&mut damaged, &[2, 7], &recovery[3..]), Ok(()));
+        assert_eq!(damaged, inputs);
+
+        // but not more
+        let mut damaged = inputs.clone();
+        assert_eq!(
+            par2::repair(&mut damaged, &[0, 1, 2, 3, 4, 5], &recovery),
+            Err(Error::TooManyErrors)
+        );
+    }
+
+    // QR-code RS
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_qr() {
+        // generator polynomials from ISO/IEC 18004 annex A, as logs
+        let poly = [0, 87, 229, 146, 149, 238, 102, 21].iter()
+            .map(|&l| gf256::GENERATOR.pow(l))
+            .collect::<Vec<_>>();
+        assert_eq!(&qr::ecc7::GENERATOR_POLY[..], &poly[..]);
+
+        // "HELLO WORLD" in version 1-M
+        let data = [
+            0x20, 0x5b, 0x0b, 0x78, 0xd1, 0x72, 0xdc, 0x4d,
+            0x43, 0x40, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11,
+        ];
+        let codewords = qr::encode_symbol(1, qr::EcLevel::M, &data);
+        assert_eq!(&codewords[16..], &[
+            0xc4, 0x23, 0x27, 0x77, 0xeb, 0xd7, 0xe7, 0xe2, 0x5d, 0x17,
+        ]);
+        assert!(qr::is_correct(&codewords, 10));
+
+        // data capacities from ISO/IEC 18004 table 7
+        for (version, level, data_size, total_size) in [
+            (1,  qr::EcLevel::L,   19,   26),
+            (1,  qr::EcLevel::H,    9,   26),
+            (5,  qr::EcLevel::Q,   62,  134),
+            (10, qr::EcLevel::M,  216,  346),
+            (40, qr::EcLevel::L, 2956, 3706),
+            (40, qr::EcLevel::H, 1276, 3706),
+        ] {
+            let layout = qr::ec_blocks(version, level);
+            assert_eq!(layout.data_size(), data_size);
+            assert_eq!(layout.total_size(), total_size);
+        }
+
+        // every version/level should be encodable and correctable
+        for version in 1..=40 {
+            for level in [qr::EcLevel::L, qr::EcLevel::M, qr::EcLevel::Q, qr::EcLevel::H] {
+                let layout = qr::ec_blocks(version, level);
+                let data = (0..layout.data_size()).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+                let mut codewords = qr::encode_symbol(version, level, &data);
+                assert_eq!(codewords.len(), layout.total_size());
+                assert_eq!(qr::decode_symbol(version, level, &codewords).as_ref(), Ok(&data));
+
+                // interleaving means a burst of ecc_size/2 errors per
+                // block is correctable
+                let burst = layout.blocks() * (layout.ecc_size/2);
+                for x in &mut codewords[..burst] {
+                    *x ^= 0xff;
+                }
+                assert_eq!(qr::decode_symbol(version, level, &codewords).as_ref(), Ok(&data));
+            }
+        }
+    }
+
+    // CCSDS RS with dual-basis representation
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_ccsds() {
+        // the CCSDS generator polynomial, in the conventional basis
+        assert_eq!(ccsds::rs().generator_poly(), &[
+            0x01, 0x5b, 0x7f, 0x56, 0x10, 0x1e, 0x0d, 0xeb,
+            0x61, 0xa5, 0x08, 0x2a, 0x36, 0x56, 0xab, 0x20,
+            0x71, 0x20, 0xab, 0x56, 0x36, 0x2a, 0x08, 0xa5,
+            0x61, 0xeb, 0x0d, 0x1e, 0x10, 0x56, 0x7f, 0x5b,
+            0x01,
+        ]);
+
+        // dual-basis tables
+        assert_eq!(&ccsds::TO_DUAL_BASIS[..8], &[0x00, 0x7b, 0xaf, 0xd4, 0x99, 0xe2, 0x36, 0x4d]);
+        for i in 0..=255u8 {
+            assert_eq!(ccsds::FROM_DUAL_BASIS[usize::from(ccsds::TO_DUAL_BASIS[usize::from(i)])], i);
+        }
+
+        // encoding in the dual basis is the same as converting, encoding in
+        // the conventional basis, and converting back
+        for len in [255, 100, 33] {
+            let mut data = (0..len).map(|i| (i*7+1) as u8).collect::<Vec<u8>>();
+            let mut expected = data.clone();
+            ccsds::encode(&mut data);
+            ccsds::from_dual_basis(&mut expected);
+            ccsds::rs().encode(&mut expected);
+            ccsds::to_dual_basis(&mut expected);
+            assert_eq!(data, expected);
+            assert!(ccsds::is_correct(&data));
+
+            // but not the same as leaving out the conversion
+            let mut conventional = data.clone();
+            ccsds::rs().encode(&mut conventional);
+            assert_ne!(conventional, data);
+
+            // correct up to k known erasures
+            let mut corrupted = data.clone();
+            corrupted[0..32].fill(b'x');
+            assert_eq!(ccsds::correct_erasures(&mut corrupted, &(0..32).collect::<Vec<_>>()), Ok(32));
+            assert_eq!(corrupted, data);
+
+            // correct up to k/2 unknown errors
+            let mut corrupted = data.clone();
+            corrupted[0..16].fill(b'x');
+            assert_eq!(ccsds::correct_errors(&mut corrupted), Ok(16));
+            assert_eq!(corrupted, data);
+
+            // correct a mix
+            let mut corrupted = data.clone();
+            corrupted[0..10].fill(b'x');
+            corrupted[20..30].fill(b'x');
+            assert_eq!(ccsds::correct(&mut corrupted, &(0..10).collect::<Vec<_>>()), Ok(20));
+            assert_eq!(corrupted, data);
+
+            // but not more
+            let mut corrupted = data.clone();
+            corrupted[0..17].fill(b'x');
+            assert!(ccsds::correct_errors(&mut corrupted).is_err());
+        }
+
+        // interleaved frames
+        for depth in [1, 2, 5, 8] {
+            let mut frame = (0..depth*255).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            ccsds::encode_interleaved(&mut frame, depth);
+            for i in 0..depth {
+                let codeword = frame[i..].iter().step_by(depth).copied().collect::<Vec<u8>>();
+                assert!(ccsds::is_correct(&codeword));
+            }
+
+            // a burst of 16*depth errors is correctable
+            let expected = frame.clone();
+            for x in &mut frame[100..100+16*depth] {
+                *x ^= 0xff;
+            }
+            assert_eq!(ccsds::correct_errors_interleaved(&mut frame, depth).ok(), Some(16*depth));
+            assert_eq!(frame, expected);
+        }
+    }
+
+    // RS with a CRC
+    #[cfg(all(feature="alloc", feature="crc"))]
+    #[test]
+    fn rs_checked() {
+        for len in [64, 100, 255] {
+            let mut data = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            checked::encode(&mut data);
+            assert!(checked::is_correct(&data));
+            assert!(rs255w223::is_correct(&data));
+            assert_eq!(&data[len-36..len-32], &crate::crc::crc32c(&data[..len-36], 0).to_be_bytes());
+
+            // correct up to k known erasures
+            let mut corrupted = data.clone();
+            corrupted[len-32..].fill(b'x');
+            assert_eq!(checked::correct_erasures(&mut corrupted, &(len-32..len).collect::<Vec<_>>()), Ok(32));
+            assert_eq!(corrupted, data);
+
+            // correct up to k/2 unknown errors, including in the crc
+            let mut corrupted = data.clone();
+            corrupted[len-48..len-32].fill(b'x');
+            assert_eq!(checked::correct_errors(&mut corrupted), Ok(16));
+            assert_eq!(corrupted, data);
+
+            // correct a mix
+            let mut corrupted = data.clone();
+            corrupted[0..20].fill(b'x');
+            assert_eq!(checked::correct(&mut corrupted, &(0..12).collect::<Vec<_>>()), Ok(20));
+            assert_eq!(corrupted, data);
+
+            // a valid RS codeword with the wrong CRC, as would be found by
+            // a miscorrection, is rejected
+            let mut miscorrected = data.clone();
+            miscorrected[0] ^= 0xff;
+            rs255w223::encode(&mut miscorrected);
+            assert!(!checked::is_correct(&miscorrected));
+            let mut corrupted = miscorrected.clone();
+            corrupted[10..26].fill(b'x');
+            let before = corrupted.clone();
+            assert_eq!(rs255w223::correct_errors(&mut corrupted.clone()), Ok(16));
+            assert_eq!(checked::correct_errors(&mut corrupted), Err(Error::TooManyErrors));
+            assert_eq!(corrupted, before);
+        }
+    }
+
+    // concatenated Reed-Solomon + convolutional coding
+    #[cfg(feature="conv")]
+    #[test]
+    fn rs_concat() {
+        // the inner code on its own
+        for len in [0, 1, 10, 100] {
+            let frame = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            let mut coded = concat::encode_inner(&frame);
+            assert_eq!(coded.len(), concat::encoded_inner_size(len));
+            assert_eq!(concat::decode_inner(&coded, len), frame);
+
+            // correct scattered bit errors
+            for i in (0..8*coded.len()).step_by(23) {
+                coded[i/8] ^= 1 << (i%8);
+            }
+            assert_eq!(concat::decode_inner(&coded, len), frame);
+        }
+
+        for (len, depth) in [(1, 1), (223, 1), (64, 2), (1115, 5)] {
+            let data = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            let coded = concat::encode(&data, depth);
+            assert_eq!(coded.len(), concat::encoded_size(len, depth));
+            assert_eq!(concat::decode(&coded, depth), Ok(data.clone()));
+
+            // scattered errors are corrected by the inner code, and bursts
+            // by the outer code
+            let mut corrupted = coded.clone();
+            for i in (0..8*corrupted.len()).step_by(29) {
+                corrupted[i/8] ^= 1 << (i%8);
+            }
+            corrupted[10..10+4*depth].fill(0x55);
+            assert_eq!(concat::decode(&corrupted, depth), Ok(data.clone()));
+
+            // soft decisions, with noise that would be errors with hard
+            // decisions
+            let bits = crate::conv::K7R12_CCSDS.encoded_bits(8*(len + depth*ccsds::ECC_SIZE));
+            let mut llrs = (0..bits)
+                .map(|i| if coded[i/8] & (0x80 >> (i%8)) != 0 { -1.0 } else { 1.0 })
+                .collect::<Vec<f32>>();
+            for i in (0..llrs.len()).step_by(5) {
+                llrs[i] = -0.25*llrs[i];
+            }
+            assert_eq!(concat::decode_soft(&llrs, depth), Ok(data.clone()));
+            assert_eq!(concat::decode_soft(&llrs[..bits-1], depth), Err(Error::InvalidParameters));
+
+            // too many errors, garbage shouldn't decode to our data
+            let mut corrupted = coded.clone();
+            for (i, x) in corrupted.iter_mut().enumerate() {
+                *x = (i*37 + 11) as u8;
+            }
+            assert_ne!(concat::decode(&corrupted, depth), Ok(data.clone()));
+
+            // bad lengths
+            assert_eq!(concat::decode(&coded[..coded.len()-1], depth), Err(Error::InvalidParameters));
+        }
+    }
+
+    // file-level k-of-n shares
+    #[cfg(all(feature="alloc", feature="crc"))]
+    #[test]
+    fn rs_share() {
+        for (len, k, n) in [(0, 1, 1), (1, 1, 3), (100, 3, 5), (1000, 10, 14), (1001, 200, 255)] {
+            let file = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            let shares = share::split(&file, k, n);
+            assert_eq!(shares.len(), n);
+            for (i, s) in shares.iter().enumerate() {
+                let header = share::verify(s).unwrap();
+                assert_eq!((usize::from(header.index), usize::from(header.k), usize::from(header.n)), (i, k, n));
+                assert_eq!(header.length, len as u64);
+            }
+            assert_eq!(share::join(&shares).as_ref(), Ok(&file));
+
+            // any k shares are enough, in any order
+            for skip in [0, (n-k)/2, n-k] {
+                let subset = shares.iter().rev().skip(skip).take(k).collect::<Vec<_>>();
+                assert_eq!(share::join(&subset).as_ref(), Ok(&file));
+                assert_eq!(share::heal(&subset).as_ref(), Ok(&shares));
+            }
+
+            // damaged shares are ignored
+            if len > 0 {
+                let mut damaged = shares.clone();
+                for s in damaged.iter_mut().take(n-k) {
+                    let last = s.len()-1;
+                    s[last] ^= 0x01;
+                    assert_eq!(share::verify(s), None);
+                }
+                assert_eq!(share::join(&damaged).as_ref(), Ok(&file));
+                assert_eq!(share::heal(&damaged).as_ref(), Ok(&shares));
+
+                // but we need k intact shares
+                damaged[n-k][share::HEADER_SIZE] ^= 0x01;
+                assert_eq!(share::join(&damaged), Err(Error::TooManyErrors));
+            }
+        }
+
+        // shares from different files can't be mixed
+        let a = share::split(b"hello", 1, 2);
+        let b = share::split(b"world!", 1, 2);
+        assert_eq!(share::join(&[&a[1], &b[1]]), Err(Error::InvalidParameters));
+    }
+
+    // packet-level FEC
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs_packet() {
+        let header = packet::Header { block: 0x123456, index: 7, k: 5, m: 3 };
+        assert_eq!(header.to_bytes(), [0x12, 0x34, 0x56, 7, 5, 3]);
+        assert_eq!(packet::Header::from_bytes(&header.to_bytes()), Some(header));
+        assert!(header.is_repair());
+        assert_eq!(packet::Header::from_bytes(&[0, 0, 0, 8, 5, 3]), None);
+        assert_eq!(packet::Header::from_bytes(&[0, 0, 0, 0, 0, 3]), None);
+        assert_eq!(packet::Header::from_bytes(&[0, 0, 0, 0, 200, 56]), None);
+
+        for (k, m) in [(1, 0), (1, 1), (5, 3), (10, 10), (200, 55)] {
+            let sources = (0..k)
+                .map(|i| (0..(i*13)%100).map(|j| (i*7+j) as u8).collect::<Vec<u8>>())
+                .collect::<Vec<_>>();
+            let packets = packet::encode(42, &sources, m);
+            assert_eq!(packets.len(), k+m);
+            for (i, p) in packets.iter().enumerate() {
+                let header = packet::Header::from_bytes(p).unwrap();
+                assert_eq!((header.block, usize::from(header.index)), (42, i));
+                if i < k {
+                    assert_eq!(&p[packet::HEADER_SIZE..], &sources[i][..]);
+                }
+            }
+            assert_eq!(packet::decode(&packets).as_ref(), Ok(&sources));
+
+            // any k packets are enough, in any order
+            for skip in [0, m/2, m] {
+                let received = packets.iter().rev().skip(skip).take(k).collect::<Vec<_>>();
+                assert_eq!(packet::decode(&received).as_ref(), Ok(&sources));
+                let received = packets.iter().skip(skip).take(k).collect::<Vec<_>>();
+                assert_eq!(packet::decode(&received).as_ref(), Ok(&sources));
+            }
+
+            // but not k-1
+            if m > 0 {
+                let received = packets.iter().skip(1).take(k-1).collect::<Vec<_>>();
+                assert_eq!(packet::decode(&received), Err(Error::TooManyErrors));
+            }
+        }
+
+        // packets from different blocks can't be mixed
+        let a = packet::encode(1, &[b"a"], 1);
+        let b = packet::encode(2, &[b"b"], 1);
+        assert_eq!(packet::decode(&[&a[1], &b[1]]), Err(Error::InvalidParameters));
+
+        // truncated repair packets are malformed
+        assert_eq!(packet::decode(&[&[0, 0, 0, 1, 1, 1][..]]), Err(Error::InvalidParameters));
+        assert_eq!(packet::decode(&[&[0, 0, 0, 1, 1, 1, 0][..]]), Err(Error::InvalidParameters));
+    }
+
     // try an overly saturated RS scheme
     #[rs(block=64, data=8)]
     mod rs64w8 {}
@@ -1609,8 +3083,269 @@ mod test {
         }
     }
 
+    #[cfg(feature="alloc")]
+    #[test]
+    fn gf16_rs15w8_bounded() {
+        let mut data = (0..15).collect::<Vec<u8>>();
+        gf16_rs15w8::encode(&mut data);
+
+        // up to max_errors are corrected
+        for max_errors in 0..=3 {
+            for errors in 0..=3 {
+                let mut corrupted = data.clone();
+                for i in 0..errors {
+                    corrupted[i*4] ^= 0x5;
+                }
+                let before = corrupted.clone();
+                let res = gf16_rs15w8::correct_bounded(&mut corrupted, &[], max_errors);
+                if errors <= max_errors {
+                    assert_eq!(res, Ok(errors));
+                    assert_eq!(corrupted, data);
+                } else {
+                    assert_eq!(res, Err(gf16_rs15w8::Error::TooManyErrors));
+                    assert_eq!(corrupted, before);
+                }
+            }
+        }
+
+        // erasures don't count towards max_errors
+        let mut corrupted = data.clone();
+        corrupted[0..5].fill(0);
+        corrupted[10] ^= 0x1;
+        assert_eq!(gf16_rs15w8::correct_bounded(&mut corrupted, &[0, 1, 2, 3, 4], 1), Ok(6));
+        assert_eq!(corrupted, data);
+
+        // in a small field, random words are often miscorrected, leaving a
+        // margin avoids most of these
+        let mut x = 0x12345678u32;
+        let mut miscorrections = [0; 4];
+        for _ in 0..10000 {
+            let word = (0..15).map(|_| {
+                x ^= x << 13; x ^= x >> 17; x ^= x << 5;
+                (x & 0xf) as u8
+            }).collect::<Vec<u8>>();
+            for (max_errors, count) in miscorrections.iter_mut().enumerate() {
+                let mut corrupted = word.clone();
+                if gf16_rs15w8::correct_bounded(&mut corrupted, &[], max_errors).is_ok() {
+                    assert!(gf16_rs15w8::is_correct(&corrupted));
+                    *count += 1;
+                }
+            }
+        }
+        assert!(miscorrections[3] > 10*miscorrections[1]);
+        assert!(miscorrections[2] > miscorrections[1]);
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn gf16_rs15w8_matrices() {
+        for n in 7..=15 {
+            let gen = gf16_rs15w8::generator_matrix(n);
+            let check = gf16_rs15w8::parity_check_matrix(n);
+            assert_eq!(gen.len(), n-7);
+            assert_eq!(check.len(), 7);
+
+            // G*H^T = 0
+            for g in &gen {
+                for h in &check {
+                    assert_eq!(g.iter().zip(h).map(|(g, h)| *g * *h).sum::<gf16>(), gf16::new(0));
+                }
+            }
+
+            // m*G = encode(m)
+            let m = (0..n-7).map(|i| gf16::new((i % 16) as u8)).collect::<Vec<_>>();
+            let mut c = vec![gf16::new(0); n];
+            for (m, g) in m.iter().zip(&gen) {
+                for (c, g) in c.iter_mut().zip(g) {
+                    *c += *m * *g;
+                }
+            }
+            let mut codeword = m.iter().map(|m| m.get()).collect::<Vec<u8>>();
+            codeword.resize(n, 0);
+            gf16_rs15w8::encode(&mut codeword);
+            assert_eq!(c.iter().map(|c| c.get()).collect::<Vec<u8>>(), codeword);
+
+            // H*c = syndromes(c)
+            codeword[0] ^= 0x3;
+            let syndromes = check.iter()
+                .map(|h| h.iter().zip(&codeword).map(|(h, c)| *h * gf16::new(*c)).sum::<gf16>())
+                .collect::<Vec<_>>();
+            assert_eq!(syndromes, gf16_rs15w8::syndromes(&codeword));
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn odd_encoder_decoder() {
+        // small fields use a multiplication table, large fields don't
+        let mut data = (0..15).collect::<Vec<u8>>();
+        let mut expected = data.clone();
+        gf16_rs15w8::encode(&mut expected);
+        gf16_rs15w8::Encoder::new().encode(&mut data);
+        assert_eq!(data, expected);
+        data[0..3].fill(0x7);
+        assert_eq!(gf16_rs15w8::Decoder::new().correct_errors(&mut data), Ok(3));
+        assert_eq!(data, expected);
+
+        let mut data = (0..26).collect::<Vec<u32>>();
+        let mut expected = data.clone();
+        gf2p23_rs26w16::encode(&mut expected);
+        gf2p23_rs26w16::Encoder::new().encode(&mut data);
+        assert_eq!(data, expected);
+        data[0..5].fill(0x787878);
+        assert_eq!(gf2p23_rs26w16::Decoder::new().correct_errors(&mut data), Ok(5));
+        assert_eq!(data, expected);
+    }
+
+    // large ECC_SIZE, which may take a different path through our slice
+    // kernels
+    #[rs(block=255, data=127)]
+    mod rs255w127 {}
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs255w127() {
+        let mut data = (0..255).collect::<Vec<u8>>();
+        let mut expected = data.clone();
+        rs255w127::encode(&mut expected);
+        rs255w127::Encoder::new().encode(&mut data);
+        assert_eq!(data, expected);
+        assert!(rs255w127::is_correct(&data));
+
+        // correct up to k/2 unknown errors
+        for i in 0..(255-127)/2 {
+            let mut data = expected.clone();
+            data[0..i].fill(b'x');
+            assert_eq!(rs255w127::correct_errors(&mut data), Ok(i));
+            assert_eq!(data, expected);
+        }
+    }
+
+    // interleaved RS
+    #[rs(block=26, data=16, interleave=4)]
+    mod rs26w16i4 {}
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn rs26w16i4() {
+        let mut frame = (0..4*26).collect::<Vec<u8>>();
+        rs26w16i4::encode_interleaved(&mut frame);
+        assert!(rs26w16i4::is_correct_interleaved(&frame));
+        for i in 0..4 {
+            let codeword = frame.iter().copied().skip(i).step_by(4).collect::<Vec<u8>>();
+            assert!(rs26w16i4::is_correct(&codeword));
+        }
+
+        // correct bursts of up to 4*k known erasures
+        for i in 0..4*(26-16) {
+            let mut frame = frame.clone();
+            frame[50..50+i].fill(b'x');
+            let res = rs26w16i4::correct_erasures_interleaved(&mut frame, &(50..50+i).collect::<Vec<_>>());
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&frame[..4*16], &(0..4*16).collect::<Vec<u8>>());
+        }
+
+        // correct bursts of up to 4*k/2 unknown errors
+        for i in 0..4*(26-16)/2 {
+            let mut frame = frame.clone();
+            frame[30..30+i].fill(b'x');
+            let res = rs26w16i4::correct_errors_interleaved(&mut frame);
+            assert_eq!(res.ok(), Some(i));
+            assert_eq!(&frame[..4*16], &(0..4*16).collect::<Vec<u8>>());
+        }
+
+        // correct a mix of both
+        let mut corrupted = frame.clone();
+        corrupted[0..20].fill(b'x');
+        corrupted[60..68].fill(b'x');
+        let res = rs26w16i4::correct_interleaved(&mut corrupted, &(0..20).collect::<Vec<_>>());
+        assert_eq!(res.ok(), Some(28));
+        assert_eq!(corrupted, frame);
+
+        // but a single codeword still only corrects k/2 errors
+        let mut corrupted = frame.clone();
+        for i in 0..6 {
+            corrupted[4*i+3] = b'x';
+        }
+        // and on error the frame is left unmodified, even the codewords that
+        // could be corrected
+        corrupted[0] = b'x';
+        let expected = corrupted.clone();
+        assert_eq!(rs26w16i4::correct_errors_interleaved(&mut corrupted), Err(rs26w16i4::Error::TooManyErrors));
+        assert_eq!(corrupted, expected);
+
+        // shortened frames work too
+        let mut frame = (0..4*20).collect::<Vec<u8>>();
+        rs26w16i4::encode_interleaved(&mut frame);
+        frame[0..20].fill(b'x');
+        assert_eq!(rs26w16i4::correct_errors_interleaved(&mut frame), Ok(20));
+        assert_eq!(&frame[..4*10], &(0..4*10).collect::<Vec<u8>>());
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn rs26w16i4_pipeline() {
+        use std::io;
+
+        for len in [0, 1, 64, 640, 1000, 6400] {
+            let message = (0..len).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+            for threads in [1, 2, 4] {
+                let mut stream = Vec::new();
+                let written = rs26w16i4::encode_pipeline(&message[..], &mut stream, threads).unwrap();
+                assert_eq!(written, stream.len() as u64);
+
+                // full frames are interleaved, the rest is encode_stream
+                let full = len / (4*16);
+                for (i, frame) in stream.chunks(4*26).take(full).enumerate() {
+                    assert!(rs26w16i4::is_correct_interleaved(frame));
+                    assert_eq!(&frame[..4*16], &message[i*4*16..(i+1)*4*16]);
+                }
+                assert_eq!(&stream[full*4*26..], &rs26w16i4::encode_stream(&message[full*4*16..])[..]);
+
+                let mut decoded = Vec::new();
+                rs26w16i4::decode_pipeline(&stream[..], &mut decoded, threads).unwrap();
+                assert_eq!(decoded, message);
+
+                // correct bursts of up to 4*k/2 errors in each frame
+                if full > 0 {
+                    for frame in stream.chunks_mut(4*26).take(full) {
+                        frame[10..30].fill(b'x');
+                    }
+                    let mut decoded = Vec::new();
+                    rs26w16i4::decode_pipeline(&stream[..], &mut decoded, threads).unwrap();
+                    assert_eq!(decoded, message);
+
+                    // but not more
+                    stream[0..21].fill(b'x');
+                    let mut decoded = Vec::new();
+                    let res = rs26w16i4::decode_pipeline(&stream[..], &mut decoded, threads);
+                    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::InvalidData);
+                }
+            }
+        }
+
+        // writer errors stop the pipeline
+        struct Full(usize);
+        impl io::Write for Full {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.0 == 0 {
+                    return Err(io::Error::new(io::ErrorKind::WriteZero, "full"));
+                }
+                let n = buf.len().min(self.0);
+                self.0 -= n;
+                Ok(n)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let message = vec![0u8; 100000];
+        let res = rs26w16i4::encode_pipeline(&message[..], Full(1000), 4);
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::WriteZero);
+    }
+
     // all RS params
-    #[rs(gf=gf256, u=u8, block=26, data=16)]
+    #[rs(gf=gf256, u=u8, block=26, data=16, interleave=1)]
     mod rs26w16_all_params {}
 
     #[test]
@@ -0,0 +1,246 @@
+//! CCSDS Reed-Solomon (255,223)
+//!
+//! The Consultative Committee for Space Data Systems (CCSDS) specifies a
+//! Reed-Solomon (255,223) code for spacecraft telemetry in CCSDS 101.0-B
+//! (and later in 131.0-B). It differs from [`rs255w223`](super::rs255w223)
+//! in a couple of ways:
+//!
+//! 1. The field is defined by the polynomial `0x187`, and the generator
+//!    polynomial has roots at `α^(11*(112+i))`, so [`FCR`] = 112 and
+//!    [`PRIMITIVE`] = `α^11`.
+//!
+//! 2. Symbols are transmitted in Berlekamp's dual-basis representation,
+//!    not the conventional polynomial-basis representation used for
+//!    arithmetic. So symbols must be converted before and after encoding
+//!    and decoding.
+//!
+//! The functions here take and return symbols in the dual basis, as they
+//! appear in a telemetry frame, converting internally:
+//!
+//! ``` rust
+//! use gf256::rs::ccsds;
+//!
+//! // encode
+//! let mut buf = b"Hello World!".to_vec();
+//! buf.resize(buf.len()+32, 0u8);
+//! ccsds::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0..16].fill(b'x');
+//!
+//! // correct
+//! ccsds::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..12], b"Hello World!");
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! Frames are usually made of [`ccsds::BLOCK_SIZE`](BLOCK_SIZE) codewords
+//! interleaved symbol-by-symbol with an interleaving depth of 1-5 or 8,
+//! where symbol `j` of codeword `i` is stored at `frame[j*depth + i]`. The
+//! `_interleaved` functions handle this. Shortened codewords, with virtual
+//! fill, can be passed by simply omitting the leading zero symbols.
+//!
+
+use super::runtime::{Rs, Error};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// Maximum size of the original data in bytes.
+pub const DATA_SIZE: usize = 223;
+
+/// Size of the appended error-correction in bytes.
+pub const ECC_SIZE: usize = 32;
+
+/// Size of the codeword, [`DATA_SIZE`] + [`ECC_SIZE`], in bytes.
+pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
+
+/// The irreducible polynomial defining CCSDS's field, `x^8+x^7+x^2+x+1`.
+pub const POLYNOMIAL: u16 = 0x187;
+
+/// The first consecutive root of the generator polynomial.
+pub const FCR: u8 = 112;
+
+/// The primitive element whose powers are the roots of the generator
+/// polynomial, `α^11` where `α` is a root of [`POLYNOMIAL`].
+pub const PRIMITIVE: u8 = 0xad;
+
+// The dual-basis transform is a linear map, so it can be described by
+// the dual-basis images of each bit in the conventional basis, lsb first
+//
+const DUAL_BASIS_ROWS: [u8; 8] = [
+    0x7b, 0xaf, 0x99, 0xfa, 0x86, 0xec, 0xef, 0x8d
+];
+
+/// Table mapping conventional-basis symbols to dual-basis symbols.
+pub const TO_DUAL_BASIS: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut k = 0;
+        while k < 8 {
+            if i & (1 << k) != 0 {
+                table[i] ^= DUAL_BASIS_ROWS[k];
+            }
+            k += 1;
+        }
+        i += 1;
+    }
+    table
+};
+
+/// Table mapping dual-basis symbols to conventional-basis symbols.
+pub const FROM_DUAL_BASIS: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[TO_DUAL_BASIS[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Convert symbols from the conventional basis to the dual basis, in-place.
+pub fn to_dual_basis(symbols: &mut [u8]) {
+    for x in symbols {
+        *x = TO_DUAL_BASIS[usize::from(*x)];
+    }
+}
+
+/// Convert symbols from the dual basis to the conventional basis, in-place.
+pub fn from_dual_basis(symbols: &mut [u8]) {
+    for x in symbols {
+        *x = FROM_DUAL_BASIS[usize::from(*x)];
+    }
+}
+
+/// The CCSDS Reed-Solomon code in the conventional basis, as a
+/// runtime-configurable [`Rs`].
+///
+/// This is useful if the dual-basis conversion is already handled
+/// elsewhere.
+///
+pub fn rs() -> Rs {
+    Rs::new(POLYNOMIAL, BLOCK_SIZE, DATA_SIZE, FCR, PRIMITIVE).unwrap()
+}
+
+/// Run a function on a copy of a codeword converted to the conventional
+/// basis, converting any changes back to the dual basis
+fn with_conventional<R>(
+    codeword: &mut [u8],
+    f: impl FnOnce(&Rs, &mut [u8]) -> R
+) -> R {
+    let mut conventional = codeword.to_vec();
+    from_dual_basis(&mut conventional);
+    let r = f(&rs(), &mut conventional);
+    to_dual_basis(&mut conventional);
+    codeword.copy_from_slice(&conventional);
+    r
+}
+
+/// Encode a message using CCSDS Reed-Solomon error-correction.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to the
+/// end of the provided slice, based on the data provided in the first
+/// `message.len()-ECC_SIZE` bytes. Both are in the dual basis.
+///
+pub fn encode(message: &mut [u8]) {
+    with_conventional(message, |rs, message| rs.encode(message))
+}
+
+/// Determine if codeword is correct and has no errors/erasures.
+pub fn is_correct(codeword: &[u8]) -> bool {
+    let mut conventional = codeword.to_vec();
+    from_dual_basis(&mut conventional);
+    rs().is_correct(&conventional)
+}
+
+/// Correct up to [`ECC_SIZE`] erasures at known locations.
+///
+/// Returns the number of erasures, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected.
+///
+pub fn correct_erasures(
+    codeword: &mut [u8],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    with_conventional(codeword, |rs, codeword| {
+        rs.correct_erasures(codeword, erasures)
+    })
+}
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations.
+///
+/// Returns the number of errors, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected.
+///
+pub fn correct_errors(codeword: &mut [u8]) -> Result<usize, Error> {
+    with_conventional(codeword, |rs, codeword| rs.correct_errors(codeword))
+}
+
+/// Correct a mixture of errors and erasures, up to `2*errors+erasures <= ECC_SIZE`.
+///
+/// Returns the number of errors and erasures, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected.
+///
+pub fn correct(
+    codeword: &mut [u8],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    with_conventional(codeword, |rs, codeword| rs.correct(codeword, erasures))
+}
+
+/// Encode a frame of `depth` interleaved codewords.
+///
+/// The frame's length must be a multiple of `depth`, with the
+/// error-correction information written to the last `depth*ECC_SIZE` bytes.
+///
+pub fn encode_interleaved(frame: &mut [u8], depth: usize) {
+    assert!(depth > 0 && frame.len().is_multiple_of(depth));
+    let rs = rs();
+    let mut codeword = Vec::with_capacity(frame.len() / depth);
+    for i in 0..depth {
+        codeword.clear();
+        codeword.extend(frame[i..].iter().step_by(depth)
+            .map(|x| FROM_DUAL_BASIS[usize::from(*x)]));
+        rs.encode(&mut codeword);
+        for (j, x) in codeword.iter().enumerate() {
+            frame[j*depth + i] = TO_DUAL_BASIS[usize::from(*x)];
+        }
+    }
+}
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations in
+/// each of the `depth` interleaved codewords of a frame.
+///
+/// Returns the total number of errors, or [`Error::TooManyErrors`] if any
+/// codeword can not be corrected. On error, the codewords that could be
+/// corrected are still corrected.
+///
+pub fn correct_errors_interleaved(
+    frame: &mut [u8],
+    depth: usize
+) -> Result<usize, Error> {
+    assert!(depth > 0 && frame.len().is_multiple_of(depth));
+    let rs = rs();
+    let mut codeword = Vec::with_capacity(frame.len() / depth);
+    let mut res = Ok(0);
+    for i in 0..depth {
+        codeword.clear();
+        codeword.extend(frame[i..].iter().step_by(depth)
+            .map(|x| FROM_DUAL_BASIS[usize::from(*x)]));
+        match rs.correct_errors(&mut codeword) {
+            Ok(count) => {
+                for (j, x) in codeword.iter().enumerate() {
+                    frame[j*depth + i] = TO_DUAL_BASIS[usize::from(*x)];
+                }
+                res = res.map(|total| total + count);
+            }
+            Err(err) => {
+                res = Err(err);
+            }
+        }
+    }
+    res
+}
@@ -0,0 +1,151 @@
+//! Reed-Solomon with a CRC to reject miscorrections
+//!
+//! When a codeword has more errors than Reed-Solomon can correct, the
+//! decoder usually notices, but it can also find a different, valid
+//! codeword, silently returning the wrong data. This is rare, but on a
+//! noisy channel, rare things happen.
+//!
+//! This wraps [`rs255w223`] with a CRC-32C of the data,
+//! which is checked after decoding. The layout of each codeword is:
+//!
+//! ``` text
+//! [ data (up to 219 bytes) | crc32c(data) (4 bytes) | ecc (32 bytes) ]
+//! '------------- protected by ecc ------------------'
+//! ```
+//!
+//! Note the CRC goes _inside_ the data protected by Reed-Solomon, not
+//! outside. This way errors in the CRC are corrected like any other errors,
+//! and the CRC checks the final, corrected data. A CRC outside the
+//! codeword would instead need its own protection, and would reject
+//! codewords Reed-Solomon could have corrected.
+//!
+//! ``` rust
+//! use gf256::rs::checked;
+//!
+//! // encode
+//! let mut buf = b"Hello World!".to_vec();
+//! buf.resize(buf.len()+checked::CRC_SIZE+checked::ECC_SIZE, 0u8);
+//! checked::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0..16].fill(b'x');
+//!
+//! // correct
+//! checked::correct_errors(&mut buf)?;
+//! assert_eq!(&buf[0..12], b"Hello World!");
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! This requires feature `crc`.
+//!
+
+use super::runtime::Error;
+use super::rs255w223;
+use crate::crc::crc32c;
+
+
+/// Maximum size of the original data in bytes.
+pub const DATA_SIZE: usize = rs255w223::DATA_SIZE - CRC_SIZE;
+
+/// Size of the CRC appended to the data, in bytes.
+pub const CRC_SIZE: usize = 4;
+
+/// Size of the appended error-correction in bytes.
+pub const ECC_SIZE: usize = rs255w223::ECC_SIZE;
+
+/// Size of the codeword, [`DATA_SIZE`] + [`CRC_SIZE`] + [`ECC_SIZE`], in
+/// bytes.
+pub const BLOCK_SIZE: usize = DATA_SIZE + CRC_SIZE + ECC_SIZE;
+
+/// Check the CRC of a codeword
+fn check_crc(codeword: &[u8]) -> bool {
+    let data_len = codeword.len() - CRC_SIZE - ECC_SIZE;
+    let crc = u32::from_be_bytes(
+        codeword[data_len..data_len+CRC_SIZE].try_into().unwrap()
+    );
+    crc32c(&codeword[..data_len], 0) == crc
+}
+
+/// Encode a message using Reed-Solomon error-correction with a CRC.
+///
+/// This writes [`CRC_SIZE`] bytes of CRC and [`ECC_SIZE`] bytes of
+/// error-correction information to the end of the provided slice, based on
+/// the data provided in the first `message.len()-CRC_SIZE-ECC_SIZE` bytes.
+/// The entire codeword is limited to at most [`BLOCK_SIZE`] bytes, but can
+/// be smaller.
+///
+pub fn encode(message: &mut [u8]) {
+    assert!(message.len() <= BLOCK_SIZE);
+    assert!(message.len() >= CRC_SIZE+ECC_SIZE);
+    let data_len = message.len() - CRC_SIZE - ECC_SIZE;
+
+    let crc = crc32c(&message[..data_len], 0);
+    message[data_len..data_len+CRC_SIZE].copy_from_slice(&crc.to_be_bytes());
+    rs255w223::encode(message);
+}
+
+/// Determine if codeword is correct and has no errors/erasures, and its
+/// CRC matches.
+pub fn is_correct(codeword: &[u8]) -> bool {
+    assert!(codeword.len() >= CRC_SIZE+ECC_SIZE);
+    rs255w223::is_correct(codeword) && check_crc(codeword)
+}
+
+/// Run a correction function on a copy of a codeword, only accepting the
+/// result if the CRC matches
+fn correct_checked(
+    codeword: &mut [u8],
+    f: impl FnOnce(&mut [u8]) -> Result<usize, rs255w223::Error>
+) -> Result<usize, Error> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= CRC_SIZE+ECC_SIZE);
+
+    let mut corrected = codeword.to_vec();
+    let count = f(&mut corrected).map_err(|_| Error::TooManyErrors)?;
+    if !check_crc(&corrected) {
+        return Err(Error::TooManyErrors);
+    }
+
+    codeword.copy_from_slice(&corrected);
+    Ok(count)
+}
+
+/// Correct up to [`ECC_SIZE`] erasures at known locations.
+///
+/// Returns the number of erasures, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected or the corrected codeword fails its CRC.
+/// On error the codeword is left unmodified.
+///
+pub fn correct_erasures(
+    codeword: &mut [u8],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    correct_checked(codeword, |codeword| {
+        rs255w223::correct_erasures(codeword, erasures)
+    })
+}
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations.
+///
+/// Returns the number of errors, or [`Error::TooManyErrors`] if the
+/// codeword can not be corrected or the corrected codeword fails its CRC.
+/// On error the codeword is left unmodified.
+///
+pub fn correct_errors(codeword: &mut [u8]) -> Result<usize, Error> {
+    correct_checked(codeword, rs255w223::correct_errors)
+}
+
+/// Correct a mixture of errors and erasures, up to `2*errors+erasures <= ECC_SIZE`.
+///
+/// Returns the number of errors and erasures, or [`Error::TooManyErrors`]
+/// if the codeword can not be corrected or the corrected codeword fails
+/// its CRC. On error the codeword is left unmodified.
+///
+pub fn correct(
+    codeword: &mut [u8],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    correct_checked(codeword, |codeword| {
+        rs255w223::correct(codeword, erasures)
+    })
+}
@@ -0,0 +1,188 @@
+//! Concatenated Reed-Solomon + convolutional coding
+//!
+//! Deep-space and broadcast links, such as CCSDS telemetry and DVB, often
+//! use a concatenated code: an outer [CCSDS Reed-Solomon](super::ccsds)
+//! (255,223) code, interleaved across several codewords, wrapped in an
+//! inner rate 1/2, constraint length 7 convolutional code decoded with the
+//! Viterbi algorithm.
+//!
+//! The two codes complement each other. The convolutional code is good at
+//! correcting scattered bit errors, especially with soft decisions, but
+//! when it fails it fails in short bursts. Reed-Solomon is good at
+//! correcting bursts, since a burst of bit errors only damages a handful of
+//! symbols, and interleaving spreads longer bursts across multiple
+//! codewords.
+//!
+//! ``` rust
+//! use gf256::rs::concat;
+//!
+//! // encode two interleaved codewords
+//! let data = b"Hello World! Concatenated codes!".to_vec();
+//! let mut coded = concat::encode(&data, 2);
+//!
+//! // corrupt some scattered bits, and a long burst
+//! for i in (0..coded.len()).step_by(7) {
+//!     coded[i] ^= 0x10;
+//! }
+//! coded[20..30].fill(0x55);
+//!
+//! // decode
+//! assert_eq!(concat::decode(&coded, 2)?, data);
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! The convolutional code is [`K7R12_CCSDS`], the constraint length 7,
+//! rate 1/2 code with polynomials `0o171` and `0o133`, and the second
+//! output inverted. Each frame is encoded MSB first, starting from the
+//! all-zero state, and terminated with a zero tail, so frames can be
+//! decoded independently. Coded bits are packed MSB first, with any unused
+//! bits in the last byte set to zero, see [`Conv::encode`].
+//!
+//! The `_inner` functions expose the convolutional code on its own, and
+//! [`decode_soft`] accepts soft decisions from a demodulator as
+//! log-likelihood ratios (LLRs), one per coded bit, see
+//! [`Conv::decode_soft`].
+//!
+//! Note this module requires feature `conv`.
+//!
+//! [`Conv::encode`]: crate::conv::Conv::encode
+//! [`Conv::decode_soft`]: crate::conv::Conv::decode_soft
+//!
+
+use super::runtime::Error;
+use super::ccsds;
+use crate::conv::K7R12_CCSDS;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Size of a frame encoded with the inner convolutional code, in bytes.
+pub fn encoded_inner_size(frame_len: usize) -> usize {
+    K7R12_CCSDS.encoded_size(frame_len)
+}
+
+/// Size of a message encoded with [`encode`], in bytes.
+pub fn encoded_size(data_len: usize, depth: usize) -> usize {
+    encoded_inner_size(data_len + depth*ccsds::ECC_SIZE)
+}
+
+/// Encode a frame with only the inner convolutional code.
+///
+/// Returns [`encoded_inner_size`] bytes of packed coded bits.
+///
+pub fn encode_inner(frame: &[u8]) -> Vec<u8> {
+    let mut coded = vec![0u8; encoded_inner_size(frame.len())];
+    K7R12_CCSDS.encode(frame, &mut coded);
+    coded
+}
+
+/// Decode a frame encoded with only the inner convolutional code, using
+/// soft decisions.
+///
+/// `llrs` must contain one LLR per coded bit for a frame of `frame_len`
+/// bytes, so [`K7R12_CCSDS.encoded_bits(8*frame_len)`](crate::conv::Conv::encoded_bits),
+/// not including the padding bits in [`encode_inner`]'s output. Any extra
+/// LLRs are ignored.
+///
+/// The Viterbi decoder always finds the most likely frame, so this can't
+/// fail, but the frame may still contain errors.
+///
+pub fn decode_inner_soft(llrs: &[f32], frame_len: usize) -> Vec<u8> {
+    let bits = K7R12_CCSDS.encoded_bits(8*frame_len);
+    assert!(llrs.len() >= bits);
+
+    let mut frame = vec![0u8; frame_len];
+    K7R12_CCSDS.decode_soft(&llrs[..bits], &mut frame);
+    frame
+}
+
+/// Decode a frame encoded with only the inner convolutional code, using
+/// hard decisions.
+///
+/// `coded` must contain at least [`encoded_inner_size`] bytes of packed
+/// coded bits for a frame of `frame_len` bytes.
+///
+pub fn decode_inner(coded: &[u8], frame_len: usize) -> Vec<u8> {
+    let size = encoded_inner_size(frame_len);
+    assert!(coded.len() >= size);
+
+    let mut frame = vec![0u8; frame_len];
+    K7R12_CCSDS.decode(&coded[..size], &mut frame);
+    frame
+}
+
+/// Find the length of the data in a frame, given the number of coded bits
+fn data_len(coded_bits: usize, depth: usize) -> Result<usize, Error> {
+    assert!(depth > 0);
+    let frame_len = (coded_bits/2).checked_sub(K7R12_CCSDS.k()-1)
+        .ok_or(Error::InvalidParameters)? / 8;
+    let data_len = frame_len.checked_sub(depth*ccsds::ECC_SIZE)
+        .ok_or(Error::InvalidParameters)?;
+    if data_len % depth != 0 || data_len/depth > ccsds::DATA_SIZE {
+        return Err(Error::InvalidParameters);
+    }
+    Ok(data_len)
+}
+
+/// Encode a message with the concatenated code.
+///
+/// The message is split into `depth` interleaved CCSDS Reed-Solomon
+/// codewords, so its length must be a multiple of `depth`, and at most
+/// `depth*`[`ccsds::DATA_SIZE`] bytes. Smaller messages result in shortened
+/// codewords.
+///
+/// Returns [`encoded_size`] bytes of packed coded bits.
+///
+pub fn encode(data: &[u8], depth: usize) -> Vec<u8> {
+    assert!(depth > 0 && data.len().is_multiple_of(depth));
+    assert!(data.len()/depth <= ccsds::DATA_SIZE);
+
+    let mut frame = vec![0u8; data.len() + depth*ccsds::ECC_SIZE];
+    frame[..data.len()].copy_from_slice(data);
+    ccsds::encode_interleaved(&mut frame, depth);
+    encode_inner(&frame)
+}
+
+/// Decode and correct a message encoded with the concatenated code, using
+/// hard decisions.
+///
+/// Returns the original message, or [`Error::TooManyErrors`] if any
+/// Reed-Solomon codeword can not be corrected, or
+/// [`Error::InvalidParameters`] if the length of `coded` doesn't match an
+/// encoded message.
+///
+pub fn decode(coded: &[u8], depth: usize) -> Result<Vec<u8>, Error> {
+    let data_len = data_len(8*coded.len(), depth)?;
+    if coded.len() != encoded_size(data_len, depth) {
+        return Err(Error::InvalidParameters);
+    }
+
+    let mut frame = decode_inner(coded, data_len + depth*ccsds::ECC_SIZE);
+    ccsds::correct_errors_interleaved(&mut frame, depth)?;
+    frame.truncate(data_len);
+    Ok(frame)
+}
+
+/// Decode and correct a message encoded with the concatenated code, using
+/// soft decisions.
+///
+/// `llrs` must contain one LLR per coded bit, not including any padding
+/// bits, so [`K7R12_CCSDS.encoded_bits`](crate::conv::Conv::encoded_bits)
+/// of the frame.
+///
+/// Returns the same errors as [`decode`].
+///
+pub fn decode_soft(llrs: &[f32], depth: usize) -> Result<Vec<u8>, Error> {
+    let data_len = data_len(llrs.len(), depth)?;
+    let frame_len = data_len + depth*ccsds::ECC_SIZE;
+    if llrs.len() != K7R12_CCSDS.encoded_bits(8*frame_len) {
+        return Err(Error::InvalidParameters);
+    }
+
+    let mut frame = decode_inner_soft(llrs, frame_len);
+    ccsds::correct_errors_interleaved(&mut frame, depth)?;
+    frame.truncate(data_len);
+    Ok(frame)
+}
@@ -0,0 +1,310 @@
+//! Packet-level forward error correction
+//!
+//! Reed-Solomon corrects symbols within a codeword, but over a lossy
+//! network, such as UDP, whole packets go missing. In the style of
+//! [RFC 6865][rfc6865], this protects a block of `k` source packets with
+//! `m` repair packets, where any `k` of the `k+m` packets are enough to
+//! reconstruct the block.
+//!
+//! ``` rust
+//! use gf256::rs::packet;
+//!
+//! let sources = [&b"Hello"[..], &b"World"[..], &b"!"[..]];
+//! let mut packets = packet::encode(0, &sources, 2);
+//! assert_eq!(packets.len(), 5);
+//!
+//! // lose two packets
+//! packets.remove(1);
+//! packets.remove(0);
+//!
+//! // reconstruct
+//! let decoded = packet::decode(&packets)?;
+//! assert_eq!(decoded, sources);
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! Every packet starts with a [`HEADER_SIZE`]-byte [`Header`], identifying
+//! its source block and its index in the block, followed by its payload.
+//! Source packets carry the original packets unmodified, so a receiver that
+//! doesn't lose anything never needs to decode.
+//!
+//! Byte `j` of each packet forms a Reed-Solomon codeword over `GF(2^8)`
+//! with `k+m` symbols, and lost packets become erasures. Since packets may
+//! have different lengths, each source packet is prefixed with its 2-byte
+//! big-endian length and zero-padded to the longest source packet in the
+//! block before encoding, much like FECFRAME's ADU Information. Only repair
+//! packets carry this padding.
+//!
+//! Header layout, all fields big-endian:
+//!
+//! ``` text
+//! 0               1               2               3
+//! 0 1 2 3 4 5 6 7 0 1 2 3 4 5 6 7 0 1 2 3 4 5 6 7 0 1 2 3 4 5 6 7
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |              Source Block Number (24)         |   Index (8)   |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! |     k (8)     |     m (8)     |
+//! +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+//! ```
+//!
+//! [rfc6865]: https://www.rfc-editor.org/rfc/rfc6865
+//!
+
+use super::runtime::{Rs, Error};
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Size of the header prepended to every packet, in bytes.
+pub const HEADER_SIZE: usize = 6;
+
+/// Maximum number of packets, source + repair, in a block.
+pub const MAX_PACKETS: usize = 255;
+
+/// Maximum size of a source packet, limited by its 2-byte length prefix.
+pub const MAX_PACKET_SIZE: usize = 0xffff;
+
+/// Maximum source block number, limited to 24 bits.
+pub const MAX_BLOCK: u32 = 0xffffff;
+
+/// Header identifying a packet in a block of `k` source packets and `m`
+/// repair packets.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Header {
+    /// Source block number, at most [`MAX_BLOCK`].
+    pub block: u32,
+    /// Index of this packet, source packets are `0..k`, repair packets
+    /// are `k..k+m`.
+    pub index: u8,
+    /// Number of source packets in the block.
+    pub k: u8,
+    /// Number of repair packets in the block.
+    pub m: u8,
+}
+
+impl Header {
+    /// Is this a repair packet?
+    pub fn is_repair(&self) -> bool {
+        self.index >= self.k
+    }
+
+    /// Serialize the header into its on-the-wire format.
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        assert!(self.block <= MAX_BLOCK);
+        let block = self.block.to_be_bytes();
+        [block[1], block[2], block[3], self.index, self.k, self.m]
+    }
+
+    /// Parse the header from the beginning of a packet.
+    ///
+    /// Returns `None` if the packet is too small or the header doesn't
+    /// describe a valid block.
+    ///
+    pub fn from_bytes(packet: &[u8]) -> Option<Self> {
+        if packet.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let header = Self {
+            block: u32::from_be_bytes([0, packet[0], packet[1], packet[2]]),
+            index: packet[3],
+            k: packet[4],
+            m: packet[5],
+        };
+        let (index, k, m) = (
+            usize::from(header.index),
+            usize::from(header.k),
+            usize::from(header.m),
+        );
+        if k == 0 || k+m > MAX_PACKETS || index >= k+m {
+            return None;
+        }
+
+        Some(header)
+    }
+}
+
+/// The Reed-Solomon code protecting each byte of a block
+fn rs(k: usize, m: usize) -> Rs {
+    Rs::new(0x11d, k+m, k, 0, 0x02).unwrap()
+}
+
+/// Encode a block of source packets into `k+m` packets, where `k` is the
+/// number of source packets.
+///
+/// The first `k` packets returned are the source packets, prefixed with
+/// their headers, and the last `m` are repair packets. The source packets
+/// can be sent as soon as they're available, but the repair packets need
+/// the whole block.
+///
+/// `k+m` must be at most [`MAX_PACKETS`], and each source packet at most
+/// [`MAX_PACKET_SIZE`] bytes.
+///
+pub fn encode<P: AsRef<[u8]>>(
+    block: u32,
+    sources: &[P],
+    m: usize
+) -> Vec<Vec<u8>> {
+    let k = sources.len();
+    assert!(block <= MAX_BLOCK);
+    assert!(k > 0 && k+m <= MAX_PACKETS);
+    assert!(sources.iter().all(|s| s.as_ref().len() <= MAX_PACKET_SIZE));
+
+    let header = |index: usize| Header {
+        block,
+        index: index as u8,
+        k: k as u8,
+        m: m as u8,
+    }.to_bytes();
+
+    let mut packets = Vec::with_capacity(k+m);
+    for (i, source) in sources.iter().enumerate() {
+        let mut packet = Vec::with_capacity(HEADER_SIZE + source.as_ref().len());
+        packet.extend_from_slice(&header(i));
+        packet.extend_from_slice(source.as_ref());
+        packets.push(packet);
+    }
+
+    // no repair packets? nothing else to do
+    if m == 0 {
+        return packets;
+    }
+
+    // length-prefix and pad our source packets
+    let symbol_size = 2 + sources.iter()
+        .map(|s| s.as_ref().len())
+        .max()
+        .unwrap();
+    let rows = sources.iter()
+        .map(|s| symbol(s.as_ref(), symbol_size))
+        .collect::<Vec<_>>();
+
+    // encode each column of bytes
+    let rs = rs(k, m);
+    let mut repairs = vec![vec![0u8; symbol_size]; m];
+    let mut codeword = vec![0u8; k+m];
+    for j in 0..symbol_size {
+        for (c, row) in codeword.iter_mut().zip(&rows) {
+            *c = row[j];
+        }
+        rs.encode(&mut codeword);
+        for (repair, c) in repairs.iter_mut().zip(&codeword[k..]) {
+            repair[j] = *c;
+        }
+    }
+
+    for (i, repair) in repairs.iter().enumerate() {
+        let mut packet = Vec::with_capacity(HEADER_SIZE + symbol_size);
+        packet.extend_from_slice(&header(k+i));
+        packet.extend_from_slice(repair);
+        packets.push(packet);
+    }
+
+    packets
+}
+
+/// Length-prefix and pad a source packet
+fn symbol(source: &[u8], symbol_size: usize) -> Vec<u8> {
+    let mut symbol = Vec::with_capacity(symbol_size);
+    symbol.extend_from_slice(&(source.len() as u16).to_be_bytes());
+    symbol.extend_from_slice(source);
+    symbol.resize(symbol_size, 0);
+    symbol
+}
+
+/// Reconstruct a block's source packets from any `k` of its packets.
+///
+/// `packets` should contain the received packets for a single block, with
+/// headers, in any order. Use [`Header::from_bytes`] to sort packets from
+/// multiple blocks.
+///
+/// Returns the `k` source packets, without headers, or
+/// [`Error::TooManyErrors`] if too many packets were lost. Returns
+/// [`Error::InvalidParameters`] if the packets have invalid or inconsistent
+/// headers, or are otherwise malformed.
+///
+pub fn decode<P: AsRef<[u8]>>(packets: &[P]) -> Result<Vec<Vec<u8>>, Error> {
+    let mut first = None;
+    let mut received: Vec<Option<&[u8]>> = Vec::new();
+    for packet in packets {
+        let packet = packet.as_ref();
+        let header = Header::from_bytes(packet)
+            .ok_or(Error::InvalidParameters)?;
+        let first = *first.get_or_insert(header);
+        if (header.block, header.k, header.m) != (first.block, first.k, first.m) {
+            return Err(Error::InvalidParameters);
+        }
+
+        received.resize(usize::from(header.k) + usize::from(header.m), None);
+        received[usize::from(header.index)] = Some(&packet[HEADER_SIZE..]);
+    }
+
+    let header = first.ok_or(Error::TooManyErrors)?;
+    let k = usize::from(header.k);
+    let m = usize::from(header.m);
+
+    // no missing source packets? nothing to do
+    if received[..k].iter().all(|r| r.is_some()) {
+        return Ok(received[..k].iter().map(|r| r.unwrap().to_vec()).collect());
+    }
+
+    // we need at least k packets
+    if received.iter().filter(|r| r.is_some()).count() < k {
+        return Err(Error::TooManyErrors);
+    }
+
+    // find the symbol size from our repair packets, we must have at least
+    // one repair packet at this point
+    let symbol_size = received[k..].iter().flatten().next().unwrap().len();
+    if symbol_size < 2
+        || received[k..].iter().flatten().any(|r| r.len() != symbol_size)
+        || received[..k].iter().flatten().any(|s| 2+s.len() > symbol_size)
+    {
+        return Err(Error::InvalidParameters);
+    }
+
+    // length-prefix and pad our source packets, missing packets become
+    // erasures
+    let mut rows = received.iter().enumerate()
+        .map(|(i, r)| match r {
+            Some(s) if i < k => symbol(s, symbol_size),
+            Some(r) => r.to_vec(),
+            None => vec![0u8; symbol_size],
+        })
+        .collect::<Vec<_>>();
+    let erasures = (0..k+m)
+        .filter(|&i| received[i].is_none())
+        .collect::<Vec<_>>();
+
+    // correct each column of bytes
+    let rs = rs(k, m);
+    let mut codeword = vec![0u8; k+m];
+    for j in 0..symbol_size {
+        for (c, row) in codeword.iter_mut().zip(&rows) {
+            *c = row[j];
+        }
+        rs.correct_erasures(&mut codeword, &erasures)?;
+        for &i in &erasures {
+            rows[i][j] = codeword[i];
+        }
+    }
+
+    // strip length-prefixes and padding
+    let mut sources = Vec::with_capacity(k);
+    for (i, row) in rows.into_iter().take(k).enumerate() {
+        match received[i] {
+            Some(s) => sources.push(s.to_vec()),
+            None => {
+                let len = usize::from(u16::from_be_bytes([row[0], row[1]]));
+                if 2+len > symbol_size {
+                    return Err(Error::InvalidParameters);
+                }
+                sources.push(row[2..2+len].to_vec());
+            }
+        }
+    }
+
+    Ok(sources)
+}
@@ -0,0 +1,215 @@
+//! PAR2-compatible Reed-Solomon over GF(2^16)
+//!
+//! [PAR2][par2] protects files by splitting them into input slices, and
+//! generating recovery slices that can replace any missing input slices.
+//! Unlike the BCH-view Reed-Solomon codes generated by the
+//! [`rs`](super::rs) macro, PAR2 uses a Vandermonde-style construction:
+//! each input slice `i` is given a constant `c_i`, and recovery slice `e`
+//! is the sum of every input slice multiplied by `c_i^e`:
+//!
+//! ``` text
+//! recovery_e = Σ c_i^e * input_i
+//!              i
+//! ```
+//!
+//! All arithmetic is in PAR2's field, GF(2^16) defined by the polynomial
+//! `0x1100b`, operating on slices as arrays of little-endian 16-bit words.
+//! The constants `c_i` are the powers `2^n` where `n` is not divisible by
+//! 3, 5, 17, or 257, in order, so every constant has order 65535.
+//!
+//! ``` rust
+//! use gf256::rs::par2;
+//!
+//! let mut data = b"Hello World! PAR2 slices!!!!".to_vec();
+//! let inputs = data.chunks(4).collect::<Vec<_>>();
+//!
+//! // create two recovery slices
+//! let mut recovery0 = vec![0u8; 4];
+//! let mut recovery1 = vec![0u8; 4];
+//! par2::encode(&inputs, 0, &mut recovery0);
+//! par2::encode(&inputs, 1, &mut recovery1);
+//!
+//! // lose two input slices
+//! data[4..12].fill(b'x');
+//!
+//! // repair
+//! let mut inputs = data.chunks_mut(4).collect::<Vec<_>>();
+//! par2::repair(&mut inputs, &[1, 2], &[(0, &recovery0), (1, &recovery1)])?;
+//! assert_eq!(&data, b"Hello World! PAR2 slices!!!!");
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! Note that, like PAR2 itself, the resulting linear system is not
+//! guaranteed to be invertible for every combination of missing slices and
+//! recovery exponents. In this case [`repair`] returns
+//! [`Error::TooManyErrors`], and a different set of recovery slices should
+//! be tried.
+//!
+//! [par2]: https://parchive.github.io/doc/Parity%20Volume%20Set%20Specification%20v2.0.html
+//!
+
+use crate::gf::gf;
+use super::runtime::Error;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+// PAR2's 16-bit binary-extension finite-field
+#[gf(polynomial=0x1100b, generator=0x2)]
+pub type gf2p16;
+
+/// Maximum number of input slices, limited by the number of constants
+/// with order 65535.
+pub const MAX_INPUTS: usize = 32768;
+
+/// Find the PAR2 constant for the given input slice, `2^n` for the
+/// `index`-th `n` not divisible by 3, 5, 17, or 257.
+///
+/// ``` rust
+/// # use gf256::rs::par2;
+/// assert_eq!(par2::input_constant(0), par2::gf2p16(2));
+/// assert_eq!(par2::input_constant(8), par2::gf2p16(0x100b));
+/// ```
+///
+pub fn input_constant(index: usize) -> gf2p16 {
+    assert!(index < MAX_INPUTS);
+    input_constants().nth(index).unwrap()
+}
+
+/// Iterate over the PAR2 constants in order
+fn input_constants() -> impl Iterator<Item=gf2p16> {
+    (1u16..)
+        .filter(|n| n % 3 != 0 && n % 5 != 0 && n % 17 != 0 && n % 257 != 0)
+        .map(|n| gf2p16::GENERATOR.pow(n))
+}
+
+/// Read a little-endian 16-bit word from a slice
+fn word(slice: &[u8], i: usize) -> gf2p16 {
+    gf2p16(u16::from_le_bytes([slice[2*i], slice[2*i+1]]))
+}
+
+/// Add c*src to dst, word by word
+fn slice_mul_add(dst: &mut [u8], src: &[u8], c: gf2p16) {
+    for i in 0..dst.len()/2 {
+        let x = (word(dst, i) + c*word(src, i)).get().to_le_bytes();
+        dst[2*i..2*i+2].copy_from_slice(&x);
+    }
+}
+
+/// Create a PAR2 recovery slice with the given exponent.
+///
+/// All input slices and the recovery slice must be the same length, a
+/// multiple of 2 bytes. PAR2 uses exponents 0, 1, 2, ... for its recovery
+/// slices, and at most [`MAX_INPUTS`] input slices.
+///
+pub fn encode<B: AsRef<[u8]>>(
+    inputs: &[B],
+    exponent: u16,
+    recovery: &mut [u8]
+) {
+    assert!(inputs.len() <= MAX_INPUTS);
+    assert!(recovery.len().is_multiple_of(2));
+    assert!(inputs.iter().all(|b| b.as_ref().len() == recovery.len()));
+
+    recovery.fill(0);
+    for (input, c) in inputs.iter().zip(input_constants()) {
+        slice_mul_add(recovery, input.as_ref(), c.pow(exponent));
+    }
+}
+
+/// Repair missing input slices using PAR2 recovery slices.
+///
+/// `missing` must be an array of indices indicating which input slices
+/// are missing, and `recovery` an array of recovery slices along with
+/// their exponents. At least as many recovery slices as missing input
+/// slices are needed.
+///
+/// Returns [`Error::TooManyErrors`] if there are too few recovery slices,
+/// or if the missing slices can't be solved for with these recovery
+/// slices.
+///
+pub fn repair<B: AsMut<[u8]>, R: AsRef<[u8]>>(
+    inputs: &mut [B],
+    missing: &[usize],
+    recovery: &[(u16, R)]
+) -> Result<(), Error> {
+    assert!(inputs.len() <= MAX_INPUTS);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if recovery.len() < missing.len() {
+        return Err(Error::TooManyErrors);
+    }
+    let recovery = &recovery[..missing.len()];
+    let len = recovery[0].1.as_ref().len();
+    assert!(len % 2 == 0);
+    assert!(recovery.iter().all(|(_, r)| r.as_ref().len() == len));
+
+    // subtract the contributions of the input slices we do have, leaving
+    // only the contributions of the missing input slices
+    let mut residuals = recovery.iter()
+        .map(|(_, r)| r.as_ref().to_vec())
+        .collect::<Vec<_>>();
+    for ((i, input), c) in inputs.iter_mut().enumerate().zip(input_constants()) {
+        if missing.contains(&i) {
+            continue;
+        }
+        for ((e, _), residual) in recovery.iter().zip(&mut residuals) {
+            slice_mul_add(residual, input.as_mut(), c.pow(*e));
+        }
+    }
+
+    // build and invert our matrix, M[r][j] = c_j^e_r, using Gauss-Jordan
+    // elimination
+    let m = missing.len();
+    let constants = missing.iter()
+        .map(|&j| input_constant(j))
+        .collect::<Vec<_>>();
+    let mut matrix = recovery.iter()
+        .map(|(e, _)| constants.iter().map(|c| c.pow(*e)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut inverse = (0..m)
+        .map(|r| {
+            let mut row = vec![gf2p16(0); m];
+            row[r] = gf2p16(1);
+            row
+        })
+        .collect::<Vec<_>>();
+    for col in 0..m {
+        let pivot = (col..m)
+            .find(|&r| matrix[r][col] != gf2p16(0))
+            .ok_or(Error::TooManyErrors)?;
+        matrix.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let scale = matrix[col][col].recip();
+        for x in matrix[col].iter_mut().chain(inverse[col].iter_mut()) {
+            *x *= scale;
+        }
+
+        for r in 0..m {
+            let factor = matrix[r][col];
+            if r != col && factor != gf2p16(0) {
+                for k in 0..m {
+                    let (a, b) = (matrix[col][k], inverse[col][k]);
+                    matrix[r][k] -= factor*a;
+                    inverse[r][k] -= factor*b;
+                }
+            }
+        }
+    }
+
+    // solve for our missing input slices
+    for (&j, row) in missing.iter().zip(&inverse) {
+        let input = inputs[j].as_mut();
+        assert!(input.len() == len);
+        input.fill(0);
+        for (&c, residual) in row.iter().zip(&residuals) {
+            slice_mul_add(input, residual, c);
+        }
+    }
+
+    Ok(())
+}
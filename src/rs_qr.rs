@@ -0,0 +1,352 @@
+//! QR-code Reed-Solomon
+//!
+//! QR codes, as specified in ISO/IEC 18004, protect their data with
+//! Reed-Solomon codes over GF(256) defined by the polynomial `0x11d`, with
+//! generator polynomials rooted at `2^0, 2^1, ... 2^(n-1)`. This happens
+//! to be the same construction the [`rs`](macro@crate::rs::rs) macro uses, so QR
+//! blocks are simply shortened codewords.
+//!
+//! The number of error-correction codewords per block depends on the
+//! symbol's version and error-correction level, but is always one of 7,
+//! 10, 13, 15, 16, 17, 18, 20, 22, 24, 26, 28, or 30. A preset module is
+//! provided for each of these, [`ecc7`] through [`ecc30`], along with
+//! [`encode`]/[`correct`] functions that pick the right one.
+//!
+//! ``` rust
+//! use gf256::rs::qr;
+//!
+//! // the data codewords of "HELLO WORLD" in a version 1-M symbol
+//! let mut block = vec![
+//!     0x20, 0x5b, 0x0b, 0x78, 0xd1, 0x72, 0xdc, 0x4d,
+//!     0x43, 0x40, 0xec, 0x11, 0xec, 0x11, 0xec, 0x11,
+//! ];
+//! block.resize(block.len()+10, 0);
+//! qr::ecc10::encode(&mut block);
+//! assert_eq!(&block[16..], &[
+//!     0xc4, 0x23, 0x27, 0x77, 0xeb, 0xd7, 0xe7, 0xe2, 0x5d, 0x17,
+//! ]);
+//! ```
+//!
+//! Larger symbols split their data into multiple blocks, which are then
+//! interleaved. [`encode_symbol`] and [`decode_symbol`] handle this given
+//! the symbol's version and error-correction level:
+//!
+//! ``` rust
+//! use gf256::rs::qr;
+//!
+//! // version 5-Q, 2 blocks of 15 data codewords, 2 blocks of 16 data
+//! // codewords, each with 18 error-correction codewords
+//! let blocks = qr::ec_blocks(5, qr::EcLevel::Q);
+//! assert_eq!(blocks.data_size(), 62);
+//!
+//! let data = (0..62).collect::<Vec<u8>>();
+//! let mut codewords = qr::encode_symbol(5, qr::EcLevel::Q, &data);
+//! assert_eq!(codewords.len(), 134);
+//!
+//! // corrupt
+//! codewords[0..32].fill(b'x');
+//!
+//! // correct
+//! assert_eq!(qr::decode_symbol(5, qr::EcLevel::Q, &codewords), Ok(data));
+//! ```
+//!
+
+use super::rs;
+use super::runtime::Error;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+// Reed-Solomon presets for each number of error-correction codewords
+// used by QR codes, these are shortened to the size of each block
+//
+#[rs(block=255, data=248)] pub mod ecc7 {}
+#[rs(block=255, data=245)] pub mod ecc10 {}
+#[rs(block=255, data=242)] pub mod ecc13 {}
+#[rs(block=255, data=240)] pub mod ecc15 {}
+#[rs(block=255, data=239)] pub mod ecc16 {}
+#[rs(block=255, data=238)] pub mod ecc17 {}
+#[rs(block=255, data=237)] pub mod ecc18 {}
+#[rs(block=255, data=235)] pub mod ecc20 {}
+#[rs(block=255, data=233)] pub mod ecc22 {}
+#[rs(block=255, data=231)] pub mod ecc24 {}
+#[rs(block=255, data=229)] pub mod ecc26 {}
+#[rs(block=255, data=227)] pub mod ecc28 {}
+#[rs(block=255, data=225)] pub mod ecc30 {}
+
+// Run an expression with $rs bound to the preset for the given number of
+// error-correction codewords
+macro_rules! with_ecc {
+    ($ecc_size:expr, $rs:ident => $e:expr) => {
+        match $ecc_size {
+            7  => { use ecc7  as $rs; $e }
+            10 => { use ecc10 as $rs; $e }
+            13 => { use ecc13 as $rs; $e }
+            15 => { use ecc15 as $rs; $e }
+            16 => { use ecc16 as $rs; $e }
+            17 => { use ecc17 as $rs; $e }
+            18 => { use ecc18 as $rs; $e }
+            20 => { use ecc20 as $rs; $e }
+            22 => { use ecc22 as $rs; $e }
+            24 => { use ecc24 as $rs; $e }
+            26 => { use ecc26 as $rs; $e }
+            28 => { use ecc28 as $rs; $e }
+            30 => { use ecc30 as $rs; $e }
+            ecc_size => panic!("QR codes don't use {} error-correction codewords", ecc_size),
+        }
+    };
+}
+
+
+/// Encode a QR block with the given number of error-correction codewords.
+///
+/// This writes `ecc_size` error-correction codewords to the end of the
+/// provided slice, based on the data codewords in the first
+/// `block.len()-ecc_size` bytes.
+///
+/// Panics if `ecc_size` isn't used by QR codes.
+///
+pub fn encode(block: &mut [u8], ecc_size: usize) {
+    with_ecc!(ecc_size, rs => rs::encode(block))
+}
+
+/// Determine if a QR block is correct and has no errors/erasures.
+///
+/// Panics if `ecc_size` isn't used by QR codes.
+///
+pub fn is_correct(block: &[u8], ecc_size: usize) -> bool {
+    with_ecc!(ecc_size, rs => rs::is_correct(block))
+}
+
+/// Correct a mixture of errors and erasures in a QR block, up to
+/// `2*errors+erasures <= ecc_size`.
+///
+/// Returns the number of errors and erasures, or [`Error::TooManyErrors`]
+/// if the block can not be corrected.
+///
+/// Panics if `ecc_size` isn't used by QR codes.
+///
+pub fn correct(
+    block: &mut [u8],
+    ecc_size: usize,
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    with_ecc!(ecc_size, rs => {
+        rs::correct(block, erasures).map_err(|_| Error::TooManyErrors)
+    })
+}
+
+
+/// QR error-correction levels
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EcLevel {
+    /// Recovers ~7% of codewords
+    L,
+    /// Recovers ~15% of codewords
+    M,
+    /// Recovers ~25% of codewords
+    Q,
+    /// Recovers ~30% of codewords
+    H,
+}
+
+/// Layout of the error-correction blocks in a QR symbol.
+///
+/// A symbol is made of `short_blocks` blocks with `short_data_size` data
+/// codewords, followed by `long_blocks` blocks with `short_data_size+1`
+/// data codewords, each with `ecc_size` error-correction codewords.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EcBlocks {
+    /// Error-correction codewords per block
+    pub ecc_size: usize,
+    /// Number of blocks with `short_data_size` data codewords
+    pub short_blocks: usize,
+    /// Data codewords per short block
+    pub short_data_size: usize,
+    /// Number of blocks with `short_data_size+1` data codewords
+    pub long_blocks: usize,
+}
+
+impl EcBlocks {
+    /// Total number of blocks.
+    pub fn blocks(&self) -> usize {
+        self.short_blocks + self.long_blocks
+    }
+
+    /// Total number of data codewords.
+    pub fn data_size(&self) -> usize {
+        self.blocks()*self.short_data_size + self.long_blocks
+    }
+
+    /// Total number of codewords, data+ecc.
+    pub fn total_size(&self) -> usize {
+        self.data_size() + self.blocks()*self.ecc_size
+    }
+
+    /// Number of data codewords in the given block.
+    pub fn block_data_size(&self, block: usize) -> usize {
+        assert!(block < self.blocks());
+        if block < self.short_blocks {
+            self.short_data_size
+        } else {
+            self.short_data_size + 1
+        }
+    }
+}
+
+// Error-correction codewords per block, indexed by level and version-1,
+// from ISO/IEC 18004 table 9
+const ECC_SIZES: [[u8; 40]; 4] = [
+    [ 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28,
+     28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26,
+     26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+    [13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30,
+     28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+    [17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28,
+     30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+// Number of error-correction blocks, indexed by level and version-1,
+// from ISO/IEC 18004 table 9
+const BLOCK_COUNTS: [[u8; 40]; 4] = [
+    [ 1,  1,  1,  1,  1,  2,  2,  2,  2,  4,  4,  4,  4,  4,  6,  6,  6,  6,  7,  8,
+      8,  9,  9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+    [ 1,  1,  1,  2,  2,  4,  4,  4,  5,  5,  5,  8,  9,  9, 10, 10, 11, 13, 14, 16,
+     17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+    [ 1,  1,  2,  2,  4,  4,  6,  6,  8,  8,  8, 10, 12, 16, 12, 17, 16, 18, 21, 20,
+     23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+    [ 1,  1,  2,  4,  4,  4,  5,  6,  8,  8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25,
+     25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// Number of codewords in a QR symbol, this is the number of modules
+/// not used by function patterns or format/version information, divided
+/// by 8
+fn total_codewords(version: u8) -> usize {
+    let v = usize::from(version);
+    let mut modules = (16*v + 128)*v + 64;
+    if v >= 2 {
+        // alignment patterns
+        let n = v/7 + 2;
+        modules -= (25*n - 10)*n - 55;
+        if v >= 7 {
+            // version information
+            modules -= 36;
+        }
+    }
+    modules / 8
+}
+
+/// Find the layout of error-correction blocks in a QR symbol of the
+/// given version (1-40) and error-correction level.
+pub fn ec_blocks(version: u8, level: EcLevel) -> EcBlocks {
+    assert!((1..=40).contains(&version), "QR versions are 1-40");
+    let level = match level {
+        EcLevel::L => 0,
+        EcLevel::M => 1,
+        EcLevel::Q => 2,
+        EcLevel::H => 3,
+    };
+    let ecc_size = usize::from(ECC_SIZES[level][usize::from(version-1)]);
+    let blocks = usize::from(BLOCK_COUNTS[level][usize::from(version-1)]);
+    let total = total_codewords(version);
+
+    EcBlocks {
+        ecc_size,
+        short_blocks: blocks - total % blocks,
+        short_data_size: total/blocks - ecc_size,
+        long_blocks: total % blocks,
+    }
+}
+
+/// Encode the data codewords of a QR symbol into its final sequence of
+/// codewords.
+///
+/// This splits the data into blocks, encodes each block, and interleaves
+/// the data and error-correction codewords of each block. `data` must
+/// contain exactly [`EcBlocks::data_size`] codewords, including any
+/// padding codewords.
+///
+pub fn encode_symbol(version: u8, level: EcLevel, data: &[u8]) -> Vec<u8> {
+    let layout = ec_blocks(version, level);
+    assert!(data.len() == layout.data_size());
+
+    // split and encode blocks
+    let mut blocks = Vec::with_capacity(layout.blocks());
+    let mut off = 0;
+    for i in 0..layout.blocks() {
+        let data_size = layout.block_data_size(i);
+        let mut block = Vec::with_capacity(data_size + layout.ecc_size);
+        block.extend_from_slice(&data[off..off+data_size]);
+        block.resize(data_size + layout.ecc_size, 0);
+        encode(&mut block, layout.ecc_size);
+        blocks.push(block);
+        off += data_size;
+    }
+
+    // interleave, data codewords first, then error-correction codewords
+    let mut codewords = Vec::with_capacity(layout.total_size());
+    for i in 0..layout.short_data_size+1 {
+        for (j, block) in blocks.iter().enumerate() {
+            if i < layout.block_data_size(j) {
+                codewords.push(block[i]);
+            }
+        }
+    }
+    for i in 0..layout.ecc_size {
+        for (j, block) in blocks.iter().enumerate() {
+            codewords.push(block[layout.block_data_size(j)+i]);
+        }
+    }
+
+    codewords
+}
+
+/// Decode the final sequence of codewords of a QR symbol, correcting up to
+/// `ecc_size/2` errors in each block.
+///
+/// Returns the data codewords, or [`Error::TooManyErrors`] if any block
+/// can not be corrected.
+///
+/// Note that some of the smallest symbols, such as version 1, reserve a
+/// few of their error-correction codewords for detecting miscorrections,
+/// so callers may want to be more conservative in what they accept.
+///
+pub fn decode_symbol(
+    version: u8,
+    level: EcLevel,
+    codewords: &[u8]
+) -> Result<Vec<u8>, Error> {
+    let layout = ec_blocks(version, level);
+    assert!(codewords.len() == layout.total_size());
+
+    // deinterleave
+    let mut blocks = (0..layout.blocks())
+        .map(|j| Vec::with_capacity(layout.block_data_size(j) + layout.ecc_size))
+        .collect::<Vec<_>>();
+    let mut codewords = codewords.iter();
+    for i in 0..layout.short_data_size+1 {
+        for (j, block) in blocks.iter_mut().enumerate() {
+            if i < layout.block_data_size(j) {
+                block.push(*codewords.next().unwrap());
+            }
+        }
+    }
+    for _ in 0..layout.ecc_size {
+        for block in blocks.iter_mut() {
+            block.push(*codewords.next().unwrap());
+        }
+    }
+
+    // correct and concatenate
+    let mut data = Vec::with_capacity(layout.data_size());
+    for (j, block) in blocks.iter_mut().enumerate() {
+        correct(block, layout.ecc_size, &[])?;
+        data.extend_from_slice(&block[..layout.block_data_size(j)]);
+    }
+
+    Ok(data)
+}
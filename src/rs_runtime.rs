@@ -0,0 +1,567 @@
+//! Runtime-configurable Reed-Solomon
+//!
+//! The [`rs`](super::rs) macro generates Reed-Solomon codes with parameters
+//! fixed at compile time, which is the most efficient option. But sometimes
+//! the parameters aren't known until runtime, such as when they're read from
+//! a file header or negotiated over the wire.
+//!
+//! [`Rs`] implements the same Reed-Solomon codes over `GF(2^8)`, with the
+//! field's polynomial, the block/data sizes, the first consecutive root, and
+//! the primitive element all chosen at runtime. Field arithmetic uses log/exp
+//! tables built when the [`Rs`] is created.
+//!
+
+#![allow(non_snake_case)]
+
+use core::fmt;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Error codes for runtime-configurable Reed-Solomon
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The provided parameters don't describe a valid Reed-Solomon code,
+    /// either the polynomial is not irreducible, the primitive element is
+    /// not primitive, or the block size is too large
+    InvalidParameters,
+
+    /// Reed-Solomon can fail to decode if:
+    /// - errors > ecc_size/2
+    /// - erasures > ecc_size
+    /// - 2*errors + erasures > ecc_size
+    ///
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidParameters => write!(f, "Invalid Reed-Solomon parameters"),
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+        }
+    }
+}
+
+
+/// A Reed-Solomon error-correction code over `GF(2^8)`, with parameters
+/// chosen at runtime.
+///
+/// ``` rust
+/// use gf256::rs::Rs;
+///
+/// // the same code as rs255w223
+/// let rs = Rs::new(0x11d, 255, 223, 0, 0x02)?;
+///
+/// // encode
+/// let mut buf = b"Hello World!".to_vec();
+/// buf.resize(buf.len()+rs.ecc_size(), 0u8);
+/// rs.encode(&mut buf);
+///
+/// // corrupt
+/// buf[0..16].fill(b'x');
+///
+/// // correct
+/// rs.correct_errors(&mut buf)?;
+/// assert_eq!(&buf[0..12], b"Hello World!");
+/// # Ok::<(), gf256::rs::Error>(())
+/// ```
+///
+/// Note all arithmetic here is in a binary field, so addition and
+/// subtraction are both xor.
+///
+#[derive(Debug, Clone)]
+pub struct Rs {
+    polynomial: u16,
+    block_size: usize,
+    data_size: usize,
+    fcr: u8,
+    primitive: u8,
+
+    log_table: [u8; 256],
+    exp_table: [u8; 255],
+    generator_poly: Vec<u8>,
+}
+
+impl Rs {
+    /// Create a new Reed-Solomon code.
+    ///
+    /// - `polynomial` - The irreducible polynomial defining `GF(2^8)`,
+    ///   including the highest bit, for example `0x11d`.
+    /// - `block_size` - Size of the codeword, data+ecc, in bytes, at most
+    ///   255.
+    /// - `data_size` - Maximum size of the data in bytes.
+    /// - `fcr` - The first consecutive root, the generator polynomial has
+    ///   roots at `primitive^(fcr+i)`. The [`rs`](super::rs) macro uses 0.
+    /// - `primitive` - A primitive element of the field, whose powers
+    ///   generate all non-zero elements, usually `0x02`.
+    ///
+    /// Returns [`Error::InvalidParameters`] if these parameters don't
+    /// describe a valid Reed-Solomon code.
+    ///
+    pub fn new(
+        polynomial: u16,
+        block_size: usize,
+        data_size: usize,
+        fcr: u8,
+        primitive: u8,
+    ) -> Result<Self, Error> {
+        if !(0x100..=0x1ff).contains(&polynomial)
+            || block_size > 255
+            || data_size > block_size
+        {
+            return Err(Error::InvalidParameters);
+        }
+
+        // build log/exp tables, this also checks that our primitive element
+        // is primitive, generating all 255 non-zero elements, which can only
+        // happen if our polynomial is irreducible
+        let mut log_table = [0u8; 256];
+        let mut exp_table = [0u8; 255];
+        let mut x = 1u8;
+        for i in 0..255 {
+            if i > 0 && x == 1 {
+                return Err(Error::InvalidParameters);
+            }
+            exp_table[i] = x;
+            log_table[usize::from(x)] = i as u8;
+            x = naive_mul(x, primitive, polynomial);
+        }
+        if x != 1 {
+            return Err(Error::InvalidParameters);
+        }
+
+        let mut rs = Self {
+            polynomial,
+            block_size,
+            data_size,
+            fcr,
+            primitive,
+            log_table,
+            exp_table,
+            generator_poly: vec![],
+        };
+
+        // find the generator polynomial
+        //
+        //     ecc_size
+        // G(x) = ∏  (x - primitive^(fcr+i))
+        //        i
+        //
+        let mut g = vec![0u8; rs.ecc_size()+1];
+        let g_len = g.len();
+        g[g_len-1] = 1;
+        for i in 0..rs.ecc_size() {
+            rs.poly_mul(&mut g, &[1, rs.pow(usize::from(fcr)+i)]);
+        }
+        rs.generator_poly = g;
+
+        Ok(rs)
+    }
+
+    /// The irreducible polynomial defining our field.
+    pub fn polynomial(&self) -> u16 {
+        self.polynomial
+    }
+
+    /// Size of the codeword, [`data_size`](Self::data_size) +
+    /// [`ecc_size`](Self::ecc_size), in bytes.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Maximum size of the original data in bytes.
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Size of the appended error-correction in bytes.
+    pub fn ecc_size(&self) -> usize {
+        self.block_size - self.data_size
+    }
+
+    /// The first consecutive root of our generator polynomial.
+    pub fn fcr(&self) -> u8 {
+        self.fcr
+    }
+
+    /// The primitive element of our field.
+    pub fn primitive(&self) -> u8 {
+        self.primitive
+    }
+
+    /// The generator polynomial for this error-correction code, ordered
+    /// biggest-coefficient first.
+    pub fn generator_poly(&self) -> &[u8] {
+        &self.generator_poly
+    }
+
+    // field arithmetic
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            let log = usize::from(self.log_table[usize::from(a)])
+                + usize::from(self.log_table[usize::from(b)]);
+            self.exp_table[log % 255]
+        }
+    }
+
+    fn div(&self, a: u8, b: u8) -> Option<u8> {
+        if b == 0 {
+            None
+        } else if a == 0 {
+            Some(0)
+        } else {
+            let log = 255 + usize::from(self.log_table[usize::from(a)])
+                - usize::from(self.log_table[usize::from(b)]);
+            Some(self.exp_table[log % 255])
+        }
+    }
+
+    /// primitive^exp
+    fn pow(&self, exp: usize) -> u8 {
+        self.exp_table[exp % 255]
+    }
+
+    /// primitive^-exp
+    fn pow_recip(&self, exp: usize) -> u8 {
+        self.exp_table[(255 - exp % 255) % 255]
+    }
+
+    // polynomial arithmetic, polynomials here are ordered
+    // biggest-coefficient first
+
+    /// Evaluate a polynomial at x using Horner's method
+    fn poly_eval(&self, f: &[u8], x: u8) -> u8 {
+        let mut y = 0;
+        for &c in f {
+            y = self.mul(y, x) ^ c;
+        }
+        y
+    }
+
+    /// Multiply two polynomials together, in-place, see the template
+    /// for more info
+    fn poly_mul(&self, f: &mut [u8], g: &[u8]) {
+        debug_assert!(f[..g.len()-1].iter().all(|x| *x == 0));
+
+        for i in (0..f.len()-g.len()+1).rev() {
+            let fi = f[f.len()-1-i];
+            f[f.len()-1-i] = 0;
+
+            for j in 0..g.len() {
+                f[f.len()-1-(i+j)] ^= self.mul(fi, g[g.len()-1-j]);
+            }
+        }
+    }
+
+    /// Encode a message using Reed-Solomon error-correction.
+    ///
+    /// This writes [`ecc_size`](Self::ecc_size) bytes of error-correction
+    /// information to the end of the provided slice, based on the data
+    /// provided in the first `message.len()-ecc_size` bytes. The entire
+    /// codeword is limited to at most [`block_size`](Self::block_size)
+    /// bytes, but can be smaller.
+    ///
+    pub fn encode(&self, message: &mut [u8]) {
+        assert!(message.len() <= self.block_size());
+        assert!(message.len() >= self.ecc_size());
+        let data_len = message.len() - self.ecc_size();
+
+        let (data, parity) = message.split_at_mut(data_len);
+        self.encode_parity(data, parity);
+    }
+
+    /// Encode a message using Reed-Solomon error-correction, writing the
+    /// error-correction information to a separate buffer.
+    ///
+    /// See [`encode`](Self::encode) for more info.
+    ///
+    pub fn encode_parity(&self, data: &[u8], parity: &mut [u8]) {
+        assert!(data.len() <= self.data_size());
+        assert!(parity.len() == self.ecc_size());
+
+        // no parity? nothing to do
+        if parity.is_empty() {
+            return;
+        }
+
+        // divide by our generator polynomial via synthetic division, keeping
+        // only the remainder, note our generator polynomial is monic
+        parity.fill(0);
+        for &d in data {
+            let q = d ^ parity[0];
+            parity.copy_within(1.., 0);
+            parity[parity.len()-1] = 0;
+            for (p, &g) in parity.iter_mut().zip(&self.generator_poly[1..]) {
+                *p ^= self.mul(q, g);
+            }
+        }
+    }
+
+    /// Find syndromes, which should be zero if there are no errors
+    fn find_syndromes(&self, codeword: &[u8]) -> Vec<u8> {
+        (0..self.ecc_size())
+            .map(|i| self.poly_eval(codeword, self.pow(usize::from(self.fcr)+i)))
+            .collect()
+    }
+
+    /// Find Forney syndromes, these hide known erasures from the original
+    /// syndromes so error detection doesn't try to find known erasures
+    fn find_forney_syndromes(
+        &self,
+        codeword: &[u8],
+        S: &[u8],
+        erasures: &[usize]
+    ) -> Vec<u8> {
+        let mut S = S.to_vec();
+        for j in erasures {
+            let Xj = self.pow(codeword.len()-1-j);
+            for i in 0 .. S.len()-1 {
+                S[i] = S[i+1] ^ self.mul(S[i], Xj);
+            }
+        }
+
+        // trim unnecessary syndromes
+        S.truncate(S.len()-erasures.len());
+        S
+    }
+
+    /// Find the error locator polynomial when we know the location of errors
+    fn find_erasure_locator(&self, codeword: &[u8], erasures: &[usize]) -> Vec<u8> {
+        let mut Λ = vec![0; erasures.len()+1];
+        let Λ_len = Λ.len();
+        Λ[Λ_len-1] = 1;
+
+        for j in erasures {
+            self.poly_mul(&mut Λ, &[self.pow(codeword.len()-1-j), 1]);
+        }
+
+        Λ
+    }
+
+    /// Iteratively find the error locator polynomial using the
+    /// Berlekamp-Massey algorithm when we don't know the location of errors
+    fn find_error_locator(&self, S: &[u8]) -> Vec<u8> {
+        // the current estimate for the error locator polynomial
+        let mut Λ = vec![0; S.len()+1];
+        let Λ_len = Λ.len();
+        Λ[Λ_len-1] = 1;
+
+        let mut prev_Λ = Λ.clone();
+
+        // the current estimate for the number of errors
+        let mut v = 0;
+
+        for i in 0..S.len() {
+            let mut delta = S[i];
+            for j in 1..v+1 {
+                delta ^= self.mul(Λ[Λ.len()-1-j], S[i-j]);
+            }
+
+            prev_Λ.rotate_left(1);
+
+            if delta != 0 {
+                if 2*v <= i {
+                    core::mem::swap(&mut Λ, &mut prev_Λ);
+                    let delta_recip = self.div(1, delta).unwrap();
+                    for x in Λ.iter_mut() {
+                        *x = self.mul(*x, delta);
+                    }
+                    for x in prev_Λ.iter_mut() {
+                        *x = self.mul(*x, delta_recip);
+                    }
+                    v = i+1-v;
+                }
+
+                for (x, &y) in Λ.iter_mut().zip(&prev_Λ) {
+                    *x ^= self.mul(y, delta);
+                }
+            }
+        }
+
+        // trim leading zeros
+        let zeros = Λ.iter().take_while(|x| **x == 0).count();
+        Λ.drain(0..zeros);
+
+        Λ
+    }
+
+    /// Find roots of the error locator polynomial by brute force
+    fn find_error_locations(&self, codeword: &[u8], Λ: &[u8]) -> Vec<usize> {
+        (0..codeword.len())
+            .filter(|j| {
+                self.poly_eval(Λ, self.pow_recip(codeword.len()-1-j)) == 0
+            })
+            .collect()
+    }
+
+    /// Find the error magnitudes using Forney's algorithm
+    ///
+    /// ``` text
+    ///        Xj^(1-fcr)*Ω(Xj^-1)
+    /// Yj = - -------------------
+    ///             Λ'(Xj^-1)
+    /// ```
+    ///
+    fn find_error_magnitudes(
+        &self,
+        codeword: &[u8],
+        S: &[u8],
+        Λ: &[u8],
+        error_locations: &[usize]
+    ) -> Vec<u8> {
+        // find the erasure evaluator polynomial
+        //
+        // Ω(x) = S(x)*Λ(x) mod x^2v
+        //
+        let mut Ω = vec![0; S.len()+Λ.len()-1];
+        let Ω_len = Ω.len();
+        Ω[Ω_len-S.len()..].copy_from_slice(S);
+        Ω[Ω_len-S.len()..].reverse();
+        self.poly_mul(&mut Ω, Λ);
+        Ω.drain(..Ω.len()-S.len());
+
+        // find the formal derivative of Λ, in a binary field this is just
+        // the odd terms
+        let mut Λ_prime = vec![0; Λ.len()-1];
+        for i in (1..Λ.len()).step_by(2) {
+            let Λ_prime_len = Λ_prime.len();
+            Λ_prime[Λ_prime_len-1-(i-1)] = Λ[Λ.len()-1-i];
+        }
+
+        // find the error magnitudes, being careful to avoid divide-by-zero
+        error_locations.iter()
+            .map(|j| {
+                let i = codeword.len()-1-j;
+                let Xj_recip = self.pow_recip(i);
+                // Xj^(1-fcr) = primitive^(i*(1-fcr)), mod 255
+                let Xj_1_fcr = self.pow(
+                    i * ((256 - usize::from(self.fcr)) % 255)
+                );
+                self.div(
+                    self.mul(Xj_1_fcr, self.poly_eval(&Ω, Xj_recip)),
+                    self.poly_eval(&Λ_prime, Xj_recip)
+                ).unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Determine if codeword is correct and has no errors/erasures.
+    pub fn is_correct(&self, codeword: &[u8]) -> bool {
+        self.find_syndromes(codeword).iter().all(|s| *s == 0)
+    }
+
+    /// Correct up to [`ecc_size`](Self::ecc_size) erasures at known
+    /// locations.
+    ///
+    /// Returns the number of erasures, or [`Error::TooManyErrors`] if the
+    /// codeword can not be corrected.
+    ///
+    pub fn correct_erasures(
+        &self,
+        codeword: &mut [u8],
+        erasures: &[usize]
+    ) -> Result<usize, Error> {
+        self.correct(codeword, erasures)
+    }
+
+    /// Correct up to [`ecc_size/2`](Self::ecc_size) errors at unknown
+    /// locations.
+    ///
+    /// Returns the number of errors, or [`Error::TooManyErrors`] if the
+    /// codeword can not be corrected.
+    ///
+    pub fn correct_errors(&self, codeword: &mut [u8]) -> Result<usize, Error> {
+        self.correct(codeword, &[])
+    }
+
+    /// Correct a mixture of errors and erasures, up to
+    /// `2*errors+erasures <= ecc_size`.
+    ///
+    /// Returns the number of errors and erasures, or [`Error::TooManyErrors`]
+    /// if the codeword can not be corrected.
+    ///
+    pub fn correct(
+        &self,
+        codeword: &mut [u8],
+        erasures: &[usize]
+    ) -> Result<usize, Error> {
+        // too many erasures?
+        if erasures.len() > self.ecc_size()
+            || codeword.len() > self.block_size()
+        {
+            return Err(Error::TooManyErrors);
+        }
+
+        // find syndromes, syndromes of all zero means there are no errors
+        let S = self.find_syndromes(codeword);
+        if S.iter().all(|s| *s == 0) {
+            return Ok(0);
+        }
+
+        // find Forney syndromes, hiding known erasures from the syndromes
+        let forney_S = self.find_forney_syndromes(codeword, &S, erasures);
+
+        // find error locator polynomial
+        let Λ = self.find_error_locator(&forney_S);
+
+        // too many errors/erasures?
+        let error_count = Λ.len() - 1;
+        let erasure_count = erasures.len();
+        if error_count*2 + erasure_count > self.ecc_size() {
+            return Err(Error::TooManyErrors);
+        }
+
+        // find all error locations
+        let mut error_locations = self.find_error_locations(codeword, &Λ);
+        error_locations.extend_from_slice(erasures);
+
+        // re-find error locator polynomial, this time including both
+        // errors and erasures
+        let Λ = self.find_erasure_locator(codeword, &error_locations);
+
+        // find erasure magnitude using Forney's algorithm
+        let error_magnitudes = self.find_error_magnitudes(
+            codeword,
+            &S,
+            &Λ,
+            &error_locations,
+        );
+
+        // correct the errors
+        for (&Xj, &Yj) in error_locations.iter().zip(&error_magnitudes) {
+            codeword[Xj] ^= Yj;
+        }
+
+        // re-find the syndromes to check if we were able to find all errors
+        if !self.is_correct(codeword) {
+            return Err(Error::TooManyErrors);
+        }
+
+        Ok(error_locations.len())
+    }
+}
+
+/// Naive multiplication in GF(2^8) modulo a polynomial, only used to
+/// build our tables
+fn naive_mul(a: u8, b: u8, polynomial: u16) -> u8 {
+    let mut a = u16::from(a);
+    let mut b = b;
+    let mut x = 0u16;
+    while b != 0 {
+        if b & 1 != 0 {
+            x ^= a;
+        }
+        b >>= 1;
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= polynomial;
+        }
+    }
+    x as u8
+}
@@ -0,0 +1,285 @@
+//! File-level forward error correction with k-of-n shares
+//!
+//! Much like [zfec][zfec], this splits a file into `n` shares, any `k` of
+//! which are enough to recover the file. This is useful for spreading a
+//! file across multiple disks, servers, or storage media, where some may
+//! be lost or damaged.
+//!
+//! ``` rust
+//! use gf256::rs::share;
+//!
+//! let file = b"Hello World! Split me into shares!";
+//! let mut shares = share::split(file, 3, 5);
+//! assert_eq!(shares.len(), 5);
+//!
+//! // lose a share, damage another
+//! shares.remove(1);
+//! shares[2][share::HEADER_SIZE] ^= 0xff;
+//!
+//! // the damaged share fails its CRC, but 3 good shares are enough
+//! assert_eq!(share::join(&shares)?, file);
+//! # Ok::<(), gf256::rs::Error>(())
+//! ```
+//!
+//! The file is zero-padded and divided into `k` equally sized chunks,
+//! which become the first `k` shares, so the file can be read directly from
+//! these without decoding. Byte `j` of each share forms a Reed-Solomon
+//! codeword over `GF(2^8)` with `n` symbols, and the remaining `n-k` shares
+//! hold the error-correction information. Lost or damaged shares become
+//! erasures.
+//!
+//! Each share starts with a [`HEADER_SIZE`]-byte [`ShareHeader`] recording
+//! its index, `k`, `n`, the original file length, and a CRC-32C protecting
+//! both the header and the share's contents. All fields are big-endian:
+//!
+//! ``` text
+//! +--------+-------+---+---+-------------+---------+---------------+
+//! | "gfsh" | index | k | n | length (64) | crc (32)| contents ...  |
+//! +--------+-------+---+---+-------------+---------+---------------+
+//! ```
+//!
+//! This requires feature `crc`.
+//!
+//! [zfec]: https://github.com/tahoe-lafs/zfec
+//!
+
+use super::runtime::{Rs, Error};
+use crate::crc::crc32c;
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::vec;
+
+
+/// Magic bytes at the start of every share.
+pub const MAGIC: [u8; 4] = *b"gfsh";
+
+/// Size of the header prepended to every share, in bytes.
+pub const HEADER_SIZE: usize = 4 + 3 + 8 + 4;
+
+/// Maximum number of shares.
+pub const MAX_SHARES: usize = 255;
+
+/// Header identifying a share.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ShareHeader {
+    /// Index of this share, `0..n`.
+    pub index: u8,
+    /// Number of shares needed to recover the file.
+    pub k: u8,
+    /// Total number of shares.
+    pub n: u8,
+    /// Length of the original file in bytes.
+    pub length: u64,
+    /// CRC-32C of the header, excluding this field, and the share's
+    /// contents.
+    pub crc: u32,
+}
+
+impl ShareHeader {
+    /// Serialize the header into its on-disk format.
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0u8; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4] = self.index;
+        bytes[5] = self.k;
+        bytes[6] = self.n;
+        bytes[7..15].copy_from_slice(&self.length.to_be_bytes());
+        bytes[15..19].copy_from_slice(&self.crc.to_be_bytes());
+        bytes
+    }
+
+    /// Parse the header from the beginning of a share.
+    ///
+    /// Returns `None` if the share is too small or the header doesn't
+    /// describe a valid share. Note this does not check the CRC, see
+    /// [`verify`].
+    ///
+    pub fn from_bytes(share: &[u8]) -> Option<Self> {
+        if share.len() < HEADER_SIZE || share[0..4] != MAGIC {
+            return None;
+        }
+
+        let header = Self {
+            index: share[4],
+            k: share[5],
+            n: share[6],
+            length: u64::from_be_bytes(share[7..15].try_into().unwrap()),
+            crc: u32::from_be_bytes(share[15..19].try_into().unwrap()),
+        };
+        if header.k == 0 || header.k > header.n || header.index >= header.n {
+            return None;
+        }
+
+        Some(header)
+    }
+}
+
+/// Find the CRC of a share, covering everything but the CRC itself
+fn share_crc(share: &[u8]) -> u32 {
+    crc32c(&share[HEADER_SIZE..], crc32c(&share[..HEADER_SIZE-4], 0))
+}
+
+/// Size of each share's contents for a file of the given length
+fn chunk_size(length: usize, k: usize) -> usize {
+    length.div_ceil(k)
+}
+
+/// The Reed-Solomon code protecting each byte of the shares
+fn rs(k: usize, n: usize) -> Rs {
+    Rs::new(0x11d, n, k, 0, 0x02).unwrap()
+}
+
+/// Split a file into `n` shares, any `k` of which can recover the file.
+///
+/// `n` must be at most [`MAX_SHARES`], and `k` must be in `1..=n`.
+///
+pub fn split(file: &[u8], k: usize, n: usize) -> Vec<Vec<u8>> {
+    assert!(k > 0 && k <= n && n <= MAX_SHARES);
+
+    // zero-pad and divide our file into k chunks
+    let chunk_size = chunk_size(file.len(), k);
+    let mut shares = (0..n)
+        .map(|i| {
+            let mut share = vec![0u8; HEADER_SIZE+chunk_size];
+            if i < k {
+                let chunk = file.get(i*chunk_size..).unwrap_or(&[]);
+                let chunk = &chunk[..chunk.len().min(chunk_size)];
+                share[HEADER_SIZE..HEADER_SIZE+chunk.len()].copy_from_slice(chunk);
+            }
+            share
+        })
+        .collect::<Vec<_>>();
+
+    // encode each column of bytes
+    if n > k {
+        let rs = rs(k, n);
+        let mut codeword = vec![0u8; n];
+        for j in HEADER_SIZE..HEADER_SIZE+chunk_size {
+            for (c, share) in codeword.iter_mut().zip(&shares) {
+                *c = share[j];
+            }
+            rs.encode(&mut codeword);
+            for (share, c) in shares[k..].iter_mut().zip(&codeword[k..]) {
+                share[j] = *c;
+            }
+        }
+    }
+
+    // fill in headers
+    for (i, share) in shares.iter_mut().enumerate() {
+        let mut header = ShareHeader {
+            index: i as u8,
+            k: k as u8,
+            n: n as u8,
+            length: file.len() as u64,
+            crc: 0,
+        };
+        share[..HEADER_SIZE].copy_from_slice(&header.to_bytes());
+        header.crc = share_crc(share);
+        share[..HEADER_SIZE].copy_from_slice(&header.to_bytes());
+    }
+
+    shares
+}
+
+/// Check a share's header and CRC.
+///
+/// Returns the share's header if the share is intact, or `None` if the
+/// share is damaged.
+///
+pub fn verify(share: &[u8]) -> Option<ShareHeader> {
+    let header = ShareHeader::from_bytes(share)?;
+    let chunk_size = chunk_size(
+        usize::try_from(header.length).ok()?,
+        usize::from(header.k),
+    );
+    if share.len() != HEADER_SIZE+chunk_size || share_crc(share) != header.crc {
+        return None;
+    }
+
+    Some(header)
+}
+
+/// Recover a file from a subset of its shares.
+///
+/// Shares can be in any order, and damaged shares, which fail their CRC,
+/// are ignored. At least `k` intact shares are needed.
+///
+/// Returns [`Error::TooManyErrors`] if there are fewer than `k` intact
+/// shares, or [`Error::InvalidParameters`] if the intact shares come from
+/// different files.
+///
+pub fn join<S: AsRef<[u8]>>(shares: &[S]) -> Result<Vec<u8>, Error> {
+    let mut first = None;
+    let mut found: Vec<Option<&[u8]>> = Vec::new();
+    for share in shares {
+        let share = share.as_ref();
+        let header = match verify(share) {
+            Some(header) => header,
+            None => continue,
+        };
+        let first = *first.get_or_insert(header);
+        if (header.k, header.n, header.length) != (first.k, first.n, first.length) {
+            return Err(Error::InvalidParameters);
+        }
+
+        found.resize(usize::from(header.n), None);
+        found[usize::from(header.index)] = Some(&share[HEADER_SIZE..]);
+    }
+
+    let header = first.ok_or(Error::TooManyErrors)?;
+    let k = usize::from(header.k);
+    let n = usize::from(header.n);
+    let length = usize::try_from(header.length).unwrap();
+    let chunk_size = chunk_size(length, k);
+
+    // we need at least k shares
+    if found.iter().filter(|s| s.is_some()).count() < k {
+        return Err(Error::TooManyErrors);
+    }
+
+    let mut chunks = found[..k].iter()
+        .map(|s| s.map(|s| s.to_vec()).unwrap_or_else(|| vec![0u8; chunk_size]))
+        .collect::<Vec<_>>();
+
+    // correct each column of bytes, missing shares become erasures, we
+    // only need to do this if any of the first k shares are missing
+    if found[..k].iter().any(|s| s.is_none()) {
+        let erasures = (0..n)
+            .filter(|&i| found[i].is_none())
+            .collect::<Vec<_>>();
+        let rs = rs(k, n);
+        let mut codeword = vec![0u8; n];
+        for j in 0..chunk_size {
+            for (c, s) in codeword.iter_mut().zip(&found) {
+                *c = s.map(|s| s[j]).unwrap_or(0);
+            }
+            rs.correct_erasures(&mut codeword, &erasures)?;
+            for (chunk, c) in chunks.iter_mut().zip(&codeword) {
+                chunk[j] = *c;
+            }
+        }
+    }
+
+    let mut file = chunks.concat();
+    file.truncate(length);
+    Ok(file)
+}
+
+/// Heal a set of shares, regenerating all `n` shares from at least `k`
+/// intact shares.
+///
+/// This can be used to replace lost or damaged shares before too many are
+/// lost. The regenerated shares are identical to the shares originally
+/// created by [`split`].
+///
+/// Returns the same errors as [`join`].
+///
+pub fn heal<S: AsRef<[u8]>>(shares: &[S]) -> Result<Vec<Vec<u8>>, Error> {
+    let header = shares.iter()
+        .find_map(|s| verify(s.as_ref()))
+        .ok_or(Error::TooManyErrors)?;
+    let file = join(shares)?;
+    Ok(split(&file, usize::from(header.k), usize::from(header.n)))
+}
@@ -287,11 +287,123 @@
 //! Because of this, Shamir's secret sharing scheme is limited to the number of non-zero
 //! elements in our field. In the case of `GF(256)`, this limits us to 255 shares.
 //!
+//! If you need more shares, you can use a larger field.
+//! [`shamir16`](crate::shamir::shamir16) operates over `GF(2^16)` in Barret
+//! mode, and supports up to 65535 shares. The catch is that each element is now
+//! 16-bits, so secrets and shares are made of [`u16`]s instead of bytes:
+//!
+//! ``` rust
+//! use gf256::shamir::shamir16;
+//!
+//! // pack our secret into u16s
+//! let secret = b"secret secret secret!!"
+//!     .chunks(2)
+//!     .map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]]))
+//!     .collect::<Vec<_>>();
+//!
+//! // generate 1000 shares
+//! let shares = shamir16::generate(&secret, 1000, 4);
+//! assert_eq!(shares[999][0], 1000);
+//!
+//! // any 4 reconstruct the secret
+//! assert_eq!(shamir16::reconstruct(&[&shares[3], &shares[500], &shares[999], &shares[42]]), secret);
+//! ```
+//!
+//! Other fields can be used with the [`shamir`](macro@crate::shamir::shamir)
+//! macro's `gf` and `u` options, for example `#[shamir(gf=gf2p64, u=u64)]`.
+//!
+//! ## Word-wise sharing
+//!
+//! Larger fields can also be used for throughput. Each element of the
+//! secret costs the same number of field operations regardless of the
+//! field's size, and with hardware carry-less multiplication a `GF(2^64)`
+//! multiplication is not much more expensive than a `GF(256)`
+//! multiplication. So sharing a secret 8 bytes at a time, with
+//! [`shamir64`](crate::shamir::shamir64), needs 8x fewer field operations.
+//!
+//! [`wide`](crate::shamir::wide) does this for byte secrets, packing secrets
+//! into 64-bit words:
+//!
+//! ``` rust
+//! use gf256::shamir::wide;
+//!
+//! let shares = wide::generate(b"secret secret secret!", 5, 4);
+//! assert_eq!(wide::reconstruct(&shares[..4]), b"secret secret secret!");
+//! ```
+//!
 //! ## Constant-time
 //!
 //! The default Shamir's secret-sharing implementation internally uses a custom
 //! Galois-field type in `barret` mode and should be constant-time.
 //!
+//! Generating shares only evaluates polynomials with Galois-field
+//! multiplication and addition, and reconstructing shares only divides by
+//! differences of the public x-coordinates, so neither branches on or indexes
+//! tables with secret data. This relies on the Galois-field type being
+//! constant-time, so if you provide a custom type with the `gf` option, you can
+//! enable the `constant_time` option to make sure it is in `barret` mode.
+//! [`shamir`](mod@crate::shamir::shamir),
+//! [`shamir16`](crate::shamir::shamir16), and
+//! [`shamir64`](crate::shamir::shamir64) all do this:
+//!
+//! ``` rust,compile_fail
+//! # use ::gf256::*;
+//! use gf256::gf::gf;
+//! use gf256::shamir::shamir;
+//!
+//! #[gf(polynomial=0x11d, generator=0x2, table)]
+//! type gf256_table;
+//!
+//! // fails to compile, tables are not constant-time
+//! #[shamir(gf=gf256_table, constant_time)]
+//! pub mod my_shamir {}
+//! # fn main() {}
+//! ```
+//!
+//! ## Zeroization
+//!
+//! With the `zeroize` feature enabled, Shamir's secret-sharing wipes its
+//! scratch buffers, such as the random polynomials used to generate shares
+//! and the intermediate sums used to reconstruct secrets, once they are no
+//! longer needed.
+//!
+//! The secrets and shares passed to or returned from these functions are still
+//! owned by the caller, and can be wiped with
+//! [`zeroize`](fn@crate::shamir::zeroize) when no longer needed:
+//!
+//! ``` rust
+//! # #[cfg(feature="zeroize")] {
+//! use gf256::shamir::{shamir, zeroize};
+//!
+//! let shares = shamir::generate(b"secret secret secret!", 5, 4);
+//! let mut secret = shamir::reconstruct(&shares[..4]);
+//! assert_eq!(secret, b"secret secret secret!");
+//!
+//! zeroize(&mut secret);
+//! assert_eq!(secret, [0u8; 21]);
+//! # }
+//! ```
+//!
+//! Note this is best-effort, the compiler may still leave copies of secrets
+//! in registers or on the stack.
+//!
+//! ## All-or-nothing transform
+//!
+//! Fewer than `k` shares only reveal nothing if the rng used to generate shares
+//! is truly random. As a defense in depth, especially for structured secrets,
+//! [`aont`](crate::shamir::aont) can package the secret with an all-or-nothing
+//! transform before splitting, so that recovering any of the secret needs all
+//! of the package:
+//!
+//! ``` rust
+//! use gf256::shamir::aont;
+//!
+//! let shares = aont::generate(b"secret secret secret!", 5, 4);
+//! assert_eq!(aont::reconstruct(&shares[..4]), b"secret secret secret!");
+//! ```
+//!
+//! This requires features `thread-rng` and `seeded-rng`.
+//!
 //! ## Security notes
 //!
 //! It's worth emphasizing that the gf256 was implemented primarily as an
@@ -299,12 +411,30 @@
 //! applications without first evaluating externally. You use this library at your
 //! own risk.
 //!
+//! Note also that Shamir's secret sharing trusts the dealer. A share holder has
+//! no way to verify that their share is consistent with the other shares
+//! without reconstructing the secret. The shares in
+//! [`checked`](crate::shamir::checked) can detect corrupted or mismatched
+//! shares, but not a dishonest dealer.
+//!
+//! Verifiable secret sharing, such as [Feldman's scheme][feldman-vss], fixes
+//! this by publishing commitments `C_i = g^a_i` to each coefficient of the
+//! polynomial, letting each share holder check that `g^f(x) = Π C_i^(x^i)`.
+//! Unfortunately this doesn't carry over to the fields used here. It relies on
+//! exponentiation being a homomorphism from the field the shares live in, a
+//! prime field, to a group where discrete logarithms are hard. Our shares live
+//! in `GF(2^n)`, where addition is xor, and discrete logarithms in the fields
+//! supported here are easy, so publishing `g^a_i` would leak the coefficients,
+//! including the secret itself. A sound verifiable mode would need a separate
+//! prime-field scheme with big-integer arithmetic, so gf256 doesn't provide one.
+//!
 //!
 //! [shamir-wiki]: https://en.wikipedia.org/wiki/Shamir%27s_Secret_Sharing
 //! [xkcd-4]: https://xkcd.com/221/
 //! [lagrange-interpolation]: https://en.wikipedia.org/wiki/Lagrange_polynomial
 //! [one-time-pad]: https://en.wikipedia.org/wiki/One-time_pad
 //! [shamir-example]: https://github.com/geky/gf256/blob/master/examples/shamir.rs
+//! [feldman-vss]: https://en.wikipedia.org/wiki/Verifiable_secret_sharing#Feldman%E2%80%99s_scheme
 
 
 /// A macro for generating custom Shamir secret-sharing modules.
@@ -336,7 +466,11 @@
 ///   [`gf256`](crate::gf256) in Barret mode.
 /// - `u` - The unsigned type to operate on, defaults to [`u8`].
 /// - `rng` - The random-number generator to use for generating shares, defaults
-///   to [`ThreadRng`][thread-rng].
+///   to [`ThreadRng`][thread-rng]. A cryptographically secure rng can also be
+///   passed directly to `generate_with_rng` and `splitter_with_rng`, or, with
+///   feature `seeded-rng`, a seed to `generate_from_seed`.
+/// - `constant_time` - Fail to compile unless `gf` is constant-time, that
+///   is, in `barret` mode.
 ///
 /// ``` rust,ignore
 /// # use ::gf256::*;
@@ -377,9 +511,58 @@ pub use gf256_macros::shamir;
 // custom Rng type
 //
 #[cfg(feature="thread-rng")]
-#[shamir]
+#[shamir(constant_time)]
 pub mod shamir {}
 
+// Shamir secret-sharing over GF(2^16), for up to 65535 shares
+//
+// Like the default, this needs a Barret-mode field to be constant-time
+//
+#[cfg(feature="thread-rng")]
+#[crate::gf::gf(polynomial=0x1002d, generator=0x2, barret)]
+type gf2p16_barret;
+
+#[cfg(feature="thread-rng")]
+#[shamir(gf=gf2p16_barret, u=u16, constant_time)]
+pub mod shamir16 {}
+
+// Shamir secret-sharing over GF(2^64), for sharing secrets a word at a time
+//
+#[cfg(feature="thread-rng")]
+#[crate::gf::gf(polynomial=0x1000000000000001b, generator=0x2, barret)]
+type gf2p64_barret;
+
+#[cfg(feature="thread-rng")]
+#[shamir(gf=gf2p64_barret, u=u64, constant_time)]
+pub mod shamir64 {}
+
+// Byte-oriented Shamir secret-sharing over GF(2^64)
+//
+#[cfg(feature="thread-rng")]
+#[path="shamir_wide.rs"]
+pub mod wide;
+
+/// Overwrite a buffer, such as a reconstructed secret, with zeros in a way
+/// the compiler won't optimize out.
+///
+/// This requires feature `zeroize`.
+///
+#[cfg(feature="zeroize")]
+pub use crate::internal::zeroize::zeroize;
+
+// Shamir shares with a header and CRC to detect damaged shares
+//
+#[cfg(all(feature="thread-rng", feature="crc"))]
+#[path="shamir_checked.rs"]
+pub mod checked;
+
+// All-or-nothing transform preprocessing, so partial shares leak nothing
+// even with structured secrets
+//
+#[cfg(all(feature="thread-rng", feature="seeded-rng"))]
+#[path="shamir_aont.rs"]
+pub mod aont;
+
 
 #[cfg(test)]
 mod test {
@@ -391,6 +574,7 @@ mod test {
 
     extern crate alloc;
     use alloc::vec::Vec;
+    use alloc::vec;
 
     #[cfg(feature="thread-rng")]
     #[test]
@@ -448,6 +632,78 @@ mod test {
         }
     }
 
+    // more than 255 shares
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir16_1000w100() {
+        let input = b"Hello World!"
+            .chunks(2)
+            .map(|chunk| u16::from_be_bytes(<_>::try_from(chunk).unwrap()))
+            .collect::<Vec<_>>();
+        let shares = shamir16::generate(&input, 1000, 100);
+        assert_eq!(shares.len(), 1000);
+        assert_eq!(shares[999][0], 1000);
+        for i in (0..900).step_by(300) {
+            let output = shamir16::reconstruct(&shares[i..i+99]);
+            assert_ne!(output, input);
+            let output = shamir16::reconstruct(&shares[i..i+100]);
+            assert_eq!(output, input);
+        }
+    }
+
+    // word-wise sharing
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_wide() {
+        let input = b"Hello World! Hello World!";
+        for len in 0..input.len() {
+            let shares = wide::generate(&input[..len], 5, 4);
+            assert_eq!(shares.len(), 5);
+            assert_eq!(shares[0].len(), 1 + 8*(len/8+1));
+            assert_eq!(wide::reconstruct(&shares[..4]), &input[..len]);
+            assert_eq!(wide::reconstruct(&shares[1..]), &input[..len]);
+            if len > 0 {
+                assert_ne!(wide::reconstruct(&shares[..3]), &input[..len]);
+            }
+        }
+
+        let input = [0x1234567890abcdefu64, 0, u64::MAX];
+        let shares = shamir64::generate(&input, 255, 100);
+        assert_eq!(shamir64::reconstruct(&shares[155..]), input);
+        assert_ne!(shamir64::reconstruct(&shares[156..]), input);
+    }
+
+    // all-or-nothing transform
+    #[cfg(all(feature="thread-rng", feature="seeded-rng"))]
+    #[test]
+    fn shamir_aont() {
+        let input = b"Hello World! Hello World! Hello World! Hello World!";
+        for len in 0..input.len() {
+            let package = aont::package(&input[..len]);
+            assert_eq!(package.len(), len + aont::KEY_SIZE);
+            assert_eq!(aont::unpackage(&package), &input[..len]);
+            if len >= 16 {
+                // packages are randomized
+                assert_ne!(&package[..len], &input[..len]);
+                assert_ne!(aont::package(&input[..len]), package);
+
+                // damaging any byte damages everything
+                for i in [0, len-1, len, package.len()-1] {
+                    let mut bad = package.clone();
+                    bad[i] ^= 1;
+                    let output = aont::unpackage(&bad);
+                    assert!(output.iter().zip(&input[..len]).filter(|(a, b)| a == b).count() < len/2);
+                }
+            }
+        }
+
+        let shares = aont::generate(input, 5, 4);
+        assert_eq!(shares[0].len(), 1 + input.len() + aont::KEY_SIZE);
+        assert_eq!(aont::reconstruct(&shares[..4]), input);
+        assert_eq!(aont::reconstruct(&shares[1..]), input);
+        assert_ne!(aont::reconstruct(&shares[..3]), input);
+    }
+
     // Shamir with very odd sizes
     #[cfg(feature="thread-rng")]
     #[gf(polynomial=0x13, generator=0x2)]
@@ -468,8 +724,7 @@ mod test {
     fn gf16_shamir15w10() {
         let input = b"Hello World!"
             .iter()
-            .map(|b| [(b >> 0) & 0xf, (b >> 4) & 0xf])
-            .flatten()
+            .flat_map(|b| [(b >> 0) & 0xf, (b >> 4) & 0xf])
             .collect::<Vec<_>>();
         let shares = gf16_shamir::generate(&input, 15, 10);
         assert_eq!(shares.len(), 15);
@@ -502,6 +757,356 @@ mod test {
         }
     }
 
+    // issuing new shares
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_issue_share() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate(input, 5, 3);
+
+        // issuing an existing index gives the same share
+        assert_eq!(gf256_shamir::issue_share(&shares[..3], 5), shares[4]);
+
+        // new shares work like any other share
+        let quorum = [shares[4][0], shares[2][0], shares[0][0]];
+        let contributions = [&shares[4], &shares[2], &shares[0]].iter()
+            .map(|s| gf256_shamir::contribute_share(s, &quorum, 200))
+            .collect::<Vec<_>>();
+        let share200 = gf256_shamir::combine_contributions(&contributions);
+        assert_eq!(share200, gf256_shamir::issue_share(&shares[2..], 200));
+        assert_eq!(share200[0], 200);
+        assert_eq!(gf256_shamir::reconstruct(&[&share200, &shares[1], &shares[3]]), input);
+        assert_ne!(gf256_shamir::reconstruct(&[&share200, &shares[1]]), input);
+
+        // and in larger fields
+        let input = (0..8).map(|i| i*0x0123456789abcdef).collect::<Vec<u64>>();
+        let shares = gf2p64_shamir::generate(&input, 30, 10);
+        let share1000 = gf2p64_shamir::issue_share(&shares[20..], 1000);
+        let mut quorum = shares[..9].to_vec();
+        quorum.push(share1000);
+        assert_eq!(gf2p64_shamir::reconstruct(&quorum), input);
+    }
+
+    // parallel shares
+    #[cfg(all(feature="thread-rng", feature="rayon"))]
+    #[test]
+    fn shamir_par() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let input = (0..100000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let shares = gf256_shamir::generate_par(&input, 10, 4);
+        assert_eq!(shares.len(), 10);
+        assert!(shares.iter().all(|share| share.len() == 100001));
+        assert_eq!(gf256_shamir::reconstruct(&shares[3..7]), input);
+        assert_ne!(gf256_shamir::reconstruct(&shares[3..6]), input);
+
+        // same results as split
+        let mut splitter = gf256_shamir::splitter_with_rng(StdRng::seed_from_u64(1), 10, 4);
+        let mut splitter_par = gf256_shamir::splitter_with_rng(StdRng::seed_from_u64(1), 10, 4);
+        for chunk in input.chunks(10000) {
+            assert_eq!(splitter.split(chunk), splitter_par.split_par(chunk));
+        }
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_reconstruct_many() {
+        // long enough for any slice kernels, with leftovers
+        let inputs = (0..10)
+            .map(|i| (0..1000+i).map(|j| (i*j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+        let shares = inputs.iter()
+            .map(|input| gf256_shamir::generate(input, 10, 4))
+            .collect::<Vec<_>>();
+
+        let quorums = shares.iter()
+            .map(|shares| [&shares[7], &shares[1], &shares[4], &shares[2]])
+            .collect::<Vec<_>>();
+        assert_eq!(gf256_shamir::reconstruct_many(&quorums), inputs);
+
+        let quorums = shares.iter()
+            .map(|shares| [&shares[7], &shares[1], &shares[4]])
+            .collect::<Vec<_>>();
+        let outputs = gf256_shamir::reconstruct_many(&quorums);
+        for (output, input) in outputs.iter().zip(&inputs) {
+            assert_ne!(output, input);
+        }
+    }
+
+    // robust reconstruction
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_robust() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate(input, 10, 4);
+        assert_eq!(gf256_shamir::reconstruct_robust(&shares, 4), Some((input.to_vec(), vec![])));
+
+        // up to (n-k)/2 = 3 bad shares can be corrected, including in the
+        // shares we check against
+        let mut bad = shares.clone();
+        bad[0][1] ^= 0x01;
+        bad[5][12] ^= 0xff;
+        bad[9][3] = b'x';
+        bad[9][4] = b'x';
+        assert_eq!(gf256_shamir::reconstruct_robust(&bad, 4), Some((input.to_vec(), vec![0, 5, 9])));
+        assert_eq!(gf256_shamir::reconstruct_robust(&bad[..9], 4), Some((input.to_vec(), vec![0, 5])));
+
+        // more can't be corrected
+        bad[2][6] ^= 0x10;
+        assert_eq!(gf256_shamir::reconstruct_robust(&bad, 4), None);
+        bad[2][6] ^= 0x10;
+        bad[2][7] ^= 0x10;
+        assert_eq!(gf256_shamir::reconstruct_robust(&bad, 4), None);
+        assert_eq!(gf256_shamir::reconstruct_robust(&shares[..3], 4), None);
+
+        // larger fields work too
+        let input = b"Hello World!\0\0\0\0"
+            .chunks(8)
+            .map(|chunk| u64::from_le_bytes(<_>::try_from(chunk).unwrap()))
+            .collect::<Vec<_>>();
+        let mut shares = gf2p64_shamir::generate(&input, 8, 4);
+        shares[3][1] ^= 1;
+        shares[6][2] ^= 1;
+        assert_eq!(gf2p64_shamir::reconstruct_robust(&shares, 4), Some((input, vec![3, 6])));
+    }
+
+    // Shamir with a provided rng
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_with_rng() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate_with_rng(StdRng::seed_from_u64(1), input, 5, 4);
+        assert_eq!(gf256_shamir::reconstruct(&shares[1..]), input);
+        assert_eq!(shares, gf256_shamir::generate_with_rng(StdRng::seed_from_u64(1), input, 5, 4));
+        assert_ne!(shares, gf256_shamir::generate_with_rng(StdRng::seed_from_u64(2), input, 5, 4));
+
+        // rngs can also be borrowed
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut splitter = gf256_shamir::splitter_with_rng(&mut rng, 5, 4);
+        let ys = splitter.split(input);
+        for (share, ys) in shares.iter().zip(ys) {
+            assert_eq!(share[1..], ys[..]);
+        }
+    }
+
+    #[cfg(all(feature="thread-rng", feature="seeded-rng"))]
+    #[test]
+    fn shamir_from_seed() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate_from_seed([1; 32], input, 5, 4);
+        assert_eq!(shares, gf256_shamir::generate_from_seed([1; 32], input, 5, 4));
+        assert_ne!(shares, gf256_shamir::generate_from_seed([2; 32], input, 5, 4));
+        assert_eq!(gf256_shamir::reconstruct(&shares[1..]), input);
+
+        // these must never change, or shares generated from a seed can't
+        // be regenerated
+        assert_eq!(shares[0], [0x01, 0xb0, 0xb9, 0x12, 0x27, 0x55, 0x3e, 0x99, 0x8e, 0x5c, 0xae, 0x5d, 0xf3]);
+        assert_eq!(shares[4], [0x05, 0x42, 0xcb, 0x22, 0x4b, 0x3c, 0xc2, 0xe7, 0x52, 0x6d, 0x0b, 0x11, 0x41]);
+    }
+
+    // constant-time Shamir, this fails to compile if gf256_barret isn't
+    // constant-time
+    #[cfg(feature="thread-rng")]
+    #[gf(polynomial=0x11d, generator=0x2, barret)]
+    type gf256_barret;
+    #[cfg(feature="thread-rng")]
+    #[shamir(gf=gf256_barret, u=u8, constant_time)]
+    mod gf256_barret_shamir {}
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_constant_time() {
+        let input = b"Hello World!";
+        let shares = gf256_barret_shamir::generate(input, 5, 4);
+        assert_ne!(gf256_barret_shamir::reconstruct(&shares[..3]), input);
+        assert_eq!(gf256_barret_shamir::reconstruct(&shares[..4]), input);
+    }
+
+    // streaming shares
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_splitter() {
+        let input = (0..1000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let mut splitter = gf256_shamir::splitter(10, 4);
+        let mut shares = splitter.indices()
+            .into_iter()
+            .map(|x| vec![x])
+            .collect::<Vec<_>>();
+        for chunk in input.chunks(77) {
+            for (share, ys) in shares.iter_mut().zip(splitter.split(chunk)) {
+                share.extend(ys);
+            }
+        }
+        assert_eq!(gf256_shamir::reconstruct(&shares[3..7]), input);
+        assert_ne!(gf256_shamir::reconstruct(&shares[3..6]), input);
+
+        let joiner = gf256_shamir::Joiner::new(&[shares[9][0], shares[0][0], shares[5][0], shares[2][0]]);
+        let mut output = Vec::new();
+        for i in (1..shares[0].len()).step_by(100) {
+            let j = (i+100).min(shares[0].len());
+            output.extend(joiner.join(&[&shares[9][i..j], &shares[0][i..j], &shares[5][i..j], &shares[2][i..j]]));
+        }
+        assert_eq!(output, input);
+    }
+
+    #[cfg(all(feature="thread-rng", feature="std"))]
+    #[test]
+    fn shamir_stream() {
+        // larger than a chunk
+        let input = (0..10000).map(|i| (i*7) as u8).collect::<Vec<u8>>();
+        let mut shares = vec![Vec::new(); 10];
+        assert_eq!(gf256_shamir::generate_stream(&input[..], &mut shares, 4).unwrap(), 10000);
+        assert!(shares.iter().all(|share| share.len() == 10001));
+        assert_eq!(gf256_shamir::reconstruct(&shares[3..7]), input);
+
+        let mut readers = [&shares[8][..], &shares[1][..], &shares[4][..], &shares[6][..]];
+        let mut output = Vec::new();
+        assert_eq!(gf256_shamir::reconstruct_stream(&mut readers, &mut output).unwrap(), 10000);
+        assert_eq!(output, input);
+
+        // mismatched shares are an error
+        let mut readers = [&shares[8][..], &shares[1][..5000], &shares[4][..]];
+        let err = gf256_shamir::reconstruct_stream(&mut readers, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature="thread-rng")]
+    #[test]
+    fn shamir_reshare() {
+        let input = b"Hello World!";
+        let shares = gf256_shamir::generate(input, 5, 3);
+
+        // 3-of-5 => 6-of-10
+        let subshares = [&shares[4], &shares[0], &shares[2]].iter()
+            .map(|share| gf256_shamir::reshare(share, 10, 6))
+            .collect::<Vec<_>>();
+        let new_shares = (0..10)
+            .map(|j| {
+                let subshares = subshares.iter().map(|s| &s[j]).collect::<Vec<_>>();
+                gf256_shamir::combine_reshares(&subshares)
+            })
+            .collect::<Vec<_>>();
+        for (j, share) in new_shares.iter().enumerate() {
+            assert_eq!(share[0], j as u8 + 1);
+        }
+        assert_ne!(gf256_shamir::reconstruct(&new_shares[2..7]), input);
+        assert_eq!(gf256_shamir::reconstruct(&new_shares[2..8]), input);
+
+        // 6-of-10 => 2-of-3, with more old holders than needed
+        let subshares = new_shares[1..9].iter()
+            .map(|share| gf256_shamir::reshare(share, 3, 2))
+            .collect::<Vec<_>>();
+        let new_shares = (0..3)
+            .map(|j| {
+                let subshares = subshares.iter().map(|s| &s[j]).collect::<Vec<_>>();
+                gf256_shamir::combine_reshares(&subshares)
+            })
+            .collect::<Vec<_>>();
+        assert_ne!(gf256_shamir::reconstruct(&new_shares[2..]), input);
+        assert_eq!(gf256_shamir::reconstruct(&new_shares[1..]), input);
+    }
+
+    // integrity-checked shares
+    #[cfg(all(feature="thread-rng", feature="crc"))]
+    #[test]
+    fn shamir_checked() {
+        let input = b"Hello World!";
+        let shares = checked::generate(input, 5, 3);
+        assert_eq!(shares.len(), 5);
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(share.len(), checked::HEADER_SIZE + input.len() + checked::CRC_SIZE);
+            assert_eq!(checked::verify(share), Some((i as u8 + 1, 3)));
+        }
+        for i in 0..5 {
+            let output = checked::reconstruct(&shares[..i]);
+            if i < 3 {
+                assert_eq!(output, Err(checked::Error::TooFewShares));
+            } else {
+                assert_eq!(output, Ok(input.to_vec()));
+            }
+        }
+
+        // truncated
+        let mut bad = shares.clone();
+        bad[2].pop();
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::InvalidShare(2)));
+
+        // transposed bytes
+        let mut bad = shares.clone();
+        bad[1].swap(4, 5);
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::InvalidShare(1)));
+
+        // duplicated
+        let bad = [&shares[0], &shares[1], &shares[0]];
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::DuplicateShare));
+
+        // from a different secret
+        let other = checked::generate(b"Hello World", 5, 3);
+        let bad = [&shares[0], &shares[1], &other[2]];
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::MismatchedShares));
+        let other = checked::generate(input, 5, 2);
+        let bad = [&shares[0], &shares[1], &other[2]];
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::MismatchedShares));
+        let other = checked::generate(input, 5, 3);
+        let bad = [&shares[0], &shares[1], &other[2]];
+        assert_eq!(checked::reconstruct(&bad), Err(checked::Error::MismatchedShares));
+    }
+
+    #[cfg(all(feature="thread-rng", feature="crc"))]
+    #[test]
+    fn shamir_checked_shares() {
+        let input = b"Hello World!";
+        let shares = checked::generate_shares(input, 5, 3);
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(share.id, shares[0].id);
+            assert_eq!(share.index, i as u8 + 1);
+            assert_eq!(share.threshold, 3);
+
+            let bytes = share.to_bytes();
+            assert_eq!(bytes.len(), share.encoded_size());
+            assert_eq!(checked::Share::from_bytes(&bytes).as_ref(), Some(share));
+            assert_eq!(bytes[checked::HEADER_SIZE-1..bytes.len()-checked::CRC_SIZE], share.to_raw());
+        }
+        assert_eq!(checked::reconstruct_shares(&shares[2..]), Ok(input.to_vec()));
+        assert_eq!(gf256_shamir::reconstruct(&[shares[0].to_raw(), shares[4].to_raw(), shares[3].to_raw()]), input);
+
+        // invalid shares
+        let mut bad = shares.clone();
+        bad[1].index = 0;
+        assert_eq!(checked::reconstruct_shares(&bad), Err(checked::Error::InvalidShare(1)));
+        let mut bad = shares.clone();
+        bad[2].id ^= 1;
+        assert_eq!(checked::reconstruct_shares(&bad), Err(checked::Error::MismatchedShares));
+        let mut bad = shares.clone();
+        bad[3].payload.push(0);
+        assert_eq!(checked::reconstruct_shares(&bad), Err(checked::Error::MismatchedShares));
+    }
+
+    #[cfg(all(feature="thread-rng", feature="crc", feature="serde"))]
+    #[test]
+    fn shamir_checked_serde() {
+        let input = b"Hello World!";
+        let shares = checked::generate_shares(input, 5, 3);
+
+        // human-readable
+        let json = serde_json::to_string(&shares).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<checked::Share>>(&json).unwrap(), shares);
+        let mut bad = json.clone().into_bytes();
+        bad[20] = if bad[20] == b'0' { b'1' } else { b'0' };
+        assert!(serde_json::from_slice::<Vec<checked::Share>>(&bad).is_err());
+        assert!(serde_json::from_str::<checked::Share>("\"0g\"").is_err());
+        assert!(serde_json::from_str::<checked::Share>("\"012\"").is_err());
+
+        // binary
+        let cbor = serde_cbor::to_vec(&shares[0]).unwrap();
+        assert!(cbor.len() <= shares[0].encoded_size() + 2);
+        assert_eq!(serde_cbor::from_slice::<checked::Share>(&cbor).unwrap(), shares[0]);
+    }
+
     // TODO test this without ThreadRng?
 
     // all Shamir parameters 
@@ -0,0 +1,195 @@
+//! All-or-nothing transform preprocessing for Shamir secret-sharing
+//!
+//! Shamir's secret-sharing is information-theoretically secure, fewer than
+//! `k` shares reveal nothing about the secret. But this relies on the
+//! random polynomials being truly random. With a weak or misconfigured rng,
+//! fewer than `k` shares can leak partial information, and structured or
+//! compressible secrets, such as text or keys with known headers, make
+//! this leakage more useful to an attacker.
+//!
+//! An all-or-nothing transform (AONT) defends against this. It transforms
+//! the secret into a "package" where every byte of the package is needed
+//! to recover any byte of the secret. So even if a byte or two of the
+//! package leaks, the secret remains hidden. This implements Rivest's
+//! [package transform][package-transform] with ChaCha20:
+//!
+//! ``` text
+//! K  = a random 32-byte key
+//! c  = m xor ChaCha20(K)
+//! h  = F(c_1 xor 1) xor F(c_2 xor 2) xor ... xor F(c_s xor s)
+//! package = c || (K xor h)
+//! ```
+//!
+//! Where `c_i` are 32-byte blocks of `c`, with the last block padded with
+//! zeros, and `F(x)` is the first 32 bytes of ChaCha20's keystream keyed
+//! with `x`. Without every block of `c`, `h` and thus `K` can't be found,
+//! and without `K`, `c` reveals nothing about `m`.
+//!
+//! ``` rust
+//! use gf256::shamir::aont;
+//!
+//! // package and generate shares
+//! let shares = aont::generate(b"secret secret secret!", 5, 4);
+//! assert_eq!(shares[0].len(), 1+21+aont::KEY_SIZE);
+//!
+//! // >=4 can reconstruct secret
+//! assert_eq!(aont::reconstruct(&shares[..4]), b"secret secret secret!");
+//! ```
+//!
+//! Note the package transform does not check for integrity, a damaged
+//! package, or a package reconstructed from too few shares, unpackages
+//! into garbage.
+//!
+//! This requires features `thread-rng` and `seeded-rng`.
+//!
+//! [package-transform]: https://people.csail.mit.edu/rivest/pubs/Riv97d.prepub.pdf
+//!
+
+use super::shamir;
+use crate::internal::rand::Rng;
+use crate::internal::rand::CryptoRng;
+use crate::internal::rand::RngCore;
+use crate::internal::rand::SeedableRng;
+use crate::internal::rand::rngs::ThreadRng;
+use crate::internal::rand_chacha::ChaCha20Rng;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// Size of the key appended to each package, in bytes.
+pub const KEY_SIZE: usize = 32;
+
+/// Size of each block hashed into the key, in bytes
+const BLOCK_SIZE: usize = 32;
+
+/// Hash a block of the package, with its index, into 32 bytes
+///
+/// This uses the block, xored with its index, as a ChaCha20 key, so
+/// finding a block from its hash requires breaking ChaCha20.
+///
+fn hash_block(i: usize, block: &[u8]) -> [u8; KEY_SIZE] {
+    let mut key = [0u8; BLOCK_SIZE];
+    key[..block.len()].copy_from_slice(block);
+    for (k, b) in key.iter_mut().zip((i as u64 + 1).to_le_bytes()) {
+        *k ^= b;
+    }
+
+    let mut h = [0u8; KEY_SIZE];
+    ChaCha20Rng::from_seed(key).fill_bytes(&mut h);
+    wipe(&mut key);
+    h
+}
+
+/// Hash all blocks of the package into 32 bytes
+fn hash(c: &[u8]) -> [u8; KEY_SIZE] {
+    let mut h = [0u8; KEY_SIZE];
+    for (i, block) in c.chunks(BLOCK_SIZE).enumerate() {
+        for (h, b) in h.iter_mut().zip(hash_block(i, block)) {
+            *h ^= b;
+        }
+    }
+    h
+}
+
+/// Xor a buffer with ChaCha20's keystream
+fn apply_keystream(key: [u8; KEY_SIZE], buf: &mut [u8]) {
+    let mut rng = ChaCha20Rng::from_seed(key);
+    let mut stream = [0u8; BLOCK_SIZE];
+    for chunk in buf.chunks_mut(BLOCK_SIZE) {
+        rng.fill_bytes(&mut stream);
+        for (x, s) in chunk.iter_mut().zip(stream) {
+            *x ^= s;
+        }
+    }
+    wipe(&mut stream);
+}
+
+/// Transform a secret into a package, [`KEY_SIZE`] bytes larger than the
+/// secret, where all of the package is needed to recover any of the
+/// secret.
+///
+pub fn package(secret: &[u8]) -> Vec<u8> {
+    package_with_rng(ThreadRng::default(), secret)
+}
+
+/// Transform a secret into a package, using the provided random-number
+/// generator to generate the key.
+///
+/// Like [`shamir::generate_with_rng`], the rng must be cryptographically
+/// secure.
+///
+pub fn package_with_rng<R: Rng + CryptoRng>(
+    mut rng: R,
+    secret: &[u8]
+) -> Vec<u8> {
+    let mut key = [0u8; KEY_SIZE];
+    rng.fill_bytes(&mut key);
+
+    let mut package = Vec::with_capacity(secret.len() + KEY_SIZE);
+    package.extend_from_slice(secret);
+    apply_keystream(key, &mut package[..secret.len()]);
+
+    let h = hash(&package);
+    package.extend(key.iter().zip(h).map(|(k, h)| k ^ h));
+    wipe(&mut key);
+    package
+}
+
+/// Recover a secret from a package.
+///
+/// If the package is damaged in any way, the result will be garbage.
+///
+pub fn unpackage(package: &[u8]) -> Vec<u8> {
+    assert!(package.len() >= KEY_SIZE, "invalid package length?");
+    let (c, k) = package.split_at(package.len() - KEY_SIZE);
+
+    let h = hash(c);
+    let mut key = [0u8; KEY_SIZE];
+    for (x, (k, h)) in key.iter_mut().zip(k.iter().zip(h)) {
+        *x = k ^ h;
+    }
+
+    let mut secret = c.to_vec();
+    apply_keystream(key, &mut secret);
+    wipe(&mut key);
+    secret
+}
+
+/// Package a secret and generate `n` shares requiring `k` shares to
+/// reconstruct.
+///
+/// Each share is [`KEY_SIZE`] bytes larger than the shares generated by
+/// [`shamir::generate`].
+///
+pub fn generate(secret: &[u8], n: usize, k: usize) -> Vec<Vec<u8>> {
+    let mut package = package(secret);
+    let shares = shamir::generate(&package, n, k);
+    wipe(&mut package);
+    shares
+}
+
+/// Attempt to reconstruct and unpackage a secret from at least `k` shares.
+///
+/// If insufficient or invalid shares are provided, the result will be
+/// garbage.
+///
+pub fn reconstruct<S: AsRef<[u8]>>(shares: &[S]) -> Vec<u8> {
+    let mut package = shamir::reconstruct(shares);
+    let secret = unpackage(&package);
+    wipe(&mut package);
+    secret
+}
+
+/// Wipe a buffer, if the zeroize feature is enabled
+#[inline]
+fn wipe<T: Copy + Default>(buf: &mut [T]) {
+    #[cfg(feature="zeroize")]
+    {
+        crate::internal::zeroize::zeroize(buf);
+    }
+    #[cfg(not(feature="zeroize"))]
+    {
+        let _ = buf;
+    }
+}
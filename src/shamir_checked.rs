@@ -0,0 +1,390 @@
+//! Integrity-checked Shamir shares
+//!
+//! Raw shares from [`shamir::generate`] are just an x-coordinate followed by
+//! y-coordinates. If a share is truncated, corrupted, or from a different
+//! secret, [`reconstruct`](crate::shamir::shamir::reconstruct) has no way to
+//! know, and silently returns garbage.
+//!
+//! This provides a structured [`Share`], which records the threshold `k`,
+//! the share's index (its x-coordinate), and a random id shared by all
+//! shares from the same split, so shares from different splits can't be
+//! mixed by accident.
+//!
+//! Shares are serialized with a small header recording the format version,
+//! followed by a CRC-32C over everything else. All fields are big-endian:
+//!
+//! ``` text
+//! +-------------+-------+---------+-----------+-------------------+----------+
+//! | version (8) | k (8) | id (32) | index (8) | y-coordinates ... | crc (32) |
+//! +-------------+-------+---------+-----------+-------------------+----------+
+//! ```
+//!
+//! Note the index and y-coordinates are exactly a raw share.
+//!
+//! [`reconstruct`] then strictly validates its shares before combining
+//! them:
+//!
+//! ``` rust
+//! use gf256::shamir::checked;
+//!
+//! let mut shares = checked::generate(b"secret secret secret!", 5, 3);
+//!
+//! // any 3 intact shares reconstruct the secret
+//! assert_eq!(checked::reconstruct(&shares[..3])?, b"secret secret secret!");
+//!
+//! // but damaged shares are rejected, not silently accepted
+//! shares[1][9] ^= 0x01;
+//! assert_eq!(checked::reconstruct(&shares[..3]), Err(checked::Error::InvalidShare(1)));
+//! assert_eq!(checked::reconstruct(&shares[2..]), Ok(b"secret secret secret!".to_vec()));
+//!
+//! // as are too few shares
+//! assert_eq!(checked::reconstruct(&shares[3..]), Err(checked::Error::TooFewShares));
+//!
+//! // and shares from a different split
+//! let other = checked::generate(b"secret secret secret!", 5, 3);
+//! assert_eq!(
+//!     checked::reconstruct(&[&shares[2], &shares[3], &other[4]]),
+//!     Err(checked::Error::MismatchedShares)
+//! );
+//! # Ok::<(), checked::Error>(())
+//! ```
+//!
+//! The structured [`Share`] can also be used directly:
+//!
+//! ``` rust
+//! use gf256::shamir::checked::{self, Share};
+//!
+//! let shares = checked::generate_shares(b"secret secret secret!", 5, 3);
+//! assert_eq!(shares[1].index, 2);
+//! assert_eq!(shares[1].threshold, 3);
+//!
+//! // round-trip through the wire format
+//! let bytes = shares[1].to_bytes();
+//! assert_eq!(Share::from_bytes(&bytes), Some(shares[1].clone()));
+//!
+//! assert_eq!(checked::reconstruct_shares(&shares[2..])?, b"secret secret secret!");
+//! # Ok::<(), checked::Error>(())
+//! ```
+//!
+//! Note a CRC only protects against accidental damage, not a malicious
+//! share holder, who can simply recompute the CRC.
+//!
+//! With the `serde` feature, [`Share`] also implements `Serialize` and
+//! `Deserialize` using the same wire format, including the CRC, so shares
+//! are checked when deserialized. Human-readable formats, such as JSON,
+//! store the wire format as a hex string, while binary formats, such as
+//! CBOR, store it as raw bytes:
+//!
+//! ``` rust
+//! # #[cfg(feature="serde")] {
+//! use gf256::shamir::checked::{self, Share};
+//!
+//! let shares = checked::generate_shares(b"secret secret secret!", 5, 3);
+//! let json = serde_json::to_string(&shares[1]).unwrap();
+//! assert_eq!(json, format!("\"{}\"", hex(&shares[1].to_bytes())));
+//! assert_eq!(serde_json::from_str::<Share>(&json).unwrap(), shares[1]);
+//! # }
+//! # fn hex(bytes: &[u8]) -> String {
+//! #     bytes.iter().map(|b| format!("{:02x}", b)).collect()
+//! # }
+//! ```
+//!
+//! This requires features `crc` and `thread-rng`.
+//!
+
+use super::shamir;
+use crate::crc::crc32c;
+use crate::internal::rand::Rng;
+use crate::internal::rand::rngs::ThreadRng;
+use core::fmt;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// Current version of the share format.
+pub const VERSION: u8 = 1;
+
+/// Size of the header prepended to each share, in bytes.
+pub const HEADER_SIZE: usize = 7;
+
+/// Size of the CRC appended to each share, in bytes.
+pub const CRC_SIZE: usize = 4;
+
+/// Error codes for integrity-checked Shamir shares
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// The share at this position is truncated, fails its CRC, has an
+    /// unknown version, or otherwise isn't a valid share
+    InvalidShare(usize),
+
+    /// The shares have different ids, thresholds, or lengths, and can't be
+    /// from the same split
+    MismatchedShares,
+
+    /// The same share index appears more than once
+    DuplicateShare,
+
+    /// Fewer shares than the threshold were provided
+    TooFewShares,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidShare(i) => write!(f, "Invalid share at position {}", i),
+            Error::MismatchedShares => write!(f, "Shares are from different secrets"),
+            Error::DuplicateShare => write!(f, "Duplicate share index"),
+            Error::TooFewShares => write!(f, "Too few shares to reconstruct secret"),
+        }
+    }
+}
+
+#[cfg(feature="std")]
+impl crate::internal::std::error::Error for Error {}
+
+/// A Shamir share with its threshold and split id.
+///
+/// Note the index is the x-coordinate of the share, and the payload holds
+/// its y-coordinates, one per byte of the secret.
+///
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Share {
+    /// Random id shared by all shares from the same split
+    pub id: u32,
+    /// Number of shares needed to reconstruct the secret
+    pub threshold: u8,
+    /// Index of this share, never zero
+    pub index: u8,
+    /// The share's y-coordinates
+    pub payload: Vec<u8>,
+}
+
+impl Share {
+    /// Size of this share in the wire format, in bytes.
+    pub fn encoded_size(&self) -> usize {
+        HEADER_SIZE + self.payload.len() + CRC_SIZE
+    }
+
+    /// Serialize this share to the wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.encoded_size());
+        bytes.push(VERSION);
+        bytes.push(self.threshold);
+        bytes.extend_from_slice(&self.id.to_be_bytes());
+        bytes.push(self.index);
+        bytes.extend_from_slice(&self.payload);
+        let crc = crc32c(&bytes, 0);
+        bytes.extend_from_slice(&crc.to_be_bytes());
+        bytes
+    }
+
+    /// Parse a share from the wire format, checking its version, header,
+    /// and CRC.
+    ///
+    /// Returns `None` if the share is damaged.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Option<Share> {
+        if bytes.len() < HEADER_SIZE + CRC_SIZE {
+            return None;
+        }
+
+        let (bytes, crc) = bytes.split_at(bytes.len()-CRC_SIZE);
+        let share = Share {
+            id: u32::from_be_bytes(bytes[2..6].try_into().unwrap()),
+            threshold: bytes[1],
+            index: bytes[6],
+            payload: bytes[HEADER_SIZE..].to_vec(),
+        };
+        if bytes[0] != VERSION
+            || share.index == 0
+            || share.threshold == 0
+            || crc32c(bytes, 0) != u32::from_be_bytes(crc.try_into().unwrap())
+        {
+            return None;
+        }
+
+        Some(share)
+    }
+
+    /// View this share as a raw share, as used by [`shamir`](mod@crate::shamir::shamir).
+    pub fn to_raw(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(1 + self.payload.len());
+        raw.push(self.index);
+        raw.extend_from_slice(&self.payload);
+        raw
+    }
+}
+
+/// Generate `n` structured shares requiring `k` shares to reconstruct.
+///
+/// Like [`shamir::generate`], `n` is limited to 255 shares.
+///
+pub fn generate_shares(secret: &[u8], n: usize, k: usize) -> Vec<Share> {
+    assert!(k > 0 && k <= n);
+    let id = ThreadRng::default().gen::<u32>();
+    shamir::generate(secret, n, k)
+        .into_iter()
+        .map(|mut raw| Share {
+            id,
+            threshold: k as u8,
+            // raw shares are prefixed with their index
+            index: raw.remove(0),
+            payload: raw,
+        })
+        .collect()
+}
+
+/// Generate `n` integrity-checked shares requiring `k` shares to
+/// reconstruct, serialized in the wire format.
+///
+/// Like [`shamir::generate`], `n` is limited to 255 shares.
+///
+pub fn generate(secret: &[u8], n: usize, k: usize) -> Vec<Vec<u8>> {
+    generate_shares(secret, n, k)
+        .iter()
+        .map(Share::to_bytes)
+        .collect()
+}
+
+/// Check a share's version, header, and CRC.
+///
+/// Returns the share's index and threshold `k` if the share is intact, or
+/// `None` if the share is damaged.
+///
+pub fn verify(share: &[u8]) -> Option<(u8, u8)> {
+    Share::from_bytes(share).map(|share| (share.index, share.threshold))
+}
+
+/// Reconstruct a secret from at least `k` structured shares.
+///
+/// Unlike [`shamir::reconstruct`], this returns an [`Error`] instead of
+/// garbage if any share has a zero index or threshold, is duplicated, or
+/// is from a different split, or if there are fewer than `k` shares.
+///
+pub fn reconstruct_shares(shares: &[Share]) -> Result<Vec<u8>, Error> {
+    if let Some(i) = shares.iter().position(|share| share.index == 0 || share.threshold == 0) {
+        return Err(Error::InvalidShare(i));
+    }
+
+    let first = shares.first().ok_or(Error::TooFewShares)?;
+    if shares.iter().any(|share| {
+        share.id != first.id
+            || share.threshold != first.threshold
+            || share.payload.len() != first.payload.len()
+    }) {
+        return Err(Error::MismatchedShares);
+    }
+
+    if shares.len() < usize::from(first.threshold) {
+        return Err(Error::TooFewShares);
+    }
+
+    // check for duplicates
+    for (i, a) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|b| a.index == b.index) {
+            return Err(Error::DuplicateShare);
+        }
+    }
+
+    let raws = shares.iter().map(Share::to_raw).collect::<Vec<_>>();
+    let secret = shamir::reconstruct(&raws);
+    #[cfg(feature="zeroize")]
+    for mut raw in raws {
+        crate::internal::zeroize::zeroize(&mut raw);
+    }
+    Ok(secret)
+}
+
+/// Reconstruct a secret from at least `k` integrity-checked shares.
+///
+/// Unlike [`shamir::reconstruct`], this validates every share first, and
+/// returns an [`Error`] instead of garbage if any share is damaged,
+/// duplicated, or from a different split, or if there are fewer than `k`
+/// shares.
+///
+pub fn reconstruct<S: AsRef<[u8]>>(shares: &[S]) -> Result<Vec<u8>, Error> {
+    let shares = shares.iter()
+        .enumerate()
+        .map(|(i, share)| Share::from_bytes(share.as_ref()).ok_or(Error::InvalidShare(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let secret = reconstruct_shares(&shares);
+    #[cfg(feature="zeroize")]
+    for mut share in shares {
+        crate::internal::zeroize::zeroize(&mut share.payload);
+    }
+    secret
+}
+
+
+// serde support, shares are serialized in the wire format, as a hex
+// string in human-readable formats, and as bytes otherwise
+
+#[cfg(feature="serde")]
+impl serde::Serialize for Share {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes();
+        if serializer.is_human_readable() {
+            const HEX: &[u8; 16] = b"0123456789abcdef";
+            let hex = bytes.iter()
+                .flat_map(|b| [char::from(HEX[usize::from(b >> 4)]), char::from(HEX[usize::from(b & 0xf)])])
+                .collect::<alloc::string::String>();
+            serializer.serialize_str(&hex)
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+#[cfg(feature="serde")]
+impl<'de> serde::Deserialize<'de> for Share {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Share, D::Error> {
+        struct ShareVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ShareVisitor {
+            type Value = Share;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a Shamir share in hex or bytes")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Share, E> {
+                Share::from_bytes(bytes)
+                    .ok_or_else(|| E::custom("damaged Shamir share"))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, hex: &str) -> Result<Share, E> {
+                if !hex.len().is_multiple_of(2) {
+                    return Err(E::invalid_value(serde::de::Unexpected::Str(hex), &self));
+                }
+                let bytes = (0..hex.len())
+                    .step_by(2)
+                    .map(|i| {
+                        hex.get(i..i+2)
+                            .and_then(|b| u8::from_str_radix(b, 16).ok())
+                    })
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(hex), &self))?;
+                self.visit_bytes(&bytes)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Share, A::Error> {
+                // don't trust the size hint for more than a reasonably
+                // sized share, like serde's own cautious size hints
+                let mut bytes = Vec::with_capacity(
+                    seq.size_hint().unwrap_or(0).min(4096)
+                );
+                while let Some(b) = seq.next_element::<u8>()? {
+                    bytes.push(b);
+                }
+                self.visit_bytes(&bytes)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(ShareVisitor)
+        } else {
+            deserializer.deserialize_bytes(ShareVisitor)
+        }
+    }
+}
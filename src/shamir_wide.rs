@@ -0,0 +1,132 @@
+//! Shamir secret-sharing over GF(2^64), one 64-bit word at a time
+//!
+//! This shares byte secrets with [`shamir64`], packing
+//! the secret into little-endian 64-bit words. This needs 8x fewer field
+//! operations than sharing one byte at a time, which, with hardware
+//! carry-less multiplication, is significantly faster for bulk secrets.
+//!
+//! Since the secret is shared in whole words, it is padded before sharing,
+//! with a single `0x80` byte followed by zeros up to a multiple of 8 bytes,
+//! and this padding is removed after reconstructing. Each share is then
+//! laid out like a byte-oriented share:
+//!
+//! ``` text
+//! +-----------+------------------------------------+
+//! | index (8) | y-coordinates (64, little-endian)  |
+//! +-----------+------------------------------------+
+//! ```
+//!
+//! ``` rust
+//! use gf256::shamir::wide;
+//!
+//! // generate shares
+//! let shares = wide::generate(b"secret secret secret!", 5, 4);
+//! assert_eq!(shares[0].len(), 1+24);
+//!
+//! // <4 can't reconstruct secret
+//! assert_ne!(wide::reconstruct(&shares[..3]), b"secret secret secret!");
+//!
+//! // >=4 can reconstruct secret
+//! assert_eq!(wide::reconstruct(&shares[..4]), b"secret secret secret!");
+//! ```
+//!
+//! Note these shares are not compatible with
+//! [`shamir`](mod@crate::shamir::shamir)'s shares.
+//!
+
+use super::shamir64;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+
+/// Size of each word in bytes
+const WORD_SIZE: usize = 8;
+
+/// Generate `n` shares requiring `k` shares to reconstruct.
+///
+/// Like [`shamir::generate`](super::shamir::generate), `n` is limited to
+/// 255 shares, since the index of each share is stored in a single byte.
+///
+pub fn generate(secret: &[u8], n: usize, k: usize) -> Vec<Vec<u8>> {
+    assert!(n <= 255, "exceeded 255 shares");
+
+    // pad to a multiple of our word size
+    let mut padded = Vec::with_capacity(secret.len() + WORD_SIZE);
+    padded.extend_from_slice(secret);
+    padded.push(0x80);
+    padded.resize(padded.len().next_multiple_of(WORD_SIZE), 0);
+
+    let mut words = padded.chunks(WORD_SIZE)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    let shares = shamir64::generate(&words, n, k)
+        .into_iter()
+        .map(|share| {
+            let mut bytes = Vec::with_capacity(1 + WORD_SIZE*(share.len()-1));
+            bytes.push(share[0] as u8);
+            for y in &share[1..] {
+                bytes.extend_from_slice(&y.to_le_bytes());
+            }
+            bytes
+        })
+        .collect();
+
+    wipe(&mut padded);
+    wipe(&mut words);
+    shares
+}
+
+/// Attempt to reconstruct a secret from at least `k` shares.
+///
+/// All shares must be the same length. If insufficient or invalid shares are
+/// provided, the result will be garbage.
+///
+pub fn reconstruct<S: AsRef<[u8]>>(shares: &[S]) -> Vec<u8> {
+    assert!(
+        shares.iter().all(|s| {
+            !s.as_ref().is_empty() && (s.as_ref().len()-1) % WORD_SIZE == 0
+        }),
+        "invalid share length?"
+    );
+
+    let words = shares.iter()
+        .map(|s| {
+            let s = s.as_ref();
+            let mut share = Vec::with_capacity(1 + (s.len()-1)/WORD_SIZE);
+            share.push(u64::from(s[0]));
+            share.extend(
+                s[1..].chunks(WORD_SIZE)
+                    .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            );
+            share
+        })
+        .collect::<Vec<_>>();
+    let mut padded_words = shamir64::reconstruct(&words);
+
+    let mut secret = padded_words.iter()
+        .flat_map(|y| y.to_le_bytes())
+        .collect::<Vec<_>>();
+    wipe(&mut padded_words);
+
+    // remove padding, if we have too few shares we may not find any
+    // padding, but the result is garbage anyways
+    while secret.last() == Some(&0) {
+        secret.pop();
+    }
+    secret.pop();
+    secret
+}
+
+/// Wipe a buffer, if the zeroize feature is enabled
+#[inline]
+fn wipe<T: Copy + Default>(buf: &mut [T]) {
+    #[cfg(feature="zeroize")]
+    {
+        crate::internal::zeroize::zeroize(buf);
+    }
+    #[cfg(not(feature="zeroize"))]
+    {
+        let _ = buf;
+    }
+}
@@ -0,0 +1,738 @@
+//! Shuffle-based split-table multiplication if available
+//!
+//! Multiplying a slice of GF(2^8) elements by a constant is linear, so it
+//! can be split into the low and high nibbles of each element:
+//!
+//! ``` text
+//! c*x = c*(x & 0xf) + c*(x & 0xf0)
+//! ```
+//!
+//! Each half only has 16 possible values, so each fits in a 16-byte table,
+//! and byte-shuffle instructions, pshufb on x86_64 and tbl on aarch64, can
+//! look up 16 elements at a time. This is the technique used by ISA-L and
+//! klauspost/reedsolomon for constant-by-slice multiplication. With AVX2,
+//! x86_64 can look up 32 elements at a time.
+//!
+//! If GFNI is available, x86_64 can do even better. Multiplying by a
+//! constant is linear over GF(2), so it can be described by an 8x8
+//! bit-matrix, which the gf2p8affineqb instruction can apply to every byte
+//! in a register. This works for any polynomial, not just GFNI's own.
+//!
+//! These functions are intended to only be used by gf256's proc_macros,
+//! they may change behavior, so they shouldn't be used directly.
+//!
+
+use cfg_if::cfg_if;
+
+
+/// A flag indicating if hardware byte-shuffle instructions are available
+/// at compile-time.
+///
+/// If this is true, the slice kernels of GF(2^8) Reed-Solomon and RAID-parity
+/// implementations use split tables with byte-shuffle instructions, or GFNI
+/// bit-matrices, to multiply long slices by constants. If this is false, but
+/// the feature `std` is enabled, these instructions may still be detected at
+/// runtime, see [`has_shuffle`].
+///
+/// Note these are still tables, so this is disabled by the `no-tables`
+/// feature.
+///
+pub const HAS_SHUFFLE: bool = {
+    cfg_if! {
+        if #[cfg(any(
+            all(
+                not(feature="no-tables"),
+                target_arch="x86_64",
+                target_feature="ssse3"
+            ),
+            all(
+                not(feature="no-tables"),
+                target_arch="x86_64",
+                target_feature="gfni"
+            ),
+            all(
+                not(feature="no-tables"),
+                target_arch="aarch64",
+                target_feature="neon"
+            )
+        ))] {
+            true
+        } else {
+            false
+        }
+    }
+};
+
+/// Minimum slice length where building split tables is worth it
+///
+/// Building the tables costs 8 multiplications, so for very short slices
+/// it's cheaper to just multiply each element.
+///
+pub const SHUFFLE_THRESHOLD: usize = 16;
+
+/// Kernels we can dispatch to
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kernel {
+    None,
+    GfniAvx2,
+    Gfni,
+    Avx2,
+    Ssse3,
+    Neon,
+}
+
+/// Find the best kernel available, either at compile-time, or if the
+/// feature `std` is enabled, at runtime
+#[inline]
+fn kernel() -> Kernel {
+    cfg_if! {
+        if #[cfg(feature="no-tables")] {
+            Kernel::None
+        } else if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="gfni",
+            target_feature="avx2"
+        ))] {
+            Kernel::GfniAvx2
+        } else if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="gfni"
+        ))] {
+            Kernel::Gfni
+        } else if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="avx2"
+        ))] {
+            Kernel::Avx2
+        } else if #[cfg(all(
+            target_arch="x86_64",
+            target_feature="ssse3"
+        ))] {
+            Kernel::Ssse3
+        } else if #[cfg(all(
+            target_arch="aarch64",
+            target_feature="neon"
+        ))] {
+            Kernel::Neon
+        } else if #[cfg(all(
+            feature="std",
+            target_arch="x86_64"
+        ))] {
+            // std caches these, so this is only a couple of loads
+            use super::std::arch::is_x86_feature_detected;
+            if is_x86_feature_detected!("gfni") && is_x86_feature_detected!("avx2") {
+                Kernel::GfniAvx2
+            } else if is_x86_feature_detected!("gfni") {
+                Kernel::Gfni
+            } else if is_x86_feature_detected!("avx2") {
+                Kernel::Avx2
+            } else if is_x86_feature_detected!("ssse3") {
+                Kernel::Ssse3
+            } else {
+                Kernel::None
+            }
+        } else if #[cfg(all(
+            feature="std",
+            target_arch="aarch64"
+        ))] {
+            if super::std::arch::is_aarch64_feature_detected!("neon") {
+                Kernel::Neon
+            } else {
+                Kernel::None
+            }
+        } else {
+            Kernel::None
+        }
+    }
+}
+
+/// Check if hardware byte-shuffle instructions are available, either at
+/// compile-time, see [`HAS_SHUFFLE`], or if the feature `std` is enabled,
+/// at runtime.
+///
+/// If this is true, [`mul_add`] and [`horner`] always handle multiples of
+/// 16 bytes entirely in registers.
+///
+#[inline]
+pub fn has_shuffle() -> bool {
+    kernel() != Kernel::None
+}
+
+/// Build split tables for a constant, given a function that multiplies
+/// by the constant
+///
+/// Returns (lo, hi), where `lo[x] = c*x` and `hi[x] = c*(x << 4)`.
+///
+/// Multiplying by a constant is linear, so we only need to multiply the 8
+/// single-bit elements, every other entry is an xor of these.
+///
+#[inline]
+pub fn split_tables(mul: impl Fn(u8) -> u8) -> ([u8; 16], [u8; 16]) {
+    let mut lo = [0u8; 16];
+    let mut hi = [0u8; 16];
+    for j in 0..4 {
+        let l = mul(1 << j);
+        let h = mul(1 << (j+4));
+        for i in 0..(1 << j) {
+            lo[(1 << j) + i] = lo[i] ^ l;
+            hi[(1 << j) + i] = hi[i] ^ h;
+        }
+    }
+    (lo, hi)
+}
+
+/// Build the 8x8 bit-matrix for gf2p8affineqb from split tables
+///
+/// Each row of the matrix selects the input bits that contribute to an
+/// output bit, with the first output bit in the last byte.
+///
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[inline]
+fn affine_matrix(lo: &[u8; 16], hi: &[u8; 16]) -> u64 {
+    // column j is c*x^j
+    let mut cols = [0u8; 8];
+    for j in 0..4 {
+        cols[j] = lo[1 << j];
+        cols[j+4] = hi[1 << j];
+    }
+
+    let mut a = 0u64;
+    for i in 0..8 {
+        let mut row = 0u8;
+        for (j, col) in cols.iter().enumerate() {
+            row |= ((col >> i) & 1) << j;
+        }
+        a |= u64::from(row) << (8*(7-i));
+    }
+    a
+}
+
+// Each mul_add kernel handles as many 16 or 32 byte chunks as it can,
+// returning the number of bytes handled, while each horner kernel handles
+// an entire slice, keeping its accumulator in registers
+
+/// x86_64 with GFNI can multiply each byte by an 8x8 bit-matrix with the
+/// gf2p8affineqb instruction
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="gfni,avx,avx2")]
+unsafe fn mul_add_gfni_avx2(
+    dst: &mut [u8],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) -> usize {
+    use core::arch::x86_64::*;
+    let a = _mm256_set1_epi64x(affine_matrix(lo, hi) as i64);
+    let mut i = 0;
+    while i+32 <= dst.len() {
+        let x = _mm256_loadu_si256(src.as_ptr().add(i) as *const __m256i);
+        let y = _mm256_gf2p8affine_epi64_epi8::<0>(x, a);
+        let d = _mm256_loadu_si256(dst.as_ptr().add(i) as *const __m256i);
+        _mm256_storeu_si256(
+            dst.as_mut_ptr().add(i) as *mut __m256i,
+            _mm256_xor_si256(d, y)
+        );
+        i += 32;
+    }
+
+    // finish any leftover 16 bytes in a 16-byte register
+    if i+16 <= dst.len() {
+        let a = _mm256_castsi256_si128(a);
+        let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        let y = _mm_gf2p8affine_epi64_epi8::<0>(x, a);
+        let d = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm_xor_si128(d, y)
+        );
+        i += 16;
+    }
+
+    i
+}
+
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="gfni,avx,avx2")]
+unsafe fn horner_gfni_avx2(
+    acc: &mut [u8; 32],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) {
+    use core::arch::x86_64::*;
+    let a = _mm256_set1_epi64x(affine_matrix(lo, hi) as i64);
+    let mut y = _mm256_loadu_si256(acc.as_ptr() as *const __m256i);
+    for chunk in src.chunks_exact(32) {
+        let x = _mm256_loadu_si256(chunk.as_ptr() as *const __m256i);
+        y = _mm256_xor_si256(_mm256_gf2p8affine_epi64_epi8::<0>(y, a), x);
+    }
+    _mm256_storeu_si256(acc.as_mut_ptr() as *mut __m256i, y);
+}
+
+/// x86_64 with GFNI, but without AVX2
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="gfni,sse2")]
+unsafe fn mul_add_gfni(
+    dst: &mut [u8],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) -> usize {
+    use core::arch::x86_64::*;
+    let a = _mm_set1_epi64x(affine_matrix(lo, hi) as i64);
+    let mut i = 0;
+    while i+16 <= dst.len() {
+        let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        let y = _mm_gf2p8affine_epi64_epi8::<0>(x, a);
+        let d = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm_xor_si128(d, y)
+        );
+        i += 16;
+    }
+
+    i
+}
+
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="gfni,sse2")]
+unsafe fn horner_gfni(
+    acc: &mut [u8; 32],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) {
+    use core::arch::x86_64::*;
+    let a = _mm_set1_epi64x(affine_matrix(lo, hi) as i64);
+    let mut y0 = _mm_loadu_si128(acc.as_ptr() as *const __m128i);
+    let mut y1 = _mm_loadu_si128(acc.as_ptr().add(16) as *const __m128i);
+    for chunk in src.chunks_exact(32) {
+        let x0 = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        let x1 = _mm_loadu_si128(chunk.as_ptr().add(16) as *const __m128i);
+        y0 = _mm_xor_si128(_mm_gf2p8affine_epi64_epi8::<0>(y0, a), x0);
+        y1 = _mm_xor_si128(_mm_gf2p8affine_epi64_epi8::<0>(y1, a), x1);
+    }
+    _mm_storeu_si128(acc.as_mut_ptr() as *mut __m128i, y0);
+    _mm_storeu_si128(acc.as_mut_ptr().add(16) as *mut __m128i, y1);
+}
+
+/// x86_64 with AVX2 can shuffle 32 bytes at a time, though vpshufb only
+/// shuffles within each 16-byte lane, so we need a copy of our tables in
+/// each lane
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="avx2")]
+unsafe fn mul_add_avx2(
+    dst: &mut [u8],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) -> usize {
+    use core::arch::x86_64::*;
+    let lo = _mm256_broadcastsi128_si256(_mm_loadu_si128(lo.as_ptr() as *const __m128i));
+    let hi = _mm256_broadcastsi128_si256(_mm_loadu_si128(hi.as_ptr() as *const __m128i));
+    let mask = _mm256_set1_epi8(0x0f);
+    let mut i = 0;
+    while i+32 <= dst.len() {
+        let x = _mm256_loadu_si256(src.as_ptr().add(i) as *const __m256i);
+        let y = _mm256_xor_si256(
+            _mm256_shuffle_epi8(lo, _mm256_and_si256(x, mask)),
+            _mm256_shuffle_epi8(hi, _mm256_and_si256(_mm256_srli_epi64::<4>(x), mask)),
+        );
+        let d = _mm256_loadu_si256(dst.as_ptr().add(i) as *const __m256i);
+        _mm256_storeu_si256(
+            dst.as_mut_ptr().add(i) as *mut __m256i,
+            _mm256_xor_si256(d, y)
+        );
+        i += 32;
+    }
+
+    // finish any leftover 16 bytes in a 16-byte register
+    if i+16 <= dst.len() {
+        let (lo, hi) = (_mm256_castsi256_si128(lo), _mm256_castsi256_si128(hi));
+        let mask = _mm256_castsi256_si128(mask);
+        let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        let y = _mm_xor_si128(
+            _mm_shuffle_epi8(lo, _mm_and_si128(x, mask)),
+            _mm_shuffle_epi8(hi, _mm_and_si128(_mm_srli_epi64::<4>(x), mask)),
+        );
+        let d = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm_xor_si128(d, y)
+        );
+        i += 16;
+    }
+
+    i
+}
+
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="avx2")]
+unsafe fn horner_avx2(
+    acc: &mut [u8; 32],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) {
+    use core::arch::x86_64::*;
+    let lo = _mm256_broadcastsi128_si256(_mm_loadu_si128(lo.as_ptr() as *const __m128i));
+    let hi = _mm256_broadcastsi128_si256(_mm_loadu_si128(hi.as_ptr() as *const __m128i));
+    let mask = _mm256_set1_epi8(0x0f);
+    let mut y = _mm256_loadu_si256(acc.as_ptr() as *const __m256i);
+    for chunk in src.chunks_exact(32) {
+        let x = _mm256_loadu_si256(chunk.as_ptr() as *const __m256i);
+        y = _mm256_xor_si256(
+            _mm256_xor_si256(
+                _mm256_shuffle_epi8(lo, _mm256_and_si256(y, mask)),
+                _mm256_shuffle_epi8(hi, _mm256_and_si256(_mm256_srli_epi64::<4>(y), mask)),
+            ),
+            x
+        );
+    }
+    _mm256_storeu_si256(acc.as_mut_ptr() as *mut __m256i, y);
+}
+
+/// x86_64 provides byte-shuffles via the pshufb instruction
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="ssse3")]
+unsafe fn mul_add_ssse3(
+    dst: &mut [u8],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) -> usize {
+    use core::arch::x86_64::*;
+    let lo = _mm_loadu_si128(lo.as_ptr() as *const __m128i);
+    let hi = _mm_loadu_si128(hi.as_ptr() as *const __m128i);
+    let mask = _mm_set1_epi8(0x0f);
+    let mut i = 0;
+    while i+16 <= dst.len() {
+        let x = _mm_loadu_si128(src.as_ptr().add(i) as *const __m128i);
+        let y = _mm_xor_si128(
+            _mm_shuffle_epi8(lo, _mm_and_si128(x, mask)),
+            _mm_shuffle_epi8(hi, _mm_and_si128(_mm_srli_epi64::<4>(x), mask)),
+        );
+        let d = _mm_loadu_si128(dst.as_ptr().add(i) as *const __m128i);
+        _mm_storeu_si128(
+            dst.as_mut_ptr().add(i) as *mut __m128i,
+            _mm_xor_si128(d, y)
+        );
+        i += 16;
+    }
+
+    i
+}
+
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="x86_64"
+))]
+#[target_feature(enable="ssse3")]
+unsafe fn horner_ssse3(
+    acc: &mut [u8; 32],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) {
+    use core::arch::x86_64::*;
+    let lo = _mm_loadu_si128(lo.as_ptr() as *const __m128i);
+    let hi = _mm_loadu_si128(hi.as_ptr() as *const __m128i);
+    let mask = _mm_set1_epi8(0x0f);
+    let mut y0 = _mm_loadu_si128(acc.as_ptr() as *const __m128i);
+    let mut y1 = _mm_loadu_si128(acc.as_ptr().add(16) as *const __m128i);
+    for chunk in src.chunks_exact(32) {
+        let x0 = _mm_loadu_si128(chunk.as_ptr() as *const __m128i);
+        let x1 = _mm_loadu_si128(chunk.as_ptr().add(16) as *const __m128i);
+        y0 = _mm_xor_si128(
+            _mm_xor_si128(
+                _mm_shuffle_epi8(lo, _mm_and_si128(y0, mask)),
+                _mm_shuffle_epi8(hi, _mm_and_si128(_mm_srli_epi64::<4>(y0), mask)),
+            ),
+            x0
+        );
+        y1 = _mm_xor_si128(
+            _mm_xor_si128(
+                _mm_shuffle_epi8(lo, _mm_and_si128(y1, mask)),
+                _mm_shuffle_epi8(hi, _mm_and_si128(_mm_srli_epi64::<4>(y1), mask)),
+            ),
+            x1
+        );
+    }
+    _mm_storeu_si128(acc.as_mut_ptr() as *mut __m128i, y0);
+    _mm_storeu_si128(acc.as_mut_ptr().add(16) as *mut __m128i, y1);
+}
+
+/// aarch64 provides byte-shuffles via the tbl instruction
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="aarch64"
+))]
+#[target_feature(enable="neon")]
+unsafe fn mul_add_neon(
+    dst: &mut [u8],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) -> usize {
+    use core::arch::aarch64::*;
+    let lo = vld1q_u8(lo.as_ptr());
+    let hi = vld1q_u8(hi.as_ptr());
+    let mask = vdupq_n_u8(0x0f);
+    let mut i = 0;
+    while i+16 <= dst.len() {
+        let x = vld1q_u8(src.as_ptr().add(i));
+        let y = veorq_u8(
+            vqtbl1q_u8(lo, vandq_u8(x, mask)),
+            vqtbl1q_u8(hi, vshrq_n_u8::<4>(x)),
+        );
+        let d = vld1q_u8(dst.as_ptr().add(i));
+        vst1q_u8(dst.as_mut_ptr().add(i), veorq_u8(d, y));
+        i += 16;
+    }
+
+    i
+}
+
+#[cfg(all(
+    not(feature="no-tables"),
+    target_arch="aarch64"
+))]
+#[target_feature(enable="neon")]
+unsafe fn horner_neon(
+    acc: &mut [u8; 32],
+    src: &[u8],
+    lo: &[u8; 16],
+    hi: &[u8; 16]
+) {
+    use core::arch::aarch64::*;
+    let lo = vld1q_u8(lo.as_ptr());
+    let hi = vld1q_u8(hi.as_ptr());
+    let mask = vdupq_n_u8(0x0f);
+    let mut y0 = vld1q_u8(acc.as_ptr());
+    let mut y1 = vld1q_u8(acc.as_ptr().add(16));
+    for chunk in src.chunks_exact(32) {
+        let x0 = vld1q_u8(chunk.as_ptr());
+        let x1 = vld1q_u8(chunk.as_ptr().add(16));
+        y0 = veorq_u8(
+            veorq_u8(
+                vqtbl1q_u8(lo, vandq_u8(y0, mask)),
+                vqtbl1q_u8(hi, vshrq_n_u8::<4>(y0)),
+            ),
+            x0
+        );
+        y1 = veorq_u8(
+            veorq_u8(
+                vqtbl1q_u8(lo, vandq_u8(y1, mask)),
+                vqtbl1q_u8(hi, vshrq_n_u8::<4>(y1)),
+            ),
+            x1
+        );
+    }
+    vst1q_u8(acc.as_mut_ptr(), y0);
+    vst1q_u8(acc.as_mut_ptr().add(16), y1);
+}
+
+/// Multiply a slice by a constant, described by its split tables, and add
+/// it to another slice, `dst += c*src`
+///
+/// This uses byte-shuffle or GFNI instructions if available, falling back
+/// to a bytewise lookup otherwise. If available, multiples of 16 bytes are
+/// always handled entirely in registers.
+///
+/// Panics if `dst` and `src` differ in length.
+///
+#[inline]
+pub fn mul_add(dst: &mut [u8], src: &[u8], lo: &[u8; 16], hi: &[u8; 16]) {
+    assert_eq!(dst.len(), src.len());
+    let i = match kernel() {
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::GfniAvx2 => unsafe { mul_add_gfni_avx2(dst, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Gfni     => unsafe { mul_add_gfni(dst, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Avx2     => unsafe { mul_add_avx2(dst, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Ssse3    => unsafe { mul_add_ssse3(dst, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="aarch64"))]
+        Kernel::Neon     => unsafe { mul_add_neon(dst, src, lo, hi) },
+        _ => 0,
+    };
+
+    // bytewise lookups for anything left over
+    for (d, s) in dst[i..].iter_mut().zip(&src[i..]) {
+        *d ^= lo[usize::from(s & 0xf)] ^ hi[usize::from(s >> 4)];
+    }
+}
+
+/// Evaluate 32 interleaved polynomials at a constant with Horner's method,
+/// `acc = c*acc + src[i..i+32]` for each 32-byte chunk of `src`
+///
+/// This keeps the accumulator in registers across the whole slice, using
+/// byte-shuffle or GFNI instructions if available, and falling back to a
+/// bytewise lookup otherwise. Note `src` must be a multiple of 32 bytes.
+///
+#[inline]
+pub fn horner(acc: &mut [u8; 32], src: &[u8], lo: &[u8; 16], hi: &[u8; 16]) {
+    debug_assert!(src.len().is_multiple_of(32));
+    match kernel() {
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::GfniAvx2 => unsafe { horner_gfni_avx2(acc, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Gfni     => unsafe { horner_gfni(acc, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Avx2     => unsafe { horner_avx2(acc, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="x86_64"))]
+        Kernel::Ssse3    => unsafe { horner_ssse3(acc, src, lo, hi) },
+        #[cfg(all(not(feature="no-tables"), target_arch="aarch64"))]
+        Kernel::Neon     => unsafe { horner_neon(acc, src, lo, hi) },
+        _ => {
+            // bytewise lookups
+            for chunk in src.chunks_exact(32) {
+                for (a, s) in acc.iter_mut().zip(chunk) {
+                    *a = lo[usize::from(*a & 0xf)] ^ hi[usize::from(*a >> 4)] ^ s;
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gf::*;
+
+    #[test]
+    fn shuffle_lengths() {
+        // exercise each register width and leftovers
+        let c = gf256(0x53);
+        let (lo, hi) = split_tables(|x| (c * gf256(x)).get());
+        for len in [0, 1, 15, 16, 17, 31, 32, 33, 48, 63, 64, 65, 80] {
+            let src: [u8; 80] = core::array::from_fn(|i| (i*13+7) as u8);
+            let mut dst: [u8; 80] = core::array::from_fn(|i| (i*5) as u8);
+            mul_add(&mut dst[..len], &src[..len], &lo, &hi);
+            for i in 0..80 {
+                if i < len {
+                    assert_eq!(gf256(dst[i]), gf256((i*5) as u8) + c*gf256(src[i]));
+                } else {
+                    assert_eq!(dst[i], (i*5) as u8);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle_horner() {
+        let c = gf256(0x53);
+        let (lo, hi) = split_tables(|x| (c * gf256(x)).get());
+        for len in [0, 32, 64, 96, 256] {
+            let src: [u8; 256] = core::array::from_fn(|i| (i*13+7) as u8);
+            let mut acc: [u8; 32] = core::array::from_fn(|i| (i*5) as u8);
+            horner(&mut acc, &src[..len], &lo, &hi);
+            for l in 0..32 {
+                let mut y = gf256((l*5) as u8);
+                for chunk in src[..len].chunks(32) {
+                    y = c*y + gf256(chunk[l]);
+                }
+                assert_eq!(gf256(acc[l]), y);
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle_split_tables() {
+        for c in 0..=255 {
+            let c = gf256(c);
+            let (lo, hi) = split_tables(|x| (c * gf256(x)).get());
+            for x in 0..16 {
+                assert_eq!(gf256(lo[x]), c*gf256(x as u8));
+                assert_eq!(gf256(hi[x]), c*gf256((x as u8) << 4));
+            }
+        }
+    }
+
+    #[cfg(all(feature="std", not(feature="no-tables"), target_arch="x86_64"))]
+    #[test]
+    fn shuffle_kernels() {
+        // check every kernel we can run, not just the best one
+        use std::arch::is_x86_feature_detected;
+        type MulAdd = unsafe fn(&mut [u8], &[u8], &[u8; 16], &[u8; 16]) -> usize;
+        type Horner = unsafe fn(&mut [u8; 32], &[u8], &[u8; 16], &[u8; 16]);
+        let kernels: [(bool, MulAdd, Horner); 4] = [
+            (
+                is_x86_feature_detected!("gfni") && is_x86_feature_detected!("avx2"),
+                mul_add_gfni_avx2,
+                horner_gfni_avx2,
+            ),
+            (is_x86_feature_detected!("gfni"), mul_add_gfni, horner_gfni),
+            (is_x86_feature_detected!("avx2"), mul_add_avx2, horner_avx2),
+            (is_x86_feature_detected!("ssse3"), mul_add_ssse3, horner_ssse3),
+        ];
+
+        let src: [u8; 96] = core::array::from_fn(|i| (i*37) as u8);
+        for (detected, mul_add, horner) in kernels {
+            if !detected {
+                continue;
+            }
+
+            for c in [0x00, 0x01, 0x02, 0x53, 0xff] {
+                let c = gf256(c);
+                let (lo, hi) = split_tables(|x| (c * gf256(x)).get());
+                let mut dst: [u8; 96] = core::array::from_fn(|i| i as u8);
+                let n = unsafe { mul_add(&mut dst[..80], &src[..80], &lo, &hi) };
+                assert_eq!(n, 80);
+                for i in 0..80 {
+                    assert_eq!(gf256(dst[i]), gf256(i as u8) + c*gf256(src[i]));
+                }
+
+                let mut acc: [u8; 32] = core::array::from_fn(|i| i as u8);
+                unsafe { horner(&mut acc, &src, &lo, &hi) };
+                for l in 0..32 {
+                    let y = c*(c*(c*gf256(l as u8) + gf256(src[l])) + gf256(src[32+l])) + gf256(src[64+l]);
+                    assert_eq!(gf256(acc[l]), y);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle() {
+        // long enough to exercise both shuffles and leftovers
+        let src: [u8; 100] = core::array::from_fn(|i| (i*37) as u8);
+        for c in 0..=255 {
+            let c = gf256(c);
+            let (lo, hi) = split_tables(|x| (c * gf256(x)).get());
+            let mut dst: [u8; 100] = core::array::from_fn(|i| i as u8);
+            mul_add(&mut dst, &src, &lo, &hi);
+            for i in 0..100 {
+                assert_eq!(gf256(dst[i]), gf256(i as u8) + c*gf256(src[i]));
+            }
+        }
+    }
+}
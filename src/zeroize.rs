@@ -0,0 +1,42 @@
+//! Best-effort wiping of secrets
+//!
+//! A plain write of zeros to a buffer that is about to be freed is a dead
+//! store, which the compiler is free to remove. Volatile writes can't be
+//! removed, and a compiler fence keeps later code from being reordered
+//! before them.
+//!
+//! These functions are used by gf256's proc_macros, and are re-exported as
+//! `shamir::zeroize` if the `zeroize` feature is
+//! enabled.
+//!
+
+use core::ptr;
+use core::sync::atomic;
+
+
+/// Overwrite a buffer with its default value, usually zero, in a way the
+/// compiler won't optimize out.
+#[inline(never)]
+pub fn zeroize<T: Copy + Default>(buf: &mut [T]) {
+    for x in buf.iter_mut() {
+        unsafe { ptr::write_volatile(x, T::default()) };
+    }
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::gf::*;
+
+    #[test]
+    fn zeroize() {
+        let mut buf = [0x12u8; 100];
+        super::zeroize(&mut buf);
+        assert_eq!(buf, [0u8; 100]);
+
+        let mut buf = [gf2p64(0x123456789abcdef); 10];
+        super::zeroize(&mut buf);
+        assert_eq!(buf, [gf2p64(0); 10]);
+    }
+}
@@ -0,0 +1,430 @@
+// Template for BCH error-correction functions
+//
+// See the module-level documentation in src/bch.rs for a more detailed
+// explanation of where these implementations come from
+
+//! BCH error-correction functions.
+//!
+//! ``` rust
+//! # use gf256::bch::bch8191t8;
+//! #
+//! // encode
+//! let mut buf = b"Hello World!".to_vec();
+//! buf.resize(buf.len()+13, 0u8);
+//! bch8191t8::encode(&mut buf);
+//!
+//! // corrupt
+//! buf[0] ^= 0x81;
+//! buf[5] ^= 0x10;
+//!
+//! // correct
+//! assert_eq!(bch8191t8::correct_errors(&mut buf)?, 3);
+//! assert_eq!(&buf[0..12], b"Hello World!");
+//! # Ok::<(), bch8191t8::Error>(())
+//! ```
+//!
+//! See the [module-level documentation](../../bch) for more info.
+
+
+use __crate::traits::TryFrom;
+use core::fmt;
+
+
+// Constants for BCH error correction
+//
+// A binary BCH code with symbols in GF(2^m) can correct T bit errors in a
+// codeword of up to 2^m-1 bits. Data and error-correction are byte-aligned
+// here, so the error-correction is rounded up to ECC_SIZE bytes, with any
+// extra bits left unused.
+//
+
+/// Number of bits in each symbol of the underlying field, `GF(2^M)`.
+pub const M: usize = __m;
+
+/// Maximum number of bit errors that can be corrected.
+pub const T: usize = __t;
+
+/// Number of bits of error-correction, this is the degree of the generator
+/// polynomial, at most `M*T`.
+pub const ECC_BITS: usize = __ecc_bits;
+
+/// Size of the appended error-correction in bytes, [`ECC_BITS`] rounded up.
+pub const ECC_SIZE: usize = ECC_BITS.div_ceil(8);
+
+/// Maximum size of the original data in bytes.
+pub const DATA_SIZE: usize = ((1 << M) - 1 - ECC_BITS) / 8;
+
+/// Size of the codeword, [`DATA_SIZE`] + [`ECC_SIZE`], in bytes.
+pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
+
+// Number of unused bits at the end of the error-correction
+const PAD_BITS: usize = 8*ECC_SIZE - ECC_BITS;
+
+// The generator polynomial of a binary BCH code is the smallest binary
+// polynomial with roots at g^1, g^2, ..., g^2T. Since the Frobenius map
+// (x -> x^2) permutes roots of binary polynomials, this is the product of
+// the minimal polynomials of g^1, g^3, ..., g^(2T-1):
+//
+// G(x) = lcm(M1(x), M3(x), ..., M(2T-1)(x))
+//
+// This is found when the macro is expanded.
+//
+
+/// The generator polynomial for this error-correction code, as big-endian
+/// bytes.
+pub const GENERATOR_POLY: [u8; (ECC_BITS+8)/8] = __generator_poly;
+
+/// The generator polynomial without its leading term, left-aligned in
+/// ECC_SIZE bytes
+const GENERATOR_ALIGNED: [u8; ECC_SIZE] = {
+    let mut g = [0u8; ECC_SIZE];
+    let mut i = 0;
+    while i < ECC_BITS {
+        let bit = (GENERATOR_POLY[GENERATOR_POLY.len()-1-i/8] >> (i%8)) & 1;
+        let j = i + PAD_BITS;
+        g[ECC_SIZE-1-j/8] |= bit << (j%8);
+        i += 1;
+    }
+    g
+};
+
+/// A remainder table for dividing by the generator polynomial a byte at
+/// a time, much like CRC
+const REM_TABLE: [[u8; ECC_SIZE]; 256] = {
+    let mut table = [[0u8; ECC_SIZE]; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut x = [0u8; ECC_SIZE];
+        x[0] = i as u8;
+        let mut j = 0;
+        while j < 8 {
+            let msb = x[0] >> 7;
+            let mut k = 0;
+            while k < ECC_SIZE {
+                x[k] = (x[k] << 1) | if k+1 < ECC_SIZE { x[k+1] >> 7 } else { 0 };
+                k += 1;
+            }
+            if msb != 0 {
+                let mut k = 0;
+                while k < ECC_SIZE {
+                    x[k] ^= GENERATOR_ALIGNED[k];
+                    k += 1;
+                }
+            }
+            j += 1;
+        }
+        table[i] = x;
+        i += 1;
+    }
+    table
+};
+
+
+/// Error codes for BCH
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// BCH can fail to decode if there are more than T bit errors.
+    ///
+    /// Note that with more than T bit errors, BCH may also "correct" the
+    /// codeword to the wrong codeword, this is only detected sometimes.
+    ///
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+        }
+    }
+}
+
+#[cfg(__if(__std))]
+impl __crate::internal::std::error::Error for Error {}
+
+
+/// Find the remainder of data*x^ECC_BITS divided by our generator polynomial,
+/// left-aligned in ECC_SIZE bytes
+fn find_remainder(data: &[u8]) -> [u8; ECC_SIZE] {
+    let mut r = [0u8; ECC_SIZE];
+    for b in data {
+        let i = usize::from(r[0] ^ b);
+        r.copy_within(1.., 0);
+        r[ECC_SIZE-1] = 0;
+        for (x, y) in r.iter_mut().zip(&REM_TABLE[i]) {
+            *x ^= y;
+        }
+    }
+    r
+}
+
+// Encode using BCH error correction
+//
+// Much like CRC, and Reed-Solomon, we want to make the message a multiple of
+// G(x), our generator polynomial. We can do this by appending the remainder
+// of our message after division by G(x).
+//
+// ``` text
+// c(x) = m(x) - (m(x) % G(x))
+// ```
+//
+
+/// Encode a message using BCH error-correction.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to the end
+/// of the provided slice, based on the data provided in the first
+/// `message.len()-ECC_SIZE` bytes. The entire codeword is limited to at most
+/// [`BLOCK_SIZE`] bytes, but can be smaller.
+///
+/// ``` rust
+/// # use gf256::bch::bch8191t8;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+13, 0u8);
+/// bch8191t8::encode(&mut codeword);
+/// assert!(bch8191t8::is_correct(&codeword));
+/// ```
+///
+pub fn encode(message: &mut [u8]) {
+    assert!(message.len() <= BLOCK_SIZE);
+    assert!(message.len() >= ECC_SIZE);
+    let data_len = message.len() - ECC_SIZE;
+
+    let (data, parity) = message.split_at_mut(data_len);
+    encode_parity(data, parity);
+}
+
+/// Encode a message using BCH error-correction, writing the error-correction
+/// information to a separate buffer.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to
+/// `parity`, based on the data provided in `data`. The data is limited to at
+/// most [`DATA_SIZE`] bytes, but can be smaller. Any unused bits at the end
+/// of `parity` are set to zero.
+///
+pub fn encode_parity(data: &[u8], parity: &mut [u8]) {
+    assert!(data.len() <= DATA_SIZE);
+    assert!(parity.len() == ECC_SIZE);
+
+    parity.copy_from_slice(&find_remainder(data));
+}
+
+/// Find the remainder of the codeword divided by our generator polynomial,
+/// left-aligned in ECC_SIZE bytes, this is zero if there are no errors
+fn find_codeword_remainder(codeword: &[u8]) -> [u8; ECC_SIZE] {
+    let (data, parity) = codeword.split_at(codeword.len()-ECC_SIZE);
+    let mut r = find_remainder(data);
+    for (x, y) in r.iter_mut().zip(parity) {
+        *x ^= y;
+    }
+
+    // ignore unused bits
+    r[ECC_SIZE-1] &= !((1u16 << PAD_BITS) - 1) as u8;
+    r
+}
+
+/// Determine if codeword is correct and has no errors/erasures.
+///
+/// This is quite a bit faster than actually finding the errors/erasures.
+///
+/// ``` rust
+/// # use gf256::bch::bch8191t8;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+13, 0u8);
+/// bch8191t8::encode(&mut codeword);
+/// assert!(bch8191t8::is_correct(&codeword));
+///
+/// codeword[3] ^= 0x04;
+/// assert!(!bch8191t8::is_correct(&codeword));
+/// ```
+///
+pub fn is_correct(codeword: &[u8]) -> bool {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+
+    find_codeword_remainder(codeword).iter().all(|x| *x == 0)
+}
+
+/// Find syndromes, which should be zero if there are no errors
+///
+/// ``` text
+/// Si = c'(g^i) = r(g^i)
+/// ```
+///
+/// Where r(x) is the remainder of c'(x) divided by G(x). Since g^i are roots
+/// of G(x), this is equivalent, but r(x) is much shorter.
+///
+#[allow(non_snake_case)]
+fn find_syndromes(r: &[u8; ECC_SIZE]) -> [__gf; 2*T] {
+    let mut S = [__gf::new(0); 2*T];
+    for i in (1..=2*T).step_by(2) {
+        let root = __gf::GENERATOR.pow(__u::try_from(i).unwrap());
+
+        let mut s = __gf::new(0);
+        for j in 0..ECC_BITS {
+            s = s*root + __gf::new(__u::from((r[j/8] >> (7-j%8)) & 1));
+        }
+        S[i-1] = s;
+    }
+
+    // for binary codes, S2i = Si^2, so we only need to evaluate the
+    // odd syndromes
+    for i in (2..=2*T).step_by(2) {
+        S[i-1] = S[i/2-1]*S[i/2-1];
+    }
+
+    S
+}
+
+/// Iteratively find the error locator polynomial using the
+/// Berlekamp-Massey algorithm
+///
+/// Note, unlike the Reed-Solomon implementation, the polynomials here are
+/// ordered smallest-coefficient first.
+///
+#[allow(non_snake_case)]
+fn find_error_locator(S: &[__gf; 2*T]) -> ([__gf; 2*T+1], usize) {
+    // the current estimate for the error locator polynomial, and the
+    // estimate before the last length change
+    let mut Λ = [__gf::new(0); 2*T+1];
+    Λ[0] = __gf::new(1);
+    let mut prev_Λ = Λ;
+    let mut prev_delta = __gf::new(1);
+
+    // the current estimate for the number of errors
+    let mut v = 0;
+    // steps since the last length change
+    let mut m = 1;
+
+    for i in 0..2*T {
+        let mut delta = S[i];
+        for j in 1..v+1 {
+            delta += Λ[j] * S[i-j];
+        }
+
+        if delta == __gf::new(0) {
+            m += 1;
+            continue;
+        }
+
+        // Λ(x) = Λ(x) - delta/prev_delta * x^m * prev_Λ(x)
+        let c = delta / prev_delta;
+        let t = Λ;
+        for j in 0..2*T+1-m {
+            Λ[j+m] -= c * prev_Λ[j];
+        }
+
+        if 2*v <= i {
+            v = i+1-v;
+            prev_Λ = t;
+            prev_delta = delta;
+            m = 1;
+        } else {
+            m += 1;
+        }
+    }
+
+    (Λ, v)
+}
+
+/// Find roots of the error locator polynomial with a Chien search, returning
+/// the number of errors found
+///
+/// This evaluates Λ(x) at g^-e for every bit e in the codeword, but instead
+/// of evaluating each term from scratch, we multiply each term by g^-i as we
+/// go.
+///
+#[allow(non_snake_case)]
+fn find_error_locations(
+    bits: usize,
+    Λ: &[__gf],
+    error_locations: &mut [usize; T]
+) -> Result<usize, Error> {
+    let mut terms = [__gf::new(0); 2*T+1];
+    let mut steps = [__gf::new(0); 2*T+1];
+    for i in 0..Λ.len() {
+        terms[i] = Λ[i];
+        steps[i] = __gf::GENERATOR.pow(__u::try_from(i).unwrap()).recip();
+    }
+
+    let mut count = 0;
+    for e in 0..bits {
+        let mut sum = __gf::new(0);
+        for i in 0..Λ.len() {
+            sum += terms[i];
+            terms[i] *= steps[i];
+        }
+
+        if sum == __gf::new(0) {
+            // found an error location!
+            if count >= Λ.len()-1 {
+                return Err(Error::TooManyErrors);
+            }
+            error_locations[count] = bits-1-e;
+            count += 1;
+        }
+    }
+
+    // if we didn't find as many roots as Λ's degree, this isn't a valid
+    // error locator
+    if count != Λ.len()-1 {
+        return Err(Error::TooManyErrors);
+    }
+
+    Ok(count)
+}
+
+/// Correct up to [`T`] bit errors at unknown locations.
+///
+/// Returns the number of bit errors corrected, or [`Error::TooManyErrors`]
+/// if the codeword can not be corrected, in which case the codeword is left
+/// unmodified.
+///
+/// ``` rust
+/// # use gf256::bch::bch8191t8;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+13, 0u8);
+/// bch8191t8::encode(&mut codeword);
+///
+/// // flip 8 bits
+/// for i in 0..8 {
+///     codeword[i] ^= 1 << i;
+/// }
+///
+/// assert_eq!(bch8191t8::correct_errors(&mut codeword), Ok(8));
+/// assert_eq!(&codeword[..12], b"Hello World!");
+/// ```
+///
+#[allow(non_snake_case)]
+pub fn correct_errors(codeword: &mut [u8]) -> Result<usize, Error> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+
+    let r = find_codeword_remainder(codeword);
+    if r.iter().all(|x| *x == 0) {
+        return Ok(0);
+    }
+
+    // find syndromes and the error locator
+    let S = find_syndromes(&r);
+    let (Λ, v) = find_error_locator(&S);
+    if v > T {
+        return Err(Error::TooManyErrors);
+    }
+
+    // find error locations, as bit offsets into the codeword, note the
+    // codeword ends after ECC_BITS, before any unused bits
+    let data_bits = 8*(codeword.len()-ECC_SIZE);
+    let mut error_locations = [0usize; T];
+    let count = find_error_locations(
+        data_bits + ECC_BITS,
+        &Λ[..v+1],
+        &mut error_locations
+    )?;
+
+    // correct the errors
+    for b in &error_locations[..count] {
+        codeword[b/8] ^= 0x80 >> (b%8);
+    }
+
+    Ok(count)
+}
@@ -9,6 +9,30 @@ use __crate::traits::FromLossy;
 use core::mem::size_of;
 
 
+/// Multiply by x^n modulo our polynomial, a bit at a time.
+///
+/// Note this operates on values aligned to the most-significant bit of
+/// __u, which avoids needing a 2x-wide type, and allows this to work with
+/// CRCs wider than 64 bits.
+///
+const fn shl_rem(x: __u, n: usize) -> __u {
+    let polynomial = ((__p2(__polynomial).0 & __nonzeros) as __u)
+        << (8*size_of::<__u>()-__width);
+
+    let mut x = x;
+    let mut i = 0;
+    while i < n {
+        let overflow = x >> (8*size_of::<__u>()-1) != 0;
+        x = x << 1;
+        if overflow {
+            x = x ^ polynomial;
+        }
+        i += 1;
+    }
+    x
+}
+
+
 /// Calculate the CRC for a piece of data.
 ///
 /// ``` rust
@@ -30,6 +54,12 @@ use core::mem::size_of;
 /// See the [module-level documentation](../crc) for more info.
 ///
 pub fn __crc(data: &[u8], crc: __u) -> __u {
+    // use hardware instructions if we find them at runtime
+    #[cfg(__if(__hardware_detect && !__hardware))]
+    if let Some(crc) = __hardware_detect_fn((crc ^ __xor) as u32, data) {
+        return (crc as __u) ^ __xor;
+    }
+
     cfg_if! {
         if #[cfg(__if(__naive))] {
             let mut crc = __p(crc ^ __xor);
@@ -53,9 +83,16 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
                         crc = crc + __p::from_be_bytes(word);
                     }
                 }
-                crc = __p::try_from(
-                    (__p2::from(crc) << 8*size_of::<__u>()) % __p2(__polynomial << (8*size_of::<__u>()-__width))
-                ).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__width > 64))] {
+                        // no 2x-wide type, fall back to bitwise division
+                        crc = __p(shl_rem(crc.0, 8*size_of::<__u>()));
+                    } else {
+                        crc = __p::try_from(
+                            (__p2::from(crc) << 8*size_of::<__u>()) % __p2(__polynomial << (8*size_of::<__u>()-__width))
+                        ).unwrap();
+                    }
+                }
             }
 
             // handle remainder
@@ -67,9 +104,16 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
                         crc = crc + (__p::from(*b) << (8*size_of::<__u>()-8));
                     }
                 }
-                crc = __p::try_from(
-                    (__p2::from(crc) << 8) % __p2(__polynomial << (8*size_of::<__u>()-__width))
-                ).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__width > 64))] {
+                        // no 2x-wide type, fall back to bitwise division
+                        crc = __p(shl_rem(crc.0, 8));
+                    } else {
+                        crc = __p::try_from(
+                            (__p2::from(crc) << 8) % __p2(__polynomial << (8*size_of::<__u>()-__width))
+                        ).unwrap();
+                    }
+                }
             }
 
             // our division is always 8-bit aligned, so we need to do some
@@ -91,14 +135,12 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
                     cfg_if! {
                         if #[cfg(__if(__reflected))] {
                             let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 8)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            let x = shl_rem(x, 8);
                             table[i] = x.reverse_bits();
                             i += 1;
                         } else {
                             let x = (i as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 8)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            let x = shl_rem(x, 8);
                             table[i] = x;
                             i += 1;
                         }
@@ -146,14 +188,12 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
                     cfg_if! {
                         if #[cfg(__if(__reflected))] {
                             let x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
-                            let x = __p2((x as __u2) << 4)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            let x = shl_rem(x, 4);
                             table[i] = x.reverse_bits();
                             i += 1;
                         } else {
                             let x = (i as __u) << (8*size_of::<__u>()-4);
-                            let x = __p2((x as __u2) << 4)
-                                .naive_rem(__p2(__polynomial << (8*size_of::<__u>()-__width))).0 as __u;
+                            let x = shl_rem(x, 4);
                             table[i] = x;
                             i += 1;
                         }
@@ -193,7 +233,151 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
             }
 
             crc ^ __xor
-        } else if #[cfg(__if(__barret))] {
+        } else if #[cfg(__if(__slice8 || __slice16))] {
+            // number of bytes processed per iteration
+            const SLICE: usize = if __slice8 { 8 } else { 16 };
+
+            // CRC_TABLE[k][i] contains the remainder of byte i followed
+            // by k bytes of zeros, this lets us compute the remainder of
+            // each byte in a slice independently
+            const CRC_TABLE: [[__u; 256]; SLICE] = {
+                let mut table = [[0; 256]; SLICE];
+                let mut i = 0;
+                while i < 256 {
+                    cfg_if! {
+                        if #[cfg(__if(__reflected))] {
+                            let mut x = ((i as u8).reverse_bits() as __u) << (8*size_of::<__u>()-8);
+                        } else {
+                            let mut x = (i as __u) << (8*size_of::<__u>()-8);
+                        }
+                    }
+                    let mut k = 0;
+                    while k < SLICE {
+                        x = shl_rem(x, 8);
+                        cfg_if! {
+                            if #[cfg(__if(__reflected))] {
+                                table[k][i] = x.reverse_bits();
+                            } else {
+                                table[k][i] = x;
+                            }
+                        }
+                        k += 1;
+                    }
+                    i += 1;
+                }
+                table
+            };
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    let mut crc = crc ^ __xor;
+                } else {
+                    let mut crc = (crc ^ __xor) << (8*size_of::<__u>()-__width);
+                }
+            }
+
+            // iterate over slices
+            let mut slices = data.chunks_exact(SLICE);
+            for slice in &mut slices {
+                // if our crc is wider than a slice, any remaining bytes are
+                // carried over
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        let mut ncrc = crc.checked_shr(8*SLICE as u32).unwrap_or(0);
+                    } else {
+                        let mut ncrc = crc.checked_shl(8*SLICE as u32).unwrap_or(0);
+                    }
+                }
+                for i in 0..SLICE {
+                    // add our current crc to the first bytes
+                    let mut b = slice[i];
+                    if i < size_of::<__u>() {
+                        cfg_if! {
+                            if #[cfg(__if(__reflected))] {
+                                b ^= (crc >> (8*i)) as u8;
+                            } else {
+                                b ^= (crc >> (8*(size_of::<__u>()-1-i))) as u8;
+                            }
+                        }
+                    }
+                    ncrc ^= CRC_TABLE[SLICE-1-i][usize::from(b)];
+                }
+                crc = ncrc;
+            }
+
+            // handle remainder
+            for b in slices.remainder() {
+                cfg_if! {
+                    if #[cfg(__if(__width <= 8))] {
+                        crc = CRC_TABLE[0][usize::from((crc as u8) ^ b)];
+                    } else if #[cfg(__if(__reflected))] {
+                        crc = (crc >> 8) ^ CRC_TABLE[0][usize::from((crc as u8) ^ b)];
+                    } else {
+                        crc = (crc << 8) ^ CRC_TABLE[0][usize::from(((crc >> (8*size_of::<__u>()-8)) as u8) ^ b)];
+                    }
+                }
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc & __nonzeros;
+                } else {
+                    crc = crc >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            crc ^ __xor
+        } else if #[cfg(__if(__folding))] {
+            use __crate::p::p64;
+            use __crate::p::p128;
+
+            // x^n mod our polynomial, computed a bit at a time
+            const fn xpow_rem(n: usize) -> p64 {
+                let mut x: u128 = 1;
+                let mut i = 0;
+                while i < n {
+                    x <<= 1;
+                    if x & (1 << __width) != 0 {
+                        x ^= __polynomial;
+                    }
+                    i += 1;
+                }
+                p64(x as u64)
+            }
+
+            // constants for folding 128-bit blocks forward by 512 bits (our
+            // 64-byte chunks), and by 128 bits (when merging our 4 blocks)
+            const FOLD_512: (p64, p64) = (xpow_rem(512+64), xpow_rem(512));
+            const FOLD_128: (p64, p64) = (xpow_rem(128+64), xpow_rem(128));
+            // constants for the final reduction into our CRC
+            const FOLD_FINAL: (p64, p64) = (xpow_rem(64+__width), xpow_rem(__width));
+
+            // multiply a 128-bit block by x^n, using precomputed constants
+            // for x^(n+64) mod p and x^n mod p, this can leave up to 128 bits
+            // which is fine since we only need to be congruent mod p
+            #[inline]
+            fn fold(a: (p64, p64), k: (p64, p64)) -> (p64, p64) {
+                let (lo0, hi0) = a.0.widening_mul(k.0);
+                let (lo1, hi1) = a.1.widening_mul(k.1);
+                (hi0 + hi1, lo0 + lo1)
+            }
+
+            // load a 128-bit block, highest degree first
+            #[inline]
+            fn load(block: &[u8]) -> (p64, p64) {
+                let hi = <[u8; 8]>::try_from(&block[0..8]).unwrap();
+                let lo = <[u8; 8]>::try_from(&block[8..16]).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        (p64::from_le_bytes(hi).reverse_bits(), p64::from_le_bytes(lo).reverse_bits())
+                    } else {
+                        (p64::from_be_bytes(hi), p64::from_be_bytes(lo))
+                    }
+                }
+            }
+
             const BARRET_CONSTANT: __p = {
                 __p(
                     __p2((__polynomial & __nonzeros) << ((8*size_of::<__u>()-__width) + 8*size_of::<__u>()))
@@ -210,6 +394,145 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
                 }
             }
 
+            // fold 64-byte chunks, this is where most of the work happens
+            let mut data = data;
+            if data.len() >= 64 {
+                let mut chunks = data.chunks_exact(64);
+                let chunk = chunks.next().unwrap();
+                let mut blocks = [
+                    load(&chunk[0..16]),
+                    load(&chunk[16..32]),
+                    load(&chunk[32..48]),
+                    load(&chunk[48..64]),
+                ];
+
+                // add our current crc to the first block
+                blocks[0].0 = blocks[0].0 + (p64::from(crc) << (64-__width));
+
+                for chunk in &mut chunks {
+                    for i in 0..4 {
+                        let block = load(&chunk[16*i..16*i+16]);
+                        let folded = fold(blocks[i], FOLD_512);
+                        blocks[i] = (folded.0 + block.0, folded.1 + block.1);
+                    }
+                }
+
+                // merge our blocks
+                let mut block = blocks[0];
+                for i in 1..4 {
+                    let folded = fold(block, FOLD_128);
+                    block = (folded.0 + blocks[i].0, folded.1 + blocks[i].1);
+                }
+
+                // and reduce into our crc
+                let (hi, lo) = fold(block, FOLD_FINAL);
+                let x = (p128::from(hi) << 64) + p128::from(lo);
+                crc = __p::try_from(
+                    p64::try_from(x % p128(__polynomial)).unwrap()
+                ).unwrap();
+
+                data = chunks.remainder();
+            }
+
+            crc = crc << 8*size_of::<__u>()-__width;
+
+            // handle remainder using Barret reduction
+            let mut words = data.chunks_exact(size_of::<__u>());
+            for word in &mut words {
+                let word = <[u8; size_of::<__u>()]>::try_from(word).unwrap();
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc = crc + __p::from_le_bytes(word).reverse_bits();
+                    } else {
+                        crc = crc + __p::from_be_bytes(word);
+                    }
+                }
+                crc = (crc.widening_mul(BARRET_CONSTANT).1 + crc)
+                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+            }
+
+            for b in words.remainder() {
+                cfg_if! {
+                    if #[cfg(__if(__reflected))] {
+                        crc = crc + (__p::from(b.reverse_bits()) << (8*size_of::<__u>()-8));
+                    } else {
+                        crc = crc + (__p::from(*b) << (8*size_of::<__u>()-8));
+                    }
+                }
+                crc = (crc << 8)
+                    + ((crc >> (8*size_of::<__u>()-8)).widening_mul(BARRET_CONSTANT).1 + (crc >> (8*size_of::<__u>()-8)))
+                        .wrapping_mul(__p((__polynomial & __nonzeros) << (8*size_of::<__u>()-__width)));
+            }
+
+            // our division is always 8-bit aligned, so we need to do some
+            // finagling if our crc is not 8-bit aligned
+            crc = crc >> (8*size_of::<__u>()-__width);
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
+            __u::from(crc) ^ __xor
+        } else if #[cfg(all(__if(__hardware), not(__hardware_predicate)))] {
+            // explicitly requested, but not available on this target
+            compile_error!(__hardware_error)
+        } else if #[cfg(__if(__hardware))] {
+            // these instructions operate on the bit-reflected state directly
+            let mut crc = (crc ^ __xor) as u32;
+
+            // iterate over words
+            let mut words = data.chunks_exact(8);
+            for word in &mut words {
+                let word = <[u8; 8]>::try_from(word).unwrap();
+                crc = __hardware_u64(crc, u64::from_le_bytes(word));
+            }
+
+            // handle remainder
+            for b in words.remainder() {
+                crc = __hardware_u8(crc, *b);
+            }
+
+            (crc as __u) ^ __xor
+        } else if #[cfg(__if(__barret))] {
+            const BARRET_CONSTANT: __p = {
+                cfg_if! {
+                    if #[cfg(__if(__width > 64))] {
+                        // no 2x-wide type, fall back to bitwise division
+                        let polynomial = ((__p2(__polynomial).0 & __nonzeros) as __u)
+                            << (8*size_of::<__u>()-__width);
+                        let mut q: __u = 0;
+                        let mut r = polynomial;
+                        let mut i = 0;
+                        while i < 8*size_of::<__u>() {
+                            let overflow = r >> (8*size_of::<__u>()-1);
+                            r = r << 1;
+                            q = (q << 1) | overflow;
+                            if overflow != 0 {
+                                r = r ^ polynomial;
+                            }
+                            i += 1;
+                        }
+                        __p(q)
+                    } else {
+                        __p(
+                            __p2((__polynomial & __nonzeros) << ((8*size_of::<__u>()-__width) + 8*size_of::<__u>()))
+                                .naive_div(__p2(__polynomial << (8*size_of::<__u>()-__width)))
+                                .0 as __u
+                        )
+                    }
+                }
+            };
+
+            let mut crc = __p(crc ^ __xor);
+
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+                }
+            }
+
             crc = crc << 8*size_of::<__u>()-__width;
 
             // iterate over words
@@ -256,3 +579,558 @@ pub fn __crc(data: &[u8], crc: __u) -> __u {
     }
 }
 
+
+/// Combine the CRCs of two pieces of data into the CRC of their
+/// concatenation.
+///
+/// This only needs the length of the second piece of data, so CRCs can
+/// be computed in parallel, or out-of-order, and merged later:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let a = crc32c(b"Hello ", 0);
+/// let b = crc32c(b"World!", 0);
+/// assert_eq!(crc32c_combine(a, b, 6), 0xfe6cf1dc);
+/// assert_eq!(crc32c(b"Hello World!", 0), 0xfe6cf1dc);
+/// ```
+///
+/// This works by multiplying `crc_a` by `x^(8*len_b)` modulo the CRC's
+/// polynomial, which is the same as appending `len_b` zero bytes, and then
+/// adding `crc_b`. The initial/final xors cancel out, so there's no need to
+/// know about them here.
+///
+/// The power of `x` is found by repeated squaring, so this only needs
+/// O(log(len_b)) polynomial multiplications.
+///
+/// See the [module-level documentation](../crc) for more info.
+///
+pub fn __crc_combine(crc_a: __u, crc_b: __u, len_b: usize) -> __u {
+    // polynomial multiplication modulo our polynomial
+    cfg_if! {
+        if #[cfg(__if(__width > 64))] {
+            // no 2x-wide type, fall back to bitwise multiplication
+            let mul_rem = |a: __p, b: __p| -> __p {
+                let a = a.0 << (8*size_of::<__u>()-__width);
+                let mut x = 0;
+                for i in (0..__width).rev() {
+                    x = shl_rem(x, 1);
+                    if (b.0 >> i) & 1 != 0 {
+                        x ^= a;
+                    }
+                }
+                __p(x >> (8*size_of::<__u>()-__width))
+            };
+        } else {
+            let mul_rem = |a: __p, b: __p| -> __p {
+                __p::try_from(
+                    (__p2::from(a) * __p2::from(b)) % __p2(__polynomial)
+                ).unwrap()
+            };
+        }
+    }
+
+    let mut crc = __p(crc_a);
+
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
+
+    // x^8 modulo our polynomial, appending zeros a byte at a time
+    let mut x = __p(
+        shl_rem(1 << (8*size_of::<__u>()-__width), 8) >> (8*size_of::<__u>()-__width)
+    );
+    let mut len = len_b;
+    while len != 0 {
+        if len & 1 != 0 {
+            crc = mul_rem(crc, x);
+        }
+        x = mul_rem(x, x);
+        len >>= 1;
+    }
+
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            crc = crc.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
+
+    __u::from(crc) ^ crc_b
+}
+
+/// Build a table for rolling a CRC over a fixed-size window with
+/// [`__crc_roll`].
+///
+/// Each entry contains the contribution of a byte leaving the window. Since
+/// CRCs are linear, this can be removed with a single xor. See
+/// [`__crc_roll`] for more info.
+///
+/// This needs O(256 log(window)) polynomial multiplications, so it's best to
+/// build the table once and reuse it.
+///
+pub fn __crc_rolling_table(window: usize) -> [__u; 256] {
+    let mut table = [0; 256];
+    for i in 0..256 {
+        // this is the CRC of the byte followed by window zeros, with the
+        // contribution of the initial/final xors removed
+        table[i] = __crc_combine(__crc(&[i as u8], 0), 0, window);
+    }
+    table
+}
+
+/// Roll a CRC over a fixed-size window, adding a new byte and removing the
+/// oldest byte from the window.
+///
+/// This needs a table built with [`__crc_rolling_table`] for the given window
+/// size. With it, the CRC of a window can be updated in constant time, which
+/// is useful for content-defined chunking and deduplication:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let data = b"Hello World! Hello World!";
+/// let table = crc32c_rolling_table(12);
+///
+/// let mut crc = crc32c(&data[..12], 0);
+/// for i in 12..data.len() {
+///     crc = crc32c_roll(crc, &table, data[i-12], data[i]);
+///     assert_eq!(crc, crc32c(&data[i+1-12..i+1], 0));
+/// }
+/// assert_eq!(crc, 0xfe6cf1dc);
+/// ```
+///
+pub fn __crc_roll(crc: __u, table: &[__u; 256], old: u8, new: u8) -> __u {
+    __crc(&[new], crc) ^ table[usize::from(old)]
+}
+
+/// Update a CRC after some bytes in the data have changed, without needing
+/// to recompute the CRC over all of the data.
+///
+/// This takes the original CRC, the offset of the changed bytes, the old and
+/// new bytes, and the total length of the data:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let crc = crc32c(b"Hello World!", 0);
+/// assert_eq!(crc32c_patch(crc, 6, b"World", b"Earth", 12), crc32c(b"Hello Earth!", 0));
+/// ```
+///
+/// Since CRCs are linear, we only need to compute the CRC of the difference
+/// between the old and new bytes, and shift it to the right position with
+/// [`__crc_combine`]'s trick. This needs O(n + log(len)) operations, where n
+/// is the number of changed bytes.
+///
+/// # Panics
+///
+/// Panics if `old` and `new` have different lengths, or if the changed
+/// bytes, `offset..offset+old.len()`, do not fit in `len` bytes of data.
+///
+pub fn __crc_patch(
+    crc: __u,
+    offset: usize,
+    old: &[u8],
+    new: &[u8],
+    len: usize
+) -> __u {
+    assert_eq!(old.len(), new.len());
+    let end = offset.checked_add(old.len());
+    assert!(end.map_or(false, |end| end <= len));
+    let end = end.unwrap();
+
+    // find the CRC of the difference, starting with an initial state of xor
+    // cancels out the initial xor, leaving only the linear part of the CRC
+    let mut delta = __xor;
+    let mut buffer = [0u8; 64];
+    for (old, new) in old.chunks(buffer.len()).zip(new.chunks(buffer.len())) {
+        for i in 0..old.len() {
+            buffer[i] = old[i] ^ new[i];
+        }
+        delta = __crc(&buffer[..old.len()], delta);
+    }
+    delta ^= __xor;
+
+    // shift into place, and apply
+    crc ^ __crc_combine(delta, 0, len - end)
+}
+
+/// Calculate the CRC for a piece of data that is not a whole number of
+/// bytes long.
+///
+/// This takes the number of bits in the data, only the first `bits` bits
+/// of the data are used. For bit-reflected CRCs, bits are consumed
+/// least-significant bit first, so any trailing bits should be in the
+/// low bits of the last byte. Otherwise bits are consumed most-significant
+/// bit first, and any trailing bits should be in the high bits of the last
+/// byte.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32c_bits(b"Hello World!", 96, 0), crc32c(b"Hello World!", 0));
+/// assert_eq!(crc32c_bits(b"Hello World!", 95, 0), 0xfcd9e3b9);
+/// ```
+///
+/// Like the CRC function, this takes the previous state of the CRC as an
+/// argument. The resulting CRC can always be continued with further calls
+/// to this function, but can only be continued with the byte-wise CRC
+/// function if `bits` is a multiple of 8:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// // crc32c is bit-reflected, so the low nibble comes first
+/// let mut crc = 0;
+/// for b in b"Hello World!" {
+///     crc = crc32c_bits(&[b & 0xf], 4, crc);
+///     crc = crc32c_bits(&[b >> 4], 4, crc);
+/// }
+/// assert_eq!(crc, crc32c(b"Hello World!", 0));
+///
+/// let crc = crc32c_bits(b"Hello ", 48, 0);
+/// assert_eq!(crc32c(b"World!", crc), crc32c(b"Hello World!", 0));
+/// ```
+///
+pub fn __crc_bits(data: &[u8], bits: usize, crc: __u) -> __u {
+    assert!(bits <= 8*data.len());
+
+    // handle whole bytes
+    let mut crc = __crc(&data[..bits/8], crc) ^ __xor;
+
+    // handle any remaining bits one at a time
+    if bits % 8 != 0 {
+        let b = data[bits/8];
+        for i in 0..bits % 8 {
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    let bit = __u::from((b >> i) & 1);
+                    let overflow = (crc ^ bit) & 1 != 0;
+                    crc = crc >> 1;
+                    if overflow {
+                        crc = crc ^ (
+                            ((__p2(__polynomial).0 & __nonzeros) as __u).reverse_bits()
+                                >> (8*size_of::<__u>()-__width)
+                        );
+                    }
+                } else {
+                    let bit = __u::from((b >> (7-i)) & 1);
+                    let overflow = ((crc >> (__width-1)) ^ bit) & 1 != 0;
+                    crc = (crc << 1) & __nonzeros;
+                    if overflow {
+                        crc = crc ^ ((__p2(__polynomial).0 & __nonzeros) as __u);
+                    }
+                }
+            }
+        }
+    }
+
+    crc ^ __xor
+}
+
+/// Attempt to correct a single-bit error in a piece of data, given the
+/// expected CRC.
+///
+/// Returns the number of bit-errors corrected, either 0 or 1, or
+/// [`Error::TooManyErrors`](__crate::crc::Error::TooManyErrors) if the data
+/// can not be corrected. Note the bit-error may also be in the expected CRC
+/// itself, in which case the data is left unmodified:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let crc = crc32c(&data, 0);
+///
+/// data[6] ^= 0x04;
+/// assert_eq!(&data, b"Hello Sorld!");
+/// assert_eq!(crc32c_correct(&mut data, crc), Ok(1));
+/// assert_eq!(&data, b"Hello World!");
+/// ```
+///
+/// This is only reliable as long as the CRC's Hamming distance is at least 3
+/// for the length of the data, otherwise multiple bit-errors may share the
+/// same syndrome, and this may "correct" the wrong bit.
+///
+/// This only corrects a single bit-error. To correct a short burst of
+/// bit-errors, see [`__crc_correct_burst`].
+///
+pub fn __crc_correct(
+    data: &mut [u8],
+    crc: __u
+) -> Result<usize, __crate::crc::Error> {
+    __crc_correct_burst(data, crc, 1)
+}
+
+/// Attempt to correct a burst of bit-errors, up to `burst` bits long, in a
+/// piece of data, given the expected CRC.
+///
+/// Returns the number of bit-errors corrected, or
+/// [`Error::TooManyErrors`](__crate::crc::Error::TooManyErrors) if the data
+/// can not be corrected. Like [`__crc_correct`], the burst may also be in the
+/// expected CRC itself, in which case those bits are counted but the data is
+/// left unmodified:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let crc = crc32c(&data, 0);
+///
+/// data[6] ^= 0x80;
+/// data[7] ^= 0x03;
+/// assert_eq!(&data, b"Hello \xd7lrld!");
+/// assert_eq!(crc32c_correct_burst(&mut data, crc, 8), Ok(3));
+/// assert_eq!(&data, b"Hello World!");
+/// ```
+///
+/// Since CRCs are linear, the difference between the CRC of the data and the
+/// expected CRC, the "syndrome", is the error polynomial E(x) mod G(x). A
+/// burst starting at bit u is E(x) = e(x)\*x^u, with e(x) less than `burst`
+/// bits, so we can find it by repeatedly dividing the syndrome by x mod G(x)
+/// until it fits in `burst` bits. This takes O(n) operations.
+///
+/// This is only reliable as long as no two bursts of up to `burst` bits share
+/// a syndrome for the length of the data, otherwise this may "correct" the
+/// wrong bits. For most CRCs this requires `burst` to be well below half the
+/// CRC's width, and at most 1 if you only know the CRC's Hamming distance is
+/// at least 3.
+///
+pub fn __crc_correct_burst(
+    data: &mut [u8],
+    crc: __u,
+    burst: usize
+) -> Result<usize, __crate::crc::Error> {
+    assert!(burst > 0);
+
+    let mut s = __crc(data, 0) ^ crc;
+    if s == 0 {
+        return Ok(0);
+    }
+
+    // find the syndrome as a polynomial, reflected CRCs store their
+    // remainders bit-reversed
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            s = s.reverse_bits() >> (8*size_of::<__u>()-__width);
+        }
+    }
+
+    // our codeword is the data followed by the CRC, so the CRC's bits start
+    // at x^0, and the data's bits start at x^width
+    let g = (__p2(__polynomial).0 & __nonzeros) as __u;
+    let n = 8*data.len() + __width;
+    for u in 0..n {
+        // does the syndrome fit in our burst?
+        let len = 8*size_of::<__u>() - usize::try_from(s.leading_zeros()).unwrap();
+        if len <= burst && u + len <= n {
+            for k in 0..len {
+                if (s >> k) & 1 != 0 && u+k >= __width {
+                    let d = u+k - __width;
+                    cfg_if! {
+                        if #[cfg(__if(__reflected))] {
+                            data[data.len()-1 - d/8] ^= 0x80 >> (d%8);
+                        } else {
+                            data[data.len()-1 - d/8] ^= 0x01 << (d%8);
+                        }
+                    }
+                }
+            }
+
+            return Ok(usize::try_from(s.count_ones()).unwrap());
+        }
+
+        // divide by x mod G(x), note G(x) always has a constant term, so
+        // we can always make the syndrome divisible by x
+        if s & 1 != 0 {
+            s = ((s ^ g) >> 1) | (1 << (__width-1));
+        } else {
+            s = s >> 1;
+        }
+    }
+
+    Err(__crate::crc::Error::TooManyErrors)
+}
+
+/// Calculate the CRC of a piece of data using multiple threads.
+///
+/// This splits the data into chunks, calculates the CRC of each chunk in
+/// parallel with [rayon](__crate::internal::rayon), and combines the
+/// resulting CRCs with [`__crc_combine`]:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let data = b"Hello World!".repeat(100000);
+/// assert_eq!(crc32c_parallel(&data, 0), crc32c(&data, 0));
+/// ```
+///
+/// Note this requires feature `rayon`.
+///
+#[cfg(__if(__rayon))]
+pub fn __crc_parallel(data: &[u8], crc: __u) -> __u {
+    use __crate::internal::rayon::prelude::*;
+
+    // chunk size, this should be large enough that the cost of combining
+    // CRCs is negligible
+    const CHUNK: usize = 64*1024;
+
+    let (crc_b, _) = data.par_chunks(CHUNK)
+        .map(|chunk| (__crc(chunk, 0), chunk.len()))
+        .reduce(
+            || (0, 0),
+            |(crc_a, len_a), (crc_b, len_b)| {
+                (__crc_combine(crc_a, crc_b, len_b), len_a + len_b)
+            }
+        );
+
+    __crc_combine(crc, crc_b, data.len())
+}
+
+/// Calculate the CRC of a piece of data split across multiple buffers.
+///
+/// This accepts anything that dereferences into bytes, including
+/// [`IoSlice`](https://doc.rust-lang.org/std/io/struct.IoSlice.html)s,
+/// which is useful for scatter/gather IO where coalescing the buffers would
+/// require an extra copy:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// assert_eq!(crc32c_vectored(&[&b"Hello "[..], &b"World!"[..]], 0), 0xfe6cf1dc);
+/// ```
+///
+pub fn __crc_vectored<B: core::ops::Deref<Target=[u8]>>(
+    bufs: &[B],
+    crc: __u
+) -> __u {
+    let mut crc = crc;
+    for buf in bufs {
+        crc = __crc(buf, crc);
+    }
+    crc
+}
+
+/// Calculate the CRC for a piece of data in a const context.
+///
+/// This uses a naive bitwise implementation, so it's slower than the
+/// CRC function at runtime, but it can be used to compute CRCs at
+/// compile-time:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// const CRC: u32 = crc32c_const(b"Hello World!", 0);
+/// assert_eq!(CRC, 0xfe6cf1dc);
+/// ```
+///
+pub const fn __crc_const(data: &[u8], crc: __u) -> __u {
+    cfg_if! {
+        if #[cfg(__if(__reflected))] {
+            let polynomial = ((__p2(__polynomial).0 & __nonzeros) as __u).reverse_bits()
+                >> (8*size_of::<__u>()-__width);
+
+            let mut crc = crc ^ __xor;
+            let mut i = 0;
+            while i < data.len() {
+                crc = crc ^ (data[i] as __u);
+                let mut j = 0;
+                while j < 8 {
+                    if crc & 1 != 0 {
+                        crc = (crc >> 1) ^ polynomial;
+                    } else {
+                        crc = crc >> 1;
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+
+            crc ^ __xor
+        } else {
+            let mut crc = (crc ^ __xor) << (8*size_of::<__u>()-__width);
+            let mut i = 0;
+            while i < data.len() {
+                crc = shl_rem(crc ^ ((data[i] as __u) << (8*size_of::<__u>()-8)), 8);
+                i += 1;
+            }
+
+            (crc >> (8*size_of::<__u>()-__width)) ^ __xor
+        }
+    }
+}
+
+/// Check a piece of data that ends with its own CRC.
+///
+/// The CRC is expected to be appended in the smallest number of bytes that
+/// fit the CRC, little-endian for bit-reflected CRCs, or big-endian and
+/// aligned to the most-significant bit otherwise. In both cases the bits of
+/// the CRC immediately follow the bits of the data.
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let crc = crc32c(&data, 0);
+/// data.extend_from_slice(&crc.to_le_bytes());
+/// assert!(crc32c_verify(&data));
+///
+/// data[0] ^= 0x01;
+/// assert!(!crc32c_verify(&data));
+/// ```
+///
+/// Rather than recomputing the CRC and comparing, this takes advantage of
+/// the fact that the CRC of any data followed by its CRC is a constant,
+/// called the residue, so only one pass over the data is needed.
+///
+pub fn __crc_verify(data: &[u8]) -> bool {
+    // number of bytes used to store the CRC
+    const N: usize = usize::div_ceil(__width, 8);
+
+    // the CRC of any data followed by its CRC, it's easiest to find this
+    // with the empty message
+    const RESIDUE: __u = {
+        let mut bytes = [0u8; N];
+        let mut i = 0;
+        while i < N {
+            cfg_if! {
+                if #[cfg(__if(__reflected))] {
+                    bytes[i] = (__crc_const(&[], 0) >> (8*i)) as u8;
+                } else {
+                    bytes[i] = ((__crc_const(&[], 0) << (8*N-__width)) >> (8*(N-1-i))) as u8;
+                }
+            }
+            i += 1;
+        }
+        __crc_const(&bytes, 0)
+    };
+
+    data.len() >= N && __crc(data, 0) == RESIDUE
+}
+
+/// The byte-wise lookup table for this CRC.
+///
+/// This is the same table used by common table-based CRC implementations,
+/// regardless of the mode used by this CRC, so it can be used to keep other
+/// implementations compatible. For bit-reflected CRCs, the table is indexed
+/// by the low byte of the CRC, otherwise the table is indexed by the high
+/// byte of the CRC:
+///
+/// ``` rust
+/// # use ::gf256::crc::*;
+/// let table = crc32c_byte_table();
+/// let mut crc = 0xffffffff;
+/// for b in b"Hello World!" {
+///     crc = (crc >> 8) ^ table[usize::from((crc as u8) ^ b)];
+/// }
+/// assert_eq!(crc ^ 0xffffffff, crc32c(b"Hello World!", 0));
+/// ```
+///
+/// See also [`export`](__crate::export) for exporting this table to C.
+///
+pub const fn __crc_byte_table() -> [__u; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < table.len() {
+        cfg_if! {
+            if #[cfg(__if(__reflected))] {
+                table[i] = __crc_const(&[i as u8], __xor) ^ __xor;
+            } else {
+                table[i] = shl_rem((i as __u) << (8*size_of::<__u>()-8), 8)
+                    >> (8*size_of::<__u>()-__width);
+            }
+        }
+        i += 1;
+    }
+    table
+}
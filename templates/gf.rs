@@ -54,11 +54,28 @@ impl __gf {
     /// Number of non-zero elements in the field.
     pub const NONZEROS: __u = __nonzeros;
 
+    /// Indicates if multiplication and division are constant-time, which is
+    /// only true in `barret` mode.
+    pub const CONSTANT_TIME: bool = __barret;
+
     // Generate log/antilog tables using our generator if we're in table mode
+
+    /// Log table, mapping each non-zero element to its discrete logarithm
+    /// with respect to the generator.
+    ///
+    /// Note this is only available in `table` mode, and `LOG_TABLE[0]`
+    /// is undefined.
+    ///
     #[cfg(__if(__table))]
-    const LOG_TABLE: [__u; __nonzeros+1] = Self::LOG_EXP_TABLES.0;
+    pub const LOG_TABLE: [__u; __nonzeros+1] = Self::LOG_EXP_TABLES.0;
+
+    /// Antilog table, mapping each logarithm to its element, this is
+    /// the inverse of [`LOG_TABLE`](Self::LOG_TABLE).
+    ///
+    /// Note this is only available in `table` mode.
+    ///
     #[cfg(__if(__table))]
-    const EXP_TABLE: [__u; __nonzeros+1] = Self::LOG_EXP_TABLES.1;
+    pub const EXP_TABLE: [__u; __nonzeros+1] = Self::LOG_EXP_TABLES.1;
     #[cfg(__if(__table))]
     const LOG_EXP_TABLES: ([__u; __nonzeros+1], [__u; __nonzeros+1]) = {
         let mut log_table = [0; __nonzeros+1];
@@ -3879,8 +3896,8 @@ impl FromStr for __gf {
     /// hexadecimal strings starting with `0x`. If you need a different radix
     /// there is [`from_str_radix`](#method.from_str_radix).
     fn from_str(s: &str) -> Result<__gf, ParseIntError> {
-        if s.starts_with("0x") {
-            Ok(__gf(__u::from_str_radix(&s[2..], 16)?))
+        if let Some(s) = s.strip_prefix("0x") {
+            Ok(__gf(__u::from_str_radix(s, 16)?))
         } else {
             "".parse::<__u>()?;
             unreachable!()
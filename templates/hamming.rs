@@ -0,0 +1,418 @@
+// Template for Hamming/SECDED error-correction functions
+//
+// See the module-level documentation in src/hamming.rs for a more detailed
+// explanation of where these implementations come from
+
+//! Hamming error-correction functions.
+//!
+//! ``` rust
+//! # use gf256::hamming::secded72w64;
+//! #
+//! // encode
+//! let mut data = 0x0123456789abcdefu64;
+//! let mut ecc = secded72w64::encode_word(data);
+//!
+//! // corrupt
+//! data ^= 1 << 17;
+//!
+//! // correct
+//! assert_eq!(secded72w64::correct_word(&mut data, &mut ecc)?, 1);
+//! assert_eq!(data, 0x0123456789abcdef);
+//! # Ok::<(), secded72w64::Error>(())
+//! ```
+//!
+//! See the [module-level documentation](../../hamming) for more info.
+
+
+use __crate::traits::TryFrom;
+use core::fmt;
+use core::mem::size_of;
+
+
+// Constants for Hamming error correction
+//
+// A Hamming code with M parity bits can correct a single bit error in a
+// codeword of up to 2^M-1 bits. An extended Hamming code adds one more
+// parity bit over the whole codeword, which lets us also detect, but not
+// correct, any two bit errors.
+//
+
+/// Number of parity bits in the underlying Hamming code, codewords are
+/// limited to `2^M-1` bits, plus the overall parity bit if [`EXTENDED`].
+pub const M: usize = __m;
+
+/// Whether this is an extended Hamming code, with an extra overall parity
+/// bit for single-error-correction/double-error-detection (SECDED).
+pub const EXTENDED: bool = __extended;
+
+/// Number of bits of error-correction, [`M`], plus one if [`EXTENDED`].
+pub const ECC_BITS: usize = M + if EXTENDED { 1 } else { 0 };
+
+/// Size of the appended error-correction in bytes, [`ECC_BITS`] rounded up.
+pub const ECC_SIZE: usize = ECC_BITS.div_ceil(8);
+
+/// Maximum size of the original data in bytes.
+pub const DATA_SIZE: usize = ((1 << M) - 1 - M) / 8;
+
+/// Size of the codeword, [`DATA_SIZE`] + [`ECC_SIZE`], in bytes.
+pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
+
+/// Number of data bits protected by [`encode_word`]/[`correct_word`], the
+/// low bits of each word, any remaining bits are ignored.
+#[cfg(__if(__words))]
+pub const WORD_BITS: usize = {
+    let max = (1 << M) - 1 - M;
+    if (__u::BITS as usize) < max { __u::BITS as usize } else { max }
+};
+
+// Mask of the used bits of error-correction
+const ECC_MASK: __c = !0 >> (__c::BITS as usize - ECC_BITS);
+
+// Mask of the data bits in each word
+#[cfg(__if(__words))]
+const WORD_MASK: __u = !0 >> (__u::BITS as usize - WORD_BITS);
+
+// Each bit in a Hamming code is assigned a unique, non-zero, M-bit column.
+// The parity bits get the columns with only one bit set, 1, 2, 4, etc, and
+// the data bits get the remaining columns in order, 3, 5, 6, 7, 9, etc.
+//
+// The parity bits are chosen so that the xor of the columns of all set bits
+// is zero. A single bit error then leaves us with a non-zero syndrome, which
+// is exactly the column of the erroneous bit.
+//
+
+/// Find the column of the i-th data bit
+const fn column(i: usize) -> usize {
+    // skip over any powers of 2
+    let mut c = i + 1;
+    let mut j = 1;
+    while j <= c {
+        c += 1;
+        j <<= 1;
+    }
+    c
+}
+
+/// Columns of each data bit in a word
+#[cfg(__if(__words))]
+const WORD_COLUMNS: [usize; WORD_BITS] = {
+    let mut columns = [0; WORD_BITS];
+    let mut i = 0;
+    while i < WORD_BITS {
+        columns[i] = column(i);
+        i += 1;
+    }
+    columns
+};
+
+
+/// Error codes for Hamming codes
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Hamming codes can only correct a single bit error.
+    ///
+    /// Extended Hamming codes are guaranteed to detect two bit errors, and
+    /// errors pointing outside of a shortened codeword are also detected,
+    /// but with more errors the codeword may be "corrected" to the wrong
+    /// codeword.
+    ///
+    TooManyErrors,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooManyErrors => write!(f, "Too many errors to correct"),
+        }
+    }
+}
+
+#[cfg(__if(__std))]
+impl __crate::internal::std::error::Error for Error {}
+
+
+/// Location of a single bit error
+enum ErrorLocation {
+    None,
+    Data(usize),
+    Ecc(usize),
+}
+
+/// Find the error-correction bits given the xor of the columns of all set
+/// data bits, and the number of set data bits
+fn find_ecc(syndrome: usize, ones: u32) -> __c {
+    let mut ecc = syndrome as __c;
+    if EXTENDED {
+        ecc |= (((ones + ecc.count_ones()) & 1) as __c) << M;
+    }
+    ecc
+}
+
+/// Find the location of a single bit error given the syndrome and overall
+/// parity of a codeword with the given number of data bits
+fn find_error(
+    syndrome: usize,
+    parity: u32,
+    data_bits: usize
+) -> Result<ErrorLocation, Error> {
+    if EXTENDED {
+        // an even number of errors leaves the overall parity unchanged,
+        // so if the syndrome is non-zero we must have two errors
+        if parity == 0 {
+            return match syndrome {
+                0 => Ok(ErrorLocation::None),
+                _ => Err(Error::TooManyErrors),
+            };
+        }
+
+        // error in the overall parity bit itself?
+        if syndrome == 0 {
+            return Ok(ErrorLocation::Ecc(M));
+        }
+    } else if syndrome == 0 {
+        return Ok(ErrorLocation::None);
+    }
+
+    if syndrome.is_power_of_two() {
+        // error in a parity bit
+        Ok(ErrorLocation::Ecc(syndrome.trailing_zeros() as usize))
+    } else {
+        // error in a data bit, find the data bit from its column by
+        // subtracting the powers of 2 we skipped
+        let i = syndrome - 2 - (usize::BITS-1 - syndrome.leading_zeros()) as usize;
+        if i < data_bits {
+            Ok(ErrorLocation::Data(i))
+        } else {
+            // error points outside of our shortened codeword
+            Err(Error::TooManyErrors)
+        }
+    }
+}
+
+
+/// Encode a word using Hamming error-correction, returning the
+/// error-correction bits.
+///
+/// This only protects the low [`WORD_BITS`] bits of the word, any other bits
+/// are ignored.
+///
+/// ``` rust
+/// # use gf256::hamming::secded72w64;
+/// let ecc = secded72w64::encode_word(0x0123456789abcdef);
+/// assert_eq!(ecc, 0x9c);
+/// ```
+///
+#[cfg(__if(__words))]
+pub fn encode_word(data: __u) -> __c {
+    let mut x = data & WORD_MASK;
+    let mut syndrome = 0;
+    while x != 0 {
+        syndrome ^= WORD_COLUMNS[x.trailing_zeros() as usize];
+        x &= x - 1;
+    }
+
+    find_ecc(syndrome, (data & WORD_MASK).count_ones())
+}
+
+/// Determine if a word and its error-correction bits are correct and have
+/// no errors.
+///
+/// ``` rust
+/// # use gf256::hamming::secded72w64;
+/// let ecc = secded72w64::encode_word(0x0123456789abcdef);
+/// assert!(secded72w64::is_word_correct(0x0123456789abcdef, ecc));
+/// assert!(!secded72w64::is_word_correct(0x0123456789abcdee, ecc));
+/// ```
+///
+#[cfg(__if(__words))]
+pub fn is_word_correct(data: __u, ecc: __c) -> bool {
+    encode_word(data) == ecc & ECC_MASK
+}
+
+/// Correct up to one bit error in a word and its error-correction bits.
+///
+/// Returns the number of bit errors corrected, or [`Error::TooManyErrors`]
+/// if the errors can not be corrected, in which case the word and
+/// error-correction bits are left unmodified. With [`EXTENDED`], this is
+/// guaranteed to detect any two bit errors.
+///
+/// ``` rust
+/// # use gf256::hamming::secded72w64;
+/// let mut data = 0x0123456789abcdefu64;
+/// let mut ecc = secded72w64::encode_word(data);
+///
+/// // one error can be corrected
+/// data ^= 0x100;
+/// assert_eq!(secded72w64::correct_word(&mut data, &mut ecc), Ok(1));
+/// assert_eq!(data, 0x0123456789abcdef);
+///
+/// // two errors can only be detected
+/// data ^= 0x101;
+/// assert_eq!(
+///     secded72w64::correct_word(&mut data, &mut ecc),
+///     Err(secded72w64::Error::TooManyErrors)
+/// );
+/// ```
+///
+#[cfg(__if(__words))]
+pub fn correct_word(data: &mut __u, ecc: &mut __c) -> Result<usize, Error> {
+    let x = *data & WORD_MASK;
+    let e = *ecc & ECC_MASK;
+    let syndrome = usize::try_from(encode_word(x) ^ e).unwrap() & ((1 << M) - 1);
+    let parity = (x.count_ones() + e.count_ones()) & 1;
+
+    match find_error(syndrome, parity, WORD_BITS)? {
+        ErrorLocation::None => Ok(0),
+        ErrorLocation::Data(i) => {
+            *data ^= 1 << i;
+            Ok(1)
+        }
+        ErrorLocation::Ecc(i) => {
+            *ecc ^= 1 << i;
+            Ok(1)
+        }
+    }
+}
+
+
+/// Find the syndrome and number of set bits of some data
+fn find_data_syndrome(data: &[u8]) -> (usize, u32) {
+    let mut syndrome = 0;
+    let mut ones = 0;
+    for (i, b) in data.iter().enumerate() {
+        let mut x = *b;
+        while x != 0 {
+            let j = x.leading_zeros() as usize;
+            syndrome ^= column(8*i + j);
+            x &= !(0x80 >> j);
+        }
+        ones += b.count_ones();
+    }
+    (syndrome, ones)
+}
+
+/// Read the error-correction bits of a codeword
+fn read_ecc(parity: &[u8]) -> __c {
+    let mut buf = [0u8; size_of::<__c>()];
+    buf[size_of::<__c>()-ECC_SIZE..].copy_from_slice(parity);
+    __c::from_be_bytes(buf) & ECC_MASK
+}
+
+/// Encode a message using Hamming error-correction.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to the end
+/// of the provided slice, based on the data provided in the first
+/// `message.len()-ECC_SIZE` bytes. The entire codeword is limited to at most
+/// [`BLOCK_SIZE`] bytes, but can be smaller.
+///
+/// ``` rust
+/// # use gf256::hamming::secded8192w8178;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+2, 0u8);
+/// secded8192w8178::encode(&mut codeword);
+/// assert!(secded8192w8178::is_correct(&codeword));
+/// ```
+///
+pub fn encode(message: &mut [u8]) {
+    assert!(message.len() <= BLOCK_SIZE);
+    assert!(message.len() >= ECC_SIZE);
+    let data_len = message.len() - ECC_SIZE;
+
+    let (data, parity) = message.split_at_mut(data_len);
+    encode_parity(data, parity);
+}
+
+/// Encode a message using Hamming error-correction, writing the
+/// error-correction information to a separate buffer.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to
+/// `parity`, based on the data provided in `data`. The data is limited to at
+/// most [`DATA_SIZE`] bytes, but can be smaller. The error-correction bits
+/// are stored big-endian, any unused bits at the start of `parity` are set
+/// to zero.
+///
+pub fn encode_parity(data: &[u8], parity: &mut [u8]) {
+    assert!(data.len() <= DATA_SIZE);
+    assert!(parity.len() == ECC_SIZE);
+
+    let (syndrome, ones) = find_data_syndrome(data);
+    let ecc = find_ecc(syndrome, ones);
+    parity.copy_from_slice(&ecc.to_be_bytes()[size_of::<__c>()-ECC_SIZE..]);
+}
+
+/// Find the syndrome and overall parity of a codeword
+fn find_syndrome(codeword: &[u8]) -> (usize, u32) {
+    let (data, parity) = codeword.split_at(codeword.len()-ECC_SIZE);
+    let (syndrome, ones) = find_data_syndrome(data);
+    let ecc = read_ecc(parity);
+    (
+        syndrome ^ (usize::try_from(ecc).unwrap() & ((1 << M) - 1)),
+        (ones + ecc.count_ones()) & 1,
+    )
+}
+
+/// Determine if codeword is correct and has no errors.
+///
+/// ``` rust
+/// # use gf256::hamming::secded8192w8178;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+2, 0u8);
+/// secded8192w8178::encode(&mut codeword);
+/// assert!(secded8192w8178::is_correct(&codeword));
+///
+/// codeword[3] ^= 0x04;
+/// assert!(!secded8192w8178::is_correct(&codeword));
+/// ```
+///
+pub fn is_correct(codeword: &[u8]) -> bool {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+
+    find_syndrome(codeword) == (0, 0)
+}
+
+/// Correct up to one bit error at an unknown location.
+///
+/// Returns the number of bit errors corrected, or [`Error::TooManyErrors`]
+/// if the codeword can not be corrected, in which case the codeword is left
+/// unmodified. With [`EXTENDED`], this is guaranteed to detect any two bit
+/// errors.
+///
+/// ``` rust
+/// # use gf256::hamming::secded8192w8178;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+2, 0u8);
+/// secded8192w8178::encode(&mut codeword);
+///
+/// // one error can be corrected
+/// codeword[5] ^= 0x10;
+/// assert_eq!(secded8192w8178::correct_errors(&mut codeword), Ok(1));
+/// assert_eq!(&codeword[..12], b"Hello World!");
+///
+/// // two errors can only be detected
+/// codeword[5] ^= 0x10;
+/// codeword[7] ^= 0x01;
+/// assert_eq!(
+///     secded8192w8178::correct_errors(&mut codeword),
+///     Err(secded8192w8178::Error::TooManyErrors)
+/// );
+/// ```
+///
+pub fn correct_errors(codeword: &mut [u8]) -> Result<usize, Error> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+
+    let data_bits = 8*(codeword.len()-ECC_SIZE);
+    let (syndrome, parity) = find_syndrome(codeword);
+    match find_error(syndrome, parity, data_bits)? {
+        ErrorLocation::None => Ok(0),
+        ErrorLocation::Data(i) => {
+            codeword[i/8] ^= 0x80 >> (i%8);
+            Ok(1)
+        }
+        ErrorLocation::Ecc(i) => {
+            codeword[data_bits/8 + ECC_SIZE-1-i/8] ^= 1 << (i%8);
+            Ok(1)
+        }
+    }
+}
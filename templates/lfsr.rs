@@ -209,9 +209,68 @@ impl __lfsr {
             }
         }
 
+        cfg_if! {
+            if #[cfg(__if(__fibonacci))] {
+                // a Fibonacci register holds the next width bits of output,
+                // these are the quotient of our Galois state times x^width,
+                // so we can find the Galois state by multiplying the seed
+                // by the polynomial, the remainder only affects the lower
+                // bits
+                seed = (
+                    __p2(seed as __u2).naive_mul(__p2(__polynomial)).0
+                        >> __width
+                ) as __u;
+            }
+        }
+
         Self(unsafe { __nzu::new_unchecked(seed) })
     }
 
+    /// Get the current state of the LFSR.
+    ///
+    /// This is in the same form as the seed passed to [`new`](Self::new),
+    /// so `new(lfsr.state())` creates an LFSR that continues exactly where
+    /// this one left off.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// assert_eq!(lfsr.next(16), 0x0001);
+    ///
+    /// let state = lfsr.state();
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// assert_eq!(lfsr.next(16), 0x0451);
+    ///
+    /// lfsr.set_state(state);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// assert_eq!(lfsr.next(16), 0x0451);
+    /// ```
+    ///
+    #[inline]
+    pub fn state(&self) -> __u {
+        cfg_if! {
+            if #[cfg(__if(__fibonacci))] {
+                // a Fibonacci register holds the next width bits of output
+                self.clone().next(__width)
+            } else if #[cfg(__if(__reflected))] {
+                __u::from(self.0).reverse_bits() >> (8*size_of::<__u>()-__width)
+            } else {
+                __u::from(self.0)
+            }
+        }
+    }
+
+    /// Set the current state of the LFSR.
+    ///
+    /// This is equivalent to replacing the LFSR with `new(state)`, so
+    /// like [`new`](Self::new), if `0` is provided, the state `1` is used
+    /// instead.
+    ///
+    #[inline]
+    pub fn set_state(&mut self, state: __u) {
+        *self = Self::new(state);
+    }
+
     /// Generate the next n-bits of pseudo-random data.
     ///
     /// ``` rust
@@ -247,7 +306,7 @@ impl __lfsr {
                 // lfsr with a per-byte division and remainder table
                 let mut x = __u::from(self.0) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
-                for i in (0..(bits+7)/8).rev() {
+                for i in (0..bits.div_ceil(8)).rev() {
                     let n = min(8, bits-8*i);
                     if n == 8*size_of::<__u>() {
                         q = __u::from(Self::DIV_TABLE[usize::try_from(
@@ -269,7 +328,7 @@ impl __lfsr {
                 // lfsr with a per-nibble division and remainder table
                 let mut x = __u::from(self.0) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
-                for i in (0..(bits+3)/4).rev() {
+                for i in (0..bits.div_ceil(4)).rev() {
                     let n = min(4, bits-4*i);
                     q = (q << n) | __u::from(Self::DIV_TABLE[usize::try_from(
                         x >> (8*size_of::<__u>()-n)).unwrap()]);
@@ -297,7 +356,7 @@ impl __lfsr {
                 // lfsr using a per-byte division table with Barret-reduction
                 let mut x = __p::from(__u::from(self.0)) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
-                for i in (0..(bits+7)/8).rev() {
+                for i in (0..bits.div_ceil(8)).rev() {
                     let n = min(8, bits-8*i);
                     if n == 8*size_of::<__u>() {
                         q = __u::from(Self::DIV_TABLE[usize::try_from(
@@ -322,7 +381,7 @@ impl __lfsr {
                 // lfsr using a per-nibble division table with Barret-reduction
                 let mut x = __p::from(__u::from(self.0)) << (8*size_of::<__u>()-__width);
                 let mut q = 0;
-                for i in (0..(bits+3)/4).rev() {
+                for i in (0..bits.div_ceil(4)).rev() {
                     let n = min(4, bits-4*i);
                     q = (q << n) | __u::from(Self::DIV_TABLE[usize::try_from(
                         x >> (8*size_of::<__u>()-n)).unwrap()]);
@@ -383,7 +442,7 @@ impl __lfsr {
                 // lfsr with a per-byte division and remainder table
                 let mut x = __u::from(self.0);
                 let mut q = 0;
-                for i in (0..(bits+7)/8).rev() {
+                for i in (0..bits.div_ceil(8)).rev() {
                     let n = min(8, bits-8*i);
                     if n == 8*size_of::<__u>() {
                         q = __u::from(Self::INVERSE_DIV_TABLE[usize::try_from(
@@ -404,7 +463,7 @@ impl __lfsr {
                 // lfsr with a per-nibble division and remainder table
                 let mut x = __u::from(self.0);
                 let mut q = 0;
-                for i in (0..(bits+3)/4).rev() {
+                for i in (0..bits.div_ceil(4)).rev() {
                     let n = min(4, bits-4*i);
                     q = (q >> n) | (__u::from(Self::INVERSE_DIV_TABLE[usize::try_from(
                         (x << (4-n)) & 0xf).unwrap()]) << (8*size_of::<__u>()-4));
@@ -432,7 +491,7 @@ impl __lfsr {
                 // lfsr using a per-byte division table with Barret-reduction
                 let mut x = __p::from(__u::from(self.0).reverse_bits());
                 let mut q = 0;
-                for i in (0..(bits+7)/8).rev() {
+                for i in (0..bits.div_ceil(8)).rev() {
                     let n = min(8, bits-8*i);
                     if n == 8*size_of::<__u>() {
                         q = __u::from(Self::INVERSE_DIV_TABLE[usize::try_from(
@@ -459,7 +518,7 @@ impl __lfsr {
                 // lfsr using a per-nibble division table with Barret-reduction
                 let mut x = __p::from(__u::from(self.0).reverse_bits());
                 let mut q = 0;
-                for i in (0..(bits+3)/4).rev() {
+                for i in (0..bits.div_ceil(4)).rev() {
                     let n = min(4, bits-4*i);
                     q = (q >> n) | (__u::from(Self::INVERSE_DIV_TABLE[usize::try_from(
                         x >> (8*size_of::<__u>()-n)).unwrap()])) << (8*size_of::<__u>()-4);
@@ -486,6 +545,41 @@ impl __lfsr {
         }
     }
 
+    /// Iterate over the pseudo-random data one bit at a time.
+    ///
+    /// Each bit advances the LFSR by one step, so the LFSR is left exactly
+    /// after the last bit taken.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// let bits = lfsr.bits().take(16).collect::<Vec<_>>();
+    /// assert_eq!(bits.iter().filter(|b| **b).count(), 1);
+    /// assert!(bits[15]);
+    /// assert_eq!(lfsr.next(16), 0x002d);
+    /// ```
+    ///
+    #[inline]
+    pub fn bits(&mut self) -> impl FusedIterator<Item=bool> + '_ {
+        core::iter::repeat_with(move || self.next(1) != 0)
+    }
+
+    /// Iterate over the previous pseudo-random data one bit at a time.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// lfsr.skip(16);
+    /// let bits = lfsr.bits_backwards().take(16).collect::<Vec<_>>();
+    /// assert!(bits[0]);
+    /// assert_eq!(bits.iter().filter(|b| **b).count(), 1);
+    /// ```
+    ///
+    #[inline]
+    pub fn bits_backwards(&mut self) -> impl FusedIterator<Item=bool> + '_ {
+        core::iter::repeat_with(move || self.prev(1) != 0)
+    }
+
     /// Skip n-bits of pseudo-random data.
     ///
     /// This takes advantage of the Galois-field representation of the LFSR to
@@ -603,6 +697,384 @@ impl __lfsr {
         //
         self.skip(__nonzeros - (bits % __nonzeros))
     }
+
+    /// Jump ahead any number of bits in the pseudo-random data.
+    ///
+    /// Unlike [`skip`](Self::skip), which is limited to the width of the
+    /// LFSR, this can jump any number of bits. The LFSR repeats every
+    /// [`NONZEROS`](Self::NONZEROS) bits, so this only needs the same
+    /// `O(log log n)` multiplications.
+    ///
+    /// This is useful for splitting one LFSR into reproducible, non-overlapping
+    /// substreams, for example one for each thread:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let lfsr = Lfsr64::new(1);
+    /// let substreams = (0..4u128)
+    ///     .map(|i| {
+    ///         let mut lfsr = lfsr.clone();
+    ///         lfsr.jump(i << 80);
+    ///         lfsr
+    ///     })
+    ///     .collect::<Vec<_>>();
+    /// assert_ne!(substreams[0].clone().next(64), substreams[1].clone().next(64));
+    ///
+    /// let mut a = Lfsr16::new(1);
+    /// let mut b = Lfsr16::new(1);
+    /// a.jump(1_000_000);
+    /// b.jump(1_000_000 + 17*u128::from(Lfsr16::NONZEROS));
+    /// assert_eq!(a.next(16), b.next(16));
+    /// ```
+    ///
+    #[inline]
+    pub fn jump(&mut self, bits: u128) {
+        self.skip(__u::try_from(bits % u128::from(Self::NONZEROS)).unwrap())
+    }
+
+    /// Jump backwards any number of bits in the pseudo-random data.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut lfsr = Lfsr16::new(1);
+    /// lfsr.jump(1_000_000);
+    /// lfsr.jump_backwards(1_000_000 - 32);
+    /// assert_eq!(lfsr.next(16), 0x0451);
+    /// assert_eq!(lfsr.next(16), 0xbdad);
+    /// ```
+    ///
+    #[inline]
+    pub fn jump_backwards(&mut self, bits: u128) {
+        self.skip_backwards(__u::try_from(bits % u128::from(Self::NONZEROS)).unwrap())
+    }
+
+    /// Split the LFSR into k interleaved, leap-frogged substreams.
+    ///
+    /// Substream i generates bytes i, i+k, i+2k, ... of this LFSR's
+    /// pseudo-random data, using [`jump`](Self::jump) to skip over the bytes
+    /// of the other substreams. This gives deterministic, non-overlapping
+    /// streams, for example one for each thread, independent of how much
+    /// data each thread consumes.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// use rand::RngCore;
+    ///
+    /// let mut substreams = Lfsr16::new(1).leapfrog(2).collect::<Vec<_>>();
+    /// assert_eq!(substreams[0].next_u32(), 0xbd040000);
+    /// assert_eq!(substreams[1].next_u32(), 0xad512d01);
+    /// ```
+    ///
+    #[inline]
+    pub fn leapfrog(
+        &self,
+        k: usize
+    ) -> impl ExactSizeIterator<Item=__crate::lfsr::LeapFrog<Self>> {
+        __crate::lfsr::LeapFrog::split(self, Self::jump, k)
+    }
+
+    /// Whiten data by xoring it with the pseudo-random data generated by the
+    /// LFSR.
+    ///
+    /// This is useful for breaking up patterns in data, such as long runs of
+    /// zeros or ones written to flash, or for conditioning test patterns.
+    /// Whitening again from the same state restores the original data. The
+    /// pseudo-random data is generated a word at a time, and is the same as
+    /// what [`fill_bytes`](RngCore::fill_bytes) would generate.
+    ///
+    /// **WARNING!** This is not encryption! LFSRs are trivially predictable,
+    /// anyone can recover the LFSR's state from a small amount of whitened
+    /// data if they can guess the original data.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut page = [0u8; 32];
+    /// Lfsr32::new(0x12345678).whiten(&mut page);
+    /// assert!(page.iter().any(|b| *b != 0));
+    /// Lfsr32::new(0x12345678).whiten(&mut page);
+    /// assert_eq!(page, [0u8; 32]);
+    /// ```
+    ///
+    #[inline]
+    pub fn whiten(&mut self, data: &mut [u8]) {
+        // special handling for <8 bit lfsrs since these can't even
+        // fill up a single byte in one go
+        cfg_if! {
+            if #[cfg(__if(__width < 8))] {
+                for chunk in data.chunks_mut(16) {
+                    let mut buf = [0u8; 16];
+                    self.fill_bytes(&mut buf[..chunk.len()]);
+                    for (x, y) in chunk.iter_mut().zip(&buf) {
+                        *x ^= y;
+                    }
+                }
+            } else {
+                // xor words at a time
+                let mut chunks = data.chunks_exact_mut(__width/8);
+                for chunk in &mut chunks {
+                    let word = self.next(8*(__width/8));
+                    cfg_if! {
+                        if #[cfg(__if(__reflected))] {
+                            let word = word.to_le_bytes();
+                            let word = &word[..__width/8];
+                        } else {
+                            let word = word.to_be_bytes();
+                            let word = &word[size_of::<__u>()-(__width/8)..];
+                        }
+                    }
+                    for (x, y) in chunk.iter_mut().zip(word) {
+                        *x ^= y;
+                    }
+                }
+
+                let remainder = chunks.into_remainder();
+                let mut buf = [0u8; size_of::<__u>()];
+                self.fill_bytes(&mut buf[..remainder.len()]);
+                for (x, y) in remainder.iter_mut().zip(&buf) {
+                    *x ^= y;
+                }
+            }
+        }
+    }
+
+    /// Scramble data with an additive, or synchronous, scrambler.
+    ///
+    /// This xors the data with the pseudo-random data generated by the LFSR,
+    /// so the descrambler must start from the same state and stay in sync.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut buf = *b"Hello World!";
+    /// Lfsr16::new(0xffff).scramble(&mut buf);
+    /// assert_ne!(&buf, b"Hello World!");
+    /// Lfsr16::new(0xffff).descramble(&mut buf);
+    /// assert_eq!(&buf, b"Hello World!");
+    /// ```
+    ///
+    #[inline]
+    pub fn scramble(&mut self, data: &mut [u8]) {
+        self.whiten(data)
+    }
+
+    /// Descramble data with an additive, or synchronous, scrambler.
+    ///
+    /// Since this is just an xor, this is the same as
+    /// [`scramble`](Self::scramble).
+    ///
+    #[inline]
+    pub fn descramble(&mut self, data: &mut [u8]) {
+        self.scramble(data)
+    }
+
+    /// Number of bit errors each channel bit error causes when descrambling
+    /// with a multiplicative scrambler, this is the number of non-zero terms
+    /// in the polynomial.
+    ///
+    /// See [`descramble_multiplicative`](Self::descramble_multiplicative).
+    ///
+    pub const ERROR_MULTIPLICATION: u32 = (__polynomial as u128).count_ones();
+
+    /// The register taps of the equivalent Fibonacci LFSR, the bit shifted
+    /// in is the xor of these bits
+    const TAPS: __u = ((__polynomial as __u) & __nonzeros).reverse_bits()
+        >> (8*size_of::<__u>()-__width);
+
+    /// Scramble data with a multiplicative, or self-synchronizing, scrambler.
+    ///
+    /// Each scrambled bit is the data bit xored with the bits tapped by the
+    /// polynomial in the previous `width` scrambled bits, which are kept in
+    /// `state`. Since the descrambler's state comes from the scrambled bits,
+    /// it synchronizes on its own after `width` bits, even if it starts from
+    /// the wrong state or bits are lost.
+    ///
+    /// Unlike the LFSR's state, `state` may be zero. Data bits are processed
+    /// msb first, or lsb first if the LFSR is reflected.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut buf = *b"Hello World!";
+    /// let mut state = 0xffff;
+    /// Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+    /// assert_ne!(&buf, b"Hello World!");
+    ///
+    /// // the descrambler recovers after only 16 bits
+    /// let mut state = 0;
+    /// Lfsr16::descramble_multiplicative(&mut state, &mut buf);
+    /// assert_eq!(&buf[2..], b"llo World!");
+    /// ```
+    ///
+    pub fn scramble_multiplicative(state: &mut __u, data: &mut [u8]) {
+        let mut s = Self::state_from(*state);
+        for byte in data {
+            let mut out = 0;
+            for i in 0..8 {
+                let x = Self::bit_of(*byte, i);
+                let y = x ^ ((s & Self::TAPS).count_ones() & 1) as u8;
+                s = ((s << 1) | __u::from(y)) & __nonzeros;
+                out |= Self::bit_to(y, i);
+            }
+            *byte = out;
+        }
+        *state = Self::state_from(s);
+    }
+
+    /// Descramble data with a multiplicative, or self-synchronizing,
+    /// scrambler.
+    ///
+    /// See [`scramble_multiplicative`](Self::scramble_multiplicative).
+    ///
+    /// Since each descrambled bit depends only on the last `width` received
+    /// bits, the descrambler resynchronizes `width` bits after any
+    /// disturbance, including bit slips where bits are dropped or inserted
+    /// in the channel. The cost is error multiplication, each bit error in
+    /// the channel causes [`ERROR_MULTIPLICATION`](Self::ERROR_MULTIPLICATION)
+    /// bit errors in the descrambled data, one for each term of the
+    /// polynomial:
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut buf = [0u8; 8];
+    /// let mut state = 0x1234;
+    /// Lfsr16::scramble_multiplicative(&mut state, &mut buf);
+    ///
+    /// // flip a single bit in the channel
+    /// buf[2] ^= 0x10;
+    ///
+    /// let mut state = 0x1234;
+    /// Lfsr16::descramble_multiplicative(&mut state, &mut buf);
+    /// let errors = buf.iter().map(|b| b.count_ones()).sum::<u32>();
+    /// assert_eq!(errors, Lfsr16::ERROR_MULTIPLICATION);
+    /// assert_eq!(errors, 5);
+    /// ```
+    ///
+    pub fn descramble_multiplicative(state: &mut __u, data: &mut [u8]) {
+        let mut s = Self::state_from(*state);
+        for byte in data {
+            let mut out = 0;
+            for i in 0..8 {
+                let y = Self::bit_of(*byte, i);
+                let x = y ^ ((s & Self::TAPS).count_ones() & 1) as u8;
+                s = ((s << 1) | __u::from(y)) & __nonzeros;
+                out |= Self::bit_to(x, i);
+            }
+            *byte = out;
+        }
+        *state = Self::state_from(s);
+    }
+
+    /// Compress words into a signature with a multiple-input signature
+    /// register (MISR).
+    ///
+    /// This is the dual of the LFSR generator, commonly used for built-in
+    /// self-test (BIST). Each word clocks the register once, shifting the
+    /// signature and xoring in all `width` bits of the word in parallel, so
+    /// the signature is the inputs, as a polynomial with word coefficients,
+    /// modulo the polynomial:
+    ///
+    /// ``` text
+    /// signature' = (signature*x + input) mod polynomial
+    /// ```
+    ///
+    /// Signatures are linear, so any error pattern that isn't itself
+    /// compressed to zero changes the signature. For random errors, the odds
+    /// of this aliasing are about `1/2^width`.
+    ///
+    /// Unlike the LFSR's state, the signature may be zero. Bits are reversed
+    /// if the LFSR is reflected.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let responses = [0x1234, 0x5678, 0x9abc, 0xdef0];
+    /// let mut signature = 0;
+    /// Lfsr16::misr(&mut signature, &responses);
+    /// assert_eq!(signature, 0x23c8);
+    ///
+    /// // a faulty response changes the signature
+    /// let mut faulty = 0;
+    /// Lfsr16::misr(&mut faulty, &[0x1234, 0x5678, 0x9abd, 0xdef0]);
+    /// assert_ne!(faulty, signature);
+    /// ```
+    ///
+    pub fn misr(signature: &mut __u, inputs: &[__u]) {
+        let mut s = Self::state_from(*signature);
+        for input in inputs {
+            let msb = s >> (__width-1);
+            s = (s << 1) & __nonzeros;
+            if msb != 0 {
+                s ^= (__polynomial as __u) & __nonzeros;
+            }
+            s ^= Self::state_from(*input);
+        }
+        *signature = Self::state_from(s);
+    }
+
+    /// Compress a stream of bits into a signature with a single-input
+    /// signature register (SISR).
+    ///
+    /// This is the same as [`misr`](Self::misr), but clocks the register
+    /// once per bit, shifting in one bit at a time. The signature is the
+    /// data, as a polynomial, modulo the polynomial, similar to a CRC without
+    /// any initial value or final xor.
+    ///
+    /// Bits are processed msb first, or lsb first if the LFSR is reflected.
+    ///
+    /// ``` rust
+    /// # use ::gf256::lfsr::*;
+    /// let mut signature = 0;
+    /// Lfsr16::sisr(&mut signature, b"Hello World!");
+    /// assert_eq!(signature, 0x1fb0);
+    /// ```
+    ///
+    pub fn sisr(signature: &mut __u, data: &[u8]) {
+        let mut s = Self::state_from(*signature);
+        for byte in data {
+            for i in 0..8 {
+                let msb = s >> (__width-1);
+                s = ((s << 1) & __nonzeros) | __u::from(Self::bit_of(*byte, i));
+                if msb != 0 {
+                    s ^= (__polynomial as __u) & __nonzeros;
+                }
+            }
+        }
+        *signature = Self::state_from(s);
+    }
+
+    /// Convert a scrambler state to/from our bit order, the most recent
+    /// bit is in the lsb, or in the msb if the LFSR is reflected
+    #[inline]
+    fn state_from(state: __u) -> __u {
+        cfg_if! {
+            if #[cfg(__if(__reflected))] {
+                (state & __nonzeros).reverse_bits() >> (8*size_of::<__u>()-__width)
+            } else {
+                state & __nonzeros
+            }
+        }
+    }
+
+    /// Get the i-th bit of a byte in output order
+    #[inline]
+    fn bit_of(byte: u8, i: u32) -> u8 {
+        cfg_if! {
+            if #[cfg(__if(__reflected))] {
+                (byte >> i) & 1
+            } else {
+                (byte >> (7-i)) & 1
+            }
+        }
+    }
+
+    /// Place a bit as the i-th bit of a byte in output order
+    #[inline]
+    fn bit_to(bit: u8, i: u32) -> u8 {
+        cfg_if! {
+            if #[cfg(__if(__reflected))] {
+                bit << i
+            } else {
+                bit << (7-i)
+            }
+        }
+    }
 }
 
 
@@ -727,3 +1199,97 @@ impl RngCore for __lfsr {
     }
 }
 
+
+
+// rand_core 0.9 implementation, this just forwards to the above
+
+#[cfg(__if(__rand_core_09))]
+impl __crate::internal::rand_core_09::SeedableRng for __lfsr {
+    type Seed = [u8; size_of::<__u>()];
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::new(__u::from_le_bytes(seed))
+    }
+
+    #[inline]
+    fn from_rng(rng: &mut impl __crate::internal::rand_core_09::RngCore) -> Self {
+        // find the first non-zero seed
+        let mut seed = [0; size_of::<__u>()];
+        loop {
+            rng.fill_bytes(&mut seed);
+            if __u::from_le_bytes(seed) & __nonzeros != 0 {
+                break;
+            }
+        }
+
+        Self::new(__u::from_le_bytes(seed))
+    }
+
+    #[inline]
+    fn try_from_rng<R: __crate::internal::rand_core_09::TryRngCore>(
+        rng: &mut R
+    ) -> Result<Self, R::Error> {
+        // find the first non-zero seed
+        let mut seed = [0; size_of::<__u>()];
+        loop {
+            rng.try_fill_bytes(&mut seed)?;
+            if __u::from_le_bytes(seed) & __nonzeros != 0 {
+                break;
+            }
+        }
+
+        Ok(Self::new(__u::from_le_bytes(seed)))
+    }
+}
+
+#[cfg(__if(__rand_core_09))]
+impl __crate::internal::rand_core_09::RngCore for __lfsr {
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RngCore::fill_bytes(self, dest)
+    }
+
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        RngCore::next_u32(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        RngCore::next_u64(self)
+    }
+}
+
+
+// serde implementation, LFSRs are serialized as their state
+
+#[cfg(__if(__serde))]
+impl __crate::internal::serde::Serialize for __lfsr {
+    fn serialize<S: __crate::internal::serde::Serializer>(
+        &self,
+        serializer: S
+    ) -> Result<S::Ok, S::Error> {
+        self.state().serialize(serializer)
+    }
+}
+
+#[cfg(__if(__serde))]
+impl<'de> __crate::internal::serde::Deserialize<'de> for __lfsr {
+    fn deserialize<D: __crate::internal::serde::Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Self, D::Error> {
+        use __crate::internal::serde::de::Error;
+        use __crate::internal::serde::de::Unexpected;
+
+        let state = __u::deserialize(deserializer)?;
+        if state == 0 || state & __nonzeros != state {
+            return Err(D::Error::invalid_value(
+                Unexpected::Other("out-of-range state"),
+                &"a non-zero LFSR state"
+            ));
+        }
+
+        Ok(Self::new(state))
+    }
+}
@@ -38,6 +38,14 @@ use core::cmp::max;
 use core::fmt;
 
 
+// we reinterpret slices of __u as slices of __gf, so these must match,
+// for example gf2p16 needs u=u16
+const _: () = assert!(
+    core::mem::size_of::<__gf>() == core::mem::size_of::<__u>(),
+    "gf and u must be the same size"
+);
+
+
 /// Error codes for RAID arrays
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Error {
@@ -56,6 +64,254 @@ impl fmt::Display for Error {
 }
 
 
+/// Parity blocks that disagree with the data blocks, see [`verify`]
+///
+/// For each parity block, this records the smallest range of symbols
+/// containing every symbol that disagrees, so only that range needs to be
+/// re-read or logged.
+///
+#[cfg(__if(__parity >= 1))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Mismatch {
+    ranges: [Option<core::ops::Range<usize>>; __parity],
+}
+
+#[cfg(__if(__parity >= 1))]
+impl Mismatch {
+    /// Range of symbols that disagree in parity block `i`, or `None` if
+    /// parity block `i` agrees with the data blocks.
+    pub fn range(&self, i: usize) -> Option<core::ops::Range<usize>> {
+        self.ranges[i].clone()
+    }
+
+    /// Indices of the parity blocks that disagree with the data blocks.
+    pub fn parity(&self) -> impl Iterator<Item=usize> + '_ {
+        self.ranges.iter()
+            .enumerate()
+            .filter(|(_, range)| range.is_some())
+            .map(|(i, _)| i)
+    }
+
+    /// Record a disagreeing symbol in parity block i
+    fn push(&mut self, i: usize, k: usize) {
+        match &mut self.ranges[i] {
+            Some(range) => range.end = k+1,
+            range => *range = Some(k..k+1),
+        }
+    }
+}
+
+#[cfg(__if(__parity >= 1))]
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Parity mismatch in")?;
+        for (n, i) in self.parity().enumerate() {
+            let range = self.ranges[i].as_ref().unwrap();
+            if n > 0 {
+                write!(f, ",")?;
+            }
+            match i {
+                #[cfg(__if(__parity <= 3))] 0 => write!(f, " p")?,
+                #[cfg(__if(__parity <= 3))] 1 => write!(f, " q")?,
+                #[cfg(__if(__parity <= 3))] 2 => write!(f, " r")?,
+                _ => write!(f, " parity {}", i)?,
+            }
+            write!(f, " at {}..{}", range.start, range.end)?;
+        }
+        Ok(())
+    }
+}
+
+/// Add a slice to another slice, f += g
+///
+/// Addition is xor in binary-extension fields, so as long as we avoid bounds
+/// checks this is simple enough for the compiler to vectorize. Note
+/// subtraction is the same as addition, so this also provides f -= g.
+///
+#[cfg(__if(__parity >= 1))]
+#[inline]
+fn slice_add(f: &mut [__gf], g: &[__u]) {
+    debug_assert!(f.len() == g.len());
+    for (x, y) in f.iter_mut().zip(g) {
+        *x += __gf::from_lossy(*y);
+    }
+}
+
+/// Multiply a slice by a scalar and add it to another slice, f += c*g
+///
+/// This is the inner loop of most operations here, so it's kept separate
+/// so it can be accelerated. Note subtraction is the same as addition in
+/// binary-extension fields, so this also provides f -= c*g.
+///
+#[cfg(__if(__parity >= 2))]
+#[inline]
+fn slice_mul_add(f: &mut [__gf], g: &[__u], c: __gf) {
+    debug_assert!(f.len() == g.len());
+
+    // long GF(2^8) slices can use split tables and byte-shuffle
+    // instructions if available
+    if core::mem::size_of::<__gf>() == 1
+        && core::mem::size_of::<__u>() == 1
+        && __gf::NONZEROS == 255
+        && f.len() >= __crate::internal::shuffle::SHUFFLE_THRESHOLD
+        && __crate::internal::shuffle::has_shuffle()
+    {
+        let (lo, hi) = __crate::internal::shuffle::split_tables(|x| {
+            (c * __gf::from_lossy(x)).get() as u8
+        });
+        let f = unsafe { slice::from_raw_parts_mut(f.as_mut_ptr() as *mut u8, f.len()) };
+        let g = unsafe { slice::from_raw_parts(g.as_ptr() as *const u8, g.len()) };
+        __crate::internal::shuffle::mul_add(f, g, &lo, &hi);
+        return;
+    }
+
+    for (x, y) in f.iter_mut().zip(g) {
+        *x += __gf::from_lossy(*y) * c;
+    }
+}
+
+/// Find the locator of a block in an array with `n` data blocks
+///
+/// With more than 3 parity blocks, each block, data or parity, is assigned
+/// a unique power of the generator, its locator. Parity blocks come first,
+/// so their locators don't depend on the number of data blocks.
+///
+#[cfg(__if(__parity > 3))]
+#[inline]
+fn locator(n: usize, j: usize) -> __gf {
+    let j = if j < n { j + __parity } else { j - n };
+    __gf::GENERATOR.pow(__u::try_from(j).unwrap())
+}
+
+/// Get a block, data or parity, by index, without borrowing any other
+/// blocks
+#[cfg(__if(__parity > 3))]
+#[inline]
+unsafe fn block_unchecked<'a, B: AsMut<[__u]> + 'a, P: AsMut<[__u]> + 'a>(
+    blocks: *mut B,
+    n: usize,
+    parity: *mut P,
+    j: usize,
+) -> &'a mut [__u] {
+    if j < n {
+        (*blocks.add(j)).as_mut()
+    } else {
+        (*parity.add(j-n)).as_mut()
+    }
+}
+
+/// Invert the Vandermonde matrix formed by a set of locators
+///
+/// The locators are unique, so the Vandermonde matrix is always
+/// invertible.
+///
+#[cfg(__if(__parity > 3))]
+fn vandermonde_inverse(xs: &[__gf]) -> [[__gf; __parity]; __parity] {
+    let m = xs.len();
+    let mut a = [[__gf::new(0); __parity]; __parity];
+    let mut inv = [[__gf::new(0); __parity]; __parity];
+    for c in 0..m {
+        let mut x = __gf::new(1);
+        for r in 0..m {
+            a[r][c] = x;
+            x *= xs[c];
+        }
+        inv[c][c] = __gf::new(1);
+    }
+
+    // Gauss-Jordan elimination
+    for c in 0..m {
+        let pivot = (c..m).find(|r| a[*r][c] != __gf::new(0)).unwrap();
+        a.swap(c, pivot);
+        inv.swap(c, pivot);
+
+        let s = a[c][c].recip();
+        for k in 0..m {
+            a[c][k] *= s;
+            inv[c][k] *= s;
+        }
+
+        for r in 0..m {
+            let f = a[r][c];
+            if r != c && f != __gf::new(0) {
+                for k in 0..m {
+                    let (ack, invck) = (a[c][k], inv[c][k]);
+                    a[r][k] -= f*ack;
+                    inv[r][k] -= f*invck;
+                }
+            }
+        }
+    }
+
+    inv
+}
+
+/// Find the coefficients that map a data block onto each parity block
+///
+/// Parity blocks are a linear function of the data blocks, so each data
+/// block contributes a constant multiple of itself to each parity block.
+/// This lets us update parity with only the changed data.
+///
+#[cfg(__if(__parity > 3))]
+fn parity_coefficients(j: usize) -> [__gf; __parity] {
+    let mut xs = [__gf::new(0); __parity];
+    for (i, x) in xs.iter_mut().enumerate() {
+        *x = __gf::GENERATOR.pow(__u::try_from(i).unwrap());
+    }
+    let inv = vandermonde_inverse(&xs);
+
+    let x = __gf::GENERATOR.pow(__u::try_from(j+__parity).unwrap());
+    let mut cs = [__gf::new(0); __parity];
+    for (c, row) in cs.iter_mut().zip(&inv) {
+        let mut xr = __gf::new(1);
+        for a in row {
+            *c += *a*xr;
+            xr *= x;
+        }
+    }
+    cs
+}
+
+/// Add the syndromes of a block, `d*x^r`, to each erased block, where `x`
+/// is the block's locator and `r` is the erased block's index
+#[cfg(__if(__parity > 3))]
+unsafe fn syndromes_add(erased: &[*mut __gf], len: usize, b: &[__u], x: __gf) {
+    assert!(b.len() == len);
+    let mut c = __gf::new(1);
+    for e in erased {
+        slice_mul_add(slice::from_raw_parts_mut(*e, len), b, c);
+        c *= x;
+    }
+}
+
+/// Solve for erased blocks, given their locators
+///
+/// Each erased block must already contain the syndromes of the surviving
+/// blocks, `Σ di*xi^r`. Since the syndromes of a whole array are zero,
+/// this is also the syndromes of the erased blocks, so we can find the
+/// erased blocks by multiplying by the inverse of their Vandermonde
+/// matrix.
+///
+#[cfg(__if(__parity > 3))]
+unsafe fn solve(erased: &[*mut __gf], xs: &[__gf], len: usize) {
+    let m = xs.len();
+    let inv = vandermonde_inverse(xs);
+    for i in 0..len {
+        let mut s = [__gf::new(0); __parity];
+        for r in 0..m {
+            s[r] = *erased[r].add(i);
+        }
+
+        for e in 0..m {
+            let mut x = __gf::new(0);
+            for r in 0..m {
+                x += inv[e][r]*s[r];
+            }
+            *erased[e].add(i) = x;
+        }
+    }
+}
+
 /// Format blocks as a RAID array.
 ///
 /// This writes the parity data to the provided parity blocks based on the
@@ -78,6 +334,7 @@ impl fmt::Display for Error {
 /// assert_eq!(&parity3,  b"\x9a\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn format<B: AsRef<[__u]>>(
     blocks: &[B],
     #[cfg(__if(__parity >= 1))] p: &mut [__u],
@@ -105,11 +362,11 @@ pub fn format<B: AsRef<[__u]>>(
     for (j, b) in blocks.iter().enumerate() {
         #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
         #[cfg(__if(__parity >= 3))] let h = g*g;
-        for i in 0..len {
-            #[cfg(__if(__parity >= 1))] { p[i] += __gf::from_lossy(b.as_ref()[i]); }
-            #[cfg(__if(__parity >= 2))] { q[i] += __gf::from_lossy(b.as_ref()[i]) * g; }
-            #[cfg(__if(__parity >= 3))] { r[i] += __gf::from_lossy(b.as_ref()[i]) * h; }
+        #[cfg(__if(__parity >= 1))] {
+            slice_add(p, b.as_ref());
         }
+        #[cfg(__if(__parity >= 2))] { slice_mul_add(q, b.as_ref(), g); }
+        #[cfg(__if(__parity >= 3))] { slice_mul_add(r, b.as_ref(), h); }
     }
 }
 
@@ -132,6 +389,7 @@ pub fn format<B: AsRef<[__u]>>(
 /// assert_eq!(&data, b"Hello World!");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn repair<B: AsMut<[__u]>>(
     blocks: &mut [B],
     #[cfg(__if(__parity >= 1))] p: &mut [__u],
@@ -140,6 +398,10 @@ pub fn repair<B: AsMut<[__u]>>(
     bad_blocks: &[usize]
 ) -> Result<(), Error> {
     let len = blocks[0].as_mut().len();
+    assert!(blocks.iter_mut().all(|b| b.as_mut().len() == len));
+    #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(q) };
     #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(r) };
@@ -173,9 +435,7 @@ pub fn repair<B: AsMut<[__u]>>(
             }
 
             for b in before.iter_mut().chain(after.iter_mut()) {
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]);
-                }
+                slice_add(d, b.as_mut());
             }
 
             bad_blocks = &mut bad_blocks[1..];
@@ -199,9 +459,7 @@ pub fn repair<B: AsMut<[__u]>>(
                 .chain((bad_blocks[0]+1..).zip(after.iter_mut()))
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                slice_mul_add(d, b.as_mut(), g);
             }
 
             let g = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
@@ -239,10 +497,8 @@ pub fn repair<B: AsMut<[__u]>>(
                 .chain((bad_blocks[1]+1..).zip(after.iter_mut()))
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                slice_add(dx, b.as_mut());
+                slice_mul_add(dy, b.as_mut(), g);
             }
 
             // find final dx/dy
@@ -286,9 +542,7 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    d[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                slice_mul_add(d, b.as_mut(), h);
             }
 
             let g = __gf::GENERATOR.pow(__u::try_from(bad_blocks[0]).unwrap());
@@ -328,10 +582,8 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                slice_mul_add(dx, b.as_mut(), g);
+                slice_mul_add(dy, b.as_mut(), h);
             }
 
             // find final dx/dy
@@ -386,10 +638,8 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                slice_add(dx, b.as_mut());
+                slice_mul_add(dy, b.as_mut(), h);
             }
 
             // find final dx/dy
@@ -450,11 +700,9 @@ pub fn repair<B: AsMut<[__u]>>(
             {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g*g;
-                for i in 0..len {
-                    dx[i] -= __gf::from_lossy(b.as_mut()[i]);
-                    dy[i] -= __gf::from_lossy(b.as_mut()[i]) * g;
-                    dz[i] -= __gf::from_lossy(b.as_mut()[i]) * h;
-                }
+                slice_add(dx, b.as_mut());
+                slice_mul_add(dy, b.as_mut(), g);
+                slice_mul_add(dz, b.as_mut(), h);
             }
 
             // find final dx/dy/dz
@@ -496,9 +744,7 @@ pub fn repair<B: AsMut<[__u]>>(
             }
 
             for b in blocks.iter_mut() {
-                for i in 0..len {
-                    p[i] += __gf::from_lossy(b.as_mut()[i]);
-                }
+                slice_add(p, b.as_mut());
             }
         }
     }
@@ -512,9 +758,7 @@ pub fn repair<B: AsMut<[__u]>>(
 
             for (j, b) in blocks.iter_mut().enumerate() {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
-                for i in 0..len {
-                    q[i] += __gf::from_lossy(b.as_mut()[i]) * g;
-                }
+                slice_mul_add(q, b.as_mut(), g);
             }
         }
     }
@@ -529,13 +773,410 @@ pub fn repair<B: AsMut<[__u]>>(
             for (j, b) in blocks.iter_mut().enumerate() {
                 let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
                 let h = g.pow(2);
-                for i in 0..len {
-                    r[i] += __gf::from_lossy(b.as_mut()[i]) * h;
+                slice_mul_add(r, b.as_mut(), h);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verify that the parity blocks agree with the data blocks.
+///
+/// Unlike [`find_and_repair`], this doesn't try to find or repair any bad
+/// blocks. Instead, on failure, the returned [`Mismatch`] reports which
+/// parity blocks disagree, and which range of symbols in each, to support
+/// targeted re-reads and logging.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// raid6::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity1, &mut parity2);
+/// assert_eq!(raid6::verify(&data.chunks(4).collect::<Vec<_>>(), &parity1, &parity2), Ok(()));
+///
+/// // corrupt
+/// parity2[1] = b'x';
+/// parity2[2] = b'x';
+///
+/// let mismatch = raid6::verify(&data.chunks(4).collect::<Vec<_>>(), &parity1, &parity2)
+///     .unwrap_err();
+/// assert_eq!(mismatch.parity().collect::<Vec<_>>(), vec![1]);
+/// assert_eq!(mismatch.range(1), Some(1..3));
+/// assert_eq!(mismatch.to_string(), "Parity mismatch in q at 1..3");
+/// ```
+///
+#[cfg(__if(__parity >= 1 && __parity <= 3))]
+pub fn verify<B: AsRef<[__u]>>(
+    blocks: &[B],
+    #[cfg(__if(__parity >= 1))] p: &[__u],
+    #[cfg(__if(__parity >= 2))] q: &[__u],
+    #[cfg(__if(__parity >= 3))] r: &[__u],
+) -> Result<(), Mismatch> {
+    let len = p.len();
+    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
+
+    let mut mismatch = Mismatch {
+        ranges: core::array::from_fn(|_| None),
+    };
+    for k in 0..len {
+        // find p - Σ di, q - Σ di*g^i, and r - Σ di*h^i
+        let mut deltas = [__gf::new(0); __parity];
+        #[cfg(__if(__parity >= 1))] { deltas[0] = __gf::from_lossy(p[k]); }
+        #[cfg(__if(__parity >= 2))] { deltas[1] = __gf::from_lossy(q[k]); }
+        #[cfg(__if(__parity >= 3))] { deltas[2] = __gf::from_lossy(r[k]); }
+        #[cfg(__if(__parity >= 2))] let mut g = __gf::new(1);
+        for b in blocks {
+            let d = __gf::from_lossy(b.as_ref()[k]);
+            #[cfg(__if(__parity >= 1))] { deltas[0] -= d; }
+            #[cfg(__if(__parity >= 2))] { deltas[1] -= d*g; }
+            #[cfg(__if(__parity >= 3))] { deltas[2] -= d*g*g; }
+            #[cfg(__if(__parity >= 2))] { g *= __gf::GENERATOR; }
+        }
+
+        for (i, delta) in deltas.iter().enumerate() {
+            if *delta != __gf::new(0) {
+                mismatch.push(i, k);
+            }
+        }
+    }
+
+    if mismatch.parity().next().is_some() {
+        Err(mismatch)
+    } else {
+        Ok(())
+    }
+}
+
+/// Verify that the parity blocks agree with the data blocks, with more
+/// than 3 parity blocks.
+///
+/// On failure, the returned [`Mismatch`] reports which parity blocks
+/// disagree, and which range of symbols in each, to support targeted
+/// re-reads and logging.
+///
+#[cfg(__if(__parity > 3))]
+pub fn verify<B: AsRef<[__u]>, P: AsRef<[__u]>>(
+    blocks: &[B],
+    parity: &[P],
+) -> Result<(), Mismatch> {
+    assert!(parity.len() == __parity);
+    assert!(blocks.len() + __parity <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+    let len = parity[0].as_ref().len();
+    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+    assert!(parity.iter().all(|p| p.as_ref().len() == len));
+
+    let mut xs = [__gf::new(0); __parity];
+    for (i, x) in xs.iter_mut().enumerate() {
+        *x = __gf::GENERATOR.pow(__u::try_from(i).unwrap());
+    }
+    let inv = vandermonde_inverse(&xs);
+
+    let mut mismatch = Mismatch {
+        ranges: core::array::from_fn(|_| None),
+    };
+    for k in 0..len {
+        // find Σ di*x^r for each data block's locator x
+        let mut ds = [__gf::new(0); __parity];
+        let mut x = __gf::GENERATOR.pow(__u::try_from(__parity).unwrap());
+        for b in blocks {
+            let d = __gf::from_lossy(b.as_ref()[k]);
+            let mut xr = __gf::new(1);
+            for s in ds.iter_mut() {
+                *s += d*xr;
+                xr *= x;
+            }
+            x *= __gf::GENERATOR;
+        }
+
+        // which determines each parity block
+        for (i, (p, row)) in parity.iter().zip(&inv).enumerate() {
+            let mut expected = __gf::new(0);
+            for (a, s) in row.iter().zip(&ds) {
+                expected += *a * *s;
+            }
+            if expected != __gf::from_lossy(p.as_ref()[k]) {
+                mismatch.push(i, k);
+            }
+        }
+    }
+
+    if mismatch.parity().next().is_some() {
+        Err(mismatch)
+    } else {
+        Ok(())
+    }
+}
+
+/// Find and repair a single silently corrupted block.
+///
+/// Unlike [`repair`], this doesn't need to know which block is bad. With at
+/// least 2 parity blocks, a single corrupted data block `dz` is off by
+/// some error `e` in both p and q, but scaled by `g^z` in q. So we can
+/// find the bad block by solving for `z`:
+///
+/// ``` text
+///      q - Σ di*g^i
+///  z = log_g ------------
+///      p - Σ di
+/// ```
+///
+/// Corruption in the parity blocks themselves only shows up in one parity
+/// block. Note this can only correct one bad block, but the block can be
+/// corrupted in any number of places.
+///
+/// Returns the index of the repaired block, `None` if the array is
+/// consistent, or [`Error::TooManyBadBlocks`] if the array is inconsistent
+/// in a way a single bad block can't explain. On error the blocks are left
+/// unmodified.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// raid6::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity1, &mut parity2);
+///
+/// // silently corrupt
+/// data[5] = b'x';
+///
+/// // find and repair
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// assert_eq!(raid6::find_and_repair(&mut datas, &mut parity1, &mut parity2), Ok(Some(1)));
+/// assert_eq!(&data, b"Hello World!");
+/// ```
+///
+#[cfg(__if(__parity >= 2 && __parity <= 3))]
+pub fn find_and_repair<B: AsMut<[__u]>>(
+    blocks: &mut [B],
+    p: &mut [__u],
+    q: &mut [__u],
+    #[cfg(__if(__parity >= 3))] r: &mut [__u],
+) -> Result<Option<usize>, Error> {
+    let bad = find(
+        blocks,
+        p,
+        q,
+        #[cfg(__if(__parity >= 3))] r,
+    )?;
+
+    // repair the bad block
+    match bad {
+        Some(z) => {
+            repair(
+                blocks,
+                p,
+                q,
+                #[cfg(__if(__parity >= 3))] r,
+                &[z]
+            )?;
+            Ok(Some(z))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Find a single silently corrupted block, see [`find_and_repair`]
+#[cfg(__if(__parity >= 2 && __parity <= 3))]
+fn find<B: AsMut<[__u]>>(
+    blocks: &mut [B],
+    p: &[__u],
+    q: &[__u],
+    #[cfg(__if(__parity >= 3))] r: &[__u],
+) -> Result<Option<usize>, Error> {
+    let n = blocks.len();
+    let len = blocks[0].as_mut().len();
+    assert!(n <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+    assert!(blocks.iter_mut().all(|b| b.as_mut().len() == len));
+    assert!(p.len() == len);
+    assert!(q.len() == len);
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
+
+    // find the bad block, every corrupted symbol must agree
+    let mut bad: Option<(usize, __gf)> = None;
+    for i in 0..len {
+        // find p - Σ di, q - Σ di*g^i, and r - Σ di*h^i
+        let mut pdelta = __gf::from_lossy(p[i]);
+        let mut qdelta = __gf::from_lossy(q[i]);
+        #[cfg(__if(__parity >= 3))] let mut rdelta = __gf::from_lossy(r[i]);
+        #[cfg(__if(__parity < 3))] let rdelta = __gf::new(0);
+        let mut g = __gf::new(1);
+        for b in blocks.iter_mut() {
+            let d = __gf::from_lossy(b.as_mut()[i]);
+            pdelta -= d;
+            qdelta -= d*g;
+            #[cfg(__if(__parity >= 3))] { rdelta -= d*g*g; }
+            g *= __gf::GENERATOR;
+        }
+
+        let zero = __gf::new(0);
+        let loc = if pdelta == zero && qdelta == zero && rdelta == zero {
+            // consistent
+            continue;
+        } else if qdelta == zero && rdelta == zero {
+            // p is bad
+            (n+0, zero)
+        } else if pdelta == zero && rdelta == zero {
+            // q is bad
+            (n+1, zero)
+        } else if pdelta == zero && qdelta == zero {
+            // r is bad
+            (n+2, zero)
+        } else if pdelta == zero || qdelta == zero {
+            return Err(Error::TooManyBadBlocks);
+        } else {
+            // a data block is bad, find z where q' = p'*g^z, checking the
+            // previous bad block first
+            let is_loc = |g: __gf| {
+                #[cfg(__if(__parity >= 3))] {
+                    if rdelta != pdelta*g*g {
+                        return false;
+                    }
+                }
+                qdelta == pdelta*g
+            };
+
+            match bad {
+                Some((z, gz)) if z < n && is_loc(gz) => (z, gz),
+                _ => {
+                    let mut g = __gf::new(1);
+                    let mut loc = None;
+                    for z in 0..n {
+                        if is_loc(g) {
+                            loc = Some((z, g));
+                            break;
+                        }
+                        g *= __gf::GENERATOR;
+                    }
+                    loc.ok_or(Error::TooManyBadBlocks)?
                 }
             }
+        };
+
+        match bad {
+            Some((z, _)) if z != loc.0 => return Err(Error::TooManyBadBlocks),
+            _ => bad = Some(loc),
         }
     }
 
+    Ok(bad.map(|(z, _)| z))
+}
+
+/// Format blocks as a RAID array with more than 3 parity blocks.
+///
+/// This writes the parity data to the provided parity blocks based on the
+/// provided data blocks. `parity` must contain exactly `parity` blocks,
+/// and the total number of blocks is limited to the number of non-zero
+/// elements in the field.
+///
+/// ``` rust,ignore
+/// # use ::gf256::raid::raid;
+/// #[raid(parity=4)]
+/// pub mod raid8 {}
+///
+/// # fn main() {
+/// let mut data = b"Hello World!".to_vec();
+/// let datas = data.chunks(4).collect::<Vec<_>>();
+/// let mut parity = vec![vec![0u8; 4]; 4];
+/// raid8::format(&datas, &mut parity);
+///
+/// // any 4 blocks can be repaired
+/// let mut datas = data.chunks_mut(4).collect::<Vec<_>>();
+/// datas[0].fill(b'x');
+/// datas[2].fill(b'x');
+/// parity[1].fill(b'x');
+/// parity[3].fill(b'x');
+/// raid8::repair(&mut datas, &mut parity, &[0, 2, 4, 6])?;
+/// assert_eq!(&data, b"Hello World!");
+/// # Ok::<(), raid8::Error>(())
+/// # }
+/// ```
+///
+#[cfg(__if(__parity > 3))]
+pub fn format<B: AsRef<[__u]>, P: AsMut<[__u]>>(
+    blocks: &[B],
+    parity: &mut [P],
+) {
+    assert!(blocks.len() >= 1);
+    assert!(parity.len() == __parity);
+    assert!(blocks.len() + __parity <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+
+    let n = blocks.len();
+    let len = blocks[0].as_ref().len();
+    assert!(blocks.iter().all(|b| b.as_ref().len() == len));
+
+    // all parity blocks are erased, and double as scratch space for
+    // syndromes
+    let mut erased = [core::ptr::null_mut(); __parity];
+    let mut xs = [__gf::new(0); __parity];
+    for (j, p) in parity.iter_mut().enumerate() {
+        let p = p.as_mut();
+        assert!(p.len() == len);
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
+        p.fill(__gf::new(0));
+        erased[j] = p.as_mut_ptr();
+        xs[j] = locator(n, n+j);
+    }
+
+    for (j, b) in blocks.iter().enumerate() {
+        unsafe { syndromes_add(&erased, len, b.as_ref(), locator(n, j)) };
+    }
+
+    unsafe { solve(&erased, &xs, len) };
+}
+
+/// Repair up to `parity` bad blocks in a RAID array with more than 3
+/// parity blocks.
+///
+/// This can include the parity blocks themselves, which are indexed after
+/// the data blocks. `bad_blocks` must be an array of unique indices
+/// indicating which blocks are bad.
+///
+/// See [`format`] for an example.
+///
+#[cfg(__if(__parity > 3))]
+pub fn repair<B: AsMut<[__u]>, P: AsMut<[__u]>>(
+    blocks: &mut [B],
+    parity: &mut [P],
+    bad_blocks: &[usize]
+) -> Result<(), Error> {
+    assert!(parity.len() == __parity);
+    if bad_blocks.len() > __parity {
+        // can't repair
+        return Err(Error::TooManyBadBlocks);
+    }
+
+    let n = blocks.len();
+    let len = blocks[0].as_mut().len();
+    assert!(n + __parity <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+    assert!(bad_blocks.iter().all(|j| *j < n+__parity));
+    assert!(bad_blocks.iter().enumerate().all(|(i, j)| !bad_blocks[..i].contains(j)));
+
+    // we need to access multiple blocks at once, erased blocks double as
+    // scratch space for syndromes
+    let blocks = blocks.as_mut_ptr();
+    let parity = parity.as_mut_ptr();
+    let mut erased = [core::ptr::null_mut(); __parity];
+    let mut xs = [__gf::new(0); __parity];
+    for (r, j) in bad_blocks.iter().enumerate() {
+        let b = unsafe { block_unchecked(blocks, n, parity, *j) };
+        assert!(b.len() == len);
+        let b = unsafe { __gf::slice_from_slice_mut_unchecked(b) };
+        b.fill(__gf::new(0));
+        erased[r] = b.as_mut_ptr();
+        xs[r] = locator(n, *j);
+    }
+
+    let m = bad_blocks.len();
+    for j in (0..n+__parity).filter(|j| !bad_blocks.contains(j)) {
+        let b = unsafe { block_unchecked(blocks, n, parity, j) };
+        unsafe { syndromes_add(&erased[..m], len, b, locator(n, j)) };
+    }
+
+    unsafe { solve(&erased[..m], &xs[..m], len) };
     Ok(())
 }
 
@@ -565,6 +1206,7 @@ pub fn repair<B: AsMut<[__u]>>(
 /// assert_eq!(&parity3,  b"\x98\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn add(
     j: usize,
     new: &[__u],
@@ -573,18 +1215,21 @@ pub fn add(
     #[cfg(__if(__parity >= 3))] r: &mut [__u],
 ) {
     let len = new.len();
+    #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(q) };
     #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(r) };
 
     #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
     #[cfg(__if(__parity >= 3))] let h = g*g;
-    for i in 0..len {
-        // calculate new parity
-        #[cfg(__if(__parity >= 1))] { p[i] += __gf::from_lossy(new[i]); }
-        #[cfg(__if(__parity >= 2))] { q[i] += __gf::from_lossy(new[i]) * g; }
-        #[cfg(__if(__parity >= 3))] { r[i] += __gf::from_lossy(new[i]) * h; }
+    // calculate new parity
+    #[cfg(__if(__parity >= 1))] {
+        slice_add(p, new);
     }
+    #[cfg(__if(__parity >= 2))] { slice_mul_add(q, new, g); }
+    #[cfg(__if(__parity >= 3))] { slice_mul_add(r, new, h); }
 }
 
 /// Remove a block from a RAID array.
@@ -610,6 +1255,7 @@ pub fn add(
 /// assert_eq!(&parity3,  b"\xd2\x0e\x4f\x8b");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn remove(
     j: usize,
     old: &[__u],
@@ -618,17 +1264,120 @@ pub fn remove(
     #[cfg(__if(__parity >= 3))] r: &mut [__u],
 ) {
     let len = old.len();
+    #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(q) };
     #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(r) };
 
     #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
     #[cfg(__if(__parity >= 3))] let h = g*g;
-    for i in 0..len {
-        // calculate new parity
-        #[cfg(__if(__parity >= 1))] { p[i] -= __gf::from_lossy(old[i]); }
-        #[cfg(__if(__parity >= 2))] { q[i] -= __gf::from_lossy(old[i]) * g; }
-        #[cfg(__if(__parity >= 3))] { r[i] -= __gf::from_lossy(old[i]) * h; }
+    // calculate new parity
+    #[cfg(__if(__parity >= 1))] {
+        slice_add(p, old);
+    }
+    #[cfg(__if(__parity >= 2))] { slice_mul_add(q, old, g); }
+    #[cfg(__if(__parity >= 3))] { slice_mul_add(r, old, h); }
+}
+
+/// Add a block to a RAID array with more than 3 parity blocks.
+///
+/// Note the block index must be unique in the array, otherwise the array will
+/// become corrupted. This does not update other block indices.
+///
+#[cfg(__if(__parity > 3))]
+pub fn add<P: AsMut<[__u]>>(
+    j: usize,
+    new: &[__u],
+    parity: &mut [P],
+) {
+    assert!(parity.len() == __parity);
+    assert!(j + __parity < usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+
+    // calculate new parity
+    for (p, c) in parity.iter_mut().zip(parity_coefficients(j)) {
+        let p = p.as_mut();
+        assert!(p.len() == new.len());
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
+        slice_mul_add(p, new, c);
+    }
+}
+
+/// Remove a block from a RAID array with more than 3 parity blocks.
+///
+/// Note the block index must already exist in the array, otherwise the
+/// array will become corrupted. This does not update other block indices.
+///
+#[cfg(__if(__parity > 3))]
+pub fn remove<P: AsMut<[__u]>>(
+    j: usize,
+    old: &[__u],
+    parity: &mut [P],
+) {
+    assert!(parity.len() == __parity);
+    assert!(j + __parity < usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+
+    // calculate new parity, note subtraction is the same as addition
+    for (p, c) in parity.iter_mut().zip(parity_coefficients(j)) {
+        let p = p.as_mut();
+        assert!(p.len() == old.len());
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
+        slice_mul_add(p, old, c);
+    }
+}
+
+/// Update a block in a RAID array with more than 3 parity blocks.
+///
+/// This applies only the difference between the old and new block to each
+/// parity block, so small writes don't need to read the rest of the array.
+/// Parity is a bytewise function of the data, so this also works on a
+/// range of a block, as long as the same range of each parity block is
+/// passed.
+///
+/// ``` rust,ignore
+/// # use ::gf256::raid::raid;
+/// #[raid(parity=4)]
+/// pub mod raid8 {}
+///
+/// # fn main() {
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity = vec![vec![0u8; 4]; 4];
+/// raid8::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity);
+///
+/// // update only the first byte of the first block
+/// raid8::update(
+///     0,
+///     &data[0..1],
+///     b"J",
+///     &mut parity.iter_mut().map(|p| &mut p[0..1]).collect::<Vec<_>>()
+/// );
+/// data[0] = b'J';
+///
+/// let mut formatted = vec![vec![0u8; 4]; 4];
+/// raid8::format(&data.chunks(4).collect::<Vec<_>>(), &mut formatted);
+/// assert_eq!(parity, formatted);
+/// # }
+/// ```
+///
+#[cfg(__if(__parity > 3))]
+pub fn update<P: AsMut<[__u]>>(
+    j: usize,
+    old: &[__u],
+    new: &[__u],
+    parity: &mut [P],
+) {
+    assert!(parity.len() == __parity);
+    assert!(new.len() == old.len());
+    assert!(j + __parity < usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+
+    // calculate new parity, p += c*(new-old) = c*new + c*old
+    for (p, c) in parity.iter_mut().zip(parity_coefficients(j)) {
+        let p = p.as_mut();
+        assert!(p.len() == old.len());
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
+        slice_mul_add(p, old, c);
+        slice_mul_add(p, new, c);
     }
 }
 
@@ -655,6 +1404,7 @@ pub fn remove(
 /// assert_eq!(&parity3,  b"\x98\x6b\x23\xe7");
 /// ```
 ///
+#[cfg(__if(__parity <= 3))]
 pub fn update(
     j: usize,
     old: &[__u],
@@ -665,17 +1415,497 @@ pub fn update(
 ) {
     let len = old.len();
     assert!(new.len() == old.len());
+    #[cfg(__if(__parity >= 1))] { assert!(p.len() == len); }
+    #[cfg(__if(__parity >= 2))] { assert!(q.len() == len); }
+    #[cfg(__if(__parity >= 3))] { assert!(r.len() == len); }
     #[cfg(__if(__parity >= 1))] let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
     #[cfg(__if(__parity >= 2))] let q = unsafe { __gf::slice_from_slice_mut_unchecked(q) };
     #[cfg(__if(__parity >= 3))] let r = unsafe { __gf::slice_from_slice_mut_unchecked(r) };
 
     #[cfg(__if(__parity >= 2))] let g = __gf::GENERATOR.pow(__u::try_from(j).unwrap());
     #[cfg(__if(__parity >= 3))] let h = g*g;
-    for i in 0..len {
-        // calculate new parity
-        #[cfg(__if(__parity >= 1))] { p[i] += (__gf::from_lossy(new[i])-__gf::from_lossy(old[i])); }
-        #[cfg(__if(__parity >= 2))] { q[i] += (__gf::from_lossy(new[i])-__gf::from_lossy(old[i])) * g; }
-        #[cfg(__if(__parity >= 3))] { r[i] += (__gf::from_lossy(new[i])-__gf::from_lossy(old[i])) * h; }
+    // calculate new parity, p += new-old, q += (new-old)*g, r += (new-old)*h
+    #[cfg(__if(__parity >= 1))] { slice_add(p, new); slice_add(p, old); }
+    #[cfg(__if(__parity >= 2))] { slice_mul_add(q, new, g); slice_mul_add(q, old, g); }
+    #[cfg(__if(__parity >= 3))] { slice_mul_add(r, new, h); slice_mul_add(r, old, h); }
+}
+
+
+/// Find the range of parity that changes when writing part of a block.
+///
+/// Parity is a symbol-wise function of the data, so writing `new` over
+/// `old` at `offset` in a block only changes the parity symbols where `old`
+/// and `new` differ. This returns the smallest range, relative to the start
+/// of the block, containing every changed symbol, which is empty if nothing
+/// changed.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// assert_eq!(raid6::dirty_range(100, b"Hello World!", b"Hello Earth!"), 106..111);
+/// assert_eq!(raid6::dirty_range(100, b"Hello", b"Hello"), 100..100);
+/// ```
+///
+pub fn dirty_range(
+    offset: usize,
+    old: &[__u],
+    new: &[__u],
+) -> core::ops::Range<usize> {
+    assert!(new.len() == old.len());
+    let start = old.iter().zip(new)
+        .position(|(a, b)| a != b);
+    match start {
+        Some(start) => {
+            let end = old.len() - old.iter().zip(new).rev()
+                .position(|(a, b)| a != b)
+                .unwrap();
+            offset+start .. offset+end
+        }
+        None => offset .. offset,
+    }
+}
+
+/// Update part of a block in a RAID array, a read-modify-write.
+///
+/// `old` and `new` are the old and new contents of block `j` at `offset`,
+/// and the parity blocks are the full parity blocks. Only the parity
+/// symbols that change are updated, see [`dirty_range`], and the range is
+/// returned so only those symbols need to be written back.
+///
+/// ``` rust
+/// # use ::gf256::raid::*;
+/// let mut data = b"Hello World!".to_vec();
+/// let mut parity1 = vec![0u8; 4];
+/// let mut parity2 = vec![0u8; 4];
+/// raid6::format(&data.chunks(4).collect::<Vec<_>>(), &mut parity1, &mut parity2);
+///
+/// // write "ld?" at offset 1 in block 2, only the last byte changes
+/// let range = raid6::update_at(2, 1, &data[9..12], b"ld?", &mut parity1, &mut parity2);
+/// data[9..12].copy_from_slice(b"ld?");
+/// assert_eq!(range, 3..4);
+///
+/// let mut formatted1 = vec![0u8; 4];
+/// let mut formatted2 = vec![0u8; 4];
+/// raid6::format(&data.chunks(4).collect::<Vec<_>>(), &mut formatted1, &mut formatted2);
+/// assert_eq!(parity1, formatted1);
+/// assert_eq!(parity2, formatted2);
+/// ```
+///
+#[cfg(__if(__parity <= 3))]
+pub fn update_at(
+    j: usize,
+    offset: usize,
+    old: &[__u],
+    new: &[__u],
+    #[cfg(__if(__parity >= 1))] p: &mut [__u],
+    #[cfg(__if(__parity >= 2))] q: &mut [__u],
+    #[cfg(__if(__parity >= 3))] r: &mut [__u],
+) -> core::ops::Range<usize> {
+    let range = dirty_range(offset, old, new);
+    let fragment = range.start-offset .. range.end-offset;
+    update(
+        j,
+        &old[fragment.clone()],
+        &new[fragment],
+        #[cfg(__if(__parity >= 1))] &mut p[range.clone()],
+        #[cfg(__if(__parity >= 2))] &mut q[range.clone()],
+        #[cfg(__if(__parity >= 3))] &mut r[range.clone()],
+    );
+    range
+}
+
+/// Update part of a block in a RAID array with more than 3 parity blocks,
+/// a read-modify-write.
+///
+/// `old` and `new` are the old and new contents of block `j` at `offset`,
+/// and `parity` contains the full parity blocks. Only the parity symbols
+/// that change are updated, see [`dirty_range`], and the range is returned
+/// so only those symbols need to be written back.
+///
+#[cfg(__if(__parity > 3))]
+pub fn update_at<P: AsMut<[__u]>>(
+    j: usize,
+    offset: usize,
+    old: &[__u],
+    new: &[__u],
+    parity: &mut [P],
+) -> core::ops::Range<usize> {
+    assert!(parity.len() == __parity);
+    assert!(j + __parity < usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX));
+    let range = dirty_range(offset, old, new);
+    let fragment = range.start-offset .. range.end-offset;
+
+    // calculate new parity, p += c*(new-old) = c*new + c*old
+    for (p, c) in parity.iter_mut().zip(parity_coefficients(j)) {
+        let p = &mut p.as_mut()[range.clone()];
+        let p = unsafe { __gf::slice_from_slice_mut_unchecked(p) };
+        slice_mul_add(p, &old[fragment.clone()], c);
+        slice_mul_add(p, &new[fragment.clone()], c);
+    }
+    range
+}
+
+/// Rebuild failed devices in a RAID array.
+///
+/// Unlike [`repair`], this doesn't require the whole array to be in memory.
+/// Instead, each stripe is read from the devices one block at a time into
+/// `buffers`, passed through [`repair`], and the repaired blocks are written
+/// back to the failed devices. `buffers` must contain one block-sized
+/// buffer for each device, so only one stripe is ever in memory, and no
+/// memory is allocated.
+///
+/// The devices are ordered the same as the blocks passed to [`repair`],
+/// data devices first, followed by the parity devices. `failed` must be an
+/// array of unique indices indicating which devices have failed, these are
+/// never read from. All devices must have the same block size and block
+/// count.
+///
+/// ``` rust
+/// # use gf256::raid::{raid6, BlockDevice};
+/// #
+/// // a simple in-memory device
+/// struct MemDevice(Vec<u8>);
+///
+/// impl BlockDevice for MemDevice {
+///     type Error = ();
+///     fn block_size(&self) -> usize { 4 }
+///     fn block_count(&self) -> u64 { (self.0.len() / 4) as u64 }
+///     fn read_block(&mut self, block: u64, buf: &mut [u8]) -> Result<(), ()> {
+///         let off = 4*block as usize;
+///         buf.copy_from_slice(&self.0[off..off+4]);
+///         Ok(())
+///     }
+///     fn write_block(&mut self, block: u64, buf: &[u8]) -> Result<(), ()> {
+///         let off = 4*block as usize;
+///         self.0[off..off+4].copy_from_slice(buf);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut devices = vec![
+///     MemDevice(b"Hellrld!".to_vec()),
+///     MemDevice(b"o Wo????".to_vec()),
+///     MemDevice(vec![0; 8]),
+///     MemDevice(vec![0; 8]),
+/// ];
+///
+/// // format, one stripe at a time
+/// for i in 0..2 {
+///     let (datas, parity) = devices.split_at_mut(2);
+///     let slices = datas.iter().map(|d| &d.0[4*i..4*i+4]).collect::<Vec<_>>();
+///     let (p, q) = parity.split_at_mut(1);
+///     raid6::format(&slices, &mut p[0].0[4*i..4*i+4], &mut q[0].0[4*i..4*i+4]);
+/// }
+///
+/// // replace two failed devices
+/// devices[0] = MemDevice(vec![0; 8]);
+/// devices[3] = MemDevice(vec![0; 8]);
+///
+/// // rebuild, one stripe at a time
+/// let mut buffers = [[0u8; 4]; 4];
+/// raid6::rebuild(&mut devices, &[0, 3], &mut buffers).unwrap();
+/// assert_eq!(&devices[0].0, b"Hellrld!");
+/// ```
+///
+pub fn rebuild<D: __crate::raid::BlockDevice<__u>, B: AsMut<[__u]>>(
+    devices: &mut [D],
+    failed: &[usize],
+    buffers: &mut [B],
+) -> Result<(), __crate::raid::RebuildError<D::Error>> {
+    assert!(devices.len() > __parity);
+    assert!(failed.iter().all(|i| *i < devices.len()));
+    if failed.len() > __parity {
+        // can't repair
+        return Err(__crate::raid::RebuildError::TooManyBadBlocks);
+    }
+
+    let block_size = devices[0].block_size();
+    let block_count = devices[0].block_count();
+    assert!(devices.iter().all(|d| {
+        d.block_size() == block_size && d.block_count() == block_count
+    }));
+    assert!(buffers.len() == devices.len());
+    assert!(buffers.iter_mut().all(|b| b.as_mut().len() == block_size));
+
+    let n = devices.len() - __parity;
+    for block in 0..block_count {
+        for (i, (d, buf)) in devices.iter_mut()
+            .zip(buffers.iter_mut())
+            .enumerate()
+        {
+            if !failed.contains(&i) {
+                d.read_block(block, buf.as_mut())
+                    .map_err(|e| __crate::raid::RebuildError::Device(i, e))?;
+            }
+        }
+
+        let (datas, parity) = buffers.split_at_mut(n);
+        #[cfg(__if(__parity <= 3))] let res = {
+            #[allow(unused_mut, unused_variables)]
+            let mut parity = parity.iter_mut();
+            repair(
+                datas,
+                #[cfg(__if(__parity >= 1))] parity.next().unwrap().as_mut(),
+                #[cfg(__if(__parity >= 2))] parity.next().unwrap().as_mut(),
+                #[cfg(__if(__parity >= 3))] parity.next().unwrap().as_mut(),
+                failed
+            )
+        };
+        #[cfg(__if(__parity > 3))] let res = repair(datas, parity, failed);
+        res.map_err(|_| __crate::raid::RebuildError::TooManyBadBlocks)?;
+
+        for (i, (d, buf)) in devices.iter_mut()
+            .zip(buffers.iter_mut())
+            .enumerate()
+        {
+            if failed.contains(&i) {
+                d.write_block(block, buf.as_mut())
+                    .map_err(|e| __crate::raid::RebuildError::Device(i, e))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Scrub a RAID array for inconsistent stripes.
+///
+/// This returns an iterator that reads each stripe from the devices into
+/// `buffers`, checks that the parity blocks are consistent with the data
+/// blocks, and yields an [`Inconsistency`](__crate::raid::Inconsistency)
+/// for each stripe that isn't. With 2 or 3 parity blocks, the device
+/// holding a single bad block can also be determined, see
+/// [`find_and_repair`].
+///
+/// The devices are ordered the same as in [`rebuild`], and `buffers` must
+/// contain one block-sized buffer for each device. The iterator only reads
+/// as far as the next inconsistent stripe. To limit the amount of work
+/// done at once, such as in a background task, see [`scrub_range`] and
+/// [`Scrub::position`].
+///
+/// Devices that fail to read yield [`RebuildError::Device`](
+/// __crate::raid::RebuildError::Device), and scrubbing can continue with
+/// the next stripe.
+///
+/// ``` rust
+/// # use gf256::raid::{raid6, BlockDevice, Inconsistency};
+/// #
+/// # struct MemDevice(Vec<u8>);
+/// #
+/// # impl BlockDevice for MemDevice {
+/// #     type Error = ();
+/// #     fn block_size(&self) -> usize { 4 }
+/// #     fn block_count(&self) -> u64 { (self.0.len() / 4) as u64 }
+/// #     fn read_block(&mut self, block: u64, buf: &mut [u8]) -> Result<(), ()> {
+/// #         let off = 4*block as usize;
+/// #         buf.copy_from_slice(&self.0[off..off+4]);
+/// #         Ok(())
+/// #     }
+/// #     fn write_block(&mut self, block: u64, buf: &[u8]) -> Result<(), ()> {
+/// #         let off = 4*block as usize;
+/// #         self.0[off..off+4].copy_from_slice(buf);
+/// #         Ok(())
+/// #     }
+/// # }
+/// #
+/// let mut devices = vec![
+///     MemDevice(b"Hellrld!".to_vec()),
+///     MemDevice(b"o Wo????".to_vec()),
+///     MemDevice(vec![0; 8]),
+///     MemDevice(vec![0; 8]),
+/// ];
+/// # for i in 0..2 {
+/// #     let (datas, parity) = devices.split_at_mut(2);
+/// #     let slices = datas.iter().map(|d| &d.0[4*i..4*i+4]).collect::<Vec<_>>();
+/// #     let (p, q) = parity.split_at_mut(1);
+/// #     raid6::format(&slices, &mut p[0].0[4*i..4*i+4], &mut q[0].0[4*i..4*i+4]);
+/// # }
+///
+/// // silently corrupt
+/// devices[1].0[5] = b'x';
+///
+/// // scrub
+/// let mut buffers = [[0u8; 4]; 4];
+/// let found = raid6::scrub(&mut devices, &mut buffers)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(found, vec![Inconsistency { stripe: 1, bad_block: Some(1) }]);
+/// ```
+///
+#[cfg(__if(__parity >= 1))]
+pub fn scrub<'a, D: __crate::raid::BlockDevice<__u>, B: AsMut<[__u]>>(
+    devices: &'a mut [D],
+    buffers: &'a mut [B],
+) -> Scrub<'a, D, B> {
+    assert!(!devices.is_empty());
+    let block_count = devices[0].block_count();
+    scrub_range(devices, buffers, 0..block_count)
+}
+
+/// Scrub a range of stripes in a RAID array for inconsistent stripes.
+///
+/// This is the same as [`scrub`], but only checks the stripes in `range`.
+/// A background task can scrub a bounded number of stripes at a time,
+/// resuming from where it left off:
+///
+/// ``` rust
+/// # use gf256::raid::{raid6, BlockDevice};
+/// #
+/// # struct MemDevice(Vec<u8>);
+/// #
+/// # impl BlockDevice for MemDevice {
+/// #     type Error = ();
+/// #     fn block_size(&self) -> usize { 4 }
+/// #     fn block_count(&self) -> u64 { (self.0.len() / 4) as u64 }
+/// #     fn read_block(&mut self, block: u64, buf: &mut [u8]) -> Result<(), ()> {
+/// #         let off = 4*block as usize;
+/// #         buf.copy_from_slice(&self.0[off..off+4]);
+/// #         Ok(())
+/// #     }
+/// #     fn write_block(&mut self, block: u64, buf: &[u8]) -> Result<(), ()> {
+/// #         let off = 4*block as usize;
+/// #         self.0[off..off+4].copy_from_slice(buf);
+/// #         Ok(())
+/// #     }
+/// # }
+/// #
+/// # let mut devices = (0..4).map(|_| MemDevice(vec![0; 64])).collect::<Vec<_>>();
+/// let mut buffers = [[0u8; 4]; 4];
+/// let mut position = 0;
+/// while position < devices[0].block_count() {
+///     // scrub at most 4 stripes at a time
+///     let end = (position + 4).min(devices[0].block_count());
+///     let mut scrub = raid6::scrub_range(&mut devices, &mut buffers, position..end);
+///     for inconsistency in &mut scrub {
+///         println!("{:?}", inconsistency);
+///     }
+///     position = scrub.position();
+/// }
+/// ```
+///
+#[cfg(__if(__parity >= 1))]
+pub fn scrub_range<'a, D: __crate::raid::BlockDevice<__u>, B: AsMut<[__u]>>(
+    devices: &'a mut [D],
+    buffers: &'a mut [B],
+    range: core::ops::Range<u64>,
+) -> Scrub<'a, D, B> {
+    assert!(devices.len() > __parity);
+    let block_size = devices[0].block_size();
+    let block_count = devices[0].block_count();
+    assert!(devices.iter().all(|d| {
+        d.block_size() == block_size && d.block_count() == block_count
+    }));
+    assert!(buffers.len() == devices.len());
+    assert!(buffers.iter_mut().all(|b| b.as_mut().len() == block_size));
+    assert!(range.end <= block_count);
+
+    Scrub {
+        devices,
+        buffers,
+        next: range.start,
+        end: max(range.start, range.end),
+    }
+}
+
+/// An iterator over the inconsistent stripes in a RAID array, see
+/// [`scrub`].
+#[cfg(__if(__parity >= 1))]
+#[derive(Debug)]
+pub struct Scrub<'a, D, B> {
+    devices: &'a mut [D],
+    buffers: &'a mut [B],
+    next: u64,
+    end: u64,
+}
+
+#[cfg(__if(__parity >= 1))]
+impl<'a, D, B> Scrub<'a, D, B> {
+    /// Index of the next stripe to check.
+    ///
+    /// After the iterator is exhausted, this is the end of the scrubbed
+    /// range, which can be used to resume scrubbing later.
+    ///
+    pub fn position(&self) -> u64 {
+        self.next
     }
 }
 
+#[cfg(__if(__parity >= 1))]
+impl<'a, D: __crate::raid::BlockDevice<__u>, B: AsMut<[__u]>> Iterator for Scrub<'a, D, B> {
+    type Item = Result<
+        __crate::raid::Inconsistency,
+        __crate::raid::RebuildError<D::Error>
+    >;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.end {
+            let block = self.next;
+            self.next += 1;
+
+            for (i, (d, buf)) in self.devices.iter_mut()
+                .zip(self.buffers.iter_mut())
+                .enumerate()
+            {
+                if let Err(e) = d.read_block(block, buf.as_mut()) {
+                    return Some(Err(__crate::raid::RebuildError::Device(i, e)));
+                }
+            }
+
+            let n = self.devices.len() - __parity;
+            let (datas, parity) = self.buffers.split_at_mut(n);
+
+            // Some(bad_block) if inconsistent
+            #[cfg(__if(__parity >= 2 && __parity <= 3))] let found = {
+                let mut parity = parity.iter_mut();
+                match find(
+                    datas,
+                    parity.next().unwrap().as_mut(),
+                    parity.next().unwrap().as_mut(),
+                    #[cfg(__if(__parity >= 3))] parity.next().unwrap().as_mut(),
+                ) {
+                    Ok(None) => None,
+                    Ok(Some(z)) => Some(Some(z)),
+                    // more than one bad block, can't tell which
+                    Err(_) => Some(None),
+                }
+            };
+            #[cfg(__if(__parity == 1 || __parity > 3))] let found = {
+                // add the data blocks to the parity blocks in place, which
+                // should cancel out, this can only tell us the stripe is
+                // inconsistent, not which block is bad
+                #[cfg(__if(__parity == 1))] {
+                    let p = unsafe {
+                        __gf::slice_from_slice_mut_unchecked(parity[0].as_mut())
+                    };
+                    for d in datas.iter_mut() {
+                        slice_add(p, d.as_mut());
+                    }
+                }
+                #[cfg(__if(__parity > 3))] {
+                    for (j, d) in datas.iter_mut().enumerate() {
+                        for (p, c) in parity.iter_mut().zip(parity_coefficients(j)) {
+                            let p = unsafe {
+                                __gf::slice_from_slice_mut_unchecked(p.as_mut())
+                            };
+                            slice_mul_add(p, d.as_mut(), c);
+                        }
+                    }
+                }
+
+                if parity.iter_mut().all(|p| p.as_mut().iter().all(|x| *x == 0)) {
+                    None
+                } else {
+                    Some(None)
+                }
+            };
+
+            if let Some(bad_block) = found {
+                return Some(Ok(__crate::raid::Inconsistency {
+                    stripe: block,
+                    bad_block,
+                }));
+            }
+        }
+
+        None
+    }
+}
@@ -26,20 +26,26 @@
 
 
 use __crate::traits::TryFrom;
+use __crate::traits::FromLossy;
 use core::slice;
 use core::fmt;
+use core::ops::Deref;
+use core::ops::DerefMut;
 
+#[cfg(__if(__alloc))]
 extern crate alloc;
+#[cfg(__if(__alloc))]
 use alloc::vec::Vec;
+#[cfg(__if(__alloc))]
 use alloc::vec;
-use alloc::borrow::Cow;
 
 
 // Constants for Reed-Solomon error correction
 //
 // Reed-Solomon can correct ECC_SIZE known erasures and ECC_SIZE/2 unknown
 // erasures. DATA_SIZE is arbitrary, however the total size is limited to
-// 255 bytes in a GF(256) field.
+// the number of non-zero elements in our field, 255 bytes in a GF(256)
+// field, 65535 symbols in a GF(2^16) field, etc.
 //
 
 /// Maximum size of the original data in bytes.
@@ -51,6 +57,15 @@ pub const ECC_SIZE:   usize = __ecc_size;
 /// Size of the codeword, [`DATA_SIZE`] + [`ECC_SIZE`], in bytes.
 pub const BLOCK_SIZE: usize = DATA_SIZE + ECC_SIZE;
 
+// Make sure our block size fits in our field
+const _: () = assert!(
+    BLOCK_SIZE as u128 <= __gf::NONZEROS as u128,
+    "block size is limited to the number of non-zero elements in the field"
+);
+
+/// Number of codewords interleaved by the `_interleaved` functions.
+pub const INTERLEAVE: usize = __interleave;
+
 // The generator polynomial in Reed-Solomon is a polynomial with roots (f(x) = 0)
 // at fixed points (g^i) in the finite-field.
 //
@@ -132,6 +147,64 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(__if(__std))]
+impl __crate::internal::std::error::Error for Error {}
+
+
+/// A fixed-capacity buffer
+///
+/// All of our intermediate polynomials are bounded by ECC_SIZE, which we
+/// know at compile time, so we can decode without alloc
+///
+#[derive(Clone)]
+struct Buf<T, const N: usize> {
+    buf: [T; N],
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> Buf<T, N> {
+    /// Create a buffer with len copies of x
+    fn new(x: T, len: usize) -> Self {
+        assert!(len <= N);
+        Self { buf: [x; N], len }
+    }
+
+    /// Push an element onto the end of the buffer, panics if full
+    fn push(&mut self, x: T) {
+        self.buf[self.len] = x;
+        self.len += 1;
+    }
+
+    /// Push a slice onto the end of the buffer, panics if full
+    fn extend_from_slice(&mut self, xs: &[T]) {
+        self.buf[self.len..self.len+xs.len()].copy_from_slice(xs);
+        self.len += xs.len();
+    }
+
+    /// Truncate the buffer to at most len elements
+    fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Remove n elements from the front of the buffer
+    fn drain_front(&mut self, n: usize) {
+        self.buf.copy_within(n..self.len, 0);
+        self.len -= n;
+    }
+}
+
+impl<T, const N: usize> Deref for Buf<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<T, const N: usize> DerefMut for Buf<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.buf[..self.len]
+    }
+}
 
 /// Evaluate a polynomial at x using Horner's method
 ///
@@ -145,6 +218,39 @@ fn poly_eval(f: &[__gf], x: __gf) -> __gf {
     y
 }
 
+/// Multiply a slice by a scalar and add it to another slice, f += c*g
+///
+/// This is the inner loop of encoding, so it's kept separate in case it can
+/// be accelerated
+///
+#[inline]
+fn slice_mul_add(f: &mut [__gf], g: &[__gf], c: __gf) {
+    debug_assert!(f.len() == g.len());
+    if c == __gf::new(0) {
+        return;
+    }
+
+    // long GF(2^8) slices can use split tables and byte-shuffle
+    // instructions if available
+    if core::mem::size_of::<__gf>() == 1
+        && __gf::NONZEROS == 255
+        && f.len() >= __crate::internal::shuffle::SHUFFLE_THRESHOLD
+        && __crate::internal::shuffle::has_shuffle()
+    {
+        let (lo, hi) = __crate::internal::shuffle::split_tables(|x| {
+            (c * __gf::from_lossy(x)).get() as u8
+        });
+        let f = unsafe { slice::from_raw_parts_mut(f.as_mut_ptr() as *mut u8, f.len()) };
+        let g = unsafe { slice::from_raw_parts(g.as_ptr() as *const u8, g.len()) };
+        __crate::internal::shuffle::mul_add(f, g, &lo, &hi);
+        return;
+    }
+
+    for (x, y) in f.iter_mut().zip(g) {
+        *x += c * *y;
+    }
+}
+
 /// Multiply a polynomial by a scalar
 fn poly_scale(f: &mut [__gf], c: __gf) {
     for i in 0..f.len() {
@@ -184,28 +290,6 @@ fn poly_mul(f: &mut [__gf], g: &[__gf]) {
     }
 }
 
-/// Divide polynomials via synthetic division
-///
-/// Note both the quotient and remainder are left in the dividend
-///
-fn poly_divrem(f: &mut [__gf], g: &[__gf]) {
-    debug_assert!(f.len() >= g.len());
-
-    // find leading coeff to normalize g, note you could avoid
-    // this if g is already normalized
-    let leading_coeff = g[0];
-
-    for i in 0 .. (f.len() - g.len() + 1) {
-        if f[i] != __gf::new(0) {
-            f[i] /= leading_coeff;
-
-            for j in 1..g.len() {
-                f[i+j] -= f[i] * g[j];
-            }
-        }
-    }
-}
-
 // Encode using Reed-Solomon error correction
 //
 // Much like in CRC, we want to make the message a multiple of G(x),
@@ -242,23 +326,200 @@ pub fn encode(message: &mut [__u]) {
     assert!(message.len() >= ECC_SIZE);
     let data_len = message.len() - ECC_SIZE;
 
-    // create copy for polynomial division
+    let (data, parity) = message.split_at_mut(data_len);
+    encode_parity(data, parity);
+}
+
+/// Encode a message using Reed-Solomon error-correction, writing the
+/// error-correction information to a separate buffer.
+///
+/// This writes [`ECC_SIZE`] bytes of error-correction information to
+/// `parity`, based on the data provided in `data`. This is equivalent to
+/// [`encode`], except the data and error-correction information don't need
+/// to be contiguous. The data is limited to at most [`DATA_SIZE`] bytes, but
+/// can be smaller.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut parity = [0u8; 32];
+/// rs255w223::encode_parity(b"Hello World!", &mut parity);
+/// assert_eq!(&parity, b"\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+pub fn encode_parity(data: &[__u], parity: &mut [__u]) {
+    assert!(data.len() <= DATA_SIZE);
+    assert!(parity.len() == ECC_SIZE);
+
+    let data = unsafe { __gf::slice_from_slice_unchecked(data) };
+    let parity = unsafe { __gf::slice_from_slice_mut_unchecked(parity) };
+    encode_parity_with(data, parity, &[])
+}
+
+/// Implementation of [`encode_parity`], using a multiplication table for
+/// our generator polynomial if provided
+fn encode_parity_with(data: &[__gf], parity: &mut [__gf], table: &[__gf]) {
+    // no parity? nothing to do
+    if parity.is_empty() {
+        return;
+    }
+
+    // GF(2^8) can use precomputed split tables of our generator polynomial,
+    // unless we're avoiding tables
+    if table.is_empty()
+        && !__no_tables
+        && core::mem::size_of::<__gf>() == 1
+        && __gf::NONZEROS == 255
+    {
+        encode_parity_split(data, parity);
+        return;
+    }
+
+    // divide by our generator polynomial via synthetic division, keeping
+    // only the remainder, note our generator polynomial is monic so we
+    // don't need to normalize
     //
-    // note if message is < DATA_SIZE we just treat it as a smaller polynomial,
+    // note if data is < DATA_SIZE we just treat it as a smaller polynomial,
     // this is equivalent to prepending zeros
     //
-    let mut divrem = message.to_vec();
-    divrem[data_len..].fill(0);
+    parity.fill(__gf::new(0));
+    for &d in data {
+        let q = d + parity[0];
+        parity.copy_within(1.., 0);
+        parity[ECC_SIZE-1] = __gf::new(0);
+        if table.is_empty() {
+            slice_mul_add(parity, &GENERATOR_POLY[1..], -q);
+        } else {
+            let i = usize::try_from(q.get()).unwrap();
+            for (p, t) in parity.iter_mut().zip(&table[i*ECC_SIZE..(i+1)*ECC_SIZE]) {
+                *p += *t;
+            }
+        }
+    }
 
-    // divide by our generator polynomial
-    poly_divrem(
-        unsafe { __gf::slice_from_slice_mut_unchecked(&mut divrem) },
-        &GENERATOR_POLY
-    );
+    // the remainder makes data + parity a polynomial perfectly
+    // divisable by our generator polynomial
+}
+
+/// Number of u64s needed to hold a remainder in [`encode_parity_split`]
+const ENCODE_WORDS: usize = ECC_SIZE.div_ceil(8);
+
+/// Split tables of our generator polynomial for GF(2^8), used by
+/// [`encode_parity_split`]
+///
+/// ENCODE_TABLES[0][x] = x*G(x) and ENCODE_TABLES[1][x] = (x << 4)*G(x),
+/// dropping the leading coefficient and packed big-endian into u64s. These
+/// are empty for other fields.
+///
+const ENCODE_TABLES: [[[u64; ENCODE_WORDS]; 16]; 2] = {
+    let mut tables = [[[0u64; ENCODE_WORDS]; 16]; 2];
+    if core::mem::size_of::<__gf>() == 1 && __gf::NONZEROS == 255 {
+        let mut k = 0;
+        while k < 2 {
+            let mut j = 0;
+            while j < 4 {
+                // multiplying is linear, so we only need to multiply the
+                // single-bit rows, every other row is an xor of these
+                let b = __gf::new((1 as __u) << (4*k+j));
+                let mut row = [0u64; ENCODE_WORDS];
+                let mut i = 0;
+                while i < ECC_SIZE {
+                    let x = GENERATOR_POLY[1+i].naive_mul(b).get() as u64;
+                    row[i/8] |= x << (56 - 8*(i%8));
+                    i += 1;
+                }
+
+                let mut x = 0;
+                while x < (1 << j) {
+                    let mut w = 0;
+                    while w < ENCODE_WORDS {
+                        tables[k][(1 << j) + x][w] = tables[k][x][w] ^ row[w];
+                        w += 1;
+                    }
+                    x += 1;
+                }
+                j += 1;
+            }
+            k += 1;
+        }
+    }
+    tables
+};
+
+/// Implementation of [`encode_parity`] for GF(2^8), using split tables of
+/// our generator polynomial
+///
+/// Multiplying our generator polynomial by a quotient q is linear in q, so
+/// we can split q into nibbles, q*G(x) = lo[q & 0xf] + hi[q >> 4]. Each step
+/// of synthetic division is then only a couple of xors.
+///
+/// To keep the remainder in registers, we pack it big-endian into u64s, so
+/// shifting out the next quotient is a shift of each word.
+///
+fn encode_parity_split(data: &[__gf], parity: &mut [__gf]) {
+    let data = unsafe { slice::from_raw_parts(data.as_ptr() as *const u8, data.len()) };
+    let parity = unsafe { slice::from_raw_parts_mut(parity.as_mut_ptr() as *mut u8, parity.len()) };
+    let [lo, hi] = &ENCODE_TABLES;
+
+    // synthetic division, note subtraction is the same as addition in
+    // GF(2^8)
+    let mut p = [0u64; ENCODE_WORDS];
+    for &d in data {
+        let q = d ^ (p[0] >> 56) as u8;
+        let mut carry = 0;
+        for x in p.iter_mut().rev() {
+            let next = *x >> 56;
+            *x = (*x << 8) | carry;
+            carry = next;
+        }
+
+        let (l, h) = (&lo[usize::from(q & 0xf)], &hi[usize::from(q >> 4)]);
+        for k in 0..ENCODE_WORDS {
+            p[k] ^= l[k] ^ h[k];
+        }
+    }
+
+    for (i, x) in parity.iter_mut().enumerate() {
+        *x = p[i/8].to_be_bytes()[i%8];
+    }
+}
+
+/// Powers of our generator, either computed as needed, or looked up in
+/// tables precomputed by a [`Decoder`]
+///
+/// Tables may be shorter than the codeword for large blocks, in which case
+/// the remaining powers are computed as needed.
+///
+#[derive(Clone, Copy)]
+enum Powers<'a> {
+    Computed,
+    Precomputed {
+        pows: &'a [__gf],
+        recip_pows: &'a [__gf],
+    },
+}
+
+impl Powers<'_> {
+    /// Find g^p
+    #[inline]
+    fn pow(self, p: usize) -> __gf {
+        match self {
+            Powers::Computed => __gf::GENERATOR.pow(__u::try_from(p).unwrap()),
+            Powers::Precomputed { pows, .. } => pows.get(p).copied()
+                .unwrap_or_else(|| Powers::Computed.pow(p)),
+        }
+    }
 
-    // return message + remainder, this new message is a polynomial
-    // perfectly divisable by our generator polynomial
-    message[data_len..].copy_from_slice(&divrem[data_len..]);
+    /// Find g^-p
+    #[inline]
+    fn recip_pow(self, p: usize) -> __gf {
+        match self {
+            Powers::Computed => self.pow(p).recip(),
+            Powers::Precomputed { recip_pows, .. } => recip_pows.get(p).copied()
+                .unwrap_or_else(|| Powers::Computed.recip_pow(p)),
+        }
+    }
 }
 
 /// Find syndromes, which should be zero if there are no errors
@@ -267,12 +528,103 @@ pub fn encode(message: &mut [__u]) {
 /// Si = c'(g^i)
 /// ```
 ///
-fn find_syndromes(f: &[__gf]) -> Vec<__gf> {
-    let mut S = vec![];
-    for i in 0..ECC_SIZE {
-        S.push(
-            poly_eval(f, __gf::GENERATOR.pow(__u::try_from(i).unwrap()))
-        );
+fn find_syndromes(f: &[__gf], powers: Powers) -> [__gf; ECC_SIZE] {
+    // long GF(2^8) codewords can evaluate each syndrome with our slice
+    // kernels
+    if core::mem::size_of::<__gf>() == 1
+        && __gf::NONZEROS == 255
+        && f.len() >= 2*SYNDROME_LANES
+        && __crate::internal::shuffle::has_shuffle()
+    {
+        return find_syndromes_split(f);
+    }
+
+    let mut roots = [__gf::new(0); ECC_SIZE];
+    for (i, r) in roots.iter_mut().enumerate() {
+        *r = powers.pow(i);
+    }
+
+    // evaluate all syndromes with Horner's method in a single pass, this
+    // keeps the inner loop independent of the codeword size
+    let mut S = [__gf::new(0); ECC_SIZE];
+    for c in f {
+        for (s, r) in S.iter_mut().zip(&roots) {
+            *s = *s * *r + c;
+        }
+    }
+    S
+}
+
+/// Number of interleaved polynomials used by [`find_syndromes_split`]
+const SYNDROME_LANES: usize = 32;
+
+/// Split tables for each syndrome's root, g^i, and g^(i*SYNDROME_LANES),
+/// for GF(2^8), used by [`find_syndromes_split`]
+///
+/// These are empty for other fields.
+///
+const SYNDROME_TABLES: [[[u8; 16]; 4]; ECC_SIZE] = {
+    let mut tables = [[[0u8; 16]; 4]; ECC_SIZE];
+    if core::mem::size_of::<__gf>() == 1 && __gf::NONZEROS == 255 {
+        let mut i = 0;
+        while i < ECC_SIZE {
+            let r = __gf::GENERATOR.naive_pow(i as __u);
+            let cs = [r, r.naive_pow(SYNDROME_LANES as __u)];
+            let mut k = 0;
+            while k < 2 {
+                // multiplying is linear, so we only need to multiply the
+                // single-bit elements, every other entry is an xor of these
+                let mut j = 0;
+                while j < 4 {
+                    let l = cs[k].naive_mul(__gf::new((1 as __u) << j)).get() as u8;
+                    let h = cs[k].naive_mul(__gf::new((1 as __u) << (j+4))).get() as u8;
+                    let mut x = 0;
+                    while x < (1 << j) {
+                        tables[i][2*k][(1 << j) + x] = tables[i][2*k][x] ^ l;
+                        tables[i][2*k+1][(1 << j) + x] = tables[i][2*k+1][x] ^ h;
+                        x += 1;
+                    }
+                    j += 1;
+                }
+                k += 1;
+            }
+            i += 1;
+        }
+    }
+    tables
+};
+
+/// Implementation of [`find_syndromes`] for GF(2^8), using our slice kernels
+///
+/// We can split our codeword into interleaved polynomials, one per lane:
+///
+/// ``` text
+///         LANES-1
+/// c'(x) =   Σ   x^(LANES-1-l) c'_l(x^LANES)
+///           l
+/// ```
+///
+/// Since x^LANES is a constant for each syndrome, we can evaluate every
+/// c'_l(x^LANES) at once with Horner's method and our slice kernels, leaving
+/// only LANES table lookups per syndrome to combine the lanes.
+///
+fn find_syndromes_split(f: &[__gf]) -> [__gf; ECC_SIZE] {
+    let f = unsafe { slice::from_raw_parts(f.as_ptr() as *const u8, f.len()) };
+
+    // pad with leading zeros so our lanes end at the end of the codeword
+    let rem = f.len() % SYNDROME_LANES;
+
+    let mut S = [__gf::new(0); ECC_SIZE];
+    for (s, [r_lo, r_hi, lo, hi]) in S.iter_mut().zip(&SYNDROME_TABLES) {
+        let mut lanes = [0u8; SYNDROME_LANES];
+        lanes[SYNDROME_LANES-rem..].copy_from_slice(&f[..rem]);
+        __crate::internal::shuffle::horner(&mut lanes, &f[rem..], lo, hi);
+
+        let mut y = 0u8;
+        for c in lanes {
+            y = r_lo[usize::from(y & 0xf)] ^ r_hi[usize::from(y >> 4)] ^ c;
+        }
+        *s = __gf::from_lossy(y);
     }
     S
 }
@@ -283,18 +635,21 @@ fn find_syndromes(f: &[__gf]) -> Vec<__gf> {
 fn find_forney_syndromes(
     codeword: &[__gf],
     S: &[__gf],
-    erasures: &[usize]
-) -> Vec<__gf> {
-    let mut S = S.to_vec();
+    erasures: &[usize],
+    powers: Powers
+) -> Buf<__gf, ECC_SIZE> {
+    let mut S_ = Buf::new(__gf::new(0), S.len());
+    S_.copy_from_slice(S);
+    let mut S = S_;
     for j in erasures {
-        let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
+        let Xj = powers.pow(codeword.len()-1-j);
         for i in 0 .. S.len()-1 {
             S[i] = S[i+1] - S[i]*Xj;
         }
     }
 
     // trim unnecessary syndromes
-    S.drain(S.len()-erasures.len()..);
+    S.truncate(S.len()-erasures.len());
     S
 }
 
@@ -306,14 +661,18 @@ fn find_forney_syndromes(
 ///        k
 /// ```
 ///
-fn find_erasure_locator(codeword: &[__gf], erasures: &[usize]) -> Vec<__gf> {
-    let mut Λ = vec![__gf::new(0); erasures.len()+1];
+fn find_erasure_locator(
+    codeword: &[__gf],
+    erasures: &[usize],
+    powers: Powers
+) -> Buf<__gf, {ECC_SIZE+1}> {
+    let mut Λ = Buf::new(__gf::new(0), erasures.len()+1);
     let Λ_len = Λ.len();
     Λ[Λ_len-1] = __gf::new(1);
 
     for j in erasures {
         poly_mul(&mut Λ, &[
-            -__gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap()),
+            -powers.pow(codeword.len()-1-j),
             __gf::new(1)
         ]);
     }
@@ -324,9 +683,10 @@ fn find_erasure_locator(codeword: &[__gf], erasures: &[usize]) -> Vec<__gf> {
 /// Iteratively find the error locator polynomial using the
 /// Berlekamp-Massey algorithm when we don't know the location of errors
 ///
-fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
+#[cfg(__if(__berlekamp_massey))]
+fn find_error_locator(S: &[__gf]) -> Buf<__gf, {ECC_SIZE+1}> {
     // the current estimate for the error locator polynomial
-    let mut Λ = vec![__gf::new(0); S.len()+1];
+    let mut Λ = Buf::new(__gf::new(0), S.len()+1);
     let Λ_len = Λ.len();
     Λ[Λ_len-1] = __gf::new(1);
 
@@ -360,29 +720,121 @@ fn find_error_locator(S: &[__gf]) -> Vec<__gf> {
 
     // trim leading zeros
     let zeros = Λ.iter().take_while(|x| **x == __gf::new(0)).count();
-    Λ.drain(0..zeros);
+    Λ.drain_front(zeros);
 
     Λ
 }
 
+/// Find the error locator polynomial using Sugiyama's algorithm, the
+/// extended Euclidean algorithm, when we don't know the location of errors
+///
+/// This solves the key equation:
+///
+/// ``` text
+/// S(x)*Λ(x) = Ω(x) mod x^n
+/// ```
+///
+/// By running the extended Euclidean algorithm on x^n and S(x) until the
+/// remainder, Ω(x), has degree < n/2. The Bézout coefficient of S(x) is
+/// then Λ(x), up to a constant.
+///
+/// Note, unlike the rest of this file, the polynomials here are ordered
+/// smallest-coefficient first, which makes the degrees easier to track.
+///
+#[cfg(__if(__euclidean))]
+fn find_error_locator(S: &[__gf]) -> Buf<__gf, {ECC_SIZE+1}> {
+    fn degree(f: &[__gf]) -> Option<usize> {
+        f.iter().rposition(|x| *x != __gf::new(0))
+    }
+
+    let n = S.len();
+
+    // r_prev = x^n, r = S(x)
+    let mut r_prev: Buf<__gf, {ECC_SIZE+1}> = Buf::new(__gf::new(0), n+1);
+    r_prev[n] = __gf::new(1);
+    let mut r: Buf<__gf, {ECC_SIZE+1}> = Buf::new(__gf::new(0), n+1);
+    r[..n].copy_from_slice(S);
+
+    // t_prev = 0, t = 1
+    let mut t_prev: Buf<__gf, {ECC_SIZE+1}> = Buf::new(__gf::new(0), n+1);
+    let mut t: Buf<__gf, {ECC_SIZE+1}> = Buf::new(__gf::new(0), n+1);
+    t[0] = __gf::new(1);
+
+    while let Some(r_deg) = degree(&r) {
+        if 2*r_deg < n {
+            break;
+        }
+
+        // divide r_prev by r, r_prev becomes the remainder
+        let mut q: Buf<__gf, {ECC_SIZE+1}> = Buf::new(__gf::new(0), n+1);
+        let r_lead_recip = r[r_deg].recip();
+        while let Some(d) = degree(&r_prev) {
+            if d < r_deg {
+                break;
+            }
+
+            let c = r_prev[d] * r_lead_recip;
+            q[d-r_deg] = c;
+            for k in 0..=r_deg {
+                r_prev[d-r_deg+k] -= c * r[k];
+            }
+        }
+        core::mem::swap(&mut r_prev, &mut r);
+
+        // t_prev - q*t, the degree of t can't exceed n
+        for i in 0..=n {
+            if q[i] != __gf::new(0) {
+                for j in 0..=n-i {
+                    t_prev[i+j] -= q[i] * t[j];
+                }
+            }
+        }
+        core::mem::swap(&mut t_prev, &mut t);
+    }
+
+    // normalize so Λ(0) = 1, if Λ(0) = 0 we have too many errors, but
+    // this will be caught when we search for roots
+    if t[0] != __gf::new(0) {
+        let t0_recip = t[0].recip();
+        for x in t.iter_mut() {
+            *x *= t0_recip;
+        }
+    }
+
+    // convert to biggest-coefficient first and trim leading zeros
+    let Λ_len = degree(&t).map(|d| d+1).unwrap_or(1);
+    t.truncate(Λ_len);
+    t.reverse();
+    t
+}
+
 /// Find roots of the error locator polynomial by brute force
 ///
 /// This just means we evaluate Λ(x) for all x locations in our
 /// message, if they equal 0, aka are a root, then we found the
 /// error location in our message.
 ///
-fn find_error_locations(codeword: &[__gf], Λ: &[__gf]) -> Vec<usize> {
-    let mut error_locations = vec![];
+/// Note Λ(x) can't have more roots than its degree, if we find more the
+/// codeword must be longer than our field allows.
+///
+fn find_error_locations(
+    codeword: &[__gf],
+    Λ: &[__gf],
+    powers: Powers
+) -> Result<Buf<usize, ECC_SIZE>, Error> {
+    let mut error_locations = Buf::new(0, 0);
     for j in 0..codeword.len() {
-        let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
-        let zero = poly_eval(&Λ, Xj.recip());
+        let zero = poly_eval(&Λ, powers.recip_pow(codeword.len()-1-j));
         if zero == __gf::new(0) {
             // found an error location!
+            if error_locations.len() >= Λ.len()-1 {
+                return Err(Error::TooManyErrors);
+            }
             error_locations.push(j);
         }
     }
 
-    error_locations
+    Ok(error_locations)
 }
 
 /// Find the error magnitudes using Forney's algorithm
@@ -419,25 +871,26 @@ fn find_error_magnitudes(
     codeword: &[__gf],
     S: &[__gf],
     Λ: &[__gf],
-    error_locations: &[usize]
-) -> Vec<__gf> {
+    error_locations: &[usize],
+    powers: Powers
+) -> Buf<__gf, ECC_SIZE> {
     // find the erasure evaluator polynomial
     //
     // Ω(x) = S(x)*Λ(x) mod x^2v
     //
-    let mut Ω = vec![__gf::new(0); S.len()+Λ.len()-1];
+    let mut Ω = Buf::<_, {2*ECC_SIZE}>::new(__gf::new(0), S.len()+Λ.len()-1);
     let Ω_len = Ω.len();
     Ω[Ω_len-S.len()..].copy_from_slice(&S);
     Ω[Ω_len-S.len()..].reverse();
     poly_mul(&mut Ω, &Λ);
-    Ω.drain(..Ω.len()-S.len());
+    Ω.drain_front(Ω.len()-S.len());
 
     // find the formal derivative of Λ
     //
     // Λ'(x) = Σ i*Λi*x^(i-1)
     //        i=1
     //
-    let mut Λ_prime = vec![__gf::new(0); Λ.len()-1];
+    let mut Λ_prime = Buf::<_, ECC_SIZE>::new(__gf::new(0), Λ.len()-1);
     for i in 1..Λ.len() {
         let mut sum = __gf::new(0);
         for _ in 0..i {
@@ -456,11 +909,12 @@ fn find_error_magnitudes(
     // we need to be careful to avoid a divide-by-zero here, this can happen
     // in some cases (provided with incorrect erasures?)
     //
-    let mut error_magnitudes = vec![];
+    let mut error_magnitudes = Buf::new(__gf::new(0), 0);
     for j in error_locations {
-        let Xj = __gf::GENERATOR.pow(__u::try_from(codeword.len()-1-j).unwrap());
-        let Yj = (-Xj*poly_eval(&Ω, Xj.recip()))
-            .checked_div(poly_eval(&Λ_prime, Xj.recip()))
+        let Xj = powers.pow(codeword.len()-1-j);
+        let Xj_recip = powers.recip_pow(codeword.len()-1-j);
+        let Yj = (-Xj*poly_eval(&Ω, Xj_recip))
+            .checked_div(poly_eval(&Λ_prime, Xj_recip))
             .unwrap_or(__gf::new(0));
         error_magnitudes.push(Yj);
     }
@@ -484,15 +938,47 @@ pub fn is_correct(codeword: &[__u]) -> bool {
     let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
 
     // find syndromes, syndromes of all zero means there are no errors
-    let syndromes = find_syndromes(codeword);
+    let syndromes = find_syndromes(codeword, Powers::Computed);
     syndromes.iter().all(|s| *s == __gf::new(0))
 }
 
+/// Find the syndromes of a codeword, `Si = c'(g^i)`.
+///
+/// All syndromes are zero if, and only if, the codeword has no
+/// errors/erasures. This is the same check [`is_correct`] does, and
+/// doesn't allocate, so it can be used as a cheap check before falling
+/// back to the full decoder. Nonzero syndromes can also be useful for
+/// logging or custom decoders.
+///
+/// ``` rust
+/// # use ::gf256::rs::rs255w223;
+/// # use ::gf256::gf::gf256;
+/// let mut codeword = b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+/// assert!(rs255w223::syndromes(&codeword).iter().all(|s| *s == gf256(0)));
+///
+/// codeword[0] = b'x';
+/// if rs255w223::syndromes(&codeword).iter().any(|s| *s != gf256(0)) {
+///     rs255w223::correct_errors(&mut codeword)?;
+/// }
+/// assert_eq!(&codeword[0..12], b"Hello World!");
+/// # Ok::<(), rs255w223::Error>(())
+/// ```
+///
+pub fn syndromes(codeword: &[__u]) -> [__gf; ECC_SIZE] {
+    let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
+    find_syndromes(codeword, Powers::Computed)
+}
+
 /// Correct up to [`ECC_SIZE`] erasures at known locations.
 ///
 /// Returns the number of erasures, or [`Error::TooManyErrors`] if the codeword
 /// can not be corrected.
 ///
+/// If there may also be errors at unknown locations, see [`correct`], which
+/// can correct both errors and erasures simultaneously.
+///
 /// ``` rust
 /// # use gf256::rs::rs255w223;
 /// let mut codeword = b"xxxxxxxxxxxx\
@@ -511,20 +997,28 @@ pub fn correct_erasures(
     erasures: &[usize]
 ) -> Result<usize, Error> {
     let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    correct_erasures_with(codeword, erasures, Powers::Computed)
+}
 
+/// Implementation of [`correct_erasures`], using the given powers of our generator
+fn correct_erasures_with(
+    codeword: &mut [__gf],
+    erasures: &[usize],
+    powers: Powers
+) -> Result<usize, Error> {
     // too many erasures?
     if erasures.len() > ECC_SIZE {
         return Err(Error::TooManyErrors);
     }
 
     // find syndromes, syndromes of all zero means there are no errors
-    let S = find_syndromes(codeword);
+    let S = find_syndromes(codeword, powers);
     if S.iter().all(|s| *s == __gf::new(0)) {
         return Ok(0);
     }
 
     // find erasure locator polynomial
-    let Λ = find_erasure_locator(codeword, &erasures);
+    let Λ = find_erasure_locator(codeword, &erasures, powers);
 
     // find erasure magnitudes using Forney's algorithm
     let erasure_magnitudes = find_error_magnitudes(
@@ -532,15 +1026,16 @@ pub fn correct_erasures(
         &S,
         &Λ,
         &erasures,
+        powers,
     );
 
     // correct the errors
-    for (&Xj, Yj) in erasures.iter().zip(erasure_magnitudes) {
+    for (&Xj, &Yj) in erasures.iter().zip(erasure_magnitudes.iter()) {
         codeword[Xj] += Yj;
     }
 
     // re-find the syndromes to check if we were able to find all errors
-    let S = find_syndromes(codeword);
+    let S = find_syndromes(codeword, powers);
     if !S.iter().all(|s| *s == __gf::new(0)) {
         return Err(Error::TooManyErrors);
     }
@@ -553,6 +1048,9 @@ pub fn correct_erasures(
 /// Returns the number of errors, or [`Error::TooManyErrors`] if the codeword
 /// can not be corrected.
 ///
+/// If some error locations are known, see [`correct`], which can use these
+/// erasures to correct more errors.
+///
 /// ``` rust
 /// # use gf256::rs::rs255w223;
 /// let mut codeword = b"xexlx xoxlx!\
@@ -567,9 +1065,13 @@ pub fn correct_erasures(
 ///
 pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
     let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    correct_errors_with(codeword, Powers::Computed)
+}
 
+/// Implementation of [`correct_errors`], using the given powers of our generator
+fn correct_errors_with(codeword: &mut [__gf], powers: Powers) -> Result<usize, Error> {
     // find syndromes, syndromes of all zero means there are no errors
-    let S = find_syndromes(codeword);
+    let S = find_syndromes(codeword, powers);
     if S.iter().all(|s| *s == __gf::new(0)) {
         return Ok(0);
     }
@@ -584,7 +1086,7 @@ pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
     }
 
     // find error locations
-    let error_locations = find_error_locations(codeword, &Λ);
+    let error_locations = find_error_locations(codeword, &Λ, powers)?;
 
     // find erasure magnitude using Forney's algorithm
     let error_magnitudes = find_error_magnitudes(
@@ -592,15 +1094,16 @@ pub fn correct_errors(codeword: &mut [__u]) -> Result<usize, Error> {
         &S,
         &Λ,
         &error_locations,
+        powers,
     );
 
     // correct the errors
-    for (&Xj, Yj) in error_locations.iter().zip(error_magnitudes) {
+    for (&Xj, &Yj) in error_locations.iter().zip(error_magnitudes.iter()) {
         codeword[Xj] += Yj;
     }
 
     // re-find the syndromes to check if we were able to find all errors
-    let S = find_syndromes(codeword);
+    let S = find_syndromes(codeword, powers);
     if !S.iter().all(|s| *s == __gf::new(0)) {
         return Err(Error::TooManyErrors);
     }
@@ -635,20 +1138,134 @@ pub fn correct(
     erasures: &[usize]
 ) -> Result<usize, Error> {
     let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    correct_with(codeword, erasures, Powers::Computed)
+}
 
+/// Implementation of [`correct`], using the given powers of our generator
+fn correct_with(
+    codeword: &mut [__gf],
+    erasures: &[usize],
+    powers: Powers
+) -> Result<usize, Error> {
     // too many erasures?
     if erasures.len() > ECC_SIZE {
         return Err(Error::TooManyErrors);
     }
 
+    correct_report_with(codeword, erasures, ECC_SIZE, powers).result()
+}
+
+/// Detailed results of decoding, see [`correct_report`].
+///
+/// This is useful for monitoring, since the number of corrected symbols,
+/// and how close we are to [`ECC_SIZE`], can show storage media or a
+/// channel degrading long before codewords become uncorrectable.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Report {
+    /// Number of errors corrected at unknown locations.
+    pub errors: usize,
+    /// Number of erasures corrected at known locations.
+    pub erasures: usize,
+    /// Number of nonzero syndromes before correction, zero if the
+    /// codeword had no errors/erasures.
+    pub syndromes_before: usize,
+    /// Number of nonzero syndromes after correction, zero if correction
+    /// succeeded.
+    pub syndromes_after: usize,
+}
+
+impl Report {
+    /// Did correction succeed? This is true if the codeword has no
+    /// errors/erasures after correction.
+    pub fn is_ok(&self) -> bool {
+        self.syndromes_after == 0
+    }
+
+    /// Total number of symbols corrected, errors and erasures.
+    pub fn corrected(&self) -> usize {
+        self.errors + self.erasures
+    }
+
+    /// Was erasure information used to correct the codeword?
+    pub fn used_erasures(&self) -> bool {
+        self.erasures > 0
+    }
+
+    /// Convert into the same result returned by [`correct`], the number of
+    /// errors and erasures, or [`Error::TooManyErrors`].
+    pub fn result(&self) -> Result<usize, Error> {
+        if self.is_ok() {
+            Ok(self.corrected())
+        } else {
+            Err(Error::TooManyErrors)
+        }
+    }
+}
+
+/// Correct a mixture of errors and erasures, returning a detailed
+/// [`Report`].
+///
+/// This corrects the same errors/erasures as [`correct`], but also reports
+/// how many errors and erasures were found, and the syndrome status before
+/// and after correction. Note a failed correction is still reported, with
+/// nonzero [`syndromes_after`](Report::syndromes_after).
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xxxxxxxxxxxx\
+///     xxxx\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34x\xa7x\xd6x\xfdx\xc2x\x81x\x8ax\xc9x".to_vec();
+///
+/// let erasures = (0..16).collect::<Vec<_>>();
+/// let report = rs255w223::correct_report(&mut codeword, &erasures);
+/// assert!(report.is_ok());
+/// assert_eq!(report.errors, 8);
+/// assert_eq!(report.erasures, 16);
+/// assert_eq!(report.syndromes_after, 0);
+/// assert_eq!(&codeword[0..12], b"Hello World!");
+/// ```
+///
+pub fn correct_report(
+    codeword: &mut [__u],
+    erasures: &[usize]
+) -> Report {
+    let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    correct_report_with(codeword, erasures, ECC_SIZE, Powers::Computed)
+}
+
+/// Count the nonzero syndromes
+fn syndrome_weight(S: &[__gf]) -> usize {
+    S.iter().filter(|s| **s != __gf::new(0)).count()
+}
+
+/// Implementation of [`correct_report`], accepting at most `max_errors`
+/// errors, and using the given powers of our generator
+fn correct_report_with(
+    codeword: &mut [__gf],
+    erasures: &[usize],
+    max_errors: usize,
+    powers: Powers
+) -> Report {
     // find syndromes, syndromes of all zero means there are no errors
-    let S = find_syndromes(codeword);
-    if S.iter().all(|s| *s == __gf::new(0)) {
-        return Ok(0);
+    let S = find_syndromes(codeword, powers);
+    let mut report = Report {
+        errors: 0,
+        erasures: 0,
+        syndromes_before: syndrome_weight(&S),
+        syndromes_after: syndrome_weight(&S),
+    };
+    if report.syndromes_before == 0 {
+        return report;
+    }
+
+    // too many erasures?
+    if erasures.len() > ECC_SIZE {
+        return report;
     }
 
     // find Forney syndromes, hiding known erasures from the syndromes
-    let forney_S = find_forney_syndromes(codeword, &S, &erasures);
+    let forney_S = find_forney_syndromes(codeword, &S, &erasures, powers);
 
     // find error locator polynomial
     let Λ = find_error_locator(&forney_S);
@@ -656,17 +1273,21 @@ pub fn correct(
     // too many errors/erasures?
     let error_count = Λ.len() - 1;
     let erasure_count = erasures.len();
-    if error_count*2 + erasure_count > ECC_SIZE {
-        return Err(Error::TooManyErrors);
+    if error_count*2 + erasure_count > ECC_SIZE || error_count > max_errors {
+        return report;
     }
 
     // find all error locations
-    let mut error_locations = find_error_locations(codeword, &Λ);
+    let mut error_locations = match find_error_locations(codeword, &Λ, powers) {
+        Ok(error_locations) => error_locations,
+        Err(_) => return report,
+    };
+    let error_count = error_locations.len();
     error_locations.extend_from_slice(&erasures);
 
     // re-find error locator polynomial, this time including both 
     // errors and erasures
-    let Λ = find_erasure_locator(codeword, &error_locations);
+    let Λ = find_erasure_locator(codeword, &error_locations, powers);
 
     // find erasure magnitude using Forney's algorithm
     let error_magnitudes = find_error_magnitudes(
@@ -674,19 +1295,2226 @@ pub fn correct(
         &S,
         &Λ,
         &error_locations,
+        powers,
     );
 
     // correct the errors
-    for (&Xj, Yj) in error_locations.iter().zip(error_magnitudes) {
+    for (&Xj, &Yj) in error_locations.iter().zip(error_magnitudes.iter()) {
         codeword[Xj] += Yj;
     }
 
     // re-find the syndromes to check if we were able to find all errors
-    let S = find_syndromes(codeword);
-    if !S.iter().all(|s| *s == __gf::new(0)) {
+    let S = find_syndromes(codeword, powers);
+    report.syndromes_after = syndrome_weight(&S);
+    if report.syndromes_after == 0 {
+        report.errors = error_count;
+        report.erasures = erasure_count;
+    }
+
+    report
+}
+
+/// Correct a mixture of errors and erasures, accepting at most `max_errors`
+/// errors at unknown locations.
+///
+/// When a codeword is damaged beyond what Reed-Solomon can correct, the
+/// decoder may still find a valid codeword, just not the original one. The
+/// likelihood of these miscorrections grows quickly with the number of
+/// errors we're willing to correct, since each additional error grows the
+/// space of damaged codewords that decode to some codeword. For a
+/// heavily damaged codeword, each error of margin below
+/// [`ECC_SIZE/2`](ECC_SIZE) reduces the chance of a miscorrection by
+/// roughly a factor of `q*e/n`, for a field with `q` elements, `e` errors,
+/// and an `n` symbol codeword.
+///
+/// Corrections with more than `max_errors` errors are rejected with
+/// [`Error::TooManyErrors`], without modifying the codeword. As with
+/// [`correct`], the corrected codeword's syndromes are always re-verified.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12x\xbdx\xd3\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+///
+/// // 16 errors, rejected with a margin of 4 errors
+/// assert!(rs255w223::correct_bounded(&mut codeword, &[], 12).is_err());
+/// assert_eq!(&codeword[0..12], b"xexlx xoxlx!");
+///
+/// // accepted with no margin
+/// assert_eq!(rs255w223::correct_bounded(&mut codeword, &[], 16), Ok(16));
+/// assert_eq!(&codeword[0..12], b"Hello World!");
+/// ```
+///
+pub fn correct_bounded(
+    codeword: &mut [__u],
+    erasures: &[usize],
+    max_errors: usize
+) -> Result<usize, Error> {
+    // too many erasures?
+    if erasures.len() > ECC_SIZE {
         return Err(Error::TooManyErrors);
     }
 
-    Ok(error_locations.len())
+    let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    correct_report_with(codeword, erasures, max_errors, Powers::Computed).result()
 }
 
+
+/// Correct errors using per-symbol reliability scores, treating the least
+/// reliable symbols as erasures.
+///
+/// This is a simple generalized minimum distance (GMD) decoder. Since
+/// Reed-Solomon can correct twice as many erasures as errors, it is often
+/// worth guessing that unreliable symbols, such as symbols received during
+/// a fade, are wrong. We try erasing the 0, 2, 4, ... up to [`ECC_SIZE`]
+/// least reliable symbols, and accept a correction only if it satisfies
+/// Forney's GMD criterion, `Σ r_i*(±1) > len-ECC_SIZE-1`, where symbols
+/// that agree with the correction count as +1 and symbols that disagree
+/// count as -1. At most one codeword can satisfy this, so unlike erasing
+/// symbols blindly, this won't return a miscorrection.
+///
+/// `reliabilities` must be the same length as `codeword`, with each
+/// reliability in the range 0.0 (no idea) to 1.0 (certainly correct).
+/// With all reliabilities 1.0 this corrects the same errors as
+/// [`correct_errors`].
+///
+/// Returns the number of errors and erasures corrected, or
+/// [`Error::TooManyErrors`] if the codeword can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"xxxxxxxxxxxxxxxxxxxx\
+///     \x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+///
+/// // the first 20 symbols were received during a fade
+/// let reliabilities = (0..codeword.len())
+///     .map(|i| if i < 20 { 0.1 } else { 1.0 })
+///     .collect::<Vec<f32>>();
+///
+/// assert_eq!(rs255w223::correct_soft(&mut codeword, &reliabilities), Ok(20));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_soft(
+    codeword: &mut [__u],
+    reliabilities: &[f32]
+) -> Result<usize, Error> {
+    assert_eq!(codeword.len(), reliabilities.len());
+    let reliabilities = reliabilities.iter()
+        .map(|&r| f64::from(r).clamp(0.0, 1.0))
+        .collect::<Vec<_>>();
+    let total = reliabilities.iter().sum::<f64>();
+    let threshold = (codeword.len() - ECC_SIZE.min(codeword.len())) as f64 - 1.0;
+
+    // order symbols from least to most reliable
+    let mut order = (0..codeword.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| reliabilities[a].total_cmp(&reliabilities[b]));
+
+    // try erasing more and more unreliable symbols
+    let mut candidate = Vec::with_capacity(codeword.len());
+    for erasure_count in (0..=ECC_SIZE.min(codeword.len())).step_by(2) {
+        candidate.clear();
+        candidate.extend_from_slice(codeword);
+        let count = match correct(&mut candidate, &order[..erasure_count]) {
+            Ok(count) => count,
+            Err(_) => continue,
+        };
+
+        // check Forney's criterion, this ensures we only ever accept
+        // the one unique codeword
+        let disagree = candidate.iter().zip(codeword.iter()).zip(&reliabilities)
+            .filter(|((a, b), _)| a != b)
+            .map(|(_, &r)| r)
+            .sum::<f64>();
+        if total - 2.0*disagree > threshold {
+            codeword.copy_from_slice(&candidate);
+            return Ok(count);
+        }
+    }
+
+    Err(Error::TooManyErrors)
+}
+
+
+// Reusable encoder/decoder state
+//
+// The above functions find powers of our generator as they need them, which
+// adds up when encoding/decoding many small codewords. Encoder and Decoder
+// instead precompute these once, along with, for small fields, the product
+// of every element with our generator polynomial.
+//
+
+/// A reusable Reed-Solomon encoder.
+///
+/// For fields with at most 256 elements, this precomputes the product of
+/// every element with our generator polynomial, replacing the
+/// multiplications in [`encode`] with table lookups. The exception is
+/// GF(2^8), where [`encode`] already uses faster split tables of our
+/// generator polynomial, unless tables are disabled. The results are the
+/// same as [`encode`] and [`encode_parity`].
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let encoder = rs255w223::Encoder::new();
+///
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+32, 0u8);
+/// encoder.encode(&mut codeword);
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone)]
+pub struct Encoder {
+    // table[q*ECC_SIZE + i] = -q*GENERATOR_POLY[i+1], or empty if our
+    // field is too large or can use encode_parity_split
+    table: Vec<__gf>,
+}
+
+#[cfg(__if(__alloc))]
+impl Encoder {
+    /// Create an encoder, precomputing any tables.
+    pub fn new() -> Self {
+        let mut table = Vec::new();
+        // GF(2^8) is faster with the split tables in encode_parity_split,
+        // which encode_parity_with only uses without a table
+        let split = !__no_tables
+            && core::mem::size_of::<__gf>() == 1
+            && __gf::NONZEROS == 255;
+        if !split && usize::try_from(__gf::NONZEROS).map_or(false, |n| n < 256) {
+            let size = usize::try_from(__gf::NONZEROS).unwrap() + 1;
+            table.reserve(size*ECC_SIZE);
+            for q in 0..size {
+                let q = __gf::try_from(q).unwrap();
+                table.extend(GENERATOR_POLY[1..].iter().map(|g| -q * *g));
+            }
+        }
+
+        Self { table }
+    }
+
+    /// Encode a message using Reed-Solomon error-correction, see [`encode`].
+    pub fn encode(&self, message: &mut [__u]) {
+        assert!(message.len() <= BLOCK_SIZE);
+        assert!(message.len() >= ECC_SIZE);
+        let data_len = message.len() - ECC_SIZE;
+
+        let (data, parity) = message.split_at_mut(data_len);
+        self.encode_parity(data, parity);
+    }
+
+    /// Encode a message using Reed-Solomon error-correction, writing the
+    /// error-correction information to a separate buffer, see
+    /// [`encode_parity`].
+    pub fn encode_parity(&self, data: &[__u], parity: &mut [__u]) {
+        assert!(data.len() <= DATA_SIZE);
+        assert!(parity.len() == ECC_SIZE);
+
+        let data = unsafe { __gf::slice_from_slice_unchecked(data) };
+        let parity = unsafe { __gf::slice_from_slice_mut_unchecked(parity) };
+        encode_parity_with(data, parity, &self.table)
+    }
+}
+
+#[cfg(__if(__alloc))]
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reusable Reed-Solomon decoder.
+///
+/// This precomputes the powers of our generator, and their inverses, used
+/// to find syndromes and error locations/magnitudes, so they don't need to
+/// be found on every call. The results are the same as the equivalent free
+/// functions.
+///
+/// To keep memory bounded for very large blocks, only the first
+/// [`DECODER_TABLE_SIZE`] powers are precomputed, powers for symbols
+/// further from the end of a codeword are found as needed.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let decoder = rs255w223::Decoder::new();
+///
+/// let mut codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12x\xbdx\xd3\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+/// assert!(!decoder.is_correct(&codeword));
+///
+/// assert_eq!(decoder.correct_errors(&mut codeword), Ok(16));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+#[derive(Debug, Clone)]
+pub struct Decoder {
+    // pows[p] = g^p, recip_pows[p] = g^-p, for p < DECODER_TABLE_SIZE
+    pows: Vec<__gf>,
+    recip_pows: Vec<__gf>,
+}
+
+/// Maximum number of powers precomputed by a [`Decoder`].
+#[cfg(__if(__alloc))]
+pub const DECODER_TABLE_SIZE: usize = 65536;
+
+#[cfg(__if(__alloc))]
+impl Decoder {
+    /// Create a decoder, precomputing any tables.
+    pub fn new() -> Self {
+        let size = BLOCK_SIZE.min(DECODER_TABLE_SIZE);
+        let mut pows = Vec::with_capacity(size);
+        let mut recip_pows = Vec::with_capacity(size);
+        let mut x = __gf::new(1);
+        for _ in 0..size {
+            pows.push(x);
+            recip_pows.push(x.recip());
+            x *= __gf::GENERATOR;
+        }
+
+        Self { pows, recip_pows }
+    }
+
+    fn powers(&self) -> Powers<'_> {
+        Powers::Precomputed {
+            pows: &self.pows,
+            recip_pows: &self.recip_pows,
+        }
+    }
+
+    /// Determine if codeword is correct and has no errors/erasures, see
+    /// [`is_correct`].
+    pub fn is_correct(&self, codeword: &[__u]) -> bool {
+        self.syndromes(codeword).iter().all(|s| *s == __gf::new(0))
+    }
+
+    /// Find the syndromes of a codeword, see [`syndromes`].
+    pub fn syndromes(&self, codeword: &[__u]) -> [__gf; ECC_SIZE] {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        let codeword = unsafe { __gf::slice_from_slice_unchecked(codeword) };
+        find_syndromes(codeword, self.powers())
+    }
+
+    /// Correct up to [`ECC_SIZE`] erasures at known locations, see
+    /// [`correct_erasures`].
+    pub fn correct_erasures(
+        &self,
+        codeword: &mut [__u],
+        erasures: &[usize]
+    ) -> Result<usize, Error> {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+        correct_erasures_with(codeword, erasures, self.powers())
+    }
+
+    /// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations,
+    /// see [`correct_errors`].
+    pub fn correct_errors(&self, codeword: &mut [__u]) -> Result<usize, Error> {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+        correct_errors_with(codeword, self.powers())
+    }
+
+    /// Correct a mixture of errors and erasures, up to
+    /// `2*errors+erasures <= ECC_SIZE`, see [`correct`].
+    pub fn correct(
+        &self,
+        codeword: &mut [__u],
+        erasures: &[usize]
+    ) -> Result<usize, Error> {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+        correct_with(codeword, erasures, self.powers())
+    }
+
+    /// Correct a mixture of errors and erasures, returning a detailed
+    /// [`Report`], see [`correct_report`].
+    pub fn correct_report(
+        &self,
+        codeword: &mut [__u],
+        erasures: &[usize]
+    ) -> Report {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+        correct_report_with(codeword, erasures, ECC_SIZE, self.powers())
+    }
+
+    /// Correct a mixture of errors and erasures, accepting at most
+    /// `max_errors` errors at unknown locations, see [`correct_bounded`].
+    pub fn correct_bounded(
+        &self,
+        codeword: &mut [__u],
+        erasures: &[usize],
+        max_errors: usize
+    ) -> Result<usize, Error> {
+        assert!(codeword.len() <= BLOCK_SIZE);
+        if erasures.len() > ECC_SIZE {
+            return Err(Error::TooManyErrors);
+        }
+
+        let codeword = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+        correct_report_with(codeword, erasures, max_errors, self.powers()).result()
+    }
+}
+
+#[cfg(__if(__alloc))]
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+// Generator and parity-check matrices
+//
+// Reed-Solomon is a linear code, so encoding is equivalent to multiplying
+// the message by a generator matrix, G, and the syndromes are equivalent to
+// multiplying the codeword by a parity-check matrix, H. These are mostly
+// useful for external analysis, or other implementations, the functions
+// above never build them.
+//
+
+/// Find the systematic generator matrix for codewords of `n` symbols.
+///
+/// Returns `n-ECC_SIZE` rows of `n` symbols, `G = [I | P]`, where row `i` is
+/// the codeword for a message with a single 1 in position `i`. Encoding is
+/// then `c = m*G`:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// # use ::gf256::rs::rs255w223;
+/// let G = rs255w223::generator_matrix(12+32);
+/// let mut c = [gf256(0); 12+32];
+/// for (m, row) in b"Hello World!".iter().zip(&G) {
+///     for (c, g) in c.iter_mut().zip(row) {
+///         *c += gf256(*m) * *g;
+///     }
+/// }
+///
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+32, 0u8);
+/// rs255w223::encode(&mut codeword);
+/// assert!(c.iter().zip(&codeword).all(|(c, x)| c.get() == *x));
+/// ```
+///
+/// `n` must be in `ECC_SIZE..=BLOCK_SIZE`.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn generator_matrix(n: usize) -> Vec<Vec<__gf>> {
+    assert!(n <= BLOCK_SIZE);
+    assert!(n >= ECC_SIZE);
+    let k = n - ECC_SIZE;
+
+    // the parity of row i is x^(n-1-i) % G(x), so we can find each row from
+    // the row below it with a single step of our division
+    let mut G = vec![vec![__gf::new(0); n]; k];
+    let mut parity = vec![__gf::new(0); ECC_SIZE];
+    for (i, row) in G.iter_mut().enumerate().rev() {
+        if ECC_SIZE > 0 {
+            let q = if i == k-1 { __gf::new(1) } else { parity[0] };
+            parity.copy_within(1.., 0);
+            parity[ECC_SIZE-1] = __gf::new(0);
+            slice_mul_add(&mut parity, &GENERATOR_POLY[1..], -q);
+        }
+        row[i] = __gf::new(1);
+        row[k..].copy_from_slice(&parity);
+    }
+
+    G
+}
+
+/// Find the parity-check matrix for codewords of `n` symbols.
+///
+/// Returns [`ECC_SIZE`] rows of `n` symbols, where `H[i][j] = g^(i*(n-1-j))`,
+/// so `H*c` gives the [`syndromes`] of a codeword `c`, and `G*H^T = 0` for
+/// the [`generator_matrix`] `G`:
+///
+/// ``` rust
+/// # use ::gf256::*;
+/// # use ::gf256::rs::rs255w223;
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+32, 0u8);
+/// rs255w223::encode(&mut codeword);
+/// codeword[0] = b'x';
+///
+/// let H = rs255w223::parity_check_matrix(codeword.len());
+/// let S = H.iter()
+///     .map(|row| row.iter().zip(&codeword).map(|(h, c)| *h * gf256(*c)).sum())
+///     .collect::<Vec<gf256>>();
+/// assert_eq!(S, rs255w223::syndromes(&codeword));
+/// ```
+///
+/// `n` must be at most [`BLOCK_SIZE`].
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn parity_check_matrix(n: usize) -> Vec<Vec<__gf>> {
+    assert!(n <= BLOCK_SIZE);
+
+    let mut H = vec![vec![__gf::new(0); n]; ECC_SIZE];
+    for (i, row) in H.iter_mut().enumerate() {
+        let r = Powers::Computed.pow(i);
+        let mut x = __gf::new(1);
+        for h in row.iter_mut().rev() {
+            *h = x;
+            x *= r;
+        }
+    }
+
+    H
+}
+
+
+// Punctured codes
+//
+// Puncturing drops some of the parity symbols after encoding, trading
+// error-correction for a higher rate. Since we know where the dropped
+// symbols were, the decoder can reinsert them as erasures, each costing
+// one symbol of our error-correction budget.
+//
+
+/// Find which parity symbols are punctured, checking that punctured
+/// positions are in-range and unique
+#[cfg(__if(__alloc))]
+fn puncture_mask(punctured: &[usize]) -> Vec<bool> {
+    let mut mask = vec![false; ECC_SIZE];
+    for &p in punctured {
+        assert!(p < ECC_SIZE, "punctured position out of range");
+        assert!(!mask[p], "punctured position repeated");
+        mask[p] = true;
+    }
+    mask
+}
+
+/// Encode a message using Reed-Solomon error-correction, omitting the
+/// punctured parity symbols.
+///
+/// `punctured` contains the positions, in `0..ECC_SIZE`, of the parity
+/// symbols to drop. This writes the remaining `ECC_SIZE-punctured.len()`
+/// parity symbols to the end of the provided slice, in order, based on the
+/// data provided in the first `message.len()-(ECC_SIZE-punctured.len())`
+/// bytes.
+///
+/// This allows rates between those provided by [`DATA_SIZE`] and
+/// [`ECC_SIZE`], at the cost of being able to correct fewer errors. The
+/// resulting codeword can be corrected with [`correct_punctured`] using
+/// the same punctured positions.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let punctured = (24..32).collect::<Vec<_>>();
+/// let mut codeword = b"Hello World!".to_vec();
+/// codeword.resize(codeword.len()+24, 0u8);
+/// rs255w223::encode_punctured(&mut codeword, &punctured);
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn encode_punctured(message: &mut [__u], punctured: &[usize]) {
+    let mask = puncture_mask(punctured);
+    let kept_size = ECC_SIZE - punctured.len();
+    assert!(message.len() >= kept_size);
+    let data_len = message.len() - kept_size;
+
+    let (data, kept) = message.split_at_mut(data_len);
+    let mut parity = vec![__u::default(); ECC_SIZE];
+    encode_parity(data, &mut parity);
+
+    for (k, (&p, _)) in kept.iter_mut().zip(
+        parity.iter().zip(&mask).filter(|(_, m)| !**m)
+    ) {
+        *k = p;
+    }
+}
+
+/// Correct a mixture of errors and erasures in a punctured codeword, up to
+/// `2*errors+erasures <= ECC_SIZE-punctured.len()`.
+///
+/// `punctured` must be the same parity positions passed to
+/// [`encode_punctured`], these are reinserted and corrected as erasures.
+/// `erasures` are locations in the punctured codeword.
+///
+/// Returns the number of errors and erasures in the punctured codeword, or
+/// [`Error::TooManyErrors`] if the codeword can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let punctured = (24..32).collect::<Vec<_>>();
+/// let mut codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84".to_vec();
+///
+/// assert_eq!(rs255w223::correct_punctured(&mut codeword, &punctured, &[]), Ok(12));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_punctured(
+    codeword: &mut [__u],
+    punctured: &[usize],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    let mask = puncture_mask(punctured);
+    let kept_size = ECC_SIZE - punctured.len();
+    assert!(codeword.len() >= kept_size);
+    let data_len = codeword.len() - kept_size;
+
+    // map positions in our punctured codeword to positions in the full
+    // codeword
+    let positions = (0..data_len)
+        .chain((0..ECC_SIZE).filter(|&p| !mask[p]).map(|p| data_len+p))
+        .collect::<Vec<_>>();
+
+    // reinsert punctured symbols as erasures
+    let mut full = vec![__u::default(); data_len+ECC_SIZE];
+    for (&i, &c) in positions.iter().zip(codeword.iter()) {
+        full[i] = c;
+    }
+    let full_erasures = erasures.iter()
+        .map(|&j| positions[j])
+        .chain(punctured.iter().map(|&p| data_len+p))
+        .collect::<Vec<_>>();
+
+    let count = correct(&mut full, &full_erasures)?;
+
+    for (&i, c) in positions.iter().zip(codeword.iter_mut()) {
+        *c = full[i];
+    }
+
+    // don't count the punctured symbols, note if there were no errors the
+    // punctured symbols may not have been counted
+    Ok(count.saturating_sub(punctured.len()))
+}
+
+
+/// Encode an arbitrary-length message into a stream of codewords.
+///
+/// The message is split into [`DATA_SIZE`] chunks, and each chunk is encoded
+/// into a [`BLOCK_SIZE`] codeword. The last chunk may be smaller, in which
+/// case it is encoded into a shortened codeword of `len+ECC_SIZE` bytes, so
+/// the resulting stream needs no extra framing information.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let message = (0..1000).map(|i| i as u8).collect::<Vec<u8>>();
+/// let mut stream = rs255w223::encode_stream(&message);
+/// assert_eq!(stream.len(), 1000 + 5*32);
+///
+/// // corrupt
+/// for i in (0..stream.len()).step_by(16) {
+///     stream[i] = b'x';
+/// }
+///
+/// // decode
+/// assert_eq!(rs255w223::decode_stream(&stream), Ok(message));
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn encode_stream(message: &[__u]) -> Vec<__u> {
+    let mut stream = Vec::with_capacity(
+        message.len() + message.len().div_ceil(DATA_SIZE)*ECC_SIZE
+    );
+
+    for chunk in message.chunks(DATA_SIZE) {
+        stream.extend_from_slice(chunk);
+        let data_len = stream.len();
+        stream.resize(data_len + ECC_SIZE, 0);
+        encode_parity(chunk, &mut stream[data_len..]);
+    }
+
+    stream
+}
+
+/// Decode a stream of codewords created by [`encode_stream`], correcting up
+/// to [`ECC_SIZE/2`](ECC_SIZE) errors in each codeword.
+///
+/// Returns the original message, or [`Error::TooManyErrors`] if any codeword
+/// can not be corrected.
+///
+/// See [`encode_stream`] for more info.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn decode_stream(stream: &[__u]) -> Result<Vec<__u>, Error> {
+    let mut message = Vec::with_capacity(stream.len());
+    let mut codeword = Vec::with_capacity(BLOCK_SIZE);
+
+    for chunk in stream.chunks(BLOCK_SIZE) {
+        // a truncated codeword can't be from encode_stream
+        if chunk.len() <= ECC_SIZE {
+            return Err(Error::TooManyErrors);
+        }
+
+        codeword.clear();
+        codeword.extend_from_slice(chunk);
+        correct_errors(&mut codeword)?;
+        message.extend_from_slice(&codeword[..codeword.len()-ECC_SIZE]);
+    }
+
+    Ok(message)
+}
+
+
+/// A writer that encodes any data written through it into a stream of
+/// Reed-Solomon codewords.
+///
+/// Data is buffered until a full [`DATA_SIZE`] chunk is available, and
+/// written to the underlying writer as a [`BLOCK_SIZE`] codeword. When done,
+/// [`finish`](RsWriter::finish) writes any remaining data as a shortened
+/// codeword. The resulting stream is the same as [`encode_stream`], and can
+/// be decoded with [`RsReader`] or [`decode_stream`].
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// # use std::io::Write;
+/// let mut writer = rs255w223::RsWriter::new(Vec::new());
+/// writer.write_all(b"Hello World!")?;
+/// let stream = writer.finish()?;
+/// assert_eq!(stream, rs255w223::encode_stream(b"Hello World!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// Note [`flush`](std::io::Write::flush) can't write a partial codeword
+/// without breaking the framing of the stream, so it only flushes full
+/// codewords. If the writer is dropped without calling
+/// [`finish`](RsWriter::finish), the last codeword is written on a best
+/// effort basis, ignoring errors.
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+#[derive(Debug)]
+pub struct RsWriter<W: __crate::internal::std::io::Write> {
+    inner: Option<W>,
+    codeword: Vec<u8>,
+}
+
+#[cfg(__if(__std && __byte_symbols))]
+impl<W: __crate::internal::std::io::Write> RsWriter<W> {
+    /// Create a new writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner: Some(inner),
+            codeword: Vec::with_capacity(BLOCK_SIZE),
+        }
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Note that data written directly to the underlying writer will
+    /// corrupt the stream.
+    ///
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Write any buffered data as a shortened codeword, and return the
+    /// underlying writer.
+    pub fn finish(mut self) -> __crate::internal::std::io::Result<W> {
+        self.write_codeword()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    fn write_codeword(&mut self) -> __crate::internal::std::io::Result<()> {
+        if self.codeword.is_empty() {
+            return Ok(());
+        }
+
+        let data_len = self.codeword.len();
+        self.codeword.resize(data_len + ECC_SIZE, 0);
+        let (data, parity) = self.codeword.split_at_mut(data_len);
+        encode_parity(data, parity);
+        let res = self.inner.as_mut().unwrap().write_all(&self.codeword);
+        self.codeword.clear();
+        res
+    }
+}
+
+#[cfg(__if(__std && __byte_symbols))]
+impl<W: __crate::internal::std::io::Write> __crate::internal::std::io::Write for RsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> __crate::internal::std::io::Result<usize> {
+        let n = core::cmp::min(buf.len(), DATA_SIZE - self.codeword.len());
+        self.codeword.extend_from_slice(&buf[..n]);
+        if self.codeword.len() == DATA_SIZE {
+            self.write_codeword()?;
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> __crate::internal::std::io::Result<()> {
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+#[cfg(__if(__std && __byte_symbols))]
+impl<W: __crate::internal::std::io::Write> Drop for RsWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.write_codeword();
+        }
+    }
+}
+
+/// A reader that decodes a stream of Reed-Solomon codewords, correcting
+/// errors and stripping the error-correction information.
+///
+/// This reads [`BLOCK_SIZE`] codewords from the underlying reader, as
+/// written by [`RsWriter`] or [`encode_stream`], correcting up to
+/// [`ECC_SIZE/2`](ECC_SIZE) errors in each codeword.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// # use std::io::Read;
+/// let mut stream = rs255w223::encode_stream(b"Hello World!");
+/// stream[0..16].fill(b'x');
+///
+/// let mut reader = rs255w223::RsReader::new(&stream[..]);
+/// let mut buf = Vec::new();
+/// reader.read_to_end(&mut buf)?;
+/// assert_eq!(buf, b"Hello World!");
+/// assert_eq!(reader.corrected(), 16);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// If a codeword can't be corrected, reading fails with an error of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData), wrapping an
+/// [`Error::TooManyErrors`].
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+#[derive(Debug)]
+pub struct RsReader<R: __crate::internal::std::io::Read> {
+    inner: R,
+    codeword: Vec<u8>,
+    off: usize,
+    corrected: usize,
+}
+
+#[cfg(__if(__std && __byte_symbols))]
+impl<R: __crate::internal::std::io::Read> RsReader<R> {
+    /// Create a new reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            codeword: Vec::with_capacity(BLOCK_SIZE),
+            off: 0,
+            corrected: 0,
+        }
+    }
+
+    /// Total number of errors corrected so far.
+    pub fn corrected(&self) -> usize {
+        self.corrected
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    ///
+    /// Note that data read directly from the underlying reader will
+    /// break the framing of the stream.
+    ///
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn read_codeword(&mut self) -> __crate::internal::std::io::Result<()> {
+        use __crate::internal::std::io;
+
+        // read up to a full codeword, only a short read at the end
+        // of the stream indicates a shortened codeword
+        self.codeword.resize(BLOCK_SIZE, 0);
+        let mut len = 0;
+        while len < BLOCK_SIZE {
+            match self.inner.read(&mut self.codeword[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.codeword.truncate(len);
+        self.off = 0;
+
+        if len == 0 {
+            return Ok(());
+        }
+
+        // a truncated codeword can't be from RsWriter
+        if len <= ECC_SIZE {
+            self.codeword.clear();
+            return Err(io::Error::new(io::ErrorKind::InvalidData, Error::TooManyErrors));
+        }
+
+        match correct_errors(&mut self.codeword) {
+            Ok(n) => {
+                self.corrected += n;
+                self.codeword.truncate(len - ECC_SIZE);
+                Ok(())
+            }
+            Err(err) => {
+                self.codeword.clear();
+                Err(io::Error::new(io::ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
+
+#[cfg(__if(__std && __byte_symbols))]
+impl<R: __crate::internal::std::io::Read> __crate::internal::std::io::Read for RsReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> __crate::internal::std::io::Result<usize> {
+        if self.off == self.codeword.len() {
+            self.read_codeword()?;
+        }
+
+        let n = core::cmp::min(buf.len(), self.codeword.len() - self.off);
+        buf[..n].copy_from_slice(&self.codeword[self.off..self.off+n]);
+        self.off += n;
+        Ok(n)
+    }
+}
+
+
+/// An async writer that encodes any data written through it into a stream
+/// of Reed-Solomon codewords, without blocking.
+///
+/// This is the async equivalent of [`RsWriter`], implementing tokio's
+/// [`AsyncWrite`](tokio::io::AsyncWrite). Data is buffered until a full
+/// [`DATA_SIZE`] chunk is available, and written to the underlying writer as
+/// a [`BLOCK_SIZE`] codeword. Shutting down the writer writes any remaining
+/// data as a shortened codeword. The resulting stream is the same as
+/// [`encode_stream`], and can be decoded with [`AsyncRsReader`],
+/// [`RsReader`], or [`decode_stream`].
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// # use std::pin::Pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake, Waker};
+/// # use tokio::io::AsyncWrite;
+/// # struct Noop;
+/// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// # let waker = Waker::from(Arc::new(Noop));
+/// # let mut cx = Context::from_waker(&waker);
+/// let mut writer = rs255w223::AsyncRsWriter::new(Vec::new());
+/// let mut data = &b"Hello World!"[..];
+/// while !data.is_empty() {
+///     if let Poll::Ready(n) = Pin::new(&mut writer).poll_write(&mut cx, data) {
+///         data = &data[n?..];
+///     }
+/// }
+/// while Pin::new(&mut writer).poll_shutdown(&mut cx)?.is_pending() {}
+/// assert_eq!(writer.into_inner(), rs255w223::encode_stream(b"Hello World!"));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// In an async context, this is usually driven by tokio's `AsyncWriteExt`,
+/// `writer.write_all(data).await?` and `writer.shutdown().await?`.
+///
+/// Note [`poll_flush`](tokio::io::AsyncWrite::poll_flush) can't write a
+/// partial codeword without breaking the framing of the stream, so it only
+/// flushes full codewords. Dropping the writer without shutting it down
+/// loses any buffered data.
+///
+/// The underlying writer must be [`Unpin`], [`Box::pin`] can be used
+/// otherwise.
+///
+/// This requires feature `async`.
+///
+#[cfg(__if(__async && __byte_symbols))]
+#[derive(Debug)]
+pub struct AsyncRsWriter<W> {
+    inner: W,
+    data: Vec<u8>,
+    codeword: Vec<u8>,
+    off: usize,
+}
+
+#[cfg(__if(__async && __byte_symbols))]
+impl<W> AsyncRsWriter<W> {
+    /// Create a new writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            data: Vec::with_capacity(DATA_SIZE),
+            codeword: Vec::with_capacity(BLOCK_SIZE),
+            off: 0,
+        }
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Note that data written directly to the underlying writer will
+    /// corrupt the stream.
+    ///
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap this writer, returning the underlying writer.
+    ///
+    /// Note any buffered data is lost, shut down the writer first to write
+    /// it.
+    ///
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn encode_codeword(&mut self) {
+        let data_len = self.data.len();
+        self.codeword.clear();
+        self.codeword.extend_from_slice(&self.data);
+        self.codeword.resize(data_len + ECC_SIZE, 0);
+        let (data, parity) = self.codeword.split_at_mut(data_len);
+        encode_parity(data, parity);
+        self.data.clear();
+        self.off = 0;
+    }
+}
+
+#[cfg(__if(__async && __byte_symbols))]
+impl<W: __crate::internal::tokio::io::AsyncWrite + Unpin> AsyncRsWriter<W> {
+    fn poll_write_codeword(
+        &mut self,
+        cx: &mut core::task::Context<'_>
+    ) -> core::task::Poll<__crate::internal::std::io::Result<()>> {
+        use __crate::internal::std::io;
+        use core::pin::Pin;
+        use core::task::Poll;
+
+        while self.off < self.codeword.len() {
+            match Pin::new(&mut self.inner).poll_write(cx, &self.codeword[self.off..]) {
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Poll::Ready(Ok(n)) => self.off += n,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        self.codeword.clear();
+        self.off = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(__if(__async && __byte_symbols))]
+impl<W: __crate::internal::tokio::io::AsyncWrite + Unpin> __crate::internal::tokio::io::AsyncWrite for AsyncRsWriter<W> {
+    fn poll_write(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        buf: &[u8]
+    ) -> core::task::Poll<__crate::internal::std::io::Result<usize>> {
+        use core::task::Poll;
+
+        // finish writing the previous codeword before accepting more data
+        let this = self.get_mut();
+        match this.poll_write_codeword(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        let n = core::cmp::min(buf.len(), DATA_SIZE - this.data.len());
+        this.data.extend_from_slice(&buf[..n]);
+        if this.data.len() == DATA_SIZE {
+            this.encode_codeword();
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>
+    ) -> core::task::Poll<__crate::internal::std::io::Result<()>> {
+        use core::pin::Pin;
+        use core::task::Poll;
+
+        let this = self.get_mut();
+        match this.poll_write_codeword(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.inner).poll_flush(cx),
+            res => res,
+        }
+    }
+
+    fn poll_shutdown(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>
+    ) -> core::task::Poll<__crate::internal::std::io::Result<()>> {
+        use core::pin::Pin;
+        use core::task::Poll;
+
+        let this = self.get_mut();
+        match this.poll_write_codeword(cx) {
+            Poll::Ready(Ok(())) => {}
+            res => return res,
+        }
+
+        // write any remaining data as a shortened codeword
+        if !this.data.is_empty() {
+            this.encode_codeword();
+            match this.poll_write_codeword(cx) {
+                Poll::Ready(Ok(())) => {}
+                res => return res,
+            }
+        }
+
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+/// An async reader that decodes a stream of Reed-Solomon codewords,
+/// correcting errors and stripping the error-correction information,
+/// without blocking.
+///
+/// This is the async equivalent of [`RsReader`], implementing tokio's
+/// [`AsyncRead`](tokio::io::AsyncRead). This reads [`BLOCK_SIZE`]
+/// codewords from the underlying reader, as written by [`AsyncRsWriter`],
+/// [`RsWriter`], or [`encode_stream`], correcting up to
+/// [`ECC_SIZE/2`](ECC_SIZE) errors in each codeword.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// # use std::pin::Pin;
+/// # use std::sync::Arc;
+/// # use std::task::{Context, Poll, Wake, Waker};
+/// # use tokio::io::{AsyncRead, ReadBuf};
+/// # struct Noop;
+/// # impl Wake for Noop { fn wake(self: Arc<Self>) {} }
+/// # let waker = Waker::from(Arc::new(Noop));
+/// # let mut cx = Context::from_waker(&waker);
+/// let mut stream = rs255w223::encode_stream(b"Hello World!");
+/// stream[0..16].fill(b'x');
+///
+/// let mut reader = rs255w223::AsyncRsReader::new(&stream[..]);
+/// let mut buf = [0u8; 64];
+/// let mut buf = ReadBuf::new(&mut buf);
+/// while Pin::new(&mut reader).poll_read(&mut cx, &mut buf)?.is_pending() {}
+/// assert_eq!(buf.filled(), b"Hello World!");
+/// assert_eq!(reader.corrected(), 16);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// In an async context, this is usually driven by tokio's `AsyncReadExt`,
+/// `reader.read_to_end(&mut buf).await?`.
+///
+/// If a codeword can't be corrected, reading fails with an error of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData), wrapping an
+/// [`Error::TooManyErrors`].
+///
+/// The underlying reader must be [`Unpin`], [`Box::pin`] can be used
+/// otherwise.
+///
+/// This requires feature `async`.
+///
+#[cfg(__if(__async && __byte_symbols))]
+#[derive(Debug)]
+pub struct AsyncRsReader<R> {
+    inner: R,
+    codeword: Vec<u8>,
+    len: usize,
+    data: Vec<u8>,
+    off: usize,
+    done: bool,
+    corrected: usize,
+}
+
+#[cfg(__if(__async && __byte_symbols))]
+impl<R> AsyncRsReader<R> {
+    /// Create a new reader.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            codeword: vec![0; BLOCK_SIZE],
+            len: 0,
+            data: Vec::with_capacity(DATA_SIZE),
+            off: 0,
+            done: false,
+            corrected: 0,
+        }
+    }
+
+    /// Total number of errors corrected so far.
+    pub fn corrected(&self) -> usize {
+        self.corrected
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    ///
+    /// Note that data read directly from the underlying reader will
+    /// break the framing of the stream.
+    ///
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap this reader, returning the underlying reader.
+    ///
+    /// Note any buffered data is lost.
+    ///
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn decode_codeword(&mut self) -> __crate::internal::std::io::Result<()> {
+        use __crate::internal::std::io;
+
+        let len = self.len;
+        self.len = 0;
+        self.data.clear();
+        self.off = 0;
+
+        // a truncated codeword can't be from AsyncRsWriter
+        if len <= ECC_SIZE {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, Error::TooManyErrors));
+        }
+
+        match correct_errors(&mut self.codeword[..len]) {
+            Ok(n) => {
+                self.corrected += n;
+                self.data.extend_from_slice(&self.codeword[..len-ECC_SIZE]);
+                Ok(())
+            }
+            Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        }
+    }
+}
+
+#[cfg(__if(__async && __byte_symbols))]
+impl<R: __crate::internal::tokio::io::AsyncRead + Unpin> __crate::internal::tokio::io::AsyncRead for AsyncRsReader<R> {
+    fn poll_read(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        buf: &mut __crate::internal::tokio::io::ReadBuf<'_>
+    ) -> core::task::Poll<__crate::internal::std::io::Result<()>> {
+        use __crate::internal::tokio::io::ReadBuf;
+        use core::pin::Pin;
+        use core::task::Poll;
+
+        let this = self.get_mut();
+        loop {
+            // return any decoded data first
+            if this.off < this.data.len() {
+                let n = core::cmp::min(buf.remaining(), this.data.len() - this.off);
+                buf.put_slice(&this.data[this.off..this.off+n]);
+                this.off += n;
+                return Poll::Ready(Ok(()));
+            }
+
+            if this.done {
+                return Poll::Ready(Ok(()));
+            }
+
+            // read up to a full codeword, only a short read at the end
+            // of the stream indicates a shortened codeword
+            while this.len < BLOCK_SIZE {
+                let mut chunk = ReadBuf::new(&mut this.codeword[this.len..]);
+                match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                    Poll::Ready(Ok(())) => {
+                        let n = chunk.filled().len();
+                        if n == 0 {
+                            this.done = true;
+                            break;
+                        }
+                        this.len += n;
+                    }
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            if this.len > 0 {
+                if let Err(err) = this.decode_codeword() {
+                    return Poll::Ready(Err(err));
+                }
+            }
+        }
+    }
+}
+
+// Interleaving
+//
+// Reed-Solomon corrects a fixed number of bytes per codeword, so a long
+// burst of errors can easily overwhelm a single codeword. By interleaving
+// INTERLEAVE codewords byte-by-byte, a burst of errors gets spread across
+// the codewords, allowing us to correct bursts up to INTERLEAVE times
+// longer.
+//
+// Byte j of codeword i is stored at frame[j*INTERLEAVE + i], so the data
+// remains in the first INTERLEAVE*(len-ECC_SIZE) bytes of the frame, and
+// the error-correction information in the last INTERLEAVE*ECC_SIZE bytes.
+//
+
+/// Extract the i-th codeword from an interleaved frame
+#[cfg(__if(__alloc))]
+fn deinterleave(frame: &[__u], i: usize, codeword: &mut Vec<__u>) {
+    codeword.clear();
+    codeword.extend(frame.iter().skip(i).step_by(INTERLEAVE));
+}
+
+/// Write the i-th codeword back into an interleaved frame
+#[cfg(__if(__alloc))]
+fn reinterleave(frame: &mut [__u], i: usize, codeword: &[__u]) {
+    for (x, y) in frame.iter_mut().skip(i).step_by(INTERLEAVE).zip(codeword) {
+        *x = *y;
+    }
+}
+
+/// Call f on each codeword in an interleaved frame, collecting the
+/// number of errors
+///
+/// The codewords are deinterleaved into a scratch buffer, and only written
+/// back if f succeeds on all of them, so the frame is left unmodified on
+/// error.
+///
+/// Note INTERLEAVE may be 1, hence allowing clippy::modulo_one here and
+/// below.
+///
+#[cfg(__if(__alloc))]
+#[allow(clippy::modulo_one)]
+fn map_interleaved(
+    frame: &mut [__u],
+    mut f: impl FnMut(usize, &mut [__u]) -> Result<usize, Error>
+) -> Result<usize, Error> {
+    assert!(frame.len() % INTERLEAVE == 0);
+
+    let len = frame.len() / INTERLEAVE;
+    let mut codewords = Vec::with_capacity(frame.len());
+    for i in 0..INTERLEAVE {
+        codewords.extend(frame.iter().skip(i).step_by(INTERLEAVE));
+    }
+
+    let mut count = 0;
+    for i in 0..INTERLEAVE {
+        count += f(i, &mut codewords[i*len..(i+1)*len])?;
+    }
+
+    for i in 0..INTERLEAVE {
+        reinterleave(frame, i, &codewords[i*len..(i+1)*len]);
+    }
+
+    Ok(count)
+}
+
+/// Find the erasures in the i-th codeword of an interleaved frame
+#[cfg(__if(__alloc))]
+#[allow(clippy::modulo_one)]
+fn deinterleave_erasures(erasures: &[usize], i: usize) -> Vec<usize> {
+    erasures.iter()
+        .filter(|&&e| e >= i && (e-i) % INTERLEAVE == 0)
+        .map(|&e| e / INTERLEAVE)
+        .collect()
+}
+
+/// Encode [`INTERLEAVE`] interleaved codewords using Reed-Solomon
+/// error-correction.
+///
+/// This writes [`INTERLEAVE`]\*[`ECC_SIZE`] bytes of error-correction
+/// information to the end of the provided slice, based on the data provided
+/// in the first `frame.len()-INTERLEAVE*ECC_SIZE` bytes. The frame must be a
+/// multiple of [`INTERLEAVE`] bytes, and is limited to at most
+/// [`INTERLEAVE`]\*[`BLOCK_SIZE`] bytes.
+///
+/// ``` rust,ignore
+/// # use ::gf256::*;
+/// # use ::gf256::rs::rs;
+/// #[rs(block=26, data=16, interleave=4)]
+/// mod rs26w16i4 {}
+///
+/// let mut frame = (0..4*26).collect::<Vec<u8>>();
+/// rs26w16i4::encode_interleaved(&mut frame);
+///
+/// // a burst of 4*5 errors is spread across 4 codewords
+/// frame[10..30].fill(b'x');
+/// assert_eq!(rs26w16i4::correct_errors_interleaved(&mut frame), Ok(20));
+/// assert_eq!(&frame[..4*16], &(0..4*16).collect::<Vec<u8>>());
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn encode_interleaved(frame: &mut [__u]) {
+    assert!(frame.len() <= INTERLEAVE*BLOCK_SIZE);
+    map_interleaved(frame, |_, codeword| {
+        encode(codeword);
+        Ok(0)
+    }).unwrap();
+}
+
+/// Determine if all [`INTERLEAVE`] interleaved codewords are valid
+/// Reed-Solomon codewords.
+///
+/// See [`is_correct`] and [`encode_interleaved`] for more info.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+#[allow(clippy::modulo_one)]
+pub fn is_correct_interleaved(frame: &[__u]) -> bool {
+    assert!(frame.len() % INTERLEAVE == 0);
+
+    let mut codeword = Vec::with_capacity(frame.len() / INTERLEAVE);
+    (0..INTERLEAVE).all(|i| {
+        deinterleave(frame, i, &mut codeword);
+        is_correct(&codeword)
+    })
+}
+
+/// Correct up to [`ECC_SIZE`] erasures at known locations in each of
+/// [`INTERLEAVE`] interleaved codewords.
+///
+/// Erasures are locations in the interleaved frame. Returns the total
+/// number of erasures, or [`Error::TooManyErrors`] if any codeword can not
+/// be corrected.
+/// On error, the frame is left unmodified.
+///
+/// See [`correct_erasures`] and [`encode_interleaved`] for more info.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_erasures_interleaved(
+    frame: &mut [__u],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    map_interleaved(frame, |i, codeword| {
+        correct_erasures(codeword, &deinterleave_erasures(erasures, i))
+    })
+}
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations in
+/// each of [`INTERLEAVE`] interleaved codewords.
+///
+/// Returns the total number of errors, or [`Error::TooManyErrors`] if any
+/// codeword can not be corrected.
+/// On error, the frame is left unmodified.
+///
+/// See [`correct_errors`] and [`encode_interleaved`] for more info.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_errors_interleaved(frame: &mut [__u]) -> Result<usize, Error> {
+    map_interleaved(frame, |_, codeword| {
+        correct_errors(codeword)
+    })
+}
+
+/// Correct a mixture of errors and erasures, up to
+/// `2*errors+erasures <= ECC_SIZE`, in each of [`INTERLEAVE`] interleaved
+/// codewords.
+///
+/// Erasures are locations in the interleaved frame. Returns the total
+/// number of errors and erasures, or [`Error::TooManyErrors`] if any
+/// codeword can not be corrected.
+/// On error, the frame is left unmodified.
+///
+/// See [`correct`] and [`encode_interleaved`] for more info.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_interleaved(
+    frame: &mut [__u],
+    erasures: &[usize]
+) -> Result<usize, Error> {
+    map_interleaved(frame, |i, codeword| {
+        correct(codeword, &deinterleave_erasures(erasures, i))
+    })
+}
+
+
+/// Encode many independent codewords using multiple threads.
+///
+/// This calls [`encode`] on each codeword in parallel with
+/// [rayon](__crate::internal::rayon). Each codeword follows the same rules
+/// as [`encode`], with [`ECC_SIZE`] bytes of error-correction information
+/// written to the end of each codeword.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = vec![[0u8; 255]; 1000];
+/// for (i, codeword) in codewords.iter_mut().enumerate() {
+///     codeword[..223].fill(i as u8);
+/// }
+///
+/// rs255w223::encode_blocks(&mut codewords);
+/// assert!(codewords.iter().all(|codeword| rs255w223::is_correct(codeword)));
+/// ```
+///
+/// Note this requires feature `rayon`.
+///
+#[cfg(__if(__rayon))]
+pub fn encode_blocks<B: AsMut<[__u]> + Send>(codewords: &mut [B]) {
+    use __crate::internal::rayon::prelude::*;
+
+    codewords.par_iter_mut()
+        .for_each(|codeword| encode(codeword.as_mut()));
+}
+
+/// Correct errors in many independent codewords using multiple threads.
+///
+/// This calls [`correct_errors`] on each codeword in parallel with
+/// [rayon](__crate::internal::rayon), returning the result for each
+/// codeword, so codewords that can't be corrected can be handled
+/// individually.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codewords = vec![[0u8; 255]; 1000];
+/// rs255w223::encode_blocks(&mut codewords);
+///
+/// // corrupt
+/// for codeword in codewords.iter_mut() {
+///     codeword[0..16].fill(b'x');
+/// }
+/// codewords[42][0..17].fill(b'x');
+///
+/// // correct
+/// let results = rs255w223::correct_blocks(&mut codewords);
+/// assert_eq!(results[0], Ok(16));
+/// assert_eq!(results[42], Err(rs255w223::Error::TooManyErrors));
+/// ```
+///
+/// Note this requires feature `rayon`.
+///
+#[cfg(__if(__rayon))]
+pub fn correct_blocks<B: AsMut<[__u]> + Send>(
+    codewords: &mut [B]
+) -> Vec<Result<usize, Error>> {
+    use __crate::internal::rayon::prelude::*;
+
+    codewords.par_iter_mut()
+        .map(|codeword| correct_errors(codeword.as_mut()))
+        .collect()
+}
+
+
+// Parallel pipelines
+//
+// Each interleaved frame can be encoded/decoded independently, so large
+// streams can be split into frames and handed out to a pool of worker
+// threads. A bounded number of frames are in flight at any time, so a slow
+// reader, writer, or worker applies backpressure to the rest of the
+// pipeline instead of buffering the whole stream in memory.
+//
+// Full frames contain INTERLEAVE*DATA_SIZE bytes of data, encoded as an
+// interleaved frame. The last, partial, frame is encoded with
+// encode_stream, which is always shorter than a full frame, so the stream
+// needs no extra framing information. Note when INTERLEAVE is 1 this is
+// exactly the same as encode_stream.
+//
+
+/// Read until buf is full or we reach the end of the stream
+#[cfg(__if(__std && __byte_symbols))]
+fn read_frame<R: __crate::internal::std::io::Read>(
+    reader: &mut R,
+    buf: &mut [u8]
+) -> __crate::internal::std::io::Result<usize> {
+    use __crate::internal::std::io;
+
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+/// Run f over frames from reader using a pool of worker threads, writing
+/// the results to writer in order
+#[cfg(__if(__std && __byte_symbols))]
+fn pipeline<R, W, F>(
+    mut reader: R,
+    mut writer: W,
+    frame_size: usize,
+    threads: usize,
+    f: F
+) -> __crate::internal::std::io::Result<u64>
+where
+    R: __crate::internal::std::io::Read,
+    W: __crate::internal::std::io::Write + Send,
+    F: Fn(Vec<u8>) -> __crate::internal::std::io::Result<Vec<u8>> + Sync
+{
+    use __crate::internal::std::io;
+    use __crate::internal::std::sync::mpsc;
+    use __crate::internal::std::sync::Mutex;
+    use __crate::internal::std::thread;
+    use __crate::internal::std::collections::BTreeMap;
+
+    // limit the number of frames in flight with a set of tokens, the
+    // reader needs a token to read a frame, and the writer returns the
+    // token once the frame is written
+    let threads = threads.max(1);
+    let depth = 2*threads;
+    let (token_tx, token_rx) = mpsc::sync_channel::<()>(depth);
+    for _ in 0..depth {
+        token_tx.send(()).unwrap();
+    }
+    let (job_tx, job_rx) = mpsc::sync_channel::<(usize, Vec<u8>)>(depth);
+    let job_rx = Mutex::new(job_rx);
+    let (result_tx, result_rx) = mpsc::sync_channel(depth);
+
+    thread::scope(|scope| {
+        // workers
+        for _ in 0..threads {
+            let job_rx = &job_rx;
+            let result_tx = result_tx.clone();
+            let f = &f;
+            scope.spawn(move || loop {
+                let job = job_rx.lock().unwrap().recv();
+                let (i, frame) = match job {
+                    Ok(job) => job,
+                    Err(_) => break,
+                };
+                if result_tx.send((i, f(frame))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_tx);
+
+        // writer, reordering frames as they finish
+        let writer = scope.spawn(move || -> io::Result<u64> {
+            let mut pending = BTreeMap::new();
+            let mut next = 0;
+            let mut written = 0;
+            for (i, result) in result_rx {
+                pending.insert(i, result);
+                while let Some(result) = pending.remove(&next) {
+                    let frame: Vec<u8> = result?;
+                    writer.write_all(&frame)?;
+                    written += frame.len() as u64;
+                    next += 1;
+                    // if the reader is done, we don't need to return tokens
+                    let _ = token_tx.send(());
+                }
+            }
+            writer.flush()?;
+            Ok(written)
+        });
+
+        // reader, if the writer fails it stops returning tokens, which
+        // stops the reader
+        let mut read = || -> io::Result<()> {
+            for i in 0.. {
+                if token_rx.recv().is_err() {
+                    break;
+                }
+
+                let mut frame = vec![0u8; frame_size];
+                let len = read_frame(&mut reader, &mut frame)?;
+                if len == 0 {
+                    break;
+                }
+                frame.truncate(len);
+                if job_tx.send((i, frame)).is_err() || len < frame_size {
+                    break;
+                }
+            }
+            Ok(())
+        };
+        let read_res = read();
+        drop(job_tx);
+        drop(token_rx);
+
+        let write_res = writer.join().unwrap();
+        read_res.and(write_res)
+    })
+}
+
+/// Encode a stream using a pool of worker threads, writing the result to
+/// `writer`.
+///
+/// The stream is split into frames of [`INTERLEAVE`] interleaved
+/// codewords, see [`encode_interleaved`], with the last frame encoded with
+/// [`encode_stream`]. When [`INTERLEAVE`] is 1, the result is the same as
+/// [`encode_stream`]. At most `2*threads` frames are buffered at a time,
+/// so this can encode arbitrarily large streams. Returns the number of
+/// bytes written.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let message = (0..10000).map(|i| i as u8).collect::<Vec<u8>>();
+/// let mut stream = Vec::new();
+/// rs255w223::encode_pipeline(&message[..], &mut stream, 4)?;
+/// assert_eq!(stream, rs255w223::encode_stream(&message));
+///
+/// let mut decoded = Vec::new();
+/// rs255w223::decode_pipeline(&stream[..], &mut decoded, 4)?;
+/// assert_eq!(decoded, message);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+pub fn encode_pipeline<R, W>(
+    reader: R,
+    writer: W,
+    threads: usize
+) -> __crate::internal::std::io::Result<u64>
+where
+    R: __crate::internal::std::io::Read,
+    W: __crate::internal::std::io::Write + Send
+{
+    pipeline(reader, writer, INTERLEAVE*DATA_SIZE, threads, |data| {
+        if data.len() < INTERLEAVE*DATA_SIZE {
+            return Ok(encode_stream(&data));
+        }
+
+        let mut frame = data;
+        frame.resize(INTERLEAVE*BLOCK_SIZE, 0);
+        encode_interleaved(&mut frame);
+        Ok(frame)
+    })
+}
+
+/// Decode a stream created by [`encode_pipeline`] using a pool of worker
+/// threads, correcting up to [`ECC_SIZE/2`](ECC_SIZE) errors in each
+/// codeword, and writing the result to `writer`.
+///
+/// At most `2*threads` frames are buffered at a time. Returns the number
+/// of bytes written, or an error of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData) if any codeword can not
+/// be corrected.
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+pub fn decode_pipeline<R, W>(
+    reader: R,
+    writer: W,
+    threads: usize
+) -> __crate::internal::std::io::Result<u64>
+where
+    R: __crate::internal::std::io::Read,
+    W: __crate::internal::std::io::Write + Send
+{
+    use __crate::internal::std::io;
+
+    pipeline(reader, writer, INTERLEAVE*BLOCK_SIZE, threads, |frame| {
+        if frame.len() < INTERLEAVE*BLOCK_SIZE {
+            return decode_stream(&frame)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+
+        let mut frame = frame;
+        correct_errors_interleaved(&mut frame)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        frame.truncate(INTERLEAVE*DATA_SIZE);
+        Ok(frame)
+    })
+}
+
+
+// List decoding
+//
+// Unique decoding can only correct up to ECC_SIZE/2 errors, beyond this
+// there may be multiple codewords equally close to what we received. List
+// decoding instead finds all codewords within some larger radius, leaving
+// it up to the caller to pick the right one, with a CRC for example.
+//
+// We use the Guruswami-Sudan algorithm, which views our codeword as the
+// evaluations of a low-degree polynomial g(x). In BCH-view, our codewords
+// are polynomials with roots at g^0..g^ECC_SIZE-1, which are equivalent to
+// evaluations scaled by column multipliers:
+//
+//     c_p = v_p * g(α^p), deg(g) < len-ECC_SIZE
+//
+//               1
+//     v_p = ----------
+//           ∏ (α^p-α^s)
+//          s≠p
+//
+// Where p is the power of each symbol, len-1-i for the i-th symbol. Note
+// this relies on our field having characteristic 2.
+//
+// Guruswami-Sudan then finds a bivariate polynomial Q(x,y) that passes
+// through every received point with multiplicity m, and low weighted degree.
+// Any g(x) that agrees with enough points must be a y-root of Q(x,y), which
+// we can find with the Roth-Ruckenstein algorithm.
+//
+
+/// Parameters for Guruswami-Sudan, returns (weight, D, radius), where the
+/// weighted degree of Q(x,y) is bounded by D
+fn list_params(len: usize, multiplicity: usize) -> (usize, usize, usize) {
+    let k = len.saturating_sub(ECC_SIZE);
+    let w = core::cmp::max(k.saturating_sub(1), 1);
+    let m = multiplicity;
+
+    // we need more monomials than constraints to guarantee a non-zero
+    // Q(x,y) exists
+    let constraints = len*m*(m+1)/2;
+    let monomials = |d: usize| -> usize {
+        (0..=d/w).map(|b| d - w*b + 1).sum()
+    };
+    let mut d = 0;
+    while monomials(d) <= constraints {
+        d += 1;
+    }
+
+    // Q(x,g(x)) has degree <= D, but has m*agreements zeros, so any g(x)
+    // with more than D/m agreements must be a y-root
+    (w, d, len.saturating_sub(d/m + 1))
+}
+
+/// Find the evaluation points, x_p = α^p, and column multipliers, v_p,
+/// for viewing a codeword of the given length as evaluations of a
+/// polynomial
+#[cfg(__if(__alloc))]
+fn find_evaluation_points(len: usize) -> (Vec<__gf>, Vec<__gf>) {
+    let x = (0..len)
+        .map(|p| __gf::GENERATOR.pow(__u::try_from(p).unwrap()))
+        .collect::<Vec<_>>();
+    let v = (0..len)
+        .map(|p| {
+            (0..len)
+                .filter(|&s| s != p)
+                .fold(__gf::new(1), |v, s| v * (x[p] - x[s]))
+                .recip()
+        })
+        .collect::<Vec<_>>();
+    (x, v)
+}
+
+/// Is the binomial coefficient C(n, k) odd? In a field with characteristic
+/// 2, this determines if C(n, k) is 1 or 0
+#[cfg(__if(__alloc))]
+fn binomial_odd(n: usize, k: usize) -> bool {
+    k <= n && n & k == k
+}
+
+/// Find a non-zero vector in the nullspace of a matrix via Gauss-Jordan
+/// elimination, assumes there are more columns than rows
+#[cfg(__if(__alloc))]
+fn find_nullspace(rows: &mut [Vec<__gf>], cols: usize) -> Vec<__gf> {
+    let mut pivots = vec![];
+    for c in 0..cols {
+        if pivots.len() == rows.len() {
+            break;
+        }
+
+        // find a pivot
+        let r = pivots.len();
+        let p = match (r..rows.len()).find(|&i| rows[i][c] != __gf::new(0)) {
+            Some(p) => p,
+            None => continue,
+        };
+        rows.swap(r, p);
+
+        // normalize and eliminate this column from all other rows
+        let recip = rows[r][c].recip();
+        for x in rows[r][c..].iter_mut() {
+            *x *= recip;
+        }
+        for i in 0..rows.len() {
+            let f = rows[i][c];
+            if i != r && f != __gf::new(0) {
+                for j in c..cols {
+                    let x = rows[r][j];
+                    rows[i][j] -= f*x;
+                }
+            }
+        }
+
+        pivots.push(c);
+    }
+
+    // set the first free variable to 1, and solve for the pivots
+    let free = (0..cols).find(|c| !pivots.contains(c)).unwrap();
+    let mut q = vec![__gf::new(0); cols];
+    q[free] = __gf::new(1);
+    for (r, &c) in pivots.iter().enumerate() {
+        q[c] = -rows[r][free];
+    }
+    q
+}
+
+/// Find all y-roots of Q(x,y) with degree < k using the Roth-Ruckenstein
+/// algorithm, Q is stored as Q[b][a] = coefficient of x^a*y^b
+///
+/// Note this may find some extra candidates, these are filtered out later
+///
+#[cfg(__if(__alloc))]
+fn find_y_roots(
+    Q: &[Vec<__gf>],
+    k: usize,
+    g: &mut Vec<__gf>,
+    roots: &mut Vec<Vec<__gf>>
+) {
+    // divide out any factors of x
+    let h = Q.iter()
+        .filter_map(|Qb| Qb.iter().position(|x| *x != __gf::new(0)))
+        .min();
+    let h = match h {
+        Some(h) => h,
+        // Q(x,y) = 0? this shouldn't happen
+        None => return,
+    };
+
+    // find roots of Q(0,y) by brute force
+    let Q0 = Q.iter()
+        .map(|Qb| Qb.get(h).copied().unwrap_or(__gf::new(0)))
+        .collect::<Vec<_>>();
+    for γ in (0..=__gf::NONZEROS).map(__gf::new) {
+        if Q0.iter().rev().fold(__gf::new(0), |y, c| y*γ + c) != __gf::new(0) {
+            continue;
+        }
+
+        g.push(γ);
+        if g.len() == k {
+            roots.push(g.clone());
+        } else {
+            // Q'(x,y) = Q(x, x*y + γ)
+            //
+            // (x*y + γ)^b = Σ C(b,t) γ^(b-t) x^t y^t
+            //
+            let mut Q_ = vec![vec![]; Q.len()];
+            for (b, Qb) in Q.iter().enumerate() {
+                let mut γ_pow = __gf::new(1);
+                for t in (0..=b).rev() {
+                    if binomial_odd(b, t) {
+                        let len = Qb.len() - h + t;
+                        if Q_[t].len() < len {
+                            Q_[t].resize(len, __gf::new(0));
+                        }
+                        for (a, &c) in Qb[h..].iter().enumerate() {
+                            Q_[t][a+t] += γ_pow*c;
+                        }
+                    }
+                    γ_pow *= γ;
+                }
+            }
+
+            find_y_roots(&Q_, k, g, roots);
+        }
+        g.pop();
+    }
+}
+
+/// Find the radius of [`correct_list`] for a given codeword length and
+/// multiplicity.
+///
+/// This is at least [`ECC_SIZE/2`](ECC_SIZE), but can be larger for
+/// low-rate codes and larger multiplicities.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// assert_eq!(rs255w223::correct_list_radius(255, 1), 16);
+/// ```
+///
+pub fn correct_list_radius(len: usize, multiplicity: usize) -> usize {
+    assert!(len >= ECC_SIZE);
+    assert!(multiplicity >= 1);
+    let (_, _, radius) = list_params(len, multiplicity);
+    core::cmp::max(radius, ECC_SIZE/2)
+}
+
+/// Find all codewords within [`correct_list_radius`] of a received
+/// codeword, using Guruswami-Sudan list decoding.
+///
+/// Unlike [`correct_errors`], this can find codewords beyond
+/// [`ECC_SIZE/2`](ECC_SIZE) errors, but may find multiple codewords, so
+/// some other mechanism, such as a CRC, is needed to pick the right one.
+/// Codewords are returned ordered by their distance from the received
+/// codeword.
+///
+/// `multiplicity` controls the tradeoff between radius and cost, a
+/// multiplicity of 1 is Sudan's algorithm. Note that list decoding only
+/// corrects more errors than [`correct_errors`] for low-rate codes, and
+/// that the cost grows quickly with both the codeword length and
+/// multiplicity, so this is mostly useful for small codes and recovering
+/// otherwise unrecoverable data.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let codeword = b"xexlx xoxlx!\
+///     x\xa6x\xf8x\x15x\x6ex\xb6x\x12x\xbdx\xd3\
+///     x\x14x\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35".to_vec();
+///
+/// let codewords = rs255w223::correct_list(&codeword, 1);
+/// assert_eq!(&codewords[0], b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+/// Note this finds roots by brute force, so it is limited to fields with
+/// at most 2^16 elements.
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_list(codeword: &[__u], multiplicity: usize) -> Vec<Vec<__u>> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+    assert!(multiplicity >= 1);
+    assert!(
+        __gf::NONZEROS as u128 <= 0xffff,
+        "list decoding is limited to fields with at most 2^16 elements"
+    );
+    let radius = correct_list_radius(codeword.len(), multiplicity);
+    let r = unsafe { __gf::slice_from_slice_unchecked(codeword) };
+    let len = r.len();
+    let k = len - ECC_SIZE;
+    let (w, d, _) = list_params(len, multiplicity);
+    let m = multiplicity;
+
+    // find our evaluation points, x_p = α^p, and the column multipliers v_p
+    let (x, v) = find_evaluation_points(len);
+    let y = (0..len)
+        .map(|p| r[len-1-p] / v[p])
+        .collect::<Vec<_>>();
+
+    let mut candidates = vec![];
+    if k == 0 {
+        // only the zero codeword
+        candidates.push(vec![]);
+    } else {
+        // build our interpolation constraints, Q(x,y) must have all Hasse
+        // derivatives Q^[u,v](x_p,y_p) = 0 for u+v < m
+        let monomials = (0..=d/w)
+            .flat_map(|b| (0..=d-w*b).map(move |a| (a, b)))
+            .collect::<Vec<_>>();
+        let mut rows = vec![];
+        for p in 0..len {
+            let x_pow = (0..=d)
+                .scan(__gf::new(1), |s, _| { let x_ = *s; *s *= x[p]; Some(x_) })
+                .collect::<Vec<_>>();
+            let y_pow = (0..=d/w)
+                .scan(__gf::new(1), |s, _| { let y_ = *s; *s *= y[p]; Some(y_) })
+                .collect::<Vec<_>>();
+
+            for u in 0..m {
+                for v_ in 0..m-u {
+                    rows.push(monomials.iter()
+                        .map(|&(a, b)| {
+                            if binomial_odd(a, u) && binomial_odd(b, v_) {
+                                x_pow[a-u] * y_pow[b-v_]
+                            } else {
+                                __gf::new(0)
+                            }
+                        })
+                        .collect::<Vec<_>>());
+                }
+            }
+        }
+
+        // find Q(x,y)
+        let q = find_nullspace(&mut rows, monomials.len());
+        let mut Q = vec![vec![__gf::new(0); d+1]; d/w+1];
+        for (&(a, b), &c) in monomials.iter().zip(&q) {
+            Q[b][a] = c;
+        }
+
+        // find y-roots of Q(x,y)
+        find_y_roots(&Q, k, &mut Vec::with_capacity(k), &mut candidates);
+    }
+
+    // evaluate our candidates, keeping only those within our radius
+    let distance = |c: &[__u]| {
+        c.iter().zip(codeword).filter(|(a, b)| a != b).count()
+    };
+    let mut codewords = vec![];
+    for g in candidates {
+        let mut c = vec![0; len];
+        for p in 0..len {
+            let gx = g.iter().rev().fold(__gf::new(0), |s, g_| s*x[p] + g_);
+            c[len-1-p] = (v[p] * gx).get();
+        }
+
+        let dist = distance(&c);
+        if dist <= radius {
+            codewords.push((dist, c));
+        }
+    }
+
+    // always include the unique decoding if there is one, Guruswami-Sudan
+    // may have a smaller radius for high-rate codes
+    let mut c = codeword.to_vec();
+    if correct_errors(&mut c).is_ok() {
+        codewords.push((distance(&c), c));
+    }
+
+    codewords.sort();
+    codewords.dedup();
+    codewords.into_iter().map(|(_, c)| c).collect()
+}
+
+
+// Berlekamp-Welch decoding
+//
+// Berlekamp-Welch is an alternative decoder that uses the same evaluation
+// view as list decoding. If our codeword is the evaluations of g(x), with
+// errors at unknown points, then there is an error locator E(x) with roots
+// at the errors, and N(x) = g(x)*E(x), such that for every point:
+//
+//     N(x_p) = y_p*E(x_p)
+//
+// This is a linear system in the coefficients of N(x) and E(x), which we
+// can solve directly, and g(x) = N(x)/E(x).
+//
+// This is much slower than Berlekamp-Massey, O(n^3) vs O(n^2), but is
+// simple and shares nothing with the syndrome-based decoders, which makes
+// it useful for differential testing.
+//
+
+/// Correct up to [`ECC_SIZE/2`](ECC_SIZE) errors at unknown locations using
+/// the Berlekamp-Welch algorithm.
+///
+/// This should always give the same result as [`correct_errors`], but is
+/// much slower, so it's mostly useful for testing and education.
+///
+/// Returns the number of errors, or [`Error::TooManyErrors`] if the codeword
+/// can not be corrected.
+///
+/// ``` rust
+/// # use gf256::rs::rs255w223;
+/// let mut codeword = b"Hellx xoxld!\
+///     \x85\xa6xxx\x15\x94\x6e\x5f\xb6\x07x\x4b\xbdxx\
+///     \x34xxxx\x25x\x84\xc2\x61\x81x\x8ax\xc9\x35".to_vec();
+///
+/// assert_eq!(rs255w223::correct_errors_berlekamp_welch(&mut codeword), Ok(16));
+/// assert_eq!(&codeword, b"Hello World!\
+///     \x85\xa6\xad\xf8\xbd\x15\x94\x6e\x5f\xb6\x07\x12\x4b\xbd\x11\xd3\
+///     \x34\x14\xa7\x06\xd6\x25\xfd\x84\xc2\x61\x81\xa7\x8a\x15\xc9\x35");
+/// ```
+///
+/// Note this requires feature `alloc`.
+///
+#[cfg(__if(__alloc))]
+pub fn correct_errors_berlekamp_welch(
+    codeword: &mut [__u]
+) -> Result<usize, Error> {
+    assert!(codeword.len() <= BLOCK_SIZE);
+    assert!(codeword.len() >= ECC_SIZE);
+    let r = unsafe { __gf::slice_from_slice_mut_unchecked(codeword) };
+    let len = r.len();
+    let k = len - ECC_SIZE;
+    let e = ECC_SIZE / 2;
+
+    // nothing to correct?
+    if len == 0 {
+        return Ok(0);
+    }
+
+    let (x, v) = find_evaluation_points(len);
+    let y = (0..len)
+        .map(|p| r[len-1-p] / v[p])
+        .collect::<Vec<_>>();
+
+    // build our linear system, deg(E) <= e, and deg(N) <= len-1-e, so
+    // any two solutions agree on more points than their degree, which
+    // makes N(x)/E(x) unique
+    let n_size = len - e;
+    let e_size = e + 1;
+    let mut rows = (0..len)
+        .map(|p| {
+            let mut row = Vec::with_capacity(n_size + e_size);
+            let mut x_pow = __gf::new(1);
+            for _ in 0..n_size {
+                row.push(x_pow);
+                x_pow *= x[p];
+            }
+            let mut x_pow = __gf::new(1);
+            for _ in 0..e_size {
+                row.push(-y[p]*x_pow);
+                x_pow *= x[p];
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+
+    // find N(x) and E(x), smallest-coefficient first
+    let mut solution = find_nullspace(&mut rows, n_size + e_size);
+    let (n, e) = solution.split_at_mut(n_size);
+    let e_deg = match e.iter().rposition(|x| *x != __gf::new(0)) {
+        Some(e_deg) => e_deg,
+        None => return Err(Error::TooManyErrors),
+    };
+
+    // g(x) = N(x)/E(x), this must divide evenly with deg(g) < k
+    let mut g = vec![__gf::new(0); n_size];
+    let e_lead_recip = e[e_deg].recip();
+    while let Some(d) = n.iter().rposition(|x| *x != __gf::new(0)) {
+        if d < e_deg || d-e_deg >= k {
+            return Err(Error::TooManyErrors);
+        }
+
+        let c = n[d] * e_lead_recip;
+        g[d-e_deg] = c;
+        for i in 0..=e_deg {
+            n[d-e_deg+i] -= c*e[i];
+        }
+    }
+
+    // evaluate g(x) to find our corrected codeword
+    let mut errors = 0;
+    for p in 0..len {
+        let gx = g.iter().rev().fold(__gf::new(0), |s, g_| s*x[p] + g_);
+        let c = v[p]*gx;
+        if r[len-1-p] != c {
+            r[len-1-p] = c;
+            errors += 1;
+        }
+    }
+
+    Ok(errors)
+}
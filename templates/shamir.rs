@@ -26,6 +26,7 @@
 
 use __crate::internal::cfg_if::cfg_if;
 use __crate::internal::rand::Rng;
+use __crate::internal::rand::CryptoRng;
 use __crate::traits::TryFrom;
 use __crate::traits::FromLossy;
 
@@ -34,7 +35,32 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 
+// In constant_time mode, refuse to compile if our Galois-field type isn't
+// constant-time
+#[cfg(__if(__constant_time))]
+const _: () = assert!(
+    __gf::CONSTANT_TIME,
+    "shamir(constant_time) requires a Galois-field type in barret mode"
+);
+
+/// Wipe a buffer that may contain secrets, if the zeroize feature is
+/// enabled
+#[inline]
+fn wipe<T: Copy + Default>(buf: &mut [T]) {
+    cfg_if! {
+        if #[cfg(__if(__zeroize))] {
+            __crate::internal::zeroize::zeroize(buf);
+        } else {
+            let _ = buf;
+        }
+    }
+}
+
 /// Generate a random polynomial of a given degree, fixing f(0) = secret
+///
+/// Note gen_range may retry samples, but this only depends on the rejected
+/// samples, not on the secret or the resulting coefficients.
+///
 fn poly_random<R: Rng>(rng: &mut R, secret: __gf, degree: usize) -> Vec<__gf> {
     let mut f = vec![secret];
     for _ in 0..degree {
@@ -52,23 +78,23 @@ fn poly_eval(f: &[__gf], x: __gf) -> __gf {
     y
 }
 
-/// Find f(0) using Lagrange interpolation
-fn poly_interpolate(xs: &[__gf], ys: &[__gf]) -> __gf {
-    assert!(xs.len() == ys.len());
-
-    let mut y = __gf::new(0);
-    for (i, (x0, y0)) in xs.iter().zip(ys).enumerate() {
-        let mut li = __gf::new(1);
-        for (j, (x1, _y1)) in xs.iter().zip(ys).enumerate() {
-            if i != j {
-                li *= x1 / (x1-x0);
-            }
+/// Find the Lagrange basis polynomial for xs[i], evaluated at x
+///
+/// ``` text
+///         x - xj
+/// li(x) = ∏ -------
+///        j!=i xi - xj
+/// ```
+///
+fn lagrange(xs: &[__gf], i: usize, x: __gf) -> __gf {
+    let x0 = xs[i];
+    let mut li = __gf::new(1);
+    for (j, x1) in xs.iter().enumerate() {
+        if i != j {
+            li *= (x-x1) / (x0-x1);
         }
-
-        y += li*y0;
     }
-
-    y
+    li
 }
 
 /// Generate `n` shares requiring `k` shares to reconstruct.
@@ -77,35 +103,142 @@ fn poly_interpolate(xs: &[__gf], ys: &[__gf]) -> __gf {
 /// non-zero elements in the field.
 ///
 pub fn generate(secret: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
-    // we only support up to 255 shares
-    assert!(
-        n <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX),
-        "exceeded {} shares",
-        __gf::NONZEROS
-    );
-    let mut shares = vec![vec![]; n];
-    let mut rng = __rng();
+    generate_with_splitter(splitter(n, k), secret)
+}
 
-    // we need to store the x coord somewhere, so just prepend the share with it
-    for i in 0..n {
-        shares[i].push(__u::try_from(i+1).unwrap());
-    }
+/// Generate `n` shares requiring `k` shares to reconstruct, using the
+/// provided random-number generator.
+///
+/// This is useful for injecting hardware-backed random-number generators,
+/// or seeded random-number generators for deterministic tests. Note the
+/// rng must be cryptographically secure, shares are only as unpredictable
+/// as the rng.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// use rand::SeedableRng;
+/// use rand::rngs::StdRng;
+///
+/// // the same seed always generates the same shares
+/// let shares = shamir::generate_with_rng(StdRng::seed_from_u64(42), b"secret secret secret!", 5, 4);
+/// let again = shamir::generate_with_rng(StdRng::seed_from_u64(42), b"secret secret secret!", 5, 4);
+/// assert_eq!(shares, again);
+///
+/// assert_eq!(shamir::reconstruct(&shares[..4]), b"secret secret secret!");
+/// ```
+///
+pub fn generate_with_rng<R: Rng + CryptoRng>(
+    rng: R,
+    secret: &[__u],
+    n: usize,
+    k: usize
+) -> Vec<Vec<__u>> {
+    generate_with_splitter(splitter_with_rng(rng, n, k), secret)
+}
 
-    for x in secret {
-        // generate a random polynomial for each byte
-        let f = poly_random(&mut rng, __gf::from_lossy(*x), k-1);
+/// Generate `n` shares requiring `k` shares to reconstruct,
+/// deterministically from a 32-byte seed.
+///
+/// This uses ChaCha20 as the random-number generator, so the same secret
+/// and seed always generate the same shares. This is useful for
+/// reproducible tests, audits, and backups that need to be regenerated
+/// exactly.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let seed = [0x42; 32];
+/// let shares = shamir::generate_from_seed(seed, b"secret secret secret!", 5, 4);
+/// assert_eq!(shares, shamir::generate_from_seed(seed, b"secret secret secret!", 5, 4));
+/// assert_eq!(shamir::reconstruct(&shares[..4]), b"secret secret secret!");
+/// ```
+///
+/// The seed is as sensitive as the secret itself, anyone with the seed
+/// and `k-1` shares can reconstruct the secret. And never reuse a seed for
+/// different secrets, the shares would use the same random polynomials,
+/// so the difference between shares would reveal the difference between
+/// secrets.
+///
+/// Note this requires feature `seeded-rng`.
+///
+#[cfg(__if(__seeded_rng))]
+pub fn generate_from_seed(
+    seed: [u8; 32],
+    secret: &[__u],
+    n: usize,
+    k: usize
+) -> Vec<Vec<__u>> {
+    use __crate::internal::rand::SeedableRng;
+    use __crate::internal::rand_chacha::ChaCha20Rng;
 
-        // assign each share with a point at f(i)
-        for i in 0..n {
-            shares[i].push(__u::from(
-                poly_eval(&f, __gf::from_lossy(i+1))
-            ));
+    generate_with_rng(ChaCha20Rng::from_seed(seed), secret, n, k)
+}
+
+/// Generate `n` shares requiring `k` shares to reconstruct, using multiple
+/// threads.
+///
+/// This splits the secret into chunks with [`Splitter::split_par`], so
+/// large secrets are shared with all available threads while keeping
+/// the memory needed for random polynomials bounded.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let secret = (0..100000).map(|i| i as u8).collect::<Vec<u8>>();
+/// let shares = shamir::generate_par(&secret, 10, 4);
+/// assert_eq!(shamir::reconstruct(&shares[3..7]), secret);
+/// ```
+///
+/// Note this requires feature `rayon`.
+///
+#[cfg(__if(__rayon))]
+pub fn generate_par(secret: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
+    let mut splitter = splitter(n, k);
+
+    let mut shares = splitter.indices()
+        .into_iter()
+        .map(|x| {
+            let mut share = Vec::with_capacity(1+secret.len());
+            share.push(x);
+            share
+        })
+        .collect::<Vec<_>>();
+
+    for chunk in secret.chunks(PAR_CHUNK_SIZE) {
+        for (share, mut ys) in shares.iter_mut().zip(splitter.split_par(chunk)) {
+            share.extend_from_slice(&ys);
+            wipe(&mut ys);
         }
     }
 
     shares
 }
 
+/// Size of the chunks generate_par splits at a time
+#[cfg(__if(__rayon))]
+const PAR_CHUNK_SIZE: usize = 64*1024;
+
+/// Generate shares using a given splitter
+fn generate_with_splitter<R: Rng>(
+    mut splitter: Splitter<R>,
+    secret: &[__u]
+) -> Vec<Vec<__u>> {
+    // we need to store the x coord somewhere, so just prepend the share with it
+    let mut shares = splitter.indices()
+        .into_iter()
+        .map(|x| {
+            let mut share = Vec::with_capacity(1+secret.len());
+            share.push(x);
+            share
+        })
+        .collect::<Vec<_>>();
+
+    for (share, mut ys) in shares.iter_mut().zip(splitter.split(secret)) {
+        share.extend_from_slice(&ys);
+        wipe(&mut ys);
+    }
+
+    shares
+}
+
 /// Attempt to reconstruct a secret from at least `k` shares.
 ///
 /// All shares must be the same length. If insufficient or invalid shares are
@@ -118,19 +251,842 @@ pub fn reconstruct<S: AsRef<[__u]>>(shares: &[S]) -> Vec<__u> {
         "mismatched share length?"
     );
 
-    let mut secret = vec![];
     let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
     if len == 0 {
-        return secret;
+        return vec![];
+    }
+
+    // x is prepended to each share
+    let xs = shares.iter().map(|s| s.as_ref()[0]).collect::<Vec<_>>();
+    let ys = shares.iter().map(|s| &s.as_ref()[1..]).collect::<Vec<_>>();
+    Joiner::new(&xs).join(&ys)
+}
+
+
+/// Reconstruct many secrets whose shares have the same indices.
+///
+/// This is useful when a large secret is shared in independent chunks, or
+/// for many secrets shared to the same share holders. Since all of the
+/// shares have the same indices, the Lagrange coefficients only need to be
+/// found once, and the rest is slice multiply-adds.
+///
+/// Each set of shares must have the same indices in the same order, but
+/// secrets may have different lengths.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let shares1 = shamir::generate(b"secret secret secret!", 5, 3);
+/// let shares2 = shamir::generate(b"another secret!", 5, 3);
+///
+/// let secrets = shamir::reconstruct_many(&[&shares1[1..4], &shares2[1..4]]);
+/// assert_eq!(secrets[0], b"secret secret secret!");
+/// assert_eq!(secrets[1], b"another secret!");
+/// ```
+///
+pub fn reconstruct_many<S, T>(share_sets: &[T]) -> Vec<Vec<__u>>
+where
+    S: AsRef<[__u]>,
+    T: AsRef<[S]>
+{
+    // x is prepended to each share
+    let xs = share_sets.get(0)
+        .map(|shares| {
+            shares.as_ref().iter()
+                .map(|s| s.as_ref().get(0).copied().unwrap_or(__u::default()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let joiner = Joiner::new(&xs);
+
+    share_sets.iter()
+        .map(|shares| {
+            let shares = shares.as_ref();
+            let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
+            if len == 0 {
+                return vec![];
+            }
+
+            assert!(
+                shares.len() == xs.len()
+                    && shares.iter().zip(&xs).all(|(s, x)| s.as_ref()[0] == *x),
+                "mismatched share indices?"
+            );
+            let ys = shares.iter().map(|s| &s.as_ref()[1..]).collect::<Vec<_>>();
+            joiner.join(&ys)
+        })
+        .collect()
+}
+
+/// Multiply a slice of y-coordinates by a constant and add it to an
+/// accumulator, `f += c*g`
+///
+/// This is the inner loop of reconstruction, long GF(2^8) slices use split
+/// tables and byte-shuffle instructions if available. Note these shuffles
+/// are in-register, so unlike memory lookups they are still constant-time,
+/// and we use them for any leftover bytes too.
+///
+#[inline]
+fn slice_mul_add(f: &mut [__gf], g: &[__u], c: __gf) {
+    assert!(g.len() >= f.len());
+
+    if core::mem::size_of::<__gf>() == 1
+        && core::mem::size_of::<__u>() == 1
+        && __gf::NONZEROS == 255
+        && f.len() >= __crate::internal::shuffle::SHUFFLE_THRESHOLD
+        && __crate::internal::shuffle::has_shuffle()
+    {
+        let (lo, hi) = __crate::internal::shuffle::split_tables(|x| {
+            (c * __gf::from_lossy(x)).get() as u8
+        });
+        let f = unsafe { core::slice::from_raw_parts_mut(f.as_mut_ptr() as *mut u8, f.len()) };
+        let g = unsafe { core::slice::from_raw_parts(g.as_ptr() as *const u8, f.len()) };
+
+        let split = f.len() - f.len()%16;
+        let rem = f.len() - split;
+        __crate::internal::shuffle::mul_add(&mut f[..split], &g[..split], &lo, &hi);
+        if rem > 0 {
+            let mut f_ = [0u8; 16];
+            let mut g_ = [0u8; 16];
+            f_[..rem].copy_from_slice(&f[split..]);
+            g_[..rem].copy_from_slice(&g[split..]);
+            __crate::internal::shuffle::mul_add(&mut f_, &g_, &lo, &hi);
+            f[split..].copy_from_slice(&f_[..rem]);
+            wipe(&mut f_);
+            wipe(&mut g_);
+        }
+        return;
+    }
+
+    for (x, y) in f.iter_mut().zip(g) {
+        *x += c * __gf::from_lossy(*y);
+    }
+}
+
+/// Solve a linear system `a*x = b` with Gauss-Jordan elimination
+///
+/// If there are multiple solutions, any free variables are set to zero.
+/// Returns `None` if there is no solution.
+///
+fn solve(mut a: Vec<Vec<__gf>>, mut b: Vec<__gf>) -> Option<Vec<__gf>> {
+    let rows = a.len();
+    let cols = a.get(0).map(|row| row.len()).unwrap_or(0);
+    let mut pivots = vec![];
+    for c in 0..cols {
+        let r = pivots.len();
+        if r == rows {
+            break;
+        }
+
+        // find a pivot
+        let p = match (r..rows).find(|i| a[*i][c] != __gf::new(0)) {
+            Some(p) => p,
+            None => continue,
+        };
+        a.swap(r, p);
+        b.swap(r, p);
+
+        // normalize the pivot row
+        let inv = __gf::new(1) / a[r][c];
+        for x in a[r][c..].iter_mut() {
+            *x *= inv;
+        }
+        b[r] *= inv;
+
+        // and eliminate this column from every other row
+        let pivot = a[r].clone();
+        for i in 0..rows {
+            let f = a[i][c];
+            if i != r && f != __gf::new(0) {
+                for (x, p) in a[i][c..].iter_mut().zip(&pivot[c..]) {
+                    *x -= f*p;
+                }
+                b[i] = b[i] - f*b[r];
+            }
+        }
+
+        pivots.push(c);
+    }
+
+    // any leftover rows must be zero, otherwise the system is inconsistent
+    if b[pivots.len()..].iter().any(|x| *x != __gf::new(0)) {
+        return None;
+    }
+
+    let mut x = vec![__gf::new(0); cols];
+    for (r, c) in pivots.iter().enumerate() {
+        x[*c] = b[r];
+    }
+    Some(x)
+}
+
+/// Find the polynomial of degree < k passing through all but at most t
+/// points using the Berlekamp-Welch algorithm
+///
+/// This finds an error locator E(x) of degree t and Q(x) = P(x)E(x) of
+/// degree < t+k, such that Q(xi) = yi*E(xi) for every point, and then
+/// P(x) = Q(x)/E(x).
+///
+fn berlekamp_welch(xs: &[__gf], ys: &[__gf], k: usize, t: usize) -> Option<Vec<__gf>> {
+    // Q(xi) - yi*(E(xi) - xi^t) = yi*xi^t, where E is monic
+    let mut a = Vec::with_capacity(xs.len());
+    let mut b = Vec::with_capacity(xs.len());
+    for (x, y) in xs.iter().zip(ys) {
+        let mut row = Vec::with_capacity(2*t+k);
+        let mut xj = __gf::new(1);
+        for _ in 0..t+k {
+            row.push(xj);
+            xj *= x;
+        }
+        let mut xj = __gf::new(1);
+        for _ in 0..t {
+            row.push(-(*y*xj));
+            xj *= x;
+        }
+        a.push(row);
+        b.push(*y*xj);
+    }
+    let solution = solve(a, b)?;
+
+    // P(x) = Q(x)/E(x), with a zero remainder
+    let mut q = solution[..t+k].to_vec();
+    let mut e = solution[t+k..].to_vec();
+    e.push(__gf::new(1));
+    let mut p = vec![__gf::new(0); k];
+    for i in (0..k).rev() {
+        let c = q[i+t];
+        p[i] = c;
+        for (q, e) in q[i..=i+t].iter_mut().zip(&e) {
+            *q -= c*e;
+        }
+    }
+    if q.iter().any(|c| *c != __gf::new(0)) {
+        return None;
+    }
+
+    Some(p)
+}
+
+/// Reconstruct a secret from `n` shares requiring `k` shares to
+/// reconstruct, even if up to `(n-k)/2` of the shares are corrupted.
+///
+/// Shamir's secret-sharing is closely related to Reed-Solomon codes, the
+/// shares of each byte are a Reed-Solomon codeword evaluated at the share
+/// indices. So extra shares beyond `k` can be used to correct corrupted
+/// shares, whether corrupted by bit-rot or by a dishonest share holder.
+///
+/// Returns the secret and the positions of any shares that are
+/// inconsistent with the secret, or `None` if there are too many corrupted
+/// shares to reconstruct the secret.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let mut shares = shamir::generate(b"secret secret secret!", 7, 3);
+///
+/// // corrupt two shares
+/// shares[1][4] ^= 0x01;
+/// shares[5][9] = b'x';
+///
+/// let (secret, bad) = shamir::reconstruct_robust(&shares, 3).unwrap();
+/// assert_eq!(secret, b"secret secret secret!");
+/// assert_eq!(bad, vec![1, 5]);
+/// ```
+///
+/// Note this is much more expensive than [`reconstruct`], and, since which
+/// shares are corrupted is secret-dependent, is not constant-time.
+///
+pub fn reconstruct_robust<S: AsRef<[__u]>>(
+    shares: &[S],
+    k: usize
+) -> Option<(Vec<__u>, Vec<usize>)> {
+    // matching lengths?
+    assert!(
+        shares.windows(2).all(|ss| ss[0].as_ref().len() == ss[1].as_ref().len()),
+        "mismatched share length?"
+    );
+    assert!(k > 0, "threshold can't be zero");
+
+    let n = shares.len();
+    if n < k {
+        return None;
+    }
+    let t = (n-k)/2;
+
+    let len = shares.get(0).map(|s| s.as_ref().len()).unwrap_or(0);
+    if len == 0 {
+        return Some((vec![], vec![]));
     }
 
     // x is prepended to each share
     let xs = shares.iter().map(|s| __gf::from_lossy(s.as_ref()[0])).collect::<Vec<_>>();
+
+    // interpolating the first k shares at every other index lets us
+    // cheaply check if a byte is consistent
+    let at_zero = (0..k)
+        .map(|j| lagrange(&xs[..k], j, __gf::new(0)))
+        .collect::<Vec<_>>();
+    let at = xs[k..].iter()
+        .map(|x| (0..k).map(|j| lagrange(&xs[..k], j, *x)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut secret = Vec::with_capacity(len-1);
+    let mut bad = vec![false; n];
+    let mut ys = vec![__gf::new(0); n];
     for i in 1..len {
-        let ys = shares.iter().map(|s| __gf::from_lossy(s.as_ref()[i])).collect::<Vec<_>>();
-        secret.push(__u::from(poly_interpolate(&xs, &ys)));
+        for (y, s) in ys.iter_mut().zip(shares) {
+            *y = __gf::from_lossy(s.as_ref()[i]);
+        }
+
+        let interpolate = |coeffs: &[__gf]| {
+            let mut y = __gf::new(0);
+            for (c, y0) in coeffs.iter().zip(&ys) {
+                y += c*y0;
+            }
+            y
+        };
+        if at.iter().zip(&ys[k..]).all(|(c, y)| interpolate(c) == *y) {
+            secret.push(__u::from(interpolate(&at_zero)));
+            continue;
+        }
+
+        // inconsistent, try to correct errors
+        let mut p = match berlekamp_welch(&xs, &ys, k, t) {
+            Some(p) => p,
+            None => {
+                wipe(&mut ys);
+                wipe(&mut secret);
+                return None;
+            }
+        };
+        for j in 0..n {
+            if poly_eval(&p, xs[j]) != ys[j] {
+                bad[j] = true;
+            }
+        }
+        secret.push(__u::from(p[0]));
+        wipe(&mut p);
+    }
+    wipe(&mut ys);
+
+    // errors in different bytes could still add up to more than we can
+    // correct
+    let bad = (0..n).filter(|i| bad[*i]).collect::<Vec<_>>();
+    if bad.len() > t {
+        wipe(&mut secret);
+        return None;
     }
 
-    secret
+    Some((secret, bad))
 }
 
+/// Generate shares incrementally, one chunk of the secret at a time.
+///
+/// Each byte of the secret gets its own random polynomial, so a secret can
+/// be split in chunks of any size, using memory proportional to the chunk
+/// instead of the whole secret. Concatenating a share's
+/// [`index`](Splitter::indices) and each of its chunks gives the same share
+/// [`generate`] would.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let mut splitter = shamir::splitter(5, 3);
+/// let mut shares = splitter.indices().into_iter().map(|x| vec![x]).collect::<Vec<_>>();
+/// for chunk in b"secret secret secret!".chunks(4) {
+///     for (share, ys) in shares.iter_mut().zip(splitter.split(chunk)) {
+///         share.extend(ys);
+///     }
+/// }
+///
+/// assert_eq!(shamir::reconstruct(&shares[..3]), b"secret secret secret!");
+/// ```
+///
+#[derive(Debug)]
+pub struct Splitter<R> {
+    rng: R,
+    n: usize,
+    k: usize,
+}
+
+/// Create a [`Splitter`] generating `n` shares requiring `k` shares to
+/// reconstruct.
+///
+/// Like [`generate`], this is limited to the number of non-zero elements in
+/// the field.
+///
+pub fn splitter(n: usize, k: usize) -> Splitter<impl Rng> {
+    new_splitter(__rng(), n, k)
+}
+
+/// Create a [`Splitter`] generating `n` shares requiring `k` shares to
+/// reconstruct, using the provided random-number generator.
+///
+/// See [`generate_with_rng`] for more info.
+///
+pub fn splitter_with_rng<R: Rng + CryptoRng>(
+    rng: R,
+    n: usize,
+    k: usize
+) -> Splitter<R> {
+    new_splitter(rng, n, k)
+}
+
+/// Create a splitter with any rng
+fn new_splitter<R: Rng>(rng: R, n: usize, k: usize) -> Splitter<R> {
+    // we only support as many shares as there are non-zero x-coordinates
+    assert!(
+        n <= usize::try_from(__gf::NONZEROS).unwrap_or(usize::MAX),
+        "exceeded {} shares",
+        __gf::NONZEROS
+    );
+    assert!(k > 0, "threshold can't be zero");
+    Splitter {
+        rng: rng,
+        n: n,
+        k: k,
+    }
+}
+
+impl<R: Rng> Splitter<R> {
+    /// The indices (x-coordinates) of each share, which should be stored
+    /// with each share.
+    pub fn indices(&self) -> Vec<__u> {
+        (1..=self.n).map(|i| __u::try_from(i).unwrap()).collect()
+    }
+
+    /// Generate the next chunk of each share from the next chunk of the
+    /// secret.
+    pub fn split(&mut self, chunk: &[__u]) -> Vec<Vec<__u>> {
+        let mut shares = vec![Vec::with_capacity(chunk.len()); self.n];
+        for x in chunk {
+            // generate a random polynomial for each byte
+            let mut f = poly_random(&mut self.rng, __gf::from_lossy(*x), self.k-1);
+
+            // assign each share with a point at f(i)
+            for (i, share) in shares.iter_mut().enumerate() {
+                share.push(__u::from(
+                    poly_eval(&f, __gf::from_lossy(i+1))
+                ));
+            }
+
+            wipe(&mut f);
+        }
+        shares
+    }
+
+    /// Generate the next chunk of each share from the next chunk of the
+    /// secret, using multiple threads.
+    ///
+    /// The random polynomials are still generated in order from our rng,
+    /// so this gives the same result as [`split`](Splitter::split), but
+    /// each share is evaluated in parallel with
+    /// [rayon](__crate::internal::rayon). Note this needs memory for `k`
+    /// coefficients per element of the chunk.
+    ///
+    /// Note this requires feature `rayon`.
+    ///
+    #[cfg(__if(__rayon))]
+    pub fn split_par(&mut self, chunk: &[__u]) -> Vec<Vec<__u>> {
+        use __crate::internal::rayon::prelude::*;
+
+        // generate a random polynomial for each byte
+        let mut fs = Vec::with_capacity(chunk.len()*self.k);
+        for x in chunk {
+            let mut f = poly_random(&mut self.rng, __gf::from_lossy(*x), self.k-1);
+            fs.extend_from_slice(&f);
+            wipe(&mut f);
+        }
+
+        // assign each share with a point at f(i)
+        let k = self.k;
+        let shares = (0..self.n).into_par_iter()
+            .map(|i| {
+                fs.chunks(k)
+                    .map(|f| __u::from(poly_eval(f, __gf::from_lossy(i+1))))
+                    .collect()
+            })
+            .collect();
+
+        wipe(&mut fs);
+        shares
+    }
+}
+
+/// Reconstruct a secret incrementally, one chunk of the shares at a time.
+///
+/// This finds the Lagrange coefficients for a set of share indices once,
+/// after which each chunk only costs one multiply-add per share per byte.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let shares = shamir::generate(b"secret secret secret!", 5, 3);
+///
+/// let joiner = shamir::Joiner::new(&[shares[0][0], shares[2][0], shares[4][0]]);
+/// let mut secret = vec![];
+/// for i in (1..shares[0].len()).step_by(4) {
+///     let j = (i+4).min(shares[0].len());
+///     secret.extend(joiner.join(&[&shares[0][i..j], &shares[2][i..j], &shares[4][i..j]]));
+/// }
+///
+/// assert_eq!(secret, b"secret secret secret!");
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Joiner {
+    coeffs: Vec<__gf>,
+}
+
+impl Joiner {
+    /// Create a joiner for shares with these indices (x-coordinates), in
+    /// the order their chunks will be provided to [`join`](Joiner::join).
+    pub fn new(indices: &[__u]) -> Self {
+        let xs = indices.iter().map(|x| __gf::from_lossy(*x)).collect::<Vec<_>>();
+        let coeffs = (0..xs.len())
+            .map(|i| lagrange(&xs, i, __gf::new(0)))
+            .collect();
+        Self { coeffs: coeffs }
+    }
+
+    /// Reconstruct the next chunk of the secret from the next chunk of each
+    /// share.
+    ///
+    /// All chunks must be the same length.
+    ///
+    pub fn join<S: AsRef<[__u]>>(&self, chunks: &[S]) -> Vec<__u> {
+        assert!(chunks.len() == self.coeffs.len(), "mismatched share count?");
+        assert!(
+            chunks.windows(2).all(|cs| cs[0].as_ref().len() == cs[1].as_ref().len()),
+            "mismatched share length?"
+        );
+
+        let len = chunks.get(0).map(|c| c.as_ref().len()).unwrap_or(0);
+        let mut secret = vec![__gf::new(0); len];
+        for (c, li) in chunks.iter().zip(&self.coeffs) {
+            slice_mul_add(&mut secret, c.as_ref(), *li);
+        }
+        let output = secret.iter().map(|s| __u::from(*s)).collect();
+        wipe(&mut secret);
+        output
+    }
+}
+
+// Streaming shares
+//
+// A share stream is exactly a share from generate, the share's index
+// followed by its y-coordinates, so streams can be mixed freely with
+// in-memory shares. We only ever buffer one chunk per share.
+//
+
+/// Size of the chunks read at a time by the stream functions
+#[cfg(__if(__std && __byte_symbols))]
+const STREAM_CHUNK_SIZE: usize = 4096;
+
+/// Read until buf is full or we reach the end of the stream
+#[cfg(__if(__std && __byte_symbols))]
+fn read_chunk<R: __crate::internal::std::io::Read>(
+    reader: &mut R,
+    buf: &mut [u8]
+) -> __crate::internal::std::io::Result<usize> {
+    use __crate::internal::std::io;
+
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+/// Generate shares of a secret read from `reader`, writing one share to
+/// each of `writers`, requiring `k` shares to reconstruct.
+///
+/// The secret is processed in chunks, so arbitrarily large secrets can be
+/// shared with bounded memory. Each share is written exactly as
+/// [`generate`] would return it. Returns the size of the secret in bytes.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let secret = (0..10000).map(|i| i as u8).collect::<Vec<u8>>();
+/// let mut shares = vec![Vec::new(); 5];
+/// shamir::generate_stream(&secret[..], &mut shares, 3)?;
+///
+/// let mut readers = [&shares[0][..], &shares[3][..], &shares[4][..]];
+/// let mut reconstructed = Vec::new();
+/// shamir::reconstruct_stream(&mut readers, &mut reconstructed)?;
+/// assert_eq!(reconstructed, secret);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+pub fn generate_stream<R, W>(
+    mut reader: R,
+    writers: &mut [W],
+    k: usize
+) -> __crate::internal::std::io::Result<u64>
+where
+    R: __crate::internal::std::io::Read,
+    W: __crate::internal::std::io::Write
+{
+    let mut splitter = splitter(writers.len(), k);
+    for (writer, x) in writers.iter_mut().zip(splitter.indices()) {
+        writer.write_all(&[x])?;
+    }
+
+    let mut chunk = vec![0u8; STREAM_CHUNK_SIZE];
+    let mut split = || -> __crate::internal::std::io::Result<u64> {
+        let mut read = 0;
+        loop {
+            let len = read_chunk(&mut reader, &mut chunk)?;
+            if len == 0 {
+                break;
+            }
+
+            for (writer, mut ys) in writers.iter_mut().zip(splitter.split(&chunk[..len])) {
+                let res = writer.write_all(&ys);
+                wipe(&mut ys);
+                res?;
+            }
+            read += len as u64;
+        }
+
+        for writer in writers.iter_mut() {
+            writer.flush()?;
+        }
+        Ok(read)
+    };
+    let res = split();
+    wipe(&mut chunk);
+    res
+}
+
+/// Reconstruct a secret from at least `k` shares read from `readers`,
+/// writing the secret to `writer`.
+///
+/// The shares are processed in chunks, so arbitrarily large secrets can be
+/// reconstructed with bounded memory. Returns the size of the secret in
+/// bytes, or an error of kind [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// if the shares have mismatched lengths.
+///
+/// Like [`reconstruct`], if insufficient or invalid shares are provided, the
+/// result will be garbage.
+///
+/// This requires feature `std`.
+///
+#[cfg(__if(__std && __byte_symbols))]
+pub fn reconstruct_stream<R, W>(
+    readers: &mut [R],
+    mut writer: W
+) -> __crate::internal::std::io::Result<u64>
+where
+    R: __crate::internal::std::io::Read,
+    W: __crate::internal::std::io::Write
+{
+    use __crate::internal::std::io;
+
+    let mismatched = || io::Error::new(
+        io::ErrorKind::InvalidData,
+        "mismatched share length?"
+    );
+
+    // x is prepended to each share
+    let mut xs = Vec::with_capacity(readers.len());
+    for reader in readers.iter_mut() {
+        let mut x = [0u8; 1];
+        match read_chunk(reader, &mut x)? {
+            1 => xs.push(x[0]),
+            _ => return Err(mismatched()),
+        }
+    }
+
+    let joiner = Joiner::new(&xs);
+    let mut chunks = vec![vec![0u8; STREAM_CHUNK_SIZE]; readers.len()];
+    let mut join = || -> io::Result<u64> {
+        let mut written = 0;
+        loop {
+            let mut len = None;
+            for (reader, chunk) in readers.iter_mut().zip(chunks.iter_mut()) {
+                let n = read_chunk(reader, chunk)?;
+                if *len.get_or_insert(n) != n {
+                    return Err(mismatched());
+                }
+            }
+
+            let len = len.unwrap_or(0);
+            if len == 0 {
+                break;
+            }
+
+            let ys = chunks.iter().map(|c| &c[..len]).collect::<Vec<_>>();
+            let mut secret = joiner.join(&ys);
+            let res = writer.write_all(&secret);
+            wipe(&mut secret);
+            res?;
+            written += len as u64;
+        }
+
+        writer.flush()?;
+        Ok(written)
+    };
+    let res = join();
+    for chunk in chunks.iter_mut() {
+        wipe(chunk);
+    }
+    res
+}
+
+
+/// Compute one share holder's contribution to a new share.
+///
+/// `share` is this holder's share, `quorum` the indices (x-coordinates) of
+/// at least `k` share holders taking part, including this one, and `index`
+/// the index of the new share. The sum of every holder's contribution,
+/// [`combine_contributions`], is the new share, so the secret is never
+/// found in one place.
+///
+/// Note that each contribution is the holder's share scaled by a public,
+/// non-zero constant, so a contribution reveals its holder's share. In a
+/// real protocol contributions should be blinded before being sent to the
+/// new share holder, for example with random masks agreed between pairs
+/// of holders that cancel out in the sum.
+///
+pub fn contribute_share(share: &[__u], quorum: &[__u], index: __u) -> Vec<__u> {
+    assert!(__gf::from_lossy(index) != __gf::new(0), "share index can't be zero");
+    let xs = quorum.iter().map(|x| __gf::from_lossy(*x)).collect::<Vec<_>>();
+    let i = quorum.iter()
+        .position(|x| *x == share[0])
+        .expect("share not in quorum?");
+
+    let li = lagrange(&xs, i, __gf::from_lossy(index));
+    let mut contribution = vec![index];
+    contribution.extend(share[1..].iter().map(|y| __u::from(__gf::from_lossy(*y)*li)));
+    contribution
+}
+
+/// Combine contributions from [`contribute_share`] into a new share.
+///
+/// All contributions must be for the same new share index.
+///
+pub fn combine_contributions<S: AsRef<[__u]>>(contributions: &[S]) -> Vec<__u> {
+    assert!(!contributions.is_empty());
+    assert!(
+        contributions.windows(2).all(|cs| cs[0].as_ref().len() == cs[1].as_ref().len()),
+        "mismatched contribution length?"
+    );
+    assert!(
+        contributions.windows(2).all(|cs| cs[0].as_ref()[0] == cs[1].as_ref()[0]),
+        "mismatched contribution index?"
+    );
+
+    let mut share = contributions[0].as_ref().to_vec();
+    for c in &contributions[1..] {
+        for (y, c) in share[1..].iter_mut().zip(&c.as_ref()[1..]) {
+            *y = __u::from(__gf::from_lossy(*y) + __gf::from_lossy(*c));
+        }
+    }
+    share
+}
+
+/// Issue a new share at the given index from at least `k` shares, without
+/// reconstructing the secret.
+///
+/// This evaluates the shared polynomial at the new index instead of at
+/// zero. This is equivalent to combining [`contribute_share`] from each
+/// share holder, which avoids collecting the shares in one place.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// let shares = shamir::generate(b"secret secret secret!", 5, 3);
+///
+/// // issue a 6th share from any 3 shares
+/// let share6 = shamir::issue_share(&shares[1..4], 6);
+/// assert_eq!(share6[0], 6);
+/// assert_eq!(shamir::reconstruct(&[&shares[0], &share6, &shares[4]]), b"secret secret secret!");
+/// ```
+///
+pub fn issue_share<S: AsRef<[__u]>>(shares: &[S], index: __u) -> Vec<__u> {
+    assert!(!shares.is_empty());
+    let quorum = shares.iter().map(|s| s.as_ref()[0]).collect::<Vec<_>>();
+    let mut contributions = shares.iter()
+        .map(|s| contribute_share(s.as_ref(), &quorum, index))
+        .collect::<Vec<_>>();
+    let share = combine_contributions(&contributions);
+    for c in contributions.iter_mut() {
+        wipe(c);
+    }
+    share
+}
+
+
+/// Split one share holder's share into sub-shares for a new sharing of the
+/// same secret, requiring `k` of `n` new shares to reconstruct.
+///
+/// This is the first step of resharing, which converts an existing sharing
+/// into a sharing with a different threshold or set of participants
+/// without reconstructing the secret. Each holder in a quorum of at least
+/// `k` old shares calls `reshare` and privately sends the `j`th sub-share
+/// to the `j`th new share holder, who then combines the sub-shares they
+/// receive with [`combine_reshares`].
+///
+/// Each sub-share is prefixed with the index of the old share it came
+/// from, followed by the index of the new share it belongs to.
+///
+/// ``` rust
+/// # use ::gf256::shamir::*;
+/// // a 3-of-5 sharing
+/// let shares = shamir::generate(b"secret secret secret!", 5, 3);
+///
+/// // any 3 holders reshare into a 4-of-7 sharing
+/// let subshares = shares[1..4].iter()
+///     .map(|share| shamir::reshare(share, 7, 4))
+///     .collect::<Vec<_>>();
+/// let new_shares = (0..7)
+///     .map(|j| shamir::combine_reshares(&subshares.iter().map(|s| &s[j]).collect::<Vec<_>>()))
+///     .collect::<Vec<_>>();
+///
+/// assert_ne!(shamir::reconstruct(&new_shares[..3]), b"secret secret secret!");
+/// assert_eq!(shamir::reconstruct(&new_shares[..4]), b"secret secret secret!");
+/// ```
+///
+/// Note the old shares must be destroyed for the old sharing to actually
+/// be revoked, and the sub-shares must be kept private, since any `k` of
+/// an old holder's sub-shares reveal that holder's share.
+///
+pub fn reshare(share: &[__u], n: usize, k: usize) -> Vec<Vec<__u>> {
+    assert!(!share.is_empty());
+    let mut subshares = generate(&share[1..], n, k);
+    for subshare in subshares.iter_mut() {
+        subshare.insert(0, share[0]);
+    }
+    subshares
+}
+
+/// Combine sub-shares from [`reshare`] into a new share.
+///
+/// Requires one sub-share from each of the old share holders taking part,
+/// all for the same new share index, and there must be at least as many
+/// as the old threshold.
+///
+pub fn combine_reshares<S: AsRef<[__u]>>(subshares: &[S]) -> Vec<__u> {
+    assert!(!subshares.is_empty());
+    assert!(
+        subshares.windows(2).all(|ss| ss[0].as_ref().len() == ss[1].as_ref().len()),
+        "mismatched sub-share length?"
+    );
+    assert!(
+        subshares.windows(2).all(|ss| ss[0].as_ref()[1] == ss[1].as_ref()[1]),
+        "mismatched sub-share index?"
+    );
+
+    // the old shares' indices are prepended to each sub-share, the rest
+    // are shares of the old shares, so the new share is their Lagrange
+    // interpolation at zero
+    let xs = subshares.iter().map(|s| s.as_ref()[0]).collect::<Vec<_>>();
+    let ys = subshares.iter().map(|s| &s.as_ref()[2..]).collect::<Vec<_>>();
+    let mut share = vec![subshares[0].as_ref()[1]];
+    share.extend(Joiner::new(&xs).join(&ys));
+    share
+}
@@ -0,0 +1,58 @@
+//! A no_std staticlib without a global allocator, to check that gf256's
+//! alloc-free features don't pull in alloc
+
+#![no_std]
+
+use gf256::crc::crc32c;
+use gf256::lfsr::Lfsr8;
+use gf256::lfsr::is_maximal;
+use gf256::raid::raid6;
+use gf256::raid::rdp;
+use gf256::rs::rs255w223;
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_crc32c(data: &[u8; 16]) -> u32 {
+    crc32c(data, 0)
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_lfsr8(seed: u8) -> u8 {
+    Lfsr8::new(seed).next(8)
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_is_maximal(polynomial: u64) -> bool {
+    is_maximal(u128::from(polynomial))
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_raid6(
+    blocks: &mut [[u8; 16]; 4],
+    p: &mut [u8; 16],
+    q: &mut [u8; 16],
+) -> bool {
+    raid6::format(&blocks[..], p, q);
+    raid6::repair(&mut blocks[..], p, q, &[0, 1]).is_ok()
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_rdp(
+    blocks: &mut [[u8; 4]; 4],
+    p: &mut [u8; 4],
+    q: &mut [u8; 4],
+) -> bool {
+    rdp::format(&blocks[..], p, q);
+    rdp::repair(&mut blocks[..], p, q, &[0, 1]).is_ok()
+}
+
+#[no_mangle]
+pub extern "C" fn no_alloc_rs255w223(codeword: &mut [u8; 255]) -> bool {
+    rs255w223::encode(&mut codeword[..]);
+    codeword[0] ^= 0xff;
+    rs255w223::correct_errors(&mut codeword[..]).is_ok()
+}